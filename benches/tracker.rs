@@ -0,0 +1,127 @@
+//! Throughput of reads against a tracker seeded with many objects
+//!
+//! Run with `cargo bench --bench tracker`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Api, ListParams, Patch, PatchParams};
+use kube_fake_client::ClientBuilder;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const NAMESPACES: usize = 20;
+const PER_NAMESPACE: usize = 1000;
+
+fn seed() -> Vec<ConfigMap> {
+    let mut objects = Vec::with_capacity(NAMESPACES * PER_NAMESPACE);
+    for ns in 0..NAMESPACES {
+        for i in 0..PER_NAMESPACE {
+            let mut cm = ConfigMap::default();
+            cm.metadata.name = Some(format!("cm-{i}"));
+            cm.metadata.namespace = Some(format!("ns-{ns}"));
+            cm.metadata.labels = Some(BTreeMap::from([(
+                "shard".to_string(),
+                (i % 10).to_string(),
+            )]));
+            cm.data = Some(BTreeMap::from([
+                ("key".to_string(), "x".repeat(256)),
+                ("index".to_string(), i.to_string()),
+            ]));
+            objects.push(cm);
+        }
+    }
+    objects
+}
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(4)
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+fn reads(c: &mut Criterion) {
+    let rt = runtime();
+    let client = rt.block_on(async {
+        ClientBuilder::new()
+            .with_objects(seed())
+            .build()
+            .await
+            .unwrap()
+    });
+    let api: Api<ConfigMap> = Api::namespaced(client.clone(), "ns-0");
+    let all: Api<ConfigMap> = Api::all(client.clone());
+
+    let mut group = c.benchmark_group("tracker");
+    group.sample_size(20);
+
+    group.bench_function("get", |b| {
+        b.to_async(&rt)
+            .iter(|| async { api.get("cm-500").await.unwrap() })
+    });
+
+    group.throughput(Throughput::Elements(PER_NAMESPACE as u64));
+    group.bench_function("list_namespace", |b| {
+        b.to_async(&rt)
+            .iter(|| async { api.list(&ListParams::default()).await.unwrap() })
+    });
+
+    group.throughput(Throughput::Elements((PER_NAMESPACE / 10) as u64));
+    group.bench_function("list_namespace_label_selector", |b| {
+        let params = ListParams::default().labels("shard=3");
+        b.to_async(&rt)
+            .iter(|| async { api.list(&params).await.unwrap() })
+    });
+
+    group.throughput(Throughput::Elements((NAMESPACES * PER_NAMESPACE) as u64));
+    group.bench_function("list_all_namespaces", |b| {
+        b.to_async(&rt)
+            .iter(|| async { all.list(&ListParams::default()).await.unwrap() })
+    });
+    group.finish();
+
+    // Reads in one namespace while writers patch objects in others
+    let mut group = c.benchmark_group("tracker_contended");
+    group.sample_size(20);
+    for writers in [0, 4] {
+        let stop = Arc::new(AtomicBool::new(false));
+        for w in 0..writers {
+            let api: Api<ConfigMap> = Api::namespaced(client.clone(), &format!("ns-{}", w + 1));
+            let stop = Arc::clone(&stop);
+            rt.spawn(async move {
+                let mut i = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    let patch = json!({ "data": { "index": i.to_string() } });
+                    let name = format!("cm-{}", i % PER_NAMESPACE);
+                    let _ = api
+                        .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+                        .await;
+                    i += 1;
+                }
+            });
+        }
+
+        group.bench_with_input(BenchmarkId::new("get", writers), &writers, |b, _| {
+            b.to_async(&rt)
+                .iter(|| async { api.get("cm-500").await.unwrap() })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("list_namespace_label_selector", writers),
+            &writers,
+            |b, _| {
+                let params = ListParams::default().labels("shard=3");
+                b.to_async(&rt)
+                    .iter(|| async { api.list(&params).await.unwrap() })
+            },
+        );
+        stop.store(true, Ordering::Relaxed);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, reads);
+criterion_main!(benches);
@@ -9,17 +9,17 @@
 //!
 //! Generate discovery code from local files:
 //! ```bash
-//! cargo run --bin discovery-gen
+//! cargo run --features codegen --bin discovery-gen
 //! ```
 //!
 //! Update discovery data from Kubernetes GitHub repo:
 //! ```bash
-//! cargo run --bin discovery-gen -- --update
+//! cargo run --features codegen --bin discovery-gen -- --update
 //! ```
 //!
 //! Target a specific Kubernetes version:
 //! ```bash
-//! cargo run --bin discovery-gen -- --update --tag v1.31.0
+//! cargo run --features codegen --bin discovery-gen -- --update --tag v1.31.0
 //! ```
 
 use clap::Parser;
@@ -390,7 +390,7 @@ fn parse_discovery_files() -> Result<Vec<ResourceMetadata>, Box<dyn std::error::
 const TEMPLATE: &str = r#"//! Auto-generated Kubernetes resource discovery metadata
 //!
 //! This file is generated by the discovery-gen binary and should not be edited manually.
-//! To regenerate: cargo run --bin discovery-gen
+//! To regenerate: cargo run --features codegen --bin discovery-gen
 
 use std::collections::HashMap;
 use once_cell::sync::Lazy;
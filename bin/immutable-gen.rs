@@ -6,21 +6,25 @@
 //! The generator parses the Kubernetes OpenAPI schema (swagger.json) and identifies fields
 //! whose descriptions contain the word "immutable".
 //!
+//! Many immutability rules enforced by the apiserver live in validation code rather than
+//! in field descriptions (e.g. `spec.selector` on Deployments). These are maintained by hand
+//! in `KNOWN_IMMUTABLE_PATHS` and emitted as nested field paths.
+//!
 //! # Usage
 //!
 //! Generate immutable field lookups from local swagger.json:
 //! ```bash
-//! cargo run --bin immutable-gen
+//! cargo run --features codegen --bin immutable-gen
 //! ```
 //!
 //! Update swagger.json from Kubernetes GitHub repo:
 //! ```bash
-//! cargo run --bin immutable-gen -- --update
+//! cargo run --features codegen --bin immutable-gen -- --update
 //! ```
 //!
 //! Target a specific Kubernetes version:
 //! ```bash
-//! cargo run --bin immutable-gen -- --update --tag v1.31.0
+//! cargo run --features codegen --bin immutable-gen -- --update --tag v1.31.0
 //! ```
 
 use clap::Parser;
@@ -37,6 +41,68 @@ const OPENAPI_FILE: &str = "kubernetes/api/openapi/swagger.json";
 const GITHUB_RAW_BASE: &str = "https://raw.githubusercontent.com/kubernetes/kubernetes";
 const USER_AGENT: &str = "kube-fake-client-immutable-gen";
 
+/// Nested immutable paths enforced by apiserver validation code, keyed by resource kind
+///
+/// Paths are dot-separated from the object root. A segment of the form `field[key]`
+/// descends into every item of the `field` list, matching old and new items by the
+/// `key` merge key.
+const KNOWN_IMMUTABLE_PATHS: &[(&str, &str, &str, &[&str])] = &[
+    (
+        "",
+        "v1",
+        "PersistentVolumeClaim",
+        &[
+            "spec.accessModes",
+            "spec.selector",
+            "spec.storageClassName",
+            "spec.volumeMode",
+            "spec.volumeName",
+        ],
+    ),
+    (
+        "",
+        "v1",
+        "Pod",
+        &[
+            "spec.containers[name].args",
+            "spec.containers[name].command",
+            "spec.containers[name].env",
+            "spec.containers[name].ports",
+            "spec.nodeName",
+            "spec.restartPolicy",
+            "spec.serviceAccountName",
+            "spec.volumes",
+        ],
+    ),
+    ("", "v1", "Service", &["spec.clusterIP", "spec.clusterIPs"]),
+    ("apps", "v1", "DaemonSet", &["spec.selector"]),
+    ("apps", "v1", "Deployment", &["spec.selector"]),
+    ("apps", "v1", "ReplicaSet", &["spec.selector"]),
+    (
+        "apps",
+        "v1",
+        "StatefulSet",
+        &[
+            "spec.podManagementPolicy",
+            "spec.selector",
+            "spec.serviceName",
+            "spec.volumeClaimTemplates",
+        ],
+    ),
+    (
+        "batch",
+        "v1",
+        "Job",
+        &[
+            "spec.completionMode",
+            "spec.selector",
+            "spec.template.spec.containers[name].command",
+            "spec.template.spec.containers[name].image",
+            "spec.template.spec.nodeSelector",
+        ],
+    ),
+];
+
 #[derive(Parser, Debug)]
 #[command(name = "immutable-gen")]
 #[command(about = "Generate immutable field lookups from OpenAPI schema", long_about = None)]
@@ -63,6 +129,15 @@ struct ImmutableFieldInfo {
     fields: Vec<String>, // e.g., ["nodeName", "serviceAccountName"]
 }
 
+/// Nested immutable paths for a resource kind
+#[derive(Debug, Serialize)]
+struct ImmutablePathInfo {
+    group: String,      // e.g., "apps" or "" for core
+    version: String,    // e.g., "v1"
+    kind: String,       // e.g., "Deployment"
+    paths: Vec<String>, // e.g., ["spec.selector"]
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
@@ -95,7 +170,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Generate immutable field lookup code
     println!("Generating immutable field lookups...");
     let output_path = args.output.join("immutable.rs");
-    generate_immutable_code(&immutable_fields, &output_path)?;
+    let immutable_paths = known_immutable_paths();
+    generate_immutable_code(&immutable_fields, &immutable_paths, &output_path)?;
     println!("Generated code written to {}", output_path.display());
 
     Ok(())
@@ -263,11 +339,24 @@ fn parse_immutable_fields() -> Result<Vec<ImmutableFieldInfo>, Box<dyn std::erro
     Ok(immutable_info)
 }
 
+/// Collect the hand-maintained nested immutable paths
+fn known_immutable_paths() -> Vec<ImmutablePathInfo> {
+    KNOWN_IMMUTABLE_PATHS
+        .iter()
+        .map(|(group, version, kind, paths)| ImmutablePathInfo {
+            group: group.to_string(),
+            version: version.to_string(),
+            kind: kind.to_string(),
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+        })
+        .collect()
+}
+
 /// Template for generating immutable.rs
 const IMMUTABLE_TEMPLATE: &str = r#"//! Auto-generated immutable field lookups
 //!
 //! This file is generated by the immutable-gen binary and should not be edited manually.
-//! To regenerate: cargo run --bin immutable-gen
+//! To regenerate: cargo run --features codegen --bin immutable-gen
 //!
 //! Immutable fields are fields that cannot be changed after resource creation.
 //! This module provides lookups to check if a field in a Kubernetes resource is immutable.
@@ -351,11 +440,37 @@ pub fn get_immutable_fields(group: &str, version: &str, kind: &str) -> Option<&'
 {% endfor %}        _ => None,
     }
 }
+
+/// Get nested immutable field paths for a given resource kind
+///
+/// Paths are dot-separated from the object root (e.g. `spec.selector`). A segment of
+/// the form `field[key]` descends into each item of the `field` list, matching old and
+/// new items by the `key` merge key (e.g. `spec.containers[name].image`).
+///
+/// These paths are set-once: a value may be populated when previously unset, but once
+/// set it cannot be changed or removed.
+///
+/// # Example
+///
+/// ```
+/// use kube_fake_client::gen::immutable::get_immutable_paths;
+///
+/// let paths = get_immutable_paths("apps", "v1", "Deployment").unwrap();
+/// assert!(paths.contains(&"spec.selector"));
+/// assert!(get_immutable_paths("", "v1", "ConfigMap").is_none());
+/// ```
+pub fn get_immutable_paths(group: &str, version: &str, kind: &str) -> Option<&'static [&'static str]> {
+    match (group, version, kind) {
+{% for info in immutable_paths %}        ("{{ info.group }}", "{{ info.version }}", "{{ info.kind }}") => Some(&[{% for path in info.paths %}"{{ path }}"{% if not loop.last %}, {% endif %}{% endfor %}]),
+{% endfor %}        _ => None,
+    }
+}
 "#;
 
 /// Generate immutable field lookup code
 fn generate_immutable_code(
     immutable_fields: &[ImmutableFieldInfo],
+    immutable_paths: &[ImmutablePathInfo],
     output_path: &Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut tera = Tera::default();
@@ -363,6 +478,7 @@ fn generate_immutable_code(
 
     let mut context = Context::new();
     context.insert("immutable_fields", immutable_fields);
+    context.insert("immutable_paths", immutable_paths);
 
     let rendered = tera.render("immutable", &context)?;
     fs::write(output_path, rendered)?;
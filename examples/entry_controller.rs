@@ -0,0 +1,88 @@
+//! Get-or-create controller using the entry API
+//!
+//! Example demonstrating a controller that keeps a ConfigMap in shape with
+//! `Api::entry`: the entry reads the object, `or_insert` starts from a default
+//! when it doesn't exist yet, and `commit` creates or replaces it. Commits carry
+//! the resourceVersion that was read, so a write made in between fails with 409
+//! Conflict and the controller retries from a fresh read.
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::entry::CommitError;
+use kube::api::{Api, Patch, PatchParams, PostParams};
+use kube_fake_client::ClientBuilder;
+use serde_json::json;
+
+pub struct SettingsController {
+    api: Api<ConfigMap>,
+}
+
+impl SettingsController {
+    pub fn new(api: Api<ConfigMap>) -> Self {
+        Self { api }
+    }
+
+    /// Make sure the ConfigMap exists and holds `key: value`, keeping any other
+    /// keys other writers put there
+    pub async fn reconcile(
+        &self,
+        name: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            let mut entry = self.api.entry(name).await?.or_insert(ConfigMap::default);
+            let data = entry.get().data.clone().unwrap_or_default();
+            if data.get(key).map(String::as_str) == Some(value) {
+                return Ok(());
+            }
+            entry
+                .get_mut()
+                .data
+                .get_or_insert_default()
+                .insert(key.to_string(), value.to_string());
+            match entry.commit(&PostParams::default()).await {
+                Ok(()) => return Ok(()),
+                // Someone else created or changed the object since it was read
+                Err(CommitError::Save(kube::Error::Api(response))) if response.code == 409 => {
+                    println!("Conflict on {name}, retrying");
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = ClientBuilder::new().build().await?;
+    let api: Api<ConfigMap> = Api::namespaced(client, "default");
+    let controller = SettingsController::new(api.clone());
+
+    // The first reconcile creates the ConfigMap
+    controller.reconcile("app-settings", "mode", "fast").await?;
+
+    // Another manager applies its own key with server-side apply
+    let apply = json!({
+        "apiVersion": "v1",
+        "kind": "ConfigMap",
+        "metadata": { "name": "app-settings" },
+        "data": { "mode": "fast", "region": "eu-west-1" },
+    });
+    api.patch(
+        "app-settings",
+        &PatchParams::apply("region-manager"),
+        &Patch::Apply(&apply),
+    )
+    .await?;
+
+    // The next reconcile replaces the object, keeping the applied key
+    controller.reconcile("app-settings", "mode", "slow").await?;
+
+    let settings = api.get("app-settings").await?;
+    println!("ConfigMap data:");
+    for (key, value) in settings.data.unwrap_or_default() {
+        println!("  {key}: {value}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Access review stubbing
+//!
+//! `SubjectAccessReview`, `SelfSubjectAccessReview` and `LocalSubjectAccessReview`
+//! are never stored by the API server: creating one returns the review with its
+//! `status` filled in by the authorizer. The fake client answers them with a
+//! configurable handler. Without one, reviews are decided by RBAC when the client
+//! has an identity and allowed otherwise.
+
+use crate::{Error, Result};
+use k8s_openapi::api::authorization::v1::SubjectAccessReviewSpec;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Decides whether the request described by an access review is allowed
+///
+/// `SelfSubjectAccessReview` specs are presented without `user` or `groups`, and
+/// `LocalSubjectAccessReview` specs always carry the namespace of the review.
+pub type AccessReviewHandler = Arc<dyn Fn(&SubjectAccessReviewSpec) -> bool + Send + Sync>;
+
+/// Answer an access review of the given kind, returning it with its status set
+pub(crate) fn review(
+    handler: Option<&AccessReviewHandler>,
+    kind: &str,
+    namespace: &str,
+    mut review: Value,
+) -> Result<Value> {
+    let invalid = |detail: &str| {
+        Error::InvalidRequest(format!(
+            "{kind}.authorization.k8s.io \"\" is invalid: {detail}"
+        ))
+    };
+
+    let mut spec = review.get("spec").cloned().unwrap_or_else(|| json!({}));
+    let has_resource = spec.get("resourceAttributes").is_some_and(|v| !v.is_null());
+    let has_non_resource = spec
+        .get("nonResourceAttributes")
+        .is_some_and(|v| !v.is_null());
+    if has_resource == has_non_resource {
+        return Err(invalid(
+            "spec.resourceAttributes: Invalid value: exactly one of nonResourceAttributes or \
+             resourceAttributes must be specified",
+        ));
+    }
+
+    match kind {
+        "SubjectAccessReview" | "LocalSubjectAccessReview" => {
+            let has_user = spec
+                .get("user")
+                .and_then(|v| v.as_str())
+                .is_some_and(|u| !u.is_empty());
+            let has_groups = spec
+                .get("groups")
+                .and_then(|v| v.as_array())
+                .is_some_and(|g| !g.is_empty());
+            if !has_user && !has_groups {
+                return Err(invalid(
+                    "spec.user: Invalid value: \"\": at least one of user or group must be specified",
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    if kind == "LocalSubjectAccessReview" {
+        if has_non_resource {
+            return Err(invalid(
+                "spec.nonResourceAttributes: Invalid value: disallowed on this kind of request",
+            ));
+        }
+        let attributes = &mut spec["resourceAttributes"];
+        match attributes.get("namespace").and_then(|v| v.as_str()) {
+            Some(ns) if !ns.is_empty() && ns != namespace => {
+                return Err(invalid(&format!(
+                    "spec.resourceAttributes.namespace: Invalid value: \"{ns}\": must match \
+                     metadata.namespace"
+                )));
+            }
+            _ => attributes["namespace"] = json!(namespace),
+        }
+    }
+
+    let parsed: SubjectAccessReviewSpec = serde_json::from_value(spec.clone())
+        .map_err(|e| invalid(&format!("spec: Invalid value: {e}")))?;
+    review["spec"] = spec;
+    let allowed = handler.is_none_or(|handler| handler(&parsed));
+
+    review["status"] = json!({ "allowed": allowed });
+    Ok(review)
+}
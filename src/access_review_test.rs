@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::authorization::v1::{
+        LocalSubjectAccessReview, ResourceAttributes, SelfSubjectAccessReview,
+        SelfSubjectAccessReviewSpec, SubjectAccessReview, SubjectAccessReviewSpec,
+    };
+    use kube::api::{Api, PostParams};
+
+    fn attributes(verb: &str, resource: &str, namespace: Option<&str>) -> ResourceAttributes {
+        ResourceAttributes {
+            verb: Some(verb.to_string()),
+            resource: Some(resource.to_string()),
+            namespace: namespace.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn self_review(verb: &str, resource: &str) -> SelfSubjectAccessReview {
+        SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: Some(attributes(verb, resource, Some("default"))),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reviews_allowed_without_handler() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let reviews: Api<SelfSubjectAccessReview> = Api::all(client);
+
+        let review = reviews
+            .create(&PostParams::default(), &self_review("delete", "pods"))
+            .await
+            .unwrap();
+        assert!(review.status.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_handler_decides_self_subject_access_reviews() {
+        let client = ClientBuilder::new()
+            .with_access_review_handler(|spec| {
+                let attrs = spec.resource_attributes.as_ref().unwrap();
+                spec.user.is_none() && attrs.verb.as_deref() == Some("get")
+            })
+            .build()
+            .await
+            .unwrap();
+        let reviews: Api<SelfSubjectAccessReview> = Api::all(client);
+
+        let allowed = reviews
+            .create(&PostParams::default(), &self_review("get", "pods"))
+            .await
+            .unwrap();
+        assert!(allowed.status.unwrap().allowed);
+
+        let denied = reviews
+            .create(&PostParams::default(), &self_review("delete", "pods"))
+            .await
+            .unwrap();
+        assert!(!denied.status.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_subject_access_review_requires_subject() {
+        let client = ClientBuilder::new()
+            .with_access_review_handler(|spec| spec.user.as_deref() == Some("alice"))
+            .build()
+            .await
+            .unwrap();
+        let reviews: Api<SubjectAccessReview> = Api::all(client);
+
+        let review = SubjectAccessReview {
+            spec: SubjectAccessReviewSpec {
+                user: Some("alice".to_string()),
+                resource_attributes: Some(attributes("list", "secrets", None)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = reviews
+            .create(&PostParams::default(), &review)
+            .await
+            .unwrap();
+        assert!(result.status.unwrap().allowed);
+
+        let anonymous = SubjectAccessReview {
+            spec: SubjectAccessReviewSpec {
+                resource_attributes: Some(attributes("list", "secrets", None)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = reviews
+            .create(&PostParams::default(), &anonymous)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+    }
+
+    #[tokio::test]
+    async fn test_local_subject_access_review_uses_review_namespace() {
+        let (client, handle) = ClientBuilder::new()
+            .with_access_review_handler(|spec| {
+                let attrs = spec.resource_attributes.as_ref().unwrap();
+                attrs.namespace.as_deref() == Some("team-a")
+            })
+            .build_with_handle()
+            .await
+            .unwrap();
+        let reviews: Api<LocalSubjectAccessReview> = Api::namespaced(client, "team-a");
+
+        let mut review = LocalSubjectAccessReview {
+            spec: SubjectAccessReviewSpec {
+                groups: Some(vec!["developers".to_string()]),
+                resource_attributes: Some(attributes("create", "deployments", None)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        review.metadata.namespace = Some("team-a".to_string());
+        let result = reviews
+            .create(&PostParams::default(), &review)
+            .await
+            .unwrap();
+        assert!(result.status.unwrap().allowed);
+
+        // Reviews are answered, not stored
+        assert!(handle.list_namespace("team-a").unwrap().is_empty());
+
+        // The attributes must not name another namespace
+        review.spec.resource_attributes = Some(attributes("create", "deployments", Some("team-b")));
+        let err = reviews
+            .create(&PostParams::default(), &review)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+    }
+}
@@ -0,0 +1,366 @@
+//! API discovery endpoints
+//!
+//! Builds the documents the API server serves under `/api` and `/apis` from the
+//! generated discovery data and registered custom resources, so that runtime
+//! discovery (`kube::discovery::Discovery`) works against the fake client. Both
+//! the legacy per-version documents and aggregated discovery
+//! (`apidiscovery.k8s.io/v2`) are supported.
+
+use crate::discovery::Discovery;
+use crate::registry::ResourceRegistry;
+use crate::tracker::GVK;
+use kube::core::Version;
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Content type of aggregated discovery responses
+pub(crate) const CONTENT_TYPE_AGGREGATED: &str =
+    "application/json;g=apidiscovery.k8s.io;v=v2;as=APIGroupDiscoveryList";
+
+/// Verbs probed for built-in resources, in the order the API server lists them
+const VERBS: &[&str] = &[
+    "create",
+    "delete",
+    "deletecollection",
+    "get",
+    "list",
+    "patch",
+    "update",
+    "watch",
+];
+
+/// Subresources known to the generated discovery data and the verbs they serve
+const SUBRESOURCES: &[(&str, &[&str])] = &[
+    ("approval", &["get", "patch", "update"]),
+    ("attach", &["create", "get"]),
+    ("binding", &["create"]),
+    ("ephemeralcontainers", &["get", "patch", "update"]),
+    ("eviction", &["create"]),
+    ("exec", &["create", "get"]),
+    ("finalize", &["update"]),
+    ("log", &["get"]),
+    ("portforward", &["create", "get"]),
+    ("proxy", &["create", "delete", "get", "patch", "update"]),
+    ("resize", &["get", "patch", "update"]),
+    ("scale", &["get", "patch", "update"]),
+    ("status", &["get", "patch", "update"]),
+    ("token", &["create"]),
+];
+
+/// Verbs served by registered subresources not in [`SUBRESOURCES`]
+const DEFAULT_SUBRESOURCE_VERBS: &[&str] = &["get", "patch", "update"];
+
+/// A served resource, either built-in or registered
+struct ServedResource {
+    gvk: GVK,
+    plural: String,
+    singular: String,
+    namespaced: bool,
+    short_names: Vec<&'static str>,
+    verbs: Vec<String>,
+    subresources: Vec<(String, &'static [&'static str])>,
+}
+
+impl ServedResource {
+    fn scope(&self) -> &'static str {
+        if self.namespaced {
+            "Namespaced"
+        } else {
+            "Cluster"
+        }
+    }
+
+    /// Kind returned by a subresource, `Scale` for scale and the resource's own Kind otherwise
+    fn subresource_kind(&self, subresource: &str) -> (String, String, String) {
+        if subresource == "scale" {
+            ("autoscaling".into(), "v1".into(), "Scale".into())
+        } else {
+            (
+                self.gvk.group.clone(),
+                self.gvk.version.clone(),
+                self.gvk.kind.clone(),
+            )
+        }
+    }
+
+    /// Entries of an `APIResourceList`, the resource followed by its subresources
+    fn api_resources(&self) -> Vec<Value> {
+        let mut entries = vec![json!({
+            "name": self.plural,
+            "singularName": self.singular,
+            "namespaced": self.namespaced,
+            "kind": self.gvk.kind,
+            "verbs": self.verbs,
+            "shortNames": self.short_names,
+        })];
+        for (subresource, verbs) in &self.subresources {
+            let (group, version, kind) = self.subresource_kind(subresource);
+            let mut entry = json!({
+                "name": format!("{}/{}", self.plural, subresource),
+                "singularName": "",
+                "namespaced": self.namespaced,
+                "kind": kind,
+                "verbs": verbs,
+            });
+            if group != self.gvk.group || version != self.gvk.version {
+                entry["group"] = json!(group);
+                entry["version"] = json!(version);
+            }
+            entries.push(entry);
+        }
+        entries
+    }
+
+    /// Entry of an aggregated `APIVersionDiscovery`
+    fn aggregated_resource(&self) -> Value {
+        let subresources: Vec<Value> = self
+            .subresources
+            .iter()
+            .map(|(subresource, verbs)| {
+                let (group, version, kind) = self.subresource_kind(subresource);
+                json!({
+                    "subresource": subresource,
+                    "responseKind": { "group": group, "version": version, "kind": kind },
+                    "verbs": verbs,
+                })
+            })
+            .collect();
+        json!({
+            "resource": self.plural,
+            "responseKind": {
+                "group": self.gvk.group,
+                "version": self.gvk.version,
+                "kind": self.gvk.kind,
+            },
+            "scope": self.scope(),
+            "singularResource": self.singular,
+            "shortNames": self.short_names,
+            "verbs": self.verbs,
+            "subresources": subresources,
+        })
+    }
+}
+
+/// All served resources grouped by API group and version
+///
+/// Versions within a group are ordered by Kubernetes version priority, so the
+/// first version of each group is its preferred version.
+fn served_resources(
+    registry: &ResourceRegistry,
+) -> BTreeMap<String, Vec<(String, Vec<ServedResource>)>> {
+    let mut by_group: BTreeMap<String, BTreeMap<String, Vec<ServedResource>>> = BTreeMap::new();
+
+    for (group, version, kind, plural) in Discovery::list_all_resources() {
+        // Registered resources replace built-in ones
+        if registry.lookup_by_kind(group, version, kind).is_some()
+            || registry.is_removed(group, version, kind)
+        {
+            continue;
+        }
+        let gvk = GVK::new(*group, *version, *kind);
+        let resource = ServedResource {
+            plural: plural.to_string(),
+            singular: Discovery::get_singular(&gvk)
+                .unwrap_or_default()
+                .to_string(),
+            namespaced: Discovery::is_namespaced(&gvk).unwrap_or(true),
+            short_names: Discovery::get_short_names(&gvk).to_vec(),
+            verbs: VERBS
+                .iter()
+                .filter(|verb| Discovery::supports_verb(&gvk, verb))
+                .map(|verb| verb.to_string())
+                .collect(),
+            subresources: SUBRESOURCES
+                .iter()
+                .filter(|(subresource, _)| Discovery::has_subresource(&gvk, subresource))
+                .map(|(subresource, verbs)| (subresource.to_string(), *verbs))
+                .collect(),
+            gvk,
+        };
+        by_group
+            .entry(group.to_string())
+            .or_default()
+            .entry(version.to_string())
+            .or_default()
+            .push(resource);
+    }
+
+    for metadata in registry.list() {
+        let resource = ServedResource {
+            gvk: GVK::new(&metadata.group, &metadata.version, &metadata.kind),
+            plural: metadata.plural,
+            singular: metadata.kind.to_lowercase(),
+            namespaced: metadata.namespaced,
+            short_names: Vec::new(),
+            verbs: metadata.verbs,
+            subresources: metadata
+                .subresources
+                .into_iter()
+                .map(|subresource| {
+                    let verbs = SUBRESOURCES
+                        .iter()
+                        .find(|(known, _)| *known == subresource)
+                        .map_or(DEFAULT_SUBRESOURCE_VERBS, |(_, verbs)| *verbs);
+                    (subresource, verbs)
+                })
+                .collect(),
+        };
+        by_group
+            .entry(metadata.group)
+            .or_default()
+            .entry(metadata.version)
+            .or_default()
+            .push(resource);
+    }
+
+    by_group
+        .into_iter()
+        .map(|(group, versions)| {
+            let mut versions: Vec<_> = versions.into_iter().collect();
+            versions.sort_by_cached_key(|(v, _)| Reverse(Version::parse(v).priority()));
+            for (_, resources) in &mut versions {
+                resources.sort_by(|a, b| a.plural.cmp(&b.plural));
+            }
+            (group, versions)
+        })
+        .collect()
+}
+
+fn group_version(group: &str, version: &str) -> String {
+    if group.is_empty() {
+        version.to_string()
+    } else {
+        format!("{group}/{version}")
+    }
+}
+
+/// `APIGroup` document for a non-core group
+fn api_group(group: &str, versions: &[(String, Vec<ServedResource>)]) -> Value {
+    let versions: Vec<Value> = versions
+        .iter()
+        .map(|(version, _)| {
+            json!({ "groupVersion": group_version(group, version), "version": version })
+        })
+        .collect();
+    json!({
+        "kind": "APIGroup",
+        "apiVersion": "v1",
+        "name": group,
+        "versions": versions,
+        "preferredVersion": versions[0],
+    })
+}
+
+/// Aggregated `APIGroupDiscovery` entry for a group
+fn aggregated_group(group: &str, versions: &[(String, Vec<ServedResource>)]) -> Value {
+    let versions: Vec<Value> = versions
+        .iter()
+        .map(|(version, resources)| {
+            json!({
+                "version": version,
+                "freshness": "Current",
+                "resources": resources
+                    .iter()
+                    .map(ServedResource::aggregated_resource)
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let metadata = if group.is_empty() {
+        json!({ "creationTimestamp": null })
+    } else {
+        json!({ "name": group, "creationTimestamp": null })
+    };
+    json!({ "metadata": metadata, "versions": versions })
+}
+
+/// Build the discovery document served at `path`
+///
+/// Returns `None` if the path is not a discovery endpoint or names an unknown
+/// group or version. `aggregated` selects the `apidiscovery.k8s.io/v2` format for
+/// `/api` and `/apis`, as requested through the `Accept` header.
+pub(crate) fn document(registry: &ResourceRegistry, path: &str, aggregated: bool) -> Option<Value> {
+    let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut groups = served_resources(registry);
+
+    match parts.as_slice() {
+        ["api"] if aggregated => {
+            let core = groups.remove("")?;
+            Some(aggregated_list(vec![aggregated_group("", &core)]))
+        }
+        ["api"] => {
+            let core = groups.remove("")?;
+            let versions: Vec<&str> = core.iter().map(|(v, _)| v.as_str()).collect();
+            Some(json!({
+                "kind": "APIVersions",
+                "versions": versions,
+                "serverAddressByClientCIDRs": [
+                    { "clientCIDR": "0.0.0.0/0", "serverAddress": "127.0.0.1:6443" }
+                ],
+            }))
+        }
+        ["apis"] if aggregated => Some(aggregated_list(
+            groups
+                .iter()
+                .filter(|(group, _)| !group.is_empty())
+                .map(|(group, versions)| aggregated_group(group, versions))
+                .collect(),
+        )),
+        ["apis"] => {
+            let groups: Vec<Value> = groups
+                .iter()
+                .filter(|(group, _)| !group.is_empty())
+                .map(|(group, versions)| api_group(group, versions))
+                .collect();
+            Some(json!({ "kind": "APIGroupList", "apiVersion": "v1", "groups": groups }))
+        }
+        ["apis", group] if !group.is_empty() => groups
+            .get(*group)
+            .map(|versions| api_group(group, versions)),
+        ["api", version] => resource_list(&mut groups, "", version),
+        ["apis", group, version] if !group.is_empty() => resource_list(&mut groups, group, version),
+        _ => None,
+    }
+}
+
+fn aggregated_list(items: Vec<Value>) -> Value {
+    json!({
+        "kind": "APIGroupDiscoveryList",
+        "apiVersion": "apidiscovery.k8s.io/v2",
+        "metadata": {},
+        "items": items,
+    })
+}
+
+/// `APIResourceList` document for a group version
+fn resource_list(
+    groups: &mut BTreeMap<String, Vec<(String, Vec<ServedResource>)>>,
+    group: &str,
+    version: &str,
+) -> Option<Value> {
+    let versions = groups.remove(group)?;
+    let (_, resources) = versions.into_iter().find(|(v, _)| v == version)?;
+    let entries: Vec<Value> = resources
+        .iter()
+        .flat_map(ServedResource::api_resources)
+        .collect();
+    Some(json!({
+        "kind": "APIResourceList",
+        "apiVersion": "v1",
+        "groupVersion": group_version(group, version),
+        "resources": entries,
+    }))
+}
+
+/// Whether an `Accept` header asks for aggregated discovery
+pub(crate) fn wants_aggregated(accept: Option<&str>) -> bool {
+    accept.is_some_and(|accept| {
+        accept.split(',').any(|media| {
+            let media = media.replace(' ', "");
+            media.contains("g=apidiscovery.k8s.io")
+                && media.contains("v=v2")
+                && media.contains("as=APIGroupDiscoveryList")
+        })
+    })
+}
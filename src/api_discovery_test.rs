@@ -0,0 +1,270 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use kube::core::GroupVersionKind;
+    use kube::discovery::{Discovery, Scope};
+    use kube::CustomResource;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+    #[kube(
+        group = "example.com",
+        version = "v1",
+        kind = "Widget",
+        plural = "widgets",
+        namespaced
+    )]
+    struct WidgetSpec {
+        size: i32,
+    }
+
+    async fn get_json(client: &kube::Client, request: http::Request<Vec<u8>>) -> Value {
+        let text = client.request_text(request).await.unwrap();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_discovery_run_resolves_builtin_and_custom_resources() {
+        let client = ClientBuilder::new()
+            .with_resource::<Widget>()
+            .build()
+            .await
+            .unwrap();
+        let discovery = Discovery::new(client).run().await.unwrap();
+
+        let gvk = GroupVersionKind::gvk("apps", "v1", "Deployment");
+        let (resource, caps) = discovery.resolve_gvk(&gvk).unwrap();
+        assert_eq!(resource.plural, "deployments");
+        assert_eq!(caps.scope, Scope::Namespaced);
+        assert!(caps.supports_operation("watch"));
+        assert!(caps.subresources.iter().any(|(r, _)| r.plural == "scale"));
+
+        let gvk = GroupVersionKind::gvk("", "v1", "Node");
+        let (_, caps) = discovery.resolve_gvk(&gvk).unwrap();
+        assert_eq!(caps.scope, Scope::Cluster);
+
+        let gvk = GroupVersionKind::gvk("example.com", "v1", "Widget");
+        let (resource, _) = discovery.resolve_gvk(&gvk).unwrap();
+        assert_eq!(resource.plural, "widgets");
+
+        // The preferred version follows Kubernetes version priority
+        let autoscaling = discovery.get("autoscaling").unwrap();
+        assert_eq!(autoscaling.preferred_version_or_latest(), "v2");
+    }
+
+    #[tokio::test]
+    async fn test_group_and_version_documents() {
+        let client = ClientBuilder::new().build().await.unwrap();
+
+        let request = http::Request::get("/apis/apps").body(vec![]).unwrap();
+        let group = get_json(&client, request).await;
+        assert_eq!(group["kind"], "APIGroup");
+        assert_eq!(group["preferredVersion"]["groupVersion"], "apps/v1");
+
+        let request = http::Request::get("/api/v1").body(vec![]).unwrap();
+        let resources = get_json(&client, request).await;
+        assert_eq!(resources["kind"], "APIResourceList");
+        let names: Vec<&str> = resources["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|r| r["name"].as_str())
+            .collect();
+        assert!(names.contains(&"pods"));
+        assert!(names.contains(&"pods/status"));
+
+        // Unknown groups are not found
+        let request = http::Request::get("/apis/unknown.example.com")
+            .body(vec![])
+            .unwrap();
+        let err = client.request_text(request).await.unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 404));
+    }
+
+    #[tokio::test]
+    async fn test_aggregated_discovery() {
+        let client = ClientBuilder::new()
+            .with_resource::<Widget>()
+            .build()
+            .await
+            .unwrap();
+        let accept = "application/json;g=apidiscovery.k8s.io;v=v2;as=APIGroupDiscoveryList,\
+                      application/json";
+
+        let request = http::Request::get("/apis")
+            .header("Accept", accept)
+            .body(vec![])
+            .unwrap();
+        let list = get_json(&client, request).await;
+        assert_eq!(list["kind"], "APIGroupDiscoveryList");
+        let items = list["items"].as_array().unwrap();
+        let widgets = items
+            .iter()
+            .find(|g| g["metadata"]["name"] == "example.com")
+            .unwrap();
+        let resource = &widgets["versions"][0]["resources"][0];
+        assert_eq!(resource["resource"], "widgets");
+        assert_eq!(resource["scope"], "Namespaced");
+
+        let request = http::Request::get("/api")
+            .header("Accept", accept)
+            .body(vec![])
+            .unwrap();
+        let core = get_json(&client, request).await;
+        let pods = core["items"][0]["versions"][0]["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["resource"] == "pods")
+            .unwrap();
+        assert_eq!(pods["responseKind"]["kind"], "Pod");
+        assert!(pods["subresources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["subresource"] == "status"));
+    }
+
+    #[tokio::test]
+    async fn test_api_resource_added_at_runtime() {
+        use kube::api::{Api, ApiResource, DynamicObject, PostParams};
+
+        let client = ClientBuilder::new()
+            .with_api_resource(
+                "metrics.k8s.io",
+                "v1beta1",
+                "PodMetrics",
+                "pods",
+                true,
+                &["get", "list"],
+                &[],
+            )
+            .with_runtime_objects(vec![serde_json::json!({
+                "apiVersion": "metrics.k8s.io/v1beta1",
+                "kind": "PodMetrics",
+                "metadata": { "name": "web-0", "namespace": "default" },
+                "containers": [{ "name": "web", "usage": { "cpu": "250m", "memory": "64Mi" } }]
+            })])
+            .build()
+            .await
+            .unwrap();
+
+        let discovery = Discovery::new(client.clone()).run().await.unwrap();
+        let gvk = GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", "PodMetrics");
+        let (resource, caps) = discovery.resolve_gvk(&gvk).unwrap();
+        assert_eq!(resource.plural, "pods");
+        assert_eq!(caps.scope, Scope::Namespaced);
+        assert!(caps.supports_operation("list"));
+        assert!(!caps.supports_operation("create"));
+
+        let metrics: Api<DynamicObject> = Api::namespaced_with(
+            client,
+            "default",
+            &ApiResource::from_gvk_with_plural(&gvk, "pods"),
+        );
+        let pod_metrics = metrics.get("web-0").await.unwrap();
+        assert_eq!(pod_metrics.data["containers"][0]["usage"]["cpu"], "250m");
+
+        let err = metrics
+            .create(&PostParams::default(), &pod_metrics)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 405));
+    }
+
+    #[tokio::test]
+    async fn test_api_resource_overrides_builtin() {
+        use k8s_openapi::api::core::v1::ConfigMap;
+        use kube::api::{Api, PostParams};
+
+        let client = ClientBuilder::new()
+            .with_api_resource(
+                "",
+                "v1",
+                "ConfigMap",
+                "configmaps",
+                true,
+                &["get", "list", "watch"],
+                &[],
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let request = http::Request::get("/api/v1").body(vec![]).unwrap();
+        let resources = get_json(&client, request).await;
+        let configmaps: Vec<&Value> = resources["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|r| r["name"] == "configmaps")
+            .collect();
+        assert_eq!(configmaps.len(), 1);
+        assert_eq!(
+            configmaps[0]["verbs"],
+            serde_json::json!(["get", "list", "watch"])
+        );
+
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("settings".to_string());
+        let err = Api::<ConfigMap>::namespaced(client, "default")
+            .create(&PostParams::default(), &cm)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 405));
+    }
+
+    #[tokio::test]
+    async fn test_without_builtin_resource() {
+        use k8s_openapi::api::batch::v1::{CronJob, Job};
+        use k8s_openapi::api::discovery::v1::EndpointSlice;
+        use kube::api::{Api, ListParams};
+
+        let client = ClientBuilder::new()
+            .without_builtin_resource("discovery.k8s.io", "v1", "EndpointSlice")
+            .without_builtin_resource("batch", "v1", "CronJob")
+            .build()
+            .await
+            .unwrap();
+
+        let discovery = Discovery::new(client.clone()).run().await.unwrap();
+        let missing = GroupVersionKind::gvk("batch", "v1", "CronJob");
+        assert!(discovery.resolve_gvk(&missing).is_none());
+        let missing = GroupVersionKind::gvk("discovery.k8s.io", "v1", "EndpointSlice");
+        assert!(discovery.resolve_gvk(&missing).is_none());
+        // The rest of the group is still served
+        let jobs = GroupVersionKind::gvk("batch", "v1", "Job");
+        assert!(discovery.resolve_gvk(&jobs).is_some());
+
+        let err = Api::<CronJob>::default_namespaced(client.clone())
+            .list(&ListParams::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 404));
+        let err = Api::<EndpointSlice>::default_namespaced(client.clone())
+            .get("web")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 404));
+        Api::<Job>::default_namespaced(client)
+            .list(&ListParams::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_initial_objects_of_removed_resources_are_rejected() {
+        use k8s_openapi::api::batch::v1::CronJob;
+
+        let mut cron_job = CronJob::default();
+        cron_job.metadata.name = Some("nightly".to_string());
+        let result = ClientBuilder::new()
+            .without_builtin_resource("batch", "v1", "CronJob")
+            .with_object(cron_job)
+            .build()
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Audit log of the requests served by the fake client
+//!
+//! Every request handled by the mock service is recorded with the verb, user,
+//! resource, response status and, for successful writes, a JSON Patch from the
+//! stored object before the request to the stored object after it. Entries can be
+//! inspected through [`ClientHandle::audit_entries`](crate::ClientHandle::audit_entries)
+//! or written out as `audit.k8s.io/v1` Events in JSON lines.
+//! [`ClientHandle::changes_for`](crate::ClientHandle::changes_for) picks out the
+//! writes to one object as [`ObjectChange`]s, with the object before and after
+//! each write.
+
+use crate::testing::SERVER_MANAGED_FIELDS;
+use crate::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use std::io::Write;
+
+/// User recorded for requests made without an identity
+pub(crate) const DEFAULT_USER: &str = "system:admin";
+
+/// One request served by the fake client
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    /// Unique ID of the request
+    pub id: String,
+    /// HTTP method
+    pub method: String,
+    /// Request URI, including the query string
+    pub uri: String,
+    /// API verb, such as `get`, `list`, `patch` or `deletecollection`
+    ///
+    /// Non-resource requests, such as discovery, use the lowercased HTTP method.
+    pub verb: String,
+    /// User the request was made as, `system:admin` without an identity
+    pub user: String,
+    /// Groups of the user
+    pub groups: Vec<String>,
+    /// API group of the resource, empty for the core group
+    pub api_group: String,
+    /// API version of the resource
+    pub api_version: Option<String>,
+    /// Resource, such as `pods`
+    pub resource: Option<String>,
+    /// Subresource, such as `status`
+    pub subresource: Option<String>,
+    /// Namespace of the request
+    pub namespace: Option<String>,
+    /// Name of the object the request names
+    pub name: Option<String>,
+    /// HTTP status code of the response
+    pub status: u16,
+    /// JSON request body
+    pub request_object: Option<Value>,
+    /// JSON response body; list responses are not recorded
+    pub response_object: Option<Value>,
+    /// JSON Patch from the stored object before the request to the stored object
+    /// after it, for successful creates, updates, patches and deletes
+    ///
+    /// An empty patch means the write left the object unchanged.
+    pub diff: Option<Value>,
+    /// The stored object before a successful write, `None` for creates
+    pub before: Option<Value>,
+    /// The stored object after a successful write, `None` once deleted
+    pub after: Option<Value>,
+    /// Tags attached to the request with
+    /// [`ClientHandle::with_request_tag`](crate::ClientHandle::with_request_tag),
+    /// outermost first
+    pub tags: Vec<String>,
+    /// The `fieldManager` the request named, if any
+    pub field_manager: Option<String>,
+    /// Warnings returned with the response by the rules added with
+    /// [`ClientBuilder::with_warning_rule`](crate::ClientBuilder::with_warning_rule)
+    pub warnings: Vec<String>,
+    /// When the request was received
+    pub received: DateTime<Utc>,
+    /// When the response was produced
+    pub completed: DateTime<Utc>,
+}
+
+impl AuditEntry {
+    /// Whether the request changed a stored object
+    pub fn is_mutation(&self) -> bool {
+        self.diff
+            .as_ref()
+            .and_then(Value::as_array)
+            .is_some_and(|ops| !ops.is_empty())
+    }
+
+    /// The entry as an `audit.k8s.io/v1` Event at the `RequestResponse` level
+    pub fn to_event(&self) -> Value {
+        let timestamp = |t: &DateTime<Utc>| t.to_rfc3339_opts(SecondsFormat::Micros, true);
+        let mut event = json!({
+            "kind": "Event",
+            "apiVersion": "audit.k8s.io/v1",
+            "level": "RequestResponse",
+            "auditID": self.id,
+            "stage": "ResponseComplete",
+            "requestURI": self.uri,
+            "verb": self.verb,
+            "user": {
+                "username": self.user,
+                "groups": self.groups,
+            },
+            "responseStatus": {
+                "metadata": {},
+                "code": self.status,
+            },
+            "requestReceivedTimestamp": timestamp(&self.received),
+            "stageTimestamp": timestamp(&self.completed),
+        });
+
+        if let Some(resource) = &self.resource {
+            let mut object_ref = json!({
+                "resource": resource,
+                "apiGroup": self.api_group,
+            });
+            let fields = [
+                ("apiVersion", &self.api_version),
+                ("subresource", &self.subresource),
+                ("namespace", &self.namespace),
+                ("name", &self.name),
+            ];
+            for (key, value) in fields {
+                if let Some(value) = value {
+                    object_ref[key] = json!(value);
+                }
+            }
+            event["objectRef"] = object_ref;
+        }
+        if let Some(request) = &self.request_object {
+            event["requestObject"] = request.clone();
+        }
+        if let Some(response) = &self.response_object {
+            event["responseObject"] = response.clone();
+        }
+        if let Some(diff) = &self.diff {
+            event["annotations"]["kube-fake-client/diff"] = json!(diff.to_string());
+        }
+        if !self.tags.is_empty() {
+            event["annotations"]["kube-fake-client/tags"] = json!(self.tags.join(","));
+        }
+        event
+    }
+}
+
+/// A successful write to one object, taken from the audit log
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectChange {
+    /// API verb: `create`, `update`, `patch` or `delete`
+    pub verb: String,
+    /// Subresource written, such as `status`
+    pub subresource: Option<String>,
+    /// User the request was made as
+    pub user: String,
+    /// The `fieldManager` the request named, if any
+    pub field_manager: Option<String>,
+    /// Tags attached to the request
+    pub tags: Vec<String>,
+    /// JSON request body: the object replaced, or the patch sent
+    pub request_object: Option<Value>,
+    /// The stored object before the write, `None` for creates
+    pub before: Option<Value>,
+    /// The stored object after the write, `None` once deleted
+    pub after: Option<Value>,
+    /// JSON Patch from `before` to `after`
+    pub diff: Value,
+}
+
+impl ObjectChange {
+    /// The change a successful write recorded in the audit log made, `None` for
+    /// reads and failed requests
+    pub(crate) fn from_entry(entry: &AuditEntry) -> Option<Self> {
+        Some(Self {
+            verb: entry.verb.clone(),
+            subresource: entry.subresource.clone(),
+            user: entry.user.clone(),
+            field_manager: entry.field_manager.clone(),
+            tags: entry.tags.clone(),
+            request_object: entry.request_object.clone(),
+            before: entry.before.clone(),
+            after: entry.after.clone(),
+            diff: entry.diff.clone()?,
+        })
+    }
+
+    /// JSON Pointer paths of the fields the write changed, in patch order
+    ///
+    /// Metadata the server maintains on every write, such as `resourceVersion`
+    /// and `managedFields`, is left out, so a reconcile can be checked to touch
+    /// exactly the fields it means to. A create or delete changes the whole
+    /// object, reported as the empty path `""`.
+    pub fn changed_paths(&self) -> Vec<String> {
+        let server_managed = |path: &str| {
+            SERVER_MANAGED_FIELDS.iter().any(|field| {
+                path.strip_prefix("/metadata/")
+                    .and_then(|rest| rest.strip_prefix(field))
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+        };
+        let mut paths: Vec<String> = Vec::new();
+        for op in self.diff.as_array().into_iter().flatten() {
+            for key in ["from", "path"] {
+                let Some(path) = op.get(key).and_then(Value::as_str) else {
+                    continue;
+                };
+                if !server_managed(path) && !paths.iter().any(|p| p == path) {
+                    paths.push(path.to_string());
+                }
+            }
+        }
+        paths
+    }
+
+    /// Whether the write changed anything besides server-maintained metadata
+    pub fn is_noop(&self) -> bool {
+        self.changed_paths().is_empty()
+    }
+}
+
+/// Recorded audit entries, shared by every clone of a client
+#[derive(Default)]
+pub(crate) struct AuditLog {
+    inner: parking_lot::Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    entries: Vec<AuditEntry>,
+    /// Entries dropped by `clear`, so positions stay stable across clears
+    cleared: usize,
+}
+
+impl AuditLog {
+    pub(crate) fn record(&self, entry: AuditEntry) {
+        self.inner.lock().entries.push(entry);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<AuditEntry> {
+        self.inner.lock().entries.clone()
+    }
+
+    /// Number of entries ever recorded, including cleared ones
+    pub(crate) fn len(&self) -> usize {
+        let inner = self.inner.lock();
+        inner.cleared + inner.entries.len()
+    }
+
+    /// Entries recorded at or after a position returned by `len`, minus cleared ones
+    pub(crate) fn entries_since(&self, position: usize) -> Vec<AuditEntry> {
+        let inner = self.inner.lock();
+        let start = position
+            .saturating_sub(inner.cleared)
+            .min(inner.entries.len());
+        inner.entries[start..].to_vec()
+    }
+
+    pub(crate) fn clear(&self) {
+        let mut inner = self.inner.lock();
+        inner.cleared += inner.entries.len();
+        inner.entries.clear();
+    }
+}
+
+/// JSON Patch turning one version of an object into another
+pub(crate) fn diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+    let before = before.unwrap_or(&Value::Null);
+    let after = after.unwrap_or(&Value::Null);
+    serde_json::to_value(json_patch::diff(before, after)).unwrap_or(Value::Null)
+}
+
+/// Write entries as JSON lines of `audit.k8s.io/v1` Events
+pub(crate) fn write_json_lines<W: Write>(entries: &[AuditEntry], mut writer: W) -> Result<()> {
+    for entry in entries {
+        serde_json::to_writer(&mut writer, &entry.to_event())?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| crate::Error::Internal(format!("failed to write audit log: {e}")))?;
+    }
+    Ok(())
+}
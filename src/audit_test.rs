@@ -0,0 +1,222 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap_with;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{ConfigMap, Pod};
+    use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_requests_are_recorded_in_order() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "key", "one"),
+            )
+            .await
+            .unwrap();
+        configmaps.get("settings").await.unwrap();
+        configmaps.list(&ListParams::default()).await.unwrap();
+        configmaps.get("missing").await.unwrap_err();
+        configmaps
+            .delete("settings", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        let entries = handle.audit_entries();
+        let summary: Vec<(&str, u16)> = entries
+            .iter()
+            .map(|e| (e.verb.as_str(), e.status))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("create", 201),
+                ("get", 200),
+                ("list", 200),
+                ("get", 404),
+                ("delete", 200)
+            ]
+        );
+
+        let create = &entries[0];
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.user, "system:admin");
+        assert_eq!(create.resource.as_deref(), Some("configmaps"));
+        assert_eq!(create.namespace.as_deref(), Some("default"));
+        assert_eq!(entries[1].name.as_deref(), Some("settings"));
+        assert!(create.request_object.is_some());
+        assert!(entries[2].response_object.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_writes_record_object_diffs() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap_with("settings", "key", "one"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let patch = json!({ "data": { "key": "two" } });
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        configmaps.get("settings").await.unwrap();
+
+        let entries = handle.audit_entries();
+        let ops = entries[0].diff.as_ref().unwrap().as_array().unwrap();
+        assert!(ops.contains(&json!({ "op": "replace", "path": "/data/key", "value": "two" })));
+        assert!(entries[0].is_mutation());
+
+        // Patching to the same content leaves the data unchanged
+        let ops = entries[1].diff.as_ref().unwrap().as_array().unwrap();
+        assert!(ops
+            .iter()
+            .all(|op| op["path"].as_str().unwrap().starts_with("/metadata/")));
+        assert!(entries[2].diff.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_identity_recorded_and_forbidden_requests_audited() {
+        let (client, handle) = ClientBuilder::new()
+            .with_identity("alice", ["developers"])
+            .build_with_handle()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+
+        pods.list(&ListParams::default()).await.unwrap_err();
+
+        let entries = handle.audit_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].user, "alice");
+        assert_eq!(entries[0].groups, ["developers"]);
+        assert_eq!(entries[0].status, 403);
+    }
+
+    #[tokio::test]
+    async fn test_export_json_lines_events() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "key", "one"),
+            )
+            .await
+            .unwrap();
+        configmaps.get("settings").await.unwrap();
+
+        let mut out = Vec::new();
+        handle.write_audit_log(&mut out).unwrap();
+        let events: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(events.len(), 2);
+        let event = &events[0];
+        assert_eq!(event["apiVersion"], "audit.k8s.io/v1");
+        assert_eq!(event["kind"], "Event");
+        assert_eq!(event["stage"], "ResponseComplete");
+        assert_eq!(event["verb"], "create");
+        assert_eq!(event["user"]["username"], "system:admin");
+        assert_eq!(event["objectRef"]["resource"], "configmaps");
+        assert_eq!(event["objectRef"]["namespace"], "default");
+        assert_eq!(event["objectRef"]["apiVersion"], "v1");
+        assert_eq!(event["responseStatus"]["code"], 201);
+        assert_eq!(event["responseObject"]["metadata"]["name"], "settings");
+        assert_eq!(events[1]["objectRef"]["name"], "settings");
+    }
+
+    #[tokio::test]
+    async fn test_clear_audit_log() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        configmaps.list(&ListParams::default()).await.unwrap();
+
+        handle.clear_audit_log();
+        assert!(handle.audit_entries().is_empty());
+
+        configmaps.list(&ListParams::default()).await.unwrap();
+        assert_eq!(handle.audit_entries().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_changes_for_reports_each_write_to_an_object() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "key", "one"),
+            )
+            .await
+            .unwrap();
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("other", "key", "one"),
+            )
+            .await
+            .unwrap();
+        let patch = json!({ "data": { "key": "two" } });
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        let mut settings = configmaps.get("settings").await.unwrap();
+        settings.metadata.labels = Some(BTreeMap::from([("app".to_string(), "web".to_string())]));
+        configmaps
+            .replace("settings", &PostParams::default(), &settings)
+            .await
+            .unwrap();
+        // A write that changes nothing is still reported
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        configmaps
+            .delete("settings", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        let changes = handle
+            .changes_for::<ConfigMap>("default", "settings")
+            .unwrap();
+        let verbs: Vec<&str> = changes.iter().map(|c| c.verb.as_str()).collect();
+        assert_eq!(verbs, ["create", "patch", "update", "patch", "delete"]);
+
+        let (create, patched) = (&changes[0], &changes[1]);
+        assert_eq!(create.before, None);
+        assert_eq!(create.changed_paths(), [""]);
+        assert_eq!(patched.changed_paths(), ["/data/key"]);
+        assert_eq!(patched.request_object, Some(patch));
+        assert_eq!(patched.before.as_ref().unwrap()["data"]["key"], "one");
+        assert_eq!(patched.after.as_ref().unwrap()["data"]["key"], "two");
+        assert_eq!(changes[2].changed_paths(), ["/metadata/labels"]);
+        assert!(changes[3].is_noop());
+        assert_eq!(changes[4].after, None);
+
+        assert!(handle
+            .changes_for::<ConfigMap>("default", "missing")
+            .unwrap()
+            .is_empty());
+        assert!(handle
+            .changes_for::<ConfigMap>("other-namespace", "settings")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+//! Request budget assertions
+//!
+//! Built on the audit log, these catch reconcile loops that thrash the API
+//! server: [`ClientHandle::assert_request_count`] bounds how many requests of a
+//! verb were made, and [`ClientHandle::read_only`] returns a guard that fails the
+//! test if anything writes to a resource the test expects to be left alone.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::budget::{at_most, Verb};
+//! use kube_fake_client::ClientBuilder;
+//! use k8s_openapi::api::core::v1::Secret;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+//! let secrets_untouched = handle.read_only::<Secret>();
+//!
+//! // ... run one reconcile with `client` ...
+//!
+//! handle.assert_request_count(Verb::Patch, at_most(2));
+//! drop(secrets_untouched);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ClientHandle::assert_request_count`]: crate::ClientHandle::assert_request_count
+//! [`ClientHandle::read_only`]: crate::ClientHandle::read_only
+
+use crate::audit::{AuditEntry, AuditLog};
+use std::fmt;
+use std::sync::Arc;
+
+/// API verb of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verb {
+    Get,
+    List,
+    Watch,
+    Create,
+    Update,
+    Patch,
+    Delete,
+    DeleteCollection,
+}
+
+impl Verb {
+    /// The verb as it appears in RBAC rules and audit events
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verb::Get => "get",
+            Verb::List => "list",
+            Verb::Watch => "watch",
+            Verb::Create => "create",
+            Verb::Update => "update",
+            Verb::Patch => "patch",
+            Verb::Delete => "delete",
+            Verb::DeleteCollection => "deletecollection",
+        }
+    }
+
+    /// The verb of an audit entry, if it is one of these
+    pub(crate) fn parse(verb: &str) -> Option<Verb> {
+        [
+            Verb::Get,
+            Verb::List,
+            Verb::Watch,
+            Verb::Create,
+            Verb::Update,
+            Verb::Patch,
+            Verb::Delete,
+            Verb::DeleteCollection,
+        ]
+        .into_iter()
+        .find(|v| v.as_str() == verb)
+    }
+
+    /// Whether requests with this verb write to the API server
+    pub fn is_write(self) -> bool {
+        !matches!(self, Verb::Get | Verb::List | Verb::Watch)
+    }
+}
+
+impl fmt::Display for Verb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Range a request count must fall in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bound {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Bound {
+    /// Whether a count is within the bound
+    pub fn contains(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "exactly {max}"),
+            Some(max) => write!(f, "at most {max}"),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
+/// No more than `max` requests
+pub fn at_most(max: usize) -> Bound {
+    Bound {
+        min: 0,
+        max: Some(max),
+    }
+}
+
+/// No fewer than `min` requests
+pub fn at_least(min: usize) -> Bound {
+    Bound { min, max: None }
+}
+
+/// Exactly `count` requests
+pub fn exactly(count: usize) -> Bound {
+    Bound {
+        min: count,
+        max: Some(count),
+    }
+}
+
+/// Panic listing the offending requests
+pub(crate) fn fail(summary: &str, entries: &[&AuditEntry]) -> ! {
+    let requests: Vec<String> = entries
+        .iter()
+        .map(|e| format!("  {} {} ({})", e.method, e.uri, e.status))
+        .collect();
+    panic!("{summary}:\n{}", requests.join("\n"));
+}
+
+/// Fails the test if a resource is written to while the guard is alive
+///
+/// Every write request counts, whether or not it succeeded. The check runs when
+/// the guard is dropped, or earlier with [`check`](Self::check). Created by
+/// [`ClientHandle::read_only`](crate::ClientHandle::read_only).
+#[must_use = "the resource is only checked when the guard is dropped"]
+pub struct ReadOnlyGuard {
+    audit: Arc<AuditLog>,
+    /// Audit entries recorded before the guard was created
+    start: usize,
+    group: String,
+    resource: String,
+}
+
+impl ReadOnlyGuard {
+    pub(crate) fn new(audit: Arc<AuditLog>, group: String, resource: String) -> Self {
+        let start = audit.len();
+        Self {
+            audit,
+            start,
+            group,
+            resource,
+        }
+    }
+
+    /// Panic if the resource has been written to since the guard was created
+    pub fn check(&self) {
+        let entries = self.audit.entries_since(self.start);
+        let writes: Vec<&AuditEntry> = entries
+            .iter()
+            .filter(|e| {
+                e.resource.as_deref() == Some(self.resource.as_str())
+                    && e.api_group == self.group
+                    && !matches!(e.verb.as_str(), "get" | "list" | "watch")
+            })
+            .collect();
+        if !writes.is_empty() {
+            let resource = match self.group.as_str() {
+                "" => self.resource.clone(),
+                group => format!("{}.{group}", self.resource),
+            };
+            fail(
+                &format!("{} write(s) to read-only resource {resource}", writes.len()),
+                &writes,
+            );
+        }
+    }
+}
+
+impl Drop for ReadOnlyGuard {
+    fn drop(&mut self) {
+        // Don't turn an unrelated failure into a double panic
+        if !std::thread::panicking() {
+            self.check();
+        }
+    }
+}
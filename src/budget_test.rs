@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::budget::{at_least, at_most, exactly, Verb};
+    use crate::test_fixtures::configmap;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use serde_json::json;
+    use std::panic::AssertUnwindSafe;
+
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = std::panic::catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn patch_twice(api: &Api<ConfigMap>) {
+        for value in ["one", "two"] {
+            let patch = json!({ "data": { "key": value } });
+            api.patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert!(at_most(2).contains(0));
+        assert!(at_most(2).contains(2));
+        assert!(!at_most(2).contains(3));
+        assert!(at_least(1).contains(5));
+        assert!(!at_least(1).contains(0));
+        assert!(exactly(1).contains(1));
+        assert!(!exactly(1).contains(2));
+        assert_eq!(exactly(3).to_string(), "exactly 3");
+        assert_eq!(at_most(3).to_string(), "at most 3");
+    }
+
+    #[tokio::test]
+    async fn test_request_count_assertions() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap("settings"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        patch_twice(&configmaps).await;
+
+        assert_eq!(handle.request_count(Verb::Patch), 2);
+        handle.assert_request_count(Verb::Patch, at_most(2));
+        handle.assert_request_count(Verb::Create, exactly(0));
+
+        let message = panic_message(|| handle.assert_request_count(Verb::Patch, at_most(1)));
+        assert!(message.starts_with("expected at most 1 patch request(s), got 2:"));
+        assert!(message.contains("PATCH /api/v1/namespaces/default/configmaps/settings"));
+    }
+
+    #[tokio::test]
+    async fn test_read_only_guard_passes_on_reads() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap("settings"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let guard = handle.read_only::<ConfigMap>();
+
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        configmaps.get("settings").await.unwrap();
+        // Writes to other resources are fine
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+        let mut secret = Secret::default();
+        secret.metadata.name = Some("token".to_string());
+        secrets
+            .create(&PostParams::default(), &secret)
+            .await
+            .unwrap();
+
+        drop(guard);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_guard_fails_on_write() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap("settings"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        // Writes before the guard was created don't count
+        patch_twice(&configmaps).await;
+        let guard = handle.read_only::<ConfigMap>();
+        guard.check();
+
+        patch_twice(&configmaps).await;
+        let message = panic_message(move || drop(guard));
+        assert!(message.starts_with("2 write(s) to read-only resource configmaps:"));
+    }
+}
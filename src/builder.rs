@@ -1,21 +1,73 @@
 //! Builder for constructing fake clients with various options
 
-use crate::client::{FakeClient, IndexerFunc};
+use crate::access_review::AccessReviewHandler;
+use crate::cassette::Replay;
+use crate::chaos::{Chaos, ChaosConfig};
+use crate::client::{DefaulterFunc, FakeClient, IndexerFunc, PermissiveVerbs};
 use crate::client_utils::extract_gvk;
+use crate::cluster::Cluster;
+use crate::csr::CsrSigner;
 use crate::discovery::Discovery;
+use crate::handle::ClientHandle;
 use crate::interceptor;
-use crate::registry::ResourceRegistry;
-use crate::tracker::{GVK, GVR};
-#[cfg(feature = "validation")]
-use crate::validator::RuntimeOpenAPIValidator;
+use crate::mock_service::MockService;
+use crate::naming::MetadataRule;
+use crate::persistence::Persistence;
+use crate::rbac::Identity;
+use crate::registry::{ResourceMetadata, ResourceRegistry, ScaleSubresource};
+use crate::service::ServiceCidr;
+use crate::shard::IndexKey;
+use crate::store::ObjectStore;
+use crate::test_env::TestEnv;
+use crate::token_review::TokenReviewHandler;
+use crate::tracker::{ListOrder, GVK, GVR};
 use crate::validator::SchemaValidator;
+#[cfg(feature = "validation")]
+use crate::validator::{Precompile, RuntimeOpenAPIValidator};
+use crate::warning::WarningRule;
 use crate::{Error, Result};
+use bytes::Bytes;
+use http::{Request, Response};
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::BodyExt;
+use k8s_openapi::api::authentication::v1::{TokenReviewSpec, UserInfo};
+use k8s_openapi::api::authorization::v1::SubjectAccessReviewSpec;
+use k8s_openapi::api::certificates::v1::CertificateSigningRequest;
+use kube::core::ApiResource;
 use kube::Resource;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use tower::util::BoxService;
+use tower::{BoxError, Layer, Service, ServiceExt};
+
+/// How initial objects are put into the fake cluster
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InitialObjectMode {
+    /// Store objects as given, like the Go fake's object tracker
+    ///
+    /// Metadata the objects carry is kept, including `resourceVersion`, `uid`,
+    /// `creationTimestamp` and a `deletionTimestamp` with finalizers, so they look
+    /// like objects that existed before the test. Missing fields are filled in.
+    #[default]
+    Add,
+    /// Create objects as if a client had just created them
+    ///
+    /// Server-set metadata (`resourceVersion`, `uid`, `creationTimestamp`,
+    /// `generation` and `deletionTimestamp`) is replaced, and objects go through
+    /// the same allocation, defaulting and validation as a create request.
+    Create,
+}
+
+/// The fake API server as seen by layers added with
+/// [`ClientBuilder::with_layer`]
+pub type LayeredService =
+    BoxService<Request<kube::client::Body>, Response<UnsyncBoxBody<Bytes, BoxError>>, BoxError>;
 
 /// Builder for creating fake clients
 ///
@@ -42,14 +94,54 @@ use std::sync::Arc;
 /// ```
 pub struct ClientBuilder {
     initial_objects: Vec<Value>,
+    initial_object_mode: InitialObjectMode,
+    allow_fixture_overwrite: bool,
+    strict_fixtures: bool,
+    list_order: ListOrder,
+    memory_limit: Option<usize>,
+    object_store: Option<Arc<dyn ObjectStore>>,
     with_status_subresource: Vec<GVK>,
     indexes: HashMap<GVK, HashMap<String, IndexerFunc>>,
+    label_indexes: Vec<(GVK, String)>,
     return_managed_fields: bool,
     fixture_dir: Option<PathBuf>,
     interceptors: Option<interceptor::Funcs>,
+    layers: Vec<Box<dyn FnOnce(LayeredService) -> LayeredService + Send>>,
     registry: ResourceRegistry,
+    service_cidr: ServiceCidr,
+    endpoints_controller: bool,
+    statefulset_controller: bool,
+    garbage_collector: bool,
+    service_account_pull_secrets: bool,
+    observed_generation: Vec<GVK>,
+    access_review_handler: Option<AccessReviewHandler>,
+    token_review_handler: Option<TokenReviewHandler>,
+    identity: Option<Identity>,
+    stale_reads: Vec<(GVK, Duration)>,
+    allow_path_mismatch: bool,
+    permissive_verbs: PermissiveVerbs,
+    strict_query_params: bool,
+    panic_on_unsupported: bool,
+    chaos: Option<ChaosConfig>,
+    persistence: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    defaulters: HashMap<GVK, Vec<DefaulterFunc>>,
+    csr_signer: Option<CsrSigner>,
+    warning_rules: Vec<WarningRule>,
+    metadata_rules: Vec<Arc<dyn MetadataRule>>,
+    injected_labels: BTreeMap<String, String>,
+    injected_annotations: BTreeMap<String, String>,
+    test_env: Option<TestEnv>,
+    /// Mistakes made by builder methods that can't fail, reported by build()
+    config_errors: Vec<String>,
+    #[cfg(feature = "validation")]
+    runtime_validator: Option<RuntimeOpenAPIValidator>,
+    #[cfg(feature = "validation")]
+    crd_schemas: Vec<Value>,
+    #[cfg(feature = "validation")]
+    precompile: Option<Precompile>,
     #[cfg(feature = "validation")]
-    runtime_validator: Option<Arc<RuntimeOpenAPIValidator>>,
+    schema_defaulting: bool,
 }
 
 impl ClientBuilder {
@@ -57,17 +149,125 @@ impl ClientBuilder {
     pub fn new() -> Self {
         Self {
             initial_objects: Vec::new(),
+            initial_object_mode: InitialObjectMode::default(),
+            allow_fixture_overwrite: false,
+            strict_fixtures: false,
+            list_order: ListOrder::default(),
+            memory_limit: None,
+            object_store: None,
             with_status_subresource: Vec::new(),
             indexes: HashMap::new(),
+            label_indexes: Vec::new(),
             return_managed_fields: false,
             fixture_dir: None,
             interceptors: None,
+            layers: Vec::new(),
             registry: ResourceRegistry::new(),
+            service_cidr: ServiceCidr::default(),
+            endpoints_controller: false,
+            statefulset_controller: false,
+            garbage_collector: false,
+            service_account_pull_secrets: false,
+            observed_generation: Vec::new(),
+            access_review_handler: None,
+            token_review_handler: None,
+            identity: None,
+            stale_reads: Vec::new(),
+            allow_path_mismatch: false,
+            permissive_verbs: PermissiveVerbs::default(),
+            strict_query_params: false,
+            panic_on_unsupported: false,
+            chaos: None,
+            persistence: None,
+            replay: None,
+            defaulters: HashMap::new(),
+            csr_signer: None,
+            warning_rules: Vec::new(),
+            metadata_rules: Vec::new(),
+            injected_labels: BTreeMap::new(),
+            injected_annotations: BTreeMap::new(),
+            test_env: TestEnv::installed().cloned(),
+            config_errors: Vec::new(),
             #[cfg(feature = "validation")]
             runtime_validator: None,
+            #[cfg(feature = "validation")]
+            crd_schemas: Vec::new(),
+            #[cfg(feature = "validation")]
+            precompile: None,
+            #[cfg(feature = "validation")]
+            schema_defaulting: false,
         }
     }
 
+    /// A builder behaving as close to a real API server as the fake can
+    ///
+    /// Starts from [`new`](Self::new) with every fidelity option at its
+    /// strictest:
+    ///
+    /// | Option | `conformant()` | `new()` | `lenient()` |
+    /// |---|---|---|---|
+    /// | Verb validation | on | on | off ([`with_permissive_verbs`](Self::with_permissive_verbs)) |
+    /// | Object name must match the path | yes | yes | no ([`with_path_mismatch_allowed`](Self::with_path_mismatch_allowed)) |
+    /// | Query parameter validation ([`with_strict_query_params`](Self::with_strict_query_params)) | on | off | off |
+    /// | `metadata.managedFields` in responses ([`with_return_managed_fields`](Self::with_return_managed_fields)) | yes | no | no |
+    /// | Initial objects ([`with_initial_object_mode`](Self::with_initial_object_mode)) | created | added | added |
+    /// | List order ([`with_list_order`](Self::with_list_order)) | by name | by name | by insertion |
+    ///
+    /// Immutable fields, server-side apply and, with the `validation` feature,
+    /// schema validation behave the same in every profile. Each option can still
+    /// be changed with its own method afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::conformant()
+    ///     .without_return_managed_fields()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn conformant() -> Self {
+        Self::new()
+            .with_strict_query_params()
+            .with_return_managed_fields()
+            .with_initial_object_mode(InitialObjectMode::Create)
+            .with_list_order(ListOrder::Name)
+    }
+
+    /// A builder using the fake as a plain object store
+    ///
+    /// Starts from [`new`](Self::new) with every fidelity option at its most
+    /// forgiving, for tests that only care about the objects they read and
+    /// write; see [`conformant`](Self::conformant) for what changes. Each option
+    /// can still be changed with its own method afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::lenient()
+    ///     .without_permissive_verbs()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lenient() -> Self {
+        Self::new()
+            .with_permissive_verbs()
+            .with_path_mismatch_allowed()
+            .with_initial_object_mode(InitialObjectMode::Add)
+            .with_list_order(ListOrder::Insertion)
+    }
+
     /// Add initial objects to the fake client
     ///
     /// These objects will be created when the client is built.
@@ -94,10 +294,13 @@ impl ClientBuilder {
     where
         K: Resource + Serialize,
     {
-        let value = serde_json::to_value(&obj).expect(
-            "Failed to serialize object - this should not happen with valid Kubernetes types",
-        );
-        self.initial_objects.push(value);
+        match serde_json::to_value(&obj) {
+            Ok(value) => self.initial_objects.push(value),
+            Err(e) => self.config_errors.push(format!(
+                "with_object() could not serialize {}: {e}",
+                std::any::type_name::<K>()
+            )),
+        }
         self
     }
 
@@ -106,11 +309,14 @@ impl ClientBuilder {
     where
         K: Resource + Serialize,
     {
-        for obj in objects {
-            let value = serde_json::to_value(&obj).expect(
-                "Failed to serialize object - this should not happen with valid Kubernetes types",
-            );
-            self.initial_objects.push(value);
+        for (index, obj) in objects.iter().enumerate() {
+            match serde_json::to_value(obj) {
+                Ok(value) => self.initial_objects.push(value),
+                Err(e) => self.config_errors.push(format!(
+                    "with_objects() could not serialize {} #{index}: {e}",
+                    std::any::type_name::<K>()
+                )),
+            }
         }
         self
     }
@@ -121,6 +327,170 @@ impl ClientBuilder {
         self
     }
 
+    /// Choose how initial objects are put into the fake cluster
+    ///
+    /// Defaults to [`InitialObjectMode::Add`]. Use [`InitialObjectMode::Create`]
+    /// for controllers that branch on whether an object looks new.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::{ClientBuilder, InitialObjectMode};
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut cm = ConfigMap::default();
+    /// cm.metadata.name = Some("settings".to_string());
+    /// cm.metadata.resource_version = Some("999".to_string());
+    ///
+    /// // Stored with a fresh resourceVersion, uid and creationTimestamp
+    /// let client = ClientBuilder::new()
+    ///     .with_initial_object_mode(InitialObjectMode::Create)
+    ///     .with_object(cm)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_initial_object_mode(mut self, mode: InitialObjectMode) -> Self {
+        self.initial_object_mode = mode;
+        self
+    }
+
+    /// Let later initial objects replace earlier ones of the same name
+    ///
+    /// By default, building fails when two initial objects, whether added with
+    /// [`with_object`](Self::with_object) or loaded from fixtures, share a kind,
+    /// namespace and name, since a fixture silently replacing another usually
+    /// hides a mistake in the test setup. With overwrites allowed, the last object
+    /// wins, taking the place of the first in insertion order, and initial objects
+    /// also replace objects already in a store passed to
+    /// [`with_object_store`](Self::with_object_store).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// // `overrides.yaml` changes some objects of `base.yaml`
+    /// let client = ClientBuilder::new()
+    ///     .with_allow_fixture_overwrite()
+    ///     .load_fixtures(["base.yaml", "overrides.yaml"])?
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_allow_fixture_overwrite(mut self) -> Self {
+        self.allow_fixture_overwrite = true;
+        self
+    }
+
+    /// Check fixtures as they are loaded rather than when the client is built
+    ///
+    /// Every document loaded with [`load_fixture`](Self::load_fixture) and its
+    /// siblings afterwards must have a name and a kind the client knows, and a
+    /// cluster-scoped object must not name a namespace. With the `validation`
+    /// feature, documents are also validated against the schemas configured so
+    /// far, even when initial objects are added without being admitted. A
+    /// mistake fails the load with [`Error::InvalidConfiguration`] naming the
+    /// file and the document, instead of surfacing as a 404 at the first request.
+    ///
+    /// Register custom resources and schemas before loading the fixtures that
+    /// use them.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_strict_fixtures()
+    ///     .with_fixture_dir("fixtures")
+    ///     .load_fixture("pods.yaml")?
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_strict_fixtures(mut self) -> Self {
+        self.strict_fixtures = true;
+        self
+    }
+
+    /// Choose the order lists return objects in
+    ///
+    /// Defaults to [`ListOrder::Name`]: by namespace, then by name, as a real API
+    /// server lists. [`ListOrder::Insertion`] returns objects in the order they
+    /// were first stored, for tests that index into list results expecting
+    /// creation order.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::{ClientBuilder, ListOrder};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_list_order(ListOrder::Insertion)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_list_order(mut self, order: ListOrder) -> Self {
+        self.list_order = order;
+        self
+    }
+
+    /// Cap the memory the stored objects and the watch history take
+    ///
+    /// Long property-based tests can write without end; with a cap, a write that
+    /// would take the tracker over about `bytes` fails with 500 InternalError,
+    /// `etcdserver: mvcc: database space exceeded`, as the API server does once
+    /// etcd's quota is used up, instead of the test slowly running out of memory.
+    /// Deletes always go through. Sizes are approximate; see
+    /// [`ClientHandle::stats`](crate::ClientHandle::stats) for what is held and
+    /// [`ClientHandle::compact`](crate::ClientHandle::compact) to free the watch
+    /// history between iterations.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new()
+    ///     .with_memory_limit(64 * 1024 * 1024)
+    ///     .build_with_handle()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Keep objects in a store of your own instead of a
+    /// [`MemoryStore`](crate::store::MemoryStore)
+    ///
+    /// The client keeps its API server semantics and hands the objects to the
+    /// store, telling it about every write through
+    /// [`ObjectStore::on_event`]. Objects already in the store are served as
+    /// they are. See the [`store`](crate::store) module for an example.
+    pub fn with_object_store(mut self, store: impl ObjectStore + 'static) -> Self {
+        self.object_store = Some(Arc::new(store));
+        self
+    }
+
     /// Register a custom resource type for discovery
     ///
     /// Custom resources (CRDs) must be explicitly registered.
@@ -156,11 +526,196 @@ impl ClientBuilder {
         self
     }
 
+    /// Register a custom resource with a scale subresource
+    ///
+    /// Like `subresources.scale` of a CustomResourceDefinition, the paths name the
+    /// fields the `Scale` object's `spec.replicas`, `status.replicas` and
+    /// `status.selector` map to, so `Api::get_scale`, `Api::replace_scale`,
+    /// `Api::patch_scale` and HorizontalPodAutoscalers targeting the resource work.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use kube::CustomResource;
+    /// use schemars::JsonSchema;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+    /// #[kube(group = "example.com", version = "v1", kind = "MyApp", plural = "myapps", namespaced)]
+    /// struct MyAppSpec {
+    ///     replicas: i32,
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_resource_scaled::<MyApp>("spec.replicas", "status.replicas", "status.selector")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_resource_scaled<K>(
+        self,
+        spec_replicas_path: &str,
+        status_replicas_path: &str,
+        label_selector_path: &str,
+    ) -> Self
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let gvk = GVK::of::<K>();
+        if self
+            .registry
+            .lookup_by_kind(&gvk.group, &gvk.version, &gvk.kind)
+            .is_none()
+        {
+            self.registry.register::<K>();
+        }
+        self.registry.set_scale(
+            &gvk.group,
+            &gvk.version,
+            &gvk.kind,
+            ScaleSubresource {
+                spec_replicas_path: spec_replicas_path.to_string(),
+                status_replicas_path: status_replicas_path.to_string(),
+                label_selector_path: Some(label_selector_path.to_string())
+                    .filter(|path| !path.is_empty()),
+            },
+        );
+        self
+    }
+
+    /// Register a namespaced resource for use with the dynamic API
+    ///
+    /// Unlike [`with_resource`](Self::with_resource), no Rust type is needed, so
+    /// custom resources only known at runtime can be served to
+    /// `Api::<DynamicObject>`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use kube::api::{Api, ApiResource, DynamicObject, GroupVersionKind};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let gvk = GroupVersionKind::gvk("example.com", "v1", "Widget");
+    /// let resource = ApiResource::from_gvk_with_plural(&gvk, "widgets");
+    /// let client = ClientBuilder::new()
+    ///     .with_dynamic_resource(resource.clone())
+    ///     .build()
+    ///     .await?;
+    /// let widgets: Api<DynamicObject> = Api::namespaced_with(client, "default", &resource);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_dynamic_resource(self, resource: ApiResource) -> Self {
+        self.registry.register_api_resource(&resource, true);
+        self
+    }
+
+    /// Register a cluster-scoped resource for use with the dynamic API
+    pub fn with_cluster_dynamic_resource(self, resource: ApiResource) -> Self {
+        self.registry.register_api_resource(&resource, false);
+        self
+    }
+
+    /// Serve a resource with the given discovery data
+    ///
+    /// Adds a resource the generated discovery data doesn't know, or replaces a
+    /// built-in resource's scope, verbs and subresources, so clusters with
+    /// aggregated APIs such as `metrics.k8s.io` can be emulated. Requests are
+    /// checked against `verbs`, and the resource is listed in `/api` and `/apis`
+    /// discovery with them. A `status` subresource is enabled as with
+    /// [`with_status_subresource`](Self::with_status_subresource).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use kube::api::{Api, ApiResource, DynamicObject, GroupVersionKind};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_api_resource("metrics.k8s.io", "v1beta1", "PodMetrics", "pods", true, &["get", "list"], &[])
+    ///     .build()
+    ///     .await?;
+    ///
+    /// let gvk = GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", "PodMetrics");
+    /// let resource = ApiResource::from_gvk_with_plural(&gvk, "pods");
+    /// let metrics: Api<DynamicObject> = Api::namespaced_with(client, "default", &resource);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_api_resource(
+        mut self,
+        group: &str,
+        version: &str,
+        kind: &str,
+        plural: &str,
+        namespaced: bool,
+        verbs: &[&str],
+        subresources: &[&str],
+    ) -> Self {
+        self.registry.register_metadata(ResourceMetadata {
+            kind: kind.to_string(),
+            group: group.to_string(),
+            version: version.to_string(),
+            plural: plural.to_string(),
+            namespaced,
+            verbs: verbs.iter().map(|verb| verb.to_string()).collect(),
+            subresources: subresources.iter().map(|s| s.to_string()).collect(),
+        });
+        if subresources.contains(&"status") {
+            self.with_status_subresource
+                .push(GVK::new(group, version, kind));
+        }
+        self
+    }
+
+    /// Serve a cluster without a built-in resource
+    ///
+    /// The resource is left out of `/api` and `/apis` discovery and requests for
+    /// it fail with 404 Not Found, as on an older cluster, so version-skew
+    /// handling such as falling back from `discovery.k8s.io/v1` EndpointSlices
+    /// can be tested. Initial objects of the resource are rejected.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .without_builtin_resource("discovery.k8s.io", "v1", "EndpointSlice")
+    ///     .without_builtin_resource("batch", "v1", "CronJob")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn without_builtin_resource(self, group: &str, version: &str, kind: &str) -> Self {
+        self.registry.remove_builtin(group, version, kind);
+        self
+    }
+
     /// Enable status subresource for a specific resource type
     ///
     /// When a status subresource is enabled for a type:
     /// - Regular Update operations will not modify the status field
     /// - Status Update operations will not modify other fields
+    /// - Create operations drop the status of the new object
+    ///
+    /// Built-in kinds whose discovery data lists a status subresource behave the
+    /// same for updates without this, but keep a status given on create. Custom
+    /// resources only get a status subresource when it is enabled here, as for a
+    /// CRD without `subresources.status`: status writes to them fail with 404
+    /// Not Found.
     ///
     /// # Example
     ///
@@ -181,16 +736,39 @@ impl ClientBuilder {
     where
         K: Resource + Serialize + Default,
     {
-        // Get GVK from a default instance
-        let dummy = K::default();
-        let dummy_value = serde_json::to_value(&dummy)
-            .expect("Failed to serialize default object - this should not happen with valid Kubernetes types");
-        let gvk = extract_gvk(&dummy_value)
-            .expect("Failed to extract GVK from resource - ensure apiVersion and kind are set");
+        let Some(gvk) = self.default_gvk::<K>("with_status_subresource") else {
+            return self;
+        };
         self.with_status_subresource.push(gvk);
         self
     }
 
+    /// Declare a subresource of a custom resource, registering the resource if needed
+    ///
+    /// Requests to a declared subresource other than `status` are passed to the
+    /// handler added with [`interceptor::Funcs::subresource`](crate::interceptor::Funcs::subresource).
+    /// Without one, a get returns the parent object and writes fail with 405
+    /// Method Not Allowed. Declaring `status` enables the status subresource.
+    pub fn with_subresource<K>(mut self, name: &str) -> Self
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let gvk = GVK::of::<K>();
+        if self
+            .registry
+            .lookup_by_kind(&gvk.group, &gvk.version, &gvk.kind)
+            .is_none()
+        {
+            self.registry.register::<K>();
+        }
+        self.registry
+            .add_subresource(&gvk.group, &gvk.version, &gvk.kind, name);
+        if name == "status" {
+            self.with_status_subresource.push(gvk);
+        }
+        self
+    }
+
     /// Register an index for field selector support
     ///
     /// Indexes allow efficient filtering using field selectors in List operations.
@@ -224,27 +802,606 @@ impl ClientBuilder {
     where
         K: Resource + Serialize + Default,
     {
-        // Get GVK from a default instance
-        let dummy = K::default();
-        let dummy_value = serde_json::to_value(&dummy)
-            .expect("Failed to serialize default object - this should not happen with valid Kubernetes types");
-        let gvk = extract_gvk(&dummy_value)
-            .expect("Failed to extract GVK from resource - ensure apiVersion and kind are set");
+        let Some(gvk) = self.default_gvk::<K>("with_index") else {
+            return self;
+        };
         let field = field.into();
         self.indexes.entry(gvk).or_default().insert(field, indexer);
         self
     }
 
-    /// Configure whether to return managed fields in responses
+    /// Index a resource by the value of a label
     ///
-    /// By default, managed fields are stripped from responses to simplify testing.
-    /// Enable this to test managed fields behavior.
-    pub fn with_return_managed_fields(mut self) -> Self {
-        self.return_managed_fields = true;
-        self
-    }
-
-    /// Configure interceptor functions to customize client behavior
+    /// Lists whose label selector requires the label to equal (or be `in`) some
+    /// values then read only the matching objects instead of scanning every object
+    /// of the type. Labels are also indexed automatically the first time they are
+    /// selected on; registering them up front moves that cost to build time.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::core::v1::Pod;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_label_index::<Pod>("app")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_label_index<K>(mut self, key: impl Into<String>) -> Self
+    where
+        K: Resource + Serialize + Default,
+    {
+        let Some(gvk) = self.default_gvk::<K>("with_label_index") else {
+            return self;
+        };
+        self.label_indexes.push((gvk, key.into()));
+        self
+    }
+
+    /// Serve gets and lists of a resource from a view that lags behind writes
+    ///
+    /// Simulates a controller reading through an informer cache that hasn't caught
+    /// up yet: a write only shows up in reads of the resource once it is `lag`
+    /// old. Writes are checked against the current objects, so an update based
+    /// on a stale read fails with a conflict as it would on a real cluster.
+    /// Watches are not delayed. The lag is measured with `tokio::time`, so tests
+    /// running with paused time control it with `tokio::time::advance`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_stale_reads::<ConfigMap>(Duration::from_millis(500))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_stale_reads<K>(mut self, lag: Duration) -> Self
+    where
+        K: Resource + Serialize + Default,
+    {
+        let Some(gvk) = self.default_gvk::<K>("with_stale_reads") else {
+            return self;
+        };
+        self.stale_reads.push((gvk, lag));
+        self
+    }
+
+    /// GVK of a type from its default instance, recording a configuration
+    /// error for build() to report if it has none
+    fn default_gvk<K>(&mut self, method: &str) -> Option<GVK>
+    where
+        K: Serialize + Default,
+    {
+        let gvk = serde_json::to_value(K::default())
+            .map_err(|e| e.to_string())
+            .and_then(|value| extract_gvk(&value).map_err(|e| e.to_string()));
+        match gvk {
+            Ok(gvk) => Some(gvk),
+            Err(e) => {
+                self.config_errors.push(format!(
+                    "{method}() could not find the apiVersion and kind of {}: {e}",
+                    std::any::type_name::<K>()
+                ));
+                None
+            }
+        }
+    }
+
+    /// Accept updates and patches whose object names another object than the URL
+    ///
+    /// Like the API server, the fake rejects a PUT whose body, or a PATCH whose
+    /// result, has a `metadata.name` or `metadata.namespace` other than the
+    /// request path's with 400 Bad Request. This restores the old lenient
+    /// behavior for tests written against it.
+    pub fn with_path_mismatch_allowed(mut self) -> Self {
+        self.allow_path_mismatch = true;
+        self
+    }
+
+    /// Reject updates and patches whose object names another object than the
+    /// URL, undoing [`with_path_mismatch_allowed`](Self::with_path_mismatch_allowed)
+    pub fn without_path_mismatch_allowed(mut self) -> Self {
+        self.allow_path_mismatch = false;
+        self
+    }
+
+    /// Skip verb validation, using the fake as a plain object store
+    ///
+    /// By default requests are checked against the verbs a resource supports,
+    /// so creating a `ComponentStatus` fails with 405 Method Not Allowed, as on a
+    /// real API server. With this option any verb is accepted for any kind.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_permissive_verbs()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_permissive_verbs(mut self) -> Self {
+        self.permissive_verbs = PermissiveVerbs::All;
+        self
+    }
+
+    /// Validate the verbs of every kind, undoing
+    /// [`with_permissive_verbs`](Self::with_permissive_verbs) and
+    /// [`with_permissive_verbs_for`](Self::with_permissive_verbs_for)
+    pub fn without_permissive_verbs(mut self) -> Self {
+        self.permissive_verbs = PermissiveVerbs::None;
+        self
+    }
+
+    /// Skip verb validation for one kind
+    ///
+    /// Like [`with_permissive_verbs`](Self::with_permissive_verbs), limited to a
+    /// resource type. Can be called several times.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::core::v1::ComponentStatus;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_permissive_verbs_for::<ComponentStatus>()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_permissive_verbs_for<K>(mut self) -> Self
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        self.permissive_verbs.add(GVK::of::<K>());
+        self
+    }
+
+    /// Fill in defaults for a kind before its objects are stored
+    ///
+    /// Emulates the defaulting the API server applies to built-in types, such as
+    /// a Deployment's rolling update strategy or a Pod's `restartPolicy`, without
+    /// a schema defaulting engine. The function runs on every object of the kind
+    /// that is created, updated or patched, after schema defaulting and before
+    /// validation, so it should only set fields that are missing. Defaulters
+    /// registered for the same kind run in registration order.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::apps::v1::{Deployment, DeploymentStrategy};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_defaulter::<Deployment>(|deployment| {
+    ///         let spec = deployment.spec.get_or_insert_with(Default::default);
+    ///         spec.replicas.get_or_insert(1);
+    ///         spec.strategy.get_or_insert_with(|| DeploymentStrategy {
+    ///             type_: Some("RollingUpdate".to_string()),
+    ///             ..Default::default()
+    ///         });
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_defaulter<K>(mut self, defaulter: impl Fn(&mut K) + Send + Sync + 'static) -> Self
+    where
+        K: Resource<DynamicType = ()> + Serialize + DeserializeOwned,
+    {
+        let gvk = GVK::of::<K>();
+        let defaulter: DefaulterFunc = Arc::new(move |value: &mut Value| {
+            let mut object: K = serde_json::from_value(value.clone())?;
+            defaulter(&mut object);
+            *value = serde_json::to_value(&object)?;
+            Ok(())
+        });
+        self.defaulters.entry(gvk).or_default().push(defaulter);
+        self
+    }
+
+    /// Add a rule that returns warnings with responses
+    ///
+    /// The rule sees every request, with its JSON body if it has one, and may
+    /// return a warning for it. Warnings are sent the way the API server sends
+    /// them, as `Warning: 299 - "<message>"` headers, and recorded in
+    /// [`AuditEntry::warnings`](crate::AuditEntry::warnings) for assertions.
+    /// Rules run in registration order and don't change how the request is
+    /// served. [`warning::label_selectors`](crate::warning::label_selectors) is
+    /// a ready-made rule.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_warning_rule(|request, object| {
+    ///         let unlabeled = object
+    ///             .and_then(|o| o.pointer("/metadata/labels/app"))
+    ///             .is_none();
+    ///         (request.method == "POST" && unlabeled)
+    ///             .then(|| "metadata.labels: objects should have an app label".to_string())
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_warning_rule(
+        mut self,
+        rule: impl Fn(&interceptor::RequestContext, Option<&Value>) -> Option<String>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.warning_rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Add a rule the metadata of every created object must follow
+    ///
+    /// Creates of objects whose name or namespace breaks the rule fail with 422
+    /// Invalid, as with the API server's own name checks. Initial objects are
+    /// checked too, so a fixture that breaks a convention fails the build. Rules
+    /// run in registration order; [`naming::StandardNames`](crate::naming::StandardNames)
+    /// is a ready-made rule with the API server's checks.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    /// use kube_fake_client::{ClientBuilder, GVK};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_metadata_rule(|_: &GVK, metadata: &ObjectMeta| {
+    ///         match metadata.name.as_deref() {
+    ///             Some(name) if !name.starts_with("team-") => Err(format!(
+    ///                 "metadata.name: Invalid value: {name:?}: must start with \"team-\""
+    ///             )),
+    ///             _ => Ok(()),
+    ///         }
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_metadata_rule(mut self, rule: impl MetadataRule + 'static) -> Self {
+        self.metadata_rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Stamp labels onto every object created through the client
+    ///
+    /// Acts like a mutating admission webhook: creates, including server-side
+    /// apply creates, get the labels before metadata rules and validation see
+    /// them, replacing values the object sets for the same keys. Useful to test
+    /// multi-tenancy conventions, or to tag everything a test created so it can
+    /// be listed with a label selector. Initial objects only get them with
+    /// [`InitialObjectMode::Create`]. Calling this again adds to the labels.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    /// use kube::api::{Api, ListParams};
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_injected_labels([("test.example.com/run", "42")])
+    ///     .build()
+    ///     .await?;
+    ///
+    /// // ... run the controller under test with `client` ...
+    ///
+    /// let configmaps: Api<ConfigMap> = Api::all(client);
+    /// let created = configmaps
+    ///     .list(&ListParams::default().labels("test.example.com/run=42"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_injected_labels<K, V>(mut self, labels: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.injected_labels
+            .extend(labels.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Stamp annotations onto every object created through the client
+    ///
+    /// Like [`with_injected_labels`](Self::with_injected_labels), for
+    /// annotations.
+    pub fn with_injected_annotations<K, V>(
+        mut self,
+        annotations: impl IntoIterator<Item = (K, V)>,
+    ) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.injected_annotations
+            .extend(annotations.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Reject requests with unknown or malformed query parameters
+    ///
+    /// By default, like older API servers, query parameters a request doesn't
+    /// take are ignored and values that don't parse fall back to defaults, so a
+    /// label selector with broken syntax silently matches nothing. In strict
+    /// mode such requests fail with 400 Bad Request: a parameter the verb doesn't
+    /// accept, a label or field selector that doesn't parse, a `limit` or
+    /// `timeoutSeconds` that isn't a non-negative integer, or an option outside
+    /// its allowed values.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::api::{Api, ListParams};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_strict_query_params()
+    ///     .build()
+    ///     .await?;
+    ///
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// let err = pods
+    ///     .list(&ListParams::default().labels("app in (web"))
+    ///     .await
+    ///     .unwrap_err();
+    /// assert!(matches!(err, kube::Error::Api(resp) if resp.code == 400));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_strict_query_params(mut self) -> Self {
+        self.strict_query_params = true;
+        self
+    }
+
+    /// Ignore unknown or malformed query parameters, undoing
+    /// [`with_strict_query_params`](Self::with_strict_query_params)
+    pub fn without_strict_query_params(mut self) -> Self {
+        self.strict_query_params = false;
+        self
+    }
+
+    /// Panic on requests the fake can't serve instead of failing them
+    ///
+    /// Some requests are valid against a real API server but beyond what the
+    /// fake implements: subresources without built-in handling such as `log`,
+    /// `eviction` or the `scale` of built-in kinds, paths it can't route,
+    /// protobuf bodies or responses, and watches with `sendInitialEvents`. They
+    /// always fail with 501 Not Implemented and a message starting with
+    /// "not supported by kube-fake-client", rather than returning wrong data or
+    /// leaving a watcher waiting forever. Controllers often retry errors
+    /// quietly, though, so in this mode the request panics instead, failing the
+    /// test at the call that needs a feature the fake lacks.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_panic_on_unsupported()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_panic_on_unsupported(mut self) -> Self {
+        self.panic_on_unsupported = true;
+        self
+    }
+
+    /// Inject random failures and latency into requests
+    ///
+    /// Each request is delayed by up to `latency_jitter` and fails with
+    /// probability `error_rate`, with 500 Internal Error, 429 Too Many Requests
+    /// or, for updates and patches, 409 Conflict. Discovery requests are left
+    /// alone. The faults are drawn from an RNG seeded with `seed`, so with the
+    /// same seed and the same sequence of requests a run fails the same way. If
+    /// a test panics, the seed is printed to stderr; it is also in the message of
+    /// every injected error and available from
+    /// [`ClientHandle::chaos_seed`](crate::ClientHandle::chaos_seed).
+    ///
+    /// # Errors
+    ///
+    /// [`build`](Self::build) returns [`Error::InvalidConfiguration`] if the
+    /// error rate is not between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::{ChaosConfig, ClientBuilder};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_chaos(ChaosConfig {
+    ///         error_rate: 0.05,
+    ///         latency_jitter: Duration::from_millis(10),
+    ///         seed: None,
+    ///     })
+    ///     .build()
+    ///     .await?;
+    ///
+    /// // ... run the controller for a while and check that it converges ...
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_chaos(mut self, config: ChaosConfig) -> Self {
+        self.chaos = Some(config);
+        self
+    }
+
+    /// Save the cluster state to a file after every write
+    ///
+    /// Every object is written to `path` as JSON after each create, update or
+    /// delete, so long fuzz or soak tests can be resumed after the process
+    /// restarts, and the last state of a crashed test can be inspected. If the
+    /// file already exists when the client is built, the cluster starts from the
+    /// objects in it and the initial objects given to the builder are not added.
+    /// The in-memory store without a file stays the default.
+    ///
+    /// The whole state is rewritten on every write, so keep object counts
+    /// moderate. Failures to save after a write are logged, not returned.
+    ///
+    /// # Errors
+    ///
+    /// [`build`](Self::build) returns an error if the file exists but can't be
+    /// read or parsed, or if the initial state can't be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_persistence("target/soak-state.json")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_persistence(mut self, path: impl Into<PathBuf>) -> Self {
+        self.persistence = Some(path.into());
+        self
+    }
+
+    /// Serve the responses recorded in a cassette file instead of faking them
+    ///
+    /// Requests get the recorded response of the first unreplayed interaction
+    /// with the same method, URI and body, recorded against a real cluster with
+    /// a [`Recorder`](crate::cassette::Recorder). A request matching none fails
+    /// with a 500 error describing the drift. Replayed requests bypass the fake
+    /// entirely, including its storage, interceptors and authorization, but are
+    /// still audited. See [`cassette`](crate::cassette) for the workflow.
+    ///
+    /// # Errors
+    ///
+    /// [`build`](Self::build) returns an error if the file can't be read or
+    /// parsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new()
+    ///     .with_replay("tests/cassettes/reconcile.json")
+    ///     .build_with_handle()
+    ///     .await?;
+    ///
+    /// // ... run the reconcile with `client` ...
+    ///
+    /// handle.assert_replayed();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.replay = Some(path.into());
+        self
+    }
+
+    /// Configure whether to return managed fields in responses
+    ///
+    /// By default, managed fields are stripped from responses to simplify testing.
+    /// Enable this to test managed fields behavior: creates, updates, patches and
+    /// server-side applies then record the fields each `fieldManager` set in
+    /// `metadata.managedFields`, with operation `Update` or `Apply`, the
+    /// subresource written, and a `FieldsV1` set. Writes without a field manager
+    /// are recorded under `unknown`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    /// use kube::api::{Api, PostParams};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_return_managed_fields()
+    ///     .build()
+    ///     .await?;
+    ///
+    /// let mut cm = ConfigMap::default();
+    /// cm.metadata.name = Some("settings".to_string());
+    /// cm.data = Some([("mode".to_string(), "fast".to_string())].into());
+    /// let params = PostParams {
+    ///     field_manager: Some("my-controller".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let cm = Api::<ConfigMap>::default_namespaced(client)
+    ///     .create(&params, &cm)
+    ///     .await?;
+    /// let managed = cm.metadata.managed_fields.unwrap_or_default();
+    /// assert_eq!(managed[0].manager.as_deref(), Some("my-controller"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_return_managed_fields(mut self) -> Self {
+        self.return_managed_fields = true;
+        self
+    }
+
+    /// Leave `metadata.managedFields` out of responses, undoing
+    /// [`with_return_managed_fields`](Self::with_return_managed_fields)
+    pub fn without_return_managed_fields(mut self) -> Self {
+        self.return_managed_fields = false;
+        self
+    }
+
+    /// Configure interceptor functions to customize client behavior
     ///
     /// Interceptors allow you to inject errors, implement custom logic, or track actions
     /// during tests. Each interceptor function can:
@@ -275,8 +1432,450 @@ impl ClientBuilder {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn with_interceptor_funcs(mut self, interceptors: interceptor::Funcs) -> Self {
-        self.interceptors = Some(interceptors);
+    pub fn with_interceptor_funcs(mut self, interceptors: interceptor::Funcs) -> Self {
+        self.interceptors = Some(interceptors);
+        self
+    }
+
+    /// Start from a [`TestEnv`] instead of the installed one
+    ///
+    /// The environment's interceptors and warning rules run before the
+    /// client's own.
+    pub fn with_test_env(mut self, env: TestEnv) -> Self {
+        self.test_env = Some(env);
+        self
+    }
+
+    /// Ignore the installed [`TestEnv`]
+    ///
+    /// For the odd test that has to break an invariant the rest of the suite
+    /// enforces.
+    pub fn without_test_env(mut self) -> Self {
+        self.test_env = None;
+        self
+    }
+
+    /// Wrap the fake API server in a tower layer
+    ///
+    /// Requests from the client pass through the layer before reaching the fake,
+    /// so standard tower middleware for tracing, metrics, timeouts or custom
+    /// fault injection works unchanged. Layers added first are outermost, as with
+    /// `tower::ServiceBuilder`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use tower::ServiceBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let requests = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&requests);
+    /// let client = ClientBuilder::new()
+    ///     .with_layer(tower::layer::layer_fn(move |service| {
+    ///         let counter = Arc::clone(&counter);
+    ///         ServiceBuilder::new()
+    ///             .map_request(move |request| {
+    ///                 counter.fetch_add(1, Ordering::SeqCst);
+    ///                 request
+    ///             })
+    ///             .service(service)
+    ///     }))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_layer<L, B>(mut self, layer: L) -> Self
+    where
+        L: Layer<LayeredService> + Send + 'static,
+        L::Service: Service<Request<kube::client::Body>, Response = Response<B>> + Send + 'static,
+        <L::Service as Service<Request<kube::client::Body>>>::Future: Send + 'static,
+        <L::Service as Service<Request<kube::client::Body>>>::Error: Into<BoxError>,
+        B: http_body::Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        self.layers.push(Box::new(move |service| {
+            let service = layer
+                .layer(service)
+                .map_response(|response: Response<B>| {
+                    response.map(|body| UnsyncBoxBody::new(body.map_err(Into::into)))
+                })
+                .map_err(Into::into);
+            BoxService::new(service)
+        }));
+        self
+    }
+
+    /// Set the CIDR that Service cluster IPs are allocated from
+    ///
+    /// Services created without `spec.clusterIP` are assigned the next free address
+    /// in this range, and explicitly requested IPs must fall inside it. Defaults to
+    /// `10.96.0.0/12`. Only IPv4 CIDRs are supported.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_service_cidr("10.0.0.0/24")?
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CIDR cannot be parsed.
+    pub fn with_service_cidr(mut self, cidr: &str) -> Result<Self> {
+        self.service_cidr = ServiceCidr::parse(cidr)?;
+        Ok(self)
+    }
+
+    /// Simulate the endpoints and endpointslice controllers
+    ///
+    /// Whenever a Service or Pod is written, the Endpoints and EndpointSlices of the
+    /// Services in that namespace are reconciled from the Pods their selectors match.
+    /// Pods need a `status.podIP` to be included, and are reported ready when their
+    /// `Ready` condition is `True`. Services without a selector are left untouched.
+    ///
+    /// EndpointSlices are named `<service>-<n>` and labeled with
+    /// `kubernetes.io/service-name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_endpoints_controller()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_endpoints_controller(mut self) -> Self {
+        self.endpoints_controller = true;
+        self
+    }
+
+    /// Simulate the volume claim handling of the StatefulSet controller
+    ///
+    /// Whenever a StatefulSet is written, a PersistentVolumeClaim is created for each
+    /// of its `volumeClaimTemplates` and ordinals up to `spec.replicas`, named
+    /// `<template>-<statefulset>-<ordinal>`. Claims start out `Pending`; use
+    /// [`ClientHandle::bind_pvc`] to bind them. Claims are kept on scale-down.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_statefulset_controller()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_statefulset_controller(mut self) -> Self {
+        self.statefulset_controller = true;
+        self
+    }
+
+    /// Simulate the garbage collector and honor the propagation policy of deletes
+    ///
+    /// Deletes with `propagationPolicy: Orphan` or `Foreground` leave the object
+    /// in deletion under the `orphan` or `foregroundDeletion` finalizer while its
+    /// dependents, the objects with an owner reference to it, are orphaned or
+    /// deleted, and remove it afterwards. Background deletes, the default, remove
+    /// the object at once and its dependents after it. Every step is a write of its
+    /// own that watches see, and a dependent with `blockOwnerDeletion: true` held
+    /// by a finalizer keeps a foreground-deleted owner in deletion until the
+    /// finalizer is removed.
+    ///
+    /// Like the real garbage collector, it deletes any object whose owners are
+    /// all gone, including initial objects referencing owners that were never
+    /// created, once the first write after the build runs it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    /// use kube::api::{Api, DeleteParams};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_garbage_collector()
+    ///     .build()
+    ///     .await?;
+    /// let deployments: Api<Deployment> = Api::namespaced(client, "default");
+    /// // ... create a Deployment and the ReplicaSets it owns ...
+    /// deployments.delete("web", &DeleteParams::foreground()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_garbage_collector(mut self) -> Self {
+        self.garbage_collector = true;
+        self
+    }
+
+    /// Simulate the ServiceAccount admission plugin adding image pull secrets
+    ///
+    /// Pods created without `spec.imagePullSecrets` get those of the
+    /// ServiceAccount they run as, `spec.serviceAccountName` or `default`, if it
+    /// exists in the Pod's namespace. Pods listing image pull secrets of their
+    /// own keep them, and later changes to the ServiceAccount don't reach
+    /// existing Pods.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::core::v1::{LocalObjectReference, ServiceAccount};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut account = ServiceAccount::default();
+    /// account.metadata.name = Some("default".to_string());
+    /// account.metadata.namespace = Some("default".to_string());
+    /// account.image_pull_secrets = Some(vec![LocalObjectReference {
+    ///     name: "registry-credentials".to_string(),
+    /// }]);
+    /// let client = ClientBuilder::new()
+    ///     .with_service_account_image_pull_secrets()
+    ///     .with_object(account)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_service_account_image_pull_secrets(mut self) -> Self {
+        self.service_account_pull_secrets = true;
+        self
+    }
+
+    /// Simulate a controller that keeps up with every spec change of a kind
+    ///
+    /// Whenever an object of kind `K` is written and its `status.observedGeneration`
+    /// differs from `metadata.generation`, a status update sets it to the current
+    /// generation, as if the kind's controller had reconciled the change. Use it for
+    /// the dependencies an operator waits on, such as Deployments it creates, and
+    /// check the operator's own objects with
+    /// [`testing::assert_observed_generation_current`](crate::testing::assert_observed_generation_current).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_observed_generation_for::<Deployment>()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_observed_generation_for<K>(mut self) -> Self
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let gvk = GVK::of::<K>();
+        if !self.observed_generation.contains(&gvk) {
+            self.observed_generation.push(gvk);
+        }
+        self
+    }
+
+    /// Decide access reviews with a policy callback
+    ///
+    /// Creating a `SubjectAccessReview`, `SelfSubjectAccessReview` or
+    /// `LocalSubjectAccessReview` returns the review with `status.allowed` set to the
+    /// handler's answer; reviews are not stored. Without a handler reviews are decided
+    /// by RBAC when [`with_identity`](Self::with_identity) is set, and allowed
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_access_review_handler(|spec| {
+    ///         spec.resource_attributes
+    ///             .as_ref()
+    ///             .is_some_and(|attrs| attrs.verb.as_deref() != Some("delete"))
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_access_review_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&SubjectAccessReviewSpec) -> bool + Send + Sync + 'static,
+    {
+        self.access_review_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Make requests as a user, enforcing RBAC
+    ///
+    /// Every request through the client is authorized against the Roles,
+    /// ClusterRoles, RoleBindings and ClusterRoleBindings in the fake cluster, and
+    /// fails with a 403 Forbidden error like the API server's when no bound rule
+    /// allows it. Members of `system:masters` are allowed everything. Seed the RBAC
+    /// objects with [`with_objects`](Self::with_objects), or create them with a
+    /// client that has no identity, since initial objects are not authorized.
+    ///
+    /// Service accounts are identified as `system:serviceaccount:<namespace>:<name>`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::rbac::v1::{PolicyRule, Role, RoleBinding, RoleRef, Subject};
+    /// use kube::api::ObjectMeta;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let role = Role {
+    ///     metadata: ObjectMeta {
+    ///         name: Some("pod-reader".to_string()),
+    ///         namespace: Some("team-a".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     rules: Some(vec![PolicyRule {
+    ///         api_groups: Some(vec!["".to_string()]),
+    ///         resources: Some(vec!["pods".to_string()]),
+    ///         verbs: vec!["get".to_string(), "list".to_string()],
+    ///         ..Default::default()
+    ///     }]),
+    /// };
+    /// let binding = RoleBinding {
+    ///     metadata: ObjectMeta {
+    ///         name: Some("alice-reads-pods".to_string()),
+    ///         namespace: Some("team-a".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     role_ref: RoleRef {
+    ///         api_group: "rbac.authorization.k8s.io".to_string(),
+    ///         kind: "Role".to_string(),
+    ///         name: "pod-reader".to_string(),
+    ///     },
+    ///     subjects: Some(vec![Subject {
+    ///         kind: "User".to_string(),
+    ///         name: "alice".to_string(),
+    ///         ..Default::default()
+    ///     }]),
+    /// };
+    ///
+    /// let client = ClientBuilder::new()
+    ///     .with_object(role)
+    ///     .with_object(binding)
+    ///     .with_identity("alice", ["developers"])
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_identity<I, G>(mut self, user: impl Into<String>, groups: I) -> Self
+    where
+        I: IntoIterator<Item = G>,
+        G: Into<String>,
+    {
+        let groups = groups.into_iter().map(Into::into).collect();
+        self.identity = Some(Identity::new(user.into(), groups));
+        self
+    }
+
+    /// Authenticate token reviews with a callback
+    ///
+    /// Creating a `TokenReview` returns it with its status filled in from the
+    /// handler: a returned user marks the token authenticated, `None` rejects it.
+    /// Without a handler every token is rejected. Reviews are not stored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::authentication::v1::UserInfo;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_token_review_handler(|spec| {
+    ///         (spec.token.as_deref() == Some("valid-token")).then(|| UserInfo {
+    ///             username: Some("system:serviceaccount:default:app".to_string()),
+    ///             ..Default::default()
+    ///         })
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_token_review_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&TokenReviewSpec) -> Option<UserInfo> + Send + Sync + 'static,
+    {
+        self.token_review_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Issue certificates for approved CertificateSigningRequests
+    ///
+    /// Stands in for a signing controller: once a request is approved through its
+    /// `approval` subresource, the signer is called and its PEM output stored in
+    /// `status.certificate`. Returning an error adds a `Failed` condition instead.
+    /// Without a signer, approved requests are left without a certificate.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_csr_signer(|csr| {
+    ///         if csr.spec.signer_name == "example.com/signer" {
+    ///             Ok(b"-----BEGIN CERTIFICATE-----\n...".to_vec())
+    ///         } else {
+    ///             Err("unknown signer".to_string())
+    ///         }
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_csr_signer<F>(mut self, signer: F) -> Self
+    where
+        F: Fn(&CertificateSigningRequest) -> std::result::Result<Vec<u8>, String>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.csr_signer = Some(Arc::new(signer));
         self
     }
 
@@ -319,7 +1918,81 @@ impl ClientBuilder {
     /// Returns an error if the OpenAPI file cannot be read or parsed.
     pub fn with_schema_validation_file(mut self, openapi_file: impl AsRef<Path>) -> Result<Self> {
         let validator = RuntimeOpenAPIValidator::from_file(openapi_file)?;
-        self.runtime_validator = Some(Arc::new(validator));
+        self.runtime_validator = Some(validator);
+        Ok(self)
+    }
+
+    /// Validate custom resources against the schema of their CRD
+    ///
+    /// **Note:** This method is only available when the `validation` feature is enabled.
+    #[cfg(feature = "validation")]
+    ///
+    /// Compiles the `openAPIV3Schema` of every version of a
+    /// CustomResourceDefinition and enables validation for them, so tests check
+    /// objects against the exact CRDs an operator ships, without generating an
+    /// OpenAPI file. Like with [`with_validation_for`](Self::with_validation_for),
+    /// fields the schema doesn't list are pruned, and
+    /// [`with_schema_defaulting`](Self::with_schema_defaulting) applies its
+    /// defaults. The resource itself still has to be registered, with
+    /// [`with_resource`](Self::with_resource) or
+    /// [`with_dynamic_resource`](Self::with_dynamic_resource).
+    ///
+    /// `crd` is a `CustomResourceDefinition`, such as the one `CustomResource::crd()`
+    /// generates, its JSON, or its YAML as a string. YAML may hold several CRDs,
+    /// one per document. Can be combined with
+    /// [`with_schema_validation_file`](Self::with_schema_validation_file).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// # use kube::CustomResource;
+    /// # use schemars::JsonSchema;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+    /// # #[kube(group = "example.com", version = "v1", kind = "MyApp", namespaced)]
+    /// # struct MyAppSpec { replicas: i32 }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_resource::<MyApp>()
+    ///     .with_crd_schema(std::fs::read_to_string("deploy/crds/myapp.yaml")?)?
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`] if `crd` isn't valid YAML or JSON,
+    /// isn't a CustomResourceDefinition, or has no version with a schema.
+    pub fn with_crd_schema(mut self, crd: impl Serialize) -> Result<Self> {
+        let crd = serde_json::to_value(&crd)?;
+        let crds = match crd {
+            Value::String(yaml) => {
+                use serde::Deserialize;
+                let mut crds = Vec::new();
+                for document in serde_yaml::Deserializer::from_str(&yaml) {
+                    let crd = Value::deserialize(document).map_err(|e| {
+                        Error::InvalidConfiguration(format!("Failed to parse CRD YAML: {e}"))
+                    })?;
+                    // Empty documents, such as after a trailing `---`
+                    if !crd.is_null() {
+                        crds.push(crd);
+                    }
+                }
+                crds
+            }
+            crd => vec![crd],
+        };
+        // Report mistakes here rather than at build time
+        let mut check = RuntimeOpenAPIValidator::new();
+        for crd in &crds {
+            check.add_crd(crd)?;
+        }
+        self.crd_schemas.extend(crds);
         Ok(self)
     }
 
@@ -329,7 +2002,10 @@ impl ClientBuilder {
     #[cfg(feature = "validation")]
     ///
     /// Must be called after `with_schema_validation_file()`. Each call enables
-    /// validation for one GVK (group/version/kind).
+    /// validation for one GVK (group/version/kind). For custom resources, fields
+    /// the schema doesn't list are also pruned from written objects, like the
+    /// API server does for CRDs with structural schemas, except below
+    /// `x-kubernetes-preserve-unknown-fields: true`.
     ///
     /// # Arguments
     /// * `gvk` - GVK in format "group/version/Kind"
@@ -363,7 +2039,7 @@ impl ClientBuilder {
     /// - No OpenAPI definition exists for the GVK
     pub fn with_validation_for(self, gvk: &str) -> Result<Self> {
         let validator = self.runtime_validator.as_ref().ok_or_else(|| {
-            Error::Internal(
+            Error::InvalidConfiguration(
                 "Call with_schema_validation_file() before with_validation_for()".to_string(),
             )
         })?;
@@ -372,6 +2048,74 @@ impl ClientBuilder {
         Ok(self)
     }
 
+    /// Compile validation schemas when the client is built
+    ///
+    /// **Note:** This method is only available when the `validation` feature is enabled.
+    #[cfg(feature = "validation")]
+    ///
+    /// Schemas are otherwise compiled on the first validation of each kind.
+    /// Compilation is cached for the whole test process, keyed by the contents of
+    /// the schema file and CRDs, so only the first client to use a schema pays for
+    /// it; precompiling moves that cost out of the first request, compiling the
+    /// schemas of every kind with validation enabled in parallel.
+    /// [`Precompile::Blocking`] compiles before `build()` returns and fails it if a
+    /// schema doesn't compile; [`Precompile::Background`] compiles on another
+    /// thread while the test starts.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::validator::Precompile;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_schema_validation_file("swagger.json")?
+    ///     .with_validation_for("apps/v1/Deployment")?
+    ///     .with_validation_for("/v1/Service")?
+    ///     .with_precompiled_schemas(Precompile::Background)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_precompiled_schemas(mut self, mode: Precompile) -> Self {
+        self.precompile = Some(mode);
+        self
+    }
+
+    /// Apply schema defaults to created and updated objects
+    ///
+    /// **Note:** This method is only available when the `validation` feature is enabled.
+    #[cfg(feature = "validation")]
+    ///
+    /// Fields missing from an object are filled in from the `default:` stanzas of its
+    /// schema (including nested objects and array items) before validation runs, so
+    /// objects read back resemble what a real API server would store. Defaulting applies
+    /// to the resources enabled with `with_validation_for()` or `with_crd_schema()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_schema_validation_file("crds.json")?
+    ///     .with_validation_for("example.com/v1/MyApp")?
+    ///     .with_schema_defaulting()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_schema_defaulting(mut self) -> Self {
+        self.schema_defaulting = true;
+        self
+    }
+
     /// Set the fixture directory for loading YAML fixtures
     ///
     /// This directory will be used as the base path for `load_fixture` calls.
@@ -409,7 +2153,7 @@ impl ClientBuilder {
     /// Returns an error if:
     /// - The file cannot be read
     /// - The YAML cannot be parsed
-    /// - The objects are invalid
+    /// - A document has no `apiVersion` or `kind`
     ///
     /// # Example
     ///
@@ -427,10 +2171,52 @@ impl ClientBuilder {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn load_fixture(mut self, path: impl AsRef<Path>) -> Result<Self> {
+    pub fn load_fixture(self, path: impl AsRef<Path>) -> Result<Self> {
+        self.load_fixture_with_namespace(path.as_ref(), None)
+    }
+
+    /// Load objects from a YAML fixture file into a namespace
+    ///
+    /// Like [`load_fixture`](Self::load_fixture), with every namespaced object
+    /// placed in `namespace`, whatever namespace the YAML gives it, so one
+    /// fixture file can seed the namespace of each test. Cluster-scoped objects
+    /// stay cluster-scoped, and only `metadata.namespace` is changed: namespaces
+    /// named elsewhere, such as in RoleBinding subjects, are left as written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the namespace is empty, or for the same reasons as
+    /// [`load_fixture`](Self::load_fixture).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_fixture_dir("fixtures")
+    ///     .load_fixture_into("test-upgrade", "app.yaml")?
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_fixture_into(self, namespace: &str, path: impl AsRef<Path>) -> Result<Self> {
+        if namespace.is_empty() {
+            return Err(Error::InvalidConfiguration(
+                "fixtures can't be loaded into an empty namespace".to_string(),
+            ));
+        }
+        self.load_fixture_with_namespace(path.as_ref(), Some(namespace))
+    }
+
+    /// Load a fixture file, placing its objects in `namespace` if given
+    fn load_fixture_with_namespace(mut self, path: &Path, namespace: Option<&str>) -> Result<Self> {
         let fixture_path = match &self.fixture_dir {
             Some(dir) => dir.join(path),
-            None => path.as_ref().to_path_buf(),
+            None => path.to_path_buf(),
         };
 
         let content = std::fs::read_to_string(&fixture_path).map_err(|e| {
@@ -440,11 +2226,63 @@ impl ClientBuilder {
             ))
         })?;
 
+        #[cfg(feature = "validation")]
+        let crd_validator = match self.strict_fixtures && !self.crd_schemas.is_empty() {
+            true => {
+                let mut validator = RuntimeOpenAPIValidator::new();
+                for crd in &self.crd_schemas {
+                    validator.add_crd(crd)?;
+                }
+                Some(validator)
+            }
+            false => None,
+        };
+
         use serde::Deserialize;
-        for document in serde_yaml::Deserializer::from_str(&content) {
+        for (index, document) in serde_yaml::Deserializer::from_str(&content).enumerate() {
             let mut value = Value::deserialize(document).map_err(|e| {
                 Error::Internal(format!("Failed to parse YAML in {:?}: {}", fixture_path, e))
             })?;
+            // Empty documents, such as after a trailing `---`
+            if value.is_null() {
+                continue;
+            }
+            for field in ["apiVersion", "kind"] {
+                if !value.get(field).is_some_and(Value::is_string) {
+                    return Err(Error::InvalidConfiguration(format!(
+                        "document {} in fixture {:?} has no {field}",
+                        index + 1,
+                        fixture_path
+                    )));
+                }
+            }
+
+            if self.strict_fixtures {
+                let check = self.check_fixture(&value);
+                #[cfg(feature = "validation")]
+                let check = check.and_then(|gvk| {
+                    let validators = self.runtime_validator.iter().chain(&crd_validator);
+                    for validator in validators {
+                        let mut object = value.clone();
+                        if self.schema_defaulting {
+                            validator
+                                .apply_defaults(&gvk.group, &gvk.version, &gvk.kind, &mut object)
+                                .map_err(|e| e.to_string())?;
+                        }
+                        validator
+                            .validate(&gvk.group, &gvk.version, &gvk.kind, &object)
+                            .map_err(|e| e.to_string())?;
+                    }
+                    Ok(gvk)
+                });
+                check.map_err(|problem| {
+                    Error::InvalidConfiguration(format!(
+                        "document {} in fixture {:?}: {problem}",
+                        index + 1,
+                        fixture_path
+                    ))
+                })?;
+            }
 
             // Set default metadata if not present
             if let Some(metadata) = value.get_mut("metadata").and_then(|m| m.as_object_mut()) {
@@ -452,12 +2290,17 @@ impl ClientBuilder {
                 if !metadata.contains_key("creationTimestamp") {
                     metadata.insert(
                         "creationTimestamp".to_string(),
-                        serde_json::to_value(chrono::Utc::now().to_rfc3339()).unwrap(),
+                        serde_json::to_value(crate::clock::now().to_rfc3339()).unwrap(),
                     );
                 }
 
-                // Set namespace to default if not specified
-                if !metadata.contains_key("namespace") {
+                if let Some(namespace) = namespace {
+                    metadata.insert(
+                        "namespace".to_string(),
+                        Value::String(namespace.to_string()),
+                    );
+                } else if !metadata.contains_key("namespace") {
+                    // Set namespace to default if not specified
                     metadata.insert(
                         "namespace".to_string(),
                         Value::String("default".to_string()),
@@ -471,6 +2314,33 @@ impl ClientBuilder {
         Ok(self)
     }
 
+    /// Check a fixture document against discovery, returning its kind
+    fn check_fixture(&self, value: &Value) -> std::result::Result<GVK, String> {
+        let gvk = extract_gvk(value).map_err(|e| e.to_string())?;
+        let Some(namespaced) = Discovery::is_namespaced_with_registry(&gvk, &self.registry) else {
+            return Err(format!(
+                "unknown kind {}; register the resource with with_resource() or \
+                 with_dynamic_resource() before loading fixtures",
+                describe_gvk(&gvk)
+            ));
+        };
+        let metadata = value.get("metadata");
+        let Some(name) = metadata.and_then(|m| m.get("name")).and_then(Value::as_str) else {
+            return Err(format!("{} has no metadata.name", describe_gvk(&gvk)));
+        };
+        let namespace = metadata
+            .and_then(|m| m.get("namespace"))
+            .and_then(Value::as_str)
+            .filter(|namespace| !namespace.is_empty());
+        if let (false, Some(namespace)) = (namespaced, namespace) {
+            return Err(format!(
+                "{} {name:?} is cluster-scoped but has namespace {namespace:?}",
+                describe_gvk(&gvk)
+            ));
+        }
+        Ok(gvk)
+    }
+
     /// Load objects from multiple YAML fixture files
     ///
     /// Loads all specified fixture files in order. Each file can contain single or
@@ -505,6 +2375,45 @@ impl ClientBuilder {
         Ok(self)
     }
 
+    /// Load objects from multiple YAML fixture files into a namespace
+    ///
+    /// Like [`load_fixtures`](Self::load_fixtures), placing the objects the way
+    /// [`load_fixture_into`](Self::load_fixture_into) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the namespace is empty, or if any file cannot be read
+    /// or parsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::new()
+    ///     .with_fixture_dir("fixtures")
+    ///     .load_fixtures_into("test-upgrade", ["app.yaml", "config.yaml"])?
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_fixtures_into<P>(
+        mut self,
+        namespace: &str,
+        paths: impl IntoIterator<Item = P>,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        for path in paths {
+            self = self.load_fixture_into(namespace, path)?;
+        }
+        Ok(self)
+    }
+
     /// Load objects from a YAML fixture file, panicking on error
     ///
     /// This is a convenience method that calls `load_fixture` and panics if it fails.
@@ -590,14 +2499,138 @@ impl ClientBuilder {
     ///
     /// # Errors
     ///
-    /// Returns an error if any initial objects fail to be created.
+    /// Returns [`Error::InvalidConfiguration`] if an initial object has no
+    /// `apiVersion`, `kind` or name, has a kind that isn't a known resource, or is
+    /// added more than once or is rejected when it is added, if a status
+    /// subresource is registered for an unknown kind, or if a builder method
+    /// such as [`with_index`](Self::with_index) was given a type whose
+    /// `apiVersion` and `kind` can't be read from its default instance.
     pub async fn build(self) -> Result<kube::Client> {
+        self.build_with_handle().await.map(|(client, _)| client)
+    }
+
+    /// Build a standard kube::Client together with a [`ClientHandle`]
+    ///
+    /// The handle shares storage with the returned client and can be used to
+    /// inspect or manipulate the fake cluster state directly from tests.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    /// let leftovers = handle.list_namespace("default")?;
+    /// assert!(leftovers.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`] if an initial object has no
+    /// `apiVersion`, `kind` or name, has a kind that isn't a known resource, or is
+    /// added more than once or is rejected when it is added, if a status
+    /// subresource is registered for an unknown kind, or if a builder method
+    /// such as [`with_index`](Self::with_index) was given a type whose
+    /// `apiVersion` and `kind` can't be read from its default instance.
+    pub async fn build_with_handle(mut self) -> Result<(kube::Client, ClientHandle)> {
+        let layers = std::mem::take(&mut self.layers);
+        let (service, handle) = self.build_parts()?;
+
+        // Create a kube::Client using the mock service, wrapped in any layers
+        let kube_client = if layers.is_empty() {
+            kube::Client::new(service, "default")
+        } else {
+            let mut service = BoxService::new(service.map_response(|response| {
+                response.map(|body| UnsyncBoxBody::new(body.map_err(|never| match never {})))
+            }));
+            for layer in layers.into_iter().rev() {
+                service = layer(service);
+            }
+            kube::Client::new(service, "default")
+        };
+
+        Ok((kube_client, handle))
+    }
+
+    /// Build the [`MockService`] a client would send its requests to, together
+    /// with a [`ClientHandle`]
+    ///
+    /// A `kube::Client` runs its request buffer on a tokio task, so it can only
+    /// be used on a tokio runtime. The service itself is a `tower::Service` that
+    /// runs on any executor: controller logic built for constrained targets, or
+    /// for another async runtime, can send it the requests it would send the API
+    /// server.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use tower::{Service, ServiceExt};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// futures::executor::block_on(async {
+    ///     let (mut service, handle) = ClientBuilder::new().build_service().await?;
+    ///     let request = http::Request::get("/api/v1/namespaces/default/configmaps")
+    ///         .body(kube::client::Body::empty())?;
+    ///     let response = service.ready().await?.call(request).await?;
+    ///     assert!(response.status().is_success());
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`build_with_handle`](Self::build_with_handle), and with
+    /// [`Error::InvalidConfiguration`] if layers were added with
+    /// [`with_layer`](Self::with_layer), which wrap the `kube::Client`.
+    pub async fn build_service(self) -> Result<(MockService, ClientHandle)> {
+        if !self.layers.is_empty() {
+            return Err(Error::InvalidConfiguration(
+                "with_layer() wraps the kube::Client, which build_service() doesn't build"
+                    .to_string(),
+            ));
+        }
+        self.build_parts()
+    }
+
+    fn build_parts(self) -> Result<(MockService, ClientHandle)> {
         // Only runtime validation is available (when validation feature is enabled)
         let validator: Option<Arc<dyn SchemaValidator>> = {
             #[cfg(feature = "validation")]
             {
-                self.runtime_validator
-                    .map(|v| v as Arc<dyn SchemaValidator>)
+                let mut runtime_validator = self.runtime_validator;
+                if !self.crd_schemas.is_empty() {
+                    let validator =
+                        runtime_validator.get_or_insert_with(RuntimeOpenAPIValidator::new);
+                    for crd in &self.crd_schemas {
+                        validator.add_crd(crd)?;
+                    }
+                }
+                let runtime_validator = runtime_validator.map(Arc::new);
+                match (&runtime_validator, self.precompile) {
+                    (Some(validator), Some(Precompile::Blocking)) => validator.precompile()?,
+                    (Some(validator), Some(Precompile::Background)) => {
+                        let validator = Arc::clone(validator);
+                        std::thread::spawn(move || {
+                            if let Err(e) = validator.precompile() {
+                                tracing::warn!("Failed to precompile schemas: {}", e);
+                            }
+                        });
+                    }
+                    (None, Some(_)) => {
+                        return Err(Error::InvalidConfiguration(
+                            "Call with_schema_validation_file() or with_crd_schema() before precompiling schemas"
+                                .to_string(),
+                        ))
+                    }
+                    (_, None) => {}
+                }
+                runtime_validator.map(|v| v as Arc<dyn SchemaValidator>)
             }
             #[cfg(not(feature = "validation"))]
             {
@@ -605,41 +2638,206 @@ impl ClientBuilder {
             }
         };
 
+        #[cfg(feature = "validation")]
+        if self.schema_defaulting && validator.is_none() {
+            return Err(Error::InvalidConfiguration(
+                "Call with_schema_validation_file() or with_crd_schema() before enabling schema defaulting".to_string(),
+            ));
+        }
+        #[cfg(feature = "validation")]
+        let schema_defaulting = self.schema_defaulting;
+        #[cfg(not(feature = "validation"))]
+        let schema_defaulting = false;
+
+        // Check the configuration up front, so mistakes are reported with context
+        // instead of surfacing as confusing errors at request time
+        if !self.config_errors.is_empty() {
+            return Err(Error::InvalidConfiguration(self.config_errors.join("; ")));
+        }
+        for gvk in &self.with_status_subresource {
+            if gvk_to_gvr(gvk, &self.registry).is_err() {
+                return Err(Error::InvalidConfiguration(format!(
+                    "status subresource registered for unknown kind {}; register the resource with with_resource() or with_dynamic_resource()",
+                    describe_gvk(gvk)
+                )));
+            }
+        }
+        let seeds = resolve_initial_objects(
+            self.initial_objects,
+            &self.registry,
+            self.allow_fixture_overwrite,
+        )?;
+        let chaos = self.chaos.map(Chaos::new).transpose()?.map(Arc::new);
+        let (interceptors, warning_rules) = match &self.test_env {
+            Some(env) => (
+                env.interceptors(self.interceptors),
+                env.warning_rules(self.warning_rules),
+            ),
+            None => (self.interceptors, self.warning_rules),
+        };
+
         let fake_client = FakeClient {
-            tracker: Arc::new(crate::tracker::ObjectTracker::new()),
-            indexes: Arc::new(std::sync::RwLock::new(self.indexes)),
+            tracker: Arc::new(match self.object_store {
+                Some(store) => crate::tracker::ObjectTracker::with_store(store),
+                None => crate::tracker::ObjectTracker::new(),
+            }),
+            indexes: Arc::new(parking_lot::RwLock::new(self.indexes)),
             return_managed_fields: self.return_managed_fields,
-            interceptors: self.interceptors.map(Arc::new),
+            interceptors: interceptors.map(Arc::new),
             registry: Arc::new(self.registry),
             validator,
+            schema_defaulting,
+            service_cidr: self.service_cidr,
+            service_allocations: Arc::default(),
+            access_review_handler: self.access_review_handler,
+            token_review_handler: self.token_review_handler,
+            identity: self.identity,
+            audit: Arc::default(),
+            metrics: Arc::default(),
+            stale_reads: Arc::default(),
+            allow_path_mismatch: self.allow_path_mismatch,
+            permissive_verbs: self.permissive_verbs,
+            strict_query_params: self.strict_query_params,
+            panic_on_unsupported: self.panic_on_unsupported,
+            defaulters: Arc::new(self.defaulters),
+            chaos,
+            persistence: self
+                .persistence
+                .map(|path| Arc::new(Persistence::new(path))),
+            warning_rules: Arc::new(warning_rules),
+            metadata_rules: Arc::new(self.metadata_rules),
+            injected_labels: Arc::new(self.injected_labels),
+            injected_annotations: Arc::new(self.injected_annotations),
+            garbage_collector: self.garbage_collector,
+            service_account_pull_secrets: self.service_account_pull_secrets,
+            replay: self.replay.map(Replay::load).transpose()?.map(Arc::new),
         };
 
+        fake_client.tracker.set_list_order(self.list_order);
+        fake_client
+            .tracker
+            .add_removal_observer(fake_client.service_allocations.removal_observer());
+        if let Some(bytes) = self.memory_limit {
+            fake_client.tracker.set_memory_limit(bytes);
+        }
+
         // Enable status subresources
         for gvk in self.with_status_subresource {
             fake_client.tracker.add_status_subresource(gvk);
         }
 
-        // Add initial objects (using add() not create() to match Go's behavior)
-        // This sets ResourceVersion to "999" instead of "1"
-        for obj in self.initial_objects {
-            let gvk = extract_gvk(&obj)?;
+        if self.endpoints_controller {
+            fake_client
+                .tracker
+                .add_observer(crate::endpoints::observer());
+        }
+        if let Some(signer) = self.csr_signer {
+            fake_client
+                .tracker
+                .add_observer(crate::csr::signer_observer(signer));
+        }
+        if self.statefulset_controller {
+            fake_client
+                .tracker
+                .add_observer(crate::statefulset::observer());
+        }
+        for gvk in self.observed_generation {
             let gvr = gvk_to_gvr(&gvk, &fake_client.registry)?;
-            let namespace = extract_namespace(&obj);
+            fake_client
+                .tracker
+                .add_observer(crate::generation::observer(gvr, gvk));
+        }
+
+        // Maintain index maps for registered field indexes and label indexes
+        for (gvk, fields) in fake_client.indexes.read().iter() {
+            let gvr = gvk_to_gvr(gvk, &fake_client.registry)?;
+            for (field, indexer) in fields {
+                fake_client.tracker.add_index(
+                    &gvr,
+                    IndexKey::Field(field.clone()),
+                    Arc::clone(indexer),
+                );
+            }
+        }
+        for (gvk, key) in &self.label_indexes {
+            let gvr = gvk_to_gvr(gvk, &fake_client.registry)?;
+            fake_client.tracker.add_index(
+                &gvr,
+                IndexKey::Label(key.clone()),
+                IndexKey::label_indexer(key),
+            );
+        }
+
+        // A saved state replaces the initial objects
+        let restored = match &fake_client.persistence {
+            Some(persistence) => persistence.load(&fake_client.tracker)?,
+            None => false,
+        };
+        let seeds = if restored { Vec::new() } else { seeds };
+
+        // Add initial objects, by default with add() to match Go's behavior
+        for seed in seeds {
+            let name = seed.object["metadata"]["name"].as_str().unwrap_or_default();
+            let describe = match seed.namespace.as_str() {
+                "" => format!("{} {name}", describe_gvk(&seed.gvk)),
+                namespace => format!("{} {namespace}/{name}", describe_gvk(&seed.gvk)),
+            };
+            let added = match self.initial_object_mode {
+                InitialObjectMode::Add => fake_client
+                    .check_metadata_rules(&seed.gvk, &seed.namespace, &seed.object)
+                    .and_then(|()| {
+                        let tracker = &fake_client.tracker;
+                        if self.allow_fixture_overwrite {
+                            tracker.add_or_replace(&seed.gvr, seed.object, &seed.namespace)
+                        } else {
+                            tracker.add(&seed.gvr, seed.object, &seed.namespace)
+                        }
+                    }),
+                InitialObjectMode::Create => fake_client.create_initial_object(seed),
+            };
+            added.map_err(|e| {
+                Error::InvalidConfiguration(format!(
+                    "initial object {describe} could not be added: {e}"
+                ))
+            })?;
+        }
 
+        // Added only now, so that initial objects added before their owners keep
+        // their owner references
+        if self.garbage_collector {
             fake_client
                 .tracker
-                .add(&gvr, &gvk, obj, &namespace)
-                .map_err(|e| Error::Internal(format!("Failed to add initial object: {}", e)))?;
+                .add_observer(crate::garbage_collector::observer());
+        }
+
+        // Stale views start from the initial objects
+        for (gvk, lag) in self.stale_reads {
+            let gvr = gvk_to_gvr(&gvk, &fake_client.registry)?;
+            fake_client.stale_reads.add(&fake_client.tracker, gvr, lag);
         }
 
-        // Create the mock service
-        let service = crate::mock_service::MockService::new(fake_client);
+        // Save the starting state, then every write after it
+        if let Some(persistence) = &fake_client.persistence {
+            persistence.save(&fake_client.tracker)?;
+            persistence.observe(&fake_client.tracker);
+        }
 
-        // Create a kube::Client using the mock service
-        let kube_client = kube::Client::new(service, "default");
+        let handle = ClientHandle::new(fake_client.clone());
+        Ok((MockService::new(fake_client), handle))
+    }
 
-        // Return the kube::Client
-        Ok(kube_client)
+    /// Build a named [`Cluster`] for multi-cluster tests
+    ///
+    /// Every cluster built has its own storage. Group them with
+    /// [`Clusters`](crate::Clusters) to look them up by name and assert across
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`build`](Self::build).
+    pub async fn build_cluster(self, name: impl Into<String>) -> Result<Cluster> {
+        let (client, handle) = self.build_with_handle().await?;
+        Ok(Cluster::new(name.into(), client, handle))
     }
 }
 
@@ -649,6 +2847,123 @@ impl Default for ClientBuilder {
     }
 }
 
+/// An initial object with the resource and namespace it is stored under
+struct Seed {
+    object: Value,
+    gvk: GVK,
+    gvr: GVR,
+    namespace: String,
+}
+
+impl FakeClient {
+    /// Create an initial object as a create request would
+    fn create_initial_object(&self, seed: Seed) -> Result<Value> {
+        let Seed {
+            mut object,
+            gvk,
+            gvr,
+            namespace,
+        } = seed;
+        if let Some(metadata) = object.get_mut("metadata").and_then(Value::as_object_mut) {
+            for field in [
+                "resourceVersion",
+                "uid",
+                "creationTimestamp",
+                "generation",
+                "deletionTimestamp",
+            ] {
+                metadata.remove(field);
+            }
+        }
+        let reservation = self.prepare_for_create(&gvk, &namespace, &mut object)?;
+        self.admit(&gvk, &mut object)?;
+        let created = self.tracker.create(&gvr, object, &namespace)?;
+        reservation.commit();
+        Ok(created)
+    }
+}
+
+/// Resolve where each initial object is stored, rejecting objects that can't be
+/// stored and objects added more than once unless overwrites are allowed
+fn resolve_initial_objects(
+    objects: Vec<Value>,
+    registry: &ResourceRegistry,
+    allow_overwrite: bool,
+) -> Result<Vec<Seed>> {
+    let mut seeds: Vec<Seed> = Vec::with_capacity(objects.len());
+    let mut seen = HashMap::new();
+    for (index, object) in objects.into_iter().enumerate() {
+        let name = object
+            .pointer("/metadata/name")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let describe = || match &name {
+            Some(name) => format!("initial object {name:?}"),
+            None => format!("initial object #{index}"),
+        };
+        let gvk = extract_gvk(&object).map_err(|_| {
+            Error::InvalidConfiguration(format!("{} has no apiVersion or kind", describe()))
+        })?;
+        let gvr = gvk_to_gvr(&gvk, registry).map_err(|_| {
+            Error::InvalidConfiguration(format!(
+                "{} has unknown kind {}; register the resource with with_resource() or with_dynamic_resource()",
+                describe(),
+                describe_gvk(&gvk)
+            ))
+        })?;
+        let Some(name) = name else {
+            return Err(Error::InvalidConfiguration(format!(
+                "{} ({}) has no metadata.name",
+                describe(),
+                describe_gvk(&gvk)
+            )));
+        };
+
+        let namespaced = Discovery::is_namespaced_with_registry(&gvk, registry);
+        // Cluster-scoped objects are stored without a namespace
+        let namespace = if namespaced == Some(false) {
+            String::new()
+        } else {
+            extract_namespace(&object)
+        };
+
+        let seed = Seed {
+            object,
+            gvk,
+            gvr,
+            namespace,
+        };
+        match seen.entry((seed.gvr.clone(), seed.namespace.clone(), name.clone())) {
+            // A later object replaces the earlier one in its place
+            Entry::Occupied(earlier) if allow_overwrite => seeds[*earlier.get()] = seed,
+            Entry::Occupied(_) => {
+                let object = match seed.namespace.as_str() {
+                    "" => name,
+                    namespace => format!("{namespace}/{name}"),
+                };
+                return Err(Error::InvalidConfiguration(format!(
+                    "{} {object} is added more than once as an initial object; use \
+                     with_allow_fixture_overwrite() to let later objects replace earlier ones",
+                    describe_gvk(&seed.gvk)
+                )));
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(seeds.len());
+                seeds.push(seed);
+            }
+        }
+    }
+    Ok(seeds)
+}
+
+/// A GVK as written in manifests, such as `apps/v1 Deployment`
+fn describe_gvk(gvk: &GVK) -> String {
+    match gvk.group.as_str() {
+        "" => format!("{} {}", gvk.version, gvk.kind),
+        group => format!("{group}/{} {}", gvk.version, gvk.kind),
+    }
+}
+
 /// Convert GVK to GVR using Discovery + Registry
 fn gvk_to_gvr(gvk: &GVK, registry: &ResourceRegistry) -> Result<GVR> {
     // Use Discovery which checks static data first, then checks the registry
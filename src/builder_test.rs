@@ -258,6 +258,161 @@ mod tests {
         assert_eq!(cm.metadata.name, Some("app-config".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_load_fixtures_into_namespace() {
+        let (_client, handle) = ClientBuilder::new()
+            .with_fixture_dir("fixtures")
+            .load_fixtures_into("team-a", ["pods.yaml", "deployment.yaml"])
+            .unwrap()
+            .load_fixture_into("team-b", "pods.yaml")
+            .unwrap()
+            .build_with_handle()
+            .await
+            .unwrap();
+
+        // Namespaces in the YAML, like `cache` and `production`, are overridden
+        let names = |namespace: &str| -> Vec<String> {
+            handle
+                .list_namespace(namespace)
+                .unwrap()
+                .into_iter()
+                .map(|object| {
+                    format!(
+                        "{}/{}",
+                        object.types.unwrap().kind,
+                        object.metadata.name.unwrap()
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(
+            names("team-a"),
+            [
+                "Pod/nginx-pod",
+                "Pod/redis-pod",
+                "Deployment/web-deployment"
+            ]
+        );
+        assert_eq!(names("team-b"), ["Pod/nginx-pod", "Pod/redis-pod"]);
+        assert!(names("cache").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_fixture_into_keeps_cluster_scoped_objects() {
+        let path = std::env::temp_dir().join(format!(
+            "kube-fake-client-fixture-{}.yaml",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(
+            &path,
+            "apiVersion: rbac.authorization.k8s.io/v1\nkind: ClusterRole\nmetadata:\n  name: reader\n",
+        )
+        .unwrap();
+        let client = ClientBuilder::new()
+            .load_fixture_into("team-a", &path)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let roles: kube::Api<k8s_openapi::api::rbac::v1::ClusterRole> = kube::Api::all(client);
+        let role = roles.get("reader").await.unwrap();
+        assert_eq!(role.metadata.namespace, None);
+
+        let err = ClientBuilder::new()
+            .load_fixture_into("", "fixtures/pods.yaml")
+            .err()
+            .expect("the namespace is empty");
+        assert!(
+            matches!(err, crate::Error::InvalidConfiguration(_)),
+            "{err}"
+        );
+    }
+
+    fn write_fixture(yaml: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "kube-fake-client-fixture-{}.yaml",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&path, yaml).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_strict_fixtures_reject_unknown_kinds_and_namespaces() {
+        let unknown = write_fixture(
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: settings\n---\n\
+             apiVersion: example.com/v1\nkind: Widget\nmetadata:\n  name: w\n",
+        );
+        let namespaced_role = write_fixture(
+            "apiVersion: rbac.authorization.k8s.io/v1\nkind: ClusterRole\nmetadata:\n  \
+             name: reader\n  namespace: team-a\n",
+        );
+
+        let err = ClientBuilder::new()
+            .with_strict_fixtures()
+            .load_fixture(&unknown)
+            .err()
+            .expect("Widget is not registered");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Invalid client configuration: document 2 in fixture {unknown:?}: unknown kind \
+                 example.com/v1 Widget; register the resource with with_resource() or \
+                 with_dynamic_resource() before loading fixtures"
+            )
+        );
+        let err = ClientBuilder::new()
+            .with_strict_fixtures()
+            .load_fixture(&namespaced_role)
+            .err()
+            .expect("ClusterRoles have no namespace");
+        assert!(
+            err.to_string().ends_with(
+                "rbac.authorization.k8s.io/v1 ClusterRole \"reader\" is cluster-scoped but has \
+                 namespace \"team-a\""
+            ),
+            "{err}"
+        );
+
+        // Without strict fixtures, the namespace is ignored
+        assert!(ClientBuilder::new().load_fixture(&namespaced_role).is_ok());
+        std::fs::remove_file(&unknown).unwrap();
+        std::fs::remove_file(&namespaced_role).unwrap();
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn test_strict_fixtures_validate_schemas() {
+        use kube::{CustomResource, CustomResourceExt};
+        use schemars::JsonSchema;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+        #[kube(group = "example.com", version = "v1", kind = "Sprocket", namespaced)]
+        struct SprocketSpec {
+            teeth: i32,
+        }
+
+        let fixture = write_fixture(
+            "apiVersion: example.com/v1\nkind: Sprocket\nmetadata:\n  name: s\n\
+             spec:\n  teeth: many\n",
+        );
+        let err = ClientBuilder::new()
+            .with_strict_fixtures()
+            .with_resource::<Sprocket>()
+            .with_crd_schema(Sprocket::crd())
+            .unwrap()
+            .load_fixture(&fixture)
+            .err()
+            .expect("teeth is an integer");
+        std::fs::remove_file(&fixture).unwrap();
+        let message = err.to_string();
+        assert!(message.contains("document 1 in fixture"), "{message}");
+        assert!(message.contains("/spec/teeth"), "{message}");
+    }
+
     #[tokio::test]
     async fn test_load_fixtures_order_preserved() {
         // Verify that fixtures are loaded in the order specified
@@ -1069,4 +1224,1232 @@ mod tests {
             .unwrap();
         assert_eq!(created_cache.metadata.name, Some("redis-cache".to_string()));
     }
+
+    /// Test schema defaulting fills in missing fields on create and patch
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_schema_defaulting() {
+        use kube::CustomResource;
+        use schemars::JsonSchema;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+        #[kube(group = "example.com", version = "v1", kind = "Widget", namespaced)]
+        struct WidgetSpec {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            replicas: Option<i32>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            ports: Option<Vec<WidgetPort>>,
+        }
+
+        #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+        struct WidgetPort {
+            port: i32,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            protocol: Option<String>,
+        }
+
+        let openapi = json!({
+            "definitions": {
+                "com.example.v1.Widget": {
+                    "type": "object",
+                    "properties": {
+                        "spec": { "$ref": "#/definitions/com.example.v1.WidgetSpec" }
+                    }
+                },
+                "com.example.v1.WidgetSpec": {
+                    "type": "object",
+                    "properties": {
+                        "replicas": { "type": "integer", "default": 1 },
+                        "ports": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "port": { "type": "integer" },
+                                    "protocol": { "type": "string", "default": "TCP" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let path = std::env::temp_dir().join(format!(
+            "kube-fake-client-defaulting-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&path, openapi.to_string()).unwrap();
+
+        let client = ClientBuilder::new()
+            .with_resource::<Widget>()
+            .with_schema_validation_file(&path)
+            .unwrap()
+            .with_validation_for("example.com/v1/Widget")
+            .unwrap()
+            .with_schema_defaulting()
+            .build()
+            .await
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let widgets: kube::Api<Widget> = kube::Api::namespaced(client, "default");
+        let widget = Widget::new(
+            "w",
+            WidgetSpec {
+                replicas: None,
+                ports: Some(vec![WidgetPort {
+                    port: 80,
+                    protocol: None,
+                }]),
+            },
+        );
+        let created = widgets
+            .create(&kube::api::PostParams::default(), &widget)
+            .await
+            .unwrap();
+        assert_eq!(created.spec.replicas, Some(1));
+        assert_eq!(
+            created.spec.ports.as_ref().unwrap()[0].protocol.as_deref(),
+            Some("TCP")
+        );
+
+        // Explicit values are left untouched
+        let patched = widgets
+            .patch(
+                "w",
+                &kube::api::PatchParams::default(),
+                &kube::api::Patch::Merge(
+                    json!({ "spec": { "replicas": 5, "ports": [{ "port": 443 }] } }),
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(patched.spec.replicas, Some(5));
+        assert_eq!(
+            patched.spec.ports.as_ref().unwrap()[0].protocol.as_deref(),
+            Some("TCP")
+        );
+    }
+
+    /// Test unknown fields of custom resources are pruned by their schema
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_schema_pruning() {
+        use kube::api::PostParams;
+        use kube::api::{Api, ApiResource, DynamicObject, GroupVersionKind, Patch, PatchParams};
+
+        let openapi = json!({
+            "definitions": {
+                "com.example.v1.Gizmo": {
+                    "type": "object",
+                    "properties": {
+                        "spec": {
+                            "type": "object",
+                            "properties": {
+                                "size": { "type": "integer" },
+                                "extra": {
+                                    "type": "object",
+                                    "x-kubernetes-preserve-unknown-fields": true
+                                },
+                                "parts": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "object",
+                                        "properties": { "name": { "type": "string" } }
+                                    }
+                                },
+                                "byZone": {
+                                    "type": "object",
+                                    "additionalProperties": {
+                                        "type": "object",
+                                        "properties": { "count": { "type": "integer" } }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let path = std::env::temp_dir().join(format!(
+            "kube-fake-client-pruning-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&path, openapi.to_string()).unwrap();
+
+        let gvk = GroupVersionKind::gvk("example.com", "v1", "Gizmo");
+        let resource = ApiResource::from_gvk_with_plural(&gvk, "gizmos");
+        let client = ClientBuilder::new()
+            .with_dynamic_resource(resource.clone())
+            .with_schema_validation_file(&path)
+            .unwrap()
+            .with_validation_for("example.com/v1/Gizmo")
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let gizmos: Api<DynamicObject> = Api::namespaced_with(client, "default", &resource);
+        let gizmo = DynamicObject::new("g", &resource).data(json!({
+            "spec": {
+                "size": 1,
+                "colour": "red",
+                "extra": { "anything": { "goes": true } },
+                "parts": [{ "name": "gear", "weight": 3 }],
+                "byZone": { "a": { "count": 2, "note": "x" } }
+            },
+            "unknownTopLevel": true
+        }));
+        let created = gizmos.create(&PostParams::default(), &gizmo).await.unwrap();
+        assert_eq!(
+            created.data,
+            json!({
+                "spec": {
+                    "size": 1,
+                    "extra": { "anything": { "goes": true } },
+                    "parts": [{ "name": "gear" }],
+                    "byZone": { "a": { "count": 2 } }
+                }
+            })
+        );
+        assert_eq!(created.metadata.name.as_deref(), Some("g"));
+
+        // Updates are pruned too
+        let patched = gizmos
+            .patch(
+                "g",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "spec": { "size": 2, "colour": "blue" } })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(patched.data["spec"]["size"], 2);
+        assert!(patched.data["spec"].get("colour").is_none());
+    }
+
+    /// Test custom resources are validated against the schema of their CRD
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_crd_schema_validation() {
+        use kube::api::{Api, ApiResource, DynamicObject, GroupVersionKind, PostParams};
+        use kube::{CustomResource, CustomResourceExt};
+        use schemars::JsonSchema;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+        #[kube(group = "example.com", version = "v1", kind = "Sprocket", namespaced)]
+        struct SprocketSpec {
+            teeth: i32,
+            colour: Option<String>,
+        }
+
+        let gadget_crd = r#"
+apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: gadgets.example.com
+spec:
+  group: example.com
+  names: { kind: Gadget, plural: gadgets }
+  scope: Namespaced
+  versions:
+    - name: v1
+      served: true
+      storage: true
+      schema:
+        openAPIV3Schema:
+          type: object
+          properties:
+            spec:
+              type: object
+              required: [size]
+              properties:
+                size: { type: integer, minimum: 1, default: 1 }
+---
+"#;
+        let gadget = ApiResource::from_gvk_with_plural(
+            &GroupVersionKind::gvk("example.com", "v1", "Gadget"),
+            "gadgets",
+        );
+        let client = ClientBuilder::new()
+            .with_resource::<Sprocket>()
+            .with_dynamic_resource(gadget.clone())
+            .with_crd_schema(Sprocket::crd())
+            .unwrap()
+            .with_crd_schema(gadget_crd)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let sprockets: Api<Sprocket> = Api::namespaced(client.clone(), "default");
+        let sprocket = Sprocket::new(
+            "s",
+            SprocketSpec {
+                teeth: 12,
+                colour: None,
+            },
+        );
+        // Optional fields may be null
+        sprockets
+            .create(&PostParams::default(), &sprocket)
+            .await
+            .unwrap();
+
+        let sprockets: Api<DynamicObject> = Api::namespaced_with(
+            client.clone(),
+            "default",
+            &ApiResource::erase::<Sprocket>(&()),
+        );
+        let invalid = DynamicObject::new("t", &ApiResource::erase::<Sprocket>(&()))
+            .data(json!({ "spec": { "teeth": "many" } }));
+        let err = sprockets
+            .create(&PostParams::default(), &invalid)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, kube::Error::Api(resp) if resp.code == 422),
+            "{err:?}"
+        );
+
+        let gadgets: Api<DynamicObject> = Api::namespaced_with(client, "default", &gadget);
+        let invalid = DynamicObject::new("g", &gadget).data(json!({ "spec": { "size": 0 } }));
+        assert!(gadgets
+            .create(&PostParams::default(), &invalid)
+            .await
+            .is_err());
+        let valid = DynamicObject::new("g", &gadget)
+            .data(json!({ "spec": { "size": 2, "unknown": true } }));
+        let created = gadgets
+            .create(&PostParams::default(), &valid)
+            .await
+            .unwrap();
+        assert_eq!(created.data["spec"], json!({ "size": 2 }));
+
+        // Only CustomResourceDefinitions are accepted
+        let message = match ClientBuilder::new().with_crd_schema(json!({ "kind": "ConfigMap" })) {
+            Err(crate::Error::InvalidConfiguration(message)) => message,
+            other => panic!(
+                "expected an invalid configuration error, got {:?}",
+                other.err()
+            ),
+        };
+        assert_eq!(
+            message,
+            r#"expected a CustomResourceDefinition, got kind "ConfigMap""#
+        );
+    }
+
+    /// Test compiled schemas are shared by content, and can be compiled at build
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_schema_cache_and_precompilation() {
+        use crate::validator::Precompile;
+        use kube::api::{Api, ApiResource, DynamicObject, GroupVersionKind, PostParams};
+
+        let gvk = GroupVersionKind::gvk("example.com", "v1", "Knob");
+        let resource = ApiResource::from_gvk_with_plural(&gvk, "knobs");
+        let schema_file = |size_type: serde_json::Value| {
+            let path = std::env::temp_dir().join(format!(
+                "kube-fake-client-cache-{}.json",
+                uuid::Uuid::new_v4()
+            ));
+            let openapi = json!({
+                "definitions": {
+                    "com.example.v1.Knob": {
+                        "type": "object",
+                        "properties": {
+                            "spec": {
+                                "type": "object",
+                                "properties": { "size": { "type": size_type } }
+                            }
+                        }
+                    }
+                }
+            });
+            std::fs::write(&path, openapi.to_string()).unwrap();
+            path
+        };
+        let build = |path: std::path::PathBuf, precompile: Option<Precompile>| {
+            let resource = resource.clone();
+            async move {
+                let mut builder = ClientBuilder::new()
+                    .with_dynamic_resource(resource)
+                    .with_schema_validation_file(&path)?
+                    .with_validation_for("example.com/v1/Knob")?;
+                if let Some(precompile) = precompile {
+                    builder = builder.with_precompiled_schemas(precompile);
+                }
+                let client = builder.build().await;
+                std::fs::remove_file(&path).unwrap();
+                client
+            }
+        };
+        let accepts = |client: kube::Client, size: serde_json::Value| {
+            let resource = resource.clone();
+            async move {
+                let knobs: Api<DynamicObject> = Api::namespaced_with(client, "default", &resource);
+                let knob =
+                    DynamicObject::new("k", &resource).data(json!({ "spec": { "size": size } }));
+                knobs.create(&PostParams::default(), &knob).await.is_ok()
+            }
+        };
+
+        // Files with the same definitions but different schemas don't share them
+        let integers = build(schema_file(json!("integer")), Some(Precompile::Blocking))
+            .await
+            .unwrap();
+        let strings = build(schema_file(json!("string")), Some(Precompile::Background))
+            .await
+            .unwrap();
+        let integers_again = build(schema_file(json!("integer")), None).await.unwrap();
+        assert!(accepts(integers.clone(), json!(3)).await);
+        assert!(!accepts(integers, json!("three")).await);
+        assert!(accepts(strings.clone(), json!("three")).await);
+        assert!(!accepts(strings, json!(3)).await);
+        assert!(!accepts(integers_again, json!("three")).await);
+
+        // Blocking precompilation reports schemas that don't compile
+        let result = build(schema_file(json!(5)), Some(Precompile::Blocking)).await;
+        assert!(result.is_err());
+
+        // Precompiling needs schemas
+        let result = ClientBuilder::new()
+            .with_precompiled_schemas(Precompile::Blocking)
+            .build()
+            .await;
+        assert!(matches!(result, Err(crate::Error::InvalidConfiguration(_))));
+    }
+
+    /// Test schema defaulting requires a schema file
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_schema_defaulting_requires_schema_file() {
+        let result = ClientBuilder::new().with_schema_defaulting().build().await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_api_for_builtin_resources() {
+        use kube::api::{Api, ApiResource, DynamicObject, ListParams, PostParams};
+
+        let resource = ApiResource::erase::<Pod>(&());
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: Api<DynamicObject> = Api::namespaced_with(client.clone(), "default", &resource);
+
+        let mut pod = DynamicObject::new("dynamic-pod", &resource);
+        pod.data = json!({ "spec": { "containers": [{ "name": "main", "image": "app:1" }] } });
+        pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        // The object is visible through the typed API
+        let typed: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        let fetched = typed.get("dynamic-pod").await.unwrap();
+        assert_eq!(fetched.spec.unwrap().containers[0].name, "main");
+
+        let list = pods.list(&ListParams::default()).await.unwrap();
+        assert_eq!(list.items.len(), 1);
+        assert_eq!(list.items[0].types.as_ref().unwrap().kind, "Pod");
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_resource_without_rust_type() {
+        use kube::api::{
+            Api, ApiResource, DeleteParams, DynamicObject, GroupVersionKind, ListParams, Patch,
+            PatchParams, PostParams,
+        };
+
+        let gvk = GroupVersionKind::gvk("example.com", "v1alpha1", "Widget");
+        let resource = ApiResource::from_gvk_with_plural(&gvk, "widgets");
+
+        // Unregistered resources are rejected
+        let client = ClientBuilder::new().build().await.unwrap();
+        let widgets: Api<DynamicObject> = Api::namespaced_with(client, "default", &resource);
+        let widget = DynamicObject::new("w1", &resource).data(json!({ "spec": { "size": 1 } }));
+        assert!(widgets
+            .create(&PostParams::default(), &widget)
+            .await
+            .is_err());
+
+        let mut initial = DynamicObject::new("seeded", &resource).within("default");
+        initial.data = json!({ "spec": { "size": 3 } });
+        let client = ClientBuilder::new()
+            .with_dynamic_resource(resource.clone())
+            .with_runtime_objects(vec![serde_json::to_value(&initial).unwrap()])
+            .build()
+            .await
+            .unwrap();
+        let widgets: Api<DynamicObject> = Api::namespaced_with(client, "default", &resource);
+
+        widgets
+            .create(&PostParams::default(), &widget)
+            .await
+            .unwrap();
+        let list = widgets.list(&ListParams::default()).await.unwrap();
+        assert_eq!(list.items.len(), 2);
+
+        let patch = json!({ "spec": { "size": 2 } });
+        let patched = widgets
+            .patch("w1", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert_eq!(patched.data["spec"]["size"], 2);
+
+        widgets
+            .delete("w1", &DeleteParams::default())
+            .await
+            .unwrap();
+        assert!(widgets.get_opt("w1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_scoped_dynamic_resource() {
+        use kube::api::{Api, ApiResource, DynamicObject, GroupVersionKind, PostParams};
+
+        let gvk = GroupVersionKind::gvk("example.com", "v1", "Tenant");
+        let resource = ApiResource::from_gvk_with_plural(&gvk, "tenants");
+        let client = ClientBuilder::new()
+            .with_cluster_dynamic_resource(resource.clone())
+            .build()
+            .await
+            .unwrap();
+        let tenants: Api<DynamicObject> = Api::all_with(client, &resource);
+
+        tenants
+            .create(
+                &PostParams::default(),
+                &DynamicObject::new("acme", &resource),
+            )
+            .await
+            .unwrap();
+        let tenant = tenants.get("acme").await.unwrap();
+        assert_eq!(tenant.metadata.namespace, None);
+    }
+
+    #[tokio::test]
+    async fn test_layers_wrap_the_fake_in_order() {
+        use std::sync::{Arc, Mutex};
+        use tower::ServiceBuilder;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = |name: &'static str| {
+            let seen = Arc::clone(&seen);
+            tower::layer::layer_fn(move |service| {
+                let seen = Arc::clone(&seen);
+                ServiceBuilder::new()
+                    .map_request(move |request: http::Request<kube::client::Body>| {
+                        seen.lock()
+                            .unwrap()
+                            .push(format!("{name} {}", request.uri().path()));
+                        request
+                    })
+                    .service(service)
+            })
+        };
+        let client = ClientBuilder::new()
+            .with_layer(record("outer"))
+            .with_layer(record("inner"))
+            .build()
+            .await
+            .unwrap();
+
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        assert!(pods.get_opt("missing").await.unwrap().is_none());
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "outer /api/v1/namespaces/default/pods/missing",
+                "inner /api/v1/namespaces/default/pods/missing",
+            ]
+        );
+    }
+
+    /// Fails every write without reaching the fake
+    struct RejectWrites(crate::LayeredService);
+
+    impl tower::Service<http::Request<kube::client::Body>> for RejectWrites {
+        type Response =
+            <crate::LayeredService as tower::Service<http::Request<kube::client::Body>>>::Response;
+        type Error = tower::BoxError;
+        type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(
+            &mut self,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Self::Error>> {
+            self.0.poll_ready(cx)
+        }
+
+        fn call(&mut self, request: http::Request<kube::client::Body>) -> Self::Future {
+            use http_body_util::BodyExt;
+
+            if request.method() == http::Method::GET {
+                return self.0.call(request);
+            }
+            let status = json!({
+                "kind": "Status",
+                "apiVersion": "v1",
+                "status": "Failure",
+                "message": "injected outage",
+                "reason": "ServiceUnavailable",
+                "code": 503,
+            });
+            let body = http_body_util::Full::new(bytes::Bytes::from(status.to_string()))
+                .map_err(|never| match never {})
+                .boxed_unsync();
+            let response = http::Response::builder().status(503).body(body).unwrap();
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layer_can_short_circuit_requests() {
+        let reject_writes = tower::layer::layer_fn(RejectWrites);
+        let client = ClientBuilder::new()
+            .with_layer(reject_writes)
+            .build()
+            .await
+            .unwrap();
+
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("test-pod".to_string());
+        let err = pods
+            .create(&kube::api::PostParams::default(), &pod)
+            .await
+            .unwrap_err();
+        match err {
+            kube::Error::Api(response) => {
+                assert_eq!(response.code, 503);
+                assert_eq!(response.message, "injected outage");
+            }
+            other => panic!("expected an API error, got {other:?}"),
+        }
+        assert!(pods.get_opt("test-pod").await.unwrap().is_none());
+    }
+
+    fn invalid_configuration(result: crate::Result<kube::Client>) -> String {
+        match result {
+            Err(crate::Error::InvalidConfiguration(message)) => message,
+            Err(other) => panic!("expected an invalid configuration error, got {other:?}"),
+            Ok(_) => panic!("expected an invalid configuration error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_initial_objects_are_rejected() {
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web".to_string());
+        pod.metadata.namespace = Some("default".to_string());
+
+        let message = invalid_configuration(
+            ClientBuilder::new()
+                .with_object(pod.clone())
+                .with_object(pod.clone())
+                .build()
+                .await,
+        );
+        assert_eq!(
+            message,
+            "v1 Pod default/web is added more than once as an initial object; use \
+             with_allow_fixture_overwrite() to let later objects replace earlier ones"
+        );
+
+        // The same name in another namespace is a different object
+        let mut other = pod.clone();
+        other.metadata.namespace = Some("other".to_string());
+        ClientBuilder::new()
+            .with_objects(vec![pod, other])
+            .build()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fixture_overwrite_keeps_the_last_object() {
+        use crate::ListOrder;
+        use k8s_openapi::api::core::v1::ConfigMap;
+        use kube::Api;
+
+        let configmap = |name: &str, level: Option<&str>| {
+            let mut cm = ConfigMap::default();
+            cm.metadata.name = Some(name.to_string());
+            cm.data = level.map(|level| [("log.level".to_string(), level.to_string())].into());
+            cm
+        };
+        let builder = || {
+            ClientBuilder::new()
+                .with_fixture_dir("fixtures")
+                .load_fixture("configmap.yaml")
+                .unwrap()
+                .with_object(configmap("other", None))
+                .with_object(configmap("app-config", Some("debug")))
+        };
+
+        let message = invalid_configuration(builder().build().await);
+        assert!(message.starts_with("v1 ConfigMap default/app-config is added more than once"));
+
+        let client = builder()
+            .with_allow_fixture_overwrite()
+            .with_list_order(ListOrder::Insertion)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        let names: Vec<String> = configmaps
+            .list(&Default::default())
+            .await
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|cm| {
+                let level = cm
+                    .data
+                    .and_then(|data| data.get("log.level").cloned())
+                    .unwrap_or_default();
+                format!("{}={level}", cm.metadata.name.unwrap())
+            })
+            .collect();
+        assert_eq!(names, ["app-config=debug", "other="]);
+    }
+
+    #[tokio::test]
+    async fn test_initial_objects_of_unknown_kinds_are_rejected() {
+        let message = invalid_configuration(
+            ClientBuilder::new()
+                .with_runtime_objects(vec![json!({
+                    "apiVersion": "example.com/v1",
+                    "kind": "Widget",
+                    "metadata": { "name": "w1", "namespace": "default" },
+                })])
+                .build()
+                .await,
+        );
+        assert!(message.starts_with(
+            "initial object \"w1\" has unknown kind example.com/v1 Widget; register the resource"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_initial_objects_without_type_or_name_are_rejected() {
+        let message = invalid_configuration(
+            ClientBuilder::new()
+                .with_runtime_objects(vec![json!({ "metadata": { "name": "orphan" } })])
+                .build()
+                .await,
+        );
+        assert_eq!(
+            message,
+            "initial object \"orphan\" has no apiVersion or kind"
+        );
+
+        let message = invalid_configuration(
+            ClientBuilder::new()
+                .with_runtime_objects(vec![json!({ "apiVersion": "v1", "kind": "ConfigMap" })])
+                .build()
+                .await,
+        );
+        assert_eq!(
+            message,
+            "initial object #0 (v1 ConfigMap) has no metadata.name"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_subresource_for_unknown_kind_is_rejected() {
+        use kube::CustomResource;
+        use schemars::JsonSchema;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(CustomResource, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+        #[kube(
+            group = "example.com",
+            version = "v1",
+            kind = "Gadget",
+            namespaced,
+            derive = "Default"
+        )]
+        struct GadgetSpec {
+            size: i32,
+        }
+
+        let message = invalid_configuration(
+            ClientBuilder::new()
+                .with_status_subresource::<Gadget>()
+                .build()
+                .await,
+        );
+        assert!(message.starts_with(
+            "status subresource registered for unknown kind example.com/v1 Gadget; register"
+        ));
+
+        ClientBuilder::new()
+            .with_resource::<Gadget>()
+            .with_status_subresource::<Gadget>()
+            .build()
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fixture_documents_without_kind_are_rejected() {
+        let path = std::env::temp_dir().join(format!("fixture-{}.yaml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: a\n---\napiVersion: v1\nmetadata:\n  name: b\n---\n",
+        )
+        .unwrap();
+
+        let result = ClientBuilder::new().load_fixture(&path);
+        std::fs::remove_file(&path).unwrap();
+        match result {
+            Err(crate::Error::InvalidConfiguration(message)) => {
+                assert_eq!(
+                    message,
+                    format!("document 2 in fixture {path:?} has no kind")
+                );
+            }
+            Err(other) => panic!("expected an invalid configuration error, got {other:?}"),
+            Ok(_) => panic!("expected an invalid configuration error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initial_object_modes() {
+        use crate::InitialObjectMode;
+        use k8s_openapi::api::core::v1::{ConfigMap, Service, ServiceSpec};
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+        let created = chrono::DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("settings".to_string());
+        cm.metadata.namespace = Some("default".to_string());
+        cm.metadata.resource_version = Some("999".to_string());
+        cm.metadata.uid = Some("original-uid".to_string());
+        cm.metadata.creation_timestamp = Some(Time(created));
+        let mut svc = Service::default();
+        svc.metadata.name = Some("web".to_string());
+        svc.metadata.namespace = Some("default".to_string());
+        svc.spec = Some(ServiceSpec::default());
+
+        // Added objects keep their metadata
+        let client = ClientBuilder::new()
+            .with_object(cm.clone())
+            .build()
+            .await
+            .unwrap();
+        let configmaps: kube::Api<ConfigMap> = kube::Api::namespaced(client, "default");
+        let added = configmaps.get("settings").await.unwrap();
+        assert_eq!(added.metadata.resource_version.as_deref(), Some("999"));
+        assert_eq!(added.metadata.uid.as_deref(), Some("original-uid"));
+        assert_eq!(added.metadata.creation_timestamp, Some(Time(created)));
+
+        // Created objects look new and go through create-time allocation
+        let client = ClientBuilder::new()
+            .with_initial_object_mode(InitialObjectMode::Create)
+            .with_object(cm)
+            .with_object(svc)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: kube::Api<ConfigMap> = kube::Api::namespaced(client.clone(), "default");
+        let fresh = configmaps.get("settings").await.unwrap();
+        assert_eq!(fresh.metadata.resource_version.as_deref(), Some("1"));
+        assert_eq!(fresh.metadata.generation, Some(1));
+        assert_ne!(fresh.metadata.uid.as_deref(), Some("original-uid"));
+        assert!(fresh.metadata.creation_timestamp.unwrap().0 > created);
+
+        let services: kube::Api<Service> = kube::Api::namespaced(client, "default");
+        let web = services.get("web").await.unwrap();
+        assert!(web.spec.unwrap().cluster_ip.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_permissive_verbs() {
+        use k8s_openapi::api::core::v1::ComponentStatus;
+        use kube::api::{Api, PostParams};
+
+        let mut status = ComponentStatus::default();
+        status.metadata.name = Some("etcd-0".to_string());
+
+        let client = ClientBuilder::new().build().await.unwrap();
+        let statuses: Api<ComponentStatus> = Api::all(client);
+        let err = statuses
+            .create(&PostParams::default(), &status)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 405));
+
+        for builder in [
+            ClientBuilder::new().with_permissive_verbs(),
+            ClientBuilder::new().with_permissive_verbs_for::<ComponentStatus>(),
+        ] {
+            let statuses: Api<ComponentStatus> = Api::all(builder.build().await.unwrap());
+            statuses
+                .create(&PostParams::default(), &status)
+                .await
+                .unwrap();
+            assert!(statuses.get_opt("etcd-0").await.unwrap().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fidelity_profiles() {
+        use k8s_openapi::api::core::v1::ComponentStatus;
+        use kube::api::{Api, ListParams, PostParams};
+
+        let mut status = ComponentStatus::default();
+        status.metadata.name = Some("etcd-0".to_string());
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web".to_string());
+        pod.metadata.labels = Some([("app".to_string(), "web".to_string())].into());
+        let broken = ListParams::default().labels("app in (web");
+
+        let pods: Api<Pod> =
+            Api::default_namespaced(ClientBuilder::conformant().build().await.unwrap());
+        let created = pods.create(&PostParams::default(), &pod).await.unwrap();
+        assert!(created.metadata.managed_fields.is_some());
+        let err = pods.list(&broken).await.unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 400));
+
+        // Each option of a profile can still be overridden
+        let client = ClientBuilder::conformant()
+            .without_strict_query_params()
+            .without_return_managed_fields()
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::default_namespaced(client);
+        let created = pods.create(&PostParams::default(), &pod).await.unwrap();
+        assert!(created.metadata.managed_fields.is_none());
+        assert!(pods.list(&broken).await.unwrap().items.is_empty());
+
+        let statuses: Api<ComponentStatus> =
+            Api::all(ClientBuilder::lenient().build().await.unwrap());
+        statuses
+            .create(&PostParams::default(), &status)
+            .await
+            .unwrap();
+
+        let client = ClientBuilder::lenient()
+            .without_permissive_verbs()
+            .build()
+            .await
+            .unwrap();
+        let err = Api::<ComponentStatus>::all(client)
+            .create(&PostParams::default(), &status)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 405));
+    }
+
+    #[tokio::test]
+    async fn test_list_order() {
+        use crate::ListOrder;
+        use kube::api::{Api, ListParams};
+
+        let pod = |name: &str, namespace: &str| {
+            let mut pod = Pod::default();
+            pod.metadata.name = Some(name.to_string());
+            pod.metadata.namespace = Some(namespace.to_string());
+            pod
+        };
+        let pods = vec![pod("b", "prod"), pod("a", "prod"), pod("c", "dev")];
+        let list = |client| async move {
+            Api::<Pod>::all(client)
+                .list(&ListParams::default())
+                .await
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|pod| pod.metadata.name.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let client = ClientBuilder::new()
+            .with_objects(pods.clone())
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(list(client).await, ["c", "a", "b"]);
+
+        let client = ClientBuilder::new()
+            .with_list_order(ListOrder::Insertion)
+            .with_objects(pods)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(list(client).await, ["b", "a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_defaulter() {
+        use k8s_openapi::api::apps::v1::{Deployment, DeploymentSpec, DeploymentStrategy};
+        use kube::api::{Api, Patch, PatchParams, PostParams};
+
+        let (client, _handle) = ClientBuilder::new()
+            .with_defaulter::<Deployment>(|deployment| {
+                let spec = deployment.spec.get_or_insert_with(Default::default);
+                spec.strategy.get_or_insert_with(|| DeploymentStrategy {
+                    type_: Some("RollingUpdate".to_string()),
+                    ..Default::default()
+                });
+            })
+            .with_defaulter::<Deployment>(|deployment| {
+                // Runs after the first defaulter
+                let spec = deployment.spec.as_mut().unwrap();
+                if spec.strategy.as_ref().unwrap().type_.as_deref() == Some("RollingUpdate") {
+                    spec.revision_history_limit.get_or_insert(10);
+                }
+            })
+            .with_defaulter::<Pod>(|pod| {
+                let spec = pod.spec.get_or_insert_with(Default::default);
+                spec.restart_policy
+                    .get_or_insert_with(|| "Always".to_string());
+            })
+            .build_with_handle()
+            .await
+            .unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), "default");
+
+        let mut deployment = Deployment::default();
+        deployment.metadata.name = Some("web".to_string());
+        deployment.spec = Some(DeploymentSpec::default());
+        let created = deployments
+            .create(&PostParams::default(), &deployment)
+            .await
+            .unwrap();
+        let spec = created.spec.unwrap();
+        assert_eq!(
+            spec.strategy.unwrap().type_.as_deref(),
+            Some("RollingUpdate")
+        );
+        assert_eq!(spec.revision_history_limit, Some(10));
+
+        // Updates are defaulted too, but fields that are set are left alone
+        let patch = json!({
+            "spec": {
+                "strategy": { "type": "Recreate", "rollingUpdate": null },
+                "revisionHistoryLimit": null,
+            }
+        });
+        let patched = deployments
+            .patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        let spec = patched.spec.unwrap();
+        assert_eq!(spec.strategy.unwrap().type_.as_deref(), Some("Recreate"));
+        assert_eq!(spec.revision_history_limit, None);
+
+        // Each kind runs its own defaulters
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web-0".to_string());
+        let pod = Api::<Pod>::namespaced(client, "default")
+            .create(&PostParams::default(), &pod)
+            .await
+            .unwrap();
+        assert_eq!(pod.spec.unwrap().restart_policy.as_deref(), Some("Always"));
+    }
+
+    #[tokio::test]
+    async fn test_injected_labels_and_annotations() {
+        use k8s_openapi::api::core::v1::ConfigMap;
+        use kube::api::{Api, ListParams, Patch, PatchParams, PostParams};
+
+        let mut fixture = ConfigMap::default();
+        fixture.metadata.name = Some("fixture".to_string());
+        fixture.metadata.namespace = Some("default".to_string());
+        let client = ClientBuilder::new()
+            .with_object(fixture)
+            .with_injected_labels([("tenant", "team-a")])
+            .with_injected_labels([("test-run", "42")])
+            .with_injected_annotations([("example.com/owner", "tests")])
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+
+        let mut configmap = ConfigMap::default();
+        configmap.metadata.name = Some("created".to_string());
+        configmap.metadata.labels = Some([("tenant".to_string(), "team-b".to_string())].into());
+        let created = configmaps
+            .create(&PostParams::default(), &configmap)
+            .await
+            .unwrap();
+        let labels = created.metadata.labels.unwrap();
+        assert_eq!(labels["tenant"], "team-a");
+        assert_eq!(labels["test-run"], "42");
+        assert_eq!(
+            created.metadata.annotations.unwrap()["example.com/owner"],
+            "tests"
+        );
+
+        // Server-side apply creates are stamped too
+        let applied = json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "applied" },
+        });
+        configmaps
+            .patch(
+                "applied",
+                &PatchParams::apply("tests"),
+                &Patch::Apply(&applied),
+            )
+            .await
+            .unwrap();
+
+        // Everything the test created, and nothing else
+        let tagged: Vec<String> = configmaps
+            .list(&ListParams::default().labels("test-run=42"))
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|cm| cm.metadata.name)
+            .collect();
+        assert_eq!(tagged, ["applied", "created"]);
+    }
+
+    #[tokio::test]
+    async fn test_custom_resource_status_subresource_strictness() {
+        use kube::api::{Api, Patch, PatchParams, PostParams};
+        use kube::CustomResource;
+        use schemars::JsonSchema;
+        use serde::{Deserialize, Serialize};
+        use serde_json::json;
+
+        #[derive(CustomResource, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+        #[kube(
+            group = "example.com",
+            version = "v1",
+            kind = "Job",
+            namespaced,
+            status = "JobStatus",
+            derive = "Default"
+        )]
+        struct JobSpec {
+            image: String,
+        }
+
+        #[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+        struct JobStatus {
+            done: bool,
+        }
+
+        fn job() -> Job {
+            let mut job = Job::new(
+                "build",
+                JobSpec {
+                    image: "rust".to_string(),
+                },
+            );
+            job.status = Some(JobStatus { done: true });
+            job
+        }
+
+        // With the status subresource, a create drops the status and status
+        // writes leave the spec alone
+        let client = ClientBuilder::new()
+            .with_resource::<Job>()
+            .with_status_subresource::<Job>()
+            .build()
+            .await
+            .unwrap();
+        let jobs: Api<Job> = Api::default_namespaced(client);
+        let created = jobs.create(&PostParams::default(), &job()).await.unwrap();
+        assert!(created.status.is_none());
+
+        let patched = jobs
+            .patch_status(
+                "build",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "spec": { "image": "go" }, "status": { "done": true } })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(patched.spec.image, "rust");
+        assert!(patched.status.unwrap().done);
+
+        // Without it, a status field doesn't enable it: the status is stored
+        // and written by regular updates
+        let client = ClientBuilder::new()
+            .with_resource::<Job>()
+            .build()
+            .await
+            .unwrap();
+        let jobs: Api<Job> = Api::default_namespaced(client);
+        let created = jobs.create(&PostParams::default(), &job()).await.unwrap();
+        assert!(created.status.unwrap().done);
+        let patched = jobs
+            .patch(
+                "build",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "status": { "done": false } })),
+            )
+            .await
+            .unwrap();
+        assert!(!patched.status.unwrap().done);
+    }
+
+    /// A resource whose serialized form has no apiVersion or kind
+    #[derive(serde::Serialize, Default)]
+    struct Untyped {
+        metadata: kube::api::ObjectMeta,
+    }
+
+    impl kube::Resource for Untyped {
+        type DynamicType = ();
+        type Scope = kube::core::NamespaceResourceScope;
+
+        fn kind(_: &()) -> std::borrow::Cow<'_, str> {
+            "Untyped".into()
+        }
+        fn group(_: &()) -> std::borrow::Cow<'_, str> {
+            "example.com".into()
+        }
+        fn version(_: &()) -> std::borrow::Cow<'_, str> {
+            "v1".into()
+        }
+        fn plural(_: &()) -> std::borrow::Cow<'_, str> {
+            "untypeds".into()
+        }
+        fn meta(&self) -> &kube::api::ObjectMeta {
+            &self.metadata
+        }
+        fn meta_mut(&mut self) -> &mut kube::api::ObjectMeta {
+            &mut self.metadata
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_mistakes_are_reported_by_build() {
+        let result = ClientBuilder::new()
+            .with_status_subresource::<Untyped>()
+            .with_index::<Untyped>("spec.owner", std::sync::Arc::new(|_| Vec::new()))
+            .build()
+            .await;
+        let Err(crate::Error::InvalidConfiguration(message)) = result else {
+            panic!("expected an invalid configuration error");
+        };
+        assert!(message.contains("with_status_subresource()"), "{message}");
+        assert!(message.contains("with_index()"), "{message}");
+        assert!(message.contains("Untyped"), "{message}");
+
+        // Initial objects the fake rejects are named in the error
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web".to_string());
+        pod.metadata.namespace = Some("default".to_string());
+        let result = ClientBuilder::new()
+            .with_metadata_rule(|_: &_, _: &_| Err("metadata.name: rejected".to_string()))
+            .with_object(pod)
+            .build()
+            .await;
+        let Err(crate::Error::InvalidConfiguration(message)) = result else {
+            panic!("expected an invalid configuration error");
+        };
+        assert!(message.contains("v1 Pod default/web"), "{message}");
+    }
 }
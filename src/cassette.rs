@@ -0,0 +1,432 @@
+//! Recording HTTP interactions to a cassette file and replaying them
+//!
+//! A [`Recorder`] is a tower layer that writes every request passing through it,
+//! and the response it got, to a JSON cassette file. Put it in front of a real
+//! client to record a test against kind or minikube once, then build the fake
+//! with [`ClientBuilder::with_replay`](crate::ClientBuilder::with_replay) to serve
+//! the recorded responses in CI without a cluster.
+//!
+//! Replay matches each request to the first unreplayed interaction with the same
+//! method, URI and body, so requests made concurrently may arrive in any order.
+//! A request that matches none has drifted from the recording and fails with a
+//! 500 error naming it; [`ClientHandle::assert_replayed`](crate::ClientHandle::assert_replayed)
+//! catches recorded requests the code no longer makes.
+//!
+//! JSON bodies are stored as JSON and anything else, such as the event stream of
+//! a watch, as a string. A recorded watch is replayed as the events received
+//! before it was closed, after which the stream ends.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::cassette::Recorder;
+//! use kube_fake_client::ClientBuilder;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let path = "tests/cassettes/reconcile.json";
+//! let client = if std::env::var("RECORD").is_ok() {
+//!     let config = kube::Config::infer().await?;
+//!     kube::client::ClientBuilder::try_from(config)?
+//!         .with_layer(&Recorder::new(path))
+//!         .build()
+//! } else {
+//!     ClientBuilder::new().with_replay(path).build().await?
+//! };
+//!
+//! // ... run the reconcile with `client` ...
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::persistence::temporary_path;
+use crate::{Error, Result};
+use bytes::{Bytes, BytesMut};
+use futures::future::{BoxFuture, FutureExt};
+use http::{HeaderMap, Request, Response};
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::BodyExt;
+use kube::client::Body as KubeBody;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{BoxError, Layer, Service};
+use tracing::warn;
+
+/// Response headers that describe the recorded body rather than the response
+const SKIPPED_HEADERS: [&str; 2] = ["content-length", "transfer-encoding"];
+
+/// Contents of a cassette file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Recorded interactions in the order the requests were made
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Read a cassette file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Internal`] if the file can't be read or isn't a cassette.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read(path).map_err(|e| file_error("read", path, e))?;
+        serde_json::from_slice(&contents).map_err(|e| file_error("parse", path, e))
+    }
+}
+
+/// One request and the response it got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interaction {
+    /// HTTP method
+    pub method: String,
+    /// Path and query string of the request
+    pub uri: String,
+    /// Request body, if it had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Value>,
+    /// HTTP status code of the response
+    pub status: u16,
+    /// Response headers by lowercase name; repeated headers are joined with `, `
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub response_headers: BTreeMap<String, String>,
+    /// Response body, if it had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<Value>,
+}
+
+impl Interaction {
+    fn describe(&self) -> String {
+        format!("{} {}", self.method, self.uri)
+    }
+}
+
+/// A body as stored in a cassette: JSON if it parses, a string otherwise
+fn payload(bytes: &[u8]) -> Option<Value> {
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(
+        serde_json::from_slice(bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned())),
+    )
+}
+
+/// The bytes of a stored body
+fn payload_bytes(payload: &Value) -> Vec<u8> {
+    match payload {
+        Value::String(text) => text.clone().into_bytes(),
+        json => json.to_string().into_bytes(),
+    }
+}
+
+fn file_error(action: &str, path: &Path, err: impl std::fmt::Display) -> Error {
+    Error::Internal(format!(
+        "failed to {action} cassette {}: {err}",
+        path.display()
+    ))
+}
+
+/// Tower layer recording every interaction to a cassette file
+///
+/// Works with any client stack whose requests are `kube::client::Body`: the
+/// stack of a real `kube::Client`, through `kube::client::ClientBuilder::with_layer`,
+/// or the fake's, through [`ClientBuilder::with_layer`](crate::ClientBuilder::with_layer).
+/// The file is rewritten each time a response body has been read to the end or
+/// dropped, so it is complete as soon as the client is done with it. Requests
+/// that fail before a response arrives, such as on connection errors, are not
+/// recorded.
+#[derive(Clone)]
+pub struct Recorder {
+    tape: Arc<Tape>,
+}
+
+impl Recorder {
+    /// Record to a file, replacing anything in it
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            tape: Arc::new(Tape {
+                path: path.into(),
+                slots: Mutex::new(Vec::new()),
+                saving: Mutex::new(()),
+            }),
+        }
+    }
+}
+
+impl<S> Layer<S> for Recorder {
+    type Service = Recording<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Recording {
+            inner,
+            tape: Arc::clone(&self.tape),
+        }
+    }
+}
+
+/// Interactions recorded so far, in request order
+struct Tape {
+    path: PathBuf,
+    /// One slot per request, filled in when its response has been read
+    slots: Mutex<Vec<Option<Interaction>>>,
+    /// Held while saving, so a save never overwrites a later one
+    saving: Mutex<()>,
+}
+
+impl Tape {
+    fn reserve(&self) -> usize {
+        let mut slots = self.slots.lock();
+        slots.push(None);
+        slots.len() - 1
+    }
+
+    fn complete(&self, slot: usize, interaction: Interaction) {
+        self.slots.lock()[slot] = Some(interaction);
+        if let Err(e) = self.save() {
+            warn!("{e}");
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let _saving = self.saving.lock();
+        let cassette = Cassette {
+            interactions: self.slots.lock().iter().flatten().cloned().collect(),
+        };
+        let contents = serde_json::to_vec_pretty(&cassette)?;
+        let temporary = temporary_path(&self.path);
+        std::fs::write(&temporary, contents).map_err(|e| file_error("write", &self.path, e))?;
+        std::fs::rename(&temporary, &self.path).map_err(|e| file_error("write", &self.path, e))
+    }
+}
+
+/// Service recording the interactions of the service it wraps
+pub struct Recording<S> {
+    inner: S,
+    tape: Arc<Tape>,
+}
+
+impl<S, B> Service<Request<KubeBody>> for Recording<S>
+where
+    S: Service<Request<KubeBody>, Response = Response<B>>,
+    S::Future: Send + 'static,
+    S::Error: Into<BoxError>,
+    B: Body<Data = Bytes> + Send + 'static,
+{
+    type Response = Response<RecordingBody<B>>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, BoxError>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), BoxError>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<KubeBody>) -> Self::Future {
+        let (parts, body) = request.into_parts();
+        // kube sends request bodies as one buffer, so they are ready right away
+        let bytes = match body.collect().now_or_never() {
+            Some(Ok(collected)) => collected.to_bytes(),
+            Some(Err(e)) => return futures::future::ready(Err(e.into())).boxed(),
+            None => {
+                let err = "cannot record a streamed request body".into();
+                return futures::future::ready(Err(err)).boxed();
+            }
+        };
+        let mut interaction = Interaction {
+            method: parts.method.to_string(),
+            uri: parts
+                .uri
+                .path_and_query()
+                .map_or_else(|| parts.uri.path().to_string(), ToString::to_string),
+            request_body: payload(&bytes),
+            status: 0,
+            response_headers: BTreeMap::new(),
+            response_body: None,
+        };
+        let tape = Arc::clone(&self.tape);
+        let slot = tape.reserve();
+        let response = self
+            .inner
+            .call(Request::from_parts(parts, KubeBody::from(bytes)));
+
+        async move {
+            let (parts, body) = response.await.map_err(Into::into)?.into_parts();
+            interaction.status = parts.status.as_u16();
+            interaction.response_headers = headers(&parts.headers);
+            let body = RecordingBody {
+                inner: Box::pin(body),
+                data: BytesMut::new(),
+                pending: Some((tape, slot, interaction)),
+            };
+            Ok(Response::from_parts(parts, body))
+        }
+        .boxed()
+    }
+}
+
+fn headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut by_name: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in headers {
+        if SKIPPED_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        let value = String::from_utf8_lossy(value.as_bytes());
+        by_name
+            .entry(name.as_str().to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(&value);
+            })
+            .or_insert_with(|| value.into_owned());
+    }
+    by_name
+}
+
+/// Response body that records what was read of it
+///
+/// The interaction is written once the body ends or is dropped, whichever
+/// comes first.
+pub struct RecordingBody<B> {
+    inner: Pin<Box<B>>,
+    data: BytesMut,
+    pending: Option<(Arc<Tape>, usize, Interaction)>,
+}
+
+impl<B> RecordingBody<B> {
+    fn finish(&mut self) {
+        if let Some((tape, slot, mut interaction)) = self.pending.take() {
+            interaction.response_body = payload(&self.data);
+            tape.complete(slot, interaction);
+        }
+    }
+}
+
+impl<B: Body<Data = Bytes>> Body for RecordingBody<B> {
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Bytes>, B::Error>>> {
+        let this = self.get_mut();
+        let frame = std::task::ready!(this.inner.as_mut().poll_frame(cx));
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(data) = frame.data_ref() {
+                    this.data.extend_from_slice(data);
+                }
+            }
+            Some(Err(_)) | None => this.finish(),
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> Drop for RecordingBody<B> {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Recorded interactions served by the mock service in place of the fake
+pub(crate) struct Replay {
+    path: PathBuf,
+    interactions: Vec<Interaction>,
+    replayed: Mutex<Vec<bool>>,
+}
+
+impl Replay {
+    pub(crate) fn load(path: PathBuf) -> Result<Self> {
+        let cassette = Cassette::load(&path)?;
+        Ok(Self {
+            path,
+            replayed: Mutex::new(vec![false; cassette.interactions.len()]),
+            interactions: cassette.interactions,
+        })
+    }
+
+    /// The recorded response to a request
+    ///
+    /// Each interaction answers one request. Fails if no unreplayed interaction
+    /// has the request's method, URI and body.
+    pub(crate) fn respond(&self, method: &str, uri: &str, body: &[u8]) -> Result<Response<Bytes>> {
+        let request_body = payload(body);
+        let mut replayed = self.replayed.lock();
+        let unreplayed = || {
+            self.interactions
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !replayed[*index])
+        };
+        let found = unreplayed().find(|(_, interaction)| {
+            interaction.method == method
+                && interaction.uri == uri
+                && interaction.request_body == request_body
+        });
+        let Some((index, interaction)) = found else {
+            let mut message = format!(
+                "request {method} {uri} does not match any unreplayed interaction in cassette {}",
+                self.path.display()
+            );
+            let same_request = unreplayed()
+                .find(|(_, interaction)| interaction.method == method && interaction.uri == uri);
+            if let Some((_, interaction)) = same_request {
+                let recorded = interaction
+                    .request_body
+                    .as_ref()
+                    .map_or_else(|| "no body".to_string(), Value::to_string);
+                message.push_str(&format!("; the recorded request had {recorded}"));
+            }
+            return Err(Error::Internal(message));
+        };
+        replayed[index] = true;
+
+        let mut response = Response::builder().status(interaction.status);
+        for (name, value) in &interaction.response_headers {
+            response = response.header(name, value);
+        }
+        let body = interaction
+            .response_body
+            .as_ref()
+            .map(payload_bytes)
+            .unwrap_or_default();
+        response.body(body.into()).map_err(|e| {
+            Error::Internal(format!(
+                "invalid recorded response to {} in cassette {}: {e}",
+                interaction.describe(),
+                self.path.display()
+            ))
+        })
+    }
+
+    /// Interactions no request has matched yet
+    pub(crate) fn unreplayed(&self) -> Vec<String> {
+        let replayed = self.replayed.lock();
+        self.interactions
+            .iter()
+            .zip(replayed.iter())
+            .filter(|(_, replayed)| !**replayed)
+            .map(|(interaction, _)| interaction.describe())
+            .collect()
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use crate::cassette::{Cassette, Recorder};
+    use crate::test_fixtures::configmap_with;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, PostParams};
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    fn cassette_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "kube-fake-client-cassette-{}.json",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    /// Create a ConfigMap, read it back and look for a missing one
+    async fn exercise(client: kube::Client) -> (ConfigMap, Option<ConfigMap>) {
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "key", "value"),
+            )
+            .await
+            .unwrap();
+        let read = configmaps.get("settings").await.unwrap();
+        let missing = configmaps.get_opt("missing").await.unwrap();
+        (read, missing)
+    }
+
+    async fn record(path: &Path) -> (ConfigMap, Option<ConfigMap>) {
+        let client = ClientBuilder::new()
+            .with_layer(Recorder::new(path))
+            .build()
+            .await
+            .unwrap();
+        exercise(client).await
+    }
+
+    #[tokio::test]
+    async fn test_record_interactions() {
+        let path = cassette_path();
+        record(&path).await;
+
+        let cassette = Cassette::load(&path).unwrap();
+        let requests: Vec<(&str, &str, u16)> = cassette
+            .interactions
+            .iter()
+            .map(|i| (i.method.as_str(), i.uri.as_str(), i.status))
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                ("POST", "/api/v1/namespaces/default/configmaps?", 201),
+                ("GET", "/api/v1/namespaces/default/configmaps/settings", 200),
+                ("GET", "/api/v1/namespaces/default/configmaps/missing", 404),
+            ]
+        );
+        let created = &cassette.interactions[0];
+        assert_eq!(
+            created.request_body.as_ref().unwrap()["data"]["key"],
+            "value"
+        );
+        assert_eq!(
+            created
+                .response_headers
+                .get("content-type")
+                .map(String::as_str),
+            Some("application/json")
+        );
+        assert!(created.response_body.as_ref().unwrap()["metadata"]["uid"].is_string());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_responses() {
+        let path = cassette_path();
+        let recorded = record(&path).await;
+
+        let (client, handle) = ClientBuilder::new()
+            .with_replay(&path)
+            .build_with_handle()
+            .await
+            .unwrap();
+        assert_eq!(exercise(client).await, recorded);
+        handle.assert_replayed();
+
+        // Nothing reached the fake's storage
+        assert!(handle.list_namespace("default").unwrap().is_empty());
+        assert_eq!(handle.audit_entries().len(), 3);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_fails_on_drift() {
+        let path = cassette_path();
+        record(&path).await;
+
+        let client = ClientBuilder::new()
+            .with_replay(&path)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        let mut renamed = configmap_with("settings", "key", "value");
+        renamed.data = Some(BTreeMap::from([("key".to_string(), "other".to_string())]));
+        let err = configmaps
+            .create(&PostParams::default(), &renamed)
+            .await
+            .unwrap_err();
+
+        let kube::Error::Api(response) = err else {
+            panic!("expected an API error, got {err:?}");
+        };
+        assert_eq!(response.code, 500);
+        assert!(response.message.contains(
+            "request POST /api/v1/namespaces/default/configmaps? does not match any unreplayed interaction"
+        ));
+        assert!(response.message.contains(r#""key":"value""#));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "2 recorded request(s) in cassette")]
+    async fn test_assert_replayed_lists_missing_requests() {
+        let path = cassette_path();
+        record(&path).await;
+
+        let (client, handle) = ClientBuilder::new()
+            .with_replay(&path)
+            .build_with_handle()
+            .await
+            .unwrap();
+        Api::<ConfigMap>::default_namespaced(client)
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "key", "value"),
+            )
+            .await
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+        handle.assert_replayed();
+    }
+
+    #[tokio::test]
+    async fn test_replay_missing_cassette() {
+        let path = cassette_path();
+        let Err(err) = ClientBuilder::new().with_replay(&path).build().await else {
+            panic!("expected the build to fail");
+        };
+        assert!(err.to_string().contains("failed to read cassette"));
+    }
+}
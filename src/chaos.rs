@@ -0,0 +1,146 @@
+//! Chaos mode
+//!
+//! With [`ClientBuilder::with_chaos`](crate::ClientBuilder::with_chaos), requests
+//! fail at random with 500 Internal Error, 429 Too Many Requests or, for updates
+//! and patches, 409 Conflict, and are delayed by a random latency. The faults
+//! come from a seeded RNG, so a failing soak test can be replayed by building
+//! the client again with the seed it printed.
+
+use crate::{Error, Result};
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Random failures and latency injected into every request
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::{ChaosConfig, ClientBuilder};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClientBuilder::new()
+///     .with_chaos(ChaosConfig {
+///         error_rate: 0.1,
+///         latency_jitter: Duration::from_millis(20),
+///         seed: Some(42),
+///     })
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChaosConfig {
+    /// Fraction of requests that fail, between 0.0 and 1.0
+    pub error_rate: f64,
+    /// Upper bound of the delay added to each request; the delay is drawn
+    /// uniformly between zero and this, on the tokio clock
+    pub latency_jitter: Duration,
+    /// Seed for the RNG; a random seed is chosen if None
+    pub seed: Option<u64>,
+}
+
+/// The fault a request is disrupted with
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fault {
+    Internal,
+    TooManyRequests,
+    Conflict,
+}
+
+/// Seeded source of faults and latency shared by every request
+pub(crate) struct Chaos {
+    config: ChaosConfig,
+    seed: u64,
+    rng: Mutex<StdRng>,
+    /// Faults injected so far
+    injected: AtomicUsize,
+}
+
+impl Chaos {
+    pub(crate) fn new(config: ChaosConfig) -> Result<Self> {
+        if !(0.0..=1.0).contains(&config.error_rate) {
+            return Err(Error::InvalidConfiguration(format!(
+                "chaos error rate must be between 0.0 and 1.0, got {}",
+                config.error_rate
+            )));
+        }
+        let seed = config.seed.unwrap_or_else(rand::random);
+        Ok(Self {
+            config,
+            seed,
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            injected: AtomicUsize::new(0),
+        })
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Delay a request and decide whether it fails
+    ///
+    /// The delay and the fault are drawn together, so the sequence of outcomes
+    /// only depends on the seed and the order requests arrive in.
+    pub(crate) async fn disrupt(
+        &self,
+        verb: &str,
+        resource: &str,
+        name: Option<&str>,
+    ) -> Result<()> {
+        let (delay, fault) = {
+            let mut rng = self.rng.lock();
+            let delay = self.config.latency_jitter.mul_f64(rng.random::<f64>());
+            let fault = rng.random_bool(self.config.error_rate).then(|| {
+                let faults: &[Fault] = match verb {
+                    "update" | "patch" => {
+                        &[Fault::Internal, Fault::TooManyRequests, Fault::Conflict]
+                    }
+                    _ => &[Fault::Internal, Fault::TooManyRequests],
+                };
+                faults[rng.random_range(0..faults.len())]
+            });
+            (delay, fault)
+        };
+        if !delay.is_zero() {
+            crate::runtime::sleep(delay).await;
+        }
+
+        let Some(fault) = fault else {
+            return Ok(());
+        };
+        self.injected.fetch_add(1, Ordering::Relaxed);
+        let seed = self.seed;
+        Err(match fault {
+            Fault::Internal => {
+                Error::Internal(format!("chaos: injected internal error (seed {seed})"))
+            }
+            Fault::TooManyRequests => Error::TooManyRequests(format!(
+                "chaos: injected rate limit, please try again later (seed {seed})"
+            )),
+            Fault::Conflict => Error::Conflict(format!(
+                "Operation cannot be fulfilled on {resource} \"{}\": the object has been modified; please apply your changes to the latest version and try again (chaos seed {seed})",
+                name.unwrap_or_default()
+            )),
+        })
+    }
+}
+
+impl Drop for Chaos {
+    /// Print the seed when a test fails, so the run can be reproduced
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!(
+                "kube-fake-client chaos: {} fault(s) injected with seed {}; reproduce with ChaosConfig {{ seed: Some({}), .. }}",
+                self.injected.load(Ordering::Relaxed),
+                self.seed,
+                self.seed
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{configmap, in_namespace};
+    use crate::{ChaosConfig, ClientBuilder};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, ListParams, ObjectList, PostParams};
+    use std::time::Duration;
+
+    fn chaos(error_rate: f64, seed: u64) -> ChaosConfig {
+        ChaosConfig {
+            error_rate,
+            latency_jitter: Duration::ZERO,
+            seed: Some(seed),
+        }
+    }
+
+    async fn outcomes(seed: u64) -> Vec<u16> {
+        let client = ClientBuilder::new()
+            .with_objects(vec![in_namespace(configmap("settings"), "default")])
+            .with_chaos(chaos(0.5, seed))
+            .build()
+            .await
+            .unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        let mut codes = Vec::new();
+        for _ in 0..32 {
+            codes.push(match api.get("settings").await {
+                Ok(_) => 200,
+                Err(kube::Error::Api(response)) => response.code,
+                Err(e) => panic!("unexpected error: {e}"),
+            });
+        }
+        codes
+    }
+
+    #[tokio::test]
+    async fn test_chaos_fails_requests() {
+        let client = ClientBuilder::new()
+            .with_objects(vec![in_namespace(configmap("settings"), "default")])
+            .with_chaos(chaos(1.0, 7))
+            .build()
+            .await
+            .unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client.clone());
+
+        let mut codes = Vec::new();
+        for _ in 0..20 {
+            match api.get("settings").await {
+                Err(kube::Error::Api(response)) => {
+                    assert!(response.message.contains("seed 7"), "{}", response.message);
+                    codes.push(response.code);
+                }
+                other => panic!("expected an injected failure, got {other:?}"),
+            }
+        }
+        assert!(codes.iter().all(|code| matches!(code, 500 | 429)));
+        assert!(codes.contains(&500) && codes.contains(&429));
+
+        // Updates can also conflict
+        let mut codes = Vec::new();
+        for _ in 0..20 {
+            match api
+                .replace(
+                    "settings",
+                    &PostParams::default(),
+                    &in_namespace(configmap("settings"), "default"),
+                )
+                .await
+            {
+                Err(kube::Error::Api(response)) => codes.push(response.code),
+                other => panic!("expected an injected failure, got {other:?}"),
+            }
+        }
+        assert!(codes.contains(&409));
+
+        // Discovery is left alone
+        kube::Discovery::new(client).run().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_chaos_is_reproducible() {
+        let first = outcomes(42).await;
+        assert!(first.contains(&200));
+        assert!(first.iter().any(|code| *code != 200));
+        assert_eq!(first, outcomes(42).await);
+        assert_ne!(first, outcomes(43).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_chaos_latency() {
+        let (client, handle) = ClientBuilder::new()
+            .with_chaos(ChaosConfig {
+                error_rate: 0.0,
+                latency_jitter: Duration::from_secs(5),
+                seed: None,
+            })
+            .build_with_handle()
+            .await
+            .unwrap();
+        assert!(handle.chaos_seed().is_some());
+
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        let started = tokio::time::Instant::now();
+        for _ in 0..10 {
+            api.list(&ListParams::default()).await.unwrap();
+        }
+        let elapsed = started.elapsed();
+        assert!(elapsed > Duration::ZERO);
+        assert!(elapsed <= Duration::from_secs(50));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_list_latency_beyond_timeout_fails_with_504() {
+        let client = ClientBuilder::new()
+            .with_chaos(ChaosConfig {
+                error_rate: 0.0,
+                latency_jitter: Duration::from_secs(600),
+                seed: Some(7),
+            })
+            .build()
+            .await
+            .unwrap();
+        // kube's ListParams don't send timeoutSeconds; other clients do
+        let list = |query: &str| {
+            let request =
+                http::Request::get(format!("/api/v1/namespaces/default/configmaps{query}"))
+                    .body(Vec::new())
+                    .unwrap();
+            client.request::<ObjectList<ConfigMap>>(request)
+        };
+
+        let started = tokio::time::Instant::now();
+        let err = list("?timeoutSeconds=1").await.unwrap_err();
+        let kube::Error::Api(response) = err else {
+            panic!("expected an API error, got {err:?}");
+        };
+        assert_eq!(response.code, 504);
+        assert_eq!(response.reason, "Timeout");
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+
+        // Without a timeout the list waits out the latency
+        list("").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_chaos_rejects_invalid_error_rate() {
+        let result = ClientBuilder::new().with_chaos(chaos(1.5, 1)).build().await;
+        assert!(matches!(result, Err(crate::Error::InvalidConfiguration(_))));
+    }
+}
@@ -1,21 +1,46 @@
 //! Fake Kubernetes client for in-memory testing
 
+use crate::access_review::{self, AccessReviewHandler};
+use crate::audit::AuditLog;
+use crate::cassette::Replay;
+use crate::chaos::Chaos;
 use crate::client_utils::extract_gvk;
+use crate::configmap;
+use crate::csr;
 use crate::discovery::Discovery;
-use crate::field_selectors::extract_preregistered_field_value;
-use crate::gen::immutable::is_field_immutable;
+use crate::field_selectors::{
+    extract_preregistered_field_value, is_preregistered_field, selected_namespace,
+};
+use crate::garbage_collector::{self, Propagation};
+use crate::gen::immutable::{get_immutable_paths, is_field_immutable};
 use crate::interceptor;
 use crate::label_selector;
+use crate::managed_fields;
+use crate::metrics::MetricsRecorder;
+use crate::naming::MetadataRule;
+use crate::normalize::normalized;
+use crate::persistence::Persistence;
+use crate::pod;
+use crate::rbac::{self, Identity};
 use crate::registry::ResourceRegistry;
+use crate::scale;
+use crate::secret;
+use crate::service::{self, Allocations, Reservation, ServiceCidr};
+use crate::shard::{IndexKey, IndexLookup};
+use crate::stale::StaleReads;
+use crate::token_review::{self, TokenReviewHandler};
 use crate::tracker::{ObjectTracker, GVK, GVR};
 use crate::validator::SchemaValidator;
+use crate::warning::WarningRule;
 use crate::{Error, Result};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
 use kube::api::{ListParams, PatchParams, PostParams};
+use kube::core::Expression;
 use kube::Resource;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
 /// Server-managed metadata fields that should not be validated as immutable
@@ -26,27 +51,90 @@ const SERVER_MANAGED_FIELDS: &[&str] = &[
     "creationTimestamp", // Set by server on create
 ];
 
-/// Standard verbs supported by custom resources
-const STANDARD_CRD_VERBS: &[&str] = &[
-    "create",
-    "get",
-    "list",
-    "update",
-    "patch",
-    "delete",
-    "deletecollection",
-    "watch",
-];
+/// Kinds whose requests skip verb validation
+#[derive(Debug, Clone, Default)]
+pub(crate) enum PermissiveVerbs {
+    /// Every kind's verbs are validated
+    #[default]
+    None,
+    /// No kind's verbs are validated
+    All,
+    /// Verbs of these kinds are not validated
+    Kinds(HashSet<GVK>),
+}
+
+impl PermissiveVerbs {
+    pub(crate) fn add(&mut self, gvk: GVK) {
+        match self {
+            PermissiveVerbs::None => *self = PermissiveVerbs::Kinds(HashSet::from([gvk])),
+            PermissiveVerbs::All => {}
+            PermissiveVerbs::Kinds(kinds) => {
+                kinds.insert(gvk);
+            }
+        }
+    }
+
+    fn allows(&self, gvk: &GVK) -> bool {
+        match self {
+            PermissiveVerbs::None => false,
+            PermissiveVerbs::All => true,
+            PermissiveVerbs::Kinds(kinds) => kinds.contains(gvk),
+        }
+    }
+}
 
 /// Index function that extracts values from an object for indexing
 pub type IndexerFunc = Arc<dyn Fn(&Value) -> Vec<String> + Send + Sync>;
 
-/// Fake Kubernetes client for testing
+/// Defaulting function registered for a kind, filling in fields of an object
+/// before it is validated and stored
+pub(crate) type DefaulterFunc = Arc<dyn Fn(&mut Value) -> Result<()> + Send + Sync>;
+
+/// Typed access to the fake cluster, without going through `kube::Client`
+///
+/// Each method takes or returns a typed resource and reads or writes the store
+/// directly: no request is encoded or routed, so these calls are synchronous and
+/// cheap enough for pure unit tests of code that doesn't need a `kube::Client`.
+/// Get one sharing the state and configuration of a built client with
+/// [`ClientHandle::fake_client`](crate::ClientHandle::fake_client), or start from
+/// an empty cluster with [`FakeClient::new`].
+///
+/// Writes go through the same admission as requests do: verb checks, metadata
+/// rules, server-allocated fields, immutable field checks, defaulting, schema
+/// validation and managed fields. Watches and simulated controllers see them
+/// too. What only the HTTP path has:
+///
+/// - interceptors, chaos, stale reads and cassette replay
+/// - authorization, the audit log, metrics and warnings
+/// - query parameters: `dryRun`, pagination, `resourceVersion`,
+///   `gracePeriodSeconds` and `propagationPolicy`, so [`delete`](Self::delete)
+///   deletes as with default `DeleteOptions`
+/// - patch types other than JSON merge patch, and server-side apply
+/// - the `kube::Error::Api` responses: errors are returned as [`Error`]
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube::api::{ListParams, PostParams};
+/// use kube_fake_client::FakeClient;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = FakeClient::new();
+/// let mut configmap = ConfigMap::default();
+/// configmap.metadata.name = Some("settings".to_string());
+/// client.create("default", &configmap, &PostParams::default())?;
+///
+/// let configmaps: Vec<ConfigMap> = client.list(Some("default"), &ListParams::default())?;
+/// assert_eq!(configmaps.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
 pub struct FakeClient {
     /// Object tracker for storage
     pub(crate) tracker: Arc<ObjectTracker>,
     /// Registered indexes for field selectors
-    pub(crate) indexes: Arc<std::sync::RwLock<HashMap<GVK, HashMap<String, IndexerFunc>>>>,
+    pub(crate) indexes: Arc<parking_lot::RwLock<HashMap<GVK, HashMap<String, IndexerFunc>>>>,
     /// Whether to return managed fields
     pub(crate) return_managed_fields: bool,
     /// Interceptor functions for customizing behavior
@@ -55,18 +143,91 @@ pub struct FakeClient {
     pub(crate) registry: Arc<ResourceRegistry>,
     /// Schema validator for object validation (optional, no validation if None)
     pub(crate) validator: Option<Arc<dyn SchemaValidator>>,
+    /// Whether to apply schema defaults before validation
+    pub(crate) schema_defaulting: bool,
+    /// Defaulting functions by kind, run in registration order
+    pub(crate) defaulters: Arc<HashMap<GVK, Vec<DefaulterFunc>>>,
+    /// CIDR that Service cluster IPs are allocated from
+    pub(crate) service_cidr: ServiceCidr,
+    /// Cluster IPs and node ports reserved by Services
+    pub(crate) service_allocations: Arc<Allocations>,
+    /// Decides access reviews (allow everything if None)
+    pub(crate) access_review_handler: Option<AccessReviewHandler>,
+    /// Authenticates token reviews (reject every token if None)
+    pub(crate) token_review_handler: Option<TokenReviewHandler>,
+    /// User requests are authorized as (no authorization if None)
+    pub(crate) identity: Option<Identity>,
+    /// Requests served so far
+    pub(crate) audit: Arc<AuditLog>,
+    /// Request counts and latencies
+    pub(crate) metrics: Arc<MetricsRecorder>,
+    /// Whether update and patch bodies may name another object than the URL
+    pub(crate) allow_path_mismatch: bool,
+    /// Kinds whose requests skip verb validation
+    pub(crate) permissive_verbs: PermissiveVerbs,
+    /// Whether unknown or malformed query parameters fail requests
+    pub(crate) strict_query_params: bool,
+    /// Whether requests the fake can't serve panic instead of failing
+    pub(crate) panic_on_unsupported: bool,
+    /// Resources whose reads lag behind writes
+    pub(crate) stale_reads: Arc<StaleReads>,
+    /// Random faults and latency injected into requests (none if None)
+    pub(crate) chaos: Option<Arc<Chaos>>,
+    /// File the cluster state is saved to (not saved if None)
+    pub(crate) persistence: Option<Arc<Persistence>>,
+    /// Rules adding warnings to responses, in registration order
+    pub(crate) warning_rules: Arc<Vec<WarningRule>>,
+    /// Rules the metadata of created objects must follow
+    pub(crate) metadata_rules: Arc<Vec<Arc<dyn MetadataRule>>>,
+    /// Labels stamped onto every created object
+    pub(crate) injected_labels: Arc<BTreeMap<String, String>>,
+    /// Annotations stamped onto every created object
+    pub(crate) injected_annotations: Arc<BTreeMap<String, String>>,
+    /// Whether deletes honor their propagation policy, with dependents garbage
+    /// collected
+    pub(crate) garbage_collector: bool,
+    /// Whether created Pods get the image pull secrets of their ServiceAccount
+    pub(crate) service_account_pull_secrets: bool,
+    /// Recorded interactions served in place of the fake (none if None)
+    pub(crate) replay: Option<Arc<Replay>>,
 }
 
 impl FakeClient {
     /// Create a new fake client with default settings
     pub fn new() -> Self {
+        let tracker = Arc::new(ObjectTracker::new());
+        let service_allocations = Arc::new(Allocations::default());
+        tracker.add_removal_observer(service_allocations.removal_observer());
         Self {
-            tracker: Arc::new(ObjectTracker::new()),
-            indexes: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            tracker,
+            indexes: Arc::new(parking_lot::RwLock::new(HashMap::new())),
             return_managed_fields: false,
             interceptors: None,
             registry: Arc::new(ResourceRegistry::new()),
             validator: None,
+            schema_defaulting: false,
+            defaulters: Arc::default(),
+            service_cidr: ServiceCidr::default(),
+            service_allocations,
+            access_review_handler: None,
+            token_review_handler: None,
+            identity: None,
+            audit: Arc::new(AuditLog::default()),
+            metrics: Arc::default(),
+            allow_path_mismatch: false,
+            permissive_verbs: PermissiveVerbs::default(),
+            strict_query_params: false,
+            panic_on_unsupported: false,
+            stale_reads: Arc::default(),
+            chaos: None,
+            persistence: None,
+            warning_rules: Arc::default(),
+            metadata_rules: Arc::default(),
+            injected_labels: Arc::default(),
+            injected_annotations: Arc::default(),
+            garbage_collector: false,
+            service_account_pull_secrets: false,
+            replay: None,
         }
     }
 
@@ -77,10 +238,73 @@ impl FakeClient {
 
     /// Get an index function for a GVK and field
     pub fn get_index(&self, gvk: &GVK, field: &str) -> Option<IndexerFunc> {
-        let indexes = self.indexes.read().unwrap();
+        let indexes = self.indexes.read();
         indexes.get(gvk)?.get(field).cloned()
     }
 
+    /// Objects a list with these selectors may return, read through the tracker's indexes
+    ///
+    /// Equality and `in` label requirements and equality field requirements narrow
+    /// the read when the label or field is indexed. Label keys and pre-registered
+    /// fields are indexed the first time they are selected on, and fields with a
+    /// custom indexer are indexed when the client is built. A list across all
+    /// namespaces selecting `metadata.namespace` reads only that namespace. The
+    /// selectors still have to be matched against the result.
+    pub(crate) fn list_candidates(
+        &self,
+        gvr: &GVR,
+        gvk: &GVK,
+        namespace: Option<&str>,
+        label_selector: Option<&str>,
+        field_selector: Option<&str>,
+    ) -> Vec<Arc<Value>> {
+        let namespace = namespace.or_else(|| field_selector.and_then(selected_namespace));
+        let mut lookups: Vec<IndexLookup> = Vec::new();
+
+        let labels = label_selector.and_then(|s| label_selector::parse_label_selector(s).ok());
+        for expression in labels.into_iter().flatten() {
+            if let Expression::In(key, values) = expression {
+                let index = IndexKey::Label(key.clone());
+                self.tracker
+                    .add_index(gvr, index.clone(), IndexKey::label_indexer(&key));
+                lookups.push((index, values));
+            }
+        }
+
+        for requirement in field_selector.into_iter().flat_map(|s| s.split(',')) {
+            if requirement.contains("!=") {
+                continue;
+            }
+            let Some((field, value)) = requirement
+                .split_once("==")
+                .or_else(|| requirement.split_once('='))
+            else {
+                continue;
+            };
+            let (field, value) = (field.trim(), value.trim());
+            if field == "metadata.namespace" {
+                continue;
+            }
+            let index = IndexKey::Field(field.to_string());
+            if !self.tracker.has_index(gvr, &index) {
+                if !is_preregistered_field(field, &gvk.kind) {
+                    continue;
+                }
+                let (field, kind) = (field.to_string(), gvk.kind.clone());
+                self.tracker.add_index(
+                    gvr,
+                    index.clone(),
+                    Arc::new(move |obj| {
+                        extract_preregistered_field_value(obj, &field, &kind).unwrap_or_default()
+                    }),
+                );
+            }
+            lookups.push((index, BTreeSet::from([value.to_string()])));
+        }
+
+        self.tracker.list_indexed(gvr, namespace, &lookups)
+    }
+
     /// Convert a Kubernetes resource to GVR from JSON value using Discovery + Registry
     fn extract_gvr(&self, value: &Value) -> Result<GVR> {
         let gvk = extract_gvk(value)?;
@@ -93,36 +317,271 @@ impl FakeClient {
         })
     }
 
+    /// Get an object of a kind, ignoring the namespace if the kind is cluster-scoped
+    pub(crate) fn get_by_kind(&self, gvk: &GVK, namespace: &str, name: &str) -> Result<Arc<Value>> {
+        let gvr = Discovery::gvk_to_gvr_with_registry(gvk, &self.registry).ok_or_else(|| {
+            Error::ResourceNotRegistered {
+                group: gvk.group.clone(),
+                version: gvk.version.clone(),
+                resource: format!("{} (kind)", gvk.kind),
+            }
+        })?;
+        let namespaced =
+            Discovery::is_namespaced_with_registry(gvk, &self.registry).unwrap_or(true);
+        let namespace = if namespaced { namespace } else { "" };
+        self.tracker.get_shared(&gvr, namespace, name)
+    }
+
     /// Validate that a verb is supported for the given GVK
     ///
-    /// For built-in resources, checks Discovery data.
-    /// For CRDs (registered in registry), allows all standard verbs by default.
+    /// Checks the verbs of registered resources, standard verbs unless registered
+    /// otherwise, then Discovery data for built-in resources.
     pub(crate) fn validate_verb(&self, gvk: &GVK, verb: &str) -> Result<()> {
-        // Check if this is a built-in resource (in Discovery)
-        if Discovery::get_plural(gvk).is_some() {
-            // Built-in resource - check if verb is supported
-            if !Discovery::supports_verb(gvk, verb) {
-                return Err(Error::VerbNotSupported {
-                    verb: verb.to_string(),
-                    kind: gvk.kind.clone(),
-                });
-            }
-        } else if self
+        if self.permissive_verbs.allows(gvk) {
+            return Ok(());
+        }
+        // Resources in neither Discovery nor the registry fail elsewhere
+        if Discovery::supports_verb_with_registry(gvk, verb, &self.registry) == Some(false) {
+            return Err(Error::VerbNotSupported {
+                verb: verb.to_string(),
+                kind: gvk.kind.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether a kind serves a subresource, per its registration or discovery data
+    pub(crate) fn serves_subresource(&self, gvk: &GVK, subresource: &str) -> bool {
+        let registered = self
             .registry
             .lookup_by_kind(&gvk.group, &gvk.version, &gvk.kind)
-            .is_some()
-        {
-            // CRD registered in registry - allow all standard verbs
-            // This matches real Kubernetes behavior where CRDs support standard verbs by default
-            if !STANDARD_CRD_VERBS.contains(&verb) {
-                return Err(Error::VerbNotSupported {
-                    verb: verb.to_string(),
-                    kind: gvk.kind.clone(),
-                });
+            .is_some_and(|metadata| metadata.subresources.iter().any(|s| s == subresource));
+        registered
+            || (subresource == "status" && self.tracker.has_status_subresource(gvk))
+            || (subresource == "scale" && scale::mapping(self, gvk).is_some())
+            || Discovery::has_subresource(gvk, subresource)
+    }
+
+    /// Answer a create of a resource the API server evaluates instead of storing
+    ///
+    /// Returns `None` for regular resources, which are stored as usual.
+    pub(crate) fn review(
+        &self,
+        gvk: &GVK,
+        namespace: &str,
+        value: &Value,
+    ) -> Result<Option<Value>> {
+        // Without a handler, reviews are decided by RBAC once an identity is set
+        let rbac_handler;
+        let access_review_handler = match (&self.access_review_handler, &self.identity) {
+            (Some(handler), _) => Some(handler),
+            (None, Some(identity)) => {
+                rbac_handler = rbac::review_handler(Arc::clone(&self.tracker), identity.clone());
+                Some(&rbac_handler)
+            }
+            (None, None) => None,
+        };
+
+        let reviewed = match (gvk.group.as_str(), gvk.kind.as_str()) {
+            (
+                "authorization.k8s.io",
+                kind @ ("SubjectAccessReview"
+                | "SelfSubjectAccessReview"
+                | "LocalSubjectAccessReview"),
+            ) => access_review::review(access_review_handler, kind, namespace, value.clone())?,
+            ("authentication.k8s.io", "TokenReview") => {
+                token_review::review(self.token_review_handler.as_ref(), value.clone())?
             }
-        } else {
-            // Resource not found in Discovery or Registry - will fail elsewhere
-            // Skip verb validation for now
+            _ => return Ok(None),
+        };
+        Ok(Some(reviewed))
+    }
+
+    /// Fill in server-assigned fields and normalize an object before it is first stored
+    ///
+    /// Cluster IPs and node ports assigned to a Service stay reserved if the
+    /// returned reservation is committed once the object is stored, and are
+    /// released if it is dropped.
+    pub(crate) fn prepare_for_create(
+        &self,
+        gvk: &GVK,
+        namespace: &str,
+        value: &mut Value,
+    ) -> Result<Reservation> {
+        self.inject_metadata(value);
+        self.check_metadata_rules(gvk, namespace, value)?;
+        let mut reservation = Reservation::none();
+        match (gvk.group.as_str(), gvk.kind.as_str()) {
+            ("", "Service") => {
+                reservation = service::prepare_for_create(
+                    &self.service_cidr,
+                    &self.service_allocations,
+                    &self.tracker,
+                    namespace,
+                    value,
+                )?
+            }
+            ("", "Secret") => secret::prepare(value)?,
+            ("", "ConfigMap") => configmap::validate(value)?,
+            ("", "Pod") if self.service_account_pull_secrets => {
+                pod::project_image_pull_secrets(&self.tracker, namespace, value)
+            }
+            _ => {}
+        }
+        // As for a CRD with the status subresource enabled, the status of a
+        // registered kind can only be written through the subresource
+        if self.tracker.is_status_subresource_registered(gvk) {
+            if let Some(object) = value.as_object_mut() {
+                object.remove("status");
+            }
+        }
+        Ok(reservation)
+    }
+
+    /// Stamp the injected labels and annotations onto an object to be created,
+    /// replacing values it sets for the same keys
+    fn inject_metadata(&self, value: &mut Value) {
+        if !value.is_object() {
+            return;
+        }
+        for (field, injected) in [
+            ("labels", &self.injected_labels),
+            ("annotations", &self.injected_annotations),
+        ] {
+            if injected.is_empty() {
+                continue;
+            }
+            let map = &mut value["metadata"][field];
+            if !map.is_object() {
+                *map = Value::Object(Default::default());
+            }
+            for (key, injected) in injected.iter() {
+                map[key] = Value::String(injected.clone());
+            }
+        }
+    }
+
+    /// Fail with 422 Invalid if an object to be created breaks a metadata rule
+    pub(crate) fn check_metadata_rules(
+        &self,
+        gvk: &GVK,
+        namespace: &str,
+        value: &Value,
+    ) -> Result<()> {
+        if self.metadata_rules.is_empty() {
+            return Ok(());
+        }
+        let mut metadata: ObjectMeta = value
+            .get("metadata")
+            .map(|metadata| serde_json::from_value(metadata.clone()))
+            .transpose()?
+            .unwrap_or_default();
+        if metadata.namespace.as_deref().is_none_or(str::is_empty) && !namespace.is_empty() {
+            metadata.namespace = Some(namespace.to_string());
+        }
+        for rule in self.metadata_rules.iter() {
+            if let Err(problem) = rule.validate(gvk, &metadata) {
+                return Err(Error::InvalidRequest(format!(
+                    "{} {:?} is invalid: {problem}",
+                    gvk.kind,
+                    metadata.name.as_deref().unwrap_or_default()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Carry over server-assigned fields and normalize an object being updated
+    ///
+    /// As on create, the returned reservation is committed once the object is
+    /// stored.
+    pub(crate) fn prepare_for_update(
+        &self,
+        gvk: &GVK,
+        namespace: &str,
+        old: &Value,
+        new: &mut Value,
+    ) -> Result<Reservation> {
+        match (gvk.group.as_str(), gvk.kind.as_str()) {
+            ("", "Service") => {
+                return service::prepare_for_update(
+                    &self.service_cidr,
+                    &self.service_allocations,
+                    &self.tracker,
+                    namespace,
+                    old,
+                    new,
+                )
+            }
+            ("", "Secret") => secret::prepare(new)?,
+            ("", "ConfigMap") => configmap::validate(new)?,
+            _ => {}
+        }
+        Ok(Reservation::none())
+    }
+
+    /// Restrict and validate a write to a subresource such as `status`
+    pub(crate) fn prepare_for_subresource(
+        &self,
+        gvk: &GVK,
+        subresource: &str,
+        old: &Value,
+        new: &mut Value,
+    ) -> Result<()> {
+        match (gvk.group.as_str(), gvk.kind.as_str(), subresource) {
+            ("certificates.k8s.io", "CertificateSigningRequest", "approval") => {
+                csr::prepare_approval(old, new)?
+            }
+            ("certificates.k8s.io", "CertificateSigningRequest", "status") => {
+                csr::prepare_status(old, new)?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Record which manager owns which fields of an object before storage, when
+    /// managed fields are returned
+    pub(crate) fn record_managed_fields(
+        &self,
+        gvk: &GVK,
+        existing: Option<&Value>,
+        value: &mut Value,
+        write: &managed_fields::Write<'_>,
+    ) {
+        if self.return_managed_fields {
+            let has_status_subresource = self.tracker.has_status_subresource(gvk);
+            managed_fields::record(existing, value, write, has_status_subresource);
+        }
+    }
+
+    /// Run schema defaulting (when enabled), the kind's registered defaulters and
+    /// validation on an object before storage
+    #[cfg_attr(
+        feature = "instrumentation",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(group = %gvk.group, version = %gvk.version, kind = %gvk.kind),
+            err(level = "debug")
+        )
+    )]
+    pub(crate) fn admit(&self, gvk: &GVK, value: &mut Value) -> Result<()> {
+        if let Some(validator) = &self.validator {
+            // As for CRDs with structural schemas, unknown fields of custom
+            // resources are dropped before defaulting
+            if Discovery::get_plural(gvk).is_none() {
+                validator.prune(&gvk.group, &gvk.version, &gvk.kind, value)?;
+            }
+            if self.schema_defaulting {
+                validator.apply_defaults(&gvk.group, &gvk.version, &gvk.kind, value)?;
+            }
+        }
+        for defaulter in self.defaulters.get(gvk).into_iter().flatten() {
+            defaulter(value)?;
+        }
+        if let Some(validator) = &self.validator {
+            validator.validate(&gvk.group, &gvk.version, &gvk.kind, value)?;
         }
         Ok(())
     }
@@ -134,8 +593,15 @@ impl FakeClient {
     /// - Top-level fields are checked against the resource Kind
     /// - Fields under "metadata" are checked against ObjectMeta
     /// - Fields under "spec" are checked against {Kind}Spec
+    /// - Nested paths from `get_immutable_paths` are walked, matching list items by merge key
+    /// - ConfigMaps and Secrets marked `immutable: true` reject any data change
+    ///
+    /// Both objects are normalized first, so `null`s and empty maps or lists
+    /// left by serialization don't count as changes.
     #[doc(hidden)] // Internal API exposed for testing
     pub fn validate_immutable_fields(&self, gvk: &GVK, old: &Value, new: &Value) -> Result<()> {
+        let (old, new) = (&normalized(old), &normalized(new));
+
         // Check top-level fields against the resource Kind
         self.check_immutable_object(&gvk.group, &gvk.version, &gvk.kind, old, new, "")?;
 
@@ -157,9 +623,98 @@ impl FakeClient {
             )?;
         }
 
+        // Check nested paths enforced by apiserver validation
+        if let Some(paths) = get_immutable_paths(&gvk.group, &gvk.version, &gvk.kind) {
+            for path in paths {
+                let segments: Vec<&str> = path.split('.').collect();
+                Self::check_immutable_path(Some(old), Some(new), &segments, "")?;
+            }
+        }
+
+        // ConfigMaps and Secrets can be frozen with `immutable: true`
+        if gvk.group.is_empty() && (gvk.kind == "ConfigMap" || gvk.kind == "Secret") {
+            let frozen = old.get("immutable").and_then(|v| v.as_bool()) == Some(true);
+            if frozen {
+                for field in ["immutable", "data", "binaryData"] {
+                    if old.get(field) != new.get(field) {
+                        return Err(Error::ImmutableField {
+                            field: field.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// Check a nested immutable path, descending into list items by merge key
+    ///
+    /// Immutable paths are set-once: an unset old value may be populated, but a set
+    /// value cannot be changed or removed.
+    fn check_immutable_path(
+        old: Option<&Value>,
+        new: Option<&Value>,
+        segments: &[&str],
+        path_prefix: &str,
+    ) -> Result<()> {
+        let old = match old {
+            Some(v) if !v.is_null() && v.as_str() != Some("") => v,
+            _ => return Ok(()),
+        };
+
+        let (segment, rest) = match segments.split_first() {
+            Some(split) => split,
+            None => {
+                if new != Some(old) {
+                    return Err(Error::ImmutableField {
+                        field: path_prefix.to_string(),
+                    });
+                }
+                return Ok(());
+            }
+        };
+
+        let join = |field: &str| {
+            if path_prefix.is_empty() {
+                field.to_string()
+            } else {
+                format!("{}.{}", path_prefix, field)
+            }
+        };
+
+        // `field[key]` descends into list items matched by merge key
+        if let Some((field, key)) = segment.strip_suffix(']').and_then(|s| s.split_once('[')) {
+            let old_items = old.get(field).and_then(|v| v.as_array());
+            let new_items = new.and_then(|v| v.get(field)).and_then(|v| v.as_array());
+            if let (Some(old_items), Some(new_items)) = (old_items, new_items) {
+                for (index, new_item) in new_items.iter().enumerate() {
+                    let Some(merge_key) = new_item.get(key) else {
+                        continue;
+                    };
+                    if let Some(old_item) = old_items.iter().find(|i| i.get(key) == Some(merge_key))
+                    {
+                        let item_path = join(&format!("{}[{}]", field, index));
+                        Self::check_immutable_path(
+                            Some(old_item),
+                            Some(new_item),
+                            rest,
+                            &item_path,
+                        )?;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        Self::check_immutable_path(
+            old.get(*segment),
+            new.and_then(|v| v.get(*segment)),
+            rest,
+            &join(segment),
+        )
+    }
+
     /// Check immutable fields within a specific object (non-recursive on nested objects)
     fn check_immutable_object(
         &self,
@@ -210,23 +765,36 @@ impl FakeClient {
     }
 
     /// Create an object
-    pub fn create<K>(&self, namespace: &str, obj: &K, _params: &PostParams) -> Result<K>
+    pub fn create<K>(&self, namespace: &str, obj: &K, params: &PostParams) -> Result<K>
     where
         K: Resource + Serialize + DeserializeOwned + Clone,
     {
-        let value = serde_json::to_value(obj)?;
+        let mut value = serde_json::to_value(obj)?;
         let gvr = self.extract_gvr(&value)?;
         let gvk = extract_gvk(&value)?;
 
         // Validate that create verb is supported
         self.validate_verb(&gvk, "create")?;
 
-        // Validate schema if validator is configured
-        if let Some(validator) = &self.validator {
-            validator.validate(&gvk.group, &gvk.version, &gvk.kind, &value)?;
-        }
-
-        let created = self.tracker.create(&gvr, &gvk, value, namespace)?;
+        // Assign server-allocated fields such as Service cluster IPs
+        let reservation = self.prepare_for_create(&gvk, namespace, &mut value)?;
+
+        // Apply schema defaults and validate if a validator is configured
+        self.admit(&gvk, &mut value)?;
+        self.record_managed_fields(
+            &gvk,
+            None,
+            &mut value,
+            &managed_fields::Write {
+                manager: field_manager(params.field_manager.as_deref()),
+                operation: managed_fields::Operation::Update,
+                subresource: None,
+                applied: None,
+            },
+        );
+
+        let created = self.tracker.create(&gvr, value, namespace)?;
+        reservation.commit();
 
         let mut result: K = serde_json::from_value(created)?;
 
@@ -261,12 +829,75 @@ impl FakeClient {
         Ok(result)
     }
 
+    /// Get an object without blocking
+    ///
+    /// Returns `None` while a write holds the lock of the object's resource in
+    /// its namespace, so tests can check reads stay responsive while other
+    /// operations run.
+    pub fn try_get<K>(&self, namespace: &str, name: &str) -> Option<Result<K>>
+    where
+        K: Resource + Serialize + DeserializeOwned + Default,
+    {
+        let gvr = match self.resource_for::<K>("get") {
+            Ok(gvr) => gvr,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let value = match self.tracker.try_get(&gvr, namespace, name)? {
+            Ok(value) => value,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(self.typed(value))
+    }
+
+    /// List objects without blocking
+    ///
+    /// Returns `None` while a write holds the lock of the resource in any
+    /// namespace listed. Selectors aren't supported; use [`FakeClient::list`]
+    /// to filter.
+    pub fn try_list<K>(&self, namespace: Option<&str>) -> Option<Result<Vec<K>>>
+    where
+        K: Resource + Serialize + DeserializeOwned + Default,
+    {
+        let gvr = match self.resource_for::<K>("list") {
+            Ok(gvr) => gvr,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let values = self.tracker.try_list(&gvr, namespace)?;
+        Some(values.into_iter().map(|value| self.typed(value)).collect())
+    }
+
+    /// The resource of a kind, if the verb is supported on it
+    fn resource_for<K>(&self, verb: &str) -> Result<GVR>
+    where
+        K: Serialize + Default,
+    {
+        let dummy_value = serde_json::to_value(K::default())?;
+        let gvr = self.extract_gvr(&dummy_value)?;
+        let gvk = extract_gvk(&dummy_value)?;
+        self.validate_verb(&gvk, verb)?;
+        Ok(gvr)
+    }
+
+    /// Deserialize a stored object, dropping managed fields unless configured to return them
+    fn typed<K>(&self, value: Value) -> Result<K>
+    where
+        K: Resource + DeserializeOwned,
+    {
+        let mut result: K = serde_json::from_value(value)?;
+        if !self.return_managed_fields {
+            result.meta_mut().managed_fields = None;
+        }
+        Ok(result)
+    }
+
     /// Update an object (replaces the entire object)
-    pub fn update<K>(&self, namespace: &str, obj: &K, _params: &PostParams) -> Result<K>
+    pub fn update<K>(&self, namespace: &str, obj: &K, params: &PostParams) -> Result<K>
     where
         K: Resource + Serialize + DeserializeOwned + Clone,
     {
-        let value = serde_json::to_value(obj)?;
+        let mut value = serde_json::to_value(obj)?;
         let gvr = self.extract_gvr(&value)?;
         let gvk = extract_gvk(&value)?;
 
@@ -316,15 +947,30 @@ impl FakeClient {
             Err(e) => return Err(e),
         };
 
+        ObjectTracker::check_resource_version(&existing, &value)?;
+
+        // Keep server-allocated fields the request did not echo back
+        let reservation = self.prepare_for_update(&gvk, namespace, &existing, &mut value)?;
+
         // Validate that no immutable fields have changed
         self.validate_immutable_fields(&gvk, &existing, &value)?;
 
-        // Validate schema if validator is configured
-        if let Some(validator) = &self.validator {
-            validator.validate(&gvk.group, &gvk.version, &gvk.kind, &value)?;
-        }
+        // Apply schema defaults and validate if a validator is configured
+        self.admit(&gvk, &mut value)?;
+        self.record_managed_fields(
+            &gvk,
+            Some(&existing),
+            &mut value,
+            &managed_fields::Write {
+                manager: field_manager(params.field_manager.as_deref()),
+                operation: managed_fields::Operation::Update,
+                subresource: None,
+                applied: None,
+            },
+        );
 
         let updated = self.tracker.update(&gvr, &gvk, value, namespace, false)?;
+        reservation.commit();
 
         let mut result: K = serde_json::from_value(updated)?;
 
@@ -336,22 +982,19 @@ impl FakeClient {
     }
 
     /// Update the status subresource
-    #[allow(dead_code)]
     pub fn update_status<K>(&self, namespace: &str, obj: &K, _params: &PostParams) -> Result<K>
     where
         K: Resource + Serialize + DeserializeOwned + Clone,
     {
-        let value = serde_json::to_value(obj)?;
+        let mut value = serde_json::to_value(obj)?;
         let gvr = self.extract_gvr(&value)?;
         let gvk = extract_gvk(&value)?;
 
         // Validate that update verb is supported (status uses same verb)
         self.validate_verb(&gvk, "update")?;
 
-        // Validate schema if validator is configured
-        if let Some(validator) = &self.validator {
-            validator.validate(&gvk.group, &gvk.version, &gvk.kind, &value)?;
-        }
+        // Apply schema defaults and validate if a validator is configured
+        self.admit(&gvk, &mut value)?;
 
         let updated = self.tracker.update(&gvr, &gvk, value, namespace, true)?;
 
@@ -364,8 +1007,11 @@ impl FakeClient {
         Ok(result)
     }
 
-    /// Delete an object
-    #[allow(dead_code)]
+    /// Delete an object, as a request with default `DeleteOptions` would
+    ///
+    /// Scheduled Pods terminate over their grace period rather than being removed
+    /// at once, and with the garbage collector enabled, dependents are deleted in
+    /// the background.
     pub fn delete<K>(&self, namespace: &str, name: &str) -> Result<K>
     where
         K: Resource + Serialize + DeserializeOwned + Default,
@@ -378,12 +1024,35 @@ impl FakeClient {
         // Validate that delete verb is supported
         self.validate_verb(&gvk, "delete")?;
 
-        let value = self.tracker.delete(&gvr, namespace, name)?;
+        let value = self.delete_object(&gvr, namespace, name, None, Propagation::Background)?;
 
         let result: K = serde_json::from_value(value)?;
         Ok(result)
     }
 
+    /// Delete an object the way the API server does: dependents per the
+    /// propagation policy, and Pods after their grace period
+    pub(crate) fn delete_object(
+        &self,
+        gvr: &GVR,
+        namespace: &str,
+        name: &str,
+        grace_period_seconds: Option<i64>,
+        propagation: Propagation,
+    ) -> Result<Value> {
+        if self.garbage_collector {
+            if let Some(result) =
+                garbage_collector::begin_deletion(&self.tracker, gvr, namespace, name, propagation)
+            {
+                return result;
+            }
+        }
+        if *gvr == pod::pods_gvr() {
+            return pod::delete(&self.tracker, namespace, name, grace_period_seconds);
+        }
+        self.tracker.delete(gvr, namespace, name)
+    }
+
     /// List objects
     pub fn list<K>(&self, namespace: Option<&str>, params: &ListParams) -> Result<Vec<K>>
     where
@@ -397,25 +1066,25 @@ impl FakeClient {
         // Validate that list verb is supported
         self.validate_verb(&gvk, "list")?;
 
-        let values = self.tracker.list(&gvr, namespace)?;
+        let values = self.list_candidates(
+            &gvr,
+            &gvk,
+            namespace,
+            params.label_selector.as_deref(),
+            params.field_selector.as_deref(),
+        );
 
         let mut results: Vec<K> = values
-            .into_iter()
-            .map(|v| serde_json::from_value(v))
+            .iter()
+            .filter(|v| {
+                params
+                    .label_selector
+                    .as_deref()
+                    .is_none_or(|selector| label_selector::matches_object(v, selector))
+            })
+            .map(|v| K::deserialize(v.as_ref()))
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        // Apply label selector
-        if let Some(label_selector_str) = &params.label_selector {
-            results.retain(|obj| {
-                let meta = obj.meta();
-                if let Some(labels) = &meta.labels {
-                    return label_selector::matches_label_selector(labels, label_selector_str)
-                        .unwrap_or(false);
-                }
-                false
-            });
-        }
-
         // Apply field selector
         if let Some(field_selector) = &params.field_selector {
             results = self.filter_by_field_selector(results, &gvk, field_selector)?;
@@ -449,7 +1118,7 @@ impl FakeClient {
                 let requirement = requirement.trim();
                 if let Some((field, expected_value)) = requirement.split_once('=') {
                     let field = field.trim_end_matches('=');
-                    let expected_value = expected_value.trim();
+                    let expected_value = expected_value.trim_start_matches('=').trim();
 
                     let obj_value = serde_json::to_value(&obj)?;
 
@@ -490,7 +1159,7 @@ impl FakeClient {
         namespace: &str,
         name: &str,
         patch: &Value,
-        _params: &PatchParams,
+        params: &PatchParams,
     ) -> Result<K>
     where
         K: Resource + Serialize + DeserializeOwned + Default,
@@ -507,15 +1176,28 @@ impl FakeClient {
         let mut patched = existing.clone();
         json_patch::merge(&mut patched, patch);
 
+        // Keep server-allocated fields the patch removed
+        let reservation = self.prepare_for_update(&gvk, namespace, &existing, &mut patched)?;
+
         // Validate that no immutable fields have changed
         self.validate_immutable_fields(&gvk, &existing, &patched)?;
 
-        // Validate the merged result
-        if let Some(validator) = &self.validator {
-            validator.validate(&gvk.group, &gvk.version, &gvk.kind, &patched)?;
-        }
+        // Apply schema defaults and validate the merged result
+        self.admit(&gvk, &mut patched)?;
+        self.record_managed_fields(
+            &gvk,
+            Some(&existing),
+            &mut patched,
+            &managed_fields::Write {
+                manager: field_manager(params.field_manager.as_deref()),
+                operation: managed_fields::Operation::Update,
+                subresource: None,
+                applied: None,
+            },
+        );
 
         let updated = self.tracker.update(&gvr, &gvk, patched, namespace, false)?;
+        reservation.commit();
 
         let mut result: K = serde_json::from_value(updated)?;
 
@@ -542,6 +1224,34 @@ impl Clone for FakeClient {
             interceptors: self.interceptors.clone(),
             registry: Arc::clone(&self.registry),
             validator: self.validator.clone(),
+            schema_defaulting: self.schema_defaulting,
+            defaulters: Arc::clone(&self.defaulters),
+            service_cidr: self.service_cidr,
+            service_allocations: Arc::clone(&self.service_allocations),
+            access_review_handler: self.access_review_handler.clone(),
+            token_review_handler: self.token_review_handler.clone(),
+            identity: self.identity.clone(),
+            audit: Arc::clone(&self.audit),
+            metrics: Arc::clone(&self.metrics),
+            allow_path_mismatch: self.allow_path_mismatch,
+            permissive_verbs: self.permissive_verbs.clone(),
+            strict_query_params: self.strict_query_params,
+            panic_on_unsupported: self.panic_on_unsupported,
+            stale_reads: Arc::clone(&self.stale_reads),
+            chaos: self.chaos.clone(),
+            persistence: self.persistence.clone(),
+            warning_rules: Arc::clone(&self.warning_rules),
+            metadata_rules: Arc::clone(&self.metadata_rules),
+            injected_labels: Arc::clone(&self.injected_labels),
+            injected_annotations: Arc::clone(&self.injected_annotations),
+            garbage_collector: self.garbage_collector,
+            service_account_pull_secrets: self.service_account_pull_secrets,
+            replay: self.replay.clone(),
         }
     }
 }
+
+/// The manager a write is recorded under, given the one it names
+pub(crate) fn field_manager(field_manager: Option<&str>) -> &str {
+    field_manager.unwrap_or(managed_fields::DEFAULT_MANAGER)
+}
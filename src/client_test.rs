@@ -171,6 +171,44 @@ mod tests {
         assert_eq!(updated.metadata.resource_version, Some("3".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_label_selector_matches_objects_without_labels() {
+        let (kube_client, handle) = crate::ClientBuilder::new()
+            .build_with_handle()
+            .await
+            .unwrap();
+        let client = handle.fake_client();
+        for (name, labels) in [("bare", None), ("web", Some("web")), ("db", Some("db"))] {
+            let mut pod = Pod::default();
+            pod.metadata.name = Some(name.to_string());
+            pod.metadata.labels = labels.map(|app| [("app".to_string(), app.to_string())].into());
+            client
+                .create("default", &pod, &PostParams::default())
+                .unwrap();
+        }
+        let pods: kube::Api<Pod> = kube::Api::namespaced(kube_client, "default");
+
+        for (selector, expected) in [
+            ("!app", vec!["bare"]),
+            ("app notin (web)", vec!["bare", "db"]),
+            ("app!=db", vec!["bare", "web"]),
+            ("app", vec!["db", "web"]),
+        ] {
+            let params = ListParams::default().labels(selector);
+            let names = |pods: Vec<Pod>| -> Vec<String> {
+                let mut names: Vec<String> =
+                    pods.into_iter().filter_map(|p| p.metadata.name).collect();
+                names.sort();
+                names
+            };
+            let typed = names(client.list(Some("default"), &params).unwrap());
+            assert_eq!(typed, expected, "{selector}");
+            // The HTTP path selects the same objects
+            let served = names(pods.list(&params).await.unwrap().items);
+            assert_eq!(served, typed, "{selector}");
+        }
+    }
+
     #[test]
     fn test_field_selector_metadata_name() {
         let client = FakeClient::new();
@@ -599,4 +637,176 @@ mod tests {
         let result = client.patch::<Pod>("default", "test-pod", &patch, &PatchParams::default());
         assert!(result.is_ok(), "Patching mutable fields should succeed");
     }
+
+    #[test]
+    fn test_immutable_field_validation_nested_path() {
+        use k8s_openapi::api::apps::v1::Deployment;
+
+        let client = FakeClient::new();
+        let deployment: Deployment = serde_json::from_value(serde_json::json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "web", "namespace": "default" },
+            "spec": {
+                "selector": { "matchLabels": { "app": "web" } },
+                "template": { "metadata": { "labels": { "app": "web" } } }
+            }
+        }))
+        .unwrap();
+        client
+            .create("default", &deployment, &PostParams::default())
+            .unwrap();
+
+        // Changing the selector is rejected
+        let patch = serde_json::json!({
+            "spec": { "selector": { "matchLabels": { "app": "api" } } }
+        });
+        let result = client.patch::<Deployment>("default", "web", &patch, &PatchParams::default());
+        match result {
+            Err(crate::Error::ImmutableField { field }) => {
+                assert_eq!(field, "spec.selector");
+            }
+            Ok(_) => panic!("Expected ImmutableField error, got success"),
+            Err(e) => panic!("Expected ImmutableField error, got: {:?}", e),
+        }
+
+        // Scaling remains allowed
+        let patch = serde_json::json!({ "spec": { "replicas": 3 } });
+        let result = client.patch::<Deployment>("default", "web", &patch, &PatchParams::default());
+        assert!(result.is_ok(), "Patching replicas should succeed");
+    }
+
+    #[test]
+    fn test_immutable_field_validation_list_items_by_merge_key() {
+        let client = FakeClient::new();
+        let gvk = crate::tracker::GVK::new("batch", "v1", "Job");
+
+        let old = serde_json::json!({
+            "apiVersion": "batch/v1",
+            "kind": "Job",
+            "metadata": { "name": "migrate", "namespace": "default" },
+            "spec": { "template": { "spec": { "containers": [
+                { "name": "sidecar", "image": "proxy:1" },
+                { "name": "main", "image": "app:1" }
+            ] } } }
+        });
+
+        // Reordering containers without changing them is allowed
+        let mut reordered = old.clone();
+        reordered["spec"]["template"]["spec"]["containers"] = serde_json::json!([
+            { "name": "main", "image": "app:1" },
+            { "name": "sidecar", "image": "proxy:1" }
+        ]);
+        assert!(client
+            .validate_immutable_fields(&gvk, &old, &reordered)
+            .is_ok());
+
+        // Changing an image is reported at the item's position in the new object
+        let mut changed = reordered.clone();
+        changed["spec"]["template"]["spec"]["containers"][1]["image"] =
+            serde_json::json!("proxy:2");
+        match client.validate_immutable_fields(&gvk, &old, &changed) {
+            Err(crate::Error::ImmutableField { field }) => {
+                assert_eq!(field, "spec.template.spec.containers[1].image");
+            }
+            Ok(_) => panic!("Expected ImmutableField error, got success"),
+            Err(e) => panic!("Expected ImmutableField error, got: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_immutable_field_validation_set_once() {
+        let client = FakeClient::new();
+        let gvk = crate::tracker::GVK::new("", "v1", "Service");
+
+        let unset = serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "Service",
+            "metadata": { "name": "web", "namespace": "default" },
+            "spec": { "clusterIP": "" }
+        });
+        let mut assigned = unset.clone();
+        assigned["spec"]["clusterIP"] = serde_json::json!("10.96.0.10");
+        let mut reassigned = unset.clone();
+        reassigned["spec"]["clusterIP"] = serde_json::json!("10.96.0.11");
+
+        // Unset -> set is allowed, set -> different value is not
+        assert!(client
+            .validate_immutable_fields(&gvk, &unset, &assigned)
+            .is_ok());
+        match client.validate_immutable_fields(&gvk, &assigned, &reassigned) {
+            Err(crate::Error::ImmutableField { field }) => {
+                assert_eq!(field, "spec.clusterIP");
+            }
+            Ok(_) => panic!("Expected ImmutableField error, got success"),
+            Err(e) => panic!("Expected ImmutableField error, got: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_immutable_configmap_rejects_data_changes() {
+        use k8s_openapi::api::core::v1::ConfigMap;
+
+        let client = FakeClient::new();
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("frozen".to_string());
+        cm.metadata.namespace = Some("default".to_string());
+        cm.immutable = Some(true);
+        cm.data = Some([("key".to_string(), "value".to_string())].into());
+
+        let created = client
+            .create("default", &cm, &PostParams::default())
+            .unwrap();
+
+        let mut updated = created.clone();
+        updated.data = Some([("key".to_string(), "other".to_string())].into());
+        match client.update("default", &updated, &PostParams::default()) {
+            Err(crate::Error::ImmutableField { field }) => {
+                assert_eq!(field, "data");
+            }
+            Ok(_) => panic!("Expected ImmutableField error, got success"),
+            Err(e) => panic!("Expected ImmutableField error, got: {:?}", e),
+        }
+
+        // A stale copy conflicts before its change is validated
+        let mut stale = updated.clone();
+        stale.metadata.resource_version = Some("0".to_string());
+        assert!(matches!(
+            client.update("default", &stale, &PostParams::default()),
+            Err(crate::Error::Conflict(_))
+        ));
+
+        // Metadata can still change
+        let mut labeled = created.clone();
+        labeled.metadata.labels = Some([("env".to_string(), "test".to_string())].into());
+        assert!(client
+            .update("default", &labeled, &PostParams::default())
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fake_client_shares_handle_state() {
+        use kube::api::Api;
+
+        let (client, handle) = crate::ClientBuilder::new()
+            .with_metadata_rule(crate::naming::StandardNames)
+            .build_with_handle()
+            .await
+            .unwrap();
+        let fake = handle.fake_client();
+
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web".to_string());
+        fake.create("default", &pod, &PostParams::default())
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        assert!(pods.get_opt("web").await.unwrap().is_some());
+
+        // The builder's configuration applies to typed writes as well
+        pod.metadata.name = Some("Web".to_string());
+        let err = fake
+            .create("default", &pod, &PostParams::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("RFC 1123 subdomain"), "{err}");
+    }
 }
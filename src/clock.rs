@@ -0,0 +1,25 @@
+//! Virtual wall clock driven by `tokio::time`
+//!
+//! Timestamps the fake writes, such as `creationTimestamp` and the
+//! `deletionTimestamp` of a Pod inside its grace period, are read from this clock
+//! rather than the system clock. It follows the system clock while tokio time
+//! runs normally; while tokio time is paused it stands still and moves forward
+//! with `tokio::time::advance`, in step with the fake's timers, so timestamps and
+//! the delays they describe agree in tests with paused time.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use std::time::Instant;
+
+/// The system time and instant the clock is measured from
+static ANCHOR: Lazy<(DateTime<Utc>, Instant)> = Lazy::new(|| (Utc::now(), Instant::now()));
+
+/// The current time on the tokio clock
+pub(crate) fn now() -> DateTime<Utc> {
+    let (wall, anchor) = *ANCHOR;
+    let instant = tokio::time::Instant::now().into_std();
+    match instant.checked_duration_since(anchor) {
+        Some(elapsed) => wall + elapsed,
+        None => wall - anchor.duration_since(instant),
+    }
+}
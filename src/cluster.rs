@@ -0,0 +1,228 @@
+//! Several independent fake clusters for multi-cluster operators
+//!
+//! Fleet managers and replication controllers talk to more than one API server.
+//! Each [`Cluster`] is a fake client built from its own
+//! [`ClientBuilder`](crate::ClientBuilder), with its own storage, and
+//! [`Clusters`] groups them by name so tests can hand each cluster's client to
+//! the operator and assert across clusters, for example that an object created
+//! in one was mirrored to another.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::{ClientBuilder, Clusters};
+//! use k8s_openapi::api::core::v1::ConfigMap;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let clusters = Clusters::new([
+//!     ClientBuilder::new().build_cluster("hub").await?,
+//!     ClientBuilder::new().build_cluster("edge").await?,
+//! ]);
+//!
+//! // ... run the replicator with `clusters.client("hub")` and `clusters.client("edge")` ...
+//!
+//! clusters.assert_mirrored::<ConfigMap>("hub", "edge", "default", "settings");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{owners, testing, ClientHandle};
+use kube::Resource;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A named fake cluster: a client and the handle to its storage
+#[derive(Clone)]
+pub struct Cluster {
+    name: String,
+    client: kube::Client,
+    handle: ClientHandle,
+}
+
+impl Cluster {
+    pub(crate) fn new(name: String, client: kube::Client, handle: ClientHandle) -> Self {
+        Self {
+            name,
+            client,
+            handle,
+        }
+    }
+
+    /// The cluster's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A client talking to the cluster
+    pub fn client(&self) -> kube::Client {
+        self.client.clone()
+    }
+
+    /// The handle to the cluster's storage
+    pub fn handle(&self) -> &ClientHandle {
+        &self.handle
+    }
+
+    /// An object in the cluster, if it exists
+    ///
+    /// The namespace is ignored for cluster-scoped types.
+    pub fn get<K>(&self, namespace: &str, name: &str) -> Option<K>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned,
+    {
+        let object = self.get_value::<K>(namespace, name)?;
+        serde_json::from_value(object.as_ref().clone()).ok()
+    }
+
+    fn get_value<K>(&self, namespace: &str, name: &str) -> Option<Arc<Value>>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        owners::get::<K>(&self.handle.client, namespace, name).ok()
+    }
+}
+
+/// Independent fake clusters, looked up by name
+///
+/// The clusters share nothing: a write to one is never seen by another unless
+/// the operator under test copies it.
+#[derive(Clone)]
+pub struct Clusters {
+    clusters: Vec<Cluster>,
+}
+
+impl Clusters {
+    /// Group clusters built with
+    /// [`ClientBuilder::build_cluster`](crate::ClientBuilder::build_cluster)
+    ///
+    /// # Panics
+    ///
+    /// Panics if two clusters have the same name.
+    pub fn new(clusters: impl IntoIterator<Item = Cluster>) -> Self {
+        let clusters: Vec<Cluster> = clusters.into_iter().collect();
+        for (index, cluster) in clusters.iter().enumerate() {
+            if clusters[..index].iter().any(|c| c.name == cluster.name) {
+                panic!("cluster {} is defined more than once", cluster.name);
+            }
+        }
+        Self { clusters }
+    }
+
+    /// Every cluster, in the order given
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
+
+    /// A cluster by name
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no cluster of the name.
+    pub fn cluster(&self, name: &str) -> &Cluster {
+        self.clusters
+            .iter()
+            .find(|cluster| cluster.name == name)
+            .unwrap_or_else(|| {
+                let names: Vec<&str> = self.clusters.iter().map(Cluster::name).collect();
+                panic!("no cluster named {name}; clusters are {names:?}")
+            })
+    }
+
+    /// A client talking to a cluster
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no cluster of the name.
+    pub fn client(&self, name: &str) -> kube::Client {
+        self.cluster(name).client()
+    }
+
+    /// The handle to a cluster's storage
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no cluster of the name.
+    pub fn handle(&self, name: &str) -> &ClientHandle {
+        self.cluster(name).handle()
+    }
+
+    /// The names of the clusters holding an object
+    ///
+    /// The namespace is ignored for cluster-scoped types.
+    pub fn locate<K>(&self, namespace: &str, name: &str) -> Vec<&str>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        self.clusters
+            .iter()
+            .filter(|cluster| cluster.get_value::<K>(namespace, name).is_some())
+            .map(Cluster::name)
+            .collect()
+    }
+
+    /// Assert that an object in one cluster was copied to another
+    ///
+    /// The copies are compared like [`testing::assert_objects_eq`] compares
+    /// objects, ignoring the fields each API server sets for itself, such as
+    /// `uid` and `resourceVersion`, and the status.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either cluster doesn't exist or lacks the object, or if the
+    /// copies differ, with a diff of the two.
+    #[track_caller]
+    pub fn assert_mirrored<K>(&self, from: &str, to: &str, namespace: &str, name: &str)
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let read = |cluster: &Cluster| {
+            let mut object = cluster
+                .get_value::<K>(namespace, name)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} not found in cluster {}",
+                        describe::<K>(namespace, name),
+                        cluster.name
+                    )
+                })
+                .as_ref()
+                .clone();
+            if let Some(fields) = object.as_object_mut() {
+                fields.remove("status");
+            }
+            object
+        };
+        let source = read(self.cluster(from));
+        let copy = read(self.cluster(to));
+        testing::assert_objects_eq(&copy, &source);
+    }
+
+    /// Assert that an object exists in no cluster
+    ///
+    /// # Panics
+    ///
+    /// Panics if any cluster holds the object, naming the clusters.
+    #[track_caller]
+    pub fn assert_absent<K>(&self, namespace: &str, name: &str)
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let found = self.locate::<K>(namespace, name);
+        if !found.is_empty() {
+            panic!(
+                "{} still exists in cluster(s) {}",
+                describe::<K>(namespace, name),
+                found.join(", ")
+            );
+        }
+    }
+}
+
+fn describe<K: Resource<DynamicType = ()>>(namespace: &str, name: &str) -> String {
+    match namespace {
+        "" => format!("{} {name}", K::kind(&())),
+        namespace => format!("{} {namespace}/{name}", K::kind(&())),
+    }
+}
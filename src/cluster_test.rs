@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap_with;
+    use crate::{ClientBuilder, Clusters};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, PostParams};
+
+    async fn clusters() -> Clusters {
+        Clusters::new([
+            ClientBuilder::new()
+                .with_object(configmap_with("settings", "mode", "fast"))
+                .build_cluster("hub")
+                .await
+                .unwrap(),
+            ClientBuilder::new().build_cluster("edge").await.unwrap(),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_clusters_are_independent() {
+        let clusters = clusters().await;
+        assert_eq!(clusters.locate::<ConfigMap>("default", "settings"), ["hub"]);
+        assert!(clusters
+            .cluster("edge")
+            .get::<ConfigMap>("default", "settings")
+            .is_none());
+
+        Api::<ConfigMap>::default_namespaced(clusters.client("edge"))
+            .create(
+                &PostParams::default(),
+                &configmap_with("local", "mode", "slow"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            clusters
+                .handle("hub")
+                .list_namespace("default")
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(clusters.locate::<ConfigMap>("default", "local"), ["edge"]);
+    }
+
+    #[tokio::test]
+    async fn test_assert_mirrored() {
+        let clusters = clusters().await;
+
+        // The copy gets its own uid and resourceVersion
+        Api::<ConfigMap>::default_namespaced(clusters.client("edge"))
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "mode", "fast"),
+            )
+            .await
+            .unwrap();
+        clusters.assert_mirrored::<ConfigMap>("hub", "edge", "default", "settings");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "objects differ")]
+    async fn test_assert_mirrored_detects_drift() {
+        let clusters = clusters().await;
+        Api::<ConfigMap>::default_namespaced(clusters.client("edge"))
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "mode", "slow"),
+            )
+            .await
+            .unwrap();
+        clusters.assert_mirrored::<ConfigMap>("hub", "edge", "default", "settings");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "ConfigMap default/settings not found in cluster edge")]
+    async fn test_assert_mirrored_missing_copy() {
+        let clusters = clusters().await;
+        clusters.assert_mirrored::<ConfigMap>("hub", "edge", "default", "settings");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "ConfigMap default/settings still exists in cluster(s) hub")]
+    async fn test_assert_absent() {
+        clusters()
+            .await
+            .assert_absent::<ConfigMap>("default", "settings");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "cluster hub is defined more than once")]
+    async fn test_duplicate_cluster_names() {
+        Clusters::new([
+            ClientBuilder::new().build_cluster("hub").await.unwrap(),
+            ClientBuilder::new().build_cluster("hub").await.unwrap(),
+        ]);
+    }
+}
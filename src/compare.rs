@@ -0,0 +1,409 @@
+//! Comparing the fake with a real cluster
+//!
+//! A [`Comparison`] is a tower layer for
+//! [`ClientBuilder::with_layer`](crate::ClientBuilder::with_layer) that sends
+//! every request to a real test cluster, such as kind or envtest, as well as to
+//! the fake, and diffs the two responses. The caller gets the fake's response,
+//! so the code under test runs as it would against the fake alone; every
+//! difference is logged as a warning and kept as a [`Divergence`], showing where
+//! the fake falls short for the workload.
+//!
+//! Fields that differ between any two clusters, such as `uid`,
+//! `resourceVersion`, `creationTimestamp`, `managedFields` and allocated IPs,
+//! are left out of the comparison, as are the `apiVersion` and `kind` of list
+//! items and everything in an error but its code and reason. List items are
+//! compared by namespace and name, so objects the cluster keeps in every
+//! namespace only show up once: `kube-root-ca.crt` is ignored by default, and
+//! [`ignoring_object`](Comparison::ignoring_object) adds more.
+//!
+//! Watches go to the fake only, since their streams can't be compared. The
+//! namespaces the test uses must exist in the cluster, and nothing else should
+//! write there, or responses diverge for reasons unrelated to the fake.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use k8s_openapi::api::core::v1::ConfigMap;
+//! use kube::api::{Api, PostParams};
+//! use kube_fake_client::compare::Comparison;
+//! use kube_fake_client::ClientBuilder;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let cluster = kube::Client::try_default().await?;
+//! let comparison = Comparison::new(cluster);
+//! let client = ClientBuilder::new()
+//!     .with_layer(comparison.clone())
+//!     .build()
+//!     .await?;
+//!
+//! let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+//! // ... run the controller under test with `client` ...
+//!
+//! for divergence in comparison.divergences() {
+//!     println!("{} {}: {}", divergence.method, divergence.uri, divergence.problem);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use bytes::Bytes;
+use futures::future::{BoxFuture, FutureExt};
+use http::{Request, Response};
+use http_body::Body;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full};
+use kube::client::Body as KubeBody;
+use parking_lot::Mutex;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{BoxError, Layer, Service};
+use tracing::warn;
+
+/// Fields, at any depth, whose values differ between any two clusters
+const VOLATILE_FIELDS: [&str; 20] = [
+    "uid",
+    "resourceVersion",
+    "creationTimestamp",
+    "deletionTimestamp",
+    "managedFields",
+    "selfLink",
+    "continue",
+    "remainingItemCount",
+    "lastTransitionTime",
+    "lastUpdateTime",
+    "lastProbeTime",
+    "lastHeartbeatTime",
+    "startTime",
+    "clusterIP",
+    "clusterIPs",
+    "nodePort",
+    "podIP",
+    "podIPs",
+    "hostIP",
+    "hostIPs",
+];
+
+/// Maps of user data, whose keys are compared whatever their name
+const DATA_FIELDS: [&str; 7] = [
+    "labels",
+    "annotations",
+    "data",
+    "stringData",
+    "binaryData",
+    "matchLabels",
+    "nodeSelector",
+];
+
+/// Request headers not passed on to the cluster, which has its own
+const SKIPPED_HEADERS: [&str; 3] = ["authorization", "host", "content-length"];
+
+/// A request the fake and the cluster answered differently
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// HTTP method
+    pub method: String,
+    /// Path and query string of the request
+    pub uri: String,
+    /// Status code the fake answered with
+    pub fake_status: u16,
+    /// Status code the cluster answered with; `None` if the request to it failed
+    pub cluster_status: Option<u16>,
+    /// What differs, in a sentence
+    pub problem: String,
+    /// JSON Patch operations turning the fake's compared response into the
+    /// cluster's
+    pub diff: Value,
+    /// The fake's response as compared, without the fields left out
+    pub fake: Option<Value>,
+    /// The cluster's response as compared
+    pub cluster: Option<Value>,
+}
+
+/// Tower layer sending every request to a real cluster as well and diffing the
+/// responses
+///
+/// Clones share the divergences found, so keep one to inspect them after
+/// handing another to [`ClientBuilder::with_layer`](crate::ClientBuilder::with_layer).
+#[derive(Clone)]
+pub struct Comparison {
+    cluster: kube::Client,
+    ignored_fields: Vec<String>,
+    ignored_objects: Vec<String>,
+    divergences: Arc<Mutex<Vec<Divergence>>>,
+}
+
+impl Comparison {
+    /// Compare the fake with the cluster a client talks to
+    pub fn new(cluster: kube::Client) -> Self {
+        Self {
+            cluster,
+            ignored_fields: VOLATILE_FIELDS.iter().map(ToString::to_string).collect(),
+            ignored_objects: vec!["kube-root-ca.crt".to_string()],
+            divergences: Arc::default(),
+        }
+    }
+
+    /// Leave fields of this name, at any depth, out of the comparison
+    pub fn ignoring_field(mut self, name: impl Into<String>) -> Self {
+        self.ignored_fields.push(name.into());
+        self
+    }
+
+    /// Leave objects of this name out of compared lists, such as the `default`
+    /// ServiceAccount the cluster creates in every namespace
+    pub fn ignoring_object(mut self, name: impl Into<String>) -> Self {
+        self.ignored_objects.push(name.into());
+        self
+    }
+
+    /// The divergences found so far, in the order the requests completed
+    pub fn divergences(&self) -> Vec<Divergence> {
+        self.divergences.lock().clone()
+    }
+
+    /// Panic listing the divergences found so far, if any
+    #[track_caller]
+    pub fn assert_no_divergences(&self) {
+        let divergences = self.divergences();
+        assert!(
+            divergences.is_empty(),
+            "the fake diverged from the cluster on {} request(s):\n{}",
+            divergences.len(),
+            divergences
+                .iter()
+                .map(|d| format!("{} {}: {}", d.method, d.uri, d.problem))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    /// A response as compared: volatile fields and ignored objects left out,
+    /// list items keyed by namespace and name, errors reduced to their code and
+    /// reason
+    fn normalize(&self, body: &[u8]) -> Option<Value> {
+        if body.is_empty() {
+            return None;
+        }
+        let Ok(mut value) = serde_json::from_slice::<Value>(body) else {
+            return Some(Value::String(String::from_utf8_lossy(body).into_owned()));
+        };
+        if value["kind"] == "Status" {
+            let kept = ["status", "reason", "code"];
+            if let Some(fields) = value.as_object_mut() {
+                fields.retain(|key, _| kept.contains(&key.as_str()));
+            }
+            return Some(value);
+        }
+        if let Some(items) = value.get_mut("items").map(Value::take) {
+            let mut by_name = Map::new();
+            for mut item in items.as_array().into_iter().flatten().cloned() {
+                let metadata = &item["metadata"];
+                let name = metadata["name"].as_str().unwrap_or_default().to_string();
+                if self.ignored_objects.contains(&name) {
+                    continue;
+                }
+                let key = match metadata["namespace"].as_str() {
+                    Some(namespace) => format!("{namespace}/{name}"),
+                    None => name,
+                };
+                if let Some(fields) = item.as_object_mut() {
+                    fields.remove("apiVersion");
+                    fields.remove("kind");
+                }
+                by_name.insert(key, item);
+            }
+            value["items"] = Value::Object(by_name);
+        }
+        self.strip(&mut value);
+        Some(value)
+    }
+
+    fn strip(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                fields.retain(|key, _| !self.ignored_fields.contains(key));
+                for (key, field) in fields.iter_mut() {
+                    if !DATA_FIELDS.contains(&key.as_str()) {
+                        self.strip(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.strip(item)),
+            _ => {}
+        }
+    }
+
+    fn compare(
+        &self,
+        method: String,
+        uri: String,
+        (fake_status, fake_body): (u16, &[u8]),
+        cluster: std::result::Result<(u16, Bytes), String>,
+    ) {
+        let fake = self.normalize(fake_body);
+        let (cluster_status, cluster) = match cluster {
+            Ok((status, body)) => (status, self.normalize(&body)),
+            Err(e) => {
+                self.record(Divergence {
+                    method,
+                    uri,
+                    fake_status,
+                    cluster_status: None,
+                    problem: format!("the request to the cluster failed: {e}"),
+                    diff: Value::Array(Vec::new()),
+                    fake,
+                    cluster: None,
+                });
+                return;
+            }
+        };
+        let (left, right) = (
+            fake.clone().unwrap_or(Value::Null),
+            cluster.clone().unwrap_or(Value::Null),
+        );
+        let patch = json_patch::diff(&left, &right);
+        let problem = if fake_status != cluster_status {
+            format!("the fake answered {fake_status}, the cluster {cluster_status}")
+        } else if patch.0.is_empty() {
+            return;
+        } else {
+            let paths: Vec<String> = patch
+                .0
+                .iter()
+                .map(|operation| operation.path().to_string())
+                .collect();
+            format!("responses differ at {}", paths.join(", "))
+        };
+        self.record(Divergence {
+            method,
+            uri,
+            fake_status,
+            cluster_status: Some(cluster_status),
+            problem,
+            diff: serde_json::to_value(patch).unwrap_or(Value::Null),
+            fake,
+            cluster,
+        });
+    }
+
+    fn record(&self, divergence: Divergence) {
+        warn!(
+            "{} {} diverges from the cluster: {}",
+            divergence.method, divergence.uri, divergence.problem
+        );
+        self.divergences.lock().push(divergence);
+    }
+}
+
+impl<S> Layer<S> for Comparison {
+    type Service = Comparing<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Comparing {
+            inner,
+            comparison: self.clone(),
+        }
+    }
+}
+
+/// Service sending the requests to the service it wraps and to a cluster
+pub struct Comparing<S> {
+    inner: S,
+    comparison: Comparison,
+}
+
+type ComparedBody = UnsyncBoxBody<Bytes, BoxError>;
+
+impl<S, B> Service<Request<KubeBody>> for Comparing<S>
+where
+    S: Service<Request<KubeBody>, Response = Response<B>>,
+    S::Future: Send + 'static,
+    S::Error: Into<BoxError>,
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = Response<ComparedBody>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, BoxError>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), BoxError>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<KubeBody>) -> Self::Future {
+        let (parts, body) = request.into_parts();
+        // kube sends request bodies as one buffer, so they are ready right away
+        let bytes = match body.collect().now_or_never() {
+            Some(Ok(collected)) => collected.to_bytes(),
+            Some(Err(e)) => return futures::future::ready(Err(e.into())).boxed(),
+            None => {
+                let err = "cannot compare a streamed request body".into();
+                return futures::future::ready(Err(err)).boxed();
+            }
+        };
+        let boxed = |body: B| UnsyncBoxBody::new(body.map_err(Into::into));
+        if is_watch(parts.uri.query()) {
+            let response = self
+                .inner
+                .call(Request::from_parts(parts, KubeBody::from(bytes)));
+            return async move { Ok(response.await.map_err(Into::into)?.map(boxed)) }.boxed();
+        }
+
+        let method = parts.method.to_string();
+        let uri = parts
+            .uri
+            .path_and_query()
+            .map_or_else(|| parts.uri.path().to_string(), ToString::to_string);
+        let mut cluster_request = Request::builder()
+            .method(parts.method.clone())
+            .uri(parts.uri.clone());
+        for (name, value) in &parts.headers {
+            if !SKIPPED_HEADERS.contains(&name.as_str()) {
+                cluster_request = cluster_request.header(name, value);
+            }
+        }
+        let cluster_request = cluster_request.body(KubeBody::from(bytes.to_vec()));
+        let fake = self
+            .inner
+            .call(Request::from_parts(parts, KubeBody::from(bytes)));
+        let comparison = self.comparison.clone();
+
+        async move {
+            let fake = async {
+                let (parts, body) = fake.await.map_err(Into::into)?.into_parts();
+                let body = body.collect().await.map_err(Into::into)?.to_bytes();
+                Ok::<_, BoxError>((parts, body))
+            };
+            let cluster = async {
+                let response = comparison
+                    .cluster
+                    .send(cluster_request.map_err(|e| e.to_string())?)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let status = response.status().as_u16();
+                let body = response
+                    .into_body()
+                    .collect()
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .to_bytes();
+                Ok((status, body))
+            };
+            let (fake, cluster) = futures::join!(fake, cluster);
+            let (parts, body) = fake?;
+            comparison.compare(method, uri, (parts.status.as_u16(), &body), cluster);
+            let body = UnsyncBoxBody::new(Full::new(body).map_err(|never| match never {}));
+            Ok(Response::from_parts(parts, body))
+        }
+        .boxed()
+    }
+}
+
+fn is_watch(query: Option<&str>) -> bool {
+    query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .any(|pair| pair == "watch=true" || pair == "watch=1")
+}
@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use crate::compare::Comparison;
+    use crate::test_fixtures::configmap_with;
+    use crate::ClientBuilder;
+    use futures::StreamExt;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, DeleteParams, ListParams, PostParams, WatchEvent, WatchParams};
+
+    /// Create, read, list and delete ConfigMaps, and look for a missing one
+    async fn exercise(client: kube::Client) {
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        for name in ["one", "two"] {
+            configmaps
+                .create(
+                    &PostParams::default(),
+                    &configmap_with(name, "key", "value"),
+                )
+                .await
+                .unwrap();
+        }
+        configmaps.get("one").await.unwrap();
+        configmaps.get_opt("missing").await.unwrap();
+        configmaps.list(&ListParams::default()).await.unwrap();
+        configmaps
+            .delete("two", &DeleteParams::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_identical_clusters_do_not_diverge() {
+        // The "cluster" assigns its own uids, timestamps and resourceVersions
+        let cluster = ClientBuilder::new()
+            .with_object(configmap_with("kube-root-ca.crt", "key", "value"))
+            .with_object(configmap_with("unrelated", "key", "value"))
+            .build()
+            .await
+            .unwrap();
+        let comparison = Comparison::new(cluster).ignoring_object("unrelated");
+        let client = ClientBuilder::new()
+            .with_layer(comparison.clone())
+            .build()
+            .await
+            .unwrap();
+
+        exercise(client).await;
+        comparison.assert_no_divergences();
+    }
+
+    #[tokio::test]
+    async fn test_differences_are_recorded_as_divergences() {
+        let cluster = ClientBuilder::new()
+            .with_defaulter::<ConfigMap>(|cm| {
+                cm.metadata
+                    .labels
+                    .get_or_insert_with(Default::default)
+                    .insert("defaulted".to_string(), "true".to_string());
+            })
+            .build()
+            .await
+            .unwrap();
+        let comparison = Comparison::new(cluster);
+        let client = ClientBuilder::new()
+            .with_layer(comparison.clone())
+            .build()
+            .await
+            .unwrap();
+
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        let created = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "key", "value"),
+            )
+            .await
+            .unwrap();
+        // Callers see the fake's response
+        assert!(created.metadata.labels.is_none());
+
+        let divergences = comparison.divergences();
+        assert_eq!(divergences.len(), 1);
+        let divergence = &divergences[0];
+        assert_eq!(divergence.method, "POST");
+        assert_eq!(
+            (divergence.fake_status, divergence.cluster_status),
+            (201, Some(201))
+        );
+        assert_eq!(divergence.problem, "responses differ at /metadata/labels");
+        assert_eq!(divergence.diff[0]["op"], "add");
+        assert_eq!(
+            divergence.cluster.as_ref().unwrap()["metadata"]["labels"]["defaulted"],
+            "true"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_codes_are_compared() {
+        let cluster = ClientBuilder::new()
+            .with_object(configmap_with("settings", "key", "value"))
+            .build()
+            .await
+            .unwrap();
+        let comparison = Comparison::new(cluster);
+        let client = ClientBuilder::new()
+            .with_layer(comparison.clone())
+            .build()
+            .await
+            .unwrap();
+
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        configmaps.get_opt("settings").await.unwrap();
+
+        let divergences = comparison.divergences();
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(
+            divergences[0].problem,
+            "the fake answered 404, the cluster 200"
+        );
+        assert_eq!(divergences[0].fake.as_ref().unwrap()["reason"], "NotFound");
+    }
+
+    #[tokio::test]
+    async fn test_ignored_fields_and_watches_are_not_compared() {
+        let cluster = ClientBuilder::new()
+            .with_defaulter::<ConfigMap>(|cm| {
+                cm.metadata.generate_name = Some("defaulted-".to_string());
+            })
+            .build()
+            .await
+            .unwrap();
+        let comparison = Comparison::new(cluster).ignoring_field("generateName");
+        let client = ClientBuilder::new()
+            .with_layer(comparison.clone())
+            .build()
+            .await
+            .unwrap();
+
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        let params = WatchParams::default().timeout(1);
+        let mut events = configmaps.watch(&params, "0").await.unwrap().boxed();
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "key", "value"),
+            )
+            .await
+            .unwrap();
+        match events.next().await {
+            Some(Ok(WatchEvent::Added(cm))) => {
+                assert_eq!(cm.metadata.name.as_deref(), Some("settings"))
+            }
+            other => panic!("expected the fake's Added event, got {other:?}"),
+        }
+        comparison.assert_no_divergences();
+    }
+}
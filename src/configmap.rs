@@ -0,0 +1,64 @@
+//! ConfigMap data validation
+//!
+//! Mirrors the API server's checks on ConfigMap writes: keys of `data` and
+//! `binaryData` must be valid config keys and may not appear in both, and the
+//! values together are limited to 1MiB, the same limit as for Secrets.
+
+use crate::secret::{is_config_key, MAX_SECRET_SIZE};
+use crate::{Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+
+/// Validate the keys and total size of a ConfigMap's data
+pub(crate) fn validate(configmap: &Value) -> Result<()> {
+    let name = configmap
+        .pointer("/metadata/name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let invalid = |detail: String| {
+        Error::InvalidRequest(format!("ConfigMap \"{name}\" is invalid: {detail}"))
+    };
+    let invalid_key = |field: &str, key: &str| {
+        invalid(format!(
+            "{field}[{key}]: Invalid value: \"{key}\": a valid config key must consist of \
+             alphanumeric characters, '-', '_' or '.'"
+        ))
+    };
+
+    let data = configmap.get("data").and_then(|v| v.as_object());
+    let binary_data = configmap.get("binaryData").and_then(|v| v.as_object());
+
+    let mut total = 0;
+    for (key, value) in data.into_iter().flatten() {
+        if !is_config_key(key) {
+            return Err(invalid_key("data", key));
+        }
+        total += value.as_str().map_or(0, str::len);
+    }
+    for (key, value) in binary_data.into_iter().flatten() {
+        if !is_config_key(key) {
+            return Err(invalid_key("binaryData", key));
+        }
+        if data.is_some_and(|data| data.contains_key(key)) {
+            return Err(invalid(format!(
+                "binaryData[{key}]: Duplicate value: \"{key}\""
+            )));
+        }
+        let encoded = value.as_str().unwrap_or_default();
+        let decoded = STANDARD.decode(encoded).map_err(|e| {
+            invalid(format!(
+                "binaryData[{key}]: Invalid value: illegal base64 data: {e}"
+            ))
+        })?;
+        total += decoded.len();
+    }
+
+    if total > MAX_SECRET_SIZE {
+        return Err(invalid(format!(
+            "[]: Too long: must have at most {MAX_SECRET_SIZE} bytes"
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap_from;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    fn base64_of(len: usize) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.encode(vec![0u8; len])
+    }
+
+    fn assert_invalid(err: kube::Error, needle: &str) {
+        match err {
+            kube::Error::Api(resp) => {
+                assert_eq!(resp.code, 422);
+                assert_eq!(resp.reason, "Invalid");
+                assert!(resp.message.contains(needle), "{}", resp.message);
+            }
+            e => panic!("Expected API error, got: {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_keys_rejected() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let err = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_from("bad", json!({ "data": { "bad/key": "value" } })),
+            )
+            .await
+            .unwrap_err();
+        assert_invalid(err, "data[bad/key]: Invalid value");
+
+        let err = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_from("binary", json!({ "binaryData": { "..": "AAE=" } })),
+            )
+            .await
+            .unwrap_err();
+        assert_invalid(err, "binaryData[..]: Invalid value");
+
+        let err = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_from(
+                    "both",
+                    json!({ "data": { "key": "a" }, "binaryData": { "key": "AAE=" } }),
+                ),
+            )
+            .await
+            .unwrap_err();
+        assert_invalid(err, "binaryData[key]: Duplicate value");
+
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_from(
+                    "good",
+                    json!({ "data": { "app.properties": "a", "KEY_NAME-1": "b" } }),
+                ),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_size_limit_enforced_on_create() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        // Text and binary data count towards the same limit
+        let half = "a".repeat(512 * 1024);
+        let quarter = base64_of(256 * 1024);
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_from(
+                    "max",
+                    json!({
+                        "data": { "one": half, "two": "a".repeat(256 * 1024) },
+                        "binaryData": { "blob": quarter },
+                    }),
+                ),
+            )
+            .await
+            .unwrap();
+
+        let err = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_from(
+                    "huge",
+                    json!({
+                        "data": { "one": half, "two": "a".repeat(256 * 1024 + 1) },
+                        "binaryData": { "blob": quarter },
+                    }),
+                ),
+            )
+            .await
+            .unwrap_err();
+        assert_invalid(err, "Too long: must have at most 1048576 bytes");
+    }
+
+    #[tokio::test]
+    async fn test_validated_on_update_and_patch() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let mut existing = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_from("settings", json!({ "data": { "key": "value" } })),
+            )
+            .await
+            .unwrap();
+
+        existing
+            .data
+            .as_mut()
+            .unwrap()
+            .insert("blob".to_string(), "a".repeat(1024 * 1024));
+        let err = configmaps
+            .replace("settings", &PostParams::default(), &existing)
+            .await
+            .unwrap_err();
+        assert_invalid(err, "Too long");
+
+        let err = configmaps
+            .patch(
+                "settings",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "data": { "not valid": "x" } })),
+            )
+            .await
+            .unwrap_err();
+        assert_invalid(err, "data[not valid]: Invalid value");
+
+        // Failed writes leave the stored object as it was
+        let stored = configmaps.get("settings").await.unwrap();
+        assert_eq!(stored.data.unwrap().len(), 1);
+    }
+}
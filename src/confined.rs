@@ -0,0 +1,80 @@
+//! Namespace-confined clients
+//!
+//! [`ClientHandle::namespaced_client`](crate::ClientHandle::namespaced_client)
+//! hands out a `kube::Client` that only sees one namespace, the way a
+//! controller running under a namespaced Role would. Requests for other
+//! namespaces, cluster-scoped resources, or lists across all namespaces are
+//! rejected with 403 Forbidden before they reach the fake.
+
+use crate::error::Error;
+use crate::mock_service::MockService;
+use crate::response_body::ResponseBody;
+use futures::future::{BoxFuture, FutureExt};
+use http::{Request, Response};
+use kube::client::Body as KubeBody;
+use std::task::{Context, Poll};
+use tower::Service;
+
+/// Wraps [`MockService`], rejecting requests outside a single namespace
+#[derive(Clone)]
+pub(crate) struct NamespaceConfined {
+    inner: MockService,
+    namespace: String,
+}
+
+impl NamespaceConfined {
+    pub(crate) fn new(inner: MockService, namespace: impl Into<String>) -> Self {
+        Self {
+            inner,
+            namespace: namespace.into(),
+        }
+    }
+
+    /// Check that a request path stays within the confined namespace
+    ///
+    /// Discovery documents and non-resource paths are always allowed. Resource
+    /// paths must have the form `.../namespaces/{namespace}/...` with the
+    /// confined namespace; `GET /api/v1/namespaces/{namespace}` itself is
+    /// allowed too.
+    fn check(&self, path: &str) -> std::result::Result<(), Error> {
+        if MockService::is_discovery_path(path) {
+            return Ok(());
+        }
+        let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let version_index = match parts.first() {
+            Some(&"api") => 1,
+            Some(&"apis") => 2,
+            _ => return Ok(()),
+        };
+        let rest = &parts[version_index + 1..];
+        match rest {
+            ["namespaces", namespace, ..] if *namespace == self.namespace => Ok(()),
+            ["namespaces", namespace, ..] => Err(Error::Forbidden(format!(
+                "namespace \"{namespace}\" is forbidden: client is confined to namespace \"{}\"",
+                self.namespace
+            ))),
+            [resource, ..] => Err(Error::Forbidden(format!(
+                "{resource} is forbidden: client is confined to namespace \"{}\" and cannot access cluster-scoped or all-namespace requests",
+                self.namespace
+            ))),
+            [] => Ok(()),
+        }
+    }
+}
+
+impl Service<Request<KubeBody>> for NamespaceConfined {
+    type Response = Response<ResponseBody>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<KubeBody>) -> Self::Future {
+        match self.check(req.uri().path()) {
+            Ok(()) => self.inner.call(req),
+            Err(err) => futures::future::ready(MockService::error_to_response(err)).boxed(),
+        }
+    }
+}
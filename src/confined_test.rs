@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap;
+    use crate::{ClientBuilder, ClientHandle};
+    use futures::StreamExt;
+    use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node};
+    use kube::api::{Api, ListParams, PostParams, WatchParams};
+    use kube::Client;
+
+    fn assert_forbidden<T: std::fmt::Debug>(result: Result<T, kube::Error>) {
+        match result {
+            Err(kube::Error::Api(response)) => {
+                assert_eq!(response.code, 403, "{}", response.message);
+                assert_eq!(response.reason, "Forbidden");
+            }
+            other => panic!("expected 403 Forbidden, got {other:?}"),
+        }
+    }
+
+    async fn setup() -> (Client, ClientHandle, Client) {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let confined = handle.namespaced_client("team-a");
+        (client, handle, confined)
+    }
+
+    #[tokio::test]
+    async fn test_namespaced_client_serves_own_namespace() {
+        let (client, _handle, confined) = setup().await;
+
+        let api: Api<ConfigMap> = Api::namespaced(confined.clone(), "team-a");
+        api.create(&PostParams::default(), &configmap("settings"))
+            .await
+            .unwrap();
+        assert_eq!(
+            api.list(&ListParams::default()).await.unwrap().items.len(),
+            1
+        );
+        api.delete("settings", &Default::default()).await.unwrap();
+
+        // The default namespace of the client is the confined one
+        let default: Api<ConfigMap> = Api::default_namespaced(confined.clone());
+        default
+            .create(&PostParams::default(), &configmap("other"))
+            .await
+            .unwrap();
+
+        // Writes land in the shared store
+        Api::<ConfigMap>::namespaced(client, "team-a")
+            .get("other")
+            .await
+            .unwrap();
+
+        // The namespace object itself is readable
+        let namespaces: Api<Namespace> = Api::all(confined);
+        assert_forbidden(namespaces.list(&ListParams::default()).await);
+        assert!(!matches!(
+            namespaces.get("team-a").await,
+            Err(kube::Error::Api(response)) if response.code == 403
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_namespaced_client_rejects_other_namespaces() {
+        let (client, _handle, confined) = setup().await;
+        Api::<ConfigMap>::namespaced(client, "team-b")
+            .create(&PostParams::default(), &configmap("settings"))
+            .await
+            .unwrap();
+
+        let api: Api<ConfigMap> = Api::namespaced(confined.clone(), "team-b");
+        assert_forbidden(api.get("settings").await);
+        assert_forbidden(api.list(&ListParams::default()).await);
+        assert_forbidden(api.create(&PostParams::default(), &configmap("new")).await);
+        assert_forbidden(api.delete("settings", &Default::default()).await);
+    }
+
+    #[tokio::test]
+    async fn test_namespaced_client_rejects_cluster_wide_requests() {
+        let (_client, _handle, confined) = setup().await;
+
+        let all: Api<ConfigMap> = Api::all(confined.clone());
+        assert_forbidden(all.list(&ListParams::default()).await);
+        let mut events = all
+            .watch(&WatchParams::default(), "0")
+            .await
+            .unwrap()
+            .boxed();
+        match events.next().await {
+            Some(Err(kube::Error::Api(response))) => assert_eq!(response.code, 403),
+            other => panic!("expected 403 Forbidden, got {other:?}"),
+        }
+
+        let nodes: Api<Node> = Api::all(confined.clone());
+        assert_forbidden(nodes.list(&ListParams::default()).await);
+        assert_forbidden(nodes.get("node-1").await);
+
+        // Discovery is not namespaced and stays available
+        let discovery = kube::Discovery::new(confined).run().await.unwrap();
+        assert!(discovery.has_group("apps"));
+    }
+}
@@ -0,0 +1,867 @@
+//! Randomized API conformance checks
+//!
+//! [`run`] drives a `kube::Client` with a random sequence of creates, replaces,
+//! patches, status patches, deletes, gets and lists of valid ConfigMaps and
+//! Deployments, and checks every response against what the API server
+//! guarantees:
+//!
+//! - Every write returns a resourceVersion newer than any returned before, and
+//!   reads never return an older version of an object than its latest write.
+//! - The uid, name, namespace and creationTimestamp of an object never change,
+//!   and a recreated object gets a new uid.
+//! - `metadata.generation` never goes back.
+//! - Status writes only change the status, and writes to the main resource
+//!   never change it.
+//! - Immutable fields stay put: changing a Deployment's selector, or the data of
+//!   a ConfigMap marked `immutable`, fails with 422 Invalid.
+//! - Requests fail as they would on the API server: 409 Conflict for creates of
+//!   existing names and replaces from a stale resourceVersion, 404 Not Found for
+//!   objects that don't exist.
+//!
+//! Operations come from a seeded RNG, so a failing run is replayed by running
+//! again with the seed from its report. Run it against a client built with the
+//! interceptors, simulations and other extensions under test to check they keep
+//! these guarantees. Every write is preceded by a get of the object, so writes
+//! an extension makes in reaction to the run's own become the baseline of the
+//! next check, as long as they are done by the time the response comes back.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::conformance::{self, ConformanceConfig};
+//! use kube_fake_client::ClientBuilder;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::new().build().await?;
+//! let report = conformance::run(client, &ConformanceConfig::default()).await?;
+//! report.assert_conformant();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::Result;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{
+    Api, ApiResource, DeleteParams, DynamicObject, ListParams, Patch, PatchParams, PostParams,
+};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Prefix of the names of the objects a run writes
+const NAME_PREFIX: &str = "fuzz-";
+
+/// Characters of generated names and label values
+const NAME_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Characters of generated ConfigMap values, including ones that need escaping
+const VALUE_CHARS: &[char] = &['a', 'Z', '0', ' ', '-', '"', '\\', '\n', 'é', '☃'];
+
+const IMAGES: &[&str] = &["nginx:1.25", "busybox:1.36", "registry.k8s.io/pause:3.9"];
+
+/// Shape of a conformance run
+#[derive(Debug, Clone)]
+pub struct ConformanceConfig {
+    /// Namespace the objects are written in
+    pub namespace: String,
+    /// Number of operations performed
+    pub operations: usize,
+    /// Number of names of each kind operations pick from; fewer names mean more
+    /// writes to the same object
+    pub objects: usize,
+    /// Seed for the RNG; a random seed is chosen if None
+    pub seed: Option<u64>,
+}
+
+impl Default for ConformanceConfig {
+    fn default() -> Self {
+        Self {
+            namespace: "conformance".to_string(),
+            operations: 200,
+            objects: 3,
+            seed: None,
+        }
+    }
+}
+
+/// Outcome of a conformance run
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    /// Seed the operations were generated from
+    pub seed: u64,
+    /// Operations performed, in order, with the status they got
+    pub operations: Vec<String>,
+    /// Guarantees broken; empty if the run conformed
+    pub violations: Vec<String>,
+}
+
+impl ConformanceReport {
+    /// Whether no guarantee was broken
+    pub fn is_conformant(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Panic listing the violations and the seed to replay them with, if any
+    pub fn assert_conformant(&self) {
+        assert!(
+            self.is_conformant(),
+            "conformance run with seed {} found {} violation(s):\n{}",
+            self.seed,
+            self.violations.len(),
+            self.violations.join("\n")
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    ConfigMap,
+    Deployment,
+}
+
+impl Kind {
+    fn resource(self) -> ApiResource {
+        match self {
+            Kind::ConfigMap => ApiResource::erase::<ConfigMap>(&()),
+            Kind::Deployment => ApiResource::erase::<Deployment>(&()),
+        }
+    }
+
+    fn operations(self) -> &'static [Op] {
+        match self {
+            Kind::ConfigMap => &Op::COMMON,
+            Kind::Deployment => &Op::WITH_STATUS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Create,
+    Replace,
+    StaleReplace,
+    Patch,
+    /// Change a Deployment's selector, or mark a ConfigMap immutable and then
+    /// change its data
+    PatchImmutable,
+    PatchStatus,
+    /// Patch the main resource with a status, which must be ignored
+    PatchWithStatus,
+    Delete,
+    Get,
+    List,
+}
+
+impl Op {
+    const COMMON: [Op; 8] = [
+        Op::Create,
+        Op::Replace,
+        Op::StaleReplace,
+        Op::Patch,
+        Op::PatchImmutable,
+        Op::Delete,
+        Op::Get,
+        Op::List,
+    ];
+    const WITH_STATUS: [Op; 10] = [
+        Op::Create,
+        Op::Replace,
+        Op::StaleReplace,
+        Op::Patch,
+        Op::PatchImmutable,
+        Op::PatchStatus,
+        Op::PatchWithStatus,
+        Op::Delete,
+        Op::Get,
+        Op::List,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Op::Create => "create",
+            Op::Replace => "replace",
+            Op::StaleReplace => "stale-replace",
+            Op::Patch => "patch",
+            Op::PatchImmutable => "patch-immutable",
+            Op::PatchStatus => "patch-status",
+            Op::PatchWithStatus => "patch-with-status",
+            Op::Delete => "delete",
+            Op::Get => "get",
+            Op::List => "list",
+        }
+    }
+}
+
+/// An object the run wrote, as it last saw it
+struct Tracked {
+    object: Value,
+    /// resourceVersion before the latest write, for replaces from a stale copy
+    stale: Option<String>,
+}
+
+/// What a write is expected to do
+struct Expected {
+    /// Status code the request must fail with; `None` if it must succeed
+    status: Option<u16>,
+    /// Values the written object must have, by JSON pointer; `None` for absent
+    fields: Vec<(String, Option<Value>)>,
+    /// Whether the write goes to the status subresource
+    is_status: bool,
+}
+
+impl Expected {
+    fn success(fields: Vec<(String, Option<Value>)>) -> Self {
+        Self {
+            status: None,
+            fields,
+            is_status: false,
+        }
+    }
+
+    fn failure(status: u16) -> Self {
+        Self {
+            status: Some(status),
+            fields: Vec::new(),
+            is_status: false,
+        }
+    }
+}
+
+struct Run {
+    rng: StdRng,
+    namespace: String,
+    apis: BTreeMap<Kind, Api<DynamicObject>>,
+    objects: BTreeMap<(Kind, String), Tracked>,
+    uids: HashSet<String>,
+    /// Newest resourceVersion returned by a write
+    latest: u64,
+    report: ConformanceReport,
+}
+
+/// Run random operations against the client and check every response
+///
+/// Objects named `fuzz-<n>` must not exist in the namespace beforehand, and
+/// nothing but the run and the extensions reacting to it should write them.
+///
+/// # Errors
+///
+/// Returns an error if a generated object can't be converted for the request.
+/// Broken guarantees are reported in [`ConformanceReport::violations`] instead.
+pub async fn run(client: kube::Client, config: &ConformanceConfig) -> Result<ConformanceReport> {
+    let seed = config.seed.unwrap_or_else(rand::random);
+    let apis = [Kind::ConfigMap, Kind::Deployment]
+        .into_iter()
+        .map(|kind| {
+            let api = Api::namespaced_with(client.clone(), &config.namespace, &kind.resource());
+            (kind, api)
+        })
+        .collect();
+    let mut run = Run {
+        rng: StdRng::seed_from_u64(seed),
+        namespace: config.namespace.clone(),
+        apis,
+        objects: BTreeMap::new(),
+        uids: HashSet::new(),
+        latest: 0,
+        report: ConformanceReport {
+            seed,
+            ..ConformanceReport::default()
+        },
+    };
+    if config.objects == 0 {
+        return Ok(run.report);
+    }
+
+    for index in 0..config.operations {
+        let kind = *[Kind::ConfigMap, Kind::Deployment]
+            .choose(&mut run.rng)
+            .unwrap_or(&Kind::ConfigMap);
+        let op = *kind.operations().choose(&mut run.rng).unwrap_or(&Op::Get);
+        let name = format!("{NAME_PREFIX}{}", run.rng.random_range(0..config.objects));
+        let step = format!("#{index} {} {} {name}", op.name(), kind.resource().plural);
+        run.step(&step, op, kind, &name).await?;
+    }
+    for kind in [Kind::ConfigMap, Kind::Deployment] {
+        run.list(&format!("final list {}", kind.resource().plural), kind)
+            .await;
+    }
+    Ok(run.report)
+}
+
+impl Run {
+    async fn step(&mut self, step: &str, op: Op, kind: Kind, name: &str) -> Result<()> {
+        if op == Op::List {
+            self.list(step, kind).await;
+            return Ok(());
+        }
+        let Some(current) = self.read(step, kind, name).await else {
+            return Ok(());
+        };
+        let api = self.apis[&kind].clone();
+        match op {
+            Op::Get | Op::List => {
+                let status = if current.is_some() { "ok" } else { "404" };
+                self.report.operations.push(format!("{step}: {status}"));
+            }
+            Op::Create => {
+                let object = self.generate(kind, name);
+                let expected = match current {
+                    Some(_) => Expected::failure(409),
+                    None => Expected::success(Self::content_fields(kind, &object)),
+                };
+                let result = api.create(&PostParams::default(), &dynamic(object)?).await;
+                self.check_write(step, kind, name, current, result, expected);
+            }
+            Op::Replace | Op::StaleReplace => {
+                let Some(existing) = &current else {
+                    let object = self.generate(kind, name);
+                    let result = api
+                        .replace(name, &PostParams::default(), &dynamic(object)?)
+                        .await;
+                    self.check_write(step, kind, name, current, result, Expected::failure(404));
+                    return Ok(());
+                };
+                let resource_version = if op == Op::StaleReplace {
+                    match &self.objects[&(kind, name.to_string())].stale {
+                        Some(stale) => stale.clone(),
+                        // Nothing written over it yet, so there is no stale copy
+                        None => {
+                            self.report
+                                .operations
+                                .push(format!("{step}: skipped, no stale copy"));
+                            return Ok(());
+                        }
+                    }
+                } else {
+                    existing["metadata"]["resourceVersion"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let mut object = self.generate(kind, name);
+                object["metadata"]["resourceVersion"] = json!(resource_version);
+                if let Some(immutable) = existing.get("immutable") {
+                    object["immutable"] = immutable.clone();
+                }
+                if kind == Kind::Deployment {
+                    // The selector can't change, so replaces keep it
+                    object["spec"]["selector"] = existing["spec"]["selector"].clone();
+                    object["spec"]["template"]["metadata"]["labels"]["app"] =
+                        existing["spec"]["selector"]["matchLabels"]["app"].clone();
+                }
+                let expected = if op == Op::StaleReplace {
+                    Expected::failure(409)
+                } else if is_frozen(existing) && existing["data"] != object["data"] {
+                    Expected::failure(422)
+                } else {
+                    Expected::success(Self::content_fields(kind, &object))
+                };
+                let result = api
+                    .replace(name, &PostParams::default(), &dynamic(object)?)
+                    .await;
+                self.check_write(step, kind, name, current, result, expected);
+            }
+            Op::Patch => {
+                let (patch, fields) = self.generate_patch(kind, current.as_ref());
+                let expected = match &current {
+                    None => Expected::failure(404),
+                    Some(existing) if is_frozen(existing) && changes_data(existing, &patch) => {
+                        Expected::failure(422)
+                    }
+                    Some(_) => Expected::success(fields),
+                };
+                let result = api
+                    .patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await;
+                self.check_write(step, kind, name, current, result, expected);
+            }
+            Op::PatchImmutable => {
+                let (patch, expected) = match (kind, &current) {
+                    (_, None) => (json!({ "metadata": { "labels": { "a": "b" } } }), None),
+                    (Kind::ConfigMap, Some(existing)) if !is_frozen(existing) => {
+                        let patch = json!({ "immutable": true });
+                        let fields = vec![("/immutable".to_string(), Some(json!(true)))];
+                        (patch, Some(Expected::success(fields)))
+                    }
+                    (Kind::ConfigMap, Some(existing)) => {
+                        let (key, value) = (self.word(), self.value());
+                        let patch = json!({ "data": { key.clone(): value.clone() } });
+                        let expected = if changes_data(existing, &patch) {
+                            Expected::failure(422)
+                        } else {
+                            Expected::success(vec![(format!("/data/{key}"), Some(json!(value)))])
+                        };
+                        (patch, Some(expected))
+                    }
+                    (Kind::Deployment, Some(_)) => {
+                        let value = self.word();
+                        let patch = json!({ "spec": {
+                            "selector": { "matchLabels": { "changed": value } },
+                            "template": { "metadata": { "labels": { "changed": value } } },
+                        } });
+                        (patch, Some(Expected::failure(422)))
+                    }
+                };
+                let expected = expected.unwrap_or_else(|| Expected::failure(404));
+                let result = api
+                    .patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await;
+                self.check_write(step, kind, name, current, result, expected);
+            }
+            Op::PatchStatus => {
+                let status = self.generate_status();
+                let patch = json!({ "status": status });
+                let expected = match current {
+                    Some(_) => Expected {
+                        is_status: true,
+                        ..Expected::success(vec![("/status".to_string(), Some(status))])
+                    },
+                    None => Expected::failure(404),
+                };
+                let result = api
+                    .patch_status(name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await;
+                self.check_write(step, kind, name, current, result, expected);
+            }
+            Op::PatchWithStatus => {
+                let replicas = self.rng.random_range(0..5);
+                let patch = json!({
+                    "spec": { "replicas": replicas },
+                    "status": self.generate_status(),
+                });
+                let expected = match current {
+                    Some(_) => Expected::success(vec![(
+                        "/spec/replicas".to_string(),
+                        Some(json!(replicas)),
+                    )]),
+                    None => Expected::failure(404),
+                };
+                let result = api
+                    .patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await;
+                self.check_write(step, kind, name, current, result, expected);
+            }
+            Op::Delete => {
+                let result = api.delete(name, &DeleteParams::default()).await;
+                let expected = if current.is_some() { None } else { Some(404) };
+                if self.outcome(step, result, expected).is_some() {
+                    self.objects.remove(&(kind, name.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get an object, checking it against the run's copy
+    ///
+    /// Returns `None` if the get itself failed, and `Some(None)` if the object
+    /// doesn't exist.
+    async fn read(&mut self, step: &str, kind: Kind, name: &str) -> Option<Option<Value>> {
+        let result = self.apis[&kind].get_opt(name).await;
+        let object = match result {
+            Ok(object) => object.map(to_value),
+            Err(e) => {
+                self.report.operations.push(format!("{step}: get failed"));
+                self.violation(step, format!("get failed: {e}"));
+                return None;
+            }
+        };
+        let key = (kind, name.to_string());
+        match (&object, self.objects.contains_key(&key)) {
+            (Some(object), _) => self.observe(step, &key, object.clone()),
+            (None, true) => {
+                self.violation(step, "object is gone without a delete".to_string());
+                self.objects.remove(&key);
+            }
+            (None, false) => {}
+        }
+        Some(object)
+    }
+
+    /// List the objects of a kind, checking them against the run's copies
+    async fn list(&mut self, step: &str, kind: Kind) {
+        let list = match self.apis[&kind].list(&ListParams::default()).await {
+            Ok(list) => list,
+            Err(e) => {
+                self.report.operations.push(format!("{step}: list failed"));
+                self.violation(step, format!("list failed: {e}"));
+                return;
+            }
+        };
+        self.report.operations.push(format!("{step}: ok"));
+        let list_version = list
+            .metadata
+            .resource_version
+            .as_deref()
+            .and_then(|rv| rv.parse::<u64>().ok())
+            .unwrap_or_default();
+        if list_version < self.latest {
+            self.violation(
+                step,
+                format!(
+                    "list resourceVersion {list_version} is older than the latest write {}",
+                    self.latest
+                ),
+            );
+        }
+        let mut listed = BTreeSet::new();
+        for object in list.items.into_iter().map(to_value) {
+            let name = object["metadata"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            if !name.starts_with(NAME_PREFIX) {
+                continue;
+            }
+            listed.insert(name.clone());
+            self.observe(step, &(kind, name), object);
+        }
+        let tracked: BTreeSet<String> = self
+            .objects
+            .keys()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, name)| name.clone())
+            .collect();
+        for missing in tracked.difference(&listed) {
+            self.violation(step, format!("{missing} exists but isn't listed"));
+        }
+    }
+
+    /// Check an object read back against the run's copy, and take it as the
+    /// latest version if an extension wrote it since
+    fn observe(&mut self, step: &str, key: &(Kind, String), object: Value) {
+        let Some(tracked) = self.objects.get(key) else {
+            self.violation(step, format!("{} exists but was never created", key.1));
+            return;
+        };
+        let (read, written) = (resource_version(&object), resource_version(&tracked.object));
+        if read < written {
+            self.violation(
+                step,
+                format!("read resourceVersion {read}, older than the latest write {written}"),
+            );
+            return;
+        }
+        if read == written {
+            if without_managed_fields(&object) != without_managed_fields(&tracked.object) {
+                self.violation(
+                    step,
+                    format!("read resourceVersion {read} with content other than its write"),
+                );
+            }
+            return;
+        }
+        let previous = tracked.object.clone();
+        self.check_identity(step, &previous, &object);
+        self.latest = self.latest.max(read);
+        if let Some(tracked) = self.objects.get_mut(key) {
+            tracked.stale = previous["metadata"]["resourceVersion"]
+                .as_str()
+                .map(str::to_string);
+            tracked.object = object;
+        }
+    }
+
+    /// Check the outcome of a write and record the written object
+    fn check_write(
+        &mut self,
+        step: &str,
+        kind: Kind,
+        name: &str,
+        previous: Option<Value>,
+        result: kube::Result<DynamicObject>,
+        expected: Expected,
+    ) {
+        let Some(written) = self.outcome(step, result, expected.status) else {
+            return;
+        };
+        let written = to_value(written);
+
+        let version = resource_version(&written);
+        // Like the API server, a write that changes nothing keeps the version
+        let unchanged = previous.as_ref().is_some_and(|previous| {
+            resource_version(previous) == version
+                && without_managed_fields(previous) == without_managed_fields(&written)
+        });
+        if version <= self.latest && !unchanged {
+            self.violation(
+                step,
+                format!(
+                    "write returned resourceVersion {version}, not newer than {} returned before",
+                    self.latest
+                ),
+            );
+        }
+        self.latest = self.latest.max(version);
+
+        for (pointer, value) in &expected.fields {
+            let actual = written.pointer(pointer);
+            if actual != value.as_ref() {
+                self.violation(
+                    step,
+                    format!("{pointer} is {actual:?} after the write, expected {value:?}"),
+                );
+            }
+        }
+
+        match &previous {
+            Some(previous) => {
+                self.check_identity(step, previous, &written);
+                if expected.is_status {
+                    for pointer in ["/spec", "/data", "/metadata/labels", "/metadata/generation"] {
+                        if previous.pointer(pointer) != written.pointer(pointer) {
+                            self.violation(step, format!("status write changed {pointer}"));
+                        }
+                    }
+                } else if kind == Kind::Deployment
+                    && previous.get("status") != written.get("status")
+                {
+                    self.violation(step, "write to the main resource changed the status".into());
+                }
+            }
+            None => {
+                let uid = written["metadata"]["uid"].as_str().unwrap_or_default();
+                if uid.is_empty() || !self.uids.insert(uid.to_string()) {
+                    self.violation(step, format!("created object got a reused uid {uid:?}"));
+                }
+            }
+        }
+
+        // A write that changed nothing leaves the stale copy as stale as it was
+        let key = (kind, name.to_string());
+        let stale = match self.objects.get(&key) {
+            Some(tracked) if unchanged => tracked.stale.clone(),
+            _ => previous
+                .as_ref()
+                .and_then(|p| p["metadata"]["resourceVersion"].as_str())
+                .map(str::to_string),
+        };
+        self.objects.insert(
+            key,
+            Tracked {
+                object: written,
+                stale,
+            },
+        );
+    }
+
+    /// Check that the identity of an object survived a write
+    fn check_identity(&mut self, step: &str, previous: &Value, object: &Value) {
+        for field in ["uid", "name", "namespace", "creationTimestamp"] {
+            let (before, after) = (&previous["metadata"][field], &object["metadata"][field]);
+            if before != after {
+                self.violation(
+                    step,
+                    format!("metadata.{field} changed from {before} to {after}"),
+                );
+            }
+        }
+        let generation = |object: &Value| object["metadata"]["generation"].as_i64();
+        if generation(object) < generation(previous) {
+            self.violation(
+                step,
+                format!(
+                    "metadata.generation went back from {:?} to {:?}",
+                    generation(previous),
+                    generation(object)
+                ),
+            );
+        }
+    }
+
+    /// Record the outcome of a request, returning what it returned if it
+    /// succeeded as expected
+    fn outcome<T>(
+        &mut self,
+        step: &str,
+        result: kube::Result<T>,
+        expected: Option<u16>,
+    ) -> Option<T> {
+        let (status, value) = match result {
+            Ok(value) => (None, Some(value)),
+            Err(kube::Error::Api(response)) => (Some(response.code), None),
+            Err(e) => {
+                self.report.operations.push(format!("{step}: failed"));
+                self.violation(step, format!("request failed: {e}"));
+                return None;
+            }
+        };
+        let describe = |status: Option<u16>| status.map_or("ok".to_string(), |s| s.to_string());
+        self.report
+            .operations
+            .push(format!("{step}: {}", describe(status)));
+        if status != expected {
+            self.violation(
+                step,
+                format!("expected {}, got {}", describe(expected), describe(status)),
+            );
+            return None;
+        }
+        value
+    }
+
+    fn violation(&mut self, step: &str, problem: String) {
+        self.report.violations.push(format!("{step}: {problem}"));
+    }
+
+    /// A random valid object of a kind
+    fn generate(&mut self, kind: Kind, name: &str) -> Value {
+        let resource = kind.resource();
+        let mut labels = BTreeMap::from([("app".to_string(), name.to_string())]);
+        for _ in 0..self.rng.random_range(0..3) {
+            labels.insert(self.word(), self.word());
+        }
+        let mut object = json!({
+            "apiVersion": resource.api_version,
+            "kind": resource.kind,
+            "metadata": { "name": name, "namespace": self.namespace, "labels": labels },
+        });
+        match kind {
+            Kind::ConfigMap => {
+                let data: BTreeMap<String, String> = (0..self.rng.random_range(1..4))
+                    .map(|_| (self.word(), self.value()))
+                    .collect();
+                object["data"] = json!(data);
+            }
+            Kind::Deployment => {
+                let image = *IMAGES.choose(&mut self.rng).unwrap_or(&IMAGES[0]);
+                object["spec"] = json!({
+                    "replicas": self.rng.random_range(0..5),
+                    "selector": { "matchLabels": { "app": name } },
+                    "template": {
+                        "metadata": { "labels": labels },
+                        "spec": { "containers": [{ "name": "main", "image": image }] },
+                    },
+                });
+            }
+        }
+        object
+    }
+
+    /// The fields of a generated object a create or replace must store as sent
+    fn content_fields(kind: Kind, object: &Value) -> Vec<(String, Option<Value>)> {
+        let pointers: &[&str] = match kind {
+            Kind::ConfigMap => &["/data"],
+            Kind::Deployment => &["/spec/replicas", "/spec/selector"],
+        };
+        // Labels are checked one by one, since extensions may add their own
+        let labels = object["metadata"]["labels"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(key, _)| format!("/metadata/labels/{key}"));
+        pointers
+            .iter()
+            .map(|pointer| pointer.to_string())
+            .chain(labels)
+            .map(|pointer| {
+                let value = object.pointer(&pointer).cloned();
+                (pointer, value)
+            })
+            .collect()
+    }
+
+    /// A random merge patch of an object's content, and the fields it sets
+    fn generate_patch(
+        &mut self,
+        kind: Kind,
+        current: Option<&Value>,
+    ) -> (Value, Vec<(String, Option<Value>)>) {
+        let label = self.word();
+        let label_value = self.word();
+        let mut fields = vec![(
+            format!("/metadata/labels/{label}"),
+            Some(json!(label_value)),
+        )];
+        let mut patch = json!({ "metadata": { "labels": { label: label_value } } });
+        match kind {
+            Kind::ConfigMap => {
+                let (key, value) = (self.word(), self.value());
+                fields.push((format!("/data/{key}"), Some(json!(value))));
+                patch["data"] = json!({ key.clone(): value });
+                // Sometimes remove a key as well, keeping at least one
+                let existing: Vec<String> = current
+                    .and_then(|c| c["data"].as_object())
+                    .map(|data| data.keys().cloned().collect())
+                    .unwrap_or_default();
+                if existing.len() > 1 && self.rng.random_bool(0.3) {
+                    let removed = existing[self.rng.random_range(0..existing.len())].clone();
+                    if removed != key {
+                        fields.push((format!("/data/{removed}"), None));
+                        patch["data"][removed] = Value::Null;
+                    }
+                }
+            }
+            Kind::Deployment => {
+                let replicas = self.rng.random_range(0..5);
+                fields.push(("/spec/replicas".to_string(), Some(json!(replicas))));
+                patch["spec"] = json!({ "replicas": replicas });
+            }
+        }
+        (patch, fields)
+    }
+
+    fn generate_status(&mut self) -> Value {
+        let replicas = self.rng.random_range(0..5);
+        json!({
+            "observedGeneration": self.rng.random_range(1..10),
+            "replicas": replicas,
+            "readyReplicas": self.rng.random_range(0..=replicas),
+        })
+    }
+
+    /// A random name or label value
+    fn word(&mut self) -> String {
+        let len = self.rng.random_range(1..9);
+        let first = char::from(b'a' + self.rng.random_range(0..26));
+        std::iter::once(first)
+            .chain((1..len).map(|_| char::from(*NAME_CHARS.choose(&mut self.rng).unwrap_or(&b'a'))))
+            .collect()
+    }
+
+    /// A random ConfigMap value
+    fn value(&mut self) -> String {
+        let len = self.rng.random_range(0..12);
+        (0..len)
+            .map(|_| *VALUE_CHARS.choose(&mut self.rng).unwrap_or(&'a'))
+            .collect()
+    }
+}
+
+fn dynamic(object: Value) -> Result<DynamicObject> {
+    Ok(serde_json::from_value(object)?)
+}
+
+fn to_value(object: DynamicObject) -> Value {
+    serde_json::to_value(object).unwrap_or_default()
+}
+
+fn resource_version(object: &Value) -> u64 {
+    object["metadata"]["resourceVersion"]
+        .as_str()
+        .and_then(|rv| rv.parse().ok())
+        .unwrap_or_default()
+}
+
+fn is_frozen(object: &Value) -> bool {
+    object["immutable"].as_bool() == Some(true)
+}
+
+/// Whether a merge patch changes the data of a ConfigMap
+fn changes_data(object: &Value, patch: &Value) -> bool {
+    let mut patched = object.clone();
+    json_patch::merge(&mut patched, patch);
+    patched.get("data") != object.get("data")
+}
+
+fn without_managed_fields(object: &Value) -> Value {
+    let mut object = object.clone();
+    if let Some(metadata) = object["metadata"].as_object_mut() {
+        metadata.remove("managedFields");
+    }
+    object
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use crate::conformance::{self, ConformanceConfig};
+    use crate::{interceptor, ClientBuilder};
+    use k8s_openapi::api::apps::v1::Deployment;
+    use serde_json::json;
+
+    fn seeded(seed: u64) -> ConformanceConfig {
+        ConformanceConfig {
+            seed: Some(seed),
+            ..ConformanceConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fake_conforms_across_seeds() {
+        for seed in 0..5 {
+            let client = ClientBuilder::new().build().await.unwrap();
+            let report = conformance::run(client, &seeded(seed)).await.unwrap();
+            report.assert_conformant();
+            assert_eq!(report.seed, seed);
+            assert!(report.operations.len() >= 200);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_replays_the_same_operations() {
+        let first = ClientBuilder::new().build().await.unwrap();
+        let second = ClientBuilder::new().build().await.unwrap();
+
+        let first = conformance::run(first, &seeded(7)).await.unwrap();
+        let second = conformance::run(second, &seeded(7)).await.unwrap();
+        assert_eq!(first.operations, second.operations);
+    }
+
+    #[tokio::test]
+    async fn test_extensions_that_keep_the_guarantees_conform() {
+        let client = ClientBuilder::new()
+            .with_return_managed_fields()
+            .with_observed_generation_for::<Deployment>()
+            .with_injected_labels([("tenant", "team-a")])
+            .build()
+            .await
+            .unwrap();
+
+        let report = conformance::run(client, &seeded(3)).await.unwrap();
+        report.assert_conformant();
+    }
+
+    #[tokio::test]
+    async fn test_reports_an_interceptor_breaking_resource_versions() {
+        // Answers patches with the version the object was created at
+        let funcs = interceptor::Funcs::new().after_patch(|ctx| {
+            ctx.response["metadata"]["resourceVersion"] = json!("1");
+            Ok(())
+        });
+        let client = ClientBuilder::new()
+            .with_interceptor_funcs(funcs)
+            .build()
+            .await
+            .unwrap();
+
+        let report = conformance::run(client, &seeded(1)).await.unwrap();
+        assert!(!report.is_conformant());
+        assert!(report
+            .violations
+            .iter()
+            .any(|violation| violation.contains("not newer than")));
+    }
+}
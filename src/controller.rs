@@ -0,0 +1,134 @@
+//! Lightweight in-process controllers registered by tests
+//!
+//! A closure registered with
+//! [`ClientHandle::on_change`](crate::ClientHandle::on_change) runs for every
+//! object of its kind that is created or changed, with a [`FakeClient`] to read
+//! and write the rest of the cluster, the way a controller of a real cluster
+//! would reconcile it. It runs right after the write, before the request that
+//! made it returns, so tests see its result as soon as their own write is done.
+//!
+//! Every object is reported once per resourceVersion: writes the closure makes
+//! to other objects of its kind report those objects in turn, and a closure
+//! that leaves its object unchanged is not run again for it.
+
+use crate::client::FakeClient;
+use crate::tracker::{ObjectTracker, WriteObserver, GVR};
+use crate::Result;
+use kube::Resource;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use tracing::warn;
+
+/// Closure run for a created or changed object
+type Reconcile<K> = dyn Fn(&K, &FakeClient) -> Result<()> + Send + Sync;
+
+/// A registered closure and the objects it has seen
+pub(crate) struct Controller<K> {
+    gvr: GVR,
+    reconcile: Box<Reconcile<K>>,
+    /// Client handed to the closure, with the tracker put back on each run
+    client: FakeClient,
+    tracker: Weak<ObjectTracker>,
+    /// resourceVersion each object was last reported at, by namespace and name
+    seen: Mutex<HashMap<(String, String), String>>,
+}
+
+impl<K> Controller<K>
+where
+    K: Resource<DynamicType = ()> + DeserializeOwned + 'static,
+{
+    pub(crate) fn new<F>(gvr: GVR, client: &FakeClient, reconcile: F) -> Arc<Self>
+    where
+        F: Fn(&K, &FakeClient) -> Result<()> + Send + Sync + 'static,
+    {
+        // The observer lives in the tracker, so holding the tracker would keep
+        // it alive forever
+        let tracker = Arc::downgrade(&client.tracker);
+        let client = FakeClient {
+            tracker: Arc::new(ObjectTracker::new()),
+            ..client.clone()
+        };
+        Arc::new(Self {
+            gvr,
+            reconcile: Box::new(reconcile),
+            client,
+            tracker,
+            seen: Mutex::default(),
+        })
+    }
+
+    /// Tracker observer running the closure for the changed objects of a write
+    pub(crate) fn observer(self: &Arc<Self>) -> WriteObserver {
+        let controller = Arc::clone(self);
+        Arc::new(move |_, gvr, namespace| {
+            if *gvr == controller.gvr {
+                controller.run(Some(namespace));
+            }
+        })
+    }
+
+    /// Run the closure for the objects in a namespace, or in every namespace,
+    /// that changed since it last saw them
+    pub(crate) fn run(&self, namespace: Option<&str>) {
+        let Some(tracker) = self.tracker.upgrade() else {
+            return;
+        };
+        let objects = tracker.list_shared(&self.gvr, namespace.filter(|ns| !ns.is_empty()));
+        let changed: Vec<Arc<Value>> = {
+            let mut seen = self.seen.lock();
+            if let Some(namespace) = namespace {
+                // Forget deleted objects, so the map does not grow forever
+                seen.retain(|(ns, name), _| {
+                    ns != namespace || objects.iter().any(|o| key(o).1 == *name)
+                });
+            }
+            objects
+                .into_iter()
+                .filter(|object| {
+                    let version = object
+                        .pointer("/metadata/resourceVersion")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    seen.insert(key(object), version.to_string()).as_deref() != Some(version)
+                })
+                .collect()
+        };
+        if changed.is_empty() {
+            return;
+        }
+
+        let client = FakeClient {
+            tracker,
+            ..self.client.clone()
+        };
+        for object in changed {
+            let (namespace, name) = key(&object);
+            let result = K::deserialize(object.as_ref())
+                .map_err(Into::into)
+                .and_then(|object| (self.reconcile)(&object, &client));
+            if let Err(e) = result {
+                warn!(
+                    "on_change closure for {} {}/{} failed: {}",
+                    K::kind(&()),
+                    namespace,
+                    name,
+                    e
+                );
+            }
+        }
+    }
+}
+
+fn key(object: &Value) -> (String, String) {
+    let field = |pointer: &str| {
+        object
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    (field("/metadata/namespace"), field("/metadata/name"))
+}
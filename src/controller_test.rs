@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use crate::{ClientBuilder, Error};
+    use k8s_openapi::api::core::v1::{ConfigMap, Pod, PodStatus, Secret};
+    use kube::api::{Api, DeleteParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn pod(name: &str) -> Pod {
+        let mut pod = Pod::default();
+        pod.metadata.name = Some(name.to_string());
+        pod
+    }
+
+    #[tokio::test]
+    async fn test_on_change_updates_status_of_existing_and_new_objects() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(pod("web"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let runs = Arc::new(AtomicUsize::new(0));
+        handle
+            .on_change({
+                let runs = Arc::clone(&runs);
+                move |pod: &Pod, store| {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    if pod.status.as_ref().and_then(|s| s.phase.as_deref()) == Some("Running") {
+                        return Ok(());
+                    }
+                    let mut pod = pod.clone();
+                    pod.status = Some(PodStatus {
+                        phase: Some("Running".to_string()),
+                        ..Default::default()
+                    });
+                    store.update_status("default", &pod, &PostParams::default())?;
+                    Ok(())
+                }
+            })
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+
+        // The object stored before registration was reconciled right away
+        let web = pods.get("web").await.unwrap();
+        assert_eq!(web.status.unwrap().phase.as_deref(), Some("Running"));
+
+        let db = pods
+            .create(&PostParams::default(), &pod("db"))
+            .await
+            .unwrap();
+        assert_eq!(db.status, None, "the response is the object as created");
+        let db = pods.get("db").await.unwrap();
+        assert_eq!(db.status.unwrap().phase.as_deref(), Some("Running"));
+
+        // Once per object version: the write and the status update it caused
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_on_change_maintains_child_objects() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        handle
+            .on_change(|configmap: &ConfigMap, store| {
+                let mut secret = Secret::default();
+                secret.metadata.name = configmap.metadata.name.clone();
+                secret.string_data = configmap.data.clone();
+                let namespace = configmap.metadata.namespace.as_deref().unwrap();
+                match store.create(namespace, &secret, &PostParams::default()) {
+                    Err(Error::AlreadyExists { .. }) => {
+                        let patch = json!({ "stringData": configmap.data });
+                        let name = secret.metadata.name.as_deref().unwrap();
+                        store.patch::<Secret>(namespace, name, &patch, &PatchParams::default())?;
+                        Ok(())
+                    }
+                    created => created.map(|_| ()),
+                }
+            })
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "team-a");
+        let secrets: Api<Secret> = Api::namespaced(client, "team-a");
+
+        let mut configmap = ConfigMap::default();
+        configmap.metadata.name = Some("settings".to_string());
+        configmap.data = Some(BTreeMap::from([("mode".to_string(), "slow".to_string())]));
+        configmaps
+            .create(&PostParams::default(), &configmap)
+            .await
+            .unwrap();
+        let copied = secrets.get("settings").await.unwrap();
+        assert!(copied.data.unwrap().contains_key("mode"));
+
+        let patch = json!({ "data": { "level": "high" } });
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        let copied = secrets.get("settings").await.unwrap();
+        assert!(copied.data.unwrap().contains_key("level"));
+
+        // Deletes are not reported
+        configmaps
+            .delete("settings", &DeleteParams::default())
+            .await
+            .unwrap();
+        assert!(secrets.get_opt("settings").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,169 @@
+//! CertificateSigningRequest approval and signing
+//!
+//! The `approval` subresource only changes the `Approved` and `Denied`
+//! conditions, which are mutually exclusive and cannot be removed once set, and
+//! the `status` subresource cannot change them. When a signer is configured, an
+//! observer stands in for the signing controller and issues a certificate for
+//! every approved request.
+
+use crate::tracker::{ObjectTracker, WriteObserver, GVK, GVR};
+use crate::{Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use k8s_openapi::api::certificates::v1::CertificateSigningRequest;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Issues certificates for approved CertificateSigningRequests
+///
+/// Returns the PEM-encoded certificate, or an error message to mark the request
+/// `Failed`.
+pub type CsrSigner =
+    Arc<dyn Fn(&CertificateSigningRequest) -> std::result::Result<Vec<u8>, String> + Send + Sync>;
+
+/// Condition types that can only be set through the approval subresource
+const APPROVAL_CONDITIONS: &[&str] = &["Approved", "Denied"];
+
+fn csrs_gvr() -> GVR {
+    GVR::new("certificates.k8s.io", "v1", "certificatesigningrequests")
+}
+
+fn csr_gvk() -> GVK {
+    GVK::new("certificates.k8s.io", "v1", "CertificateSigningRequest")
+}
+
+fn conditions(csr: &Value) -> &[Value] {
+    csr.pointer("/status/conditions")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn condition_type(condition: &Value) -> &str {
+    condition
+        .get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+}
+
+fn has_condition(csr: &Value, type_: &str) -> bool {
+    conditions(csr).iter().any(|c| condition_type(c) == type_)
+}
+
+fn invalid(csr: &Value, detail: String) -> Error {
+    let name = csr
+        .pointer("/metadata/name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    Error::InvalidRequest(format!(
+        "CertificateSigningRequest.certificates.k8s.io \"{name}\" is invalid: {detail}"
+    ))
+}
+
+fn forbidden(csr: &Value, action: &str, type_: &str) -> Error {
+    invalid(
+        csr,
+        format!(
+            "status.conditions: Forbidden: updates may not {action} a condition of type \
+             \"{type_}\""
+        ),
+    )
+}
+
+/// Restrict a write to the approval subresource to the approval conditions
+///
+/// Everything except `status.conditions` is taken from the stored object.
+pub(crate) fn prepare_approval(old: &Value, new: &mut Value) -> Result<()> {
+    let new_conditions = conditions(new).to_vec();
+
+    if has_condition(new, "Approved") && has_condition(new, "Denied") {
+        return Err(invalid(
+            new,
+            "status.conditions: Invalid value: Approved and Denied conditions are mutually \
+             exclusive"
+                .to_string(),
+        ));
+    }
+    for type_ in APPROVAL_CONDITIONS {
+        if has_condition(old, type_) && !has_condition(new, type_) {
+            return Err(forbidden(new, "remove", type_));
+        }
+    }
+
+    let mut merged = old.clone();
+    merged["status"]["conditions"] = json!(new_conditions);
+    if let Some(rv) = new.pointer("/metadata/resourceVersion") {
+        merged["metadata"]["resourceVersion"] = rv.clone();
+    }
+    *new = merged;
+    Ok(())
+}
+
+/// Reject status writes that add or remove approval conditions
+pub(crate) fn prepare_status(old: &Value, new: &Value) -> Result<()> {
+    let count = |csr: &Value, type_: &str| {
+        conditions(csr)
+            .iter()
+            .filter(|c| condition_type(c) == type_)
+            .count()
+    };
+    for type_ in APPROVAL_CONDITIONS {
+        let (before, after) = (count(old, type_), count(new, type_));
+        if after > before {
+            return Err(forbidden(new, "add", type_));
+        }
+        if after < before {
+            return Err(forbidden(new, "remove", type_));
+        }
+    }
+    Ok(())
+}
+
+/// Tracker observer issuing certificates for approved requests
+pub(crate) fn signer_observer(signer: CsrSigner) -> WriteObserver {
+    Arc::new(move |tracker, gvr, _namespace| {
+        if *gvr != csrs_gvr() {
+            return;
+        }
+        if let Err(e) = sign_approved(tracker, &signer) {
+            warn!("Failed to sign CertificateSigningRequests: {}", e);
+        }
+    })
+}
+
+fn sign_approved(tracker: &ObjectTracker, signer: &CsrSigner) -> Result<()> {
+    for mut csr in tracker.list(&csrs_gvr(), None)? {
+        let issued = csr
+            .pointer("/status/certificate")
+            .and_then(|v| v.as_str())
+            .is_some_and(|c| !c.is_empty());
+        if !has_condition(&csr, "Approved")
+            || has_condition(&csr, "Denied")
+            || has_condition(&csr, "Failed")
+            || issued
+        {
+            continue;
+        }
+
+        let typed: CertificateSigningRequest = serde_json::from_value(csr.clone())?;
+        match signer(&typed) {
+            Ok(certificate) => csr["status"]["certificate"] = json!(STANDARD.encode(certificate)),
+            Err(message) => {
+                let now = crate::clock::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+                let mut conditions = conditions(&csr).to_vec();
+                conditions.push(json!({
+                    "type": "Failed",
+                    "status": "True",
+                    "reason": "SignerFailed",
+                    "message": message,
+                    "lastUpdateTime": now,
+                    "lastTransitionTime": now,
+                }));
+                csr["status"]["conditions"] = json!(conditions);
+            }
+        }
+        tracker.update(&csrs_gvr(), &csr_gvk(), csr, "", true)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::certificates::v1::CertificateSigningRequest;
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    fn csr(name: &str, signer_name: &str) -> CertificateSigningRequest {
+        serde_json::from_value(json!({
+            "apiVersion": "certificates.k8s.io/v1",
+            "kind": "CertificateSigningRequest",
+            "metadata": { "name": name },
+            "spec": {
+                "request": "LS0tLS1CRUdJTiBDRVJUSUZJQ0FURSBSRVFVRVNULS0tLS0K",
+                "signerName": signer_name,
+                "usages": ["client auth"]
+            }
+        }))
+        .unwrap()
+    }
+
+    fn condition(type_: &str) -> serde_json::Value {
+        json!({
+            "status": {
+                "conditions": [{
+                    "type": type_,
+                    "status": "True",
+                    "reason": "Test",
+                    "message": "set by test"
+                }]
+            }
+        })
+    }
+
+    fn condition_types(csr: &CertificateSigningRequest) -> Vec<String> {
+        csr.status
+            .as_ref()
+            .and_then(|s| s.conditions.as_ref())
+            .map(|c| c.iter().map(|c| c.type_.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_approval_subresource_only_changes_conditions() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let csrs: Api<CertificateSigningRequest> = Api::all(client);
+        csrs.create(&PostParams::default(), &csr("node", "example.com/signer"))
+            .await
+            .unwrap();
+
+        let mut patch = condition("Approved");
+        patch["spec"] = json!({ "signerName": "example.com/other" });
+        let approved = csrs
+            .patch_approval("node", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert_eq!(condition_types(&approved), vec!["Approved"]);
+        assert_eq!(approved.spec.signer_name, "example.com/signer");
+
+        let fetched = csrs.get_approval("node").await.unwrap();
+        assert_eq!(condition_types(&fetched), vec!["Approved"]);
+
+        // Approved and Denied are mutually exclusive
+        let both = json!({
+            "status": { "conditions": [
+                { "type": "Approved", "status": "True" },
+                { "type": "Denied", "status": "True" }
+            ] }
+        });
+        let err = csrs
+            .patch_approval("node", &PatchParams::default(), &Patch::Merge(&both))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+
+        // Approval conditions cannot be removed, nor changed through status
+        let cleared = json!({ "status": { "conditions": [] } });
+        let err = csrs
+            .patch_approval("node", &PatchParams::default(), &Patch::Merge(&cleared))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+        let err = csrs
+            .patch_status("node", &PatchParams::default(), &Patch::Merge(&cleared))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+    }
+
+    #[tokio::test]
+    async fn test_signer_issues_certificates_for_approved_requests() {
+        let client = ClientBuilder::new()
+            .with_csr_signer(|csr| {
+                if csr.spec.signer_name == "example.com/signer" {
+                    Ok(b"-----BEGIN CERTIFICATE-----\nfake\n-----END CERTIFICATE-----\n".to_vec())
+                } else {
+                    Err("unsupported signer".to_string())
+                }
+            })
+            .build()
+            .await
+            .unwrap();
+        let csrs: Api<CertificateSigningRequest> = Api::all(client);
+
+        csrs.create(&PostParams::default(), &csr("good", "example.com/signer"))
+            .await
+            .unwrap();
+        csrs.create(
+            &PostParams::default(),
+            &csr("pending", "example.com/signer"),
+        )
+        .await
+        .unwrap();
+        csrs.create(&PostParams::default(), &csr("bad", "example.com/unknown"))
+            .await
+            .unwrap();
+
+        for name in ["good", "bad"] {
+            csrs.patch_approval(
+                name,
+                &PatchParams::default(),
+                &Patch::Merge(&condition("Approved")),
+            )
+            .await
+            .unwrap();
+        }
+
+        let good = csrs.get("good").await.unwrap();
+        let certificate = good.status.unwrap().certificate.unwrap();
+        assert!(String::from_utf8(certificate.0)
+            .unwrap()
+            .starts_with("-----BEGIN CERTIFICATE-----"));
+
+        let pending = csrs.get("pending").await.unwrap();
+        assert!(pending.status.and_then(|s| s.certificate).is_none());
+
+        let bad = csrs.get("bad").await.unwrap();
+        assert_eq!(condition_types(&bad), vec!["Approved", "Failed"]);
+        assert!(bad.status.unwrap().certificate.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_denied_requests_are_not_signed() {
+        let client = ClientBuilder::new()
+            .with_csr_signer(|_| Ok(b"cert".to_vec()))
+            .build()
+            .await
+            .unwrap();
+        let csrs: Api<CertificateSigningRequest> = Api::all(client);
+        csrs.create(&PostParams::default(), &csr("node", "example.com/signer"))
+            .await
+            .unwrap();
+
+        csrs.patch_approval(
+            "node",
+            &PatchParams::default(),
+            &Patch::Merge(&condition("Denied")),
+        )
+        .await
+        .unwrap();
+        let denied = csrs.get("node").await.unwrap();
+        assert!(denied.status.unwrap().certificate.is_none());
+    }
+}
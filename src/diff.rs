@@ -0,0 +1,102 @@
+//! Line diffs for assertion failures
+//!
+//! Renders the difference between two texts, such as objects serialized as YAML,
+//! the way `diff -u` does: unchanged lines around each change are kept for
+//! context and the rest is elided.
+
+/// Unchanged lines shown around each change
+const CONTEXT: usize = 3;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Diff two texts line by line, marking lines only in `expected` with `-` and
+/// lines only in `actual` with `+`, colored red and green if `color` is set
+pub(crate) fn lines(expected: &str, actual: &str, color: bool) -> String {
+    let lines = diff(
+        &expected.lines().collect::<Vec<_>>(),
+        &actual.lines().collect::<Vec<_>>(),
+    );
+
+    // Keep the lines within CONTEXT of a change
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Line::Same(_)))
+        .map(|(index, _)| index)
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+    let shown = |index: usize| {
+        changed
+            .iter()
+            .any(|changed| changed.abs_diff(index) <= CONTEXT)
+    };
+
+    let mut out = String::new();
+    let mut elided = false;
+    for (index, line) in lines.iter().enumerate() {
+        if !shown(index) {
+            elided = true;
+            continue;
+        }
+        if std::mem::take(&mut elided) {
+            out.push_str("  ...\n");
+        }
+        let (marker, text, paint) = match line {
+            Line::Same(text) => (' ', text, None),
+            Line::Removed(text) => ('-', text, Some(RED)),
+            Line::Added(text) => ('+', text, Some(GREEN)),
+        };
+        match paint {
+            Some(paint) if color => out.push_str(&format!("{paint}{marker} {text}{RESET}\n")),
+            _ => out.push_str(&format!("{marker} {text}\n")),
+        }
+    }
+    if elided {
+        out.push_str("  ...\n");
+    }
+    out
+}
+
+/// Longest common subsequence diff, with removals before additions in each change
+fn diff<'a>(expected: &[&'a str], actual: &[&'a str]) -> Vec<Line<'a>> {
+    // common[i][j] is the length of the LCS of expected[i..] and actual[j..]
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::with_capacity(expected.len().max(actual.len()));
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(Line::Same(expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+        {
+            lines.push(Line::Removed(expected[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(actual[j]));
+            j += 1;
+        }
+    }
+    lines
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use crate::diff::lines;
+
+    #[test]
+    fn test_identical_texts_have_no_diff() {
+        assert_eq!(lines("a\nb\n", "a\nb\n", false), "");
+    }
+
+    #[test]
+    fn test_changed_line() {
+        let expected = "kind: ConfigMap\ndata:\n  mode: fast\n";
+        let actual = "kind: ConfigMap\ndata:\n  mode: safe\n";
+        assert_eq!(
+            lines(expected, actual, false),
+            "  kind: ConfigMap\n  data:\n-   mode: fast\n+   mode: safe\n"
+        );
+    }
+
+    #[test]
+    fn test_context_is_elided_away_from_changes() {
+        let expected: String = (0..20).map(|i| format!("line {i}\n")).collect();
+        let actual = expected.replace("line 10\n", "line ten\n");
+        assert_eq!(
+            lines(&expected, &actual, false),
+            "  ...\n  line 7\n  line 8\n  line 9\n- line 10\n+ line ten\n  line 11\n  line 12\n  line 13\n  ...\n"
+        );
+    }
+
+    #[test]
+    fn test_added_and_removed_lines() {
+        assert_eq!(lines("a\nb\n", "a\nc\nb\n", false), "  a\n+ c\n  b\n");
+        assert_eq!(lines("a\nb\nc\n", "a\nc\n", false), "  a\n- b\n  c\n");
+    }
+
+    #[test]
+    fn test_color() {
+        assert_eq!(
+            lines("a\n", "b\n", true),
+            "\x1b[31m- a\x1b[0m\n\x1b[32m+ b\x1b[0m\n"
+        );
+    }
+}
@@ -5,7 +5,7 @@
 //!
 //! The data is sourced from `kubernetes/api/discovery/` JSON files.
 //! To update the generated lookup functions, run:
-//! `cargo run --bin discovery-gen`
+//! `cargo run --features codegen --bin discovery-gen`
 //!
 //! # Architecture
 //!
@@ -76,7 +76,8 @@ impl Discovery {
 
     /// Get the Kind for a given plural resource name, checking both built-in resources and registry
     ///
-    /// This checks static discovery first, then checks the registry for registered CRDs.
+    /// This checks the registry first, for registered CRDs and overridden built-in
+    /// resources, then static discovery.
     /// Returns None if the resource is not found in either location.
     ///
     /// This method should be used when parsing URLs to ensure CRDs are properly handled.
@@ -99,15 +100,12 @@ impl Discovery {
         plural: &str,
         registry: &ResourceRegistry,
     ) -> Option<Cow<'static, str>> {
-        // First check static discovery (zero-cost for built-in resources)
-        if let Some(kind) = plural_to_kind(group, version, plural) {
-            return Some(Cow::Borrowed(kind));
+        if let Some(kind) = registry.plural_to_kind(group, version, plural) {
+            return Some(Cow::Owned(kind));
         }
-
-        // Check registry for registered CRDs
-        registry
-            .plural_to_kind(group, version, plural)
-            .map(Cow::Owned)
+        plural_to_kind(group, version, plural)
+            .filter(|kind| !registry.is_removed(group, version, kind))
+            .map(Cow::Borrowed)
     }
 
     /// Convert GVR to GVK using discovery data
@@ -122,7 +120,7 @@ impl Discovery {
 
     /// Convert GVR to GVK, checking both built-in resources and registry
     ///
-    /// This checks static discovery first, then falls back to the registry for CRDs.
+    /// This checks the registry first, then falls back to static discovery.
     /// Returns None if the resource is not found in either location.
     ///
     /// This method should be used when parsing URLs to ensure CRDs are properly handled.
@@ -158,18 +156,45 @@ impl Discovery {
 
     /// Convert GVK to GVR, checking both built-in resources and registry
     ///
-    /// This checks static discovery first, then checks the registry for registered CRDs.
+    /// This checks the registry first, for registered CRDs and overridden built-in
+    /// resources, then static discovery.
     /// Returns None if the resource is not found in either location.
     pub fn gvk_to_gvr_with_registry(gvk: &GVK, registry: &ResourceRegistry) -> Option<GVR> {
-        // First check static discovery
-        if let Some(plural) = Self::get_plural(gvk) {
+        if let Some(plural) = registry.kind_to_plural(&gvk.group, &gvk.version, &gvk.kind) {
             return Some(GVR::new(&gvk.group, &gvk.version, plural));
         }
+        Self::builtin_plural(gvk, registry).map(|plural| GVR::new(&gvk.group, &gvk.version, plural))
+    }
 
-        // Check registry for registered CRDs
+    /// Check if a resource is namespaced, checking both the registry and built-in resources
+    ///
+    /// Returns `None` if the resource is not found in either location.
+    pub fn is_namespaced_with_registry(gvk: &GVK, registry: &ResourceRegistry) -> Option<bool> {
         registry
-            .kind_to_plural(&gvk.group, &gvk.version, &gvk.kind)
-            .map(|plural| GVR::new(&gvk.group, &gvk.version, plural))
+            .is_namespaced(&gvk.group, &gvk.version, &gvk.kind)
+            .or_else(|| {
+                Self::builtin_plural(gvk, registry)?;
+                Self::is_namespaced(gvk)
+            })
+    }
+
+    /// Check if a resource supports a verb, checking both the registry and built-in resources
+    ///
+    /// Returns `None` if the resource is not found in either location.
+    pub fn supports_verb_with_registry(
+        gvk: &GVK,
+        verb: &str,
+        registry: &ResourceRegistry,
+    ) -> Option<bool> {
+        if let Some(metadata) = registry.lookup_by_kind(&gvk.group, &gvk.version, &gvk.kind) {
+            return Some(metadata.verbs.iter().any(|v| v == verb));
+        }
+        Self::builtin_plural(gvk, registry).map(|_| Self::supports_verb(gvk, verb))
+    }
+
+    /// The plural of a built-in resource, unless it was removed from the registry
+    fn builtin_plural(gvk: &GVK, registry: &ResourceRegistry) -> Option<&'static str> {
+        Self::get_plural(gvk).filter(|_| !registry.is_removed(&gvk.group, &gvk.version, &gvk.kind))
     }
 
     /// List all known built-in resources (for debugging/introspection)
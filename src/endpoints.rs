@@ -0,0 +1,449 @@
+//! Endpoints and EndpointSlice controller simulation
+//!
+//! When enabled, every write to a Service or Pod reconciles the Endpoints and
+//! EndpointSlice objects of the Services in that namespace, the way the
+//! endpoint and endpointslice controllers of a real cluster would.
+//!
+//! Only Services with a non-empty selector are managed. Each selected Pod that
+//! has a `status.podIP` and is not finished becomes an endpoint; its readiness
+//! comes from the Pod's `Ready` condition.
+
+use crate::tracker::{ObjectTracker, WriteObserver, GVK, GVR};
+use crate::Result;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Label linking an EndpointSlice to its Service
+const SERVICE_NAME_LABEL: &str = "kubernetes.io/service-name";
+/// Label identifying the controller that manages an EndpointSlice
+const MANAGED_BY_LABEL: &str = "endpointslice.kubernetes.io/managed-by";
+const MANAGED_BY: &str = "endpointslice-controller.k8s.io";
+/// Label the endpoints controller puts on the Endpoints it manages
+const SKIP_MIRROR_LABEL: &str = "endpointslice.kubernetes.io/skip-mirror";
+
+fn services_gvr() -> GVR {
+    GVR::new("", "v1", "services")
+}
+
+fn pods_gvr() -> GVR {
+    GVR::new("", "v1", "pods")
+}
+
+fn endpoints_gvr() -> GVR {
+    GVR::new("", "v1", "endpoints")
+}
+
+fn slices_gvr() -> GVR {
+    GVR::new("discovery.k8s.io", "v1", "endpointslices")
+}
+
+/// Tracker observer reconciling endpoints whenever a Service or Pod changes
+pub(crate) fn observer() -> WriteObserver {
+    Arc::new(|tracker, gvr, namespace| {
+        if *gvr != services_gvr() && *gvr != pods_gvr() {
+            return;
+        }
+        if let Err(e) = reconcile(tracker, namespace) {
+            warn!(
+                "Failed to reconcile endpoints in namespace {}: {}",
+                namespace, e
+            );
+        }
+    })
+}
+
+/// A Pod backing a Service
+struct Backend<'a> {
+    ip: &'a str,
+    pod: &'a Value,
+    ready: bool,
+    terminating: bool,
+}
+
+/// Resolved Service port: (name, port, protocol)
+type EndpointPort = (String, i64, String);
+
+/// Bring the Endpoints and EndpointSlices of every Service in a namespace up to date
+pub(crate) fn reconcile(tracker: &ObjectTracker, namespace: &str) -> Result<()> {
+    let services = tracker.list(&services_gvr(), Some(namespace))?;
+    let pods = tracker.list(&pods_gvr(), Some(namespace))?;
+
+    let mut managed = Vec::new();
+    for service in &services {
+        let Some(selector) = selector(service) else {
+            continue;
+        };
+        let name = str_at(service, &["metadata", "name"]).unwrap_or_default();
+        managed.push(name.to_string());
+
+        let backends: Vec<Backend> = pods
+            .iter()
+            .filter(|pod| matches_selector(pod, selector))
+            .filter_map(backend)
+            .collect();
+        let groups = group_by_ports(service, &backends);
+
+        sync_endpoints(tracker, namespace, service, &groups)?;
+        sync_slices(tracker, namespace, service, &groups)?;
+    }
+
+    // Clean up slices whose Service is gone or no longer has a selector
+    for slice in tracker.list(&slices_gvr(), Some(namespace))? {
+        let Some(labels) = slice.pointer("/metadata/labels") else {
+            continue;
+        };
+        if labels.get(MANAGED_BY_LABEL).and_then(|v| v.as_str()) != Some(MANAGED_BY) {
+            continue;
+        }
+        let service = labels
+            .get(SERVICE_NAME_LABEL)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        if !managed.iter().any(|m| m == service) {
+            let name = str_at(&slice, &["metadata", "name"]).unwrap_or_default();
+            tracker.delete(&slices_gvr(), namespace, name)?;
+        }
+    }
+
+    // Endpoints share the Service's name and are removed along with it
+    for endpoints in tracker.list(&endpoints_gvr(), Some(namespace))? {
+        let name = str_at(&endpoints, &["metadata", "name"]).unwrap_or_default();
+        let has_service = services
+            .iter()
+            .any(|s| str_at(s, &["metadata", "name"]) == Some(name));
+        let was_managed = endpoints
+            .pointer("/metadata/labels")
+            .and_then(|l| l.get(SKIP_MIRROR_LABEL))
+            .is_some();
+        if !has_service && was_managed {
+            tracker.delete(&endpoints_gvr(), namespace, name)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn sync_endpoints(
+    tracker: &ObjectTracker,
+    namespace: &str,
+    service: &Value,
+    groups: &BTreeMap<Vec<EndpointPort>, Vec<&Backend>>,
+) -> Result<()> {
+    let name = str_at(service, &["metadata", "name"]).unwrap_or_default();
+    let subsets: Vec<Value> = groups
+        .iter()
+        .filter_map(|(ports, backends)| {
+            let addresses: Vec<Value> = backends
+                .iter()
+                .filter(|b| b.ready && !b.terminating)
+                .map(|b| target_address(b))
+                .collect();
+            let not_ready: Vec<Value> = backends
+                .iter()
+                .filter(|b| !b.ready && !b.terminating)
+                .map(|b| target_address(b))
+                .collect();
+            if addresses.is_empty() && not_ready.is_empty() {
+                return None;
+            }
+            let mut subset = Map::new();
+            if !addresses.is_empty() {
+                subset.insert("addresses".to_string(), json!(addresses));
+            }
+            if !not_ready.is_empty() {
+                subset.insert("notReadyAddresses".to_string(), json!(not_ready));
+            }
+            let ports: Vec<Value> = ports
+                .iter()
+                .map(|(port_name, port, protocol)| port_json(port_name, *port, protocol))
+                .collect();
+            subset.insert("ports".to_string(), json!(ports));
+            Some(Value::Object(subset))
+        })
+        .collect();
+
+    let mut labels = labels(service);
+    labels.insert(SKIP_MIRROR_LABEL.to_string(), json!("true"));
+    let mut desired = json!({
+        "apiVersion": "v1",
+        "kind": "Endpoints",
+        "metadata": { "name": name, "namespace": namespace, "labels": labels },
+    });
+    if !subsets.is_empty() {
+        desired["subsets"] = json!(subsets);
+    }
+
+    upsert(
+        tracker,
+        &endpoints_gvr(),
+        &GVK::new("", "v1", "Endpoints"),
+        namespace,
+        desired,
+        &["subsets"],
+    )
+}
+
+fn sync_slices(
+    tracker: &ObjectTracker,
+    namespace: &str,
+    service: &Value,
+    groups: &BTreeMap<Vec<EndpointPort>, Vec<&Backend>>,
+) -> Result<()> {
+    let service_name = str_at(service, &["metadata", "name"]).unwrap_or_default();
+
+    // A Service without endpoints still gets a single, empty slice
+    let empty = BTreeMap::from([(Vec::new(), Vec::new())]);
+    let groups = if groups.is_empty() { &empty } else { groups };
+
+    let mut labels = labels(service);
+    labels.insert(SERVICE_NAME_LABEL.to_string(), json!(service_name));
+    labels.insert(MANAGED_BY_LABEL.to_string(), json!(MANAGED_BY));
+
+    let mut names = Vec::new();
+    for (index, (ports, backends)) in groups.iter().enumerate() {
+        let name = format!("{service_name}-{index}");
+        let endpoints: Vec<Value> = backends
+            .iter()
+            .map(|b| {
+                let mut endpoint = json!({
+                    "addresses": [b.ip],
+                    "conditions": {
+                        "ready": b.ready && !b.terminating,
+                        "serving": b.ready,
+                        "terminating": b.terminating,
+                    },
+                    "targetRef": target_ref(b.pod),
+                });
+                if let Some(node) = str_at(b.pod, &["spec", "nodeName"]) {
+                    endpoint["nodeName"] = json!(node);
+                }
+                endpoint
+            })
+            .collect();
+        let ports: Vec<Value> = ports
+            .iter()
+            .map(|(port_name, port, protocol)| port_json(port_name, *port, protocol))
+            .collect();
+
+        let desired = json!({
+            "apiVersion": "discovery.k8s.io/v1",
+            "kind": "EndpointSlice",
+            "metadata": {
+                "name": name,
+                "namespace": namespace,
+                "labels": labels,
+                "ownerReferences": [{
+                    "apiVersion": "v1",
+                    "kind": "Service",
+                    "name": service_name,
+                    "uid": str_at(service, &["metadata", "uid"]).unwrap_or_default(),
+                    "controller": true,
+                    "blockOwnerDeletion": true,
+                }],
+            },
+            "addressType": "IPv4",
+            "endpoints": endpoints,
+            "ports": ports,
+        });
+        upsert(
+            tracker,
+            &slices_gvr(),
+            &GVK::new("discovery.k8s.io", "v1", "EndpointSlice"),
+            namespace,
+            desired,
+            &["endpoints", "ports"],
+        )?;
+        names.push(name);
+    }
+
+    // Remove slices left over from a larger set of port groups
+    for slice in tracker.list(&slices_gvr(), Some(namespace))? {
+        let owned = slice
+            .pointer("/metadata/labels")
+            .and_then(|l| l.get(SERVICE_NAME_LABEL))
+            .and_then(|v| v.as_str())
+            == Some(service_name);
+        let name = str_at(&slice, &["metadata", "name"]).unwrap_or_default();
+        if owned && !names.iter().any(|n| n == name) {
+            tracker.delete(&slices_gvr(), namespace, name)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Create the object, or update it when any of `fields` or its labels differ
+fn upsert(
+    tracker: &ObjectTracker,
+    gvr: &GVR,
+    gvk: &GVK,
+    namespace: &str,
+    mut desired: Value,
+    fields: &[&str],
+) -> Result<()> {
+    let name = str_at(&desired, &["metadata", "name"])
+        .unwrap_or_default()
+        .to_string();
+    let existing = match tracker.get(gvr, namespace, &name) {
+        Ok(existing) => existing,
+        Err(_) => {
+            tracker.create(gvr, desired, namespace)?;
+            return Ok(());
+        }
+    };
+
+    let unchanged = fields.iter().all(|f| existing.get(f) == desired.get(f))
+        && existing.pointer("/metadata/labels") == desired.pointer("/metadata/labels");
+    if unchanged {
+        return Ok(());
+    }
+
+    let mut metadata = existing["metadata"].clone();
+    metadata["labels"] = desired["metadata"]["labels"].clone();
+    desired["metadata"] = metadata;
+    tracker.update(gvr, gvk, desired, namespace, false)?;
+    Ok(())
+}
+
+/// Group backends by their resolved port list
+///
+/// Named target ports are resolved against each Pod's container ports, so Pods
+/// exposing a name on different numbers end up in different groups.
+fn group_by_ports<'a>(
+    service: &Value,
+    backends: &'a [Backend<'a>],
+) -> BTreeMap<Vec<EndpointPort>, Vec<&'a Backend<'a>>> {
+    let service_ports: &[Value] = service
+        .pointer("/spec/ports")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut groups: BTreeMap<Vec<EndpointPort>, Vec<&Backend>> = BTreeMap::new();
+    for backend in backends {
+        let ports = service_ports
+            .iter()
+            .filter_map(|port| {
+                let name = port
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let protocol = port
+                    .get("protocol")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("TCP")
+                    .to_string();
+                let number = match port.get("targetPort") {
+                    Some(Value::Number(n)) => n.as_i64()?,
+                    Some(Value::String(target)) => container_port(backend.pod, target)?,
+                    _ => port.get("port").and_then(|v| v.as_i64())?,
+                };
+                Some((name, number, protocol))
+            })
+            .collect();
+        groups.entry(ports).or_default().push(backend);
+    }
+    for backends in groups.values_mut() {
+        backends.sort_by_key(|b| b.ip);
+    }
+    groups
+}
+
+fn container_port(pod: &Value, name: &str) -> Option<i64> {
+    pod.pointer("/spec/containers")?
+        .as_array()?
+        .iter()
+        .flat_map(|c| {
+            c.get("ports")
+                .and_then(|p| p.as_array())
+                .into_iter()
+                .flatten()
+        })
+        .find(|p| p.get("name").and_then(|v| v.as_str()) == Some(name))
+        .and_then(|p| p.get("containerPort"))
+        .and_then(|v| v.as_i64())
+}
+
+fn backend(pod: &Value) -> Option<Backend<'_>> {
+    let ip = str_at(pod, &["status", "podIP"]).filter(|ip| !ip.is_empty())?;
+    let phase = str_at(pod, &["status", "phase"]);
+    if matches!(phase, Some("Succeeded") | Some("Failed")) {
+        return None;
+    }
+    let ready = pod
+        .pointer("/status/conditions")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .any(|c| {
+            c.get("type").and_then(|v| v.as_str()) == Some("Ready")
+                && c.get("status").and_then(|v| v.as_str()) == Some("True")
+        });
+    let terminating = pod.pointer("/metadata/deletionTimestamp").is_some();
+    Some(Backend {
+        ip,
+        pod,
+        ready,
+        terminating,
+    })
+}
+
+fn target_address(backend: &Backend) -> Value {
+    let mut address = json!({ "ip": backend.ip, "targetRef": target_ref(backend.pod) });
+    if let Some(node) = str_at(backend.pod, &["spec", "nodeName"]) {
+        address["nodeName"] = json!(node);
+    }
+    address
+}
+
+fn target_ref(pod: &Value) -> Value {
+    json!({
+        "kind": "Pod",
+        "name": str_at(pod, &["metadata", "name"]),
+        "namespace": str_at(pod, &["metadata", "namespace"]),
+        "uid": str_at(pod, &["metadata", "uid"]),
+    })
+}
+
+fn port_json(name: &str, port: i64, protocol: &str) -> Value {
+    let mut value = json!({ "port": port, "protocol": protocol });
+    if !name.is_empty() {
+        value["name"] = json!(name);
+    }
+    value
+}
+
+/// The Service's selector, if it should be managed by the controller
+fn selector(service: &Value) -> Option<&Map<String, Value>> {
+    if str_at(service, &["spec", "type"]) == Some("ExternalName") {
+        return None;
+    }
+    service
+        .pointer("/spec/selector")
+        .and_then(|v| v.as_object())
+        .filter(|s| !s.is_empty())
+}
+
+fn matches_selector(pod: &Value, selector: &Map<String, Value>) -> bool {
+    let labels = pod.pointer("/metadata/labels");
+    selector
+        .iter()
+        .all(|(k, v)| labels.and_then(|l| l.get(k)) == Some(v))
+}
+
+fn labels(service: &Value) -> Map<String, Value> {
+    service
+        .pointer("/metadata/labels")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn str_at<'a>(value: &'a Value, path: &[&str]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .and_then(|v| v.as_str())
+}
@@ -0,0 +1,201 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{Endpoints, Pod, Service};
+    use k8s_openapi::api::discovery::v1::EndpointSlice;
+    use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    fn service(name: &str, selector: serde_json::Value) -> Service {
+        serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "Service",
+            "metadata": { "name": name },
+            "spec": {
+                "selector": selector,
+                "ports": [{ "name": "http", "port": 80, "targetPort": "web" }]
+            }
+        }))
+        .unwrap()
+    }
+
+    fn pod(name: &str, app: &str, ip: &str, ready: bool) -> Pod {
+        serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "name": name, "labels": { "app": app } },
+            "spec": {
+                "nodeName": "node-1",
+                "containers": [{
+                    "name": "main",
+                    "ports": [{ "name": "web", "containerPort": 8080 }]
+                }]
+            },
+            "status": {
+                "podIP": ip,
+                "conditions": [{ "type": "Ready", "status": if ready { "True" } else { "False" } }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_follow_matching_pods() {
+        let client = ClientBuilder::new()
+            .with_endpoints_controller()
+            .build()
+            .await
+            .unwrap();
+        let services: Api<Service> = Api::namespaced(client.clone(), "default");
+        let pods: Api<Pod> = Api::namespaced(client.clone(), "default");
+        let endpoints: Api<Endpoints> = Api::namespaced(client, "default");
+
+        services
+            .create(
+                &PostParams::default(),
+                &service("web", json!({ "app": "web" })),
+            )
+            .await
+            .unwrap();
+        let ep = endpoints.get("web").await.unwrap();
+        assert!(ep.subsets.is_none());
+
+        for p in [
+            pod("web-b", "web", "10.0.0.2", true),
+            pod("web-a", "web", "10.0.0.1", false),
+            pod("db", "db", "10.0.0.3", true),
+        ] {
+            pods.create(&PostParams::default(), &p).await.unwrap();
+        }
+
+        let ep = endpoints.get("web").await.unwrap();
+        let subsets = ep.subsets.unwrap();
+        assert_eq!(subsets.len(), 1);
+        let ready: Vec<_> = subsets[0]
+            .addresses
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|a| a.ip.as_str())
+            .collect();
+        let not_ready: Vec<_> = subsets[0]
+            .not_ready_addresses
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|a| a.ip.as_str())
+            .collect();
+        assert_eq!(ready, vec!["10.0.0.2"]);
+        assert_eq!(not_ready, vec!["10.0.0.1"]);
+
+        // Named target ports resolve to the container port
+        let port = &subsets[0].ports.as_ref().unwrap()[0];
+        assert_eq!(port.port, 8080);
+        assert_eq!(port.name.as_deref(), Some("http"));
+
+        // Deleting a pod removes it from the endpoints
+        pods.delete("web-b", &DeleteParams::default())
+            .await
+            .unwrap();
+        let ep = endpoints.get("web").await.unwrap();
+        assert!(ep.subsets.unwrap()[0].addresses.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_slices_track_readiness() {
+        let client = ClientBuilder::new()
+            .with_endpoints_controller()
+            .with_object(service("web", json!({ "app": "web" })))
+            .with_object(pod("web-a", "web", "10.0.0.1", false))
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client.clone(), "default");
+        let slices: Api<EndpointSlice> = Api::namespaced(client, "default");
+
+        let lp = ListParams::default().labels("kubernetes.io/service-name=web");
+        let list = slices.list(&lp).await.unwrap();
+        assert_eq!(list.items.len(), 1);
+        let endpoint = &list.items[0].endpoints[0];
+        assert_eq!(endpoint.addresses, vec!["10.0.0.1".to_string()]);
+        assert_eq!(endpoint.conditions.as_ref().unwrap().ready, Some(false));
+        assert_eq!(endpoint.node_name.as_deref(), Some("node-1"));
+
+        // Marking the pod ready updates the slice
+        let patch = json!({
+            "status": { "conditions": [{ "type": "Ready", "status": "True" }] }
+        });
+        pods.patch_status("web-a", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        let slice = slices.get("web-0").await.unwrap();
+        let conditions = slice.endpoints[0].conditions.as_ref().unwrap();
+        assert_eq!(conditions.ready, Some(true));
+        assert_eq!(slice.ports.unwrap()[0].port, Some(8080));
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_removed_with_service() {
+        let client = ClientBuilder::new()
+            .with_endpoints_controller()
+            .build()
+            .await
+            .unwrap();
+        let services: Api<Service> = Api::namespaced(client.clone(), "default");
+        let endpoints: Api<Endpoints> = Api::namespaced(client.clone(), "default");
+        let slices: Api<EndpointSlice> = Api::namespaced(client, "default");
+
+        services
+            .create(
+                &PostParams::default(),
+                &service("web", json!({ "app": "web" })),
+            )
+            .await
+            .unwrap();
+        assert!(endpoints.get("web").await.is_ok());
+
+        services
+            .delete("web", &DeleteParams::default())
+            .await
+            .unwrap();
+        assert!(endpoints.get("web").await.is_err());
+        assert!(slices
+            .list(&ListParams::default())
+            .await
+            .unwrap()
+            .items
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_selectorless_services_are_not_managed() {
+        let client = ClientBuilder::new()
+            .with_endpoints_controller()
+            .build()
+            .await
+            .unwrap();
+        let services: Api<Service> = Api::namespaced(client.clone(), "default");
+        let endpoints: Api<Endpoints> = Api::namespaced(client, "default");
+
+        let mut svc = service("external", json!({}));
+        svc.spec.as_mut().unwrap().selector = None;
+        services.create(&PostParams::default(), &svc).await.unwrap();
+        assert!(endpoints.get("external").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_controller_disabled_by_default() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let services: Api<Service> = Api::namespaced(client.clone(), "default");
+        let endpoints: Api<Endpoints> = Api::namespaced(client, "default");
+
+        services
+            .create(
+                &PostParams::default(),
+                &service("web", json!({ "app": "web" })),
+            )
+            .await
+            .unwrap();
+        assert!(endpoints.get("web").await.is_err());
+    }
+}
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::entry::{CommitError, Entry};
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn data(key: &str, value: &str) -> Option<BTreeMap<String, String>> {
+        Some(BTreeMap::from([(key.to_string(), value.to_string())]))
+    }
+
+    fn status_code(error: &CommitError) -> Option<u16> {
+        match error {
+            CommitError::Save(kube::Error::Api(response)) => Some(response.code),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entry_creates_missing_and_replaces_existing_objects() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let mut entry = configmaps
+            .entry("settings")
+            .await
+            .unwrap()
+            .or_insert(ConfigMap::default);
+        entry.get_mut().data = data("mode", "slow");
+        entry.commit(&PostParams::default()).await.unwrap();
+        let created = entry.get().clone();
+        assert_eq!(created.metadata.namespace.as_deref(), Some("default"));
+        assert!(created.metadata.resource_version.is_some());
+        assert!(created.metadata.uid.is_some());
+
+        let Entry::Occupied(mut entry) = configmaps.entry("settings").await.unwrap() else {
+            panic!("the committed object exists");
+        };
+        entry
+            .get_mut()
+            .data
+            .get_or_insert_default()
+            .insert("level".to_string(), "high".to_string());
+        entry.commit(&PostParams::default()).await.unwrap();
+
+        let stored = configmaps.get("settings").await.unwrap();
+        assert_eq!(stored.data.unwrap().len(), 2);
+        assert_ne!(
+            stored.metadata.resource_version,
+            created.metadata.resource_version
+        );
+    }
+
+    #[tokio::test]
+    async fn test_entry_commit_conflicts_and_retries() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        // Two writers find the object missing; the second create loses
+        let mut first = configmaps
+            .entry("settings")
+            .await
+            .unwrap()
+            .or_insert(ConfigMap::default);
+        let mut second = configmaps
+            .entry("settings")
+            .await
+            .unwrap()
+            .or_insert(ConfigMap::default);
+        first.commit(&PostParams::default()).await.unwrap();
+        let error = second.commit(&PostParams::default()).await.unwrap_err();
+        assert_eq!(status_code(&error), Some(409));
+
+        // A writer holding an old copy conflicts with a server-side apply
+        let Entry::Occupied(mut stale) = configmaps.entry("settings").await.unwrap() else {
+            panic!("the object was created");
+        };
+        let apply = json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "settings" },
+            "data": { "owner": "apply" },
+        });
+        configmaps
+            .patch(
+                "settings",
+                &PatchParams::apply("other-manager"),
+                &Patch::Apply(&apply),
+            )
+            .await
+            .unwrap();
+        stale.get_mut().data = data("mode", "slow");
+        let error = stale.commit(&PostParams::default()).await.unwrap_err();
+        assert_eq!(status_code(&error), Some(409));
+
+        // Retrying from a fresh read keeps the other manager's change
+        loop {
+            let mut entry = configmaps
+                .entry("settings")
+                .await
+                .unwrap()
+                .or_insert(ConfigMap::default);
+            entry
+                .get_mut()
+                .data
+                .get_or_insert_default()
+                .insert("mode".to_string(), "slow".to_string());
+            match entry.commit(&PostParams::default()).await {
+                Ok(()) => break,
+                Err(e) if status_code(&e) == Some(409) => continue,
+                Err(e) => panic!("commit failed: {e}"),
+            }
+        }
+        let stored = configmaps.get("settings").await.unwrap().data.unwrap();
+        assert_eq!(stored["owner"], "apply");
+        assert_eq!(stored["mode"], "slow");
+    }
+}
@@ -1,4 +1,6 @@
+use kube::core::response::StatusCause;
 use kube::error::ErrorResponse;
+use std::time::Duration;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -23,9 +25,15 @@ pub enum Error {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
@@ -35,6 +43,9 @@ pub enum Error {
     #[error("Internal error: {0}")]
     Internal(String),
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
     #[error("Failed to access object metadata: {0}")]
     MetadataError(String),
 
@@ -56,14 +67,136 @@ pub enum Error {
 
     #[error("Immutable field cannot be changed: {field}")]
     ImmutableField { field: String },
+
+    #[error("Invalid client configuration: {0}")]
+    InvalidConfiguration(String),
+
+    #[error("Not supported by kube-fake-client: {0}")]
+    Unsupported(String),
+
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
+    /// An API error with the exact status to respond with
+    ///
+    /// Built with [`Error::api`], or from any other error by
+    /// [`with_cause`](Error::with_cause) and
+    /// [`with_retry_after`](Error::with_retry_after).
+    #[error("{reason} ({code}): {message}")]
+    Api {
+        code: u16,
+        reason: String,
+        message: String,
+        /// Field causes, reported in the `details.causes` of the response
+        causes: Vec<StatusCause>,
+        /// How long the client should wait before retrying, sent as the
+        /// `Retry-After` header and `details.retryAfterSeconds`
+        retry_after: Option<Duration>,
+    },
 }
 
 impl Error {
+    /// An API error with a status code, reason and message of the caller's choice
+    ///
+    /// Lets interceptors fail requests the way a real API server or admission
+    /// webhook would, down to the reason controllers match on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kube_fake_client::{interceptor, Error};
+    ///
+    /// let funcs = interceptor::Funcs::new().create(|_ctx| {
+    ///     Err(Error::api(422, "Invalid", r#"ConfigMap "settings" is invalid"#)
+    ///         .with_cause("data.mode", "FieldValueNotSupported", r#"Unsupported value: "turbo""#))
+    /// });
+    /// ```
+    pub fn api(code: u16, reason: impl Into<String>, message: impl Into<String>) -> Self {
+        Error::Api {
+            code,
+            reason: reason.into(),
+            message: message.into(),
+            causes: Vec::new(),
+            retry_after: None,
+        }
+    }
+
+    /// Add a field cause to the error
+    ///
+    /// Errors other than [`Error::Api`] become one with their usual status.
+    pub fn with_cause(
+        self,
+        field: impl Into<String>,
+        reason: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        let mut error = self.into_api();
+        if let Error::Api { causes, .. } = &mut error {
+            causes.push(StatusCause {
+                reason: reason.into(),
+                message: message.into(),
+                field: field.into(),
+            });
+        }
+        error
+    }
+
+    /// Ask the client to wait before retrying
+    ///
+    /// Errors other than [`Error::Api`] become one with their usual status, so
+    /// `Error::TooManyRequests(..).with_retry_after(..)` throttles like an API
+    /// server under load.
+    pub fn with_retry_after(self, delay: Duration) -> Self {
+        let mut error = self.into_api();
+        if let Error::Api { retry_after, .. } = &mut error {
+            *retry_after = Some(delay);
+        }
+        error
+    }
+
+    /// The field causes of the error
+    pub fn causes(&self) -> &[StatusCause] {
+        match self {
+            Error::Api { causes, .. } => causes,
+            _ => &[],
+        }
+    }
+
+    /// How long the client should wait before retrying, if the error says
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::Api { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// The error as an [`Error::Api`] with the same status
+    fn into_api(self) -> Self {
+        match self {
+            api @ Error::Api { .. } => api,
+            other => {
+                let response = other.error_response();
+                Error::Api {
+                    code: response.code,
+                    reason: response.reason,
+                    message: response.message,
+                    causes: Vec::new(),
+                    retry_after: None,
+                }
+            }
+        }
+    }
+
     /// Convert internal error to kube::Error for API compatibility
     /// This ensures fake client returns the same error types as real kube client
     /// with exact message formats matching Kubernetes API
     pub fn into_kube_err(self) -> kube::Error {
-        let error_response = match &self {
+        kube::Error::Api(self.error_response())
+    }
+
+    /// The status of the error as the API server reports it
+    fn error_response(&self) -> ErrorResponse {
+        match self {
             // Format: 'pods "my-pod" not found'
             Error::NotFound { kind, name, .. } => ErrorResponse {
                 status: "Failure".to_string(),
@@ -79,13 +212,21 @@ impl Error {
                 code: 409,
             },
             Error::Conflict(msg)
+            | Error::Forbidden(msg)
             | Error::InvalidRequest(msg)
+            | Error::BadRequest(msg)
             | Error::MetadataError(msg)
-            | Error::Internal(msg) => {
+            | Error::Internal(msg)
+            | Error::TooManyRequests(msg)
+            | Error::Timeout(msg) => {
                 let (reason, code) = match self {
                     Error::Conflict(_) => ("Conflict", 409),
+                    Error::Forbidden(_) => ("Forbidden", 403),
                     Error::InvalidRequest(_) | Error::MetadataError(_) => ("Invalid", 422),
+                    Error::BadRequest(_) => ("BadRequest", 400),
                     Error::Internal(_) => ("InternalError", 500),
+                    Error::TooManyRequests(_) => ("TooManyRequests", 429),
+                    Error::Timeout(_) => ("Timeout", 504),
                     _ => unreachable!(),
                 };
                 ErrorResponse {
@@ -153,8 +294,30 @@ impl Error {
                 reason: "Invalid".to_string(),
                 code: 422,
             },
-        };
-
-        kube::Error::Api(error_response)
+            Error::InvalidConfiguration(msg) => ErrorResponse {
+                status: "Failure".to_string(),
+                message: msg.clone(),
+                reason: "InternalError".to_string(),
+                code: 500,
+            },
+            // Format: 'not supported by kube-fake-client: the "log" subresource of pods'
+            Error::Unsupported(feature) => ErrorResponse {
+                status: "Failure".to_string(),
+                message: format!("not supported by kube-fake-client: {feature}"),
+                reason: "NotImplemented".to_string(),
+                code: 501,
+            },
+            Error::Api {
+                code,
+                reason,
+                message,
+                ..
+            } => ErrorResponse {
+                status: "Failure".to_string(),
+                message: message.clone(),
+                reason: reason.clone(),
+                code: *code,
+            },
+        }
     }
 }
@@ -50,72 +50,103 @@ pub fn extract_preregistered_field_value(
     field: &str,
     kind: &str,
 ) -> Option<Vec<String>> {
+    preregistered_extractor(field, kind).and_then(|extract| extract(obj_value))
+}
+
+/// Whether a field can be selected on for a kind without a custom index
+pub(crate) fn is_preregistered_field(field: &str, kind: &str) -> bool {
+    preregistered_extractor(field, kind).is_some()
+}
+
+/// The namespace a field selector restricts objects to, if it has an equality
+/// requirement on `metadata.namespace`
+///
+/// Lists across all namespaces read just that namespace's objects then; the
+/// selector still has to be matched against them.
+pub(crate) fn selected_namespace(selector: &str) -> Option<&str> {
+    selector.split(',').find_map(|requirement| {
+        if requirement.contains("!=") {
+            return None;
+        }
+        let (field, value) = requirement
+            .split_once("==")
+            .or_else(|| requirement.split_once('='))?;
+        (field.trim() == "metadata.namespace").then(|| value.trim())
+    })
+}
+
+/// Extracts the values of a pre-registered field from an object
+type Extractor = fn(&Value) -> Option<Vec<String>>;
+
+fn preregistered_extractor(field: &str, kind: &str) -> Option<Extractor> {
     // Universal metadata fields (supported by all resources)
     match field {
-        "metadata.name" => return get_string_field(obj_value, "metadata", "name"),
-        "metadata.namespace" => return get_string_field(obj_value, "metadata", "namespace"),
+        "metadata.name" => return Some(|o| get_string_field(o, "metadata", "name")),
+        "metadata.namespace" => return Some(|o| get_string_field(o, "metadata", "namespace")),
         _ => {}
     }
 
     // Resource-specific pre-registered fields
     match kind {
         "Pod" => match field {
-            "spec.nodeName" => get_string_field(obj_value, "spec", "nodeName"),
-            "spec.restartPolicy" => get_string_field(obj_value, "spec", "restartPolicy"),
-            "spec.schedulerName" => get_string_field(obj_value, "spec", "schedulerName"),
-            "spec.serviceAccountName" => get_string_field(obj_value, "spec", "serviceAccountName"),
-            "spec.hostNetwork" => get_bool_field(obj_value, "spec", "hostNetwork"),
-            "status.phase" => get_string_field(obj_value, "status", "phase"),
-            "status.podIP" => get_string_field(obj_value, "status", "podIP"),
+            "spec.nodeName" => Some(|o| get_string_field(o, "spec", "nodeName")),
+            "spec.restartPolicy" => Some(|o| get_string_field(o, "spec", "restartPolicy")),
+            "spec.schedulerName" => Some(|o| get_string_field(o, "spec", "schedulerName")),
+            "spec.serviceAccountName" => {
+                Some(|o| get_string_field(o, "spec", "serviceAccountName"))
+            }
+            "spec.hostNetwork" => Some(|o| get_bool_field(o, "spec", "hostNetwork")),
+            "status.phase" => Some(|o| get_string_field(o, "status", "phase")),
+            "status.podIP" => Some(|o| get_string_field(o, "status", "podIP")),
             "status.nominatedNodeName" => {
-                get_string_field(obj_value, "status", "nominatedNodeName")
+                Some(|o| get_string_field(o, "status", "nominatedNodeName"))
             }
             _ => None,
         },
         "Event" => match field {
-            "involvedObject.kind" => get_string_field(obj_value, "involvedObject", "kind"),
+            "involvedObject.kind" => Some(|o| get_string_field(o, "involvedObject", "kind")),
             "involvedObject.namespace" => {
-                get_string_field(obj_value, "involvedObject", "namespace")
+                Some(|o| get_string_field(o, "involvedObject", "namespace"))
             }
-            "involvedObject.name" => get_string_field(obj_value, "involvedObject", "name"),
-            "involvedObject.uid" => get_string_field(obj_value, "involvedObject", "uid"),
+            "involvedObject.name" => Some(|o| get_string_field(o, "involvedObject", "name")),
+            "involvedObject.uid" => Some(|o| get_string_field(o, "involvedObject", "uid")),
             "involvedObject.apiVersion" => {
-                get_string_field(obj_value, "involvedObject", "apiVersion")
+                Some(|o| get_string_field(o, "involvedObject", "apiVersion"))
             }
             "involvedObject.resourceVersion" => {
-                get_string_field(obj_value, "involvedObject", "resourceVersion")
+                Some(|o| get_string_field(o, "involvedObject", "resourceVersion"))
             }
             "involvedObject.fieldPath" => {
-                get_string_field(obj_value, "involvedObject", "fieldPath")
+                Some(|o| get_string_field(o, "involvedObject", "fieldPath"))
             }
-            "reason" => get_top_level_string(obj_value, "reason"),
-            "reportingComponent" => get_top_level_string(obj_value, "reportingComponent"),
-            "source" => get_top_level_string(obj_value, "source"),
-            "type" => get_top_level_string(obj_value, "type"),
+            "reason" => Some(|o| get_top_level_string(o, "reason")),
+            "reportingComponent" => Some(|o| get_top_level_string(o, "reportingComponent")),
+            "source" => Some(|o| get_top_level_string(o, "source")),
+            "type" => Some(|o| get_top_level_string(o, "type")),
             _ => None,
         },
         "Secret" => match field {
-            "type" => get_top_level_string(obj_value, "type"),
+            "type" => Some(|o| get_top_level_string(o, "type")),
             _ => None,
         },
         "Namespace" => match field {
-            "status.phase" => get_string_field(obj_value, "status", "phase"),
+            "status.phase" => Some(|o| get_string_field(o, "status", "phase")),
             _ => None,
         },
         "ReplicaSet" | "ReplicationController" => match field {
-            "status.replicas" => get_int_field(obj_value, "status", "replicas"),
+            "status.replicas" => Some(|o| get_int_field(o, "status", "replicas")),
             _ => None,
         },
         "Job" => match field {
-            "status.successful" => get_int_field(obj_value, "status", "successful"),
+            "status.successful" => Some(|o| get_int_field(o, "status", "successful")),
             _ => None,
         },
         "Node" => match field {
-            "spec.unschedulable" => get_bool_field(obj_value, "spec", "unschedulable"),
+            "spec.unschedulable" => Some(|o| get_bool_field(o, "spec", "unschedulable")),
             _ => None,
         },
         "CertificateSigningRequest" => match field {
-            "spec.signerName" => get_string_field(obj_value, "spec", "signerName"),
+            "spec.signerName" => Some(|o| get_string_field(o, "spec", "signerName")),
             _ => None,
         },
         _ => None,
@@ -0,0 +1,304 @@
+//! Simulated garbage collection and deletion propagation
+//!
+//! When enabled, deletes honor their `propagationPolicy` the way the API server
+//! and the garbage collector do together:
+//!
+//! - `Orphan` (or `orphanDependents=true`) gives the owner the `orphan` finalizer
+//!   and a `deletionTimestamp`; the collector removes the owner's references from
+//!   its dependents, then the finalizer, and the owner goes away.
+//! - `Foreground` gives the owner the `foregroundDeletion` finalizer and a
+//!   `deletionTimestamp`; the collector deletes the dependents, in the foreground
+//!   as well, and removes the finalizer once no dependent with
+//!   `blockOwnerDeletion: true` is left.
+//! - `Background`, the default, removes the owner right away; the collector then
+//!   deletes every object whose owners are all gone.
+//!
+//! The collector runs after every write, so each step is a write of its own:
+//! watches see the owner in deletion, its dependents going away and the owner's
+//! final removal in order. A dependent held by a finalizer of its own keeps a
+//! foreground-deleted owner in deletion until the finalizer is removed, which is
+//! where controllers reacting to owners in deletion get to see them.
+
+use crate::client_utils::extract_gvk;
+use crate::discovery::Discovery;
+use crate::owners::references;
+use crate::tracker::{ObjectTracker, WriteObserver, GVK, GVR};
+use crate::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Finalizer of an owner whose dependents are being orphaned
+pub(crate) const ORPHAN_FINALIZER: &str = "orphan";
+
+/// Finalizer of an owner waiting for its dependents to be deleted
+pub(crate) const FOREGROUND_FINALIZER: &str = "foregroundDeletion";
+
+/// How a delete treats the dependents of the deleted object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Propagation {
+    Orphan,
+    Background,
+    Foreground,
+}
+
+impl Propagation {
+    /// The finalizer the owner waits for, if any
+    fn finalizer(self) -> Option<&'static str> {
+        match self {
+            Propagation::Orphan => Some(ORPHAN_FINALIZER),
+            Propagation::Background => None,
+            Propagation::Foreground => Some(FOREGROUND_FINALIZER),
+        }
+    }
+}
+
+/// Read the propagation of a delete from its DeleteOptions body or query string
+///
+/// `propagationPolicy` wins over the deprecated `orphanDependents`; without
+/// either, dependents are deleted in the background.
+pub(crate) fn propagation(query: Option<&str>, body: &[u8]) -> Propagation {
+    let options = serde_json::from_slice::<Value>(body).unwrap_or_default();
+    let param = |key: &str| -> Option<String> {
+        options
+            .get(key)
+            .map(|value| match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .or_else(|| {
+                query?.split('&').find_map(|pair| {
+                    let (k, value) = pair.split_once('=')?;
+                    (k == key).then(|| value.to_string())
+                })
+            })
+    };
+    match param("propagationPolicy").as_deref() {
+        Some("Orphan") => Propagation::Orphan,
+        Some("Foreground") => Propagation::Foreground,
+        Some(_) => Propagation::Background,
+        None if param("orphanDependents").as_deref() == Some("true") => Propagation::Orphan,
+        None => Propagation::Background,
+    }
+}
+
+/// Delete an object, leaving it in deletion under a finalizer if its dependents
+/// are orphaned or deleted first
+///
+/// Returns `None` for background deletes, which the caller carries out as usual.
+pub(crate) fn begin_deletion(
+    tracker: &ObjectTracker,
+    gvr: &GVR,
+    namespace: &str,
+    name: &str,
+    propagation: Propagation,
+) -> Option<Result<Value>> {
+    let finalizer = propagation.finalizer()?;
+    Some(tracker.begin_finalized_deletion(gvr, namespace, name, finalizer))
+}
+
+/// Tracker observer running the garbage collector after every write
+pub(crate) fn observer() -> WriteObserver {
+    Arc::new(|tracker, _, _| {
+        if let Err(e) = collect(tracker) {
+            warn!("Garbage collection failed: {}", e);
+        }
+    })
+}
+
+/// A stored object and where it is stored
+struct Item {
+    gvr: GVR,
+    object: Arc<Value>,
+}
+
+impl Item {
+    fn uid(&self) -> Option<&str> {
+        self.object.pointer("/metadata/uid").and_then(Value::as_str)
+    }
+
+    fn namespace(&self) -> &str {
+        self.object
+            .pointer("/metadata/namespace")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    }
+
+    fn name(&self) -> &str {
+        self.object
+            .pointer("/metadata/name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    }
+
+    fn is_deleting(&self) -> bool {
+        self.object.pointer("/metadata/deletionTimestamp").is_some()
+    }
+
+    fn has_finalizer(&self, finalizer: &str) -> bool {
+        self.object
+            .pointer("/metadata/finalizers")
+            .and_then(Value::as_array)
+            .is_some_and(|finalizers| finalizers.iter().any(|f| f == finalizer))
+    }
+
+    fn owner_uids(&self) -> impl Iterator<Item = &str> {
+        references(&self.object)
+            .iter()
+            .filter_map(|reference| reference.get("uid").and_then(Value::as_str))
+    }
+
+    fn blocks_owner(&self, owner_uid: &str) -> bool {
+        references(&self.object).iter().any(|reference| {
+            reference.get("uid").and_then(Value::as_str) == Some(owner_uid)
+                && reference.get("blockOwnerDeletion") == Some(&Value::Bool(true))
+        })
+    }
+
+    fn gvk(&self) -> Option<GVK> {
+        extract_gvk(&self.object)
+            .ok()
+            .or_else(|| Discovery::gvr_to_gvk(&self.gvr))
+    }
+
+    /// Write the object with its metadata changed, deleting it if that removed
+    /// its last finalizer
+    fn update_metadata(
+        &self,
+        tracker: &ObjectTracker,
+        change: impl FnOnce(&mut serde_json::Map<String, Value>),
+    ) -> Result<()> {
+        let Some(gvk) = self.gvk() else {
+            return Ok(());
+        };
+        let mut object = self.object.as_ref().clone();
+        if let Some(metadata) = object.get_mut("metadata").and_then(Value::as_object_mut) {
+            change(metadata);
+        }
+        tracker.update(&self.gvr, &gvk, object, self.namespace(), false)?;
+        Ok(())
+    }
+
+    fn remove_finalizer(&self, tracker: &ObjectTracker, finalizer: &str) -> Result<()> {
+        self.update_metadata(tracker, |metadata| {
+            if let Some(Value::Array(finalizers)) = metadata.get_mut("finalizers") {
+                finalizers.retain(|f| f != finalizer);
+                if finalizers.is_empty() {
+                    metadata.remove("finalizers");
+                }
+            }
+        })
+    }
+
+    fn remove_references(&self, tracker: &ObjectTracker, uids: &[&str]) -> Result<()> {
+        self.update_metadata(tracker, |metadata| {
+            if let Some(Value::Array(references)) = metadata.get_mut("ownerReferences") {
+                references.retain(|reference| {
+                    !uids.contains(
+                        &reference
+                            .get("uid")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default(),
+                    )
+                });
+                if references.is_empty() {
+                    metadata.remove("ownerReferences");
+                }
+            }
+        })
+    }
+
+    /// Delete the object the way the collector deletes dependents
+    fn delete(&self, tracker: &ObjectTracker, propagation: Propagation) -> Result<()> {
+        let (namespace, name) = (self.namespace(), self.name());
+        if let Some(finalizer) = propagation.finalizer() {
+            tracker.begin_finalized_deletion(&self.gvr, namespace, name, finalizer)?;
+        } else if self
+            .object
+            .pointer("/metadata/finalizers")
+            .and_then(Value::as_array)
+            .is_some_and(|finalizers| !finalizers.is_empty())
+        {
+            tracker.begin_graceful_deletion(&self.gvr, namespace, name, 0)?;
+        } else {
+            tracker.delete(&self.gvr, namespace, name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Take the next step towards the end state of the deletions in progress
+///
+/// A pass makes at most one write: the write runs the collector again, on the
+/// state it left, until nothing is left to do.
+fn collect(tracker: &ObjectTracker) -> Result<()> {
+    let items: Vec<Item> = tracker
+        .list_all_shared()
+        .into_iter()
+        .map(|(gvr, object)| Item { gvr, object })
+        .collect();
+    let by_uid: HashMap<&str, &Item> = items
+        .iter()
+        .filter_map(|item| Some((item.uid()?, item)))
+        .collect();
+    let dependents_of = |owner: &Item| -> Vec<&Item> {
+        let Some(uid) = owner.uid() else {
+            return Vec::new();
+        };
+        items
+            .iter()
+            .filter(|item| item.owner_uids().any(|owner_uid| owner_uid == uid))
+            .collect()
+    };
+
+    for owner in items.iter().filter(|item| item.is_deleting()) {
+        let Some(uid) = owner.uid() else {
+            continue;
+        };
+        if owner.has_finalizer(ORPHAN_FINALIZER) {
+            return match dependents_of(owner).first() {
+                Some(dependent) => dependent.remove_references(tracker, &[uid]),
+                None => owner.remove_finalizer(tracker, ORPHAN_FINALIZER),
+            };
+        }
+        if owner.has_finalizer(FOREGROUND_FINALIZER) {
+            let dependents = dependents_of(owner);
+            if let Some(dependent) = dependents.iter().find(|d| !d.is_deleting()) {
+                // A dependent with another owner that stays only loses its
+                // reference to this one
+                let other_owner_stays = dependent.owner_uids().any(|other| {
+                    other != uid && by_uid.get(other).is_some_and(|o| !o.is_deleting())
+                });
+                return if other_owner_stays {
+                    dependent.remove_references(tracker, &[uid])
+                } else {
+                    dependent.delete(tracker, Propagation::Foreground)
+                };
+            }
+            if !dependents.iter().any(|d| d.blocks_owner(uid)) {
+                return owner.remove_finalizer(tracker, FOREGROUND_FINALIZER);
+            }
+        }
+    }
+
+    // Dependents of owners that are gone
+    for dependent in items.iter().filter(|item| !item.is_deleting()) {
+        let owners: Vec<&str> = dependent.owner_uids().collect();
+        let dangling: Vec<&str> = owners
+            .iter()
+            .copied()
+            .filter(|uid| !by_uid.contains_key(uid))
+            .collect();
+        if dangling.is_empty() {
+            continue;
+        }
+        if dangling.len() == owners.len() {
+            dependent.delete(tracker, Propagation::Background)?;
+        } else {
+            dependent.remove_references(tracker, &dangling)?;
+        }
+        return Ok(());
+    }
+    Ok(())
+}
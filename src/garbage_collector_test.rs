@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use futures::StreamExt;
+    use k8s_openapi::api::apps::v1::{Deployment, ReplicaSet};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+    use kube::api::{Api, DeleteParams, Patch, PatchParams, PostParams};
+    use kube::core::WatchEvent;
+    use kube::Client;
+    use serde_json::json;
+
+    async fn create_owner(client: &Client, name: &str) -> OwnerReference {
+        let mut deployment = Deployment::default();
+        deployment.metadata.name = Some(name.to_string());
+        let deployment = Api::<Deployment>::namespaced(client.clone(), "default")
+            .create(&PostParams::default(), &deployment)
+            .await
+            .unwrap();
+        OwnerReference {
+            api_version: "apps/v1".to_string(),
+            kind: "Deployment".to_string(),
+            name: name.to_string(),
+            uid: deployment.metadata.uid.unwrap(),
+            block_owner_deletion: Some(true),
+            ..Default::default()
+        }
+    }
+
+    async fn create_dependent(
+        client: &Client,
+        name: &str,
+        owners: Vec<OwnerReference>,
+        finalizers: &[&str],
+    ) {
+        let mut replicaset = ReplicaSet::default();
+        replicaset.metadata.name = Some(name.to_string());
+        replicaset.metadata.owner_references = Some(owners);
+        if !finalizers.is_empty() {
+            replicaset.metadata.finalizers =
+                Some(finalizers.iter().map(|f| f.to_string()).collect());
+        }
+        Api::<ReplicaSet>::namespaced(client.clone(), "default")
+            .create(&PostParams::default(), &replicaset)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_foreground_deletion_waits_for_blocking_dependents() {
+        let (client, handle) = ClientBuilder::new()
+            .with_garbage_collector()
+            .build_with_handle()
+            .await
+            .unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), "default");
+        let replicasets: Api<ReplicaSet> = Api::namespaced(client.clone(), "default");
+        let owner = create_owner(&client, "web").await;
+        create_dependent(
+            &client,
+            "web-1",
+            vec![owner.clone()],
+            &["example.com/drain"],
+        )
+        .await;
+        create_dependent(&client, "web-2", vec![owner], &[]).await;
+        let mut events = handle.watch_events::<Deployment>(Some("default")).unwrap();
+
+        let deleted = deployments
+            .delete("web", &DeleteParams::foreground())
+            .await
+            .unwrap()
+            .unwrap_left();
+        assert!(deleted.metadata.deletion_timestamp.is_some());
+        assert_eq!(
+            deleted.metadata.finalizers,
+            Some(vec!["foregroundDeletion".to_string()])
+        );
+
+        // web-2 is gone, web-1 is held by its finalizer and holds the owner
+        assert!(replicasets.get_opt("web-2").await.unwrap().is_none());
+        let held = replicasets.get("web-1").await.unwrap();
+        assert!(held.metadata.deletion_timestamp.is_some());
+        let owner = deployments.get("web").await.unwrap();
+        assert_eq!(
+            owner.metadata.finalizers,
+            Some(vec!["foregroundDeletion".to_string()])
+        );
+
+        let patch = json!({ "metadata": { "finalizers": null } });
+        replicasets
+            .patch("web-1", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert!(replicasets.get_opt("web-1").await.unwrap().is_none());
+        assert!(deployments.get_opt("web").await.unwrap().is_none());
+
+        // The owner went through deletion in steps
+        let mut seen = Vec::new();
+        while seen.last() != Some(&"deleted") {
+            seen.push(match events.next().await.unwrap() {
+                WatchEvent::Modified(_) => "modified",
+                WatchEvent::Deleted(_) => "deleted",
+                other => panic!("unexpected event {other:?}"),
+            });
+        }
+        assert_eq!(seen, ["modified", "deleted"]);
+        handle.verify_event_ordering();
+    }
+
+    #[tokio::test]
+    async fn test_orphan_deletion_keeps_dependents() {
+        let client = ClientBuilder::new()
+            .with_garbage_collector()
+            .build()
+            .await
+            .unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), "default");
+        let replicasets: Api<ReplicaSet> = Api::namespaced(client.clone(), "default");
+        let owner = create_owner(&client, "web").await;
+        create_dependent(&client, "web-1", vec![owner], &[]).await;
+
+        let deleted = deployments
+            .delete("web", &DeleteParams::orphan())
+            .await
+            .unwrap()
+            .unwrap_left();
+        assert_eq!(
+            deleted.metadata.finalizers,
+            Some(vec!["orphan".to_string()])
+        );
+
+        assert!(deployments.get_opt("web").await.unwrap().is_none());
+        let orphan = replicasets.get("web-1").await.unwrap();
+        assert!(orphan.metadata.deletion_timestamp.is_none());
+        assert_eq!(orphan.metadata.owner_references, None);
+    }
+
+    #[tokio::test]
+    async fn test_background_deletion_removes_dependents_without_owners() {
+        let client = ClientBuilder::new()
+            .with_garbage_collector()
+            .build()
+            .await
+            .unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), "default");
+        let replicasets: Api<ReplicaSet> = Api::namespaced(client.clone(), "default");
+        let web = create_owner(&client, "web").await;
+        let api = create_owner(&client, "api").await;
+        create_dependent(&client, "web-1", vec![web.clone()], &[]).await;
+        create_dependent(&client, "shared", vec![web, api.clone()], &[]).await;
+
+        deployments
+            .delete("web", &DeleteParams::background())
+            .await
+            .unwrap();
+
+        assert!(deployments.get_opt("web").await.unwrap().is_none());
+        assert!(replicasets.get_opt("web-1").await.unwrap().is_none());
+        // A dependent with another owner only loses the dangling reference
+        let shared = replicasets.get("shared").await.unwrap();
+        assert_eq!(shared.metadata.owner_references, Some(vec![api]));
+    }
+
+    #[tokio::test]
+    async fn test_propagation_is_ignored_without_garbage_collector() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), "default");
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        let owner = create_owner(&client, "web").await;
+        let mut configmap = ConfigMap::default();
+        configmap.metadata.name = Some("settings".to_string());
+        configmap.metadata.owner_references = Some(vec![owner]);
+        configmaps
+            .create(&PostParams::default(), &configmap)
+            .await
+            .unwrap();
+
+        deployments
+            .delete("web", &DeleteParams::foreground())
+            .await
+            .unwrap();
+
+        assert!(deployments.get_opt("web").await.unwrap().is_none());
+        assert!(configmaps.get_opt("settings").await.unwrap().is_some());
+    }
+}
@@ -1,7 +1,7 @@
 //! Auto-generated immutable field lookups
 //!
 //! This file is generated by the immutable-gen binary and should not be edited manually.
-//! To regenerate: cargo run --bin immutable-gen
+//! To regenerate: cargo run --features codegen --bin immutable-gen
 //!
 //! Immutable fields are fields that cannot be changed after resource creation.
 //! This module provides lookups to check if a field in a Kubernetes resource is immutable.
@@ -143,3 +143,65 @@ pub fn get_immutable_fields(
         _ => None,
     }
 }
+
+/// Get nested immutable field paths for a given resource kind
+///
+/// Paths are dot-separated from the object root (e.g. `spec.selector`). A segment of
+/// the form `field[key]` descends into each item of the `field` list, matching old and
+/// new items by the `key` merge key (e.g. `spec.containers[name].image`).
+///
+/// These paths are set-once: a value may be populated when previously unset, but once
+/// set it cannot be changed or removed.
+///
+/// # Example
+///
+/// ```
+/// use kube_fake_client::gen::immutable::get_immutable_paths;
+///
+/// let paths = get_immutable_paths("apps", "v1", "Deployment").unwrap();
+/// assert!(paths.contains(&"spec.selector"));
+/// assert!(get_immutable_paths("", "v1", "ConfigMap").is_none());
+/// ```
+pub fn get_immutable_paths(
+    group: &str,
+    version: &str,
+    kind: &str,
+) -> Option<&'static [&'static str]> {
+    match (group, version, kind) {
+        ("", "v1", "PersistentVolumeClaim") => Some(&[
+            "spec.accessModes",
+            "spec.selector",
+            "spec.storageClassName",
+            "spec.volumeMode",
+            "spec.volumeName",
+        ]),
+        ("", "v1", "Pod") => Some(&[
+            "spec.containers[name].args",
+            "spec.containers[name].command",
+            "spec.containers[name].env",
+            "spec.containers[name].ports",
+            "spec.nodeName",
+            "spec.restartPolicy",
+            "spec.serviceAccountName",
+            "spec.volumes",
+        ]),
+        ("", "v1", "Service") => Some(&["spec.clusterIP", "spec.clusterIPs"]),
+        ("apps", "v1", "DaemonSet") => Some(&["spec.selector"]),
+        ("apps", "v1", "Deployment") => Some(&["spec.selector"]),
+        ("apps", "v1", "ReplicaSet") => Some(&["spec.selector"]),
+        ("apps", "v1", "StatefulSet") => Some(&[
+            "spec.podManagementPolicy",
+            "spec.selector",
+            "spec.serviceName",
+            "spec.volumeClaimTemplates",
+        ]),
+        ("batch", "v1", "Job") => Some(&[
+            "spec.completionMode",
+            "spec.selector",
+            "spec.template.spec.containers[name].command",
+            "spec.template.spec.containers[name].image",
+            "spec.template.spec.nodeSelector",
+        ]),
+        _ => None,
+    }
+}
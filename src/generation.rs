@@ -0,0 +1,61 @@
+//! Simulated controllers catching up with generations
+//!
+//! Operators often wait for a dependency's controller to observe its latest
+//! spec, comparing `status.observedGeneration` with `metadata.generation`, before
+//! moving on. When enabled for a kind, every write to an object of that kind that
+//! leaves `status.observedGeneration` behind is followed by a status update
+//! catching it up, as if the object's controller had reconciled it.
+
+use crate::tracker::{ObjectTracker, WriteObserver, GVK, GVR};
+use crate::Result;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Tracker observer catching `status.observedGeneration` up for a kind
+pub(crate) fn observer(gvr: GVR, gvk: GVK) -> WriteObserver {
+    Arc::new(move |tracker, written, namespace| {
+        if *written != gvr {
+            return;
+        }
+        if let Err(e) = reconcile(tracker, &gvr, &gvk, namespace) {
+            warn!(
+                "Failed to update observedGeneration of {} in namespace {}: {}",
+                gvr.resource, namespace, e
+            );
+        }
+    })
+}
+
+/// Catch `status.observedGeneration` up for every object of a resource in a
+/// namespace
+fn reconcile(tracker: &ObjectTracker, gvr: &GVR, gvk: &GVK, namespace: &str) -> Result<()> {
+    for mut object in tracker.list(gvr, Some(namespace))? {
+        let Some(generation) = object
+            .pointer("/metadata/generation")
+            .and_then(Value::as_i64)
+        else {
+            continue;
+        };
+        if object.pointer("/metadata/deletionTimestamp").is_some()
+            || observed_generation(&object) == Some(generation)
+        {
+            continue;
+        }
+        match object.get_mut("status") {
+            Some(Value::Object(status)) => {
+                status.insert("observedGeneration".to_string(), json!(generation));
+            }
+            _ => object["status"] = json!({ "observedGeneration": generation }),
+        }
+        tracker.update(gvr, gvk, object, namespace, true)?;
+    }
+    Ok(())
+}
+
+/// The `status.observedGeneration` of an object
+pub(crate) fn observed_generation(object: &Value) -> Option<i64> {
+    object
+        .pointer("/status/observedGeneration")
+        .and_then(Value::as_i64)
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use crate::{testing, ClientBuilder};
+    use k8s_openapi::api::apps::v1::Deployment;
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    fn deployment() -> Deployment {
+        serde_json::from_value(json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "web" },
+            "spec": {
+                "replicas": 1,
+                "selector": { "matchLabels": { "app": "web" } },
+                "template": { "metadata": { "labels": { "app": "web" } } }
+            },
+            "status": { "replicas": 1 }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_observed_generation_follows_spec_changes() {
+        let (client, handle) = ClientBuilder::new()
+            .with_observed_generation_for::<Deployment>()
+            .build_with_handle()
+            .await
+            .unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client, "default");
+
+        deployments
+            .create(&PostParams::default(), &deployment())
+            .await
+            .unwrap();
+        testing::assert_observed_generation_current::<Deployment>(&handle, "default", "web");
+
+        deployments
+            .patch(
+                "web",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "spec": { "replicas": 3 } })),
+            )
+            .await
+            .unwrap();
+        let web = deployments.get("web").await.unwrap();
+        let status = web.status.unwrap();
+        assert_eq!(web.metadata.generation, Some(2));
+        assert_eq!(status.observed_generation, Some(2));
+        // The rest of the status is kept
+        assert_eq!(status.replicas, Some(1));
+        testing::assert_observed_generation_current::<Deployment>(&handle, "default", "web");
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Deployment default/web has status.observedGeneration 1, expected the current metadata.generation 2"
+    )]
+    async fn test_assert_observed_generation_current_behind() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client, "default");
+
+        let mut web = deployment();
+        web.status.as_mut().unwrap().observed_generation = Some(1);
+        deployments
+            .create(&PostParams::default(), &web)
+            .await
+            .unwrap();
+        deployments
+            .patch(
+                "web",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "spec": { "replicas": 3 } })),
+            )
+            .await
+            .unwrap();
+
+        testing::assert_observed_generation_current::<Deployment>(&handle, "default", "web");
+    }
+}
@@ -0,0 +1,1436 @@
+//! Handle for inspecting and manipulating fake client state from tests
+//!
+//! A [`ClientHandle`] shares its storage with the `kube::Client` returned by
+//! [`ClientBuilder::build_with_handle`](crate::ClientBuilder::build_with_handle),
+//! giving tests direct access to the in-memory cluster without going through
+//! the HTTP layer.
+
+use crate::audit::{self, AuditEntry, ObjectChange};
+use crate::budget::{self, Bound, ReadOnlyGuard, Verb};
+use crate::client::FakeClient;
+use crate::confined::NamespaceConfined;
+use crate::controller::Controller;
+use crate::discovery::Discovery;
+use crate::manifests::{self, AppliedManifests};
+use crate::metrics::Metrics;
+use crate::mock_service::MockService;
+use crate::naming::Pluralizer;
+use crate::owners;
+use crate::recorder::{Call, CallRecorder};
+use crate::request;
+use crate::runtime;
+use crate::service::Reservation;
+use crate::snapshot::Snapshot;
+use crate::stats::TrackerStats;
+use crate::tracker::{GVK, GVR};
+use crate::watch::{self, EventType, Expired};
+use crate::{Error, Result};
+use futures::{Stream, StreamExt};
+use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+use k8s_openapi::api::coordination::v1::Lease;
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Pod};
+use kube::api::DynamicObject;
+use kube::core::{ErrorResponse, WatchEvent};
+use kube::Resource;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+/// How long [`ClientHandle::flush_watches`] waits for watches to catch up
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Direct access to the state behind a fake `kube::Client`
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::ClientBuilder;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+///
+/// // ... run the controller under test with `client` ...
+///
+/// assert!(handle.list_namespace("default")?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClientHandle {
+    pub(crate) client: FakeClient,
+}
+
+impl ClientHandle {
+    pub(crate) fn new(client: FakeClient) -> Self {
+        Self { client }
+    }
+
+    /// Typed access to the same cluster, without the HTTP layer
+    ///
+    /// The [`FakeClient`] shares storage and configuration with the built
+    /// `kube::Client`, so helpers under test that take typed objects can run
+    /// against the state a controller left behind. See [`FakeClient`] for what
+    /// it skips.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... run the controller under test with `client` ...
+    ///
+    /// let settings: ConfigMap = handle.fake_client().get("default", "settings")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fake_client(&self) -> &FakeClient {
+        &self.client
+    }
+
+    /// Map kinds to resource names the way this client does
+    ///
+    /// Knows the resources registered with the client besides the built-in
+    /// ones, so fixture tools and dynamic tests build the same paths the fake
+    /// serves.
+    pub fn pluralizer(&self) -> Pluralizer {
+        Pluralizer::new().with_registry(&self.client.registry)
+    }
+
+    /// List every object stored in a namespace, regardless of kind
+    ///
+    /// Useful for "nothing left behind" assertions after cleanup tests, without
+    /// enumerating every `Api<K>` the controller might have touched. Objects are
+    /// grouped by resource type and ordered by name.
+    pub fn list_namespace(&self, namespace: &str) -> Result<Vec<DynamicObject>> {
+        self.client
+            .tracker
+            .list_namespace(namespace)
+            .into_iter()
+            .map(|value| serde_json::from_value(value).map_err(Into::into))
+            .collect()
+    }
+
+    /// List the objects with an owner reference to an object
+    ///
+    /// Walks `metadata.ownerReferences` of every stored object, matching the
+    /// owner's apiVersion, kind, name and uid, as garbage collection does. The
+    /// namespace is ignored for cluster-scoped owners. Objects are grouped by
+    /// resource type and ordered by namespace and name.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... reconcile the `web` Deployment with `client` ...
+    ///
+    /// let kinds: Vec<_> = handle
+    ///     .dependents_of::<Deployment>("default", "web")?
+    ///     .into_iter()
+    ///     .filter_map(|object| object.types.map(|types| types.kind))
+    ///     .collect();
+    /// assert_eq!(kinds, ["ConfigMap", "Service"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the owner does not exist.
+    pub fn dependents_of<K>(&self, namespace: &str, name: &str) -> Result<Vec<DynamicObject>>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let owner = owners::get::<K>(&self.client, namespace, name)?;
+        owners::dependents_of(&self.client, &owner)
+            .into_iter()
+            .map(|object| serde_json::from_value(object.as_ref().clone()).map_err(Into::into))
+            .collect()
+    }
+
+    /// Copy every object in the cluster
+    ///
+    /// Objects are shared copy-on-write, so snapshots are cheap. Take them while no
+    /// requests are in flight.
+    pub fn snapshot(&self) -> Snapshot {
+        self.client.tracker.snapshot()
+    }
+
+    /// Put the cluster back to a snapshot
+    ///
+    /// Objects created since the snapshot are removed and changed or deleted ones
+    /// restored. Resource versions keep increasing: objects written after the
+    /// restore get versions newer than any seen before it. Open watches are not
+    /// sent events for the restore.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    /// let initial = handle.snapshot();
+    ///
+    /// // ... run a scenario with `client` ...
+    ///
+    /// handle.restore(&initial);
+    /// // ... run another scenario from the same starting point ...
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore(&self, snapshot: &Snapshot) {
+        self.client.tracker.restore(snapshot);
+        self.client.stale_reads.reset(&self.client.tracker);
+        if let Some(persistence) = &self.client.persistence {
+            if let Err(e) = persistence.save(&self.client.tracker) {
+                tracing::warn!("{e}");
+            }
+        }
+    }
+
+    /// What the tracker holds: objects per resource and the watch history, with
+    /// their approximate size
+    ///
+    /// Useful to find what grows over a long property-based test, and to size
+    /// [`ClientBuilder::with_memory_limit`](crate::ClientBuilder::with_memory_limit).
+    /// The audit log is kept apart; see [`audit_entries`](Self::audit_entries).
+    pub fn stats(&self) -> TrackerStats {
+        self.client.tracker.stats()
+    }
+
+    /// Drop what the tracker keeps beyond the stored objects
+    ///
+    /// Empties the watch history, as etcd compaction does: open watches keep
+    /// running, but watches resuming from an earlier resourceVersion get 410 Gone
+    /// and relist. Also forgets deleted objects and the empty collections and
+    /// write locks they left. Stored objects and the audit log are untouched;
+    /// [`clear_audit_log`](Self::clear_audit_log) empties the latter. Call it
+    /// between iterations, while no requests are in flight.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    /// let initial = handle.snapshot();
+    ///
+    /// for _ in 0..1000 {
+    ///     // ... run one generated scenario with `client` ...
+    ///
+    ///     handle.restore(&initial);
+    ///     handle.compact();
+    ///     handle.clear_audit_log();
+    /// }
+    /// assert_eq!(handle.stats().watch_events, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compact(&self) {
+        self.client.tracker.compact();
+    }
+
+    /// Stop delivering watch events
+    ///
+    /// Writes still take effect and show up in gets and lists, but open watches
+    /// don't see them until [`resume_watches`](Self::resume_watches). This opens
+    /// the window between a controller's cache and the API server on purpose, so
+    /// races between the two can be reproduced without sleeps.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// handle.pause_watches();
+    /// // ... write with `client`; the controller's cache still has the old state ...
+    /// handle.resume_watches();
+    /// handle.flush_watches().await;
+    /// // ... the controller's cache has caught up ...
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pause_watches(&self) {
+        self.client.tracker.watch_cache().pause();
+    }
+
+    /// Deliver the events held back since [`pause_watches`](Self::pause_watches)
+    /// and resume delivering new ones
+    pub fn resume_watches(&self) {
+        self.client.tracker.watch_cache().resume();
+    }
+
+    /// Wait until every open watch has received the events delivered so far
+    ///
+    /// Events held back by [`pause_watches`](Self::pause_watches) are not waited
+    /// for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a watch doesn't catch up within ten seconds, which usually means
+    /// nothing is reading its stream.
+    pub async fn flush_watches(&self) {
+        let watch_cache = self.client.tracker.watch_cache();
+        let deadline = Instant::now() + FLUSH_TIMEOUT;
+        while watch_cache.undelivered() > 0 {
+            if Instant::now() >= deadline {
+                panic!(
+                    "{} watch event(s) not received within {FLUSH_TIMEOUT:?}",
+                    watch_cache.undelivered()
+                );
+            }
+            runtime::sleep(Duration::from_millis(1)).await;
+        }
+        // Let watchers handle the events they just received
+        Self::yield_to_tasks().await;
+    }
+
+    /// Check that every watch event so far was delivered in order
+    ///
+    /// The events of each object must carry strictly increasing resourceVersions,
+    /// as they do from the API server. Informers and reflectors rely on this;
+    /// calling this at the end of a test makes sure the fake never broke it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an event was delivered that was not newer than the previous event
+    /// of its object, listing each out-of-order or duplicate event.
+    #[track_caller]
+    pub fn verify_event_ordering(&self) {
+        let violations = self.client.tracker.watch_cache().ordering_violations();
+        if !violations.is_empty() {
+            panic!(
+                "{} watch event(s) delivered out of order:\n  {}",
+                violations.len(),
+                violations.join("\n  ")
+            );
+        }
+    }
+
+    /// Move paused tokio time forward and let the fake catch up
+    ///
+    /// Advances the tokio clock with `tokio::time::advance`, yielding before and
+    /// after so that the fake's timers are started and the work whose timers fired
+    /// gets to run. Everything time-dependent in the fake
+    /// follows the tokio clock: Pods inside their termination grace period are
+    /// removed, stale reads catch up, chaos latency elapses, and timestamps the
+    /// fake writes, such as `creationTimestamp` and `deletionTimestamp`, move
+    /// forward by the same amount. A reconcile that requeues after a delay can so
+    /// be tested without waiting for it.
+    ///
+    /// Needs the `tokio-runtime` feature, enabled by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if tokio time isn't paused, as with `#[tokio::test(start_paused = true)]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... delete a running Pod with a 30 second grace period ...
+    ///
+    /// handle.advance(Duration::from_secs(30)).await;
+    /// // ... the Pod is gone ...
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio-runtime")]
+    pub async fn advance(&self, duration: Duration) {
+        // Let freshly spawned work start its timers before the clock moves, and
+        // the work whose timers fired run after
+        Self::yield_to_tasks().await;
+        tokio::time::advance(duration).await;
+        Self::yield_to_tasks().await;
+    }
+
+    async fn yield_to_tasks() {
+        for _ in 0..10 {
+            runtime::yield_now().await;
+        }
+    }
+
+    /// Tag the requests a future makes
+    ///
+    /// Every request made while `future` runs carries the tag, in its
+    /// [`AuditEntry::tags`] and in the
+    /// [`RequestContext`](crate::interceptor::RequestContext) interceptors see,
+    /// so API calls can be attributed to a specific reconcile in assertions. Tags
+    /// nest, outermost first. Only requests made from the task running `future`
+    /// are tagged, not those of tasks it spawns.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    /// use kube::api::Api;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    /// let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+    ///
+    /// handle
+    ///     .with_request_tag("reconcile-42", async {
+    ///         // ... reconcile with `configmaps` ...
+    ///         configmaps.get_opt("settings").await
+    ///     })
+    ///     .await?;
+    ///
+    /// let requests = handle
+    ///     .audit_entries()
+    ///     .into_iter()
+    ///     .filter(|entry| entry.tags.iter().any(|tag| tag == "reconcile-42"))
+    ///     .count();
+    /// assert_eq!(requests, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_request_tag<F: Future>(
+        &self,
+        tag: impl Into<String>,
+        future: F,
+    ) -> F::Output {
+        request::tagged(tag.into(), future).await
+    }
+
+    /// Stream the writes to objects of a type from now on
+    ///
+    /// Every create, update and delete stored after the call is delivered as an
+    /// `Added`, `Modified` or `Deleted` event with the object as written, in
+    /// commit order, without going through the HTTP watch. Pass a namespace to see
+    /// only its objects, or `None` for every namespace and cluster-scoped types.
+    /// Events are held back while watches are paused. A stream that falls more
+    /// than a few thousand events behind ends with an `Error` event, like a watch
+    /// that is dropped by the API server.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use futures::StreamExt;
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::core::WatchEvent;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    /// let mut events = handle.watch_events::<Pod>(Some("default"))?;
+    ///
+    /// // ... run a reconcile that creates a pod ...
+    ///
+    /// assert!(matches!(events.next().await, Some(WatchEvent::Added(_))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type is not a known resource.
+    pub fn watch_events<K>(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<impl Stream<Item = WatchEvent<K>> + Send + Unpin + 'static>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned + Send + 'static,
+    {
+        let gvr = self.gvr_of(&GVK::of::<K>())?;
+        let namespace = namespace.map(str::to_string);
+        // Subscribe before returning, so no write after the call is missed
+        let (_, receiver) = self
+            .client
+            .tracker
+            .watch_cache()
+            .subscribe(&gvr, None)
+            .unwrap_or_else(|Expired| unreachable!("subscribing from now never expires"));
+
+        let events = futures::stream::unfold(Some(receiver), |receiver| async move {
+            let mut receiver = receiver?;
+            match receiver.recv().await {
+                Ok(event) => Some((Ok(event), Some(receiver))),
+                Err(RecvError::Lagged(missed)) => Some((Err(missed), None)),
+                Err(RecvError::Closed) => None,
+            }
+        });
+        Ok(events
+            .filter_map(move |event| {
+                let event = match event {
+                    Ok(event)
+                        if event.gvr == gvr
+                            && namespace.as_ref().is_none_or(|ns| *ns == event.namespace) =>
+                    {
+                        Some(typed_event(&event))
+                    }
+                    Ok(_) => None,
+                    Err(missed) => Some(WatchEvent::Error(ErrorResponse {
+                        status: "Failure".to_string(),
+                        message: format!("watch fell behind and missed {missed} event(s)"),
+                        reason: "Expired".to_string(),
+                        code: 410,
+                    })),
+                };
+                futures::future::ready(event)
+            })
+            .boxed())
+    }
+
+    /// Wait until an object of a kind matches a predicate, and return it
+    ///
+    /// Checks the stored objects first, then every object written after, so a
+    /// state a controller under test reaches on its own is seen as soon as it is
+    /// stored, without polling. On tokio the timeout runs on the tokio clock, and
+    /// passes at once when time is paused and nothing else is left to run.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube_fake_client::{testing, ClientBuilder};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... start the controller under test with `client` ...
+    ///
+    /// let pod = handle
+    ///     .wait_for(|pod: &Pod| testing::is_pod_ready(pod), Duration::from_secs(5))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if no object matches in time, or an error if
+    /// the type is not a known resource.
+    pub async fn wait_for<K, F>(&self, predicate: F, timeout: Duration) -> Result<K>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned + Send + 'static,
+        F: Fn(&K) -> bool,
+    {
+        self.wait_until(None, predicate, timeout).await
+    }
+
+    /// Wait until a named object matches a predicate, and return it
+    ///
+    /// Like [`wait_for`](Self::wait_for), for one object; it doesn't have to
+    /// exist yet. Pass an empty namespace for cluster-scoped kinds.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    /// use kube_fake_client::{testing, ClientBuilder};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... start the controller under test with `client` ...
+    ///
+    /// handle
+    ///     .wait_for_object("default", "web", testing::is_deployment_ready, Duration::from_secs(5))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if the object doesn't match in time, or an
+    /// error if the type is not a known resource.
+    pub async fn wait_for_object<K, F>(
+        &self,
+        namespace: &str,
+        name: &str,
+        predicate: F,
+        timeout: Duration,
+    ) -> Result<K>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned + Send + 'static,
+        F: Fn(&K) -> bool,
+    {
+        self.wait_until(Some((namespace, name)), predicate, timeout)
+            .await
+    }
+
+    async fn wait_until<K, F>(
+        &self,
+        target: Option<(&str, &str)>,
+        predicate: F,
+        timeout: Duration,
+    ) -> Result<K>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned + Send + 'static,
+        F: Fn(&K) -> bool,
+    {
+        let namespace = target.map(|(namespace, _)| namespace);
+        let matches = |object: &K| {
+            target.is_none_or(|(_, name)| object.meta().name.as_deref() == Some(name))
+                && predicate(object)
+        };
+        let wait = async {
+            loop {
+                // Subscribe before reading, so no write in between is missed
+                let mut events = self.watch_events::<K>(namespace)?;
+                let gvr = self.gvr_of(&GVK::of::<K>())?;
+                let stored = match target {
+                    Some((namespace, name)) => self
+                        .client
+                        .tracker
+                        .get(&gvr, namespace, name)
+                        .into_iter()
+                        .collect(),
+                    None => self.client.tracker.list(&gvr, None)?,
+                };
+                for object in stored {
+                    let object: K = serde_json::from_value(object)?;
+                    if matches(&object) {
+                        return Ok(object);
+                    }
+                }
+                while let Some(event) = events.next().await {
+                    match event {
+                        WatchEvent::Added(object) | WatchEvent::Modified(object)
+                            if matches(&object) =>
+                        {
+                            return Ok(object);
+                        }
+                        // Fell behind: start over from the stored objects
+                        WatchEvent::Error(_) => break,
+                        _ => {}
+                    }
+                }
+            }
+        };
+        match runtime::timeout(timeout, wait).await {
+            Some(result) => result,
+            None => {
+                let object = match target {
+                    Some(("", name)) => format!(" {name}"),
+                    Some((namespace, name)) => format!(" {namespace}/{name}"),
+                    None => String::new(),
+                };
+                Err(Error::Timeout(format!(
+                    "no {}{object} matched within {timeout:?}",
+                    K::kind(&())
+                )))
+            }
+        }
+    }
+
+    /// The resource a kind is stored under
+    fn gvr_of(&self, gvk: &GVK) -> Result<GVR> {
+        Discovery::gvk_to_gvr_with_registry(gvk, &self.client.registry).ok_or_else(|| {
+            Error::ResourceNotRegistered {
+                group: gvk.group.clone(),
+                version: gvk.version.clone(),
+                resource: format!("{} (kind)", gvk.kind),
+            }
+        })
+    }
+
+    /// Change a stored object in place, the way an external actor would
+    ///
+    /// Reads the object, runs `mutate` on it and writes it back at the version
+    /// it was read at. If another write lands in between, the object is read
+    /// again and `mutate` runs again on the latest state, so it may run more
+    /// than once. The write is checked like an update request: immutable
+    /// fields, defaulting and schema validation; use
+    /// [`modify_unchecked`](Self::modify_unchecked) to store whatever state a
+    /// test needs. Status changes of a kind with a status subresource are
+    /// written as a status update of their own, after any other change.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... let the operator create the `web` Deployment ...
+    ///
+    /// // Someone scales the Deployment by hand
+    /// handle.modify("default", "web", |deployment: &mut Deployment| {
+    ///     deployment.spec.get_or_insert_default().replicas = Some(5);
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object does not exist, the type is not a known
+    /// resource, or the changed object fails the checks.
+    pub fn modify<K, F>(&self, namespace: &str, name: &str, mutate: F) -> Result<K>
+    where
+        K: Resource<DynamicType = ()> + Serialize + DeserializeOwned,
+        F: FnMut(&mut K),
+    {
+        self.modify_object(namespace, name, mutate, true)
+    }
+
+    /// Change a stored object in place without checking the result
+    ///
+    /// Like [`modify`](Self::modify), without the immutable field checks,
+    /// defaulting and schema validation, for states the API server would refuse
+    /// but a test needs, such as a changed immutable field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object does not exist or the type is not a known
+    /// resource.
+    pub fn modify_unchecked<K, F>(&self, namespace: &str, name: &str, mutate: F) -> Result<K>
+    where
+        K: Resource<DynamicType = ()> + Serialize + DeserializeOwned,
+        F: FnMut(&mut K),
+    {
+        self.modify_object(namespace, name, mutate, false)
+    }
+
+    fn modify_object<K, F>(
+        &self,
+        namespace: &str,
+        name: &str,
+        mut mutate: F,
+        checked: bool,
+    ) -> Result<K>
+    where
+        K: Resource<DynamicType = ()> + Serialize + DeserializeOwned,
+        F: FnMut(&mut K),
+    {
+        let gvk = GVK::of::<K>();
+        let gvr = self.gvr_of(&gvk)?;
+        let tracker = &self.client.tracker;
+        loop {
+            let existing = tracker.get(&gvr, namespace, name)?;
+            let mut object: K = serde_json::from_value(existing.clone())?;
+            mutate(&mut object);
+            let mut value = serde_json::to_value(&object)?;
+            // Written at the version read, so a write in between is a conflict
+            value["metadata"]["name"] = existing["metadata"]["name"].clone();
+            value["metadata"]["resourceVersion"] = existing["metadata"]["resourceVersion"].clone();
+            let reservation = if checked {
+                let reservation = self
+                    .client
+                    .prepare_for_update(&gvk, namespace, &existing, &mut value)?;
+                self.client
+                    .validate_immutable_fields(&gvk, &existing, &value)?;
+                self.client.admit(&gvk, &mut value)?;
+                reservation
+            } else {
+                Reservation::none()
+            };
+
+            let status_changed = tracker.has_status_subresource(&gvk)
+                && value.get("status") != existing.get("status");
+            let written = tracker
+                .update(&gvr, &gvk, value.clone(), namespace, false)
+                .and_then(|updated| {
+                    reservation.commit();
+                    if !status_changed {
+                        return Ok(updated);
+                    }
+                    value["metadata"]["resourceVersion"] =
+                        updated["metadata"]["resourceVersion"].clone();
+                    tracker.update(&gvr, &gvk, value, namespace, true)
+                });
+            match written {
+                Err(Error::Conflict(_)) => continue,
+                written => return Ok(serde_json::from_value(written?)?),
+            }
+        }
+    }
+
+    /// Run a closure for every object of a kind that is created or changed
+    ///
+    /// The closure acts as a lightweight controller for the parts of the cluster
+    /// a test does not run: it gets the object and a [`FakeClient`] to read and
+    /// write related objects, such as a status updater or a creator of child
+    /// objects. It runs for the objects already stored when it is registered,
+    /// then right after every write that creates or changes one, before the
+    /// request that made the write returns. Each resourceVersion of an object
+    /// is reported once, so a closure that leaves its object as it is does not
+    /// run again for it; deleted objects are not reported, while objects that
+    /// start deletion under a finalizer are. Errors the closure returns are
+    /// logged.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::api::core::v1::{Service, ServiceStatus};
+    /// use k8s_openapi::api::core::v1::{LoadBalancerIngress, LoadBalancerStatus};
+    /// use kube::api::PostParams;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // A cloud provider giving every LoadBalancer Service an address
+    /// handle.on_change(|service: &Service, store| {
+    ///     let is_load_balancer = service.spec.as_ref().and_then(|s| s.type_.as_deref())
+    ///         == Some("LoadBalancer");
+    ///     if !is_load_balancer || service.status.is_some() {
+    ///         return Ok(());
+    ///     }
+    ///     let mut service = service.clone();
+    ///     service.status = Some(ServiceStatus {
+    ///         load_balancer: Some(LoadBalancerStatus {
+    ///             ingress: Some(vec![LoadBalancerIngress {
+    ///                 ip: Some("203.0.113.10".to_string()),
+    ///                 ..Default::default()
+    ///             }]),
+    ///         }),
+    ///         ..Default::default()
+    ///     });
+    ///     let namespace = service.metadata.namespace.clone().unwrap_or_default();
+    ///     store.update_status(&namespace, &service, &PostParams::default())?;
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type is not a known resource.
+    pub fn on_change<K, F>(&self, reconcile: F) -> Result<()>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned + 'static,
+        F: Fn(&K, &FakeClient) -> Result<()> + Send + Sync + 'static,
+    {
+        let gvr = self.gvr_of(&GVK::of::<K>())?;
+        let controller = Controller::new(gvr, &self.client, reconcile);
+        self.client.tracker.add_observer(controller.observer());
+        controller.run(None);
+        Ok(())
+    }
+
+    /// Bind a PersistentVolumeClaim to a PersistentVolume
+    ///
+    /// Stands in for the PV controller: the claim gets `spec.volumeName` set and
+    /// moves to the `Bound` phase, reporting its requested storage as capacity. When
+    /// the named PersistentVolume exists, its `claimRef` is set and it is marked
+    /// `Bound` as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new()
+    ///     .with_statefulset_controller()
+    ///     .build_with_handle()
+    ///     .await?;
+    ///
+    /// // ... create a StatefulSet with a `data` volume claim template ...
+    ///
+    /// let claim = handle.bind_pvc("default", "data-db-0", "pv-0001")?;
+    /// assert_eq!(claim.status.unwrap().phase.as_deref(), Some("Bound"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the claim does not exist or is already bound to a
+    /// different volume.
+    pub fn bind_pvc(
+        &self,
+        namespace: &str,
+        pvc: &str,
+        pv_name: &str,
+    ) -> Result<PersistentVolumeClaim> {
+        let claim = crate::statefulset::bind_pvc(&self.client.tracker, namespace, pvc, pv_name)?;
+        Ok(serde_json::from_value(claim)?)
+    }
+
+    /// Server-side apply a bundle of manifests, as a GitOps sync would
+    ///
+    /// Every document of the multi-document YAML is created if it doesn't exist,
+    /// or merged into the existing object otherwise. Namespaced objects without a
+    /// namespace go to `default`. Applied objects are labelled
+    /// `kube-fake-client.io/applied-by: <field_manager>`, which
+    /// [`apply_manifests_with_prune`](Self::apply_manifests_with_prune) uses to
+    /// find what an earlier sync applied. Writes go straight to storage, bypassing
+    /// interceptors, RBAC and the audit log, but open watches see them.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// let applied = handle.apply_manifests(
+    ///     r#"
+    /// apiVersion: v1
+    /// kind: ConfigMap
+    /// metadata:
+    ///   name: settings
+    /// data:
+    ///   mode: fast
+    /// ---
+    /// apiVersion: v1
+    /// kind: Namespace
+    /// metadata:
+    ///   name: staging
+    /// "#,
+    ///     "gitops",
+    /// )?;
+    /// assert_eq!(applied.applied.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error without applying anything if a document isn't valid YAML,
+    /// has no `apiVersion`, `kind` or name, has an unknown kind or appears twice.
+    /// Returns the error of the first object that fails to be written otherwise;
+    /// the objects before it stay applied.
+    pub fn apply_manifests(&self, yaml: &str, field_manager: &str) -> Result<AppliedManifests> {
+        manifests::apply(&self.client, yaml, field_manager, false)
+    }
+
+    /// Server-side apply a bundle of manifests and delete what it no longer contains
+    ///
+    /// Like [`apply_manifests`](Self::apply_manifests), followed by deleting every
+    /// object an earlier sync with the same field manager applied that isn't in
+    /// this bundle, like `kubectl apply --prune`. Objects applied by other field
+    /// managers or created by other means are never pruned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// let revision_1 = std::fs::read_to_string("bundle/v1.yaml")?;
+    /// let revision_2 = std::fs::read_to_string("bundle/v2.yaml")?;
+    ///
+    /// handle.apply_manifests_with_prune(&revision_1, "gitops")?;
+    /// // ... let the operator reconcile ...
+    /// let synced = handle.apply_manifests_with_prune(&revision_2, "gitops")?;
+    /// for object in &synced.pruned {
+    ///     println!("pruned {:?}", object.metadata.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in the cases [`apply_manifests`](Self::apply_manifests)
+    /// does, in which case nothing is pruned.
+    pub fn apply_manifests_with_prune(
+        &self,
+        yaml: &str,
+        field_manager: &str,
+    ) -> Result<AppliedManifests> {
+        manifests::apply(&self.client, yaml, field_manager, true)
+    }
+
+    /// Expire a Lease as if its holder stopped renewing it
+    ///
+    /// Moves `spec.renewTime` back by more than `spec.leaseDurationSeconds`, so
+    /// leader election candidates see the lease as expired and may acquire it.
+    /// The holder identity is kept, and the resource version changes like any
+    /// other write, so a stale holder's next renewal conflicts.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... let a candidate acquire the `my-operator` lease ...
+    ///
+    /// handle.expire_lease("default", "my-operator")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Lease does not exist.
+    pub fn expire_lease(&self, namespace: &str, name: &str) -> Result<Lease> {
+        let lease = crate::lease::expire(&self.client.tracker, namespace, name)?;
+        Ok(serde_json::from_value(lease)?)
+    }
+
+    /// Mark a Pod running and ready, as the kubelet would
+    ///
+    /// Writes the status a kubelet reports once every container is running and
+    /// passing its readiness probe: phase `Running`, the `PodScheduled`,
+    /// `Initialized`, `ContainersReady` and `Ready` conditions true, and a ready
+    /// container status for each container in the spec. Restart counts and start
+    /// times already reported are kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... let the operator create the `web-0` Pod ...
+    ///
+    /// let pod = handle.set_pod_ready("default", "web-0")?;
+    /// assert_eq!(pod.status.unwrap().phase.as_deref(), Some("Running"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Pod does not exist.
+    pub fn set_pod_ready(&self, namespace: &str, name: &str) -> Result<Pod> {
+        let pod = crate::pod::set_ready(&self.client.tracker, namespace, name, true)?;
+        Ok(serde_json::from_value(pod)?)
+    }
+
+    /// Mark a Pod running but not ready, as after a failing readiness probe
+    ///
+    /// Like [`set_pod_ready`](Self::set_pod_ready), with the `ContainersReady`
+    /// and `Ready` conditions false for reason `ContainersNotReady` and every
+    /// container status unready.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Pod does not exist.
+    pub fn set_pod_not_ready(&self, namespace: &str, name: &str) -> Result<Pod> {
+        let pod = crate::pod::set_ready(&self.client.tracker, namespace, name, false)?;
+        Ok(serde_json::from_value(pod)?)
+    }
+
+    /// Feed metric values to a HorizontalPodAutoscaler and let it scale
+    ///
+    /// Stands in for the HPA controller and the metrics pipeline behind it:
+    /// `metrics` gives the current value of each metric by name, a resource name
+    /// such as `cpu` for `Resource` metrics (as a utilization percentage when the
+    /// target is one) or the metric's name otherwise. The desired replica count is
+    /// computed the way the controller does it, the scale target's `spec.replicas`
+    /// is set to it, and the HPA's status is updated with the replica counts, the
+    /// metrics and the `AbleToScale`, `ScalingActive` and `ScalingLimited`
+    /// conditions. A metric without a value leaves `ScalingActive` false, and a
+    /// missing scale target leaves `AbleToScale` false.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... create a Deployment `web` and an HPA targeting 50% CPU for it ...
+    ///
+    /// let hpa = handle.set_hpa_metrics("default", "web", &[("cpu", 90.0)])?;
+    /// let status = hpa.status.unwrap();
+    /// assert!(status.desired_replicas > status.current_replicas.unwrap_or_default());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HorizontalPodAutoscaler does not exist.
+    pub fn set_hpa_metrics(
+        &self,
+        namespace: &str,
+        name: &str,
+        metrics: &[(&str, f64)],
+    ) -> Result<HorizontalPodAutoscaler> {
+        let hpa = crate::hpa::sync(&self.client, namespace, name, metrics)?;
+        Ok(serde_json::from_value(hpa)?)
+    }
+
+    /// A client confined to one namespace
+    ///
+    /// The returned client shares the store with the client from
+    /// [`ClientBuilder::build_with_handle`](crate::ClientBuilder::build_with_handle),
+    /// but answers 403 Forbidden to requests for other namespaces, for
+    /// cluster-scoped resources, and for lists or watches across all
+    /// namespaces, like a controller deployed with a namespaced Role. Discovery
+    /// still works. Layers added with
+    /// [`ClientBuilder::with_layer`](crate::ClientBuilder::with_layer) are not
+    /// applied, and rejected requests are not recorded in the audit log.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    /// use kube::api::Api;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (_client, handle) = ClientBuilder::new().build_with_handle().await?;
+    /// let client = handle.namespaced_client("team-a");
+    ///
+    /// assert!(Api::<ConfigMap>::namespaced(client.clone(), "team-a").list(&Default::default()).await.is_ok());
+    /// assert!(Api::<ConfigMap>::all(client).list(&Default::default()).await.is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn namespaced_client(&self, namespace: &str) -> kube::Client {
+        let service = NamespaceConfined::new(MockService::new(self.client.clone()), namespace);
+        kube::Client::new(service, namespace)
+    }
+
+    /// The seed chaos faults are drawn from, if chaos mode is on
+    ///
+    /// Useful when the seed was chosen at random, to log it or to rebuild the
+    /// client with the same faults.
+    pub fn chaos_seed(&self) -> Option<u64> {
+        self.client.chaos.as_ref().map(|chaos| chaos.seed())
+    }
+
+    /// Requests served by the client so far, oldest first
+    ///
+    /// Useful for asserting how much API traffic a reconcile causes, for example
+    /// that it patches at most once, or that a second pass writes nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... run one reconcile with `client` ...
+    ///
+    /// let patches = handle
+    ///     .audit_entries()
+    ///     .iter()
+    ///     .filter(|entry| entry.verb == "patch")
+    ///     .count();
+    /// assert!(patches <= 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn audit_entries(&self) -> Vec<AuditEntry> {
+        self.client.audit.entries()
+    }
+
+    /// Forget the requests recorded so far
+    pub fn clear_audit_log(&self) {
+        self.client.audit.clear();
+    }
+
+    /// The resource requests recorded so far, as a [`CallRecorder`]
+    ///
+    /// Counts calls with the same methods as a recorder filled by
+    /// [`CallRecorder::funcs`] interceptors, so assertions can move between the
+    /// two. The recorder is a copy: later requests are not added to it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::budget::Verb;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... run one reconcile with `client` ...
+    ///
+    /// assert_eq!(handle.recorded_calls().count_named(Verb::Delete, "web"), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn recorded_calls(&self) -> CallRecorder {
+        self.client
+            .audit
+            .entries()
+            .iter()
+            .filter_map(Call::from_audit)
+            .collect()
+    }
+
+    /// The successful writes to an object recorded so far, oldest first
+    ///
+    /// Each [`ObjectChange`] carries the request body, the stored object before
+    /// and after the write and a JSON Patch between the two, so a test can check
+    /// precisely which fields a reconcile touched. Status and other subresource
+    /// writes are included. Pass `""` as the namespace of cluster-scoped objects.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... run one reconcile with `client` ...
+    ///
+    /// for change in handle.changes_for::<Deployment>("default", "web")? {
+    ///     assert_eq!(change.changed_paths(), ["/spec/replicas"]);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the kind is not a known resource.
+    pub fn changes_for<K>(&self, namespace: &str, name: &str) -> Result<Vec<ObjectChange>>
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let gvr = self.gvr_of(&GVK::of::<K>())?;
+        let changes = self
+            .client
+            .audit
+            .entries()
+            .iter()
+            .filter(|entry| {
+                let written = entry
+                    .name
+                    .as_deref()
+                    .or_else(|| entry.after.as_ref()?.pointer("/metadata/name")?.as_str());
+                entry.resource.as_deref() == Some(gvr.resource.as_str())
+                    && entry.api_group == gvr.group
+                    && entry.namespace.as_deref().unwrap_or_default() == namespace
+                    && written == Some(name)
+            })
+            .filter_map(ObjectChange::from_entry)
+            .collect();
+        Ok(changes)
+    }
+
+    /// Write the recorded requests as JSON lines of `audit.k8s.io/v1` Events
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_audit_log<W: Write>(&self, writer: W) -> Result<()> {
+        audit::write_json_lines(&self.client.audit.entries(), writer)
+    }
+
+    /// Request counts and latencies since the client was built or the metrics were
+    /// last reset
+    ///
+    /// The same metrics are served in the Prometheus text format at `GET /metrics`.
+    pub fn metrics(&self) -> Metrics {
+        self.client.metrics.snapshot()
+    }
+
+    /// Start counting requests from zero
+    pub fn reset_metrics(&self) {
+        self.client.metrics.reset();
+    }
+
+    /// Number of requests made with a verb
+    pub fn request_count(&self, verb: Verb) -> usize {
+        self.client
+            .audit
+            .entries()
+            .iter()
+            .filter(|e| e.verb == verb.as_str())
+            .count()
+    }
+
+    /// Assert how many requests were made with a verb
+    ///
+    /// Counts every recorded request, whether or not it succeeded; use
+    /// [`clear_audit_log`](Self::clear_audit_log) to start counting from a known
+    /// point, such as the start of a reconcile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the count is outside the bound, listing the requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::budget::{at_most, exactly, Verb};
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... run one reconcile with `client` ...
+    ///
+    /// handle.assert_request_count(Verb::Patch, at_most(2));
+    /// handle.assert_request_count(Verb::Delete, exactly(0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn assert_request_count(&self, verb: Verb, bound: Bound) {
+        let entries = self.client.audit.entries();
+        let matching: Vec<&AuditEntry> =
+            entries.iter().filter(|e| e.verb == verb.as_str()).collect();
+        if !bound.contains(matching.len()) {
+            budget::fail(
+                &format!("expected {bound} {verb} request(s), got {}", matching.len()),
+                &matching,
+            );
+        }
+    }
+
+    /// Assert that every create, update and patch named the given field manager
+    ///
+    /// Writes without a `fieldManager` are recorded under a default manager by
+    /// the fake but named by the API server after the client binary, so
+    /// controllers should always set one. Counts every recorded write, whether
+    /// or not it succeeded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any write named another field manager or none, listing them.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use kube_fake_client::ClientBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+    ///
+    /// // ... run the controller with `client` ...
+    ///
+    /// handle.assert_field_manager("my-controller");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn assert_field_manager(&self, manager: &str) {
+        let entries = self.client.audit.entries();
+        let mismatched: Vec<&AuditEntry> = entries
+            .iter()
+            .filter(|e| matches!(e.verb.as_str(), "create" | "update" | "patch"))
+            .filter(|e| e.field_manager.as_deref() != Some(manager))
+            .collect();
+        if !mismatched.is_empty() {
+            let managers: BTreeSet<&str> = mismatched
+                .iter()
+                .map(|e| e.field_manager.as_deref().unwrap_or("<none>"))
+                .collect();
+            let managers: Vec<&str> = managers.into_iter().collect();
+            budget::fail(
+                &format!(
+                    "expected every write to use field manager {manager:?}, got {}",
+                    managers.join(", ")
+                ),
+                &mismatched,
+            );
+        }
+    }
+
+    /// Assert that every interaction in the replayed cassette was requested
+    ///
+    /// Does nothing unless the client was built with
+    /// [`ClientBuilder::with_replay`](crate::ClientBuilder::with_replay).
+    ///
+    /// # Panics
+    ///
+    /// Panics if recorded requests were never made, listing them.
+    #[track_caller]
+    pub fn assert_replayed(&self) {
+        let Some(replay) = &self.client.replay else {
+            return;
+        };
+        let unreplayed = replay.unreplayed();
+        if !unreplayed.is_empty() {
+            panic!(
+                "{} recorded request(s) in cassette {} were not made:\n  {}",
+                unreplayed.len(),
+                replay.path().display(),
+                unreplayed.join("\n  ")
+            );
+        }
+    }
+
+    /// Declare a resource read-only for as long as the returned guard lives
+    ///
+    /// Dropping the guard panics if any create, update, patch or delete of the
+    /// resource was requested in the meantime, in any namespace.
+    pub fn read_only<K>(&self) -> ReadOnlyGuard
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        ReadOnlyGuard::new(
+            Arc::clone(&self.client.audit),
+            K::group(&()).into_owned(),
+            K::plural(&()).into_owned(),
+        )
+    }
+}
+
+/// A stored write as a typed watch event
+fn typed_event<K: DeserializeOwned>(event: &watch::WatchEvent) -> WatchEvent<K> {
+    match serde_json::from_value(event.object.as_ref().clone()) {
+        Ok(object) => match event.event_type {
+            EventType::Added => WatchEvent::Added(object),
+            EventType::Modified => WatchEvent::Modified(object),
+            EventType::Deleted => WatchEvent::Deleted(object),
+        },
+        Err(e) => WatchEvent::Error(ErrorResponse {
+            status: "Failure".to_string(),
+            message: format!("failed to decode {} event: {e}", event.event_type.as_str()),
+            reason: "InternalError".to_string(),
+            code: 500,
+        }),
+    }
+}
@@ -0,0 +1,353 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{configmap, in_namespace};
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{ConfigMap, Node, Pod};
+    use kube::api::{Api, DeleteParams, PostParams};
+
+    fn pod(name: &str, namespace: &str) -> Pod {
+        let mut pod = Pod::default();
+        pod.metadata.name = Some(name.to_string());
+        pod.metadata.namespace = Some(namespace.to_string());
+        pod
+    }
+
+    #[tokio::test]
+    async fn test_list_namespace_across_kinds() {
+        let (_client, handle) = ClientBuilder::new()
+            .with_object(pod("pod-b", "default"))
+            .with_object(pod("pod-a", "default"))
+            .with_object(pod("other-pod", "other"))
+            .with_object(in_namespace(configmap("config"), "default"))
+            .build_with_handle()
+            .await
+            .unwrap();
+
+        let objects = handle.list_namespace("default").unwrap();
+        let names: Vec<_> = objects
+            .iter()
+            .map(|o| {
+                (
+                    o.types.as_ref().unwrap().kind.clone(),
+                    o.metadata.name.clone().unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("ConfigMap".to_string(), "config".to_string()),
+                ("Pod".to_string(), "pod-a".to_string()),
+                ("Pod".to_string(), "pod-b".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_namespace_empty_after_cleanup() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let pods: Api<Pod> = Api::namespaced(client.clone(), "default");
+        let cms: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        pods.create(&PostParams::default(), &pod("web", "default"))
+            .await
+            .unwrap();
+        cms.create(
+            &PostParams::default(),
+            &in_namespace(configmap("web"), "default"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(handle.list_namespace("default").unwrap().len(), 2);
+
+        pods.delete("web", &DeleteParams::default()).await.unwrap();
+        cms.delete("web", &DeleteParams::default()).await.unwrap();
+        assert!(handle.list_namespace("default").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_namespace_excludes_cluster_scoped() {
+        let mut node = Node::default();
+        node.metadata.name = Some("node-1".to_string());
+
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let nodes: Api<Node> = Api::all(client);
+        nodes.create(&PostParams::default(), &node).await.unwrap();
+
+        assert!(handle.list_namespace("default").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dependents_of() {
+        use k8s_openapi::api::apps::v1::Deployment;
+        use kube::Resource;
+
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), "default");
+        let mut deployment = Deployment::default();
+        deployment.metadata.name = Some("web".to_string());
+        let deployment = deployments
+            .create(&PostParams::default(), &deployment)
+            .await
+            .unwrap();
+        let owner = deployment.controller_owner_ref(&()).unwrap();
+
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        for name in ["web-config", "unrelated"] {
+            let mut cm = in_namespace(configmap(name), "default");
+            if name != "unrelated" {
+                cm.metadata.owner_references = Some(vec![owner.clone()]);
+            }
+            configmaps
+                .create(&PostParams::default(), &cm)
+                .await
+                .unwrap();
+        }
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        let mut owned_pod = pod("web-0", "default");
+        owned_pod.metadata.owner_references = Some(vec![owner.clone()]);
+        pods.create(&PostParams::default(), &owned_pod)
+            .await
+            .unwrap();
+
+        // A reference to an earlier owner of the same name doesn't count
+        let mut stale = pod("web-old", "default");
+        let mut stale_owner = owner;
+        stale_owner.uid = "earlier-uid".to_string();
+        stale.metadata.owner_references = Some(vec![stale_owner]);
+        pods.create(&PostParams::default(), &stale).await.unwrap();
+
+        let dependents: Vec<_> = handle
+            .dependents_of::<Deployment>("default", "web")
+            .unwrap()
+            .into_iter()
+            .map(|o| (o.types.unwrap().kind, o.metadata.name.unwrap()))
+            .collect();
+        assert_eq!(
+            dependents,
+            [
+                ("ConfigMap".to_string(), "web-config".to_string()),
+                ("Pod".to_string(), "web-0".to_string()),
+            ]
+        );
+
+        assert!(handle
+            .dependents_of::<Deployment>("default", "missing")
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_events() {
+        use futures::StreamExt;
+        use kube::api::{Patch, PatchParams};
+        use kube::core::WatchEvent;
+
+        let (client, handle) = ClientBuilder::new()
+            .with_object(pod("existing", "default"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client.clone(), "default");
+        let mut events = handle.watch_events::<Pod>(Some("default")).unwrap();
+        let mut all = handle.watch_events::<Pod>(None).unwrap();
+
+        pods.create(&PostParams::default(), &pod("web", "default"))
+            .await
+            .unwrap();
+        Api::<Pod>::namespaced(client.clone(), "other")
+            .create(&PostParams::default(), &pod("db", "other"))
+            .await
+            .unwrap();
+        Api::<ConfigMap>::namespaced(client, "default")
+            .create(
+                &PostParams::default(),
+                &in_namespace(configmap("settings"), "default"),
+            )
+            .await
+            .unwrap();
+        let patch = serde_json::json!({ "metadata": { "labels": { "app": "web" } } });
+        pods.patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        pods.delete("web", &DeleteParams::default()).await.unwrap();
+
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            seen.push(match events.next().await.unwrap() {
+                WatchEvent::Added(pod) => format!("added {}", pod.metadata.name.unwrap()),
+                WatchEvent::Modified(pod) => format!(
+                    "modified {} {:?}",
+                    pod.metadata.name.unwrap(),
+                    pod.metadata.labels.unwrap_or_default()
+                ),
+                WatchEvent::Deleted(pod) => format!("deleted {}", pod.metadata.name.unwrap()),
+                other => panic!("unexpected event: {other:?}"),
+            });
+        }
+        assert_eq!(
+            seen,
+            [
+                "added web",
+                "modified web {\"app\": \"web\"}",
+                "deleted web"
+            ]
+        );
+
+        // Without a namespace, every namespace's writes are seen
+        let mut names = Vec::new();
+        for _ in 0..4 {
+            if let WatchEvent::Added(pod) | WatchEvent::Modified(pod) | WatchEvent::Deleted(pod) =
+                all.next().await.unwrap()
+            {
+                names.push(pod.metadata.name.unwrap());
+            }
+        }
+        assert_eq!(names, ["web", "db", "web", "web"]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_sees_later_writes() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(pod("web", "default"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let readiness = tokio::spawn({
+            let handle = handle.clone();
+            async move {
+                handle
+                    .wait_for(
+                        |pod: &Pod| crate::testing::is_pod_ready(pod),
+                        std::time::Duration::from_secs(5),
+                    )
+                    .await
+            }
+        });
+        tokio::task::yield_now().await;
+
+        handle.set_pod_ready("default", "web").unwrap();
+        let ready = readiness.await.unwrap().unwrap();
+        assert_eq!(ready.metadata.name.as_deref(), Some("web"));
+
+        // An object that already matches is returned at once
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        pods.create(&PostParams::default(), &pod("db", "default"))
+            .await
+            .unwrap();
+        let db: Pod = handle
+            .wait_for_object("default", "db", |_: &Pod| true, std::time::Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(db.metadata.name.as_deref(), Some("db"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_object_times_out() {
+        let (_client, handle) = ClientBuilder::new()
+            .with_object(pod("web", "default"))
+            .build_with_handle()
+            .await
+            .unwrap();
+
+        let err = handle
+            .wait_for_object(
+                "default",
+                "web",
+                crate::testing::is_pod_ready,
+                std::time::Duration::from_secs(30),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::Timeout(_)), "{err}");
+        assert_eq!(
+            err.to_string(),
+            "Timeout: no Pod default/web matched within 30s"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modify_writes_spec_and_status() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(pod("web", "default"))
+            .build_with_handle()
+            .await
+            .unwrap();
+
+        let modified: Pod = handle
+            .modify("default", "web", |pod: &mut Pod| {
+                pod.metadata
+                    .labels
+                    .get_or_insert_default()
+                    .insert("tier".to_string(), "frontend".to_string());
+                pod.status.get_or_insert_default().phase = Some("Running".to_string());
+            })
+            .unwrap();
+        assert_eq!(
+            modified.status.and_then(|status| status.phase).as_deref(),
+            Some("Running")
+        );
+
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        let stored = pods.get("web").await.unwrap();
+        assert_eq!(stored.metadata.labels.unwrap()["tier"], "frontend");
+        assert_eq!(stored.status.unwrap().phase.as_deref(), Some("Running"));
+    }
+
+    #[tokio::test]
+    async fn test_modify_retries_on_conflict() {
+        let (_client, handle) = ClientBuilder::new()
+            .with_object(in_namespace(configmap("settings"), "default"))
+            .build_with_handle()
+            .await
+            .unwrap();
+
+        let mut runs = 0;
+        let modified: ConfigMap = handle
+            .modify("default", "settings", |cm: &mut ConfigMap| {
+                runs += 1;
+                if runs == 1 {
+                    // Another actor writes between the read and the write
+                    handle
+                        .modify("default", "settings", |cm: &mut ConfigMap| {
+                            cm.data = Some([("owner".to_string(), "other".to_string())].into());
+                        })
+                        .unwrap();
+                }
+                cm.data
+                    .get_or_insert_default()
+                    .insert("mode".to_string(), "fast".to_string());
+            })
+            .unwrap();
+
+        assert_eq!(runs, 2);
+        let data = modified.data.unwrap();
+        assert_eq!(data["owner"], "other");
+        assert_eq!(data["mode"], "fast");
+    }
+
+    #[tokio::test]
+    async fn test_modify_unchecked_skips_immutable_fields() {
+        let mut settings = in_namespace(configmap("settings"), "default");
+        settings.immutable = Some(true);
+        settings.data = Some([("mode".to_string(), "slow".to_string())].into());
+        let (_client, handle) = ClientBuilder::new()
+            .with_object(settings)
+            .build_with_handle()
+            .await
+            .unwrap();
+        let set_fast = |cm: &mut ConfigMap| {
+            cm.data = Some([("mode".to_string(), "fast".to_string())].into());
+        };
+
+        let err = handle
+            .modify::<ConfigMap, _>("default", "settings", set_fast)
+            .unwrap_err();
+        assert!(err.to_string().contains("Immutable field"), "{err}");
+
+        let modified: ConfigMap = handle
+            .modify_unchecked("default", "settings", set_fast)
+            .unwrap();
+        assert_eq!(modified.data.unwrap()["mode"], "fast");
+    }
+}
@@ -0,0 +1,293 @@
+//! A reconciler driven against the fake client's watch streams
+//!
+//! Reconciles run on a spawned tokio task, so the harness needs the
+//! `tokio-runtime` feature. It is exported as
+//! [`testing::ControllerHarness`](crate::testing::ControllerHarness).
+
+use futures::StreamExt;
+use kube::api::{Api, DynamicObject};
+use kube::runtime::controller::{
+    self, applier, trigger_self, Action, Config, ReconcileReason, ReconcileRequest,
+};
+use kube::runtime::reflector::{self, reflector, ObjectRef};
+use kube::runtime::watcher::{self, watcher};
+use kube::runtime::WatchStreamExt;
+use kube::Resource;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Outcome of one reconcile run by a [`ControllerHarness`]
+#[derive(Debug, Clone)]
+pub struct ReconcileResult<K: Resource<DynamicType = ()>> {
+    /// The object reconciled
+    pub object: ObjectRef<K>,
+    /// The action the reconciler returned, or its error
+    pub action: std::result::Result<Action, String>,
+}
+
+/// Drives a reconciler against the fake client the way `kube_runtime::Controller`
+/// does
+///
+/// The harness watches the resource through the fake client, keeps a reflector
+/// store of it and runs the reconciler for every change, including the changes
+/// the reconciler makes itself; [`trigger`](Self::trigger) queues a reconcile by
+/// hand. Reconciles run on a spawned task and their outcomes are collected, so
+/// a test can let the controller settle with
+/// [`run_until_idle`](Self::run_until_idle) and then assert on the cluster and
+/// on the results.
+///
+/// The controller task stops when the harness is dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::testing::ControllerHarness;
+/// use kube_fake_client::ClientBuilder;
+/// use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube::api::{Api, Patch, PatchParams, ResourceExt};
+/// use kube::runtime::controller::Action;
+/// use serde_json::json;
+/// use std::sync::Arc;
+///
+/// async fn reconcile(cm: Arc<ConfigMap>, client: Arc<kube::Client>) -> Result<Action, kube::Error> {
+///     let api: Api<ConfigMap> = Api::namespaced((*client).clone(), &cm.namespace().unwrap());
+///     let patch = json!({ "metadata": { "labels": { "reconciled": "true" } } });
+///     api.patch(&cm.name_any(), &PatchParams::default(), &Patch::Merge(&patch)).await?;
+///     Ok(Action::await_change())
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClientBuilder::new().build().await?;
+/// let mut harness = ControllerHarness::new(
+///     Api::<ConfigMap>::all(client.clone()),
+///     reconcile,
+///     |_, _, _| Action::await_change(),
+///     Arc::new(client),
+/// );
+///
+/// // ... create ConfigMaps through the client ...
+///
+/// let results = harness.run_until_idle().await;
+/// assert!(results.iter().all(|r| r.action.is_ok()));
+/// # Ok(())
+/// # }
+/// ```
+pub struct ControllerHarness<K: Resource<DynamicType = ()>> {
+    triggers: futures::channel::mpsc::UnboundedSender<ObjectRef<K>>,
+    outcomes: tokio::sync::mpsc::UnboundedReceiver<Outcome<K>>,
+    results: Vec<ReconcileResult<K>>,
+    controller_errors: Vec<String>,
+    /// Reconciles currently running
+    active: Arc<AtomicUsize>,
+    idle_timeout: Duration,
+    reconcile_limit: usize,
+    task: tokio::task::JoinHandle<()>,
+}
+
+enum Outcome<K: Resource<DynamicType = ()>> {
+    Reconciled(ReconcileResult<K>),
+    ControllerError(String),
+}
+
+impl<K> ControllerHarness<K>
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Debug + Send + Sync + 'static,
+{
+    /// Start a controller for the objects visible through `api`
+    ///
+    /// `reconcile` and `error_policy` have the signatures `Controller::run` takes.
+    /// Must be called from within a tokio runtime.
+    pub fn new<Ctx, Fut, E>(
+        api: Api<K>,
+        mut reconcile: impl FnMut(Arc<K>, Arc<Ctx>) -> Fut + Send + 'static,
+        error_policy: impl Fn(Arc<K>, &E, Arc<Ctx>) -> Action + Send + Sync + 'static,
+        context: Arc<Ctx>,
+    ) -> Self
+    where
+        Ctx: Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<Action, E>> + Send + 'static,
+        E: std::error::Error + Send + 'static,
+    {
+        let (triggers, manual) = futures::channel::mpsc::unbounded();
+        let (sender, outcomes) = tokio::sync::mpsc::unbounded_channel();
+        let active = Arc::new(AtomicUsize::new(0));
+
+        let writer = reflector::store::Writer::<K>::default();
+        let store = writer.as_reader();
+        let changes = trigger_self(
+            reflector(writer, watcher(api, watcher::Config::default()))
+                .default_backoff()
+                .applied_objects(),
+            (),
+        );
+        let manual = manual.map(|obj_ref| {
+            Ok(ReconcileRequest {
+                obj_ref,
+                reason: ReconcileReason::Custom {
+                    reason: "triggered by test".to_string(),
+                },
+            })
+        });
+
+        let running = Arc::clone(&active);
+        let reconciler = move |object, context| {
+            let guard = ActiveGuard::new(Arc::clone(&running));
+            let reconcile = reconcile(object, context);
+            Box::pin(async move {
+                let result = reconcile.await;
+                drop(guard);
+                result
+            })
+        };
+
+        let applier = applier(
+            reconciler,
+            error_policy,
+            context,
+            store,
+            futures::stream::select(changes, manual),
+            Config::default(),
+        );
+        let task = tokio::spawn(async move {
+            let mut applier = std::pin::pin!(applier);
+            while let Some(result) = applier.next().await {
+                let outcome = match result {
+                    Ok((object, action)) => Outcome::Reconciled(ReconcileResult {
+                        object,
+                        action: Ok(action),
+                    }),
+                    Err(controller::Error::ReconcilerFailed(err, object)) => {
+                        Outcome::Reconciled(ReconcileResult {
+                            object: object_ref(&object),
+                            action: Err(err.to_string()),
+                        })
+                    }
+                    Err(controller::Error::ObjectNotFound(object)) => {
+                        Outcome::Reconciled(ReconcileResult {
+                            object: object_ref(&object),
+                            action: Err(format!("object {object} not found in the store")),
+                        })
+                    }
+                    Err(err) => Outcome::ControllerError(format!("{err}: {err:?}")),
+                };
+                if sender.send(outcome).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            triggers,
+            outcomes,
+            results: Vec::new(),
+            controller_errors: Vec::new(),
+            active,
+            idle_timeout: Duration::from_millis(100),
+            reconcile_limit: 1000,
+            task,
+        }
+    }
+
+    /// How long the controller must be quiet to count as idle (100ms by default)
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Reconciles a single [`run_until_idle`](Self::run_until_idle) may see before
+    /// giving up on the controller settling (1000 by default)
+    pub fn with_reconcile_limit(mut self, limit: usize) -> Self {
+        self.reconcile_limit = limit;
+        self
+    }
+
+    /// Queue a reconcile of an object
+    ///
+    /// The object is looked up in the controller's store when the reconcile runs,
+    /// so it must exist in the cluster; otherwise the result is an error.
+    pub fn trigger(&self, object: &K) {
+        // Only fails once the controller task has stopped
+        let _ = self.triggers.unbounded_send(ObjectRef::from_obj(object));
+    }
+
+    /// Wait until no reconcile is running and none finished for the idle timeout
+    ///
+    /// Returns the results of the reconciles that finished during the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more reconciles than the reconcile limit finish, which usually
+    /// means the reconciler keeps triggering itself.
+    pub async fn run_until_idle(&mut self) -> &[ReconcileResult<K>] {
+        let start = self.results.len();
+        loop {
+            match tokio::time::timeout(self.idle_timeout, self.outcomes.recv()).await {
+                Ok(Some(Outcome::Reconciled(result))) => {
+                    self.results.push(result);
+                    let count = self.results.len() - start;
+                    if count > self.reconcile_limit {
+                        panic!(
+                            "controller did not settle after {count} reconciles; last: {:?}",
+                            self.results.last()
+                        );
+                    }
+                }
+                Ok(Some(Outcome::ControllerError(err))) => self.controller_errors.push(err),
+                Ok(None) => break,
+                Err(_) if self.active.load(Ordering::SeqCst) > 0 => {}
+                Err(_) => break,
+            }
+        }
+        &self.results[start..]
+    }
+
+    /// Every reconcile result collected so far, oldest first
+    pub fn results(&self) -> &[ReconcileResult<K>] {
+        &self.results
+    }
+
+    /// Results of the reconciles that failed
+    pub fn errors(&self) -> Vec<&ReconcileResult<K>> {
+        self.results.iter().filter(|r| r.action.is_err()).collect()
+    }
+
+    /// Errors of the controller itself, such as watch failures
+    pub fn controller_errors(&self) -> &[String] {
+        &self.controller_errors
+    }
+}
+
+impl<K: Resource<DynamicType = ()>> Drop for ControllerHarness<K> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Counts a reconcile as running until dropped
+struct ActiveGuard(Arc<AtomicUsize>);
+
+impl ActiveGuard {
+    fn new(active: Arc<AtomicUsize>) -> Self {
+        active.fetch_add(1, Ordering::SeqCst);
+        Self(active)
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn object_ref<K: Resource<DynamicType = ()>>(object: &ObjectRef<DynamicObject>) -> ObjectRef<K> {
+    let obj_ref = ObjectRef::new(&object.name);
+    match &object.namespace {
+        Some(namespace) => obj_ref.within(namespace),
+        None => obj_ref,
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap_with;
+    use crate::testing::ControllerHarness;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, Patch, PatchParams, PostParams, ResourceExt};
+    use kube::runtime::controller::Action;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    async fn label_reconciled(
+        cm: Arc<ConfigMap>,
+        configmaps: Arc<Api<ConfigMap>>,
+    ) -> Result<Action, kube::Error> {
+        if cm.labels().contains_key("reconciled") {
+            return Ok(Action::await_change());
+        }
+        let patch = json!({ "metadata": { "labels": { "reconciled": "true" } } });
+        configmaps
+            .patch(
+                &cm.name_any(),
+                &PatchParams::default(),
+                &Patch::Merge(&patch),
+            )
+            .await?;
+        Ok(Action::await_change())
+    }
+
+    #[tokio::test]
+    async fn test_controller_harness_reconciles_until_idle() {
+        let client = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let mut harness = ControllerHarness::new(
+            configmaps.clone(),
+            label_reconciled,
+            |_, _, _| Action::await_change(),
+            Arc::new(configmaps.clone()),
+        );
+
+        let results = harness.run_until_idle().await;
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.action.is_ok()));
+        let existing = configmaps.get("existing").await.unwrap();
+        assert_eq!(existing.labels()["reconciled"], "true");
+
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("created", "mode", "active"),
+            )
+            .await
+            .unwrap();
+        harness.run_until_idle().await;
+        let created = configmaps.get("created").await.unwrap();
+        assert_eq!(created.labels()["reconciled"], "true");
+
+        // A manual trigger reconciles an object that is already settled
+        let before = harness.results().len();
+        harness.trigger(&created);
+        let results = harness.run_until_idle().await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].object.name, "created");
+        assert_eq!(harness.results().len(), before + 1);
+        assert!(harness.controller_errors().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_controller_harness_collects_errors() {
+        let client = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let reconcile = |cm: Arc<ConfigMap>, configmaps: Arc<Api<ConfigMap>>| async move {
+            configmaps
+                .get(&format!("{}-missing", cm.name_any()))
+                .await?;
+            Ok::<_, kube::Error>(Action::await_change())
+        };
+        let mut harness = ControllerHarness::new(
+            configmaps.clone(),
+            reconcile,
+            |_, _, _| Action::await_change(),
+            Arc::new(configmaps),
+        );
+
+        harness.run_until_idle().await;
+        let errors = harness.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].object.name, "existing");
+        assert!(errors[0]
+            .action
+            .as_ref()
+            .unwrap_err()
+            .contains("existing-missing"));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "controller did not settle")]
+    async fn test_controller_harness_detects_hot_loop() {
+        let client = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        // Every reconcile writes, which triggers another reconcile
+        let reconcile = |cm: Arc<ConfigMap>, configmaps: Arc<Api<ConfigMap>>| async move {
+            let generation = cm.resource_version().unwrap_or_default();
+            let patch = json!({ "data": { "seen": generation } });
+            configmaps
+                .patch(
+                    &cm.name_any(),
+                    &PatchParams::default(),
+                    &Patch::Merge(&patch),
+                )
+                .await?;
+            Ok::<_, kube::Error>(Action::await_change())
+        };
+        let mut harness = ControllerHarness::new(
+            configmaps.clone(),
+            reconcile,
+            |_, _, _| Action::await_change(),
+            Arc::new(configmaps),
+        )
+        .with_reconcile_limit(5);
+
+        harness.run_until_idle().await;
+    }
+}
@@ -0,0 +1,404 @@
+//! HorizontalPodAutoscaler simulation
+//!
+//! There is no metrics pipeline behind the fake client, so tests supply metric
+//! values through [`ClientHandle::set_hpa_metrics`](crate::ClientHandle::set_hpa_metrics),
+//! which runs one pass of the HPA controller's algorithm on them: the desired
+//! replica count is the current count scaled by how far each metric is from its
+//! target, ignoring deviations within a 10% tolerance, taking the largest
+//! proposal and clamping it to `minReplicas`..`maxReplicas`. The scale target's
+//! `spec.replicas`, or the field its scale subresource maps it to for custom
+//! resources, is set to the result, and the HPA's status reports the
+//! replica counts, metrics and the `AbleToScale`, `ScalingActive` and
+//! `ScalingLimited` conditions the way the controller does.
+
+use crate::client::FakeClient;
+use crate::clock;
+use crate::discovery::Discovery;
+use crate::scale;
+use crate::tracker::{GVK, GVR};
+use crate::{Error, Result};
+use chrono::SecondsFormat;
+use serde_json::{json, Value};
+
+/// Relative deviation from a target within which the HPA doesn't scale
+const TOLERANCE: f64 = 0.1;
+
+/// Target used by the API server when an HPA specifies no metrics
+const DEFAULT_CPU_UTILIZATION: f64 = 80.0;
+
+fn hpas_gvr() -> GVR {
+    GVR::new("autoscaling", "v2", "horizontalpodautoscalers")
+}
+
+fn hpa_gvk() -> GVK {
+    GVK::new("autoscaling", "v2", "HorizontalPodAutoscaler")
+}
+
+/// A metric an HPA scales on
+struct MetricSpec {
+    /// `Resource`, `ContainerResource`, `Pods`, `Object` or `External`
+    source: String,
+    /// Resource name such as `cpu`, or the custom metric's name
+    name: String,
+    /// `Utilization`, `AverageValue` or `Value`
+    target_type: String,
+    target: f64,
+    /// The spec entry, for building the metric's status
+    spec: Value,
+}
+
+impl MetricSpec {
+    fn from_spec(spec: &Value) -> Option<Self> {
+        let source = spec.get("type")?.as_str()?.to_string();
+        let field = match source.as_str() {
+            "Resource" => "resource",
+            "ContainerResource" => "containerResource",
+            "Pods" => "pods",
+            "Object" => "object",
+            "External" => "external",
+            _ => return None,
+        };
+        let body = spec.get(field)?;
+        let name = body
+            .get("name")
+            .or_else(|| body.pointer("/metric/name"))?
+            .as_str()?
+            .to_string();
+        let target = body.get("target")?;
+        let target_type = target.get("type")?.as_str()?.to_string();
+        let target_value = match target_type.as_str() {
+            "Utilization" => target.get("averageUtilization")?.as_f64()?,
+            "AverageValue" => parse_quantity(target.get("averageValue")?)?,
+            "Value" => parse_quantity(target.get("value")?)?,
+            _ => return None,
+        };
+        Some(Self {
+            source,
+            name,
+            target_type,
+            target: target_value,
+            spec: spec.clone(),
+        })
+    }
+
+    /// The metric the API server defaults an HPA without metrics to
+    fn default_cpu() -> Self {
+        let spec = json!({
+            "type": "Resource",
+            "resource": {
+                "name": "cpu",
+                "target": { "type": "Utilization", "averageUtilization": DEFAULT_CPU_UTILIZATION },
+            },
+        });
+        Self::from_spec(&spec).expect("default metric is valid")
+    }
+
+    /// Replica count this metric calls for
+    fn proposal(&self, current_replicas: i64, value: f64) -> i64 {
+        let ratio = value / self.target;
+        if (ratio - 1.0).abs() <= TOLERANCE {
+            return current_replicas;
+        }
+        (current_replicas as f64 * ratio).ceil() as i64
+    }
+
+    /// Entry of `status.currentMetrics` reporting a value
+    fn status(&self, value: f64) -> Value {
+        let current = match self.target_type.as_str() {
+            "Utilization" => json!({ "averageUtilization": value.round() as i64 }),
+            "AverageValue" => json!({ "averageValue": format_quantity(value) }),
+            _ => json!({ "value": format_quantity(value) }),
+        };
+        let field = match self.source.as_str() {
+            "Resource" => "resource",
+            "ContainerResource" => "containerResource",
+            "Pods" => "pods",
+            "Object" => "object",
+            _ => "external",
+        };
+        let mut body = self.spec[field].clone();
+        if let Some(body) = body.as_object_mut() {
+            body.remove("target");
+            body.insert("current".to_string(), current);
+        }
+        json!({ "type": self.source, field: body })
+    }
+
+    /// Reason of the `ScalingActive` condition when the metric has no value
+    fn missing_reason(&self) -> String {
+        format!("FailedGet{}Metric", self.source)
+    }
+}
+
+/// Run one HPA controller pass with the given metric values, by metric name
+pub(crate) fn sync(
+    client: &FakeClient,
+    namespace: &str,
+    name: &str,
+    metrics: &[(&str, f64)],
+) -> Result<Value> {
+    let tracker = &client.tracker;
+    let hpa = tracker.get(&hpas_gvr(), namespace, name)?;
+    let spec = hpa["spec"].clone();
+    let min_replicas = spec["minReplicas"].as_i64().unwrap_or(1);
+    let max_replicas = spec["maxReplicas"].as_i64().unwrap_or(min_replicas);
+
+    let mut conditions = Vec::new();
+    let mut status = json!({
+        "currentReplicas": hpa.pointer("/status/currentReplicas").cloned().unwrap_or(json!(0)),
+        "desiredReplicas": hpa.pointer("/status/desiredReplicas").cloned().unwrap_or(json!(0)),
+    });
+    if let Some(generation) = hpa.pointer("/metadata/generation") {
+        status["observedGeneration"] = generation.clone();
+    }
+    if let Some(last_scale) = hpa.pointer("/status/lastScaleTime") {
+        status["lastScaleTime"] = last_scale.clone();
+    }
+
+    let target_ref = &spec["scaleTargetRef"];
+    let target = match scale_target(client, namespace, target_ref) {
+        Ok(target) => target,
+        Err(e) => {
+            conditions.push(condition(
+                "AbleToScale",
+                false,
+                "FailedGetScale",
+                &format!("the HPA controller was unable to get the target's current scale: {e}"),
+            ));
+            return write_status(client, namespace, hpa, status, conditions);
+        }
+    };
+    let (target_gvr, target_gvk, target) = target;
+    let mapping = scale::target_mapping(client, &target_gvk);
+    let spec_replicas = scale::spec_replicas(&target, &mapping).unwrap_or(1);
+    let current_replicas = scale::status_replicas(&target, &mapping).unwrap_or(spec_replicas);
+    status["currentReplicas"] = json!(current_replicas);
+    conditions.push(condition(
+        "AbleToScale",
+        true,
+        "SucceededGetScale",
+        "the HPA controller was able to get the target's current scale",
+    ));
+
+    if spec_replicas == 0 {
+        status["desiredReplicas"] = json!(0);
+        conditions.push(condition(
+            "ScalingActive",
+            false,
+            "ScalingDisabled",
+            "scaling is disabled since the replica count of the target is zero",
+        ));
+        return write_status(client, namespace, hpa, status, conditions);
+    }
+
+    let specs: Vec<MetricSpec> = match spec["metrics"].as_array() {
+        Some(metrics) if !metrics.is_empty() => {
+            metrics.iter().filter_map(MetricSpec::from_spec).collect()
+        }
+        _ => vec![MetricSpec::default_cpu()],
+    };
+    let mut proposals = Vec::new();
+    let mut current_metrics = Vec::new();
+    let mut missing = None;
+    for metric in &specs {
+        match metrics.iter().find(|(name, _)| *name == metric.name) {
+            Some((_, value)) => {
+                proposals.push(metric.proposal(current_replicas, *value));
+                current_metrics.push(metric.status(*value));
+            }
+            None => {
+                missing.get_or_insert(metric);
+            }
+        }
+    }
+    status["currentMetrics"] = json!(current_metrics);
+
+    let Some(proposal) = proposals.into_iter().max() else {
+        let (reason, message) = match missing {
+            Some(metric) => (
+                metric.missing_reason(),
+                format!(
+                    "the HPA was unable to compute the replica count: no value set for metric {}",
+                    metric.name
+                ),
+            ),
+            None => (
+                "InvalidMetricSourceType".to_string(),
+                "the HPA has no metrics it can scale on".to_string(),
+            ),
+        };
+        status["desiredReplicas"] = json!(current_replicas);
+        conditions.push(condition("ScalingActive", false, &reason, &message));
+        return write_status(client, namespace, hpa, status, conditions);
+    };
+    conditions.push(condition(
+        "ScalingActive",
+        true,
+        "ValidMetricFound",
+        "the HPA was able to successfully calculate a replica count",
+    ));
+
+    let desired = proposal.clamp(min_replicas, max_replicas);
+    conditions.push(match proposal {
+        p if p > max_replicas => condition(
+            "ScalingLimited",
+            true,
+            "TooManyReplicas",
+            "the desired replica count is more than the maximum replica count",
+        ),
+        p if p < min_replicas => condition(
+            "ScalingLimited",
+            true,
+            "TooFewReplicas",
+            "the desired replica count is less than the minimum replica count",
+        ),
+        _ => condition(
+            "ScalingLimited",
+            false,
+            "DesiredWithinRange",
+            "the desired count is within the acceptable range",
+        ),
+    });
+    status["desiredReplicas"] = json!(desired);
+
+    if desired != spec_replicas {
+        rescale(client, namespace, &target_gvr, &target_gvk, target, desired)?;
+        status["lastScaleTime"] = json!(now());
+        conditions[0] = condition(
+            "AbleToScale",
+            true,
+            "SucceededRescale",
+            &format!("the HPA controller was able to update the target scale to {desired}"),
+        );
+    }
+    write_status(client, namespace, hpa, status, conditions)
+}
+
+/// The object an HPA scales, with its resource and kind
+fn scale_target(
+    client: &FakeClient,
+    namespace: &str,
+    target_ref: &Value,
+) -> Result<(GVR, GVK, Value)> {
+    let invalid = || Error::InvalidRequest("spec.scaleTargetRef is incomplete".to_string());
+    let api_version = target_ref["apiVersion"].as_str().ok_or_else(invalid)?;
+    let kind = target_ref["kind"].as_str().ok_or_else(invalid)?;
+    let name = target_ref["name"].as_str().ok_or_else(invalid)?;
+    let (group, version) = api_version.split_once('/').unwrap_or(("", api_version));
+    let gvk = GVK::new(group, version, kind);
+    let gvr = Discovery::gvk_to_gvr_with_registry(&gvk, &client.registry).ok_or_else(|| {
+        Error::ResourceNotRegistered {
+            group: gvk.group.clone(),
+            version: gvk.version.clone(),
+            resource: format!("{} (kind)", gvk.kind),
+        }
+    })?;
+    let target = client.tracker.get(&gvr, namespace, name)?;
+    Ok((gvr, gvk, target))
+}
+
+/// Set the scale target's replica count, retrying on concurrent writes
+fn rescale(
+    client: &FakeClient,
+    namespace: &str,
+    gvr: &GVR,
+    gvk: &GVK,
+    mut target: Value,
+    replicas: i64,
+) -> Result<()> {
+    let name = target["metadata"]["name"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let mapping = scale::target_mapping(client, gvk);
+    loop {
+        scale::set_spec_replicas(&mut target, &mapping, replicas);
+        match client.tracker.update(gvr, gvk, target, namespace, false) {
+            Err(Error::Conflict(_)) => target = client.tracker.get(gvr, namespace, &name)?,
+            result => return result.map(drop),
+        }
+    }
+}
+
+/// Store the HPA status, keeping the transition time of unchanged conditions
+fn write_status(
+    client: &FakeClient,
+    namespace: &str,
+    mut hpa: Value,
+    mut status: Value,
+    mut conditions: Vec<Value>,
+) -> Result<Value> {
+    let previous = hpa
+        .pointer("/status/conditions")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for condition in &mut conditions {
+        if let Some(before) = previous.iter().find(|before| {
+            before["type"] == condition["type"] && before["status"] == condition["status"]
+        }) {
+            condition["lastTransitionTime"] = before["lastTransitionTime"].clone();
+        }
+    }
+    status["conditions"] = json!(conditions);
+    hpa["status"] = status;
+    if let Some(metadata) = hpa.get_mut("metadata").and_then(Value::as_object_mut) {
+        metadata.remove("resourceVersion");
+    }
+    client
+        .tracker
+        .update(&hpas_gvr(), &hpa_gvk(), hpa, namespace, true)
+}
+
+fn condition(condition_type: &str, status: bool, reason: &str, message: &str) -> Value {
+    json!({
+        "type": condition_type,
+        "status": if status { "True" } else { "False" },
+        "lastTransitionTime": now(),
+        "reason": reason,
+        "message": message,
+    })
+}
+
+fn now() -> String {
+    clock::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Parse a resource quantity such as `500m`, `2` or `64Mi`
+fn parse_quantity(value: &Value) -> Option<f64> {
+    if let Some(number) = value.as_f64() {
+        return Some(number);
+    }
+    let text = value.as_str()?.trim();
+    let split = text
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    let (number, suffix) = text.split_at(split);
+    let multiplier = match suffix {
+        "" => 1.0,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024.0_f64.powi(2),
+        "Gi" => 1024.0_f64.powi(3),
+        "Ti" => 1024.0_f64.powi(4),
+        "Pi" => 1024.0_f64.powi(5),
+        "Ei" => 1024.0_f64.powi(6),
+        _ => return None,
+    };
+    Some(number.parse::<f64>().ok()? * multiplier)
+}
+
+/// Format a value as a quantity, in milli-units if it isn't whole
+fn format_quantity(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}m", (value * 1000.0).round() as i64)
+    }
+}
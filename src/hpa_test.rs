@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod tests {
+    use crate::{ClientBuilder, ClientHandle};
+    use k8s_openapi::api::apps::v1::Deployment;
+    use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+    use kube::api::{Api, PostParams};
+    use kube::Client;
+    use serde_json::json;
+
+    async fn setup(metrics: serde_json::Value) -> (Client, ClientHandle) {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let deployment: Deployment = serde_json::from_value(json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "web" },
+            "spec": {
+                "replicas": 2,
+                "selector": { "matchLabels": { "app": "web" } },
+                "template": {
+                    "metadata": { "labels": { "app": "web" } },
+                    "spec": { "containers": [{ "name": "web", "image": "nginx" }] },
+                },
+            },
+        }))
+        .unwrap();
+        let hpa: HorizontalPodAutoscaler = serde_json::from_value(json!({
+            "apiVersion": "autoscaling/v2",
+            "kind": "HorizontalPodAutoscaler",
+            "metadata": { "name": "web" },
+            "spec": {
+                "scaleTargetRef": { "apiVersion": "apps/v1", "kind": "Deployment", "name": "web" },
+                "minReplicas": 1,
+                "maxReplicas": 6,
+                "metrics": metrics,
+            },
+        }))
+        .unwrap();
+        Api::<Deployment>::namespaced(client.clone(), "default")
+            .create(&PostParams::default(), &deployment)
+            .await
+            .unwrap();
+        Api::<HorizontalPodAutoscaler>::namespaced(client.clone(), "default")
+            .create(&PostParams::default(), &hpa)
+            .await
+            .unwrap();
+        (client, handle)
+    }
+
+    fn cpu_utilization(target: i32) -> serde_json::Value {
+        json!([{
+            "type": "Resource",
+            "resource": {
+                "name": "cpu",
+                "target": { "type": "Utilization", "averageUtilization": target },
+            },
+        }])
+    }
+
+    fn condition(hpa: &HorizontalPodAutoscaler, condition_type: &str) -> (String, String) {
+        let condition = hpa
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == condition_type))
+            .unwrap_or_else(|| panic!("no {condition_type} condition"));
+        (
+            condition.status.clone(),
+            condition.reason.clone().unwrap_or_default(),
+        )
+    }
+
+    async fn replicas(client: &Client) -> i32 {
+        Api::<Deployment>::namespaced(client.clone(), "default")
+            .get("web")
+            .await
+            .unwrap()
+            .spec
+            .unwrap()
+            .replicas
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_set_hpa_metrics_scales_target() {
+        let (client, handle) = setup(cpu_utilization(50)).await;
+
+        let hpa = handle
+            .set_hpa_metrics("default", "web", &[("cpu", 100.0)])
+            .unwrap();
+        let status = hpa.status.as_ref().unwrap();
+        assert_eq!(status.current_replicas, Some(2));
+        assert_eq!(status.desired_replicas, 4);
+        assert!(status.last_scale_time.is_some());
+        let current = &status.current_metrics.as_ref().unwrap()[0];
+        assert_eq!(
+            current
+                .resource
+                .as_ref()
+                .unwrap()
+                .current
+                .average_utilization,
+            Some(100)
+        );
+        assert_eq!(
+            condition(&hpa, "AbleToScale"),
+            ("True".to_string(), "SucceededRescale".to_string())
+        );
+        assert_eq!(
+            condition(&hpa, "ScalingActive"),
+            ("True".to_string(), "ValidMetricFound".to_string())
+        );
+        assert_eq!(
+            condition(&hpa, "ScalingLimited"),
+            ("False".to_string(), "DesiredWithinRange".to_string())
+        );
+        assert_eq!(replicas(&client).await, 4);
+
+        // The status is visible through the API as well
+        let stored = Api::<HorizontalPodAutoscaler>::namespaced(client.clone(), "default")
+            .get("web")
+            .await
+            .unwrap();
+        assert_eq!(stored.status.unwrap().desired_replicas, 4);
+
+        // Within the tolerance, nothing changes
+        let hpa = handle
+            .set_hpa_metrics("default", "web", &[("cpu", 53.0)])
+            .unwrap();
+        assert_eq!(hpa.status.as_ref().unwrap().desired_replicas, 4);
+        assert_eq!(condition(&hpa, "AbleToScale").1, "SucceededGetScale");
+        assert_eq!(replicas(&client).await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_set_hpa_metrics_clamps_to_range() {
+        let (client, handle) = setup(cpu_utilization(50)).await;
+
+        let hpa = handle
+            .set_hpa_metrics("default", "web", &[("cpu", 400.0)])
+            .unwrap();
+        assert_eq!(hpa.status.as_ref().unwrap().desired_replicas, 6);
+        assert_eq!(
+            condition(&hpa, "ScalingLimited"),
+            ("True".to_string(), "TooManyReplicas".to_string())
+        );
+        assert_eq!(replicas(&client).await, 6);
+
+        let hpa = handle
+            .set_hpa_metrics("default", "web", &[("cpu", 1.0)])
+            .unwrap();
+        assert_eq!(hpa.status.as_ref().unwrap().desired_replicas, 1);
+        assert_eq!(condition(&hpa, "ScalingLimited").1, "DesiredWithinRange");
+        assert_eq!(replicas(&client).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_set_hpa_metrics_takes_largest_proposal() {
+        let metrics = json!([
+            cpu_utilization(50)[0],
+            {
+                "type": "Pods",
+                "pods": {
+                    "metric": { "name": "requests_per_second" },
+                    "target": { "type": "AverageValue", "averageValue": "500m" },
+                },
+            },
+        ]);
+        let (client, handle) = setup(metrics).await;
+
+        let hpa = handle
+            .set_hpa_metrics(
+                "default",
+                "web",
+                &[("cpu", 50.0), ("requests_per_second", 1.5)],
+            )
+            .unwrap();
+        let status = hpa.status.as_ref().unwrap();
+        assert_eq!(status.desired_replicas, 6);
+        let pods = status.current_metrics.as_ref().unwrap()[1]
+            .pods
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            pods.current.average_value.as_ref().unwrap().0,
+            "1500m".to_string()
+        );
+        assert_eq!(replicas(&client).await, 6);
+    }
+
+    #[tokio::test]
+    async fn test_set_hpa_metrics_without_values_or_target() {
+        let (client, handle) = setup(cpu_utilization(50)).await;
+
+        let hpa = handle.set_hpa_metrics("default", "web", &[]).unwrap();
+        assert_eq!(
+            condition(&hpa, "ScalingActive"),
+            ("False".to_string(), "FailedGetResourceMetric".to_string())
+        );
+        assert_eq!(hpa.status.as_ref().unwrap().desired_replicas, 2);
+        assert_eq!(replicas(&client).await, 2);
+
+        Api::<Deployment>::namespaced(client.clone(), "default")
+            .delete("web", &Default::default())
+            .await
+            .unwrap();
+        let hpa = handle
+            .set_hpa_metrics("default", "web", &[("cpu", 100.0)])
+            .unwrap();
+        assert_eq!(
+            condition(&hpa, "AbleToScale"),
+            ("False".to_string(), "FailedGetScale".to_string())
+        );
+
+        assert!(handle
+            .set_hpa_metrics("default", "missing", &[("cpu", 100.0)])
+            .is_err());
+    }
+}
@@ -1,14 +1,51 @@
 //! Interceptors for customizing client behavior during testing
 
 use crate::client::FakeClient;
-use crate::Result;
+use crate::label_selector;
+use crate::tracker::GVK;
+use crate::{Error, Result};
 use kube::api::{ListParams, PatchParams, PostParams};
+use kube::core::Selector;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+pub use crate::recorder::{Call, CallRecorder};
+pub use crate::request::RequestContext;
+
+/// The request an interceptor is running for
+///
+/// Returns the method, URI, headers, the client's identity and the tags
+/// attached with
+/// [`ClientHandle::with_request_tag`](crate::ClientHandle::with_request_tag),
+/// for interceptors that behave differently per caller. Returns `None` outside
+/// of a request, such as when [`FakeClient`] is called directly.
+///
+/// # Example
+/// ```
+/// use kube_fake_client::interceptor;
+///
+/// let funcs = interceptor::Funcs::new().create(|ctx| {
+///     let request = interceptor::current_request();
+///     if request.is_some_and(|r| r.tags.iter().any(|t| t == "flaky")) {
+///         return Err(kube_fake_client::Error::Internal("injected".to_string()));
+///     }
+///     Ok(None)
+/// });
+/// ```
+pub fn current_request() -> Option<RequestContext> {
+    crate::request::current()
+}
+
 /// Interceptor functions for client operations
 ///
-/// Return `Ok(Some(value))` to override, `Ok(None)` to continue, or `Err(e)` to inject an error.
+/// Return `Ok(Some(value))` to override, `Ok(None)` to continue, or `Err(e)` to inject an error,
+/// such as one built with [`Error::api`](crate::Error::api) for an exact status.
+///
+/// The `after_*` hooks run once a write has succeeded, with the request and the
+/// object returned for it. They can change the response, or return an error to
+/// fail the request, for example to enforce an invariant across a test suite;
+/// the write itself stays stored either way.
 ///
 /// # Example
 /// ```
@@ -24,7 +61,7 @@ use std::sync::Arc;
 ///         Ok(None)
 ///     });
 /// ```
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Funcs {
     /// Intercept Create operations
     pub(crate) create: Option<CreateInterceptor>,
@@ -50,9 +87,20 @@ pub struct Funcs {
     pub(crate) patch_status: Option<PatchStatusInterceptor>,
     /// Intercept Replace Status subresource operations
     pub(crate) replace_status: Option<ReplaceStatusInterceptor>,
+    /// Run after successful Create operations
+    pub(crate) after_create: Option<AfterCreateHook>,
+    /// Run after successful Replace operations, including of the status
+    pub(crate) after_replace: Option<AfterReplaceHook>,
+    /// Run after successful Patch operations, including of the status
+    pub(crate) after_patch: Option<AfterPatchHook>,
+    /// Run after successful Delete operations
+    pub(crate) after_delete: Option<AfterDeleteHook>,
+    /// Handlers of other subresources, by subresource name
+    pub(crate) subresources: HashMap<String, SubresourceInterceptor>,
 }
 
 /// Context passed to Create interceptors
+#[derive(Clone, Copy)]
 pub struct CreateContext<'a> {
     pub client: &'a FakeClient,
     /// The object being created
@@ -64,6 +112,7 @@ pub struct CreateContext<'a> {
 }
 
 /// Context passed to Get interceptors
+#[derive(Clone, Copy)]
 pub struct GetContext<'a> {
     pub client: &'a FakeClient,
     /// Namespace of the object
@@ -73,6 +122,7 @@ pub struct GetContext<'a> {
 }
 
 /// Context passed to Update interceptors
+#[derive(Clone, Copy)]
 pub struct UpdateContext<'a> {
     pub client: &'a FakeClient,
     /// The updated object
@@ -86,6 +136,7 @@ pub struct UpdateContext<'a> {
 }
 
 /// Context passed to Delete interceptors
+#[derive(Clone, Copy)]
 pub struct DeleteContext<'a> {
     pub client: &'a FakeClient,
     /// Namespace of the object
@@ -95,6 +146,7 @@ pub struct DeleteContext<'a> {
 }
 
 /// Context passed to List interceptors
+#[derive(Clone, Copy)]
 pub struct ListContext<'a> {
     pub client: &'a FakeClient,
     pub namespace: Option<&'a str>,
@@ -102,6 +154,7 @@ pub struct ListContext<'a> {
 }
 
 /// Context passed to Patch interceptors
+#[derive(Clone, Copy)]
 pub struct PatchContext<'a> {
     pub client: &'a FakeClient,
     /// The patch data to apply
@@ -123,6 +176,7 @@ pub type ListInterceptor = Arc<dyn Fn(ListContext) -> Result<Option<Vec<Value>>>
 pub type PatchInterceptor = Arc<dyn Fn(PatchContext) -> Result<Option<Value>> + Send + Sync>;
 
 /// Context passed to Replace interceptors
+#[derive(Clone, Copy)]
 pub struct ReplaceContext<'a> {
     pub client: &'a FakeClient,
     /// The replacement object
@@ -137,23 +191,113 @@ pub struct ReplaceContext<'a> {
 
 pub type ReplaceInterceptor = Arc<dyn Fn(ReplaceContext) -> Result<Option<Value>> + Send + Sync>;
 
+/// Context passed to Delete Collection interceptors
+#[derive(Clone, Copy)]
 pub struct DeleteCollectionContext<'a> {
     pub client: &'a FakeClient,
+    /// Kind of the objects being deleted
+    pub gvk: &'a GVK,
+    /// Namespace the objects are deleted from, `None` for all namespaces or a
+    /// cluster-scoped kind
     pub namespace: Option<&'a str>,
+    /// Selectors of the objects to delete
     pub params: &'a ListParams,
 }
 
+impl DeleteCollectionContext<'_> {
+    /// The label selector of the request, parsed; `None` if it has none
+    ///
+    /// Fails with 400 Bad Request if the selector doesn't parse.
+    pub fn label_selector(&self) -> Result<Option<Selector>> {
+        parse_label_selector(self.params)
+    }
+
+    /// The requirements of the request's field selector, in order
+    pub fn field_selector(&self) -> Vec<FieldRequirement> {
+        parse_field_selector(self.params)
+    }
+}
+
 pub type DeleteCollectionInterceptor =
     Arc<dyn Fn(DeleteCollectionContext) -> Result<Option<Vec<Value>>> + Send + Sync>;
 
+/// Context passed to Watch interceptors
+#[derive(Clone, Copy)]
 pub struct WatchContext<'a> {
     pub client: &'a FakeClient,
+    /// Kind of the objects being watched
+    pub gvk: &'a GVK,
+    /// Namespace being watched, `None` for all namespaces or a cluster-scoped kind
     pub namespace: Option<&'a str>,
+    /// Selectors and resourceVersion of the watch
     pub params: &'a ListParams,
 }
 
+impl WatchContext<'_> {
+    /// The label selector of the request, parsed; `None` if it has none
+    ///
+    /// Fails with 400 Bad Request if the selector doesn't parse.
+    pub fn label_selector(&self) -> Result<Option<Selector>> {
+        parse_label_selector(self.params)
+    }
+
+    /// The requirements of the request's field selector, in order
+    pub fn field_selector(&self) -> Vec<FieldRequirement> {
+        parse_field_selector(self.params)
+    }
+}
+
+/// A requirement of a field selector, such as `status.phase!=Running`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRequirement {
+    /// The selected field, such as `status.phase`
+    pub field: String,
+    /// The value the field is compared with
+    pub value: String,
+    /// Whether the field must differ from the value (`!=`) rather than equal it
+    pub negated: bool,
+}
+
+fn parse_label_selector(params: &ListParams) -> Result<Option<Selector>> {
+    params
+        .label_selector
+        .as_deref()
+        .map(|selector| {
+            label_selector::parse_label_selector(selector).map_err(|e| {
+                Error::BadRequest(format!("unable to parse requirement: {selector:?}: {e}"))
+            })
+        })
+        .transpose()
+}
+
+fn parse_field_selector(params: &ListParams) -> Vec<FieldRequirement> {
+    let Some(selector) = params.field_selector.as_deref() else {
+        return Vec::new();
+    };
+    selector
+        .split(',')
+        .filter_map(|requirement| {
+            let (field, value, negated) = match requirement.split_once("!=") {
+                Some((field, value)) => (field, value, true),
+                None => {
+                    let (field, value) = requirement
+                        .split_once("==")
+                        .or_else(|| requirement.split_once('='))?;
+                    (field, value, false)
+                }
+            };
+            Some(FieldRequirement {
+                field: field.trim().to_string(),
+                value: value.trim().to_string(),
+                negated,
+            })
+        })
+        .collect()
+}
+
 pub type WatchInterceptor = Arc<dyn Fn(WatchContext) -> Result<Option<Vec<Value>>> + Send + Sync>;
 
+#[derive(Clone, Copy)]
 pub struct GetStatusContext<'a> {
     pub client: &'a FakeClient,
     /// Namespace of the object
@@ -165,6 +309,7 @@ pub struct GetStatusContext<'a> {
 pub type GetStatusInterceptor =
     Arc<dyn Fn(GetStatusContext) -> Result<Option<Value>> + Send + Sync>;
 
+#[derive(Clone, Copy)]
 pub struct PatchStatusContext<'a> {
     pub client: &'a FakeClient,
     /// The patch data to apply
@@ -180,6 +325,7 @@ pub struct PatchStatusContext<'a> {
 pub type PatchStatusInterceptor =
     Arc<dyn Fn(PatchStatusContext) -> Result<Option<Value>> + Send + Sync>;
 
+#[derive(Clone, Copy)]
 pub struct ReplaceStatusContext<'a> {
     pub client: &'a FakeClient,
     /// The replacement object
@@ -195,12 +341,219 @@ pub struct ReplaceStatusContext<'a> {
 pub type ReplaceStatusInterceptor =
     Arc<dyn Fn(ReplaceStatusContext) -> Result<Option<Value>> + Send + Sync>;
 
+/// Context passed to the handler of a subresource
+#[derive(Clone, Copy)]
+pub struct SubresourceContext<'a> {
+    pub client: &'a FakeClient,
+    /// API verb of the request: `get`, `create`, `update` or `patch`
+    pub verb: &'a str,
+    /// Plural name of the parent resource
+    pub resource: &'a str,
+    /// Name of the subresource
+    pub subresource: &'a str,
+    /// Namespace of the object
+    pub namespace: &'a str,
+    /// Name of the object
+    pub name: &'a str,
+    /// The stored object the subresource belongs to
+    pub object: &'a Value,
+    /// The request body, for writes
+    pub body: Option<&'a Value>,
+}
+
+pub type SubresourceInterceptor =
+    Arc<dyn Fn(SubresourceContext) -> Result<Option<Value>> + Send + Sync>;
+
+/// Context passed to hooks run after a Create
+pub struct AfterCreateContext<'a> {
+    pub client: &'a FakeClient,
+    /// The object in the request
+    pub object: &'a Value,
+    /// Namespace for the object
+    pub namespace: &'a str,
+    /// The created object, as returned to the client
+    pub response: &'a mut Value,
+}
+
+/// Context passed to hooks run after a Replace
+pub struct AfterReplaceContext<'a> {
+    pub client: &'a FakeClient,
+    /// The replacement object in the request
+    pub object: &'a Value,
+    /// Namespace for the object
+    pub namespace: &'a str,
+    /// Name of the object
+    pub name: &'a str,
+    /// Whether this replaced the status subresource
+    pub is_status: bool,
+    /// The updated object, as returned to the client
+    pub response: &'a mut Value,
+}
+
+/// Context passed to hooks run after a Patch
+pub struct AfterPatchContext<'a> {
+    pub client: &'a FakeClient,
+    /// The patch in the request
+    pub patch: &'a Value,
+    /// Namespace of the object
+    pub namespace: &'a str,
+    /// Name of the object
+    pub name: &'a str,
+    /// Whether this patched the status subresource
+    pub is_status: bool,
+    /// The patched object, as returned to the client
+    pub response: &'a mut Value,
+}
+
+/// Context passed to hooks run after a Delete
+pub struct AfterDeleteContext<'a> {
+    pub client: &'a FakeClient,
+    /// Namespace of the object
+    pub namespace: &'a str,
+    /// Name of the object
+    pub name: &'a str,
+    /// The deleted object, or the object marked for deletion, as returned to the
+    /// client
+    pub response: &'a mut Value,
+}
+
+impl AfterCreateContext<'_> {
+    fn reborrow(&mut self) -> AfterCreateContext<'_> {
+        AfterCreateContext {
+            response: self.response,
+            ..*self
+        }
+    }
+}
+
+impl AfterReplaceContext<'_> {
+    fn reborrow(&mut self) -> AfterReplaceContext<'_> {
+        AfterReplaceContext {
+            response: self.response,
+            ..*self
+        }
+    }
+}
+
+impl AfterPatchContext<'_> {
+    fn reborrow(&mut self) -> AfterPatchContext<'_> {
+        AfterPatchContext {
+            response: self.response,
+            ..*self
+        }
+    }
+}
+
+impl AfterDeleteContext<'_> {
+    fn reborrow(&mut self) -> AfterDeleteContext<'_> {
+        AfterDeleteContext {
+            response: self.response,
+            ..*self
+        }
+    }
+}
+
+pub type AfterCreateHook = Arc<dyn Fn(AfterCreateContext) -> Result<()> + Send + Sync>;
+pub type AfterReplaceHook = Arc<dyn Fn(AfterReplaceContext) -> Result<()> + Send + Sync>;
+pub type AfterPatchHook = Arc<dyn Fn(AfterPatchContext) -> Result<()> + Send + Sync>;
+pub type AfterDeleteHook = Arc<dyn Fn(AfterDeleteContext) -> Result<()> + Send + Sync>;
+
+/// Run one interceptor, then the other unless the first overrode the result or
+/// failed
+macro_rules! chain_interceptors {
+    ($first:expr, $second:expr, $context:ident) => {
+        match ($first, $second) {
+            (Some(first), Some(second)) => {
+                Some(Arc::new(move |ctx: $context<'_>| match first(ctx)? {
+                    Some(value) => Ok(Some(value)),
+                    None => second(ctx),
+                }) as Arc<_>)
+            }
+            (first, second) => first.or(second),
+        }
+    };
+}
+
+/// Run one hook, then the other unless the first failed
+macro_rules! chain_hooks {
+    ($first:expr, $second:expr, $context:ident) => {
+        match ($first, $second) {
+            (Some(first), Some(second)) => Some(Arc::new(move |mut ctx: $context<'_>| {
+                first(ctx.reborrow())?;
+                second(ctx)
+            }) as Arc<_>),
+            (first, second) => first.or(second),
+        }
+    };
+}
+
 impl Funcs {
     /// Create a new empty set of interceptors
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Combine with another set of interceptors, running this set's first
+    ///
+    /// For each operation, `next`'s interceptor only runs if this set's returned
+    /// `Ok(None)`, so an override or an injected error here wins. Both `after_*`
+    /// hooks run, this set's first, unless it fails.
+    ///
+    /// # Example
+    /// ```
+    /// use kube_fake_client::interceptor;
+    ///
+    /// let audit_deletes = interceptor::Funcs::new().delete(|ctx| {
+    ///     println!("deleting {}/{}", ctx.namespace, ctx.name);
+    ///     Ok(None)
+    /// });
+    /// let funcs = audit_deletes.merge(interceptor::Funcs::new().get(|_| Ok(None)));
+    /// ```
+    pub fn merge(self, next: Funcs) -> Self {
+        let mut subresources = self.subresources;
+        for (name, second) in next.subresources {
+            let first = subresources.remove(&name);
+            if let Some(chained) = chain_interceptors!(first, Some(second), SubresourceContext) {
+                subresources.insert(name, chained);
+            }
+        }
+        Self {
+            create: chain_interceptors!(self.create, next.create, CreateContext),
+            get: chain_interceptors!(self.get, next.get, GetContext),
+            update: chain_interceptors!(self.update, next.update, UpdateContext),
+            replace: chain_interceptors!(self.replace, next.replace, ReplaceContext),
+            delete: chain_interceptors!(self.delete, next.delete, DeleteContext),
+            delete_collection: chain_interceptors!(
+                self.delete_collection,
+                next.delete_collection,
+                DeleteCollectionContext
+            ),
+            list: chain_interceptors!(self.list, next.list, ListContext),
+            patch: chain_interceptors!(self.patch, next.patch, PatchContext),
+            watch: chain_interceptors!(self.watch, next.watch, WatchContext),
+            get_status: chain_interceptors!(self.get_status, next.get_status, GetStatusContext),
+            patch_status: chain_interceptors!(
+                self.patch_status,
+                next.patch_status,
+                PatchStatusContext
+            ),
+            replace_status: chain_interceptors!(
+                self.replace_status,
+                next.replace_status,
+                ReplaceStatusContext
+            ),
+            after_create: chain_hooks!(self.after_create, next.after_create, AfterCreateContext),
+            after_replace: chain_hooks!(
+                self.after_replace,
+                next.after_replace,
+                AfterReplaceContext
+            ),
+            after_patch: chain_hooks!(self.after_patch, next.after_patch, AfterPatchContext),
+            after_delete: chain_hooks!(self.after_delete, next.after_delete, AfterDeleteContext),
+            subresources,
+        }
+    }
+
     /// Add a Create interceptor
     pub fn create<F>(mut self, f: F) -> Self
     where
@@ -247,6 +600,26 @@ impl Funcs {
     }
 
     /// Add a Delete Collection interceptor
+    ///
+    /// Runs before the objects a `deletecollection` request selects are deleted.
+    /// Returning `Ok(Some(objects))` answers the request as if those objects had
+    /// been deleted, leaving them stored; returning an error rejects it, which
+    /// guards against bulk deletes a test doesn't expect.
+    ///
+    /// # Example
+    /// ```
+    /// use kube_fake_client::{interceptor, Error};
+    ///
+    /// let funcs = interceptor::Funcs::new().delete_collection(|ctx| {
+    ///     if ctx.label_selector()?.is_none() && ctx.field_selector().is_empty() {
+    ///         return Err(Error::Forbidden(format!(
+    ///             "refusing to delete every {} without a selector",
+    ///             ctx.gvk.kind
+    ///         )));
+    ///     }
+    ///     Ok(None)
+    /// });
+    /// ```
     pub fn delete_collection<F>(mut self, f: F) -> Self
     where
         F: Fn(DeleteCollectionContext) -> Result<Option<Vec<Value>>> + Send + Sync + 'static,
@@ -274,6 +647,9 @@ impl Funcs {
     }
 
     /// Add a Watch interceptor
+    ///
+    /// Returning `Ok(Some(objects))` answers the watch with an `ADDED` event for
+    /// each object and ends it; returning an error fails the request.
     pub fn watch<F>(mut self, f: F) -> Self
     where
         F: Fn(WatchContext) -> Result<Option<Vec<Value>>> + Send + Sync + 'static,
@@ -308,4 +684,53 @@ impl Funcs {
         self.replace_status = Some(Arc::new(f));
         self
     }
+
+    /// Add a hook run after each successful Create
+    pub fn after_create<F>(mut self, f: F) -> Self
+    where
+        F: Fn(AfterCreateContext) -> Result<()> + Send + Sync + 'static,
+    {
+        self.after_create = Some(Arc::new(f));
+        self
+    }
+
+    /// Add a hook run after each successful Replace, of the object or its status
+    pub fn after_replace<F>(mut self, f: F) -> Self
+    where
+        F: Fn(AfterReplaceContext) -> Result<()> + Send + Sync + 'static,
+    {
+        self.after_replace = Some(Arc::new(f));
+        self
+    }
+
+    /// Add a hook run after each successful Patch, of the object or its status
+    pub fn after_patch<F>(mut self, f: F) -> Self
+    where
+        F: Fn(AfterPatchContext) -> Result<()> + Send + Sync + 'static,
+    {
+        self.after_patch = Some(Arc::new(f));
+        self
+    }
+
+    /// Add a hook run after each successful Delete of a single object
+    pub fn after_delete<F>(mut self, f: F) -> Self
+    where
+        F: Fn(AfterDeleteContext) -> Result<()> + Send + Sync + 'static,
+    {
+        self.after_delete = Some(Arc::new(f));
+        self
+    }
+
+    /// Add a handler for requests to a subresource, such as `approve`
+    ///
+    /// Returning `Ok(Some(value))` responds with `value`. With `Ok(None)`, a
+    /// `get` returns the parent object and a write fails with 405 Method Not
+    /// Allowed, as no built-in behavior exists for the subresource.
+    pub fn subresource<F>(mut self, name: &str, f: F) -> Self
+    where
+        F: Fn(SubresourceContext) -> Result<Option<Value>> + Send + Sync + 'static,
+    {
+        self.subresources.insert(name.to_string(), Arc::new(f));
+        self
+    }
 }
@@ -11,6 +11,7 @@
 //! - Multiple selectors combined with commas: `key1=value1,key2 in (v2,v3)`
 
 use kube::core::{Expression, Selector, SelectorExt};
+use serde_json::Value;
 use std::collections::{BTreeMap, BTreeSet};
 
 /// Split a selector string by commas, but not inside parentheses
@@ -177,3 +178,22 @@ pub fn matches_label_selector(
     let selector = parse_label_selector(selector)?;
     Ok(selector.matches(labels))
 }
+
+/// Whether a stored object matches a label selector
+///
+/// Objects without labels are matched as having none, so `!key` and `key notin
+/// (...)` select them. Invalid selectors match nothing. Both the HTTP and the
+/// typed list paths filter with this.
+pub(crate) fn matches_object(object: &Value, selector: &str) -> bool {
+    let labels: BTreeMap<String, String> = object
+        .pointer("/metadata/labels")
+        .and_then(Value::as_object)
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    matches_label_selector(&labels, selector).unwrap_or(false)
+}
@@ -0,0 +1,35 @@
+//! Lease helpers for leader election tests
+//!
+//! Leader election treats a Lease as held while `spec.renewTime` plus
+//! `spec.leaseDurationSeconds` lies in the future. Expiring a lease moves its
+//! renew time back so that other candidates may take it over, simulating a
+//! leader that stopped renewing.
+
+use crate::tracker::{ObjectTracker, GVK, GVR};
+use crate::Result;
+use serde_json::{json, Value};
+
+/// Lease duration assumed when the Lease doesn't specify one
+const DEFAULT_LEASE_DURATION_SECONDS: i64 = 15;
+
+fn leases_gvr() -> GVR {
+    GVR::new("coordination.k8s.io", "v1", "leases")
+}
+
+fn lease_gvk() -> GVK {
+    GVK::new("coordination.k8s.io", "v1", "Lease")
+}
+
+/// Move a Lease's renew time back past its duration
+pub(crate) fn expire(tracker: &ObjectTracker, namespace: &str, name: &str) -> Result<Value> {
+    let mut lease = tracker.get(&leases_gvr(), namespace, name)?;
+    let duration = lease
+        .pointer("/spec/leaseDurationSeconds")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(DEFAULT_LEASE_DURATION_SECONDS);
+
+    let expired_at = chrono::Utc::now() - chrono::Duration::seconds(duration + 1);
+    lease["spec"]["renewTime"] =
+        json!(expired_at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true));
+    tracker.update(&leases_gvr(), &lease_gvk(), lease, namespace, false)
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::MicroTime;
+    use kube::api::{Api, PostParams};
+
+    const LEASE_DURATION_SECONDS: i32 = 15;
+
+    /// Minimal leader election step: acquire or renew the lease for `identity`
+    async fn try_acquire(leases: &Api<Lease>, identity: &str) -> bool {
+        let now = chrono::Utc::now();
+        let Some(mut lease) = leases.get_opt("leader").await.unwrap() else {
+            let mut lease = Lease::default();
+            lease.metadata.name = Some("leader".to_string());
+            lease.spec = Some(LeaseSpec {
+                holder_identity: Some(identity.to_string()),
+                lease_duration_seconds: Some(LEASE_DURATION_SECONDS),
+                renew_time: Some(MicroTime(now)),
+                ..Default::default()
+            });
+            return leases.create(&PostParams::default(), &lease).await.is_ok();
+        };
+
+        let spec = lease.spec.get_or_insert_with(Default::default);
+        let held_by_other = spec.holder_identity.as_deref() != Some(identity);
+        let expired = spec
+            .renew_time
+            .as_ref()
+            .is_none_or(|t| t.0 + chrono::Duration::seconds(LEASE_DURATION_SECONDS.into()) < now);
+        if held_by_other && !expired {
+            return false;
+        }
+        if held_by_other {
+            spec.lease_transitions = Some(spec.lease_transitions.unwrap_or(0) + 1);
+        }
+        spec.holder_identity = Some(identity.to_string());
+        spec.renew_time = Some(MicroTime(now));
+
+        match leases
+            .replace("leader", &PostParams::default(), &lease)
+            .await
+        {
+            Ok(_) => true,
+            Err(kube::Error::Api(resp)) if resp.code == 409 => false,
+            Err(e) => panic!("unexpected error: {e:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_leader_election_failover() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let leases: Api<Lease> = Api::namespaced(client, "default");
+
+        assert!(try_acquire(&leases, "a").await);
+        assert!(!try_acquire(&leases, "b").await);
+        assert!(try_acquire(&leases, "a").await);
+
+        // Once the leader stops renewing, another candidate takes over
+        let expired = handle.expire_lease("default", "leader").unwrap();
+        assert_eq!(
+            expired.spec.as_ref().unwrap().holder_identity.as_deref(),
+            Some("a")
+        );
+        assert!(try_acquire(&leases, "b").await);
+
+        let lease = leases.get("leader").await.unwrap();
+        let spec = lease.spec.unwrap();
+        assert_eq!(spec.holder_identity.as_deref(), Some("b"));
+        assert_eq!(spec.lease_transitions, Some(1));
+
+        // The old leader no longer holds the lease
+        assert!(!try_acquire(&leases, "a").await);
+    }
+
+    #[tokio::test]
+    async fn test_stale_lease_update_conflicts() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let leases: Api<Lease> = Api::namespaced(client, "default");
+        assert!(try_acquire(&leases, "a").await);
+
+        let stale = leases.get("leader").await.unwrap();
+        let mut fresh = stale.clone();
+        fresh.spec.as_mut().unwrap().holder_identity = Some("b".to_string());
+        leases
+            .replace("leader", &PostParams::default(), &fresh)
+            .await
+            .unwrap();
+
+        let err = leases
+            .replace("leader", &PostParams::default(), &stale)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 409));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_lease_updates_have_one_winner() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let leases: Api<Lease> = Api::namespaced(client, "default");
+        assert!(try_acquire(&leases, "seed").await);
+
+        for round in 0..20 {
+            let base = leases.get("leader").await.unwrap();
+            let tasks: Vec<_> = (0..8)
+                .map(|i| {
+                    let leases = leases.clone();
+                    let mut lease = base.clone();
+                    lease.spec.as_mut().unwrap().holder_identity =
+                        Some(format!("candidate-{round}-{i}"));
+                    tokio::spawn(async move {
+                        leases
+                            .replace("leader", &PostParams::default(), &lease)
+                            .await
+                            .is_ok()
+                    })
+                })
+                .collect();
+
+            let mut winners = 0;
+            for task in tasks {
+                if task.await.unwrap() {
+                    winners += 1;
+                }
+            }
+            assert_eq!(winners, 1, "round {round}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expire_missing_lease() {
+        let (_client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        assert!(handle.expire_lease("default", "missing").is_err());
+    }
+}
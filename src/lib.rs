@@ -24,34 +24,205 @@
 //! # }
 //! ```
 
+mod access_review;
+mod api_discovery;
+mod audit;
+pub mod budget;
 mod builder;
+pub mod cassette;
+mod chaos;
 mod client;
 mod client_utils;
+mod clock;
+mod cluster;
+pub mod compare;
+mod configmap;
+mod confined;
+pub mod conformance;
+mod controller;
+mod csr;
+mod diff;
 pub mod discovery;
+mod endpoints;
 mod error;
 mod field_selectors;
+mod garbage_collector;
 pub mod gen;
+mod generation;
+mod handle;
+#[cfg(feature = "tokio-runtime")]
+mod harness;
+mod hpa;
 pub mod interceptor;
 pub mod label_selector;
+mod lease;
+mod managed_fields;
+mod manifests;
+mod metrics;
 mod mock_service;
+pub mod naming;
+mod normalize;
+mod owners;
+mod persistence;
+mod pod;
+mod query;
+mod rbac;
+mod recorder;
 pub mod registry;
+mod request;
+mod response_body;
+mod runtime;
+mod scale;
+mod secret;
+mod service;
+mod shard;
+mod snapshot;
+mod stale;
+mod statefulset;
+mod stats;
+pub mod store;
+#[cfg(feature = "tokio-runtime")]
+pub mod stress;
+mod test_env;
+pub mod testing;
+mod token_review;
 mod tracker;
 mod utils;
 pub mod validator;
+pub mod warning;
+mod watch;
 
+#[cfg(test)]
+mod access_review_test;
+#[cfg(test)]
+mod api_discovery_test;
+#[cfg(test)]
+mod audit_test;
+#[cfg(test)]
+mod budget_test;
 #[cfg(test)]
 mod builder_test;
 #[cfg(test)]
+mod cassette_test;
+#[cfg(test)]
+mod chaos_test;
+#[cfg(test)]
 mod client_test;
 #[cfg(test)]
+mod cluster_test;
+#[cfg(test)]
+mod compare_test;
+#[cfg(test)]
+mod configmap_test;
+#[cfg(test)]
+mod confined_test;
+#[cfg(test)]
+mod conformance_test;
+#[cfg(test)]
+mod controller_test;
+#[cfg(test)]
+mod csr_test;
+#[cfg(test)]
+mod diff_test;
+#[cfg(test)]
+mod endpoints_test;
+#[cfg(test)]
+mod entry_test;
+#[cfg(test)]
+mod garbage_collector_test;
+#[cfg(test)]
+mod generation_test;
+#[cfg(test)]
+mod handle_test;
+#[cfg(all(test, feature = "tokio-runtime"))]
+mod harness_test;
+#[cfg(test)]
+mod hpa_test;
+#[cfg(test)]
 mod label_selector_test;
 #[cfg(test)]
+mod lease_test;
+#[cfg(test)]
+mod managed_fields_test;
+#[cfg(test)]
+mod manifests_test;
+#[cfg(test)]
+mod metrics_test;
+#[cfg(test)]
 mod mock_service_test;
 #[cfg(test)]
+mod naming_test;
+#[cfg(test)]
+mod normalize_test;
+#[cfg(test)]
+mod persistence_test;
+#[cfg(test)]
+mod pod_test;
+#[cfg(test)]
+mod query_test;
+#[cfg(test)]
+mod rbac_test;
+#[cfg(test)]
+mod recorder_test;
+#[cfg(test)]
+mod request_test;
+#[cfg(test)]
+mod response_body_test;
+#[cfg(test)]
+mod runtime_test;
+#[cfg(test)]
+mod scale_test;
+#[cfg(test)]
+mod secret_test;
+#[cfg(test)]
+mod service_test;
+#[cfg(test)]
+mod shard_test;
+#[cfg(test)]
+mod stale_test;
+#[cfg(test)]
+mod statefulset_test;
+#[cfg(test)]
+mod stats_test;
+#[cfg(test)]
+mod store_test;
+#[cfg(all(test, feature = "tokio-runtime"))]
+mod stress_test;
+#[cfg(test)]
+mod subresource_test;
+#[cfg(test)]
+mod test_env_test;
+#[cfg(test)]
+mod test_fixtures;
+#[cfg(test)]
+mod testing_test;
+#[cfg(test)]
+mod token_review_test;
+#[cfg(test)]
 mod tracker_test;
 #[cfg(test)]
 mod utils_test;
+#[cfg(test)]
+mod warning_test;
+#[cfg(test)]
+mod watch_test;
 
-pub use builder::ClientBuilder;
+pub use access_review::AccessReviewHandler;
+pub use audit::{AuditEntry, ObjectChange};
+pub use builder::{ClientBuilder, InitialObjectMode, LayeredService};
+pub use chaos::ChaosConfig;
+pub use client::FakeClient;
+pub use cluster::{Cluster, Clusters};
+pub use csr::CsrSigner;
 pub use error::{Error, Result};
+pub use handle::ClientHandle;
 pub use kube::Client;
+pub use manifests::AppliedManifests;
+pub use metrics::{Histogram, Metrics, RequestLabels};
+pub use mock_service::MockService;
+pub use normalize::normalize_object;
+pub use snapshot::Snapshot;
+pub use stats::{ResourceStats, TrackerStats};
+pub use test_env::{TestEnv, TestEnvBuilder};
+pub use token_review::TokenReviewHandler;
+pub use tracker::{ListOrder, GVK, GVR};
@@ -0,0 +1,296 @@
+//! Field ownership tracking
+//!
+//! With [`ClientBuilder::with_return_managed_fields`](crate::ClientBuilder::with_return_managed_fields),
+//! writes record which manager set which fields in `metadata.managedFields`,
+//! in the API server's `FieldsV1` format. The tracking is a simplification of
+//! the API server's:
+//!
+//! - An update takes ownership of the fields it changes, and keeps the fields
+//!   the same manager set earlier.
+//! - A server-side apply owns exactly the fields in the applied configuration.
+//! - Fields that change or disappear are taken away from every other manager;
+//!   managers left with no fields are dropped.
+//! - Lists of objects with a `name`, `uid`, `type`, `port` or `containerPort`
+//!   key are tracked per item, lists of strings under `metadata` per value, and
+//!   other lists as a whole.
+//!
+//! Apply conflicts are not detected: applying a field another manager owns
+//! behaves like a forced apply.
+
+use crate::clock;
+use chrono::SecondsFormat;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Manager recorded for writes without a `fieldManager` parameter
+pub(crate) const DEFAULT_MANAGER: &str = "unknown";
+
+/// Keys that identify the items of a list, tried in order
+const LIST_KEYS: &[&str] = &["name", "uid", "type", "containerPort", "port"];
+
+/// Metadata fields owned by managers; the rest are set by the server
+const OWNED_METADATA: &[&str] = &["annotations", "finalizers", "labels", "ownerReferences"];
+
+/// Kind of write recorded in a managedFields entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    Apply,
+    Update,
+}
+
+impl Operation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Operation::Apply => "Apply",
+            Operation::Update => "Update",
+        }
+    }
+}
+
+/// A write whose field ownership is being recorded
+pub(crate) struct Write<'a> {
+    pub(crate) manager: &'a str,
+    pub(crate) operation: Operation,
+    /// `status` for writes to the status subresource
+    pub(crate) subresource: Option<&'a str>,
+    /// The applied configuration, for [`Operation::Apply`]
+    pub(crate) applied: Option<&'a Value>,
+}
+
+/// Path of segments such as `f:spec` or `k:{"name":"web"}` to an owned field
+type FieldPath = Vec<String>;
+
+/// Update `metadata.managedFields` of an object about to be stored
+///
+/// `existing` is the stored object the write replaces, if any; its entries are
+/// the starting point, so entries sent back by the client are ignored. Writes
+/// to the main resource don't own status fields if the resource has a status
+/// subresource.
+pub(crate) fn record(
+    existing: Option<&Value>,
+    object: &mut Value,
+    write: &Write<'_>,
+    has_status_subresource: bool,
+) {
+    let scope = Scope {
+        subresource: write.subresource,
+        has_status_subresource,
+    };
+    let previous = existing
+        .map(|existing| leaves(existing, scope))
+        .unwrap_or_default();
+    let current = leaves(object, scope);
+    let changed: BTreeSet<&FieldPath> = current
+        .iter()
+        .filter(|(path, value)| previous.get(*path) != Some(*value))
+        .map(|(path, _)| path)
+        .collect();
+    let removed: BTreeSet<&FieldPath> = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .collect();
+
+    let mut entries: Vec<Value> = existing
+        .and_then(|existing| existing.pointer("/metadata/managedFields"))
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let is_own = |entry: &Value| {
+        entry["manager"] == write.manager
+            && entry["operation"] == write.operation.as_str()
+            && entry["subresource"].as_str() == write.subresource
+    };
+
+    let owned: BTreeSet<FieldPath> = match write.operation {
+        Operation::Apply => write
+            .applied
+            .map(|applied| leaves(applied, scope))
+            .unwrap_or_default()
+            .into_keys()
+            .filter(|path| current.contains_key(path))
+            .collect(),
+        Operation::Update => entries
+            .iter()
+            .filter(|entry| is_own(entry))
+            .flat_map(|entry| paths(&entry["fieldsV1"]))
+            .filter(|path| current.contains_key(path))
+            .chain(changed.iter().map(|path| (*path).clone()))
+            .collect(),
+    };
+
+    entries.retain_mut(|entry| {
+        if is_own(entry) {
+            return false;
+        }
+        let kept: BTreeSet<FieldPath> = paths(&entry["fieldsV1"])
+            .into_iter()
+            .filter(|path| !changed.contains(path) && !removed.contains(path))
+            .collect();
+        entry["fieldsV1"] = tree(&kept);
+        !kept.is_empty()
+    });
+    if !owned.is_empty() {
+        let mut entry = json!({
+            "manager": write.manager,
+            "operation": write.operation.as_str(),
+            "apiVersion": object["apiVersion"],
+            "time": clock::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            "fieldsType": "FieldsV1",
+            "fieldsV1": tree(&owned),
+        });
+        if let Some(subresource) = write.subresource {
+            entry["subresource"] = json!(subresource);
+        }
+        entries.push(entry);
+    }
+
+    if let Some(metadata) = object.get_mut("metadata").and_then(Value::as_object_mut) {
+        if entries.is_empty() {
+            metadata.remove("managedFields");
+        } else {
+            metadata.insert("managedFields".to_string(), Value::Array(entries));
+        }
+    }
+}
+
+/// Which top-level fields a write can own
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    subresource: Option<&'a str>,
+    has_status_subresource: bool,
+}
+
+/// The fields of an object a write can own, with their values
+fn leaves<'v>(object: &'v Value, scope: Scope<'_>) -> BTreeMap<FieldPath, &'v Value> {
+    let mut out = BTreeMap::new();
+    let Some(fields) = object.as_object() else {
+        return out;
+    };
+    for (key, value) in fields {
+        let in_scope = match (scope.subresource, key.as_str()) {
+            (_, "apiVersion" | "kind") => false,
+            (Some(_), field) => field == "status",
+            (None, "status") => !scope.has_status_subresource,
+            (None, _) => true,
+        };
+        if !in_scope {
+            continue;
+        }
+        if key == "metadata" {
+            let Some(metadata) = value.as_object() else {
+                continue;
+            };
+            for field in OWNED_METADATA {
+                if let Some(value) = metadata.get(*field) {
+                    let path = vec!["f:metadata".to_string(), format!("f:{field}")];
+                    collect(value, path, true, &mut out);
+                }
+            }
+        } else {
+            collect(value, vec![format!("f:{key}")], false, &mut out);
+        }
+    }
+    out
+}
+
+fn collect<'v>(
+    value: &'v Value,
+    path: FieldPath,
+    in_metadata: bool,
+    out: &mut BTreeMap<FieldPath, &'v Value>,
+) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (key, value) in fields {
+                let mut path = path.clone();
+                path.push(format!("f:{key}"));
+                collect(value, path, in_metadata, out);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            if let Some(key) = list_key(items) {
+                for item in items {
+                    let mut path = path.clone();
+                    path.push(format!("k:{}", json!({ key: item[key] })));
+                    collect(item, path, in_metadata, out);
+                }
+            } else if in_metadata && items.iter().all(Value::is_string) {
+                for item in items {
+                    let mut path = path.clone();
+                    path.push(format!("v:{item}"));
+                    out.insert(path, item);
+                }
+            } else {
+                out.insert(path, value);
+            }
+        }
+        _ => {
+            out.insert(path, value);
+        }
+    }
+}
+
+/// The key identifying every item of a list of objects, if there is one
+fn list_key(items: &[Value]) -> Option<&'static str> {
+    LIST_KEYS.iter().copied().find(|key| {
+        let values: Vec<&Value> = items
+            .iter()
+            .filter_map(|item| item.as_object()?.get(*key))
+            .filter(|value| !value.is_null())
+            .collect();
+        let unique: BTreeSet<String> = values.iter().map(|value| value.to_string()).collect();
+        values.len() == items.len() && unique.len() == items.len()
+    })
+}
+
+/// Build the `FieldsV1` tree of a set of field paths
+fn tree(paths: &BTreeSet<FieldPath>) -> Value {
+    let mut root = Map::new();
+    for path in paths {
+        let mut node = &mut root;
+        for segment in path {
+            let child = node
+                .entry(segment.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if segment.starts_with("k:") {
+                if let Some(item) = child.as_object_mut() {
+                    item.entry(".").or_insert_with(|| Value::Object(Map::new()));
+                }
+            }
+            node = child.as_object_mut().expect("fieldsV1 nodes are objects");
+        }
+    }
+    Value::Object(root)
+}
+
+/// The field paths in a `FieldsV1` tree
+fn paths(tree: &Value) -> BTreeSet<FieldPath> {
+    fn walk(node: &Map<String, Value>, path: &mut FieldPath, out: &mut BTreeSet<FieldPath>) {
+        let mut children = node
+            .iter()
+            .filter(|(segment, _)| *segment != ".")
+            .peekable();
+        if children.peek().is_none() {
+            if !path.is_empty() {
+                out.insert(path.clone());
+            }
+            return;
+        }
+        for (segment, child) in children {
+            path.push(segment.clone());
+            match child.as_object() {
+                Some(child) => walk(child, path, out),
+                None => {
+                    out.insert(path.clone());
+                }
+            }
+            path.pop();
+        }
+    }
+
+    let mut out = BTreeSet::new();
+    if let Some(root) = tree.as_object() {
+        walk(root, &mut Vec::new(), &mut out);
+    }
+    out
+}
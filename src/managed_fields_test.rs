@@ -0,0 +1,309 @@
+#[cfg(test)]
+mod tests {
+    use crate::{interceptor, ClientBuilder};
+    use k8s_openapi::api::core::v1::{ConfigMap, Pod};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ManagedFieldsEntry;
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use kube::Client;
+    use parking_lot::Mutex;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    async fn client() -> Client {
+        ClientBuilder::new()
+            .with_return_managed_fields()
+            .build()
+            .await
+            .unwrap()
+    }
+
+    fn post_params(manager: &str) -> PostParams {
+        PostParams {
+            field_manager: Some(manager.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn patch_params(manager: &str) -> PatchParams {
+        PatchParams {
+            field_manager: Some(manager.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn config_map() -> ConfigMap {
+        serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "settings", "labels": { "app": "web" } },
+            "data": { "mode": "fast" },
+        }))
+        .unwrap()
+    }
+
+    /// The entry of a manager, with its FieldsV1 set
+    fn entry<'a>(
+        entries: &'a [ManagedFieldsEntry],
+        manager: &str,
+    ) -> (&'a ManagedFieldsEntry, Value) {
+        let entry = entries
+            .iter()
+            .find(|entry| entry.manager.as_deref() == Some(manager))
+            .unwrap_or_else(|| panic!("no managedFields entry for {manager}"));
+        let fields = serde_json::to_value(entry.fields_v1.as_ref().unwrap()).unwrap();
+        (entry, fields)
+    }
+
+    #[tokio::test]
+    async fn test_update_ownership() {
+        let api: Api<ConfigMap> = Api::default_namespaced(client().await);
+
+        let created = api
+            .create(&post_params("creator"), &config_map())
+            .await
+            .unwrap();
+        let entries = created.metadata.managed_fields.unwrap();
+        assert_eq!(entries.len(), 1);
+        let (creator, fields) = entry(&entries, "creator");
+        assert_eq!(creator.operation.as_deref(), Some("Update"));
+        assert_eq!(creator.api_version.as_deref(), Some("v1"));
+        assert_eq!(creator.fields_type.as_deref(), Some("FieldsV1"));
+        assert!(creator.time.is_some());
+        assert_eq!(
+            fields,
+            json!({
+                "f:data": { "f:mode": {} },
+                "f:metadata": { "f:labels": { "f:app": {} } },
+            })
+        );
+
+        // Another manager changing a field takes it over
+        let patched = api
+            .patch(
+                "settings",
+                &patch_params("tuner"),
+                &Patch::Merge(json!({ "data": { "mode": "slow", "level": "3" } })),
+            )
+            .await
+            .unwrap();
+        let entries = patched.metadata.managed_fields.clone().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entry(&entries, "creator").1,
+            json!({ "f:metadata": { "f:labels": { "f:app": {} } } })
+        );
+        assert_eq!(
+            entry(&entries, "tuner").1,
+            json!({ "f:data": { "f:level": {}, "f:mode": {} } })
+        );
+
+        // A manager that loses all its fields is dropped, and a manager keeps
+        // the fields it set earlier
+        let mut replaced = patched.clone();
+        replaced.metadata.labels = None;
+        let replaced = api
+            .replace("settings", &post_params("tuner"), &replaced)
+            .await
+            .unwrap();
+        let entries = replaced.metadata.managed_fields.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entry(&entries, "tuner").1,
+            json!({ "f:data": { "f:level": {}, "f:mode": {} } })
+        );
+
+        // Writes without a field manager are recorded under "unknown"
+        let patched = api
+            .patch(
+                "settings",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "data": { "extra": "1" } })),
+            )
+            .await
+            .unwrap();
+        let entries = patched.metadata.managed_fields.unwrap();
+        assert_eq!(
+            entry(&entries, "unknown").1,
+            json!({ "f:data": { "f:extra": {} } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_ownership() {
+        let api: Api<ConfigMap> = Api::default_namespaced(client().await);
+        api.create(&post_params("creator"), &config_map())
+            .await
+            .unwrap();
+
+        let applied = api
+            .patch(
+                "settings",
+                &PatchParams::apply("applier"),
+                &Patch::Apply(json!({
+                    "apiVersion": "v1",
+                    "kind": "ConfigMap",
+                    "metadata": { "name": "settings" },
+                    "data": { "mode": "fast", "replicas": "2" },
+                })),
+            )
+            .await
+            .unwrap();
+        let entries = applied.metadata.managed_fields.unwrap();
+        let (applier, fields) = entry(&entries, "applier");
+        assert_eq!(applier.operation.as_deref(), Some("Apply"));
+        assert_eq!(
+            fields,
+            json!({ "f:data": { "f:mode": {}, "f:replicas": {} } })
+        );
+        // Applying an unchanged value shares ownership of it
+        assert_eq!(
+            entry(&entries, "creator").1,
+            json!({
+                "f:data": { "f:mode": {} },
+                "f:metadata": { "f:labels": { "f:app": {} } },
+            })
+        );
+
+        // Applying again replaces the applied set
+        let applied = api
+            .patch(
+                "settings",
+                &PatchParams::apply("applier"),
+                &Patch::Apply(json!({
+                    "apiVersion": "v1",
+                    "kind": "ConfigMap",
+                    "metadata": { "name": "settings" },
+                    "data": { "replicas": "3" },
+                })),
+            )
+            .await
+            .unwrap();
+        let entries = applied.metadata.managed_fields.unwrap();
+        assert_eq!(
+            entry(&entries, "applier").1,
+            json!({ "f:data": { "f:replicas": {} } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_and_list_ownership() {
+        let api: Api<Pod> = Api::default_namespaced(client().await);
+        let pod: Pod = serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "name": "web", "finalizers": ["example.com/cleanup"] },
+            "spec": { "containers": [{ "name": "app", "image": "nginx" }] },
+        }))
+        .unwrap();
+        let created = api.create(&post_params("deployer"), &pod).await.unwrap();
+        let entries = created.metadata.managed_fields.unwrap();
+        assert_eq!(
+            entry(&entries, "deployer").1,
+            json!({
+                "f:metadata": { "f:finalizers": { "v:\"example.com/cleanup\"": {} } },
+                "f:spec": {
+                    "f:containers": {
+                        "k:{\"name\":\"app\"}": { ".": {}, "f:image": {}, "f:name": {} },
+                    },
+                },
+            })
+        );
+
+        let patched = api
+            .patch_status(
+                "web",
+                &patch_params("kubelet"),
+                &Patch::Merge(json!({ "status": { "phase": "Running" } })),
+            )
+            .await
+            .unwrap();
+        let entries = patched.metadata.managed_fields.unwrap();
+        assert_eq!(entries.len(), 2);
+        let (kubelet, fields) = entry(&entries, "kubelet");
+        assert_eq!(kubelet.subresource.as_deref(), Some("status"));
+        assert_eq!(fields, json!({ "f:status": { "f:phase": {} } }));
+        assert!(entry(&entries, "deployer").0.subresource.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_not_recorded_by_default() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        let created = api
+            .create(&post_params("creator"), &config_map())
+            .await
+            .unwrap();
+        assert!(created.metadata.managed_fields.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_field_manager_recorded_per_request() {
+        let seen: Arc<Mutex<Vec<Option<String>>>> = Arc::default();
+        let recorded = Arc::clone(&seen);
+        let (client, handle) = ClientBuilder::new()
+            .with_interceptor_funcs(interceptor::Funcs::new().create(move |ctx| {
+                recorded.lock().push(ctx.params.field_manager.clone());
+                Ok(None)
+            }))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+
+        api.create(&post_params("operator"), &config_map())
+            .await
+            .unwrap();
+        api.patch(
+            "settings",
+            &patch_params("operator"),
+            &Patch::Merge(json!({ "data": { "mode": "slow" } })),
+        )
+        .await
+        .unwrap();
+        api.get("settings").await.unwrap();
+        assert_eq!(*seen.lock(), [Some("operator".to_string())]);
+        handle.assert_field_manager("operator");
+
+        api.patch(
+            "settings",
+            &PatchParams::default(),
+            &Patch::Merge(json!({ "data": { "mode": "fast" } })),
+        )
+        .await
+        .unwrap();
+        let managers: Vec<Option<String>> = handle
+            .audit_entries()
+            .into_iter()
+            .map(|entry| entry.field_manager)
+            .collect();
+        assert_eq!(
+            managers,
+            [
+                Some("operator".to_string()),
+                Some("operator".to_string()),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = r#"expected every write to use field manager "operator", got <none>, intruder"#
+    )]
+    async fn test_assert_field_manager_lists_other_managers() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        api.create(&post_params("intruder"), &config_map())
+            .await
+            .unwrap();
+        api.patch(
+            "settings",
+            &PatchParams::default(),
+            &Patch::Merge(json!({ "data": { "mode": "slow" } })),
+        )
+        .await
+        .unwrap();
+        handle.assert_field_manager("operator");
+    }
+}
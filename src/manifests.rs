@@ -0,0 +1,223 @@
+//! Applying manifest bundles
+//!
+//! Operators that reconcile bundles of manifests are usually tested end to end
+//! against a GitOps sync: apply a set of YAML documents, let the operator react,
+//! apply the next revision. [`ClientHandle::apply_manifests`] emulates that sync
+//! with server-side apply semantics: objects that don't exist are created and
+//! existing ones have the manifest merged into them. Every applied object is
+//! labelled with its field manager, so that a later sync with pruning deletes the
+//! objects that have since been dropped from the bundle, like
+//! `kubectl apply --prune`.
+//!
+//! [`ClientHandle::apply_manifests`]: crate::ClientHandle::apply_manifests
+
+use crate::client::FakeClient;
+use crate::client_utils::extract_gvk;
+use crate::discovery::Discovery;
+use crate::tracker::{GVK, GVR};
+use crate::{Error, Result};
+use kube::api::DynamicObject;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Label recording the field manager that applied an object
+pub(crate) const APPLIED_BY_LABEL: &str = "kube-fake-client.io/applied-by";
+
+/// Objects written by [`ClientHandle::apply_manifests`](crate::ClientHandle::apply_manifests)
+#[derive(Debug, Clone, Default)]
+pub struct AppliedManifests {
+    /// The applied objects as stored, in manifest order
+    pub applied: Vec<DynamicObject>,
+    /// Objects of an earlier sync that were deleted because the manifests no
+    /// longer contain them
+    pub pruned: Vec<DynamicObject>,
+}
+
+/// A manifest document with the resource it is stored under
+struct Manifest {
+    object: Value,
+    gvk: GVK,
+    gvr: GVR,
+    namespace: String,
+    name: String,
+}
+
+/// Apply every document of a multi-document YAML, then delete the objects an
+/// earlier sync by the same field manager applied but these manifests no longer
+/// contain if `prune` is set
+pub(crate) fn apply(
+    client: &FakeClient,
+    yaml: &str,
+    field_manager: &str,
+    prune: bool,
+) -> Result<AppliedManifests> {
+    // Reject a broken bundle before writing any of it
+    let manifests = parse(client, yaml, field_manager)?;
+
+    let mut result = AppliedManifests::default();
+    let mut applied = HashSet::new();
+    for manifest in manifests {
+        applied.insert((
+            manifest.gvr.clone(),
+            manifest.namespace.clone(),
+            manifest.name.clone(),
+        ));
+        let object = apply_one(client, manifest)?;
+        result.applied.push(serde_json::from_value(object)?);
+    }
+
+    if prune {
+        for (gvr, object) in client.tracker.list_all_shared() {
+            if object
+                .pointer("/metadata/labels")
+                .and_then(|labels| labels.get(APPLIED_BY_LABEL))
+                .and_then(Value::as_str)
+                != Some(field_manager)
+            {
+                continue;
+            }
+            let namespace = object
+                .pointer("/metadata/namespace")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let name = object
+                .pointer("/metadata/name")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if applied.contains(&(gvr.clone(), namespace.to_string(), name.to_string())) {
+                continue;
+            }
+            let deleted = if gvr == crate::pod::pods_gvr() {
+                crate::pod::delete(&client.tracker, namespace, name, None)?
+            } else {
+                client.tracker.delete(&gvr, namespace, name)?
+            };
+            result.pruned.push(serde_json::from_value(deleted)?);
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse(client: &FakeClient, yaml: &str, field_manager: &str) -> Result<Vec<Manifest>> {
+    let mut manifests = Vec::new();
+    let mut seen = HashSet::new();
+    for (index, document) in serde_yaml::Deserializer::from_str(yaml).enumerate() {
+        let describe = || format!("document {}", index + 1);
+        let mut object = Value::deserialize(document)
+            .map_err(|e| Error::InvalidRequest(format!("Failed to parse YAML: {e}")))?;
+        // Empty documents, such as after a trailing `---`
+        if object.is_null() {
+            continue;
+        }
+        let gvk = extract_gvk(&object).map_err(|_| {
+            Error::InvalidRequest(format!("{} has no apiVersion or kind", describe()))
+        })?;
+        let gvr = Discovery::gvk_to_gvr_with_registry(&gvk, &client.registry).ok_or_else(|| {
+            Error::ResourceNotRegistered {
+                group: gvk.group.clone(),
+                version: gvk.version.clone(),
+                resource: format!("{} (kind)", gvk.kind),
+            }
+        })?;
+        let name = object
+            .pointer("/metadata/name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| Error::InvalidRequest(format!("{} has no metadata.name", describe())))?;
+
+        let namespaced = Discovery::is_namespaced_with_registry(&gvk, &client.registry);
+        let metadata = object["metadata"]
+            .as_object_mut()
+            .ok_or_else(|| Error::InvalidRequest(format!("{} has no metadata", describe())))?;
+        // Cluster-scoped objects are stored without a namespace
+        let namespace = if namespaced == Some(false) {
+            metadata.remove("namespace");
+            String::new()
+        } else {
+            let namespace = metadata
+                .get("namespace")
+                .and_then(Value::as_str)
+                .unwrap_or("default")
+                .to_string();
+            metadata.insert("namespace".to_string(), Value::from(namespace.clone()));
+            namespace
+        };
+        // Like server-side apply, manifests don't carry the version they were
+        // written against
+        metadata.remove("resourceVersion");
+        metadata
+            .entry("labels")
+            .or_insert_with(|| Value::Object(Default::default()))
+            .as_object_mut()
+            .ok_or_else(|| Error::InvalidRequest(format!("{} has invalid labels", describe())))?
+            .insert(APPLIED_BY_LABEL.to_string(), Value::from(field_manager));
+
+        if !seen.insert((gvr.clone(), namespace.clone(), name.clone())) {
+            return Err(Error::InvalidRequest(format!(
+                "{} applies {} {name} more than once",
+                describe(),
+                gvk.kind
+            )));
+        }
+        manifests.push(Manifest {
+            object,
+            gvk,
+            gvr,
+            namespace,
+            name,
+        });
+    }
+    Ok(manifests)
+}
+
+/// Create the object, or merge the manifest into the existing one
+fn apply_one(client: &FakeClient, manifest: Manifest) -> Result<Value> {
+    let Manifest {
+        object,
+        gvk,
+        gvr,
+        namespace,
+        name,
+    } = manifest;
+    loop {
+        let existing = match client.tracker.get(&gvr, &namespace, &name) {
+            Ok(existing) => existing,
+            Err(Error::NotFound { .. }) => {
+                let mut object = object.clone();
+                let reservation = client.prepare_for_create(&gvk, &namespace, &mut object)?;
+                client.admit(&gvk, &mut object)?;
+                match client.tracker.create(&gvr, object, &namespace) {
+                    // Created concurrently, so apply to that object instead
+                    Err(Error::AlreadyExists { .. }) => continue,
+                    result => {
+                        if result.is_ok() {
+                            reservation.commit();
+                        }
+                        return result;
+                    }
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut updated = existing.clone();
+        json_patch::merge(&mut updated, &object);
+        let reservation = client.prepare_for_update(&gvk, &namespace, &existing, &mut updated)?;
+        client.validate_immutable_fields(&gvk, &existing, &updated)?;
+        client.admit(&gvk, &mut updated)?;
+        match client
+            .tracker
+            .update(&gvr, &gvk, updated, &namespace, false)
+        {
+            Err(Error::Conflict(_)) => continue,
+            result => {
+                if result.is_ok() {
+                    reservation.commit();
+                }
+                return result;
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use crate::{ClientBuilder, Error};
+    use k8s_openapi::api::core::v1::{ConfigMap, Service};
+    use kube::api::{Api, PostParams};
+
+    const REVISION_1: &str = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: settings
+data:
+  mode: fast
+---
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: legacy
+  namespace: apps
+data:
+  enabled: "true"
+---
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: apps
+---
+"#;
+
+    const REVISION_2: &str = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: settings
+data:
+  mode: safe
+---
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: apps
+"#;
+
+    #[tokio::test]
+    async fn test_apply_manifests_creates_and_updates() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let config_maps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+
+        let applied = handle.apply_manifests(REVISION_1, "gitops").unwrap();
+        let names: Vec<_> = applied
+            .applied
+            .iter()
+            .map(|object| object.metadata.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, ["settings", "legacy", "apps"]);
+        assert!(applied.pruned.is_empty());
+
+        let settings = config_maps.get("settings").await.unwrap();
+        assert_eq!(settings.data.unwrap()["mode"], "fast");
+        assert_eq!(
+            settings.metadata.labels.unwrap()["kube-fake-client.io/applied-by"],
+            "gitops"
+        );
+        // Cluster-scoped objects are not put in the default namespace
+        assert_eq!(applied.applied[2].metadata.namespace, None);
+
+        // A field added by someone else survives the next apply
+        let mut edited = config_maps.get("settings").await.unwrap();
+        edited
+            .data
+            .get_or_insert_with(Default::default)
+            .insert("owner".to_string(), "operator".to_string());
+        config_maps
+            .replace("settings", &PostParams::default(), &edited)
+            .await
+            .unwrap();
+
+        handle.apply_manifests(REVISION_2, "gitops").unwrap();
+        let data = config_maps.get("settings").await.unwrap().data.unwrap();
+        assert_eq!(data["mode"], "safe");
+        assert_eq!(data["owner"], "operator");
+
+        // Without pruning, objects dropped from the bundle are kept
+        let legacy: Api<ConfigMap> = Api::namespaced(client, "apps");
+        assert!(legacy.get_opt("legacy").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_apply_manifests_with_prune() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let config_maps: Api<ConfigMap> = Api::namespaced(client.clone(), "apps");
+
+        handle
+            .apply_manifests_with_prune(REVISION_1, "gitops")
+            .unwrap();
+        handle
+            .apply_manifests(
+                "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: other\n  namespace: apps\n",
+                "other-sync",
+            )
+            .unwrap();
+        let mut unmanaged = ConfigMap::default();
+        unmanaged.metadata.name = Some("unmanaged".to_string());
+        config_maps
+            .create(&PostParams::default(), &unmanaged)
+            .await
+            .unwrap();
+
+        let synced = handle
+            .apply_manifests_with_prune(REVISION_2, "gitops")
+            .unwrap();
+        let pruned: Vec<_> = synced
+            .pruned
+            .iter()
+            .map(|object| object.metadata.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(pruned, ["legacy"]);
+
+        assert!(config_maps.get_opt("legacy").await.unwrap().is_none());
+        assert!(config_maps.get_opt("other").await.unwrap().is_some());
+        assert!(config_maps.get_opt("unmanaged").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_apply_manifests_rejects_invalid_bundle() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+
+        let missing_name = "apiVersion: v1\nkind: Service\nmetadata:\n  name: web\n---\napiVersion: v1\nkind: Service\nmetadata: {}\n";
+        let err = handle.apply_manifests(missing_name, "gitops").unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest(msg) if msg == "document 2 has no metadata.name"),
+            "{err}"
+        );
+
+        let unknown_kind = "apiVersion: example.com/v1\nkind: Widget\nmetadata:\n  name: w\n";
+        let err = handle.apply_manifests(unknown_kind, "gitops").unwrap_err();
+        assert!(matches!(err, Error::ResourceNotRegistered { .. }), "{err}");
+
+        // Nothing was applied from the rejected bundles
+        let services: Api<Service> = Api::namespaced(client, "default");
+        assert!(services.get_opt("web").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,233 @@
+//! Metrics of fake API server usage
+//!
+//! Counters and latency histograms in the shape of the API server's own
+//! `apiserver_request_total` and `apiserver_request_duration_seconds`, kept for
+//! every request the fake serves. Unlike the audit log they only hold numbers, so
+//! they stay cheap over long performance tests. Read them with
+//! [`ClientHandle::metrics`](crate::ClientHandle::metrics), or scrape `GET /metrics`
+//! through the client for the Prometheus text format.
+
+use crate::budget::Verb;
+use kube::Resource;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the latency histogram buckets
+const BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0,
+];
+
+/// What a request was counted under
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RequestLabels {
+    /// API verb, or the lowercased HTTP method for non-resource requests
+    pub verb: String,
+    /// API group, empty for the core group
+    pub group: String,
+    /// API version, empty for non-resource requests
+    pub version: String,
+    /// Resource, empty for non-resource requests
+    pub resource: String,
+    /// Subresource, empty if none
+    pub subresource: String,
+    /// HTTP status code of the response
+    pub code: u16,
+}
+
+/// Distribution of request latencies
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Histogram {
+    /// Observations per bucket of [`BUCKETS`], not cumulative
+    counts: [u64; BUCKETS.len()],
+    count: u64,
+    sum: Duration,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(bucket) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.counts[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += duration;
+    }
+
+    /// Number of requests observed
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Total latency of the requests observed
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// Cumulative counts: requests that took at most each upper bound, in seconds
+    ///
+    /// Requests slower than the last bound are only included in [`count`](Self::count).
+    pub fn buckets(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        BUCKETS
+            .iter()
+            .zip(self.counts.iter().scan(0, |total, count| {
+                *total += count;
+                Some(*total)
+            }))
+            .map(|(bound, total)| (*bound, total))
+    }
+}
+
+/// Requests served by a fake client, counted by verb, resource and status
+///
+/// A point-in-time copy taken with
+/// [`ClientHandle::metrics`](crate::ClientHandle::metrics).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::budget::Verb;
+/// use kube_fake_client::ClientBuilder;
+/// use k8s_openapi::api::core::v1::ConfigMap;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+///
+/// // ... run 10 reconciles with `client` ...
+///
+/// let metrics = handle.metrics();
+/// let patches_per_reconcile = metrics.count_for::<ConfigMap>(Verb::Patch) as f64 / 10.0;
+/// assert!(patches_per_reconcile <= 1.0);
+/// assert_eq!(metrics.errors(), 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metrics {
+    requests: BTreeMap<RequestLabels, u64>,
+    latency: BTreeMap<String, Histogram>,
+}
+
+impl Metrics {
+    /// Request counts by labels
+    pub fn requests(&self) -> impl Iterator<Item = (&RequestLabels, u64)> {
+        self.requests.iter().map(|(labels, count)| (labels, *count))
+    }
+
+    /// Number of requests served
+    pub fn total(&self) -> u64 {
+        self.requests.values().sum()
+    }
+
+    /// Number of requests made with a verb
+    pub fn count(&self, verb: Verb) -> u64 {
+        self.sum(|labels| labels.verb == verb.as_str())
+    }
+
+    /// Number of requests made with a verb on a resource type
+    pub fn count_for<K>(&self, verb: Verb) -> u64
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        let (group, resource) = (K::group(&()), K::plural(&()));
+        self.sum(|labels| {
+            labels.verb == verb.as_str() && labels.group == group && labels.resource == resource
+        })
+    }
+
+    /// Number of responses with a status code
+    pub fn count_by_code(&self, code: u16) -> u64 {
+        self.sum(|labels| labels.code == code)
+    }
+
+    /// Number of responses with an error status code
+    pub fn errors(&self) -> u64 {
+        self.sum(|labels| labels.code >= 400)
+    }
+
+    /// Latency of the requests made with a verb
+    pub fn latency(&self, verb: Verb) -> Option<&Histogram> {
+        self.latency.get(verb.as_str())
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP apiserver_request_total Counter of apiserver requests broken out for each verb, group, version, resource, subresource and HTTP response code.\n");
+        out.push_str("# TYPE apiserver_request_total counter\n");
+        for (labels, count) in &self.requests {
+            let _ = writeln!(
+                out,
+                "apiserver_request_total{{code=\"{}\",group=\"{}\",resource=\"{}\",subresource=\"{}\",verb=\"{}\",version=\"{}\"}} {count}",
+                labels.code,
+                labels.group,
+                labels.resource,
+                labels.subresource,
+                labels.verb,
+                labels.version,
+            );
+        }
+        out.push_str("# HELP apiserver_request_duration_seconds Response latency distribution in seconds for each verb.\n");
+        out.push_str("# TYPE apiserver_request_duration_seconds histogram\n");
+        for (verb, histogram) in &self.latency {
+            for (bound, count) in histogram.buckets() {
+                let _ = writeln!(
+                    out,
+                    "apiserver_request_duration_seconds_bucket{{verb=\"{verb}\",le=\"{bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "apiserver_request_duration_seconds_bucket{{verb=\"{verb}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "apiserver_request_duration_seconds_sum{{verb=\"{verb}\"}} {}",
+                histogram.sum.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "apiserver_request_duration_seconds_count{{verb=\"{verb}\"}} {}",
+                histogram.count
+            );
+        }
+        out
+    }
+
+    fn sum(&self, matches: impl Fn(&RequestLabels) -> bool) -> u64 {
+        self.requests
+            .iter()
+            .filter(|(labels, _)| matches(labels))
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// Metrics updated as requests are served
+#[derive(Default)]
+pub(crate) struct MetricsRecorder {
+    inner: Mutex<Metrics>,
+}
+
+impl MetricsRecorder {
+    pub(crate) fn record(&self, labels: RequestLabels, duration: Duration) {
+        let mut metrics = self.inner.lock();
+        metrics
+            .latency
+            .entry(labels.verb.clone())
+            .or_default()
+            .observe(duration);
+        *metrics.requests.entry(labels).or_default() += 1;
+    }
+
+    pub(crate) fn snapshot(&self) -> Metrics {
+        self.inner.lock().clone()
+    }
+
+    pub(crate) fn reset(&self) {
+        *self.inner.lock() = Metrics::default();
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use crate::budget::Verb;
+    use crate::test_fixtures::configmap;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+    use kube::api::{Api, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_requests_are_counted_by_verb_resource_and_code() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+
+        configmaps
+            .create(&PostParams::default(), &configmap("settings"))
+            .await
+            .unwrap();
+        let patch = json!({ "data": { "mode": "active" } });
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        configmaps
+            .patch("missing", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap_err();
+        configmaps.list(&ListParams::default()).await.unwrap();
+        secrets.list(&ListParams::default()).await.unwrap();
+
+        let metrics = handle.metrics();
+        assert_eq!(metrics.total(), 5);
+        assert_eq!(metrics.count(Verb::Patch), 2);
+        assert_eq!(metrics.count(Verb::List), 2);
+        assert_eq!(metrics.count_for::<ConfigMap>(Verb::List), 1);
+        assert_eq!(metrics.count_for::<Secret>(Verb::Create), 0);
+        assert_eq!(metrics.count_by_code(201), 1);
+        assert_eq!(metrics.count_by_code(404), 1);
+        assert_eq!(metrics.errors(), 1);
+
+        let latency = metrics.latency(Verb::Patch).unwrap();
+        assert_eq!(latency.count(), 2);
+        let (_, slowest_bucket) = latency.buckets().last().unwrap();
+        assert!(slowest_bucket <= 2);
+
+        handle.reset_metrics();
+        assert_eq!(handle.metrics().total(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_prometheus_text() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        configmaps
+            .create(&PostParams::default(), &configmap("settings"))
+            .await
+            .unwrap();
+
+        let request = http::Request::get("/metrics").body(Vec::new()).unwrap();
+        let text = client.request_text(request).await.unwrap();
+        assert!(text.contains("# TYPE apiserver_request_total counter"));
+        assert!(text.contains(
+            r#"apiserver_request_total{code="201",group="",resource="configmaps",subresource="",verb="create",version="v1"} 1"#
+        ));
+        assert!(text.contains(r#"apiserver_request_duration_seconds_count{verb="create"} 1"#));
+        assert!(text
+            .contains(r#"apiserver_request_duration_seconds_bucket{verb="create",le="+Inf"} 1"#));
+    }
+}
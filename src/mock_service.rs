@@ -1,21 +1,37 @@
 //! Mock tower service that routes HTTP requests to the fake client
 
-use crate::client::FakeClient;
+use crate::api_discovery;
+use crate::audit::{self, AuditEntry};
+use crate::client::{self, FakeClient};
 use crate::client_utils::extract_gvk;
 use crate::discovery::Discovery;
 use crate::error::Error;
 use crate::field_selectors::extract_preregistered_field_value;
+use crate::garbage_collector::{self, Propagation};
 use crate::interceptor;
 use crate::label_selector;
-use crate::tracker::GVR;
+use crate::managed_fields::{self, Operation};
+use crate::metrics::RequestLabels;
+use crate::query;
+use crate::rbac;
+use crate::registry::ScaleSubresource;
+use crate::request::{self, RequestContext};
+use crate::response_body::ResponseBody;
+use crate::runtime;
+use crate::scale;
+use crate::service::Reservation;
+use crate::tracker::{ObjectTracker, GVK, GVR};
+use crate::warning;
+use crate::watch::{EventType, Expired, WatchEvent};
 use bytes::Bytes;
+use chrono::Utc;
 use futures::future::{BoxFuture, FutureExt};
+use futures::StreamExt;
 use http::{Request, Response, StatusCode};
-use http_body_util::Full;
 use kube::api::{ListParams, PatchParams, PostParams};
 use kube::client::Body as KubeBody;
 use serde_json::Value;
-use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tower::Service;
 
@@ -25,6 +41,7 @@ const CONTENT_TYPE_JSON_PATCH: &str = "application/json-patch+json";
 const CONTENT_TYPE_MERGE_PATCH: &str = "application/merge-patch+json";
 const CONTENT_TYPE_STRATEGIC_MERGE: &str = "application/strategic-merge-patch+json";
 const CONTENT_TYPE_APPLY_PATCH: &str = "application/apply-patch+yaml";
+const CONTENT_TYPE_PROTOBUF: &str = "application/vnd.kubernetes.protobuf";
 
 /// Macro to handle crate::Error conversion to HTTP response
 macro_rules! handle_error {
@@ -44,6 +61,7 @@ struct ParsedPath {
     namespace: Option<String>,
     resource: String,
     name: Option<String>,
+    subresource: Option<String>,
 }
 
 /// Patch types based on Content-Type header
@@ -61,12 +79,18 @@ enum PatchType {
 }
 
 /// Mock HTTP service that routes requests to the fake client backend
+///
+/// The `tower::Service` behind every client the builder builds. It doesn't need
+/// a tokio runtime; get one with
+/// [`ClientBuilder::build_service`](crate::ClientBuilder::build_service) to serve
+/// requests on another executor.
 #[derive(Clone)]
 pub struct MockService {
     client: FakeClient,
 }
 
 impl MockService {
+    /// Serve requests from a fake client's storage and configuration
     pub fn new(client: FakeClient) -> Self {
         Self { client }
     }
@@ -113,6 +137,7 @@ impl MockService {
                 namespace: Some(parts[version_idx + 2].to_string()),
                 resource: parts[version_idx + 3].to_string(),
                 name: parts.get(version_idx + 4).map(|s| s.to_string()),
+                subresource: parts.get(version_idx + 5).map(|s| s.to_string()),
             })
         } else {
             // Cluster-scoped resource: /api/v1/{resource}[/{name}]
@@ -122,6 +147,7 @@ impl MockService {
                 namespace: None,
                 resource: parts[version_idx + 1].to_string(),
                 name: parts.get(version_idx + 2).map(|s| s.to_string()),
+                subresource: parts.get(version_idx + 3).map(|s| s.to_string()),
             })
         }
     }
@@ -142,6 +168,13 @@ impl MockService {
             })
     }
 
+    /// Subresources whose writes only modify the object's status
+    ///
+    /// `approval` is the CertificateSigningRequest subresource for approval conditions.
+    fn status_subresource(subresource: Option<&str>) -> Option<&str> {
+        subresource.filter(|s| matches!(*s, "status" | "approval"))
+    }
+
     /// Extract namespace from parsed path, defaulting to empty string for cluster-scoped
     fn extract_namespace(parsed: &ParsedPath) -> String {
         parsed.namespace.as_deref().unwrap_or("").to_string()
@@ -170,8 +203,10 @@ impl MockService {
         if let Some(query_str) = query {
             for pair in query_str.split('&') {
                 if let Some((key, value)) = pair.split_once('=') {
-                    let decoded_value =
-                        urlencoding::decode(value).unwrap_or(std::borrow::Cow::Borrowed(value));
+                    // Query strings are form-encoded, with spaces sent as '+'
+                    let value = value.replace('+', " ");
+                    let decoded_value = urlencoding::decode(&value)
+                        .unwrap_or(std::borrow::Cow::Borrowed(value.as_str()));
 
                     match key {
                         "labelSelector" => params.label_selector = Some(decoded_value.to_string()),
@@ -199,35 +234,20 @@ impl MockService {
         params
     }
 
-    /// Check if object matches label selector
-    fn matches_label_selector(obj: &Value, selector: &str) -> bool {
-        let labels_obj = obj
-            .get("metadata")
-            .and_then(|m| m.get("labels"))
-            .and_then(|l| l.as_object());
-
-        let labels: BTreeMap<String, String> = labels_obj
-            .map(|obj| {
-                obj.iter()
-                    .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        label_selector::matches_label_selector(&labels, selector).unwrap_or(false)
-    }
-
-    /// Check if object matches field selector (uses pre-registered fields)
-    fn matches_field_selector(obj: &Value, selector: &str) -> bool {
-        let kind = obj.get("kind").and_then(|k| k.as_str()).unwrap_or("");
-
+    /// Check if object matches field selector (uses pre-registered fields and custom indexes)
+    fn matches_field_selector(&self, gvk: &GVK, obj: &Value, selector: &str) -> bool {
         for requirement in selector.split(',') {
             let requirement = requirement.trim();
             if let Some((field, expected_value)) = requirement.split_once('=') {
                 let field = field.trim_end_matches('=');
-                let expected_value = expected_value.trim();
+                let expected_value = expected_value.trim_start_matches('=').trim();
 
-                let values = extract_preregistered_field_value(obj, field, kind);
+                let values =
+                    extract_preregistered_field_value(obj, field, &gvk.kind).or_else(|| {
+                        self.client
+                            .get_index(gvk, field)
+                            .map(|indexer| indexer(obj))
+                    });
 
                 if !values.is_some_and(|v| v.iter().any(|val| val == expected_value)) {
                     return false;
@@ -286,7 +306,7 @@ impl MockService {
                     };
                     return match get_status_interceptor(ctx) {
                         Ok(Some(result)) => Ok(result),
-                        Ok(None) => self.client.tracker().get(gvr, namespace, name),
+                        Ok(None) => self.read_object(gvr, namespace, name),
                         Err(e) => Err(e),
                     };
                 }
@@ -298,43 +318,426 @@ impl MockService {
                 };
                 return match get_interceptor(ctx) {
                     Ok(Some(result)) => Ok(result),
-                    Ok(None) => self.client.tracker().get(gvr, namespace, name),
+                    Ok(None) => self.read_object(gvr, namespace, name),
                     Err(e) => Err(e),
                 };
             }
         }
-        self.client.tracker().get(gvr, namespace, name)
+        self.read_object(gvr, namespace, name)
+    }
+
+    /// Get an object, through the stale view if reads of its resource lag
+    fn read_object(
+        &self,
+        gvr: &GVR,
+        namespace: &str,
+        name: &str,
+    ) -> std::result::Result<Value, Error> {
+        let tracker = &self.client.tracker;
+        match self.client.stale_reads.get(tracker, gvr, namespace, name) {
+            Some(object) => object.map(|object| object.as_ref().clone()),
+            None => tracker.get(gvr, namespace, name),
+        }
+    }
+
+    /// Reject an object whose name or namespace differs from the request path's,
+    /// as the API server does for updates and patches
+    fn check_object_matches_path(
+        &self,
+        object: &Value,
+        namespace: &str,
+        name: &str,
+    ) -> std::result::Result<(), Error> {
+        if self.client.allow_path_mismatch {
+            return Ok(());
+        }
+        let object_name = object
+            .pointer("/metadata/name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if object_name != name {
+            return Err(Error::BadRequest(format!(
+                "the name of the object ({object_name}) does not match the name on the URL ({name})"
+            )));
+        }
+        let object_namespace = object
+            .pointer("/metadata/namespace")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !namespace.is_empty() && !object_namespace.is_empty() && object_namespace != namespace {
+            return Err(Error::BadRequest(
+                "the namespace of the provided object does not match the namespace sent on the request"
+                    .to_string(),
+            ));
+        }
+        Ok(())
     }
 
     /// Execute interceptor or default action for LIST operations
     fn execute_list_with_interceptor(
         &self,
         gvr: &GVR,
+        gvk: &GVK,
         namespace: Option<&str>,
         params: &ListParams,
-    ) -> std::result::Result<Vec<Value>, Error> {
+    ) -> std::result::Result<(Vec<Arc<Value>>, u64), Error> {
         if let Some(ref interceptors) = self.client.interceptors {
             if let Some(ref list_interceptor) = interceptors.list {
+                let resource_version = self.client.tracker.current_resource_version();
                 let ctx = interceptor::ListContext {
                     client: &self.client,
                     namespace,
                     params,
                 };
                 return match list_interceptor(ctx) {
-                    Ok(Some(result)) => Ok(result),
-                    Ok(None) => self.client.tracker().list(gvr, namespace),
+                    Ok(Some(result)) => {
+                        Ok((result.into_iter().map(Arc::new).collect(), resource_version))
+                    }
+                    Ok(None) => Ok(self.list_candidates(gvr, gvk, namespace, params)),
                     Err(e) => Err(e),
                 };
             }
         }
-        self.client.tracker().list(gvr, namespace)
+        Ok(self.list_candidates(gvr, gvk, namespace, params))
+    }
+
+    /// Objects a list may return, with the resourceVersion they are current as of
+    fn list_candidates(
+        &self,
+        gvr: &GVR,
+        gvk: &GVK,
+        namespace: Option<&str>,
+        params: &ListParams,
+    ) -> (Vec<Arc<Value>>, u64) {
+        if let Some(listed) = self
+            .client
+            .stale_reads
+            .list(&self.client.tracker, gvr, namespace)
+        {
+            return listed;
+        }
+        // Read before listing, so a watch from this version misses no writes
+        let resource_version = self.client.tracker.current_resource_version();
+        let objects = self.client.list_candidates(
+            gvr,
+            gvk,
+            namespace,
+            params.label_selector.as_deref(),
+            params.field_selector.as_deref(),
+        );
+        (objects, resource_version)
+    }
+
+    /// An interceptor function, if interceptors are configured
+    fn interceptor<T>(&self, select: impl FnOnce(&interceptor::Funcs) -> Option<T>) -> Option<T> {
+        self.client.interceptors.as_deref().and_then(select)
+    }
+
+    /// Execute interceptor or default action for POST (create) operations
+    fn execute_create_with_interceptor(
+        &self,
+        gvr: &GVR,
+        gvk: &GVK,
+        mut obj: Value,
+        namespace: &str,
+        field_manager: Option<&str>,
+    ) -> std::result::Result<Value, Error> {
+        if let Some(create_interceptor) = self
+            .client
+            .interceptors
+            .as_ref()
+            .and_then(|i| i.create.as_ref())
+        {
+            let ctx = interceptor::CreateContext {
+                client: &self.client,
+                object: &obj,
+                namespace,
+                params: &PostParams {
+                    field_manager: field_manager.map(str::to_string),
+                    ..PostParams::default()
+                },
+            };
+            if let Some(result) = create_interceptor(ctx)? {
+                return Ok(result);
+            }
+        }
+        if let Some(reviewed) = self.client.review(gvk, namespace, &obj)? {
+            return Ok(reviewed);
+        }
+        let reservation = self.client.prepare_for_create(gvk, namespace, &mut obj)?;
+        self.client.admit(gvk, &mut obj)?;
+        self.client.record_managed_fields(
+            gvk,
+            None,
+            &mut obj,
+            &managed_fields::Write {
+                manager: client::field_manager(field_manager),
+                operation: Operation::Update,
+                subresource: None,
+                applied: None,
+            },
+        );
+        let created = self.client.tracker().create(gvr, obj, namespace)?;
+        reservation.commit();
+        Ok(created)
+    }
+
+    /// Execute interceptor or default action for PUT (replace) operations
+    ///
+    /// Writes to a status-like subresource only ever modify the status field.
+    fn execute_replace_with_interceptor(
+        &self,
+        gvr: &GVR,
+        obj: Value,
+        namespace: &str,
+        name: &str,
+        subresource: Option<&str>,
+        field_manager: Option<&str>,
+    ) -> std::result::Result<Value, Error> {
+        let is_status = subresource.is_some();
+        if let Some(ref interceptors) = self.client.interceptors {
+            let params = PostParams {
+                field_manager: field_manager.map(str::to_string),
+                ..PostParams::default()
+            };
+            let intercepted = if is_status {
+                interceptors.replace_status.as_ref().map(|f| {
+                    f(interceptor::ReplaceStatusContext {
+                        client: &self.client,
+                        object: &obj,
+                        namespace,
+                        name,
+                        params: &params,
+                    })
+                })
+            } else {
+                interceptors.replace.as_ref().map(|f| {
+                    f(interceptor::ReplaceContext {
+                        client: &self.client,
+                        object: &obj,
+                        namespace,
+                        name,
+                        params: &params,
+                    })
+                })
+            };
+            if let Some(Some(result)) = intercepted.transpose()? {
+                return Ok(result);
+            }
+        }
+        let mut obj = obj;
+        let gvk = extract_gvk(&obj)?;
+        let existing = self.client.tracker().get(gvr, namespace, name).ok();
+        let mut reservation = Reservation::none();
+        if let Some(existing) = &existing {
+            ObjectTracker::check_resource_version(existing, &obj)?;
+            match subresource {
+                Some(subresource) => {
+                    self.client
+                        .prepare_for_subresource(&gvk, subresource, existing, &mut obj)?
+                }
+                None => {
+                    reservation = self
+                        .client
+                        .prepare_for_update(&gvk, namespace, existing, &mut obj)?;
+                    self.client
+                        .validate_immutable_fields(&gvk, existing, &obj)?;
+                }
+            }
+        }
+        self.client.admit(&gvk, &mut obj)?;
+        self.client.record_managed_fields(
+            &gvk,
+            existing.as_ref(),
+            &mut obj,
+            &managed_fields::Write {
+                manager: client::field_manager(field_manager),
+                operation: Operation::Update,
+                subresource,
+                applied: None,
+            },
+        );
+        let updated = self
+            .client
+            .tracker()
+            .update(gvr, &gvk, obj, namespace, is_status)?;
+        reservation.commit();
+        Ok(updated)
+    }
+
+    /// Execute interceptor or default action for PATCH operations
+    ///
+    /// Writes to a status-like subresource only ever modify the status field.
+    /// Server-side apply creates the object if it doesn't exist, answering 201
+    /// Created; other patches to a missing object fail with 404 Not Found.
+    #[allow(clippy::too_many_arguments)]
+    fn execute_patch_with_interceptor(
+        &self,
+        gvr: &GVR,
+        gvk: &GVK,
+        patch: &Value,
+        patch_type: PatchType,
+        namespace: &str,
+        name: &str,
+        subresource: Option<&str>,
+        field_manager: Option<&str>,
+    ) -> std::result::Result<(Value, StatusCode), Error> {
+        let is_status = subresource.is_some();
+        if let Some(ref interceptors) = self.client.interceptors {
+            let params = PatchParams {
+                field_manager: field_manager.map(str::to_string),
+                ..PatchParams::default()
+            };
+            let intercepted = if is_status {
+                interceptors.patch_status.as_ref().map(|f| {
+                    f(interceptor::PatchStatusContext {
+                        client: &self.client,
+                        patch,
+                        namespace,
+                        name,
+                        params: &params,
+                    })
+                })
+            } else {
+                interceptors.patch.as_ref().map(|f| {
+                    f(interceptor::PatchContext {
+                        client: &self.client,
+                        patch,
+                        namespace,
+                        name,
+                        params: &params,
+                    })
+                })
+            };
+            if let Some(Some(result)) = intercepted.transpose()? {
+                return Ok((result, StatusCode::OK));
+            }
+        }
+        // Like the API server, a patch that doesn't pin a resourceVersion is
+        // reapplied to the latest object when a concurrent write gets in first
+        loop {
+            let existing = match self.client.tracker().get(gvr, namespace, name) {
+                Err(Error::NotFound { .. })
+                    if patch_type == PatchType::ApplyPatch && !is_status =>
+                {
+                    let created =
+                        self.create_from_apply(gvr, gvk, patch, namespace, name, field_manager)?;
+                    return Ok((created, StatusCode::CREATED));
+                }
+                existing => existing?,
+            };
+            let mut patched = existing.clone();
+            Self::apply_patch(&mut patched, patch, patch_type)
+                .map_err(|e| Error::InvalidRequest(format!("Patch error: {e}")))?;
+            self.check_object_matches_path(&patched, namespace, name)?;
+            let gvk = extract_gvk(&patched)?;
+            let mut reservation = Reservation::none();
+            match subresource {
+                Some(subresource) => self.client.prepare_for_subresource(
+                    &gvk,
+                    subresource,
+                    &existing,
+                    &mut patched,
+                )?,
+                None => {
+                    reservation =
+                        self.client
+                            .prepare_for_update(&gvk, namespace, &existing, &mut patched)?;
+                    self.client
+                        .validate_immutable_fields(&gvk, &existing, &patched)?;
+                }
+            }
+            self.client.admit(&gvk, &mut patched)?;
+            let (operation, applied) = match patch_type {
+                PatchType::ApplyPatch => (Operation::Apply, Some(patch)),
+                _ => (Operation::Update, None),
+            };
+            self.client.record_managed_fields(
+                &gvk,
+                Some(&existing),
+                &mut patched,
+                &managed_fields::Write {
+                    manager: client::field_manager(field_manager),
+                    operation,
+                    subresource,
+                    applied,
+                },
+            );
+
+            let pinned = patched.pointer("/metadata/resourceVersion")
+                != existing.pointer("/metadata/resourceVersion");
+            match self
+                .client
+                .tracker()
+                .update(gvr, &gvk, patched, namespace, is_status)
+            {
+                Err(Error::Conflict(_)) if !pinned => continue,
+                result => {
+                    let updated = result?;
+                    reservation.commit();
+                    return Ok((updated, StatusCode::OK));
+                }
+            }
+        }
+    }
+
+    /// Create the object a server-side apply patch describes
+    ///
+    /// The applied configuration becomes the object, named by the path if it
+    /// doesn't name itself, and the field manager owns every field it sets.
+    fn create_from_apply(
+        &self,
+        gvr: &GVR,
+        gvk: &GVK,
+        patch: &Value,
+        namespace: &str,
+        name: &str,
+        field_manager: Option<&str>,
+    ) -> std::result::Result<Value, Error> {
+        self.client.validate_verb(gvk, "create")?;
+        if !patch.is_object() {
+            return Err(Error::BadRequest(
+                "an apply patch must be a JSON object".to_string(),
+            ));
+        }
+        let mut obj = patch.clone();
+        if obj.get("apiVersion").is_none() {
+            let group = Some(gvk.group.clone()).filter(|g| !g.is_empty());
+            obj["apiVersion"] = serde_json::json!(Self::build_api_version(&group, &gvk.version));
+        }
+        if obj.get("kind").is_none() {
+            obj["kind"] = serde_json::json!(gvk.kind);
+        }
+        if obj.pointer("/metadata/name").is_none() {
+            obj["metadata"]["name"] = serde_json::json!(name);
+        }
+        self.check_object_matches_path(&obj, namespace, name)?;
+        let reservation = self.client.prepare_for_create(gvk, namespace, &mut obj)?;
+        self.client.admit(gvk, &mut obj)?;
+        self.client.record_managed_fields(
+            gvk,
+            None,
+            &mut obj,
+            &managed_fields::Write {
+                manager: client::field_manager(field_manager),
+                operation: Operation::Apply,
+                subresource: None,
+                applied: Some(patch),
+            },
+        );
+        let created = self.client.tracker().create(gvr, obj, namespace)?;
+        reservation.commit();
+        Ok(created)
     }
 
     async fn handle_request(
         &self,
         req: Request<KubeBody>,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        let received = Utc::now();
+        let started = std::time::Instant::now();
         let method = req.method().clone();
+        let uri = req.uri().to_string();
         let path = req.uri().path().to_string();
         let query = req.uri().query().map(|s| s.to_string());
         let content_type = req
@@ -342,6 +745,18 @@ impl MockService {
             .get("content-type")
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string());
+        let accept = req
+            .headers()
+            .get("accept")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let context = RequestContext::new(
+            uuid::Uuid::new_v4().to_string(),
+            &method,
+            uri.clone(),
+            req.headers(),
+            self.client.identity.as_ref(),
+        );
 
         // Read the body
         let body_bytes = {
@@ -350,26 +765,735 @@ impl MockService {
             let collected = body.collect().await?;
             collected.to_bytes()
         };
+        let request_object: Option<Value> = serde_json::from_slice(&body_bytes).ok();
+
+        let parsed = if Self::is_discovery_path(&path) {
+            None
+        } else {
+            Self::parse_path(&path)
+        };
+        let verb = Self::request_verb(method.as_str(), parsed.as_ref(), query.as_deref());
+        let before = match verb {
+            Some("update" | "patch" | "delete") => {
+                parsed.as_ref().and_then(|p| self.stored_object(p))
+            }
+            _ => None,
+        };
+
+        #[cfg(feature = "instrumentation")]
+        let span = Self::request_span(&method, &path, verb, parsed.as_ref());
+
+        let mut response = if let Some(replay) = &self.client.replay {
+            match replay.respond(method.as_str(), &uri, &body_bytes) {
+                Ok(recorded) => recorded.map(ResponseBody::full),
+                Err(e) => Self::error_to_response(e)?,
+            }
+        } else {
+            let admitted = self
+                .check_subresource(parsed.as_ref(), verb)
+                .and_then(|()| {
+                    self.check_supported(
+                        &method,
+                        &path,
+                        parsed.as_ref(),
+                        query.as_deref(),
+                        content_type.as_deref(),
+                        accept.as_deref(),
+                    )
+                })
+                .and_then(|()| self.authorize(parsed.as_ref(), verb))
+                .and_then(|()| self.validate_query(parsed.as_ref(), verb, query.as_deref()));
+            let serve = async {
+                let admitted = match admitted {
+                    Ok(()) => self.disrupt(parsed.as_ref(), verb).await,
+                    Err(e) => Err(e),
+                };
+                match admitted {
+                    Ok(()) => {
+                        let routed = self.route_request(
+                            &method,
+                            &path,
+                            query.as_deref(),
+                            content_type.as_deref(),
+                            accept.as_deref(),
+                            body_bytes.clone(),
+                        );
+                        #[cfg(feature = "instrumentation")]
+                        let routed = tracing::Instrument::instrument(routed, span.clone());
+                        request::serve(context.clone(), routed).await
+                    }
+                    Err(e) => Self::error_to_response(e),
+                }
+            };
+            match Self::request_timeout(verb, query.as_deref()) {
+                Some(limit) => match runtime::timeout(limit, serve).await {
+                    Some(served) => served?,
+                    None => Self::error_to_response(Error::Timeout(
+                        "request did not complete within requested timeout - context deadline exceeded"
+                            .to_string(),
+                    ))?,
+                },
+                None => serve.await?,
+            }
+        };
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED
+            && !response.headers().contains_key(http::header::ALLOW)
+        {
+            if let Some(allow) = parsed.as_ref().and_then(|p| self.allow_header(p)) {
+                response.headers_mut().insert(http::header::ALLOW, allow);
+            }
+        }
+        let warnings: Vec<String> = self
+            .client
+            .warning_rules
+            .iter()
+            .filter_map(|rule| rule(&context, request_object.as_ref()))
+            .collect();
+        for message in &warnings {
+            if let Some(value) = warning::header_value(message) {
+                response.headers_mut().append(http::header::WARNING, value);
+            }
+        }
+
+        let identity = self.client.identity.as_ref();
+        let response_object = response
+            .body()
+            .as_full()
+            .and_then(|bytes| serde_json::from_slice::<Value>(bytes).ok());
+        let succeeded = response.status().is_success();
+        #[cfg(feature = "instrumentation")]
+        Self::trace_outcome(
+            &span,
+            response.status(),
+            response_object.as_ref(),
+            started.elapsed(),
+        );
+        let (diff, before, after) = match verb {
+            Some("create" | "update" | "patch" | "delete")
+                if succeeded && self.client.replay.is_none() =>
+            {
+                let after = parsed.as_ref().and_then(|p| {
+                    // Creates name the object in the body rather than the path
+                    let name = p
+                        .name
+                        .clone()
+                        .or_else(|| response_object.as_ref().and_then(Self::extract_object_name))?;
+                    self.stored_object(&ParsedPath {
+                        name: Some(name),
+                        ..p.clone()
+                    })
+                });
+                let diff = audit::diff(before.as_ref(), after.as_ref());
+                (Some(diff), before, after)
+            }
+            _ => (None, None, None),
+        };
+        self.client.metrics.record(
+            RequestLabels {
+                verb: verb
+                    .map(str::to_string)
+                    .unwrap_or_else(|| method.as_str().to_lowercase()),
+                group: parsed
+                    .as_ref()
+                    .and_then(|p| p.group.clone())
+                    .unwrap_or_default(),
+                version: parsed
+                    .as_ref()
+                    .map(|p| p.version.clone())
+                    .unwrap_or_default(),
+                resource: parsed
+                    .as_ref()
+                    .map(|p| p.resource.clone())
+                    .unwrap_or_default(),
+                subresource: parsed
+                    .as_ref()
+                    .and_then(|p| p.subresource.clone())
+                    .unwrap_or_default(),
+                code: response.status().as_u16(),
+            },
+            started.elapsed(),
+        );
+        self.client.audit.record(AuditEntry {
+            id: context.id,
+            method: method.to_string(),
+            uri,
+            verb: verb
+                .map(str::to_string)
+                .unwrap_or_else(|| method.as_str().to_lowercase()),
+            user: identity.map_or_else(|| audit::DEFAULT_USER.to_string(), |i| i.user.clone()),
+            groups: identity.map(|i| i.groups.clone()).unwrap_or_default(),
+            api_group: parsed
+                .as_ref()
+                .and_then(|p| p.group.clone())
+                .unwrap_or_default(),
+            api_version: parsed.as_ref().map(|p| p.version.clone()),
+            resource: parsed.as_ref().map(|p| p.resource.clone()),
+            subresource: parsed.as_ref().and_then(|p| p.subresource.clone()),
+            namespace: parsed.as_ref().and_then(|p| p.namespace.clone()),
+            name: parsed.as_ref().and_then(|p| p.name.clone()),
+            status: response.status().as_u16(),
+            request_object,
+            response_object,
+            diff,
+            before,
+            after,
+            tags: context.tags,
+            field_manager: Self::field_manager(query.as_deref()),
+            warnings,
+            received,
+            completed: Utc::now(),
+        });
+
+        Ok(response)
+    }
+
+    /// Span covering the handling of one request
+    #[cfg(feature = "instrumentation")]
+    fn request_span(
+        method: &http::Method,
+        path: &str,
+        verb: Option<&str>,
+        parsed: Option<&ParsedPath>,
+    ) -> tracing::Span {
+        let field = |get: fn(&ParsedPath) -> Option<&str>| {
+            parsed.and_then(get).unwrap_or_default().to_string()
+        };
+        tracing::debug_span!(
+            "request",
+            method = %method,
+            path = %path,
+            verb = verb.unwrap_or_default(),
+            group = field(|p| p.group.as_deref()),
+            version = field(|p| Some(&p.version)),
+            resource = field(|p| Some(&p.resource)),
+            subresource = field(|p| p.subresource.as_deref()),
+            namespace = field(|p| p.namespace.as_deref()),
+            name = field(|p| p.name.as_deref()),
+            status = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        )
+    }
+
+    /// Record how a request went on its span and log it
+    ///
+    /// The outcome is "Success", or the reason of the returned Status.
+    #[cfg(feature = "instrumentation")]
+    fn trace_outcome(
+        span: &tracing::Span,
+        status: StatusCode,
+        response_object: Option<&Value>,
+        duration: std::time::Duration,
+    ) {
+        let outcome = if status.is_success() {
+            "Success"
+        } else {
+            response_object
+                .and_then(|object| object.get("reason"))
+                .and_then(Value::as_str)
+                .unwrap_or("Unknown")
+        };
+        let duration_ms = duration.as_secs_f64() * 1000.0;
+        span.record("status", status.as_u16());
+        span.record("outcome", outcome);
+        span.record("duration_ms", duration_ms);
+        span.in_scope(|| {
+            if status.is_server_error() {
+                tracing::warn!(
+                    status = status.as_u16(),
+                    outcome,
+                    duration_ms,
+                    "request failed"
+                );
+            } else {
+                tracing::debug!(
+                    status = status.as_u16(),
+                    outcome,
+                    duration_ms,
+                    "request served"
+                );
+            }
+        });
+    }
+
+    async fn route_request(
+        &self,
+        method: &http::Method,
+        path: &str,
+        query: Option<&str>,
+        content_type: Option<&str>,
+        accept: Option<&str>,
+        body_bytes: Bytes,
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(parsed) = Self::parse_path(path) {
+            if !self
+                .path_methods(&parsed)
+                .iter()
+                .any(|(accepted, _)| accepted == method)
+            {
+                return Self::error_to_response(Error::api(
+                    405,
+                    "MethodNotAllowed",
+                    "the server does not allow this method on the requested resource",
+                ));
+            }
+            if let Some(mapping) = self.scale_mapping(&parsed) {
+                return self.handle_scale(
+                    method.as_str(),
+                    &parsed,
+                    &mapping,
+                    content_type,
+                    body_bytes,
+                );
+            }
+            if self.is_custom_subresource(&parsed) {
+                return self.handle_subresource(method.as_str(), &parsed, body_bytes);
+            }
+        }
 
         // Route based on HTTP method
         match method.as_str() {
-            "GET" => self.handle_get(&path, query.as_deref()).await,
-            "POST" => self.handle_post(&path, body_bytes).await,
-            "PUT" => self.handle_put(&path, body_bytes).await,
+            "GET" => self.handle_get(path, query, accept).await,
+            "POST" => self.handle_post(path, query, body_bytes).await,
+            "PUT" => self.handle_put(path, query, body_bytes).await,
             "PATCH" => {
-                self.handle_patch(&path, body_bytes, content_type.as_deref())
+                self.handle_patch(path, query, body_bytes, content_type)
                     .await
             }
-            "DELETE" => self.handle_delete(&path, query.as_deref()).await,
+            "DELETE" => self.handle_delete(path, query, body_bytes).await,
             _ => Self::error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
         }
     }
 
+    /// The HTTP methods a path takes, with the verb each is served as
+    ///
+    /// Collections take reads, creates and collection deletes, objects take
+    /// everything but creates, and subresources are read and written; custom
+    /// subresources take creates too.
+    fn path_methods(&self, parsed: &ParsedPath) -> &'static [(&'static str, &'static str)] {
+        match (&parsed.name, &parsed.subresource) {
+            (None, _) => &[
+                ("GET", "list"),
+                ("GET", "watch"),
+                ("POST", "create"),
+                ("DELETE", "deletecollection"),
+            ],
+            (Some(_), None) => &[
+                ("GET", "get"),
+                ("PUT", "update"),
+                ("PATCH", "patch"),
+                ("DELETE", "delete"),
+            ],
+            (Some(_), Some(_)) if self.is_custom_subresource(parsed) => &[
+                ("GET", "get"),
+                ("POST", "create"),
+                ("PUT", "update"),
+                ("PATCH", "patch"),
+            ],
+            (Some(_), Some(_)) => &[("GET", "get"), ("PUT", "update"), ("PATCH", "patch")],
+        }
+    }
+
+    /// The `Allow` header of a path: the methods whose verbs its resource
+    /// supports
+    ///
+    /// `None` for resources the fake doesn't know, which are not found instead.
+    fn allow_header(&self, parsed: &ParsedPath) -> Option<http::HeaderValue> {
+        let group = parsed.group.as_deref().unwrap_or_default();
+        let kind = self
+            .resource_to_kind(group, &parsed.version, &parsed.resource)
+            .ok()?;
+        let gvk = GVK::new(group, &parsed.version, &kind);
+        // Handlers of custom subresources decide for themselves
+        let custom_subresource = self.is_custom_subresource(parsed);
+        let mut allowed: Vec<&str> = Vec::new();
+        for (method, verb) in self.path_methods(parsed) {
+            let supported = custom_subresource || self.client.validate_verb(&gvk, verb).is_ok();
+            if supported && !allowed.contains(method) {
+                allowed.push(method);
+            }
+        }
+        http::HeaderValue::from_str(&allowed.join(", ")).ok()
+    }
+
+    /// The scale mapping of a custom resource whose scale subresource a path names
+    fn scale_mapping(&self, parsed: &ParsedPath) -> Option<ScaleSubresource> {
+        if parsed.name.is_none() || parsed.subresource.as_deref() != Some("scale") {
+            return None;
+        }
+        let group = parsed.group.as_deref().unwrap_or_default();
+        let kind = self
+            .resource_to_kind(group, &parsed.version, &parsed.resource)
+            .ok()?;
+        scale::mapping(&self.client, &GVK::new(group, &parsed.version, &kind))
+    }
+
+    /// Serve the scale subresource of a custom resource
+    fn handle_scale(
+        &self,
+        method: &str,
+        parsed: &ParsedPath,
+        mapping: &ScaleSubresource,
+        content_type: Option<&str>,
+        body: Bytes,
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        let name = parsed.name.as_deref().unwrap_or_default();
+        let namespace = Self::extract_namespace(parsed);
+        let group = parsed.group.as_deref().unwrap_or_default();
+        let kind = handle_error!(self.resource_to_kind(group, &parsed.version, &parsed.resource));
+        let gvr = GVR::new(group, &parsed.version, &parsed.resource);
+        let gvk = GVK::new(group, &parsed.version, &kind);
+
+        let mut object = handle_error!(self.client.tracker.get(&gvr, &namespace, name));
+        let mut requested = scale::to_scale(&object, mapping);
+        match method {
+            "GET" => return Self::success_response(requested),
+            "PUT" => requested = serde_json::from_slice(&body)?,
+            "PATCH" => {
+                let patch: Value = serde_json::from_slice(&body)?;
+                Self::apply_patch(
+                    &mut requested,
+                    &patch,
+                    Self::determine_patch_type(content_type),
+                )?;
+            }
+            _ => return Self::error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+        }
+        handle_error!(scale::apply(&mut object, &requested, mapping));
+        let updated = handle_error!(self
+            .client
+            .tracker
+            .update(&gvr, &gvk, object, &namespace, false));
+        Self::success_response(scale::to_scale(&updated, mapping))
+    }
+
+    /// Whether a path names a subresource served by [`handle_subresource`](Self::handle_subresource)
+    ///
+    /// Those are the subresources without built-in handling that are declared
+    /// on a registered resource or have a handler.
+    fn is_custom_subresource(&self, parsed: &ParsedPath) -> bool {
+        let (Some(_), Some(subresource)) = (&parsed.name, parsed.subresource.as_deref()) else {
+            return false;
+        };
+        if Self::status_subresource(Some(subresource)).is_some() {
+            return false;
+        }
+        let has_handler = self
+            .interceptor(|funcs| funcs.subresources.get(subresource).cloned())
+            .is_some();
+        has_handler
+            || self
+                .client
+                .registry
+                .lookup(
+                    parsed.group.as_deref().unwrap_or_default(),
+                    &parsed.version,
+                    &parsed.resource,
+                )
+                .is_some_and(|metadata| metadata.subresources.iter().any(|s| s == subresource))
+    }
+
+    /// Serve a request to a custom subresource of an object
+    fn handle_subresource(
+        &self,
+        method: &str,
+        parsed: &ParsedPath,
+        body: Bytes,
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        let verb = match method {
+            "GET" => "get",
+            "POST" => "create",
+            "PUT" => "update",
+            "PATCH" => "patch",
+            _ => return Self::error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+        };
+        let name = parsed.name.as_deref().unwrap_or_default();
+        let subresource = parsed.subresource.as_deref().unwrap_or_default();
+        let namespace = Self::extract_namespace(parsed);
+        let gvr = GVR::new(
+            parsed.group.as_deref().unwrap_or_default(),
+            &parsed.version,
+            &parsed.resource,
+        );
+        let object = handle_error!(self.client.tracker.get_shared(&gvr, &namespace, name));
+        let body: Option<Value> = if verb == "get" || body.is_empty() {
+            None
+        } else {
+            Some(serde_json::from_slice(&body)?)
+        };
+
+        let handler = self.interceptor(|funcs| funcs.subresources.get(subresource).cloned());
+        if let Some(handler) = handler {
+            let response = handle_error!(handler(interceptor::SubresourceContext {
+                client: &self.client,
+                verb,
+                resource: &parsed.resource,
+                subresource,
+                namespace: &namespace,
+                name,
+                object: &object,
+                body: body.as_ref(),
+            }));
+            if let Some(response) = response {
+                let status = if verb == "create" {
+                    StatusCode::CREATED
+                } else {
+                    StatusCode::OK
+                };
+                return Self::success_response_with_status(response, status);
+            }
+        }
+
+        if verb == "get" {
+            return Self::success_response(object.as_ref().clone());
+        }
+        Self::error_to_response(Error::VerbNotSupported {
+            verb: verb.to_string(),
+            kind: format!("{}/{subresource}", parsed.resource),
+        })
+    }
+
+    /// The API verb of a resource request
+    fn request_verb(
+        method: &str,
+        parsed: Option<&ParsedPath>,
+        query: Option<&str>,
+    ) -> Option<&'static str> {
+        let parsed = parsed?;
+        let watch = Self::is_watch(query);
+        let verb = match (method, &parsed.name) {
+            ("GET", Some(_)) => "get",
+            ("GET", None) if watch => "watch",
+            ("GET", None) => "list",
+            ("POST", _) => "create",
+            ("PUT", _) => "update",
+            ("PATCH", _) => "patch",
+            ("DELETE", Some(_)) => "delete",
+            ("DELETE", None) => "deletecollection",
+            _ => return None,
+        };
+        Some(verb)
+    }
+
+    /// The stored object a request path names, if any
+    fn stored_object(&self, parsed: &ParsedPath) -> Option<Value> {
+        let name = parsed.name.as_deref()?;
+        let gvr = GVR::new(
+            parsed.group.as_deref().unwrap_or_default(),
+            &parsed.version,
+            &parsed.resource,
+        );
+        self.client
+            .tracker
+            .get_shared(&gvr, &Self::extract_namespace(parsed), name)
+            .ok()
+            .map(|object| object.as_ref().clone())
+    }
+
+    /// Authorize a request as the client's identity, if one is configured
+    ///
+    /// Discovery is allowed for everyone, as the `system:discovery` ClusterRole does
+    /// on a real cluster.
+    fn authorize(
+        &self,
+        parsed: Option<&ParsedPath>,
+        verb: Option<&str>,
+    ) -> std::result::Result<(), Error> {
+        let (Some(identity), Some(parsed), Some(verb)) = (&self.client.identity, parsed, verb)
+        else {
+            return Ok(());
+        };
+        let attrs = rbac::Attributes {
+            verb: verb.to_string(),
+            group: parsed.group.clone().unwrap_or_default(),
+            resource: parsed.resource.clone(),
+            subresource: parsed.subresource.clone(),
+            namespace: parsed.namespace.clone(),
+            name: parsed.name.clone(),
+            path: None,
+        };
+
+        if rbac::authorize(&self.client.tracker, identity, &attrs) {
+            Ok(())
+        } else {
+            Err(rbac::forbidden(identity, &attrs))
+        }
+    }
+
+    /// Fail a request that needs a feature the fake doesn't implement
+    ///
+    /// Panics instead if the client was built
+    /// [`with_panic_on_unsupported`](crate::ClientBuilder::with_panic_on_unsupported).
+    fn check_supported(
+        &self,
+        method: &http::Method,
+        path: &str,
+        parsed: Option<&ParsedPath>,
+        query: Option<&str>,
+        content_type: Option<&str>,
+        accept: Option<&str>,
+    ) -> std::result::Result<(), Error> {
+        let Some(feature) = self.unsupported_feature(path, parsed, query, content_type, accept)
+        else {
+            return Ok(());
+        };
+        if self.client.panic_on_unsupported {
+            panic!("{method} {path}: not supported by kube-fake-client: {feature}");
+        }
+        Err(Error::Unsupported(feature))
+    }
+
+    /// Fail writes to a status, approval or scale subresource the resource
+    /// doesn't have, as the API server has no route for them
+    ///
+    /// Catches status writes to kinds whose status is part of the object.
+    fn check_subresource(
+        &self,
+        parsed: Option<&ParsedPath>,
+        verb: Option<&str>,
+    ) -> Result<(), Error> {
+        let Some(parsed) = parsed.filter(|_| matches!(verb, Some("update" | "patch"))) else {
+            return Ok(());
+        };
+        let Some(subresource) = parsed
+            .subresource
+            .as_deref()
+            .filter(|s| matches!(*s, "status" | "approval" | "scale"))
+        else {
+            return Ok(());
+        };
+        let group = parsed.group.as_deref().unwrap_or_default();
+        // Unknown resources are not found anyway
+        let Ok(kind) = self.resource_to_kind(group, &parsed.version, &parsed.resource) else {
+            return Ok(());
+        };
+        let gvk = GVK::new(group, &parsed.version, &kind);
+        if self.client.serves_subresource(&gvk, subresource) || self.is_custom_subresource(parsed) {
+            return Ok(());
+        }
+        Err(Error::api(
+            404,
+            "NotFound",
+            "the server could not find the requested resource",
+        ))
+    }
+
+    /// The feature a request needs that the fake doesn't implement, if any
+    fn unsupported_feature(
+        &self,
+        path: &str,
+        parsed: Option<&ParsedPath>,
+        query: Option<&str>,
+        content_type: Option<&str>,
+        accept: Option<&str>,
+    ) -> Option<String> {
+        if content_type.is_some_and(|ct| ct.contains(CONTENT_TYPE_PROTOBUF)) {
+            return Some("protobuf request bodies".to_string());
+        }
+        if Self::is_discovery_path(path) || path == "/metrics" {
+            return None;
+        }
+        if let Some(accept) = accept.filter(|accept| !Self::accepts_json(accept)) {
+            return Some(format!("responses of type {accept:?}"));
+        }
+        let Some(parsed) = parsed else {
+            return Some(format!("the path {path:?}"));
+        };
+        if let Some(subresource) = parsed.subresource.as_deref() {
+            if Self::status_subresource(Some(subresource)).is_none()
+                && self.scale_mapping(parsed).is_none()
+                && !self.is_custom_subresource(parsed)
+            {
+                return Some(format!(
+                    "the {subresource:?} subresource of {}",
+                    parsed.resource
+                ));
+            }
+        }
+        let streaming_list = query
+            .unwrap_or_default()
+            .split('&')
+            .any(|pair| pair == "sendInitialEvents=true");
+        if Self::is_watch(query) && streaming_list {
+            return Some("watches with sendInitialEvents".to_string());
+        }
+        None
+    }
+
+    /// Whether an Accept header allows a plain JSON response
+    ///
+    /// Media types with an `as` parameter, like `as=Table`, ask for a
+    /// conversion the fake doesn't do.
+    fn accepts_json(accept: &str) -> bool {
+        accept.split(',').any(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let media_type = parts.next().unwrap_or_default();
+            matches!(
+                media_type,
+                "" | "application/json" | "application/*" | "*/*"
+            ) && !parts.any(|param| param.starts_with("as="))
+        })
+    }
+
+    /// Check the query parameters of a request in strict mode
+    fn validate_query(
+        &self,
+        parsed: Option<&ParsedPath>,
+        verb: Option<&str>,
+        query: Option<&str>,
+    ) -> std::result::Result<(), Error> {
+        let (true, Some(parsed), Some(verb)) = (self.client.strict_query_params, parsed, verb)
+        else {
+            return Ok(());
+        };
+        query::validate(verb, parsed.subresource.as_deref(), query)
+    }
+
+    /// How long a list may take, from its `timeoutSeconds`
+    ///
+    /// A list that doesn't finish in time, say under chaos latency, fails with
+    /// 504 Timeout as on the API server. Watches end their stream at the timeout
+    /// instead.
+    fn request_timeout(verb: Option<&str>, query: Option<&str>) -> Option<std::time::Duration> {
+        if verb != Some("list") {
+            return None;
+        }
+        let seconds = Self::parse_list_params(query).timeout.filter(|s| *s > 0)?;
+        Some(std::time::Duration::from_secs(seconds.into()))
+    }
+
+    /// Delay the request and maybe fail it, if chaos mode is on
+    async fn disrupt(
+        &self,
+        parsed: Option<&ParsedPath>,
+        verb: Option<&str>,
+    ) -> std::result::Result<(), Error> {
+        let (Some(chaos), Some(parsed), Some(verb)) = (&self.client.chaos, parsed, verb) else {
+            return Ok(());
+        };
+        chaos
+            .disrupt(verb, &parsed.resource, parsed.name.as_deref())
+            .await
+    }
+
     async fn handle_get(
         &self,
         path: &str,
         query: Option<&str>,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+        accept: Option<&str>,
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        if Self::is_discovery_path(path) {
+            return self.handle_discovery(path, accept);
+        }
+        if path == "/metrics" {
+            return Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/plain; version=0.0.4")
+                .body(ResponseBody::full(
+                    self.client.metrics.snapshot().to_prometheus(),
+                ))
+                .expect("Failed to build response"));
+        }
         let parsed = Self::parse_path(path).ok_or("Invalid path")?;
         let namespace = Self::extract_namespace(&parsed);
         let kind = handle_error!(self.resource_to_kind(
@@ -384,7 +1508,7 @@ impl MockService {
             parsed.resource.clone(),
         );
 
-        let gvk = crate::tracker::GVK::new(
+        let gvk = GVK::new(
             parsed.group.clone().unwrap_or_default(),
             parsed.version.clone(),
             &kind,
@@ -399,24 +1523,28 @@ impl MockService {
                 self.execute_get_with_interceptor(&gvr, &namespace, &name, is_status)
             );
             Self::success_response(obj)
+        } else if Self::is_watch(query) {
+            self.handle_watch(gvr, gvk, parsed.namespace, query)
         } else {
             // LIST objects
             handle_error!(self.client.validate_verb(&gvk, "list"));
 
             let list_params = Self::parse_list_params(query);
-            let mut objects = handle_error!(self.execute_list_with_interceptor(
-                &gvr,
-                parsed.namespace.as_deref(),
-                &list_params
-            ));
+            let (mut objects, resource_version) = handle_error!(self
+                .execute_list_with_interceptor(
+                    &gvr,
+                    &gvk,
+                    parsed.namespace.as_deref(),
+                    &list_params
+                ));
 
             // Apply selectors
             if let Some(label_selector) = &list_params.label_selector {
-                objects.retain(|obj| Self::matches_label_selector(obj, label_selector));
+                objects.retain(|obj| label_selector::matches_object(obj, label_selector));
             }
 
             if let Some(field_selector) = &list_params.field_selector {
-                objects.retain(|obj| Self::matches_field_selector(obj, field_selector));
+                objects.retain(|obj| self.matches_field_selector(&gvk, obj, field_selector));
             }
 
             // Apply limit
@@ -424,22 +1552,199 @@ impl MockService {
                 objects.truncate(limit as usize);
             }
 
-            let list = serde_json::json!({
+            // Items are streamed from the tracker's shared copies rather than
+            // collected into one JSON document
+            let envelope = serde_json::json!({
                 "kind": format!("{kind}List"),
                 "apiVersion": Self::build_api_version(&parsed.group, &parsed.version),
-                "metadata": { "resourceVersion": "1" },
-                "items": objects
+                "metadata": { "resourceVersion": resource_version.to_string() },
             });
 
-            Self::success_response(list)
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", CONTENT_TYPE_JSON)
+                .body(ResponseBody::list(&envelope, objects))
+                .expect("Failed to build response"))
+        }
+    }
+
+    /// Whether a collection GET asks for a watch rather than a list
+    fn is_watch(query: Option<&str>) -> bool {
+        query
+            .unwrap_or_default()
+            .split('&')
+            .any(|pair| pair == "watch=true" || pair == "watch=1")
+    }
+
+    /// Stream the changes to a collection
+    ///
+    /// Without a resourceVersion (or with "0"), the objects currently stored are
+    /// sent as ADDED events first. With one, every write after it is sent; a
+    /// version older than the watch history ends the watch with a 410 Expired
+    /// error event, telling the client to relist.
+    fn handle_watch(
+        &self,
+        gvr: GVR,
+        gvk: GVK,
+        namespace: Option<String>,
+        query: Option<&str>,
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        handle_error!(self.client.validate_verb(&gvk, "watch"));
+        let params = Self::parse_list_params(query);
+        let event_line = |event: Value| {
+            let mut line = event.to_string().into_bytes();
+            line.push(b'\n');
+            Bytes::from(line)
+        };
+        let stream_response = |body: ResponseBody| {
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", CONTENT_TYPE_JSON)
+                .body(body)
+                .expect("Failed to build response"))
+        };
+
+        if let Some(interceptors) = &self.client.interceptors {
+            if let Some(watch_fn) = &interceptors.watch {
+                let ctx = interceptor::WatchContext {
+                    client: &self.client,
+                    gvk: &gvk,
+                    namespace: namespace.as_deref(),
+                    params: &params,
+                };
+                if let Some(objects) = handle_error!(watch_fn(ctx)) {
+                    let events: Vec<u8> = objects
+                        .into_iter()
+                        .flat_map(|object| {
+                            event_line(serde_json::json!({ "type": "ADDED", "object": object }))
+                        })
+                        .collect();
+                    return stream_response(ResponseBody::full(events));
+                }
+            }
+        }
+
+        let since = params
+            .resource_version
+            .as_deref()
+            .and_then(|rv| rv.parse::<u64>().ok())
+            .filter(|rv| *rv != 0);
+        let (initial, receiver) = match self.client.tracker.watch_cache().subscribe(&gvr, since) {
+            Ok((replay, receiver)) if since.is_some() => (replay, receiver),
+            Ok((_, receiver)) => {
+                let current = self
+                    .client
+                    .tracker
+                    .list_shared(&gvr, namespace.as_deref())
+                    .into_iter()
+                    .map(|object| WatchEvent {
+                        gvr: gvr.clone(),
+                        namespace: namespace.clone().unwrap_or_default(),
+                        event_type: EventType::Added,
+                        object,
+                        resource_version: 0,
+                    })
+                    .collect();
+                (current, receiver)
+            }
+            Err(Expired) => {
+                let status = serde_json::json!({
+                    "type": "ERROR",
+                    "object": {
+                        "kind": "Status",
+                        "apiVersion": "v1",
+                        "status": "Failure",
+                        "message": format!(
+                            "too old resource version: {} ({})",
+                            since.unwrap_or_default(),
+                            self.client.tracker.current_resource_version()
+                        ),
+                        "reason": "Expired",
+                        "code": 410,
+                    },
+                });
+                return stream_response(ResponseBody::full(event_line(status)));
+            }
+        };
+
+        // A watch that falls too far behind ends, and the client resumes it
+        let live = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.ok().map(|event| (event, receiver))
+        });
+        let service = self.clone();
+        let events = futures::stream::iter(initial)
+            .chain(live)
+            .filter(move |event| {
+                let matches = event.gvr == gvr
+                    && namespace.as_ref().is_none_or(|ns| *ns == event.namespace)
+                    && params
+                        .label_selector
+                        .as_ref()
+                        .is_none_or(|sel| label_selector::matches_object(&event.object, sel))
+                    && params
+                        .field_selector
+                        .as_ref()
+                        .is_none_or(|sel| service.matches_field_selector(&gvk, &event.object, sel));
+                futures::future::ready(matches)
+            })
+            .map(move |event| event_line(event.to_json()));
+
+        let body = match params.timeout.filter(|s| *s > 0) {
+            Some(seconds) => {
+                let timeout = runtime::sleep(std::time::Duration::from_secs(seconds.into()));
+                events.take_until(timeout).boxed()
+            }
+            None => events.boxed(),
+        };
+        stream_response(ResponseBody::stream(body))
+    }
+
+    /// Whether a path names a discovery document rather than a resource
+    ///
+    /// `/api`, `/api/{version}`, `/apis`, `/apis/{group}` and `/apis/{group}/{version}`
+    /// are served by `handle_discovery`; longer paths fall through to normal routing.
+    pub(crate) fn is_discovery_path(path: &str) -> bool {
+        let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        match parts.first() {
+            Some(&"api") => parts.len() <= 2,
+            Some(&"apis") => parts.len() <= 3,
+            _ => false,
         }
     }
 
+    /// Serve discovery documents for `/api` and `/apis` paths
+    fn handle_discovery(
+        &self,
+        path: &str,
+        accept: Option<&str>,
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        let aggregated = api_discovery::wants_aggregated(accept)
+            && matches!(path.trim_matches('/'), "api" | "apis");
+        let Some(document) = api_discovery::document(&self.client.registry, path, aggregated)
+        else {
+            return Self::error_response(
+                StatusCode::NOT_FOUND,
+                &format!("the server could not find the requested resource: {path}"),
+            );
+        };
+        let content_type = if aggregated {
+            api_discovery::CONTENT_TYPE_AGGREGATED
+        } else {
+            CONTENT_TYPE_JSON
+        };
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", content_type)
+            .body(ResponseBody::full(document.to_string()))
+            .expect("Failed to build response"))
+    }
+
     async fn handle_post(
         &self,
         path: &str,
+        query: Option<&str>,
         body: Bytes,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
         let parsed = Self::parse_path(path).ok_or("Invalid path")?;
         let namespace = Self::extract_namespace(&parsed);
 
@@ -469,28 +1774,24 @@ impl MockService {
 
         handle_error!(self.client.validate_verb(&gvk, "create"));
 
-        let created = if let Some(ref interceptors) = self.client.interceptors {
-            if let Some(ref create_interceptor) = interceptors.create {
-                let ctx = interceptor::CreateContext {
-                    client: &self.client,
-                    object: &obj,
-                    namespace: &namespace,
-                    params: &PostParams::default(),
-                };
-
-                match create_interceptor(ctx) {
-                    Ok(Some(result)) => result,
-                    Ok(None) => {
-                        handle_error!(self.client.tracker().create(&gvr, &gvk, obj, &namespace))
-                    }
-                    Err(e) => return Self::error_to_response(e),
-                }
-            } else {
-                handle_error!(self.client.tracker().create(&gvr, &gvk, obj, &namespace))
-            }
-        } else {
-            handle_error!(self.client.tracker().create(&gvr, &gvk, obj, &namespace))
-        };
+        let after_create = self.interceptor(|funcs| funcs.after_create.clone());
+        let request = after_create.is_some().then(|| obj.clone());
+        let manager = Self::field_manager(query);
+        let mut created = handle_error!(self.execute_create_with_interceptor(
+            &gvr,
+            &gvk,
+            obj,
+            &namespace,
+            manager.as_deref()
+        ));
+        if let (Some(after_create), Some(object)) = (after_create, &request) {
+            handle_error!(after_create(interceptor::AfterCreateContext {
+                client: &self.client,
+                object,
+                namespace: &namespace,
+                response: &mut created,
+            }));
+        }
 
         Self::success_response_with_status(created, StatusCode::CREATED)
     }
@@ -498,8 +1799,9 @@ impl MockService {
     async fn handle_put(
         &self,
         path: &str,
+        query: Option<&str>,
         body: Bytes,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
         let parsed = Self::parse_path(path).ok_or("Invalid path")?;
         let namespace = Self::extract_namespace(&parsed);
         let name = parsed.name.as_ref().ok_or("Name required for PUT")?;
@@ -526,64 +1828,32 @@ impl MockService {
             parsed.resource,
         );
         let gvk = extract_gvk(&obj)?;
-        let is_status = path.ends_with("/status");
+        let subresource = Self::status_subresource(parsed.subresource.as_deref());
 
         handle_error!(self.client.validate_verb(&gvk, "update"));
-
-        let updated = if let Some(ref interceptors) = self.client.interceptors {
-            if is_status {
-                if let Some(ref replace_status_interceptor) = interceptors.replace_status {
-                    let ctx = interceptor::ReplaceStatusContext {
-                        client: &self.client,
-                        object: &obj,
-                        namespace: &namespace,
-                        name,
-                        params: &PostParams::default(),
-                    };
-
-                    match replace_status_interceptor(ctx) {
-                        Ok(Some(result)) => result,
-                        Ok(None) => handle_error!(self
-                            .client
-                            .tracker()
-                            .update(&gvr, &gvk, obj, &namespace, true)),
-                        Err(e) => return Self::error_to_response(e),
-                    }
-                } else {
-                    handle_error!(self
-                        .client
-                        .tracker()
-                        .update(&gvr, &gvk, obj, &namespace, true))
-                }
-            } else if let Some(ref replace_interceptor) = interceptors.replace {
-                let ctx = interceptor::ReplaceContext {
-                    client: &self.client,
-                    object: &obj,
-                    namespace: &namespace,
-                    name,
-                    params: &PostParams::default(),
-                };
-
-                match replace_interceptor(ctx) {
-                    Ok(Some(result)) => result,
-                    Ok(None) => handle_error!(self
-                        .client
-                        .tracker()
-                        .update(&gvr, &gvk, obj, &namespace, false)),
-                    Err(e) => return Self::error_to_response(e),
-                }
-            } else {
-                handle_error!(self
-                    .client
-                    .tracker()
-                    .update(&gvr, &gvk, obj, &namespace, false))
-            }
-        } else {
-            handle_error!(self
-                .client
-                .tracker()
-                .update(&gvr, &gvk, obj, &namespace, is_status))
-        };
+        handle_error!(self.check_object_matches_path(&obj, &namespace, name));
+
+        let after_replace = self.interceptor(|funcs| funcs.after_replace.clone());
+        let request = after_replace.is_some().then(|| obj.clone());
+        let manager = Self::field_manager(query);
+        let mut updated = handle_error!(self.execute_replace_with_interceptor(
+            &gvr,
+            obj,
+            &namespace,
+            name,
+            subresource,
+            manager.as_deref()
+        ));
+        if let (Some(after_replace), Some(object)) = (after_replace, &request) {
+            handle_error!(after_replace(interceptor::AfterReplaceContext {
+                client: &self.client,
+                object,
+                namespace: &namespace,
+                name,
+                is_status: subresource.is_some(),
+                response: &mut updated,
+            }));
+        }
 
         Self::success_response(updated)
     }
@@ -591,15 +1861,24 @@ impl MockService {
     async fn handle_patch(
         &self,
         path: &str,
+        query: Option<&str>,
         body: Bytes,
         content_type: Option<&str>,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
         let parsed = Self::parse_path(path).ok_or("Invalid path")?;
         let namespace = Self::extract_namespace(&parsed);
         let name = parsed.name.ok_or("Name required for PATCH")?;
 
-        let patch: Value = serde_json::from_slice(&body)?;
         let patch_type = Self::determine_patch_type(content_type);
+        // An empty body is an empty patch, which changes nothing
+        let patch: Value = if body.iter().all(u8::is_ascii_whitespace) {
+            match patch_type {
+                PatchType::JsonPatch => serde_json::json!([]),
+                _ => serde_json::json!({}),
+            }
+        } else {
+            serde_json::from_slice(&body)?
+        };
 
         let gvr = GVR::new(
             parsed.group.clone().unwrap_or_default(),
@@ -612,99 +1891,46 @@ impl MockService {
             &parsed.version,
             &parsed.resource
         ));
-        let gvk = crate::tracker::GVK::new(parsed.group.unwrap_or_default(), parsed.version, &kind);
-        let is_status = path.ends_with("/status");
+        let subresource = Self::status_subresource(parsed.subresource.as_deref());
+        let gvk = GVK::new(parsed.group.unwrap_or_default(), parsed.version, &kind);
 
         handle_error!(self.client.validate_verb(&gvk, "patch"));
 
-        let updated = if let Some(ref interceptors) = self.client.interceptors {
-            if is_status {
-                if let Some(ref patch_status_interceptor) = interceptors.patch_status {
-                    let ctx = interceptor::PatchStatusContext {
-                        client: &self.client,
-                        patch: &patch,
-                        namespace: &namespace,
-                        name: &name,
-                        params: &PatchParams::default(),
-                    };
-
-                    match patch_status_interceptor(ctx) {
-                        Ok(Some(result)) => result,
-                        Ok(None) => {
-                            let mut existing =
-                                handle_error!(self.client.tracker().get(&gvr, &namespace, &name));
-                            Self::apply_patch(&mut existing, &patch, patch_type)?;
-                            let gvk = extract_gvk(&existing)?;
-                            handle_error!(self
-                                .client
-                                .tracker()
-                                .update(&gvr, &gvk, existing, &namespace, true))
-                        }
-                        Err(e) => return Self::error_to_response(e),
-                    }
-                } else {
-                    let mut existing =
-                        handle_error!(self.client.tracker().get(&gvr, &namespace, &name));
-                    Self::apply_patch(&mut existing, &patch, patch_type)?;
-                    let gvk = extract_gvk(&existing)?;
-                    handle_error!(self
-                        .client
-                        .tracker()
-                        .update(&gvr, &gvk, existing, &namespace, true))
-                }
-            } else if let Some(ref patch_interceptor) = interceptors.patch {
-                let ctx = interceptor::PatchContext {
-                    client: &self.client,
-                    patch: &patch,
-                    namespace: &namespace,
-                    name: &name,
-                    params: &PatchParams::default(),
-                };
-
-                match patch_interceptor(ctx) {
-                    Ok(Some(result)) => result,
-                    Ok(None) => {
-                        let mut existing =
-                            handle_error!(self.client.tracker().get(&gvr, &namespace, &name));
-                        Self::apply_patch(&mut existing, &patch, patch_type)?;
-                        let gvk = extract_gvk(&existing)?;
-                        handle_error!(self
-                            .client
-                            .tracker()
-                            .update(&gvr, &gvk, existing, &namespace, false))
-                    }
-                    Err(e) => return Self::error_to_response(e),
-                }
-            } else {
-                let mut existing =
-                    handle_error!(self.client.tracker().get(&gvr, &namespace, &name));
-                Self::apply_patch(&mut existing, &patch, patch_type)?;
-                let gvk = extract_gvk(&existing)?;
-                handle_error!(self
-                    .client
-                    .tracker()
-                    .update(&gvr, &gvk, existing, &namespace, false))
-            }
-        } else {
-            let mut existing = handle_error!(self.client.tracker().get(&gvr, &namespace, &name));
-            Self::apply_patch(&mut existing, &patch, patch_type)?;
-            let gvk = extract_gvk(&existing)?;
-            handle_error!(self
-                .client
-                .tracker()
-                .update(&gvr, &gvk, existing, &namespace, is_status))
-        };
+        let manager = Self::field_manager(query);
+        let (mut updated, status) = handle_error!(self.execute_patch_with_interceptor(
+            &gvr,
+            &gvk,
+            &patch,
+            patch_type,
+            &namespace,
+            &name,
+            subresource,
+            manager.as_deref()
+        ));
+        if let Some(after_patch) = self.interceptor(|funcs| funcs.after_patch.clone()) {
+            handle_error!(after_patch(interceptor::AfterPatchContext {
+                client: &self.client,
+                patch: &patch,
+                namespace: &namespace,
+                name: &name,
+                is_status: subresource.is_some(),
+                response: &mut updated,
+            }));
+        }
 
-        Self::success_response(updated)
+        Self::success_response_with_status(updated, status)
     }
 
     async fn handle_delete(
         &self,
         path: &str,
         query: Option<&str>,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+        body: Bytes,
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
         let parsed = Self::parse_path(path).ok_or("Invalid path")?;
         let namespace = Self::extract_namespace(&parsed);
+        let grace_period_seconds = Self::parse_grace_period(query, &body);
+        let propagation = garbage_collector::propagation(query, &body);
 
         let gvr = GVR::new(
             parsed.group.clone().unwrap_or_default(),
@@ -717,17 +1943,21 @@ impl MockService {
             &parsed.version,
             &parsed.resource
         ));
-        let gvk = crate::tracker::GVK::new(
+        let gvk = GVK::new(
             parsed.group.clone().unwrap_or_default(),
             parsed.version.clone(),
             &kind,
         );
 
-        handle_error!(self.client.validate_verb(&gvk, "delete"));
+        let verb = match parsed.name {
+            Some(_) => "delete",
+            None => "deletecollection",
+        };
+        handle_error!(self.client.validate_verb(&gvk, verb));
 
         if let Some(name) = parsed.name {
             // Single object deletion
-            let deleted = if let Some(ref interceptors) = self.client.interceptors {
+            let mut deleted = if let Some(ref interceptors) = self.client.interceptors {
                 if let Some(ref delete_interceptor) = interceptors.delete {
                     let ctx = interceptor::DeleteContext {
                         client: &self.client,
@@ -737,74 +1967,168 @@ impl MockService {
 
                     match delete_interceptor(ctx) {
                         Ok(Some(result)) => result,
-                        Ok(None) => {
-                            handle_error!(self.client.tracker().delete(&gvr, &namespace, &name))
-                        }
+                        Ok(None) => handle_error!(self.execute_delete(
+                            &gvr,
+                            &namespace,
+                            &name,
+                            grace_period_seconds,
+                            propagation
+                        )),
                         Err(e) => return Self::error_to_response(e),
                     }
                 } else {
-                    handle_error!(self.client.tracker().delete(&gvr, &namespace, &name))
+                    handle_error!(self.execute_delete(
+                        &gvr,
+                        &namespace,
+                        &name,
+                        grace_period_seconds,
+                        propagation
+                    ))
                 }
             } else {
-                handle_error!(self.client.tracker().delete(&gvr, &namespace, &name))
+                handle_error!(self.execute_delete(
+                    &gvr,
+                    &namespace,
+                    &name,
+                    grace_period_seconds,
+                    propagation
+                ))
             };
+            if let Some(after_delete) = self.interceptor(|funcs| funcs.after_delete.clone()) {
+                handle_error!(after_delete(interceptor::AfterDeleteContext {
+                    client: &self.client,
+                    namespace: &namespace,
+                    name: &name,
+                    response: &mut deleted,
+                }));
+            }
 
             Self::success_response(deleted)
         } else {
             // Collection deletion
             let list_params = Self::parse_list_params(query);
-            let mut objects = handle_error!(self
-                .client
-                .tracker()
-                .list(&gvr, parsed.namespace.as_deref()));
+            let status_response = |deleted_count: usize| {
+                serde_json::json!({
+                    "kind": "Status",
+                    "apiVersion": "v1",
+                    "status": "Success",
+                    "details": {
+                        "kind": kind,
+                        "group": parsed.group.clone().unwrap_or_default(),
+                        "deleted": deleted_count
+                    }
+                })
+            };
+            if let Some(delete_collection) =
+                self.interceptor(|funcs| funcs.delete_collection.clone())
+            {
+                let ctx = interceptor::DeleteCollectionContext {
+                    client: &self.client,
+                    gvk: &gvk,
+                    namespace: parsed.namespace.as_deref(),
+                    params: &list_params,
+                };
+                if let Some(deleted) = handle_error!(delete_collection(ctx)) {
+                    return Self::success_response(status_response(deleted.len()));
+                }
+            }
+            // Deletes go to the current objects, even when reads of them lag
+            let mut objects = self.client.list_candidates(
+                &gvr,
+                &gvk,
+                parsed.namespace.as_deref(),
+                list_params.label_selector.as_deref(),
+                list_params.field_selector.as_deref(),
+            );
 
             // Apply selectors
             if let Some(label_selector) = &list_params.label_selector {
-                objects.retain(|obj| Self::matches_label_selector(obj, label_selector));
+                objects.retain(|obj| label_selector::matches_object(obj, label_selector));
             }
 
             if let Some(field_selector) = &list_params.field_selector {
-                objects.retain(|obj| Self::matches_field_selector(obj, field_selector));
+                objects.retain(|obj| self.matches_field_selector(&gvk, obj, field_selector));
             }
 
             // Delete each matching object
             let deleted_count = objects
                 .iter()
-                .filter_map(Self::extract_object_name)
+                .filter_map(|obj| Self::extract_object_name(obj))
                 .filter(|obj_name| {
-                    self.client
-                        .tracker()
-                        .delete(&gvr, &namespace, obj_name)
-                        .is_ok()
+                    self.execute_delete(
+                        &gvr,
+                        &namespace,
+                        obj_name,
+                        grace_period_seconds,
+                        propagation,
+                    )
+                    .is_ok()
                 })
                 .count();
 
-            let status_response = serde_json::json!({
-                "kind": "Status",
-                "apiVersion": "v1",
-                "status": "Success",
-                "details": {
-                    "kind": kind,
-                    "group": parsed.group.unwrap_or_default(),
-                    "deleted": deleted_count
-                }
-            });
-
-            Self::success_response(status_response)
+            Self::success_response(status_response(deleted_count))
         }
     }
 
+    /// Delete an object, giving Pods their termination grace period
+    ///
+    /// With the garbage collector, orphaning and foreground deletes leave the
+    /// object in deletion until its dependents are taken care of.
+    fn execute_delete(
+        &self,
+        gvr: &GVR,
+        namespace: &str,
+        name: &str,
+        grace_period_seconds: Option<i64>,
+        propagation: Propagation,
+    ) -> std::result::Result<Value, Error> {
+        self.client
+            .delete_object(gvr, namespace, name, grace_period_seconds, propagation)
+    }
+
+    /// The `fieldManager` query parameter of a request
+    fn field_manager(query: Option<&str>) -> Option<String> {
+        query.unwrap_or_default().split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == "fieldManager").then(|| {
+                urlencoding::decode(value)
+                    .map(|value| value.into_owned())
+                    .unwrap_or_else(|_| value.to_string())
+            })
+        })
+    }
+
+    /// Read `gracePeriodSeconds` from the DeleteOptions body or the query string
+    fn parse_grace_period(query: Option<&str>, body: &[u8]) -> Option<i64> {
+        let from_body = serde_json::from_slice::<Value>(body)
+            .ok()
+            .and_then(|options| options.get("gracePeriodSeconds")?.as_i64());
+        from_body.or_else(|| {
+            query?.split('&').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                (key == "gracePeriodSeconds")
+                    .then(|| value.parse().ok())
+                    .flatten()
+            })
+        })
+    }
+
     /// Convert crate::Error to proper HTTP response matching Kubernetes API format
-    fn error_to_response(
+    pub(crate) fn error_to_response(
         err: Error,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
+        let causes = err.causes().to_vec();
+        // Retry-After is in whole seconds; round up so clients never retry early
+        let retry_after = err
+            .retry_after()
+            .map(|delay| delay.as_secs() + u64::from(delay.subsec_nanos() > 0));
         let kube_err = err.into_kube_err();
 
         if let kube::Error::Api(error_response) = kube_err {
             let status_code = StatusCode::from_u16(error_response.code)
                 .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
 
-            let body = serde_json::json!({
+            let mut body = serde_json::json!({
                 "kind": "Status",
                 "apiVersion": "v1",
                 "status": error_response.status,
@@ -812,11 +2136,21 @@ impl MockService {
                 "reason": error_response.reason,
                 "code": error_response.code
             });
+            if !causes.is_empty() {
+                body["details"]["causes"] = serde_json::json!(causes);
+            }
+            if let Some(seconds) = retry_after {
+                body["details"]["retryAfterSeconds"] = serde_json::json!(seconds);
+            }
 
-            Ok(Response::builder()
+            let mut response = Response::builder()
                 .status(status_code)
-                .header("Content-Type", CONTENT_TYPE_JSON)
-                .body(Full::new(Bytes::from(body.to_string())))
+                .header("Content-Type", CONTENT_TYPE_JSON);
+            if let Some(seconds) = retry_after {
+                response = response.header("Retry-After", seconds);
+            }
+            Ok(response
+                .body(ResponseBody::full(body.to_string()))
                 .expect("Failed to build response"))
         } else {
             Self::error_response(StatusCode::INTERNAL_SERVER_ERROR, &kube_err.to_string())
@@ -826,7 +2160,7 @@ impl MockService {
     fn error_response(
         status: StatusCode,
         message: &str,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
         let body = serde_json::json!({
             "kind": "Status",
             "apiVersion": "v1",
@@ -838,30 +2172,30 @@ impl MockService {
         Ok(Response::builder()
             .status(status)
             .header("Content-Type", CONTENT_TYPE_JSON)
-            .body(Full::new(Bytes::from(body.to_string())))
+            .body(ResponseBody::full(body.to_string()))
             .expect("Failed to build response"))
     }
 
     fn success_response(
         data: Value,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
         Self::success_response_with_status(data, StatusCode::OK)
     }
 
     fn success_response_with_status(
         data: Value,
         status: StatusCode,
-    ) -> std::result::Result<Response<Full<Bytes>>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> std::result::Result<Response<ResponseBody>, Box<dyn std::error::Error + Send + Sync>> {
         Ok(Response::builder()
             .status(status)
             .header("Content-Type", CONTENT_TYPE_JSON)
-            .body(Full::new(Bytes::from(data.to_string())))
+            .body(ResponseBody::full(data.to_string()))
             .expect("Failed to build response"))
     }
 }
 
 impl Service<Request<KubeBody>> for MockService {
-    type Response = Response<Full<Bytes>>;
+    type Response = Response<ResponseBody>;
     type Error = Box<dyn std::error::Error + Send + Sync>;
     type Future = BoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
 
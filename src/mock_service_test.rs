@@ -1,6 +1,12 @@
 //! Tests for mock_service.rs functionality including:
 //! - Patch type handling (JSON Patch, Merge Patch, Strategic Merge Patch, Apply Patch)
+//! - Server-Side Apply creating missing objects
+//! - No-op patches leaving the resourceVersion unchanged
 //! - Cluster-scoped resource support (Nodes, ClusterRoles, etc.)
+//! - Validation of the object's name and namespace against the request path
+//! - Interceptor hooks run after successful writes
+//! - Requests for features the fake doesn't implement
+//! - 405 responses and their Allow header
 
 #[cfg(test)]
 mod tests {
@@ -298,6 +304,77 @@ mod tests {
         assert_eq!(labels.get("managed-by").unwrap(), "kubectl");
     }
 
+    /// Server-Side Apply creates a missing object; other patch types don't
+    #[tokio::test]
+    async fn test_apply_patch_creates_missing_object() {
+        let (client, handle) = ClientBuilder::new()
+            .with_return_managed_fields()
+            .build_with_handle()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+
+        let merge = pods
+            .patch(
+                "web",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "metadata": { "labels": { "app": "web" } } })),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(merge, kube::Error::Api(ref e) if e.code == 404));
+
+        let apply = json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "labels": { "app": "web" } },
+            "spec": { "containers": [{ "name": "app", "image": "app:1" }] }
+        });
+        let created = pods
+            .patch(
+                "web",
+                &PatchParams::apply("deployer"),
+                &Patch::Apply(&apply),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.metadata.name.as_deref(), Some("web"));
+        assert_eq!(created.metadata.namespace.as_deref(), Some("default"));
+        assert!(created.metadata.uid.is_some());
+        let managed = created.metadata.managed_fields.unwrap();
+        assert_eq!(managed[0].manager.as_deref(), Some("deployer"));
+        assert_eq!(managed[0].operation.as_deref(), Some("Apply"));
+        let statuses: Vec<u16> = handle.audit_entries().iter().map(|e| e.status).collect();
+        assert_eq!(statuses, [404, 201]);
+
+        // Applying again updates the object it created
+        let updated = pods
+            .patch(
+                "web",
+                &PatchParams::apply("deployer"),
+                &Patch::Apply(&apply),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.metadata.uid, created.metadata.uid);
+        assert_eq!(handle.audit_entries()[2].status, 200);
+
+        // The applied configuration must name the object on the URL
+        let err = pods
+            .patch(
+                "other",
+                &PatchParams::apply("deployer"),
+                &Patch::Apply(json!({
+                    "apiVersion": "v1",
+                    "kind": "Pod",
+                    "metadata": { "name": "web" }
+                })),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(ref e) if e.code == 400));
+    }
+
     /// Test that different patch types behave differently
     #[tokio::test]
     async fn test_patch_type_differences() {
@@ -498,6 +575,66 @@ mod tests {
         assert!(new_rv.parse::<u64>().unwrap() > original_rv.parse::<u64>().unwrap());
     }
 
+    /// Test that a patch leaving the object unchanged is not written
+    #[tokio::test]
+    async fn test_noop_patch_keeps_resource_version() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client.clone(), "default");
+
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("test-pod".to_string());
+        pod.metadata.labels = Some([("app".to_string(), "web".to_string())].into());
+        let created = pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        let same_label = json!({"metadata": {"labels": {"app": "web"}}});
+        let patched = pods
+            .patch(
+                "test-pod",
+                &PatchParams::default(),
+                &Patch::Merge(&same_label),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            patched.metadata.resource_version,
+            created.metadata.resource_version
+        );
+        assert_eq!(patched.metadata.generation, created.metadata.generation);
+
+        // An empty body is an empty patch
+        let request = http::Request::patch("/api/v1/namespaces/default/pods/test-pod")
+            .header("content-type", "application/merge-patch+json")
+            .body(kube::client::Body::empty())
+            .unwrap();
+        let response = client.send(request).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        // Replacing the object with itself changes nothing either
+        let replaced = pods
+            .replace("test-pod", &PostParams::default(), &patched)
+            .await
+            .unwrap();
+        assert_eq!(
+            replaced.metadata.resource_version,
+            created.metadata.resource_version
+        );
+        assert!(handle
+            .audit_entries()
+            .iter()
+            .skip(1)
+            .all(|entry| !entry.is_mutation()));
+
+        let changed = json!({"metadata": {"labels": {"app": "api"}}});
+        let patched = pods
+            .patch("test-pod", &PatchParams::default(), &Patch::Merge(&changed))
+            .await
+            .unwrap();
+        assert_ne!(
+            patched.metadata.resource_version,
+            created.metadata.resource_version
+        );
+    }
+
     // ============================================================================
     // Cluster-Scoped Resource Tests
     // ============================================================================
@@ -806,6 +943,98 @@ mod tests {
         );
     }
 
+    /// Test that listing across namespaces with a metadata.namespace selector
+    /// returns what listing the namespace does, reading only that namespace
+    #[tokio::test]
+    async fn test_field_selector_metadata_namespace_reads_one_namespace() {
+        use crate::store::{MemoryStore, ObjectStore, StoredObject};
+        use crate::GVR;
+        use kube::api::ListParams;
+        use parking_lot::Mutex;
+        use std::sync::Arc;
+
+        /// A store recording the namespaces lists read
+        #[derive(Default)]
+        struct Recording {
+            inner: MemoryStore,
+            listed: Arc<Mutex<Vec<Option<String>>>>,
+        }
+
+        impl ObjectStore for Recording {
+            fn get(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+                self.inner.get(gvr, namespace, name)
+            }
+
+            fn list(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<(String, StoredObject)> {
+                self.listed.lock().push(namespace.map(str::to_string));
+                self.inner.list(gvr, namespace)
+            }
+
+            fn collections(&self) -> Vec<(GVR, String)> {
+                self.inner.collections()
+            }
+
+            fn insert(&self, gvr: &GVR, namespace: &str, name: &str, object: StoredObject) {
+                self.inner.insert(gvr, namespace, name, object)
+            }
+
+            fn remove(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+                self.inner.remove(gvr, namespace, name)
+            }
+        }
+
+        let store = Recording::default();
+        let listed = Arc::clone(&store.listed);
+        let client = ClientBuilder::new()
+            .with_object_store(store)
+            .build()
+            .await
+            .unwrap();
+        for namespace in ["a", "b", "c"] {
+            let pods: kube::Api<Pod> = kube::Api::namespaced(client.clone(), namespace);
+            for (name, tier) in [("web", "frontend"), ("db", "backend"), ("cache", "backend")] {
+                let mut pod = Pod::default();
+                pod.metadata.name = Some(name.to_string());
+                pod.metadata.labels = Some([("tier".to_string(), tier.to_string())].into());
+                pods.create(&PostParams::default(), &pod).await.unwrap();
+            }
+        }
+
+        let names = |pods: Vec<Pod>| -> Vec<(String, String)> {
+            pods.into_iter()
+                .map(|pod| (pod.metadata.namespace.unwrap(), pod.metadata.name.unwrap()))
+                .collect()
+        };
+        let all: kube::Api<Pod> = kube::Api::all(client.clone());
+        let namespaced: kube::Api<Pod> = kube::Api::namespaced(client, "b");
+        for (labels, fields) in [
+            ("", ""),
+            ("tier=backend", ""),
+            ("tier!=backend", "metadata.name!=web"),
+            ("", "metadata.name=db"),
+        ] {
+            let params = ListParams::default().labels(labels).fields(fields);
+            let expected = names(namespaced.list(&params).await.unwrap().items);
+
+            let fields = match fields {
+                "" => "metadata.namespace=b".to_string(),
+                fields => format!("metadata.namespace==b,{fields}"),
+            };
+            listed.lock().clear();
+            let params = ListParams::default().labels(labels).fields(&fields);
+            assert_eq!(
+                names(all.list(&params).await.unwrap().items),
+                expected,
+                "labels {labels:?}, fields {fields:?}"
+            );
+            assert_eq!(*listed.lock(), [Some("b".to_string())]);
+        }
+
+        // Selecting a namespace that differs from the request's finds nothing
+        let params = ListParams::default().fields("metadata.namespace=a");
+        assert!(namespaced.list(&params).await.unwrap().items.is_empty());
+    }
+
     /// Test field selector spec.nodeName (Pod-specific pre-registered field)
     #[tokio::test]
     async fn test_field_selector_spec_nodename_http() {
@@ -1102,7 +1331,7 @@ mod tests {
 
         // Delete specific pod by name using field selector
         let params = kube::api::ListParams::default().fields("metadata.name=pod-2");
-        pods.delete_collection(&kube::api::DeleteParams::default(), &params)
+        pods.delete_collection(&kube::api::DeleteParams::default().grace_period(0), &params)
             .await
             .unwrap();
 
@@ -1164,7 +1393,7 @@ mod tests {
         let params = kube::api::ListParams::default()
             .labels("app=nginx")
             .fields("spec.nodeName=node-1");
-        pods.delete_collection(&kube::api::DeleteParams::default(), &params)
+        pods.delete_collection(&kube::api::DeleteParams::default().grace_period(0), &params)
             .await
             .unwrap();
 
@@ -1185,6 +1414,89 @@ mod tests {
             .any(|p| p.metadata.name == Some("pod-4".to_string())));
     }
 
+    /// Test delete collection interceptors seeing parsed selectors
+    #[tokio::test]
+    async fn test_delete_collection_interceptor() {
+        use crate::interceptor::{self, FieldRequirement};
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        let funcs = interceptor::Funcs::new().delete_collection(move |ctx| {
+            let selector = ctx.label_selector()?;
+            recorded.lock().unwrap().push((
+                ctx.gvk.kind.clone(),
+                ctx.namespace.map(str::to_string),
+                selector.is_some(),
+                ctx.field_selector(),
+            ));
+            match selector {
+                Some(_) => Ok(None),
+                None if ctx.field_selector().is_empty() => Err(crate::Error::Forbidden(
+                    "deletecollection needs a selector".to_string(),
+                )),
+                None => Ok(Some(Vec::new())),
+            }
+        });
+        let client = ClientBuilder::new()
+            .with_interceptor_funcs(funcs)
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        for name in ["web", "db"] {
+            let mut pod = Pod::default();
+            pod.metadata.name = Some(name.to_string());
+            pod.metadata.labels = Some([("app".to_string(), name.to_string())].into());
+            pods.create(&PostParams::default(), &pod).await.unwrap();
+        }
+        let delete = kube::api::DeleteParams::default();
+
+        // Without a selector the interceptor refuses
+        let err = pods
+            .delete_collection(&delete, &kube::api::ListParams::default())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, kube::Error::Api(ref e) if e.code == 403),
+            "{err}"
+        );
+
+        // An override leaves the objects stored
+        let by_field = kube::api::ListParams::default().fields("metadata.name!=web");
+        pods.delete_collection(&delete, &by_field).await.unwrap();
+        let list = pods.list(&kube::api::ListParams::default()).await.unwrap();
+        assert_eq!(list.items.len(), 2);
+
+        // Otherwise the delete goes ahead
+        let by_label = kube::api::ListParams::default().labels("app=web");
+        pods.delete_collection(&delete, &by_label).await.unwrap();
+        let list = pods.list(&kube::api::ListParams::default()).await.unwrap();
+        assert_eq!(list.items.len(), 1);
+        assert_eq!(list.items[0].metadata.name.as_deref(), Some("db"));
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(
+            seen[0],
+            (
+                "Pod".to_string(),
+                Some("default".to_string()),
+                false,
+                vec![]
+            )
+        );
+        assert_eq!(
+            seen[1].3,
+            vec![FieldRequirement {
+                field: "metadata.name".to_string(),
+                value: "web".to_string(),
+                negated: true,
+            }]
+        );
+        assert!(seen[2].2);
+    }
+
     /// Test delete collection on cluster-scoped resources
     #[tokio::test]
     async fn test_delete_collection_cluster_scoped() {
@@ -1252,4 +1564,793 @@ mod tests {
         assert_eq!(list.items.len(), 1);
         assert_eq!(list.items[0].metadata.name, Some("test-pod".to_string()));
     }
+
+    // ============================================================================
+    // Status Subresource Tests
+    // ============================================================================
+
+    fn status_test_pod() -> Pod {
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("status-pod".to_string());
+        pod.metadata.labels = Some([("app".to_string(), "web".to_string())].into());
+        pod.spec = Some(k8s_openapi::api::core::v1::PodSpec {
+            node_name: Some("node-a".to_string()),
+            ..Default::default()
+        });
+        pod
+    }
+
+    /// Test replace_status only modifies the status field
+    #[tokio::test]
+    async fn test_replace_status_only_modifies_status() {
+        let client = ClientBuilder::new()
+            .with_status_subresource::<Pod>()
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        let created = pods
+            .create(&PostParams::default(), &status_test_pod())
+            .await
+            .unwrap();
+
+        let mut modified = created.clone();
+        modified.metadata.labels = Some([("app".to_string(), "changed".to_string())].into());
+        modified.spec.as_mut().unwrap().node_name = Some("node-b".to_string());
+        modified.status = Some(k8s_openapi::api::core::v1::PodStatus {
+            phase: Some("Running".to_string()),
+            ..Default::default()
+        });
+
+        let updated = pods
+            .replace_status(
+                "status-pod",
+                &PostParams::default(),
+                serde_json::to_vec(&modified).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            updated.status.as_ref().unwrap().phase,
+            Some("Running".to_string())
+        );
+        assert_eq!(
+            updated.spec.as_ref().unwrap().node_name,
+            Some("node-a".to_string())
+        );
+        assert_eq!(
+            updated
+                .metadata
+                .labels
+                .as_ref()
+                .unwrap()
+                .get("app")
+                .unwrap(),
+            "web"
+        );
+        assert_eq!(updated.metadata.generation, created.metadata.generation);
+        assert_ne!(
+            updated.metadata.resource_version,
+            created.metadata.resource_version
+        );
+    }
+
+    /// Test patch_status ignores changes outside of status, for every merge-style patch type
+    #[tokio::test]
+    async fn test_patch_status_only_modifies_status() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        let created = pods
+            .create(&PostParams::default(), &status_test_pod())
+            .await
+            .unwrap();
+
+        let patch = json!({
+            "metadata": { "labels": { "app": "changed" } },
+            "spec": { "nodeName": "node-b" },
+            "status": { "phase": "Running" }
+        });
+
+        for patch in [Patch::Merge(patch.clone()), Patch::Strategic(patch.clone())] {
+            let updated = pods
+                .patch_status("status-pod", &PatchParams::default(), &patch)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                updated.status.as_ref().unwrap().phase,
+                Some("Running".to_string())
+            );
+            assert_eq!(
+                updated.spec.as_ref().unwrap().node_name,
+                Some("node-a".to_string())
+            );
+            assert_eq!(
+                updated
+                    .metadata
+                    .labels
+                    .as_ref()
+                    .unwrap()
+                    .get("app")
+                    .unwrap(),
+                "web"
+            );
+            assert_eq!(updated.metadata.generation, created.metadata.generation);
+        }
+    }
+
+    /// Test writes to the main resource can't set a status the object doesn't have
+    #[tokio::test]
+    async fn test_main_writes_do_not_add_a_status() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        pods.create(&PostParams::default(), &status_test_pod())
+            .await
+            .unwrap();
+
+        let patch = json!({
+            "metadata": { "labels": { "app": "changed" } },
+            "status": { "phase": "Running" }
+        });
+        let updated = pods
+            .patch("status-pod", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert_eq!(updated.metadata.labels.as_ref().unwrap()["app"], "changed");
+        assert!(updated.status.is_none_or(|status| status.phase.is_none()));
+    }
+
+    /// Test status isolation is identical when interceptors are configured but don't override
+    #[tokio::test]
+    async fn test_status_isolation_with_passthrough_interceptors() {
+        let client = ClientBuilder::new()
+            .with_interceptor_funcs(
+                crate::interceptor::Funcs::new()
+                    .replace(|_| Ok(None))
+                    .patch(|_| Ok(None)),
+            )
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        let created = pods
+            .create(&PostParams::default(), &status_test_pod())
+            .await
+            .unwrap();
+
+        let mut modified = created.clone();
+        modified.spec.as_mut().unwrap().node_name = Some("node-b".to_string());
+        modified.status = Some(k8s_openapi::api::core::v1::PodStatus {
+            phase: Some("Succeeded".to_string()),
+            ..Default::default()
+        });
+        let updated = pods
+            .replace_status(
+                "status-pod",
+                &PostParams::default(),
+                serde_json::to_vec(&modified).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            updated.status.as_ref().unwrap().phase,
+            Some("Succeeded".to_string())
+        );
+        assert_eq!(
+            updated.spec.as_ref().unwrap().node_name,
+            Some("node-a".to_string())
+        );
+        assert_eq!(updated.metadata.generation, created.metadata.generation);
+    }
+
+    // ============================================================================
+    // Immutable Field Tests
+    // ============================================================================
+
+    /// Immutable nested paths are enforced for HTTP patches
+    #[tokio::test]
+    async fn test_patch_immutable_nested_field_rejected() {
+        use k8s_openapi::api::batch::v1::Job;
+
+        let client = ClientBuilder::new().build().await.unwrap();
+        let jobs: kube::Api<Job> = kube::Api::namespaced(client, "default");
+
+        let job: Job = serde_json::from_value(json!({
+            "apiVersion": "batch/v1",
+            "kind": "Job",
+            "metadata": { "name": "migrate" },
+            "spec": { "template": { "spec": {
+                "nodeSelector": { "pool": "batch" },
+                "containers": [{ "name": "main", "image": "migrate:1" }]
+            } } }
+        }))
+        .unwrap();
+        jobs.create(&PostParams::default(), &job).await.unwrap();
+
+        let patch = json!({
+            "spec": { "template": { "spec": { "nodeSelector": { "pool": "general" } } } }
+        });
+        let err = jobs
+            .patch("migrate", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap_err();
+        match err {
+            kube::Error::Api(resp) => {
+                assert_eq!(resp.code, 422);
+                assert!(resp.message.contains("spec.template.spec.nodeSelector"));
+            }
+            e => panic!("Expected API error, got: {:?}", e),
+        }
+
+        // Mutable fields can still be patched
+        let patch = json!({ "metadata": { "labels": { "team": "data" } } });
+        jobs.patch("migrate", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+    }
+
+    /// Immutable nested paths are enforced for HTTP replaces
+    #[tokio::test]
+    async fn test_replace_immutable_nested_field_rejected() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+
+        let mut pod = status_test_pod();
+        pod.metadata.name = Some("immutable-pod".to_string());
+        let created = pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        let mut updated = created.clone();
+        updated.spec.as_mut().unwrap().node_name = Some("node-b".to_string());
+        let err = pods
+            .replace("immutable-pod", &PostParams::default(), &updated)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+    }
+
+    // ============================================================================
+    // Path Validation Tests
+    // ============================================================================
+
+    fn expect_bad_request<T: std::fmt::Debug>(result: Result<T, kube::Error>, message: &str) {
+        match result {
+            Err(kube::Error::Api(resp)) => {
+                assert_eq!(resp.code, 400);
+                assert_eq!(resp.reason, "BadRequest");
+                assert!(resp.message.contains(message), "{}", resp.message);
+            }
+            other => panic!("Expected 400 Bad Request, got: {:?}", other),
+        }
+    }
+
+    /// A replace whose body names another object than the URL is rejected
+    #[tokio::test]
+    async fn test_replace_name_mismatch_rejected() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+
+        let mut pod = status_test_pod();
+        pod.metadata.name = Some("web".to_string());
+        let created = pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        let mut renamed = created.clone();
+        renamed.metadata.name = Some("api".to_string());
+        expect_bad_request(
+            pods.replace("web", &PostParams::default(), &renamed).await,
+            "the name of the object (api) does not match the name on the URL (web)",
+        );
+
+        let mut moved = created;
+        moved.metadata.namespace = Some("other".to_string());
+        expect_bad_request(
+            pods.replace("web", &PostParams::default(), &moved).await,
+            "the namespace of the provided object does not match the namespace sent on the request",
+        );
+    }
+
+    /// A patch that changes the object's name is rejected
+    #[tokio::test]
+    async fn test_patch_name_change_rejected() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+
+        let mut pod = status_test_pod();
+        pod.metadata.name = Some("web".to_string());
+        pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        let patch = json!({ "metadata": { "name": "api" } });
+        expect_bad_request(
+            pods.patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+                .await,
+            "does not match the name on the URL (web)",
+        );
+        assert!(pods.get_opt("api").await.unwrap().is_none());
+    }
+
+    /// The check can be turned off for tests relying on the lenient behavior
+    #[tokio::test]
+    async fn test_path_mismatch_allowed() {
+        let client = ClientBuilder::new()
+            .with_path_mismatch_allowed()
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+
+        let mut pod = status_test_pod();
+        pod.metadata.name = Some("web".to_string());
+        let created = pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        // The rename reaches storage, which refuses it as before
+        let mut renamed = created;
+        renamed.metadata.name = Some("api".to_string());
+        let err = pods
+            .replace("web", &PostParams::default(), &renamed)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+    }
+
+    // ============================================================================
+    // Unsupported Request Tests
+    // ============================================================================
+
+    /// Requests for features the fake lacks fail with 501 rather than returning
+    /// the wrong data
+    #[tokio::test]
+    async fn test_unsupported_requests() {
+        use http_body_util::BodyExt;
+        use kube::api::LogParams;
+        use kube::client::Body;
+
+        let client = ClientBuilder::new()
+            .with_object(status_test_pod())
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client.clone(), "default");
+
+        let err = pods
+            .logs("status-pod", &LogParams::default())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, kube::Error::Api(resp) if resp.code == 501
+                && resp.message == r#"not supported by kube-fake-client: the "log" subresource of pods"#),
+            "{err:?}"
+        );
+
+        let unsupported = [
+            (
+                "/api/v1/namespaces/default/pods/status-pod",
+                "application/vnd.kubernetes.protobuf",
+                r#"responses of type "application/vnd.kubernetes.protobuf""#,
+            ),
+            (
+                "/api/v1/namespaces/default/pods/status-pod",
+                "application/json;as=Table;v=v1;g=meta.k8s.io",
+                r#"responses of type "application/json;as=Table;v=v1;g=meta.k8s.io""#,
+            ),
+            (
+                "/apis/apps/v1/namespaces/default/deployments/web/scale",
+                "application/json",
+                r#"the "scale" subresource of deployments"#,
+            ),
+            (
+                "/api/v1/namespaces/default/pods?watch=true&sendInitialEvents=true",
+                "application/json",
+                "watches with sendInitialEvents",
+            ),
+            (
+                "/api/v1/namespaces/default",
+                "application/json",
+                r#"the path "/api/v1/namespaces/default""#,
+            ),
+        ];
+        for (uri, accept, feature) in unsupported {
+            let request = http::Request::get(uri)
+                .header("accept", accept)
+                .body(Body::empty())
+                .unwrap();
+            let response = client.send(request).await.unwrap();
+            assert_eq!(response.status(), 501, "{uri}");
+            let bytes = response.into_body().collect().await.unwrap().to_bytes();
+            let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(
+                body["message"],
+                format!("not supported by kube-fake-client: {feature}")
+            );
+        }
+
+        // Tables can still fall back to JSON
+        let request = http::Request::get("/api/v1/namespaces/default/pods/status-pod")
+            .header(
+                "accept",
+                "application/json;as=Table;v=v1;g=meta.k8s.io,application/json",
+            )
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(client.send(request).await.unwrap().status(), 200);
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = r#"not supported by kube-fake-client: the "log" subresource of pods"#
+    )]
+    async fn test_panic_on_unsupported() {
+        let client = ClientBuilder::new()
+            .with_object(status_test_pod())
+            .with_panic_on_unsupported()
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        let _ = pods.logs("status-pod", &Default::default()).await;
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_api_errors() {
+        use crate::{interceptor, Error};
+        use http_body_util::BodyExt;
+        use kube::client::Body;
+        use std::time::Duration;
+
+        let client = ClientBuilder::new()
+            .with_object(status_test_pod())
+            .with_interceptor_funcs(
+                interceptor::Funcs::new()
+                    .create(|_| {
+                        Err(
+                            Error::api(422, "Invalid", r#"Pod "web" is invalid"#).with_cause(
+                                "spec.containers[0].image",
+                                "FieldValueRequired",
+                                "Required value",
+                            ),
+                        )
+                    })
+                    .get(|_| {
+                        Err(Error::TooManyRequests("slow down".to_string())
+                            .with_retry_after(Duration::from_millis(1500)))
+                    }),
+            )
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client.clone(), "default");
+
+        let mut web = Pod::default();
+        web.metadata.name = Some("web".to_string());
+        let err = pods.create(&PostParams::default(), &web).await.unwrap_err();
+        assert!(
+            matches!(&err, kube::Error::Api(resp) if resp.code == 422
+                && resp.reason == "Invalid"
+                && resp.message == r#"Pod "web" is invalid"#),
+            "{err:?}"
+        );
+
+        let request = http::Request::post("/api/v1/namespaces/default/pods")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&web).unwrap()))
+            .unwrap();
+        let response = client.send(request).await.unwrap();
+        assert_eq!(response.status(), 422);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            body["details"]["causes"],
+            json!([{
+                "field": "spec.containers[0].image",
+                "reason": "FieldValueRequired",
+                "message": "Required value",
+            }])
+        );
+
+        // Retry-After is rounded up to whole seconds
+        let request = http::Request::get("/api/v1/namespaces/default/pods/status-pod")
+            .body(Body::empty())
+            .unwrap();
+        let response = client.send(request).await.unwrap();
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()["retry-after"], "2");
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["reason"], "TooManyRequests");
+        assert_eq!(body["message"], "slow down");
+        assert_eq!(body["details"]["retryAfterSeconds"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_method_not_allowed_lists_allowed_methods() {
+        use http_body_util::BodyExt;
+        use kube::client::Body;
+
+        let client = ClientBuilder::new().build().await.unwrap();
+        let send = |method: &str, path: &str| {
+            let request = http::Request::builder()
+                .method(method)
+                .uri(path)
+                .header("content-type", "application/json")
+                .body(Body::from(b"{}".to_vec()))
+                .unwrap();
+            let client = client.clone();
+            async move { client.send(request).await.unwrap() }
+        };
+
+        // Methods a path of that shape never takes
+        let response = send("PUT", "/api/v1/namespaces/default/configmaps").await;
+        assert_eq!(response.status(), 405);
+        assert_eq!(response.headers()["allow"], "GET, POST, DELETE");
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["reason"], "MethodNotAllowed");
+
+        let response = send("POST", "/api/v1/namespaces/default/configmaps/settings").await;
+        assert_eq!(response.status(), 405);
+        assert_eq!(response.headers()["allow"], "GET, PUT, PATCH, DELETE");
+
+        let response = send("DELETE", "/api/v1/namespaces/default/pods/web/status").await;
+        assert_eq!(response.status(), 405);
+        assert_eq!(response.headers()["allow"], "GET, PUT, PATCH");
+
+        // Verbs the resource doesn't support, collection deletes included
+        let response = send("POST", "/api/v1/componentstatuses").await;
+        assert_eq!(response.status(), 405);
+        assert_eq!(response.headers()["allow"], "GET");
+        let response = send("DELETE", "/api/v1/componentstatuses").await;
+        assert_eq!(response.status(), 405);
+
+        let response = send(
+            "GET",
+            "/apis/authorization.k8s.io/v1/selfsubjectaccessreviews",
+        )
+        .await;
+        assert_eq!(response.status(), 405);
+        assert_eq!(response.headers()["allow"], "POST");
+    }
+
+    // ============================================================================
+    // After Hook Tests
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_after_hooks() {
+        use crate::{interceptor, Error};
+        use kube::api::DeleteParams;
+        use std::sync::{Arc, Mutex};
+
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let (after_replace, after_delete) = (Arc::clone(&recorded), Arc::clone(&recorded));
+        let client = ClientBuilder::new()
+            .with_interceptor_funcs(
+                interceptor::Funcs::new()
+                    // Every pod must carry a team label
+                    .after_create(|ctx| match ctx.response.pointer("/metadata/labels/team") {
+                        Some(_) => Ok(()),
+                        None => Err(Error::InvalidRequest(format!(
+                            "pod {} has no team label",
+                            ctx.object["metadata"]["name"]
+                        ))),
+                    })
+                    .after_patch(|ctx| {
+                        assert_eq!(ctx.patch["metadata"]["labels"]["tier"], "web");
+                        ctx.response["metadata"]["annotations"] = json!({ "seen": "true" });
+                        Ok(())
+                    })
+                    .after_replace(move |ctx| {
+                        after_replace
+                            .lock()
+                            .unwrap()
+                            .push(format!("replace {} status={}", ctx.name, ctx.is_status));
+                        Ok(())
+                    })
+                    .after_delete(move |ctx| {
+                        after_delete
+                            .lock()
+                            .unwrap()
+                            .push(format!("delete {}/{}", ctx.namespace, ctx.name));
+                        Ok(())
+                    }),
+            )
+            .build()
+            .await
+            .unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("status-pod".to_string());
+        let err = pods.create(&PostParams::default(), &pod).await.unwrap_err();
+        assert!(
+            matches!(&err, kube::Error::Api(resp) if resp.message == "pod \"status-pod\" has no team label"),
+            "{err:?}"
+        );
+        // The hook fails the request, but the write is kept
+        pods.delete("status-pod", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        pod.metadata.labels = Some([("team".to_string(), "a".to_string())].into());
+        pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        let patched = pods
+            .patch(
+                "status-pod",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "metadata": { "labels": { "tier": "web" } } })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            patched.metadata.annotations.unwrap()["seen"],
+            "true".to_string()
+        );
+        let stored = pods.get("status-pod").await.unwrap();
+        assert!(stored.metadata.annotations.is_none());
+
+        let stored = pods
+            .replace_status(
+                "status-pod",
+                &PostParams::default(),
+                serde_json::to_vec(&stored).unwrap(),
+            )
+            .await
+            .unwrap();
+        pods.replace("status-pod", &PostParams::default(), &stored)
+            .await
+            .unwrap();
+        pods.delete("status-pod", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *recorded.lock().unwrap(),
+            [
+                "delete default/status-pod",
+                "replace status-pod status=true",
+                "replace status-pod status=false",
+                "delete default/status-pod",
+            ]
+        );
+    }
+
+    // ============================================================================
+    // Instrumentation Tests
+    // ============================================================================
+
+    #[cfg(feature = "instrumentation")]
+    mod instrumentation {
+        use super::*;
+        use std::collections::HashMap;
+        use std::fmt::Debug;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        type Fields = HashMap<String, String>;
+
+        impl Visit for FieldsVisitor<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                self.0
+                    .insert(field.name().to_string(), format!("{value:?}"));
+            }
+
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.insert(field.name().to_string(), value.to_string());
+            }
+        }
+
+        struct FieldsVisitor<'a>(&'a mut Fields);
+
+        /// Keeps every span and event, with their fields
+        #[derive(Clone, Default)]
+        struct Recorder(Arc<Records>);
+
+        #[derive(Default)]
+        struct Records {
+            next_id: AtomicU64,
+            spans: Mutex<HashMap<u64, (&'static str, Fields)>>,
+            events: Mutex<Vec<Fields>>,
+        }
+
+        impl std::ops::Deref for Recorder {
+            type Target = Records;
+
+            fn deref(&self) -> &Records {
+                &self.0
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+                let mut fields = Fields::new();
+                span.record(&mut FieldsVisitor(&mut fields));
+                self.spans
+                    .lock()
+                    .unwrap()
+                    .insert(id, (span.metadata().name(), fields));
+                Id::from_u64(id)
+            }
+
+            fn record(&self, span: &Id, values: &Record<'_>) {
+                if let Some((_, fields)) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+                    values.record(&mut FieldsVisitor(fields));
+                }
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Fields::new();
+                event.record(&mut FieldsVisitor(&mut fields));
+                self.events.lock().unwrap().push(fields);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        impl Records {
+            fn spans_named(&self, name: &str) -> Vec<Fields> {
+                self.spans
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .filter(|(span, _)| *span == name)
+                    .map(|(_, fields)| fields.clone())
+                    .collect()
+            }
+        }
+
+        #[tokio::test]
+        async fn test_requests_are_traced_with_outcome() {
+            let recorder = Recorder::default();
+            let _guard = tracing::subscriber::set_default(recorder.clone());
+
+            let client = ClientBuilder::new().build().await.unwrap();
+            let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+            let mut pod = Pod::default();
+            pod.metadata.name = Some("traced".to_string());
+            pods.create(&PostParams::default(), &pod).await.unwrap();
+            pods.get("missing").await.unwrap_err();
+
+            let requests = recorder.spans_named("request");
+            let create = requests
+                .iter()
+                .find(|fields| fields["verb"] == "create")
+                .unwrap();
+            assert_eq!(create["method"], "POST");
+            assert_eq!(create["resource"], "pods");
+            assert_eq!(create["namespace"], "default");
+            assert_eq!(create["status"], "201");
+            assert_eq!(create["outcome"], "Success");
+            assert!(create.contains_key("duration_ms"));
+
+            let get = requests
+                .iter()
+                .find(|fields| fields["verb"] == "get")
+                .unwrap();
+            assert_eq!(get["name"], "missing");
+            assert_eq!(get["status"], "404");
+            assert_eq!(get["outcome"], "NotFound");
+
+            // Storage calls are traced within the request
+            let creates = recorder.spans_named("create");
+            assert_eq!(creates.len(), 1);
+            assert_eq!(creates[0]["resource"], "pods");
+
+            let events = recorder.events.lock().unwrap();
+            assert!(events
+                .iter()
+                .any(|fields| fields.get("message").map(String::as_str) == Some("request served")));
+        }
+    }
 }
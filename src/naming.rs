@@ -0,0 +1,249 @@
+//! Rules for object names and namespaces
+//!
+//! Rules added with [`ClientBuilder::with_metadata_rule`](crate::ClientBuilder::with_metadata_rule)
+//! check the metadata of every object created through the fake, initial objects
+//! included. A violation fails the create with 422 Invalid, the way the API server
+//! rejects a malformed name, so fixtures and controllers that break a project's
+//! naming conventions fail tests early.
+//!
+//! [`StandardNames`] is a ready-made rule with the API server's own checks: names
+//! of most kinds must be DNS-1123 subdomains, Namespace names DNS-1123 labels, and
+//! RBAC names valid path segments.
+//!
+//! [`pluralize`] and [`singularize`] map kinds to the resource names in request
+//! paths and back, as discovery does for built-in resources. A [`Pluralizer`]
+//! takes overrides for kinds the rules get wrong, and
+//! [`ClientHandle::pluralizer`](crate::ClientHandle::pluralizer) knows the
+//! resources registered with a client, so fixture tools and dynamic tests use
+//! the same names the fake serves.
+
+use crate::discovery::Discovery;
+use crate::registry::ResourceRegistry;
+use crate::tracker::GVK;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use std::collections::BTreeMap;
+
+/// A rule the metadata of created objects must follow
+///
+/// Closures taking the kind and metadata of an object are rules too.
+pub trait MetadataRule: Send + Sync {
+    /// Check the metadata of an object about to be created
+    ///
+    /// Returns the violation as a field error, such as
+    /// `metadata.name: Invalid value: "web": must start with "team-"`.
+    fn validate(&self, gvk: &GVK, metadata: &ObjectMeta) -> Result<(), String>;
+}
+
+impl<F> MetadataRule for F
+where
+    F: Fn(&GVK, &ObjectMeta) -> Result<(), String> + Send + Sync,
+{
+    fn validate(&self, gvk: &GVK, metadata: &ObjectMeta) -> Result<(), String> {
+        self(gvk, metadata)
+    }
+}
+
+/// The API server's checks of names and namespaces
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::{naming, ClientBuilder};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClientBuilder::new()
+///     .with_metadata_rule(naming::StandardNames)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardNames;
+
+impl MetadataRule for StandardNames {
+    fn validate(&self, gvk: &GVK, metadata: &ObjectMeta) -> Result<(), String> {
+        if let Some(namespace) = metadata.namespace.as_deref().filter(|ns| !ns.is_empty()) {
+            if let Some(problem) = dns1123_label(namespace) {
+                return Err(invalid("metadata.namespace", namespace, &problem));
+            }
+        }
+        let Some(name) = metadata.name.as_deref() else {
+            return Ok(());
+        };
+        let problem = match (gvk.group.as_str(), gvk.kind.as_str()) {
+            ("", "Namespace") => dns1123_label(name),
+            ("rbac.authorization.k8s.io", _) => path_segment(name),
+            _ => dns1123_subdomain(name),
+        };
+        match problem {
+            Some(problem) => Err(invalid("metadata.name", name, &problem)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn invalid(field: &str, value: &str, problem: &str) -> String {
+    format!("{field}: Invalid value: {value:?}: {problem}")
+}
+
+/// Why a name isn't a DNS-1123 label, if it isn't one
+fn dns1123_label(name: &str) -> Option<String> {
+    if name.len() > 63 {
+        return Some("must be no more than 63 characters".to_string());
+    }
+    (!is_dns1123_label(name)).then(|| {
+        "a lowercase RFC 1123 label must consist of lower case alphanumeric characters \
+         or '-', and must start and end with an alphanumeric character"
+            .to_string()
+    })
+}
+
+/// Why a name isn't a DNS-1123 subdomain, if it isn't one
+fn dns1123_subdomain(name: &str) -> Option<String> {
+    if name.len() > 253 {
+        return Some("must be no more than 253 characters".to_string());
+    }
+    (!name.split('.').all(is_dns1123_label)).then(|| {
+        "a lowercase RFC 1123 subdomain must consist of lower case alphanumeric \
+         characters, '-' or '.', and must start and end with an alphanumeric character"
+            .to_string()
+    })
+}
+
+/// Why a name can't be a path segment, if it can't
+fn path_segment(name: &str) -> Option<String> {
+    match name {
+        "." | ".." => Some(format!("may not be '{name}'")),
+        _ if name.contains('/') => Some("may not contain '/'".to_string()),
+        _ if name.contains('%') => Some("may not contain '%'".to_string()),
+        _ => None,
+    }
+}
+
+fn is_dns1123_label(label: &str) -> bool {
+    let alphanumeric = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+    label.starts_with(alphanumeric)
+        && label.ends_with(alphanumeric)
+        && label.chars().all(|c| alphanumeric(c) || c == '-')
+}
+
+/// Kinds whose plural the suffix rules get wrong
+const IRREGULAR: &[(&str, &str)] = &[("endpoints", "endpoints")];
+
+/// Maps kinds to resource names and back
+///
+/// Names are lowercase, as in request paths. Overrides come first, then the
+/// built-in resources of the discovery data, then English suffix rules:
+/// `Ingress` becomes `ingresses`, `NetworkPolicy` `networkpolicies` and
+/// `Deployment` `deployments`.
+///
+/// # Example
+///
+/// ```
+/// use kube_fake_client::naming::Pluralizer;
+///
+/// let pluralizer = Pluralizer::new().with_override("Cactus", "cacti");
+/// assert_eq!(pluralizer.plural("Cactus"), "cacti");
+/// assert_eq!(pluralizer.singular("cacti"), "cactus");
+/// assert_eq!(pluralizer.plural("Ingress"), "ingresses");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Pluralizer {
+    /// Plurals by lowercase kind
+    overrides: BTreeMap<String, String>,
+}
+
+impl Pluralizer {
+    /// A pluralizer without overrides
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a plural for a kind, and the kind for the plural
+    pub fn with_override(mut self, kind: &str, plural: &str) -> Self {
+        self.overrides
+            .insert(kind.to_ascii_lowercase(), plural.to_ascii_lowercase());
+        self
+    }
+
+    /// Use the plurals of the resources in a registry
+    pub fn with_registry(mut self, registry: &ResourceRegistry) -> Self {
+        for metadata in registry.list() {
+            self = self.with_override(&metadata.kind, &metadata.plural);
+        }
+        self
+    }
+
+    /// The resource name of a kind, such as `ingresses` for `Ingress`
+    pub fn plural(&self, kind: &str) -> String {
+        let singular = kind.to_ascii_lowercase();
+        if let Some(plural) = self.overrides.get(&singular) {
+            return plural.clone();
+        }
+        let builtin = Discovery::list_all_resources()
+            .iter()
+            .find(|(_, _, builtin, _)| builtin.eq_ignore_ascii_case(kind));
+        if let Some((_, _, _, plural)) = builtin {
+            return plural.to_string();
+        }
+        if let Some((_, plural)) = IRREGULAR.iter().find(|(s, _)| *s == singular) {
+            return plural.to_string();
+        }
+        if ["s", "x", "z", "ch", "sh"]
+            .iter()
+            .any(|suffix| singular.ends_with(suffix))
+        {
+            return format!("{singular}es");
+        }
+        if let Some(stem) = singular.strip_suffix('y') {
+            if stem.ends_with(|c: char| !"aeiou".contains(c)) {
+                return format!("{stem}ies");
+            }
+        }
+        format!("{singular}s")
+    }
+
+    /// The singular of a resource name, the kind in lowercase, such as
+    /// `ingress` for `ingresses`
+    pub fn singular(&self, plural: &str) -> String {
+        let plural = plural.to_ascii_lowercase();
+        let overridden = self.overrides.iter().find(|(_, p)| **p == plural);
+        if let Some((singular, _)) = overridden {
+            return singular.clone();
+        }
+        let builtin = Discovery::list_all_resources()
+            .iter()
+            .find(|(_, _, _, builtin)| *builtin == plural);
+        if let Some((_, _, kind, _)) = builtin {
+            return kind.to_ascii_lowercase();
+        }
+        if let Some((singular, _)) = IRREGULAR.iter().find(|(_, p)| *p == plural) {
+            return singular.to_string();
+        }
+        if let Some(stem) = plural.strip_suffix("ies") {
+            return format!("{stem}y");
+        }
+        // Not "es" alone: "releases" is the plural of "release"
+        let es = ["sses", "uses", "xes", "zes", "ches", "shes"];
+        if es.iter().any(|suffix| plural.ends_with(suffix)) {
+            return plural[..plural.len() - 2].to_string();
+        }
+        plural.strip_suffix('s').unwrap_or(&plural).to_string()
+    }
+}
+
+/// The resource name of a kind, such as `ingresses` for `Ingress`
+///
+/// See [`Pluralizer`] for the rules and for overrides.
+pub fn pluralize(kind: &str) -> String {
+    Pluralizer::new().plural(kind)
+}
+
+/// The singular of a resource name, such as `ingress` for `ingresses`
+///
+/// See [`Pluralizer`] for the rules and for overrides.
+pub fn singularize(plural: &str) -> String {
+    Pluralizer::new().singular(plural)
+}
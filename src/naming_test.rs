@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use crate::{naming, ClientBuilder, GVK};
+    use k8s_openapi::api::core::v1::{ConfigMap, Namespace};
+    use k8s_openapi::api::rbac::v1::ClusterRole;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kube::api::{Api, PostParams};
+
+    fn named<K: kube::Resource<DynamicType = ()> + Default>(name: &str) -> K {
+        let mut object = K::default();
+        object.meta_mut().name = Some(name.to_string());
+        object
+    }
+
+    fn team_prefix(_: &GVK, metadata: &ObjectMeta) -> Result<(), String> {
+        match metadata.name.as_deref() {
+            Some(name) if !name.starts_with("team-") => Err(format!(
+                "metadata.name: Invalid value: {name:?}: must start with \"team-\""
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_rule_rejects_creates() {
+        let client = ClientBuilder::new()
+            .with_metadata_rule(team_prefix)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        configmaps
+            .create(&PostParams::default(), &named("team-settings"))
+            .await
+            .unwrap();
+        let err = configmaps
+            .create(&PostParams::default(), &named("settings"))
+            .await
+            .unwrap_err();
+        let kube::Error::Api(response) = err else {
+            panic!("expected an API error, got {err:?}");
+        };
+        assert_eq!(response.code, 422);
+        assert_eq!(response.reason, "Invalid");
+        assert_eq!(
+            response.message,
+            "ConfigMap \"settings\" is invalid: metadata.name: Invalid value: \"settings\": \
+             must start with \"team-\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_rule_checks_initial_objects() {
+        let result = ClientBuilder::new()
+            .with_metadata_rule(team_prefix)
+            .with_object(named::<ConfigMap>("settings"))
+            .build()
+            .await;
+        let err = result.err().expect("the fixture breaks the rule");
+        assert!(
+            err.to_string().contains("must start with \"team-\""),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_standard_names() {
+        let client = ClientBuilder::new()
+            .with_metadata_rule(naming::StandardNames)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        let cluster_roles: Api<ClusterRole> = Api::all(client.clone());
+        let pp = PostParams::default();
+
+        configmaps
+            .create(&pp, &named("app.settings"))
+            .await
+            .unwrap();
+        let err = configmaps
+            .create(&pp, &named("App_Settings"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("RFC 1123 subdomain"), "{err}");
+
+        // RBAC names only have to be path segments
+        cluster_roles
+            .create(&pp, &named("system:controller:team-a"))
+            .await
+            .unwrap();
+        let err = cluster_roles.create(&pp, &named("..")).await.unwrap_err();
+        assert!(err.to_string().contains("may not be '..'"), "{err}");
+
+        let in_bad_namespace: Api<ConfigMap> = Api::namespaced(client, "Team-A");
+        let err = in_bad_namespace
+            .create(&pp, &named("settings"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("metadata.namespace"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_standard_names_of_namespaces() {
+        // Namespace names are labels, without dots
+        ClientBuilder::new()
+            .with_metadata_rule(naming::StandardNames)
+            .with_object(named::<Namespace>("team-a"))
+            .build()
+            .await
+            .unwrap();
+        let err = ClientBuilder::new()
+            .with_metadata_rule(naming::StandardNames)
+            .with_object(named::<Namespace>("team.a"))
+            .build()
+            .await
+            .err()
+            .expect("the namespace name has a dot");
+        assert!(err.to_string().contains("RFC 1123 label"), "{err}");
+    }
+
+    #[test]
+    fn test_pluralize_and_singularize() {
+        let pairs = [
+            // Built-in resources come from discovery
+            ("Ingress", "ingresses"),
+            ("Endpoints", "endpoints"),
+            ("NetworkPolicy", "networkpolicies"),
+            ("ComponentStatus", "componentstatuses"),
+            // Others follow the suffix rules
+            ("Widget", "widgets"),
+            ("Gateway", "gateways"),
+            ("Proxy", "proxies"),
+            ("Mailbox", "mailboxes"),
+            ("Patch", "patches"),
+            ("Release", "releases"),
+            ("Class", "classes"),
+        ];
+        for (kind, plural) in pairs {
+            assert_eq!(naming::pluralize(kind), plural, "{kind}");
+            assert_eq!(
+                naming::singularize(plural),
+                kind.to_ascii_lowercase(),
+                "{plural}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pluralizer_overrides_and_registered_resources() {
+        use kube::api::{ApiResource, GroupVersionKind};
+
+        let pluralizer = naming::Pluralizer::new().with_override("Octopus", "octopodes");
+        assert_eq!(pluralizer.plural("Octopus"), "octopodes");
+        assert_eq!(pluralizer.singular("octopodes"), "octopus");
+        assert_eq!(naming::pluralize("Octopus"), "octopuses");
+
+        let gvk = GroupVersionKind::gvk("example.com", "v1", "Cactus");
+        let (_client, handle) = ClientBuilder::new()
+            .with_dynamic_resource(ApiResource::from_gvk_with_plural(&gvk, "cacti"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let pluralizer = handle.pluralizer();
+        assert_eq!(pluralizer.plural("Cactus"), "cacti");
+        assert_eq!(pluralizer.singular("cacti"), "cactus");
+        assert_eq!(pluralizer.plural("Pod"), "pods");
+    }
+}
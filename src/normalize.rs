@@ -0,0 +1,66 @@
+//! Normalization of serialization artifacts
+//!
+//! The API server does not distinguish a field set to `null`, an empty map or an
+//! empty list from a missing field. Typed clients do: k8s-openapi serializes
+//! `ObjectMeta::creation_timestamp` as `creationTimestamp: null` when unset, and
+//! some types serialize defaulted collections as `{}` or `[]`. [`normalize_object`]
+//! removes these so objects that only differ in such artifacts compare equal.
+
+use serde_json::Value;
+
+/// Remove fields that are `null`, empty maps or empty lists, recursively
+///
+/// A map or list left empty once its own fields are removed is removed too, so
+/// `{"metadata": {"creationTimestamp": null}}` normalizes to `{}`. Items of
+/// lists are normalized but never removed, since their position is meaningful.
+///
+/// Used when checking immutable fields on update, and available for comparing
+/// objects in test assertions.
+///
+/// # Example
+///
+/// ```rust
+/// use kube_fake_client::normalize_object;
+/// use serde_json::json;
+///
+/// let mut object = json!({
+///     "metadata": { "name": "web", "creationTimestamp": null, "labels": {} },
+///     "spec": { "nodeSelector": {}, "containers": [{ "name": "app", "args": [] }] },
+/// });
+/// normalize_object(&mut object);
+/// assert_eq!(
+///     object,
+///     json!({
+///         "metadata": { "name": "web" },
+///         "spec": { "containers": [{ "name": "app" }] },
+///     })
+/// );
+/// ```
+pub fn normalize_object(object: &mut Value) {
+    match object {
+        Value::Object(fields) => {
+            for value in fields.values_mut() {
+                normalize_object(value);
+            }
+            fields.retain(|_, value| !is_empty(value));
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize_object),
+        _ => {}
+    }
+}
+
+/// A normalized copy of an object
+pub(crate) fn normalized(object: &Value) -> Value {
+    let mut object = object.clone();
+    normalize_object(&mut object);
+    object
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Object(fields) => fields.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::FakeClient;
+    use crate::normalize_object;
+    use crate::tracker::GVK;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, PostParams};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_normalize_removes_empty_fields() {
+        let mut object = json!({
+            "metadata": { "name": "web", "creationTimestamp": null, "annotations": {} },
+            "spec": {
+                "selector": { "matchLabels": {} },
+                "ports": [{ "port": 80, "name": null }, {}],
+                "replicas": 0,
+                "paused": false,
+                "hostname": "",
+            },
+            "status": null,
+        });
+        normalize_object(&mut object);
+        assert_eq!(
+            object,
+            json!({
+                "metadata": { "name": "web" },
+                "spec": {
+                    "ports": [{ "port": 80 }, {}],
+                    "replicas": 0,
+                    "paused": false,
+                    "hostname": "",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_immutability_ignores_serialization_artifacts() {
+        let client = FakeClient::new();
+        let gvk = GVK::new("", "v1", "ConfigMap");
+        let old = json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "frozen", "creationTimestamp": null },
+            "immutable": true,
+        });
+        let new = json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "frozen", "labels": {} },
+            "immutable": true,
+            "data": {},
+            "binaryData": null,
+        });
+        client.validate_immutable_fields(&gvk, &old, &new).unwrap();
+
+        let changed = json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "frozen" },
+            "immutable": true,
+            "data": { "key": "value" },
+        });
+        assert!(matches!(
+            client.validate_immutable_fields(&gvk, &old, &changed),
+            Err(crate::Error::ImmutableField { field }) if field == "data"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replace_immutable_configmap_with_empty_data() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        let mut configmap = ConfigMap::default();
+        configmap.metadata.name = Some("frozen".to_string());
+        configmap.immutable = Some(true);
+        let mut stored = api
+            .create(&PostParams::default(), &configmap)
+            .await
+            .unwrap();
+
+        // A typed client sends an empty map the stored object doesn't have
+        stored.data = Some(BTreeMap::new());
+        api.replace("frozen", &PostParams::default(), &stored)
+            .await
+            .unwrap();
+    }
+}
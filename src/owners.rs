@@ -0,0 +1,81 @@
+//! Owner reference lookups
+//!
+//! Operators wire the objects they create to their custom resource through
+//! `metadata.ownerReferences`, so garbage collection cleans them up. These helpers
+//! walk the references in storage for
+//! [`ClientHandle::dependents_of`](crate::ClientHandle::dependents_of) and
+//! [`testing::assert_owned_by`](crate::testing::assert_owned_by).
+
+use crate::client::FakeClient;
+use crate::tracker::{GVK, GVR};
+use crate::Result;
+use kube::Resource;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Get an object of a resource type, ignoring the namespace if it's cluster-scoped
+pub(crate) fn get<K>(client: &FakeClient, namespace: &str, name: &str) -> Result<Arc<Value>>
+where
+    K: Resource<DynamicType = ()>,
+{
+    let gvk = GVK::of::<K>();
+    client.get_by_kind(&gvk, namespace, name)
+}
+
+/// The owner references of an object
+pub(crate) fn references(object: &Value) -> &[Value] {
+    object
+        .pointer("/metadata/ownerReferences")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Whether an owner reference points to an object
+///
+/// The reference must name the object's apiVersion, kind and name, and its uid
+/// if the object has one: a reference to a deleted and recreated owner is left
+/// dangling, and garbage collection removes its dependents.
+pub(crate) fn points_to(reference: &Value, owner: &Value) -> bool {
+    ["apiVersion", "kind"]
+        .iter()
+        .all(|field| reference.get(field) == owner.get(field))
+        && reference.get("name") == owner.pointer("/metadata/name")
+        && owner
+            .pointer("/metadata/uid")
+            .is_none_or(|uid| reference.get("uid") == Some(uid))
+}
+
+/// Objects with an owner reference to an object
+///
+/// Dependents of a namespaced owner can only be in its namespace. Objects are
+/// ordered by resource type, namespace and name.
+pub(crate) fn dependents_of(client: &FakeClient, owner: &Value) -> Vec<Arc<Value>> {
+    let owner_namespace = owner
+        .pointer("/metadata/namespace")
+        .and_then(Value::as_str)
+        .filter(|namespace| !namespace.is_empty());
+    let mut dependents: Vec<(GVR, Arc<Value>)> = client
+        .tracker
+        .list_all_shared()
+        .into_iter()
+        .filter(|(_, object)| {
+            owner_namespace.is_none_or(|namespace| {
+                object
+                    .pointer("/metadata/namespace")
+                    .and_then(Value::as_str)
+                    == Some(namespace)
+            }) && references(object)
+                .iter()
+                .any(|reference| points_to(reference, owner))
+        })
+        .collect();
+    dependents.sort_by_cached_key(|(gvr, object)| {
+        (
+            (gvr.group.clone(), gvr.version.clone(), gvr.resource.clone()),
+            object["metadata"]["namespace"].as_str().map(str::to_string),
+            object["metadata"]["name"].as_str().map(str::to_string),
+        )
+    });
+    dependents.into_iter().map(|(_, object)| object).collect()
+}
@@ -0,0 +1,133 @@
+//! File-backed cluster state
+//!
+//! With [`ClientBuilder::with_persistence`](crate::ClientBuilder::with_persistence),
+//! every write is followed by saving all stored objects to a JSON file, and a
+//! client built with an existing file starts from the objects in it. Long fuzz
+//! or soak tests can then resume across process restarts, and when a test
+//! crashes the file shows what the cluster looked like at the last write.
+//!
+//! The whole state is rewritten on each write, which is fine for the object
+//! counts tests use but makes persistence a poor fit for benchmarks.
+
+use crate::tracker::{ObjectTracker, GVR};
+use crate::{Error, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Contents of the state file
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    /// Latest resourceVersion handed out, so versions keep increasing after a restart
+    resource_version: u64,
+    /// Stored objects in insertion order
+    items: Vec<Item>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Item {
+    group: String,
+    version: String,
+    resource: String,
+    namespace: String,
+    object: Value,
+}
+
+/// Saves the tracker's objects to a file
+pub(crate) struct Persistence {
+    path: PathBuf,
+    /// Held while saving, so a save never overwrites a later one
+    saving: Mutex<()>,
+}
+
+impl Persistence {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            saving: Mutex::new(()),
+        }
+    }
+
+    /// Add the objects saved in the file to the tracker
+    ///
+    /// Returns whether the file existed; a missing file is an empty cluster.
+    pub(crate) fn load(&self, tracker: &ObjectTracker) -> Result<bool> {
+        let contents = match std::fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(self.error("read", e)),
+        };
+        let state: State = serde_json::from_slice(&contents).map_err(|e| self.error("parse", e))?;
+
+        tracker.advance_resource_version(state.resource_version);
+        for item in state.items {
+            let gvr = GVR::new(&item.group, &item.version, &item.resource);
+            tracker.add(&gvr, item.object, &item.namespace)?;
+        }
+        Ok(true)
+    }
+
+    /// Write every stored object to the file
+    ///
+    /// The file is replaced atomically, so a crash mid-save leaves the previous
+    /// state behind rather than a truncated file.
+    pub(crate) fn save(&self, tracker: &ObjectTracker) -> Result<()> {
+        let _saving = self.saving.lock();
+        let snapshot = tracker.snapshot();
+        let mut items: Vec<(u64, Item)> = snapshot
+            .objects
+            .iter()
+            .map(|(key, stored)| {
+                let (group, version, resource, namespace, _) = key.clone();
+                let insertion = snapshot.insertions.get(key).copied().unwrap_or_default();
+                let item = Item {
+                    group,
+                    version,
+                    resource,
+                    namespace,
+                    object: stored.data.as_ref().clone(),
+                };
+                (insertion, item)
+            })
+            .collect();
+        items.sort_by_key(|(insertion, _)| *insertion);
+        let state = State {
+            resource_version: tracker.current_resource_version(),
+            items: items.into_iter().map(|(_, item)| item).collect(),
+        };
+
+        let contents = serde_json::to_vec_pretty(&state)?;
+        let temporary = temporary_path(&self.path);
+        std::fs::write(&temporary, contents).map_err(|e| self.error("write", e))?;
+        std::fs::rename(&temporary, &self.path).map_err(|e| self.error("write", e))
+    }
+
+    /// Save after every write to the tracker
+    ///
+    /// Failures can't be returned from an observer, so they are logged.
+    pub(crate) fn observe(self: &Arc<Self>, tracker: &ObjectTracker) {
+        let persistence = Arc::clone(self);
+        tracker.add_observer(Arc::new(move |tracker, _, _| {
+            if let Err(e) = persistence.save(tracker) {
+                warn!("{e}");
+            }
+        }));
+    }
+
+    fn error(&self, action: &str, err: impl std::fmt::Display) -> Error {
+        Error::Internal(format!(
+            "failed to {action} cluster state file {}: {err}",
+            self.path.display()
+        ))
+    }
+}
+
+pub(crate) fn temporary_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{configmap_with, in_namespace};
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{ConfigMap, Node};
+    use kube::api::{Api, ListParams, PostParams};
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn state_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "kube-fake-client-state-{}.json",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    fn names(list: &[ConfigMap]) -> Vec<String> {
+        list.iter()
+            .map(|cm| cm.metadata.name.clone().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_persistence_survives_restart() {
+        let path = state_path();
+
+        let client = ClientBuilder::new()
+            .with_persistence(&path)
+            .with_object(in_namespace(
+                configmap_with("seeded", "key", "initial"),
+                "default",
+            ))
+            .build()
+            .await
+            .unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client.clone());
+        api.create(
+            &PostParams::default(),
+            &in_namespace(configmap_with("created", "key", "one"), "default"),
+        )
+        .await
+        .unwrap();
+        let mut updated = api.get("seeded").await.unwrap();
+        updated.data = Some(BTreeMap::from([("key".to_string(), "changed".to_string())]));
+        let updated = api
+            .replace("seeded", &PostParams::default(), &updated)
+            .await
+            .unwrap();
+        let mut node = Node::default();
+        node.metadata.name = Some("node-1".to_string());
+        Api::<Node>::all(client)
+            .create(&PostParams::default(), &node)
+            .await
+            .unwrap();
+
+        // The file is readable after every write
+        let state: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(state["items"].as_array().unwrap().len(), 3);
+
+        // A new client resumes from the file, ignoring its initial objects
+        let client = ClientBuilder::new()
+            .with_persistence(&path)
+            .with_object(in_namespace(
+                configmap_with("ignored", "key", "initial"),
+                "default",
+            ))
+            .build()
+            .await
+            .unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(client.clone());
+        let list = api.list(&ListParams::default()).await.unwrap();
+        assert_eq!(names(&list.items), ["created", "seeded"]);
+        let seeded = api.get("seeded").await.unwrap();
+        assert_eq!(seeded.data.unwrap()["key"], "changed");
+        assert_eq!(
+            seeded.metadata.resource_version,
+            updated.metadata.resource_version
+        );
+        Api::<Node>::all(client).get("node-1").await.unwrap();
+
+        // Resource versions keep increasing across the restart
+        let created = api
+            .create(
+                &PostParams::default(),
+                &in_namespace(configmap_with("after", "key", "two"), "default"),
+            )
+            .await
+            .unwrap();
+        let version = |cm: &ConfigMap| {
+            cm.metadata
+                .resource_version
+                .as_deref()
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+        };
+        assert!(version(&created) > version(&updated));
+
+        api.delete("created", &Default::default()).await.unwrap();
+        let state: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let saved: Vec<&str> = state["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["object"]["metadata"]["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(saved, ["seeded", "node-1", "after"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_persistence_saves_restores() {
+        let path = state_path();
+        let (client, handle) = ClientBuilder::new()
+            .with_persistence(&path)
+            .build_with_handle()
+            .await
+            .unwrap();
+        let initial = handle.snapshot();
+        Api::<ConfigMap>::default_namespaced(client)
+            .create(
+                &PostParams::default(),
+                &in_namespace(configmap_with("temporary", "key", "one"), "default"),
+            )
+            .await
+            .unwrap();
+
+        handle.restore(&initial);
+        let state: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert!(state["items"].as_array().unwrap().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_persistence_rejects_corrupt_file() {
+        let path = state_path();
+        std::fs::write(&path, "not json").unwrap();
+
+        let result = ClientBuilder::new().with_persistence(&path).build().await;
+        assert!(matches!(result, Err(crate::Error::Internal(_))));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
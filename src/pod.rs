@@ -0,0 +1,259 @@
+//! Graceful Pod deletion and status transitions
+//!
+//! Deleting a Pod that is running on a node does not remove it right away: like
+//! the API server, the Pod gets a `deletionTimestamp` and is removed once its
+//! grace period has elapsed. On a tokio runtime that is the tokio clock, so tests
+//! can pause time and call [`ClientHandle::advance`](crate::ClientHandle::advance)
+//! to drive termination deterministically; elsewhere a timer thread removes it.
+//!
+//! With no kubelet behind the fake, Pods keep whatever status they were created
+//! with. [`ClientHandle::set_pod_ready`](crate::ClientHandle::set_pod_ready) and
+//! [`ClientHandle::set_pod_not_ready`](crate::ClientHandle::set_pod_not_ready)
+//! write the status a kubelet reports for a running Pod whose containers pass, or
+//! fail, their readiness probes.
+//!
+//! With [`ClientBuilder::with_service_account_image_pull_secrets`](crate::ClientBuilder::with_service_account_image_pull_secrets),
+//! created Pods get the `imagePullSecrets` of their ServiceAccount, as the
+//! ServiceAccount admission plugin adds them.
+
+use crate::clock;
+use crate::runtime;
+use crate::tracker::{ObjectTracker, GVK, GVR};
+use crate::Result;
+use chrono::SecondsFormat;
+use serde_json::{json, Value};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tracing::debug;
+
+/// Grace period used when neither the request nor the Pod specifies one
+const DEFAULT_GRACE_PERIOD_SECONDS: i64 = 30;
+
+pub(crate) fn pods_gvr() -> GVR {
+    GVR::new("", "v1", "pods")
+}
+
+fn service_accounts_gvr() -> GVR {
+    GVR::new("", "v1", "serviceaccounts")
+}
+
+fn pod_gvk() -> GVK {
+    GVK::new("", "v1", "Pod")
+}
+
+/// Delete a Pod, honoring its termination grace period
+///
+/// `grace_period_seconds` overrides the Pod's `terminationGracePeriodSeconds`.
+/// Pods that were never scheduled or have already finished, and deletions with a
+/// zero grace period, are removed immediately.
+pub(crate) fn delete(
+    tracker: &Arc<ObjectTracker>,
+    namespace: &str,
+    name: &str,
+    grace_period_seconds: Option<i64>,
+) -> Result<Value> {
+    let gvr = pods_gvr();
+    let pod = tracker.get(&gvr, namespace, name)?;
+
+    let grace = grace_period_seconds
+        .or_else(|| {
+            pod.pointer("/spec/terminationGracePeriodSeconds")
+                .and_then(|v| v.as_i64())
+        })
+        .unwrap_or(DEFAULT_GRACE_PERIOD_SECONDS)
+        .max(0);
+    let scheduled = pod
+        .pointer("/spec/nodeName")
+        .and_then(|v| v.as_str())
+        .is_some_and(|n| !n.is_empty());
+    let finished = matches!(
+        pod.pointer("/status/phase").and_then(|v| v.as_str()),
+        Some("Succeeded") | Some("Failed")
+    );
+
+    if grace == 0 || !scheduled || finished {
+        return tracker.delete(&gvr, namespace, name);
+    }
+
+    let terminating = tracker.begin_graceful_deletion(&gvr, namespace, name, grace)?;
+    let uid = terminating
+        .pointer("/metadata/uid")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let tracker = Arc::downgrade(tracker);
+    let (namespace, name) = (namespace.to_string(), name.to_string());
+    runtime::run_after(Duration::from_secs(grace as u64), move || {
+        finish(tracker, &namespace, &name, &uid);
+    });
+
+    Ok(terminating)
+}
+
+/// Remove a Pod whose grace period has elapsed
+///
+/// Pods with finalizers stay until the finalizers are removed, with their grace
+/// period dropped to zero as after a kubelet's final delete.
+fn finish(tracker: Weak<ObjectTracker>, namespace: &str, name: &str, uid: &str) {
+    let Some(tracker) = tracker.upgrade() else {
+        return;
+    };
+    let gvr = pods_gvr();
+    let Ok(pod) = tracker.get(&gvr, namespace, name) else {
+        return;
+    };
+    // The Pod may have been recreated under the same name in the meantime
+    if pod.pointer("/metadata/uid").and_then(|v| v.as_str()) != Some(uid)
+        || pod.pointer("/metadata/deletionTimestamp").is_none()
+    {
+        return;
+    }
+
+    let has_finalizers = pod
+        .pointer("/metadata/finalizers")
+        .and_then(|v| v.as_array())
+        .is_some_and(|f| !f.is_empty());
+    let result = if has_finalizers {
+        tracker
+            .begin_graceful_deletion(&gvr, namespace, name, 0)
+            .map(|_| ())
+    } else {
+        tracker.delete(&gvr, namespace, name).map(|_| ())
+    };
+    if let Err(e) = result {
+        debug!(
+            "Failed to finish deleting pod {}/{}: {}",
+            namespace, name, e
+        );
+    }
+}
+
+/// Copy the `imagePullSecrets` of a Pod's ServiceAccount into a Pod to be created
+///
+/// As in the admission plugin, Pods listing image pull secrets of their own keep
+/// them, and Pods without a `serviceAccountName` use the `default`
+/// ServiceAccount. Pods whose ServiceAccount doesn't exist are left as they are
+/// rather than rejected, since the fake doesn't create `default` ServiceAccounts.
+pub(crate) fn project_image_pull_secrets(
+    tracker: &ObjectTracker,
+    namespace: &str,
+    pod: &mut Value,
+) {
+    let has_own = pod
+        .pointer("/spec/imagePullSecrets")
+        .and_then(Value::as_array)
+        .is_some_and(|secrets| !secrets.is_empty());
+    if has_own || !pod["spec"].is_object() {
+        return;
+    }
+    let account = pod
+        .pointer("/spec/serviceAccountName")
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty())
+        .unwrap_or("default");
+    let Ok(account) = tracker.get(&service_accounts_gvr(), namespace, account) else {
+        return;
+    };
+    match account.get("imagePullSecrets") {
+        Some(Value::Array(secrets)) if !secrets.is_empty() => {
+            pod["spec"]["imagePullSecrets"] = Value::Array(secrets.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Write the status of a running Pod whose containers are all ready, or all
+/// unready
+///
+/// The Pod is `Running` with the `PodScheduled` and `Initialized` conditions
+/// true, and `ContainersReady` and `Ready` set to `ready`. Every container of
+/// the spec gets a running container status; restart counts and start times
+/// already reported are kept, as are the transition times of conditions whose
+/// status doesn't change.
+pub(crate) fn set_ready(
+    tracker: &ObjectTracker,
+    namespace: &str,
+    name: &str,
+    ready: bool,
+) -> Result<Value> {
+    let mut pod = tracker.get(&pods_gvr(), namespace, name)?;
+    let now = now();
+    let mut status = pod.get("status").cloned().unwrap_or_else(|| json!({}));
+
+    let previous_conditions = status["conditions"].as_array().cloned().unwrap_or_default();
+    let condition = |condition_type: &str, value: bool| {
+        let status = if value { "True" } else { "False" };
+        let last_transition_time = previous_conditions
+            .iter()
+            .find(|before| before["type"] == condition_type && before["status"] == status)
+            .map(|before| before["lastTransitionTime"].clone())
+            .unwrap_or_else(|| json!(now));
+        let mut condition = json!({
+            "type": condition_type,
+            "status": status,
+            "lastProbeTime": null,
+            "lastTransitionTime": last_transition_time,
+        });
+        if !value {
+            condition["reason"] = json!("ContainersNotReady");
+        }
+        condition
+    };
+    let conditions = json!([
+        condition("PodReadyToStartContainers", true),
+        condition("Initialized", true),
+        condition("Ready", ready),
+        condition("ContainersReady", ready),
+        condition("PodScheduled", true),
+    ]);
+
+    let previous_statuses = status["containerStatuses"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let container_statuses: Vec<Value> = pod
+        .pointer("/spec/containers")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|container| {
+            let container_name = container["name"].clone();
+            let previous = previous_statuses
+                .iter()
+                .find(|before| before["name"] == container_name);
+            let started_at = previous
+                .and_then(|before| before.pointer("/state/running/startedAt"))
+                .cloned()
+                .unwrap_or_else(|| json!(now));
+            json!({
+                "name": container_name,
+                "image": container["image"],
+                "imageID": "",
+                "ready": ready,
+                "started": true,
+                "restartCount": previous
+                    .and_then(|before| before["restartCount"].as_i64())
+                    .unwrap_or(0),
+                "state": { "running": { "startedAt": started_at } },
+            })
+        })
+        .collect();
+
+    status["phase"] = json!("Running");
+    status["conditions"] = conditions;
+    status["containerStatuses"] = json!(container_statuses);
+    if status.get("startTime").is_none_or(Value::is_null) {
+        status["startTime"] = json!(now);
+    }
+    pod["status"] = status;
+    if let Some(metadata) = pod.get_mut("metadata").and_then(Value::as_object_mut) {
+        metadata.remove("resourceVersion");
+    }
+    tracker.update(&pods_gvr(), &pod_gvk(), pod, namespace, true)
+}
+
+fn now() -> String {
+    clock::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
@@ -0,0 +1,306 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{LocalObjectReference, Pod, ServiceAccount};
+    use kube::api::{Api, DeleteParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn scheduled_pod(name: &str, grace: Option<i64>) -> Pod {
+        serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "name": name },
+            "spec": {
+                "nodeName": "node-1",
+                "terminationGracePeriodSeconds": grace,
+                "containers": [{ "name": "main", "image": "app:1" }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_graceful_delete_waits_for_grace_period() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        pods.create(&PostParams::default(), &scheduled_pod("web", Some(10)))
+            .await
+            .unwrap();
+
+        let terminating = pods
+            .delete("web", &DeleteParams::default())
+            .await
+            .unwrap()
+            .left()
+            .expect("graceful delete returns the pod");
+        assert!(terminating.metadata.deletion_timestamp.is_some());
+        assert_eq!(terminating.metadata.deletion_grace_period_seconds, Some(10));
+
+        // Status updates during termination don't remove the pod
+        let patch = json!({ "status": { "phase": "Running" } });
+        pods.patch_status("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+
+        tokio::time::advance(Duration::from_secs(9)).await;
+        assert!(pods.get_opt("web").await.unwrap().is_some());
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(pods.get_opt("web").await.unwrap().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_typed_delete_is_graceful() {
+        let (_, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let fake = handle.fake_client();
+        fake.create(
+            "default",
+            &scheduled_pod("web", Some(10)),
+            &PostParams::default(),
+        )
+        .unwrap();
+
+        // Deletes without a request terminate Pods as ones over HTTP do
+        let terminating: Pod = fake.delete("default", "web").unwrap();
+        assert!(terminating.metadata.deletion_timestamp.is_some());
+        assert_eq!(terminating.metadata.deletion_grace_period_seconds, Some(10));
+        assert!(fake.get::<Pod>("default", "web").is_ok());
+
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert!(fake.get::<Pod>("default", "web").is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_delete_grace_period_override() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        pods.create(&PostParams::default(), &scheduled_pod("web", None))
+            .await
+            .unwrap();
+
+        // Without a pod-level setting the default of 30 seconds applies
+        let terminating = pods
+            .delete("web", &DeleteParams::default())
+            .await
+            .unwrap()
+            .left()
+            .unwrap();
+        assert_eq!(terminating.metadata.deletion_grace_period_seconds, Some(30));
+
+        // A shorter grace period on a second delete shortens termination
+        let dp = DeleteParams {
+            grace_period_seconds: Some(5),
+            ..Default::default()
+        };
+        let terminating = pods.delete("web", &dp).await.unwrap().left().unwrap();
+        assert_eq!(terminating.metadata.deletion_grace_period_seconds, Some(5));
+
+        tokio::time::sleep(Duration::from_secs(6)).await;
+        assert!(pods.get_opt("web").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_force_delete_and_unscheduled_pods_are_immediate() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+
+        pods.create(&PostParams::default(), &scheduled_pod("forced", Some(60)))
+            .await
+            .unwrap();
+        pods.delete("forced", &DeleteParams::default().grace_period(0))
+            .await
+            .unwrap();
+        assert!(pods.get_opt("forced").await.unwrap().is_none());
+
+        let mut pending = scheduled_pod("pending", Some(60));
+        pending.spec.as_mut().unwrap().node_name = None;
+        pods.create(&PostParams::default(), &pending).await.unwrap();
+        pods.delete("pending", &DeleteParams::default())
+            .await
+            .unwrap();
+        assert!(pods.get_opt("pending").await.unwrap().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_finalizers_hold_pod_after_grace_period() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        let mut pod = scheduled_pod("web", Some(1));
+        pod.metadata.finalizers = Some(vec!["example.com/cleanup".to_string()]);
+        pods.create(&PostParams::default(), &pod).await.unwrap();
+
+        pods.delete("web", &DeleteParams::default()).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        let held = pods.get("web").await.unwrap();
+        assert_eq!(held.metadata.deletion_grace_period_seconds, Some(0));
+
+        // Removing the finalizer completes the deletion
+        let patch = json!({ "metadata": { "finalizers": null } });
+        pods.patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert!(pods.get_opt("web").await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    #[tokio::test(start_paused = true)]
+    async fn test_timestamps_follow_advanced_time() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        let created = pods
+            .create(&PostParams::default(), &scheduled_pod("web", Some(30)))
+            .await
+            .unwrap();
+        let created_at = created.metadata.creation_timestamp.unwrap().0;
+
+        handle.advance(Duration::from_secs(60)).await;
+        let terminating = pods
+            .delete("web", &DeleteParams::default())
+            .await
+            .unwrap()
+            .left()
+            .expect("graceful delete returns the pod");
+        let deadline = terminating.metadata.deletion_timestamp.unwrap().0;
+        assert_eq!(deadline - created_at, chrono::Duration::seconds(90));
+
+        handle.advance(Duration::from_secs(29)).await;
+        assert!(pods.get_opt("web").await.unwrap().is_some());
+        handle.advance(Duration::from_secs(1)).await;
+        assert!(pods.get_opt("web").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_pod_ready_and_not_ready() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        pods.create(&PostParams::default(), &scheduled_pod("web", None))
+            .await
+            .unwrap();
+        pods.patch_status(
+            "web",
+            &PatchParams::default(),
+            &Patch::Merge(json!({
+                "status": { "containerStatuses": [{
+                    "name": "main", "image": "app:1", "imageID": "", "ready": false, "restartCount": 2
+                }] }
+            })),
+        )
+        .await
+        .unwrap();
+
+        let condition = |pod: &Pod, condition_type: &str| {
+            let conditions = pod.status.as_ref().unwrap().conditions.as_ref().unwrap();
+            let condition = conditions
+                .iter()
+                .find(|c| c.type_ == condition_type)
+                .unwrap();
+            (condition.status.clone(), condition.reason.clone())
+        };
+
+        let ready = handle.set_pod_ready("default", "web").unwrap();
+        let status = ready.status.as_ref().unwrap();
+        assert_eq!(status.phase.as_deref(), Some("Running"));
+        assert!(status.start_time.is_some());
+        for condition_type in ["PodScheduled", "Initialized", "ContainersReady", "Ready"] {
+            assert_eq!(
+                condition(&ready, condition_type),
+                ("True".to_string(), None)
+            );
+        }
+        let container = &status.container_statuses.as_ref().unwrap()[0];
+        assert_eq!(container.name, "main");
+        assert!(container.ready);
+        assert_eq!(container.restart_count, 2);
+        assert!(container.state.as_ref().unwrap().running.is_some());
+        // The spec is untouched
+        assert_eq!(ready.metadata.generation, Some(1));
+
+        let not_ready = handle.set_pod_not_ready("default", "web").unwrap();
+        assert_eq!(
+            condition(&not_ready, "Ready"),
+            ("False".to_string(), Some("ContainersNotReady".to_string()))
+        );
+        assert_eq!(condition(&not_ready, "Initialized").0, "True");
+        let status = not_ready.status.unwrap();
+        assert!(!status.container_statuses.unwrap()[0].ready);
+        assert_eq!(status.phase.as_deref(), Some("Running"));
+
+        assert!(handle.set_pod_ready("default", "missing").is_err());
+    }
+
+    fn pull_secret_names(pod: &Pod) -> Vec<String> {
+        let spec = pod.spec.as_ref().unwrap();
+        spec.image_pull_secrets
+            .iter()
+            .flatten()
+            .map(|secret| secret.name.clone())
+            .collect()
+    }
+
+    fn service_account(name: &str, secrets: &[&str]) -> ServiceAccount {
+        serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "ServiceAccount",
+            "metadata": { "name": name, "namespace": "default" },
+            "imagePullSecrets": secrets.iter().map(|s| json!({ "name": s })).collect::<Vec<_>>(),
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_service_account_image_pull_secrets_projected() {
+        let client = ClientBuilder::new()
+            .with_service_account_image_pull_secrets()
+            .with_object(service_account("default", &["registry"]))
+            .with_object(service_account("builder", &["mirror", "internal"]))
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client.clone(), "default");
+
+        let created = pods
+            .create(&PostParams::default(), &scheduled_pod("web", None))
+            .await
+            .unwrap();
+        assert_eq!(pull_secret_names(&created), ["registry"]);
+
+        let mut builder = scheduled_pod("build", None);
+        builder.spec.as_mut().unwrap().service_account_name = Some("builder".to_string());
+        let created = pods.create(&PostParams::default(), &builder).await.unwrap();
+        assert_eq!(pull_secret_names(&created), ["mirror", "internal"]);
+
+        // Pods with image pull secrets of their own keep them
+        let mut own = scheduled_pod("own", None);
+        own.spec.as_mut().unwrap().image_pull_secrets = Some(vec![LocalObjectReference {
+            name: "private".to_string(),
+        }]);
+        let created = pods.create(&PostParams::default(), &own).await.unwrap();
+        assert_eq!(pull_secret_names(&created), ["private"]);
+
+        // Missing ServiceAccounts leave the Pod as it is
+        let other: Api<Pod> = Api::namespaced(client, "other");
+        let created = other
+            .create(&PostParams::default(), &scheduled_pod("web", None))
+            .await
+            .unwrap();
+        assert!(pull_secret_names(&created).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_image_pull_secrets_not_projected_by_default() {
+        let client = ClientBuilder::new()
+            .with_object(service_account("default", &["registry"]))
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+
+        let created = pods
+            .create(&PostParams::default(), &scheduled_pod("web", None))
+            .await
+            .unwrap();
+        assert!(pull_secret_names(&created).is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+//! Strict query parameter validation
+//!
+//! The fake ignores query parameters it doesn't understand, and falls back to
+//! defaults for values it can't parse, so a controller that builds a broken
+//! label selector gets an empty list instead of an error. With
+//! [`ClientBuilder::with_strict_query_params`](crate::ClientBuilder::with_strict_query_params),
+//! requests whose query names a parameter the verb doesn't take, or has a
+//! malformed value, fail with 400 Bad Request instead.
+
+use crate::label_selector::parse_label_selector;
+use crate::{Error, Result};
+use std::borrow::Cow;
+
+/// Parameters every request may carry
+const COMMON: &[&str] = &["pretty"];
+const GET: &[&str] = &["resourceVersion"];
+const LIST: &[&str] = &[
+    "allowWatchBookmarks",
+    "continue",
+    "fieldSelector",
+    "labelSelector",
+    "limit",
+    "resourceVersion",
+    "resourceVersionMatch",
+    "sendInitialEvents",
+    "timeoutSeconds",
+    "watch",
+];
+const WRITE: &[&str] = &["dryRun", "fieldManager", "fieldValidation"];
+const PATCH: &[&str] = &["dryRun", "fieldManager", "fieldValidation", "force"];
+const DELETE: &[&str] = &[
+    "dryRun",
+    "gracePeriodSeconds",
+    "ignoreStoreReadErrorWithClusterBreakingPotential",
+    "orphanDependents",
+    "propagationPolicy",
+];
+
+/// Check the query of a request against the parameters its verb accepts
+///
+/// Only requests for objects and their `status`, `scale` and `approval`
+/// subresources are checked; other subresources, like `log` or `exec`, take
+/// their own options.
+pub(crate) fn validate(verb: &str, subresource: Option<&str>, query: Option<&str>) -> Result<()> {
+    if subresource.is_some_and(|s| !matches!(s, "status" | "scale" | "approval")) {
+        return Ok(());
+    }
+    let accepted: &[&[&str]] = match verb {
+        "get" => &[GET],
+        "list" | "watch" => &[LIST],
+        "create" | "update" => &[WRITE],
+        "patch" => &[PATCH],
+        "delete" => &[DELETE],
+        "deletecollection" => &[DELETE, LIST],
+        _ => return Ok(()),
+    };
+
+    for pair in query.unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        // Query strings are form-encoded, with spaces sent as '+'
+        let value = value.replace('+', " ");
+        let value = urlencoding::decode(&value).unwrap_or(Cow::Borrowed(value.as_str()));
+        if !COMMON.contains(&key) && !accepted.iter().any(|params| params.contains(&key)) {
+            return Err(Error::BadRequest(format!(
+                "unknown query parameter \"{key}\" for {verb}"
+            )));
+        }
+        validate_value(key, &value)
+            .map_err(|reason| Error::BadRequest(format!("invalid {key} \"{value}\": {reason}")))?;
+    }
+    Ok(())
+}
+
+fn validate_value(key: &str, value: &str) -> std::result::Result<(), String> {
+    let one_of = |allowed: &[&str]| {
+        if allowed.contains(&value) {
+            Ok(())
+        } else {
+            Err(format!("must be one of {}", allowed.join(", ")))
+        }
+    };
+    match key {
+        "labelSelector" => parse_label_selector(value).map(drop),
+        "fieldSelector" => validate_field_selector(value),
+        "limit" | "timeoutSeconds" => value
+            .parse::<u64>()
+            .map(drop)
+            .map_err(|_| "must be a non-negative integer".to_string()),
+        "gracePeriodSeconds" => value
+            .parse::<i64>()
+            .map(drop)
+            .map_err(|_| "must be an integer".to_string()),
+        "resourceVersion" if !value.is_empty() => value
+            .parse::<u64>()
+            .map(drop)
+            .map_err(|_| "must be a resource version returned by the server".to_string()),
+        "allowWatchBookmarks"
+        | "force"
+        | "ignoreStoreReadErrorWithClusterBreakingPotential"
+        | "orphanDependents"
+        | "pretty"
+        | "sendInitialEvents"
+        | "watch" => one_of(&["true", "false", "1", "0", ""]),
+        "resourceVersionMatch" => one_of(&["Exact", "NotOlderThan"]),
+        "propagationPolicy" => one_of(&["Orphan", "Background", "Foreground"]),
+        "dryRun" => one_of(&["All"]),
+        "fieldValidation" => one_of(&["Ignore", "Warn", "Strict"]),
+        _ => Ok(()),
+    }
+}
+
+/// Field selectors are comma-separated `field=value`, `field==value` or
+/// `field!=value` requirements
+fn validate_field_selector(selector: &str) -> std::result::Result<(), String> {
+    for requirement in selector.split(',') {
+        let requirement = requirement.trim();
+        if requirement.is_empty() {
+            continue;
+        }
+        let field = ["!=", "==", "="]
+            .iter()
+            .find_map(|op| requirement.split_once(op).map(|(field, _)| field))
+            .ok_or_else(|| format!("\"{requirement}\" has no operator"))?;
+        if field.trim().is_empty() {
+            return Err(format!("\"{requirement}\" has no field"));
+        }
+    }
+    Ok(())
+}
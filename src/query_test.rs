@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use crate::query::validate;
+    use crate::{ClientBuilder, Error};
+    use k8s_openapi::api::core::v1::Pod;
+    use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+    use kube::Client;
+    use serde_json::json;
+
+    fn bad_request(result: crate::Result<()>) -> String {
+        match result {
+            Err(Error::BadRequest(message)) => message,
+            other => panic!("expected a bad request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_well_formed_queries() {
+        let list = "labelSelector=app%20in%20(web,db),tier!=cache&fieldSelector=status.phase%3DRunning&limit=500&watch=true&allowWatchBookmarks=true&timeoutSeconds=290&resourceVersion=12";
+        validate("list", None, Some(list)).unwrap();
+        validate("watch", None, Some(list)).unwrap();
+        validate("get", None, Some("resourceVersion=0&pretty=true")).unwrap();
+        validate("patch", Some("status"), Some("fieldManager=op&force=true")).unwrap();
+        validate("create", None, Some("dryRun=All&fieldValidation=Strict")).unwrap();
+        validate(
+            "delete",
+            None,
+            Some("gracePeriodSeconds=0&propagationPolicy=Foreground"),
+        )
+        .unwrap();
+        validate(
+            "deletecollection",
+            None,
+            Some("labelSelector=app&gracePeriodSeconds=5"),
+        )
+        .unwrap();
+        validate("list", None, None).unwrap();
+
+        // Subresources with their own options are not checked
+        validate("get", Some("log"), Some("container=web&follow=true")).unwrap();
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_and_malformed_parameters() {
+        assert_eq!(
+            bad_request(validate("list", None, Some("labelselector=app"))),
+            "unknown query parameter \"labelselector\" for list"
+        );
+        assert_eq!(
+            bad_request(validate("get", None, Some("limit=5"))),
+            "unknown query parameter \"limit\" for get"
+        );
+        assert!(bad_request(validate(
+            "list",
+            None,
+            Some("labelSelector=app%20in%20(web")
+        ))
+        .starts_with("invalid labelSelector \"app in (web\""));
+        assert!(
+            bad_request(validate("list", None, Some("fieldSelector=status.phase")))
+                .starts_with("invalid fieldSelector")
+        );
+        assert_eq!(
+            bad_request(validate("list", None, Some("limit=-1"))),
+            "invalid limit \"-1\": must be a non-negative integer"
+        );
+        assert!(
+            bad_request(validate("list", None, Some("resourceVersionMatch=Latest")))
+                .ends_with("must be one of Exact, NotOlderThan")
+        );
+        assert!(
+            bad_request(validate("delete", None, Some("propagationPolicy=Cascade")))
+                .starts_with("invalid propagationPolicy")
+        );
+    }
+
+    async fn client(strict: bool) -> Client {
+        let builder = ClientBuilder::new();
+        let builder = if strict {
+            builder.with_strict_query_params()
+        } else {
+            builder
+        };
+        builder.build().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_strict_query_params() {
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web".to_string());
+        pod.metadata.labels = Some([("app".to_string(), "web".to_string())].into());
+        let broken = ListParams::default().labels("app in (web");
+
+        // By default, a broken selector silently matches nothing
+        let pods: Api<Pod> = Api::default_namespaced(client(false).await);
+        pods.create(&PostParams::default(), &pod).await.unwrap();
+        assert!(pods.list(&broken).await.unwrap().items.is_empty());
+
+        let pods: Api<Pod> = Api::default_namespaced(client(true).await);
+        pods.create(&PostParams::default(), &pod).await.unwrap();
+        let err = pods.list(&broken).await.unwrap_err();
+        assert!(
+            matches!(&err, kube::Error::Api(resp) if resp.code == 400 && resp.reason == "BadRequest"),
+            "{err:?}"
+        );
+
+        // Requests the kube client builds itself pass
+        let valid = ListParams::default().labels("app in (web)").limit(10);
+        assert_eq!(pods.list(&valid).await.unwrap().items.len(), 1);
+        pods.get("web").await.unwrap();
+        pods.patch(
+            "web",
+            &PatchParams::apply("test").force(),
+            &Patch::Apply(json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "metadata": { "name": "web", "labels": { "tier": "frontend" } },
+            })),
+        )
+        .await
+        .unwrap();
+        pods.delete("web", &DeleteParams::default().grace_period(0))
+            .await
+            .unwrap();
+    }
+}
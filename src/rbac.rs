@@ -0,0 +1,306 @@
+//! RBAC authorization
+//!
+//! When the client is given an identity, every request is authorized against the
+//! Roles, ClusterRoles, RoleBindings and ClusterRoleBindings stored in the fake
+//! cluster, following the API server's RBAC authorizer: a request is allowed if
+//! any rule of any role bound to the user (directly, through one of its groups,
+//! or as a service account) covers it. Members of `system:masters` are allowed
+//! everything, and ClusterRoles with an `aggregationRule` are given the rules of
+//! the ClusterRoles they select.
+
+use crate::access_review::AccessReviewHandler;
+use crate::tracker::{ObjectTracker, GVR};
+use crate::Error;
+use k8s_openapi::api::authorization::v1::SubjectAccessReviewSpec;
+use k8s_openapi::api::rbac::v1::{
+    ClusterRole, ClusterRoleBinding, PolicyRule, Role, RoleBinding, RoleRef, Subject,
+};
+use kube::core::{Selector, SelectorExt};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+const RBAC_GROUP: &str = "rbac.authorization.k8s.io";
+
+/// Group whose members bypass authorization
+const MASTERS_GROUP: &str = "system:masters";
+
+/// Group every authenticated user belongs to
+const AUTHENTICATED_GROUP: &str = "system:authenticated";
+
+/// Resources every authenticated user may create, as granted by the bootstrap
+/// `system:basic-user` ClusterRole
+const SELF_REVIEWS: [(&str, &str); 3] = [
+    ("authorization.k8s.io", "selfsubjectaccessreviews"),
+    ("authorization.k8s.io", "selfsubjectrulesreviews"),
+    ("authentication.k8s.io", "selfsubjectreviews"),
+];
+
+/// The user the fake client makes requests as
+#[derive(Debug, Clone)]
+pub(crate) struct Identity {
+    pub(crate) user: String,
+    pub(crate) groups: Vec<String>,
+}
+
+impl Identity {
+    pub(crate) fn new(user: String, groups: Vec<String>) -> Self {
+        Self { user, groups }
+    }
+
+    fn is_member(&self, group: &str) -> bool {
+        group == AUTHENTICATED_GROUP || self.groups.iter().any(|g| g == group)
+    }
+
+    fn is_subject(&self, subject: &Subject, binding_namespace: Option<&str>) -> bool {
+        match subject.kind.as_str() {
+            "User" => subject.name == self.user,
+            "Group" => self.is_member(&subject.name),
+            "ServiceAccount" => {
+                let namespace = subject.namespace.as_deref().or(binding_namespace);
+                namespace.is_some_and(|ns| {
+                    self.user == format!("system:serviceaccount:{ns}:{}", subject.name)
+                })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// What a request does, in the terms RBAC rules are written in
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Attributes {
+    pub(crate) verb: String,
+    pub(crate) group: String,
+    pub(crate) resource: String,
+    pub(crate) subresource: Option<String>,
+    pub(crate) namespace: Option<String>,
+    pub(crate) name: Option<String>,
+    /// Path of a non-resource request; when set, the resource fields are unused
+    pub(crate) path: Option<String>,
+}
+
+impl Attributes {
+    fn matches(&self, rule: &PolicyRule) -> bool {
+        if !covers(&rule.verbs, &self.verb) {
+            return false;
+        }
+
+        if let Some(path) = &self.path {
+            return rule.non_resource_urls.as_ref().is_some_and(|urls| {
+                urls.iter().any(|url| match url.strip_suffix('*') {
+                    Some(prefix) => path.starts_with(prefix),
+                    None => url == path,
+                })
+            });
+        }
+
+        let resource = match &self.subresource {
+            Some(subresource) => format!("{}/{subresource}", self.resource),
+            None => self.resource.clone(),
+        };
+        let resource_matches = rule.resources.as_ref().is_some_and(|resources| {
+            resources.iter().any(|r| {
+                r == "*"
+                    || *r == resource
+                    || self.subresource.as_ref().is_some_and(|sub| {
+                        *r == format!("{}/*", self.resource) || *r == format!("*/{sub}")
+                    })
+            })
+        });
+        let names_match = match rule.resource_names.as_deref() {
+            None | Some([]) => true,
+            Some(names) => self.name.as_ref().is_some_and(|n| names.contains(n)),
+        };
+
+        rule.api_groups
+            .as_ref()
+            .is_some_and(|groups| covers(groups, &self.group))
+            && resource_matches
+            && names_match
+    }
+
+    /// Describes the request as the API server does in its Forbidden messages
+    fn describe(&self) -> String {
+        if let Some(path) = &self.path {
+            return format!("cannot {} path \"{path}\"", self.verb);
+        }
+        let resource = match &self.subresource {
+            Some(subresource) => format!("{}/{subresource}", self.resource),
+            None => self.resource.clone(),
+        };
+        let scope = match &self.namespace {
+            Some(ns) => format!("in the namespace \"{ns}\""),
+            None => "at the cluster scope".to_string(),
+        };
+        format!(
+            "cannot {} resource \"{resource}\" in API group \"{}\" {scope}",
+            self.verb, self.group
+        )
+    }
+}
+
+fn covers(values: &[String], value: &str) -> bool {
+    values.iter().any(|v| v == "*" || v == value)
+}
+
+fn list<K: DeserializeOwned>(tracker: &ObjectTracker, resource: &str, ns: Option<&str>) -> Vec<K> {
+    tracker
+        .list_shared(&GVR::new(RBAC_GROUP, "v1", resource), ns)
+        .iter()
+        .filter_map(|object| K::deserialize(object.as_ref()).ok())
+        .collect()
+}
+
+/// Rules of a ClusterRole, including aggregated ones
+fn cluster_role_rules(cluster_roles: &[ClusterRole], name: &str) -> Vec<PolicyRule> {
+    let Some(role) = cluster_roles
+        .iter()
+        .find(|r| r.metadata.name.as_deref() == Some(name))
+    else {
+        return Vec::new();
+    };
+    let mut rules = role.rules.clone().unwrap_or_default();
+
+    let selectors = role
+        .aggregation_rule
+        .as_ref()
+        .and_then(|rule| rule.cluster_role_selectors.clone())
+        .unwrap_or_default();
+    for selector in selectors {
+        let Ok(selector) = Selector::try_from(selector) else {
+            continue;
+        };
+        for other in cluster_roles {
+            let labels = other.metadata.labels.clone().unwrap_or_default();
+            if other.metadata.name != role.metadata.name && selector.matches(&labels) {
+                rules.extend(other.rules.clone().unwrap_or_default());
+            }
+        }
+    }
+    rules
+}
+
+/// Rules of the role a binding refers to
+fn referenced_rules(
+    tracker: &ObjectTracker,
+    cluster_roles: &[ClusterRole],
+    role_ref: &RoleRef,
+    namespace: Option<&str>,
+) -> Vec<PolicyRule> {
+    match (role_ref.kind.as_str(), namespace) {
+        ("ClusterRole", _) => cluster_role_rules(cluster_roles, &role_ref.name),
+        ("Role", Some(ns)) => list::<Role>(tracker, "roles", Some(ns))
+            .into_iter()
+            .find(|r| r.metadata.name.as_deref() == Some(role_ref.name.as_str()))
+            .and_then(|r| r.rules)
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether the identity may perform the request
+pub(crate) fn authorize(tracker: &ObjectTracker, identity: &Identity, attrs: &Attributes) -> bool {
+    if identity.is_member(MASTERS_GROUP) {
+        return true;
+    }
+    if attrs.verb == "create"
+        && attrs.path.is_none()
+        && SELF_REVIEWS.contains(&(attrs.group.as_str(), attrs.resource.as_str()))
+    {
+        return true;
+    }
+    let cluster_roles: Vec<ClusterRole> = list(tracker, "clusterroles", None);
+
+    let cluster_bindings: Vec<ClusterRoleBinding> = list(tracker, "clusterrolebindings", None);
+    for binding in cluster_bindings {
+        let bound = binding
+            .subjects
+            .iter()
+            .flatten()
+            .any(|s| identity.is_subject(s, None));
+        if bound
+            && referenced_rules(tracker, &cluster_roles, &binding.role_ref, None)
+                .iter()
+                .any(|rule| attrs.matches(rule))
+        {
+            return true;
+        }
+    }
+
+    // Role bindings only grant access within their own namespace
+    let Some(namespace) = attrs.namespace.as_deref().filter(|_| attrs.path.is_none()) else {
+        return false;
+    };
+    let bindings: Vec<RoleBinding> = list(tracker, "rolebindings", Some(namespace));
+    bindings.into_iter().any(|binding| {
+        binding
+            .subjects
+            .iter()
+            .flatten()
+            .any(|s| identity.is_subject(s, Some(namespace)))
+            && referenced_rules(tracker, &cluster_roles, &binding.role_ref, Some(namespace))
+                .iter()
+                .any(|rule| attrs.matches(rule))
+    })
+}
+
+/// The error returned for a request the identity may not perform
+pub(crate) fn forbidden(identity: &Identity, attrs: &Attributes) -> Error {
+    let subject = if attrs.path.is_some() {
+        "forbidden".to_string()
+    } else {
+        match &attrs.name {
+            Some(name) => format!("{} \"{name}\" is forbidden", attrs.resource),
+            None => format!("{} is forbidden", attrs.resource),
+        }
+    };
+    Error::Forbidden(format!(
+        "{subject}: User \"{}\" {}",
+        identity.user,
+        attrs.describe()
+    ))
+}
+
+/// Access review handler deciding reviews with RBAC
+///
+/// The user and groups of the spec are checked, falling back to the client's
+/// identity for `SelfSubjectAccessReview`s, which don't carry them.
+pub(crate) fn review_handler(
+    tracker: Arc<ObjectTracker>,
+    identity: Identity,
+) -> AccessReviewHandler {
+    Arc::new(move |spec| review(&tracker, &identity, spec))
+}
+
+fn review(tracker: &ObjectTracker, identity: &Identity, spec: &SubjectAccessReviewSpec) -> bool {
+    let subject = match &spec.user {
+        Some(user) if !user.is_empty() => {
+            Identity::new(user.clone(), spec.groups.clone().unwrap_or_default())
+        }
+        _ if spec.groups.as_ref().is_some_and(|g| !g.is_empty()) => {
+            Identity::new(String::new(), spec.groups.clone().unwrap_or_default())
+        }
+        _ => identity.clone(),
+    };
+
+    let attrs = if let Some(resource) = &spec.resource_attributes {
+        Attributes {
+            verb: resource.verb.clone().unwrap_or_default(),
+            group: resource.group.clone().unwrap_or_default(),
+            resource: resource.resource.clone().unwrap_or_default(),
+            subresource: resource.subresource.clone().filter(|s| !s.is_empty()),
+            namespace: resource.namespace.clone().filter(|s| !s.is_empty()),
+            name: resource.name.clone().filter(|s| !s.is_empty()),
+            path: None,
+        }
+    } else if let Some(non_resource) = &spec.non_resource_attributes {
+        Attributes {
+            verb: non_resource.verb.clone().unwrap_or_default(),
+            path: non_resource.path.clone(),
+            ..Default::default()
+        }
+    } else {
+        return false;
+    };
+    authorize(tracker, &subject, &attrs)
+}
@@ -0,0 +1,404 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::authorization::v1::{
+        ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+        SubjectAccessReview, SubjectAccessReviewSpec,
+    };
+    use k8s_openapi::api::core::v1::{ConfigMap, Pod};
+    use k8s_openapi::api::rbac::v1::{
+        AggregationRule, ClusterRole, ClusterRoleBinding, PolicyRule, Role, RoleBinding, RoleRef,
+        Subject,
+    };
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+    use kube::api::{Api, DeleteParams, ListParams, ObjectMeta, PostParams};
+    use std::collections::BTreeMap;
+
+    fn meta(name: &str, namespace: Option<&str>) -> ObjectMeta {
+        ObjectMeta {
+            name: Some(name.to_string()),
+            namespace: namespace.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn rule(verbs: &[&str], resources: &[&str]) -> PolicyRule {
+        PolicyRule {
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(resources.iter().map(|r| r.to_string()).collect()),
+            verbs: verbs.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn role(name: &str, namespace: &str, rules: Vec<PolicyRule>) -> Role {
+        Role {
+            metadata: meta(name, Some(namespace)),
+            rules: Some(rules),
+        }
+    }
+
+    fn cluster_role(name: &str, rules: Vec<PolicyRule>) -> ClusterRole {
+        ClusterRole {
+            metadata: meta(name, None),
+            rules: Some(rules),
+            ..Default::default()
+        }
+    }
+
+    fn subject(kind: &str, name: &str) -> Subject {
+        Subject {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn role_ref(kind: &str, name: &str) -> RoleRef {
+        RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: kind.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn role_binding(namespace: &str, role: RoleRef, subject: Subject) -> RoleBinding {
+        RoleBinding {
+            metadata: meta(&format!("{}-binding", role.name), Some(namespace)),
+            role_ref: role,
+            subjects: Some(vec![subject]),
+        }
+    }
+
+    fn cluster_role_binding(role: &str, subject: Subject) -> ClusterRoleBinding {
+        ClusterRoleBinding {
+            metadata: meta(&format!("{role}-binding"), None),
+            role_ref: role_ref("ClusterRole", role),
+            subjects: Some(vec![subject]),
+        }
+    }
+
+    fn pod(name: &str, namespace: &str) -> Pod {
+        Pod {
+            metadata: meta(name, Some(namespace)),
+            ..Default::default()
+        }
+    }
+
+    fn forbidden(err: kube::Error) -> String {
+        match err {
+            kube::Error::Api(response) => {
+                assert_eq!(response.code, 403);
+                assert_eq!(response.reason, "Forbidden");
+                response.message
+            }
+            other => panic!("expected a Forbidden error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_forbidden_without_bindings() {
+        let client = ClientBuilder::new()
+            .with_object(pod("web", "team-a"))
+            .with_identity("alice", Vec::<String>::new())
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "team-a");
+
+        let err = pods.get("web").await.unwrap_err();
+        assert_eq!(
+            forbidden(err),
+            "pods \"web\" is forbidden: User \"alice\" cannot get resource \"pods\" in API group \"\" in the namespace \"team-a\""
+        );
+
+        let err = pods.list(&ListParams::default()).await.unwrap_err();
+        assert_eq!(
+            forbidden(err),
+            "pods is forbidden: User \"alice\" cannot list resource \"pods\" in API group \"\" in the namespace \"team-a\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_role_binding_grants_verbs_in_its_namespace() {
+        let client = ClientBuilder::new()
+            .with_object(pod("web", "team-a"))
+            .with_object(pod("web", "team-b"))
+            .with_object(role(
+                "reader",
+                "team-a",
+                vec![rule(&["get", "list"], &["pods"])],
+            ))
+            .with_object(role_binding(
+                "team-a",
+                role_ref("Role", "reader"),
+                subject("User", "alice"),
+            ))
+            .with_identity("alice", Vec::<String>::new())
+            .build()
+            .await
+            .unwrap();
+        let team_a: Api<Pod> = Api::namespaced(client.clone(), "team-a");
+        let team_b: Api<Pod> = Api::namespaced(client.clone(), "team-b");
+
+        team_a.get("web").await.unwrap();
+        assert_eq!(
+            team_a
+                .list(&ListParams::default())
+                .await
+                .unwrap()
+                .items
+                .len(),
+            1
+        );
+
+        forbidden(
+            team_a
+                .delete("web", &DeleteParams::default())
+                .await
+                .unwrap_err(),
+        );
+        forbidden(team_b.get("web").await.unwrap_err());
+
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "team-a");
+        forbidden(configmaps.list(&ListParams::default()).await.unwrap_err());
+    }
+
+    #[tokio::test]
+    async fn test_role_binding_to_cluster_role_is_namespaced() {
+        let client = ClientBuilder::new()
+            .with_object(pod("web", "team-a"))
+            .with_object(pod("web", "team-b"))
+            .with_object(cluster_role("pod-reader", vec![rule(&["get"], &["pods"])]))
+            .with_object(role_binding(
+                "team-a",
+                role_ref("ClusterRole", "pod-reader"),
+                subject("User", "alice"),
+            ))
+            .with_identity("alice", Vec::<String>::new())
+            .build()
+            .await
+            .unwrap();
+
+        let team_a: Api<Pod> = Api::namespaced(client.clone(), "team-a");
+        team_a.get("web").await.unwrap();
+        let team_b: Api<Pod> = Api::namespaced(client, "team-b");
+        forbidden(team_b.get("web").await.unwrap_err());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_role_binding_grants_group_everywhere() {
+        let client = ClientBuilder::new()
+            .with_object(pod("web", "team-a"))
+            .with_object(pod("web", "team-b"))
+            .with_object(cluster_role("pod-reader", vec![rule(&["list"], &["pods"])]))
+            .with_object(cluster_role_binding(
+                "pod-reader",
+                subject("Group", "auditors"),
+            ))
+            .with_identity("bob", ["auditors"])
+            .build()
+            .await
+            .unwrap();
+
+        let all: Api<Pod> = Api::all(client.clone());
+        assert_eq!(
+            all.list(&ListParams::default()).await.unwrap().items.len(),
+            2
+        );
+
+        let pods: Api<Pod> = Api::namespaced(client, "team-b");
+        let err = pods
+            .create(&PostParams::default(), &pod("new", "team-b"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            forbidden(err),
+            "pods is forbidden: User \"bob\" cannot create resource \"pods\" in API group \"\" in the namespace \"team-b\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_account_subject() {
+        let mut service_account = subject("ServiceAccount", "deployer");
+        service_account.namespace = Some("ci".to_string());
+        let client = ClientBuilder::new()
+            .with_object(role(
+                "creator",
+                "team-a",
+                vec![rule(&["create"], &["pods"])],
+            ))
+            .with_object(role_binding(
+                "team-a",
+                role_ref("Role", "creator"),
+                service_account,
+            ))
+            .with_identity(
+                "system:serviceaccount:ci:deployer",
+                ["system:serviceaccounts"],
+            )
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "team-a");
+
+        pods.create(&PostParams::default(), &pod("new", "team-a"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resource_names_and_subresources() {
+        let mut named = rule(&["get"], &["pods"]);
+        named.resource_names = Some(vec!["web".to_string()]);
+        let client = ClientBuilder::new()
+            .with_object(pod("web", "team-a"))
+            .with_object(pod("db", "team-a"))
+            .with_object(role(
+                "limited",
+                "team-a",
+                vec![named, rule(&["update"], &["pods/status"])],
+            ))
+            .with_object(role_binding(
+                "team-a",
+                role_ref("Role", "limited"),
+                subject("User", "alice"),
+            ))
+            .with_identity("alice", Vec::<String>::new())
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "team-a");
+
+        let web = pods.get("web").await.unwrap();
+        forbidden(pods.get("db").await.unwrap_err());
+
+        pods.replace_status(
+            "web",
+            &PostParams::default(),
+            serde_json::to_vec(&web).unwrap(),
+        )
+        .await
+        .unwrap();
+        let err = pods
+            .replace("web", &PostParams::default(), &web)
+            .await
+            .unwrap_err();
+        assert!(forbidden(err).contains("cannot update resource \"pods\""));
+    }
+
+    #[tokio::test]
+    async fn test_aggregated_cluster_role() {
+        let mut extension = cluster_role("pod-extension", vec![rule(&["get"], &["pods"])]);
+        extension.metadata.labels = Some(BTreeMap::from([(
+            "aggregate-to-view".to_string(),
+            "true".to_string(),
+        )]));
+        let view = ClusterRole {
+            metadata: meta("view", None),
+            aggregation_rule: Some(AggregationRule {
+                cluster_role_selectors: Some(vec![LabelSelector {
+                    match_labels: Some(BTreeMap::from([(
+                        "aggregate-to-view".to_string(),
+                        "true".to_string(),
+                    )])),
+                    ..Default::default()
+                }]),
+            }),
+            ..Default::default()
+        };
+        let client = ClientBuilder::new()
+            .with_object(pod("web", "team-a"))
+            .with_object(extension)
+            .with_object(view)
+            .with_object(cluster_role_binding("view", subject("User", "alice")))
+            .with_identity("alice", Vec::<String>::new())
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "team-a");
+
+        pods.get("web").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_system_masters_allowed_everything() {
+        let client = ClientBuilder::new()
+            .with_identity("admin", ["system:masters"])
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "team-a");
+
+        pods.create(&PostParams::default(), &pod("web", "team-a"))
+            .await
+            .unwrap();
+        pods.delete("web", &DeleteParams::default()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_access_reviews_decided_by_rbac() {
+        let client = ClientBuilder::new()
+            .with_object(role("reader", "team-a", vec![rule(&["get"], &["pods"])]))
+            .with_object(role_binding(
+                "team-a",
+                role_ref("Role", "reader"),
+                subject("User", "alice"),
+            ))
+            .with_object(cluster_role(
+                "reviewer",
+                vec![PolicyRule {
+                    api_groups: Some(vec!["authorization.k8s.io".to_string()]),
+                    resources: Some(vec!["subjectaccessreviews".to_string()]),
+                    verbs: vec!["create".to_string()],
+                    ..Default::default()
+                }],
+            ))
+            .with_object(cluster_role_binding("reviewer", subject("User", "alice")))
+            .with_identity("alice", Vec::<String>::new())
+            .build()
+            .await
+            .unwrap();
+
+        let attributes = |verb: &str| ResourceAttributes {
+            verb: Some(verb.to_string()),
+            resource: Some("pods".to_string()),
+            namespace: Some("team-a".to_string()),
+            ..Default::default()
+        };
+        let self_reviews: Api<SelfSubjectAccessReview> = Api::all(client.clone());
+        let self_review = |verb: &str| SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: Some(attributes(verb)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let allowed = self_reviews
+            .create(&PostParams::default(), &self_review("get"))
+            .await
+            .unwrap();
+        assert!(allowed.status.unwrap().allowed);
+        let denied = self_reviews
+            .create(&PostParams::default(), &self_review("delete"))
+            .await
+            .unwrap();
+        assert!(!denied.status.unwrap().allowed);
+
+        // Other users are reviewed against their own bindings
+        let reviews: Api<SubjectAccessReview> = Api::all(client);
+        let review = SubjectAccessReview {
+            spec: SubjectAccessReviewSpec {
+                user: Some("bob".to_string()),
+                resource_attributes: Some(attributes("get")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let reviewed = reviews
+            .create(&PostParams::default(), &review)
+            .await
+            .unwrap();
+        assert!(!reviewed.status.unwrap().allowed);
+    }
+}
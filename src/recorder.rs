@@ -0,0 +1,251 @@
+//! Shared, thread-safe state for interceptors that record calls
+//!
+//! A [`CallRecorder`] replaces the `Arc<Mutex<Vec<...>>>` that tests otherwise
+//! capture in their interceptors: clones share one list of calls, counted per
+//! verb and per name, and tests can wait for a call or receive every call as it
+//! is recorded. [`CallRecorder::funcs`] records every request an interceptor
+//! sees, and [`ClientHandle::recorded_calls`](crate::ClientHandle::recorded_calls)
+//! builds a recorder from the audit log, so the same assertions work either way.
+
+use crate::audit::AuditEntry;
+use crate::budget::Verb;
+use crate::interceptor::Funcs;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Notify};
+
+/// A request recorded by a [`CallRecorder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    /// Verb of the request
+    pub verb: Verb,
+    /// Namespace of the request, `None` for cluster-scoped and all-namespace
+    /// requests
+    pub namespace: Option<String>,
+    /// Name of the object the request names, `None` for lists, watches and
+    /// collection deletes
+    pub name: Option<String>,
+}
+
+impl Call {
+    /// The call of a resource request in the audit log; `None` for discovery
+    /// and other non-resource requests
+    ///
+    /// Creates are named by the object they send.
+    pub(crate) fn from_audit(entry: &AuditEntry) -> Option<Call> {
+        entry.resource.as_ref()?;
+        let name = entry.name.clone().or_else(|| {
+            let object = entry.request_object.as_ref()?;
+            Some(object.pointer("/metadata/name")?.as_str()?.to_string())
+        });
+        Some(Call {
+            verb: Verb::parse(&entry.verb)?,
+            namespace: entry.namespace.clone(),
+            name,
+        })
+    }
+}
+
+/// Calls recorded by interceptors, shared between clones
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::budget::Verb;
+/// use kube_fake_client::interceptor::CallRecorder;
+/// use kube_fake_client::ClientBuilder;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let recorder = CallRecorder::new();
+/// let client = ClientBuilder::new()
+///     .with_interceptor_funcs(recorder.funcs())
+///     .build()
+///     .await?;
+///
+/// // ... start the controller under test with `client` ...
+///
+/// recorder.wait_for(Verb::Patch, 1).await;
+/// assert_eq!(recorder.count_named(Verb::Delete, "web"), 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct CallRecorder {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    calls: Mutex<Vec<Call>>,
+    subscribers: Mutex<Vec<mpsc::UnboundedSender<Call>>>,
+    recorded: Notify,
+}
+
+impl CallRecorder {
+    /// Create an empty recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a call, waking tests waiting for it
+    pub fn record(&self, verb: Verb, namespace: Option<&str>, name: Option<&str>) {
+        let call = Call {
+            verb,
+            namespace: namespace.filter(|ns| !ns.is_empty()).map(str::to_string),
+            name: name.map(str::to_string),
+        };
+        self.inner
+            .subscribers
+            .lock()
+            .retain(|subscriber| subscriber.send(call.clone()).is_ok());
+        self.inner.calls.lock().push(call);
+        self.inner.recorded.notify_waiters();
+    }
+
+    /// Every call recorded so far, in order
+    pub fn calls(&self) -> Vec<Call> {
+        self.inner.calls.lock().clone()
+    }
+
+    /// Number of calls recorded so far
+    pub fn len(&self) -> usize {
+        self.inner.calls.lock().len()
+    }
+
+    /// Whether no call has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of calls with a verb
+    pub fn count(&self, verb: Verb) -> usize {
+        self.count_where(|call| call.verb == verb)
+    }
+
+    /// Number of calls with a verb naming an object, in any namespace
+    pub fn count_named(&self, verb: Verb, name: &str) -> usize {
+        self.count_where(|call| call.verb == verb && call.name.as_deref() == Some(name))
+    }
+
+    fn count_where(&self, matches: impl Fn(&Call) -> bool) -> usize {
+        self.inner
+            .calls
+            .lock()
+            .iter()
+            .filter(|c| matches(c))
+            .count()
+    }
+
+    /// Forget every call recorded so far
+    pub fn clear(&self) {
+        self.inner.calls.lock().clear();
+    }
+
+    /// Receive every call recorded from now on, in order
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<Call> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.inner.subscribers.lock().push(sender);
+        receiver
+    }
+
+    /// Wait until at least `count` calls with a verb have been recorded
+    ///
+    /// Wrap it in [`tokio::time::timeout`] to fail tests instead of hanging.
+    pub async fn wait_for(&self, verb: Verb, count: usize) {
+        loop {
+            let recorded = self.inner.recorded.notified();
+            tokio::pin!(recorded);
+            recorded.as_mut().enable();
+            if self.count(verb) >= count {
+                return;
+            }
+            recorded.await;
+        }
+    }
+
+    /// Interceptors recording every request they see, then letting it through
+    ///
+    /// Status subresource requests are recorded under the verb they use. Merge
+    /// with other interceptors with [`Funcs::merge`]; put the recorder first to
+    /// see requests the others fail or override.
+    pub fn funcs(&self) -> Funcs {
+        let on = |verb: Verb| {
+            let recorder = self.clone();
+            move |namespace: Option<&str>, name: Option<&str>| {
+                recorder.record(verb, namespace, name)
+            }
+        };
+        let (create, get, replace, delete) = (
+            on(Verb::Create),
+            on(Verb::Get),
+            on(Verb::Update),
+            on(Verb::Delete),
+        );
+        let (delete_collection, list, patch, watch) = (
+            on(Verb::DeleteCollection),
+            on(Verb::List),
+            on(Verb::Patch),
+            on(Verb::Watch),
+        );
+        let (get_status, patch_status, replace_status) =
+            (on(Verb::Get), on(Verb::Patch), on(Verb::Update));
+        Funcs::new()
+            .create(move |ctx| {
+                create(
+                    Some(ctx.namespace),
+                    ctx.object.pointer("/metadata/name").and_then(Value::as_str),
+                );
+                Ok(None)
+            })
+            .get(move |ctx| {
+                get(Some(ctx.namespace), Some(ctx.name));
+                Ok(None)
+            })
+            .replace(move |ctx| {
+                replace(Some(ctx.namespace), Some(ctx.name));
+                Ok(None)
+            })
+            .delete(move |ctx| {
+                delete(Some(ctx.namespace), Some(ctx.name));
+                Ok(None)
+            })
+            .delete_collection(move |ctx| {
+                delete_collection(ctx.namespace, None);
+                Ok(None)
+            })
+            .list(move |ctx| {
+                list(ctx.namespace, None);
+                Ok(None)
+            })
+            .patch(move |ctx| {
+                patch(Some(ctx.namespace), Some(ctx.name));
+                Ok(None)
+            })
+            .watch(move |ctx| {
+                watch(ctx.namespace, None);
+                Ok(None)
+            })
+            .get_status(move |ctx| {
+                get_status(Some(ctx.namespace), Some(ctx.name));
+                Ok(None)
+            })
+            .patch_status(move |ctx| {
+                patch_status(Some(ctx.namespace), Some(ctx.name));
+                Ok(None)
+            })
+            .replace_status(move |ctx| {
+                replace_status(Some(ctx.namespace), Some(ctx.name));
+                Ok(None)
+            })
+    }
+}
+
+impl FromIterator<Call> for CallRecorder {
+    fn from_iter<I: IntoIterator<Item = Call>>(calls: I) -> Self {
+        let recorder = Self::new();
+        recorder.inner.calls.lock().extend(calls);
+        recorder
+    }
+}
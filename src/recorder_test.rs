@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use crate::budget::Verb;
+    use crate::interceptor::{Call, CallRecorder, Funcs};
+    use crate::test_fixtures::configmap;
+    use crate::{ClientBuilder, Error};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_recorder_counts_calls_per_verb_and_name() {
+        let recorder = CallRecorder::new();
+        let (client, handle) = ClientBuilder::new()
+            .with_interceptor_funcs(recorder.funcs())
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        configmaps
+            .create(&PostParams::default(), &configmap("web"))
+            .await
+            .unwrap();
+        configmaps
+            .create(&PostParams::default(), &configmap("db"))
+            .await
+            .unwrap();
+        let patch = json!({ "data": { "key": "value" } });
+        configmaps
+            .patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        configmaps.list(&ListParams::default()).await.unwrap();
+        configmaps
+            .delete("db", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        assert_eq!(recorder.len(), 5);
+        assert_eq!(recorder.count(Verb::Create), 2);
+        assert_eq!(recorder.count_named(Verb::Patch, "web"), 1);
+        assert_eq!(recorder.count_named(Verb::Delete, "web"), 0);
+        assert_eq!(
+            recorder.calls()[3],
+            Call {
+                verb: Verb::List,
+                namespace: Some("default".to_string()),
+                name: None,
+            }
+        );
+
+        // The audit log answers the same questions
+        let audited = handle.recorded_calls();
+        assert_eq!(audited.calls(), recorder.calls());
+
+        recorder.clear();
+        assert!(recorder.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recorder_sees_requests_failed_by_later_interceptors() {
+        let recorder = CallRecorder::new();
+        let deny = Funcs::new().create(|_| Err(Error::Internal("denied".to_string())));
+        let client = ClientBuilder::new()
+            .with_interceptor_funcs(recorder.funcs().merge(deny))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        configmaps
+            .create(&PostParams::default(), &configmap("web"))
+            .await
+            .unwrap_err();
+        assert_eq!(recorder.count_named(Verb::Create, "web"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_and_subscribe() {
+        let recorder = CallRecorder::new();
+        let mut calls = recorder.subscribe();
+        let client = ClientBuilder::new()
+            .with_interceptor_funcs(recorder.funcs())
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let waiting = tokio::spawn({
+            let recorder = recorder.clone();
+            async move { recorder.wait_for(Verb::Create, 2).await }
+        });
+        for name in ["a", "b"] {
+            configmaps
+                .create(&PostParams::default(), &configmap(name))
+                .await
+                .unwrap();
+        }
+        tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .expect("both creates were recorded")
+            .unwrap();
+
+        let first = calls.recv().await.unwrap();
+        assert_eq!(first.name.as_deref(), Some("a"));
+        assert_eq!(calls.recv().await.unwrap().name.as_deref(), Some("b"));
+    }
+}
@@ -5,9 +5,12 @@
 //! before they can be used, the fake client requires custom resources to be
 //! explicitly registered.
 
+use crate::discovery::Discovery;
+use crate::tracker::GVK;
+use kube::core::ApiResource;
 use kube::Resource;
-use std::collections::HashMap;
-use std::sync::RwLock;
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
 
 /// Metadata for a registered resource type
 #[derive(Debug, Clone)]
@@ -22,8 +25,39 @@ pub struct ResourceMetadata {
     pub plural: String,
     /// Whether the resource is namespaced
     pub namespaced: bool,
+    /// Verbs the resource supports (e.g., "get", "list")
+    pub verbs: Vec<String>,
+    /// Subresources the resource serves (e.g., "status", "scale")
+    pub subresources: Vec<String>,
 }
 
+/// Where the scale subresource of a custom resource reads and writes its fields
+///
+/// Mirrors `subresources.scale` of a CustomResourceDefinition. Paths are dotted
+/// field paths such as `spec.replicas`; a leading `.`, as CRDs write them, is
+/// accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScaleSubresource {
+    /// Field holding the desired replica count (`specReplicasPath`)
+    pub spec_replicas_path: String,
+    /// Field holding the observed replica count (`statusReplicasPath`)
+    pub status_replicas_path: String,
+    /// Field holding the pods' label selector in string form (`labelSelectorPath`)
+    pub label_selector_path: Option<String>,
+}
+
+/// Verbs supported by custom resources
+pub(crate) const STANDARD_VERBS: &[&str] = &[
+    "create",
+    "delete",
+    "deletecollection",
+    "get",
+    "list",
+    "patch",
+    "update",
+    "watch",
+];
+
 /// Registry for custom resource types
 ///
 /// Stores metadata about registered CRDs to enable URL parsing and discovery.
@@ -33,6 +67,10 @@ pub struct ResourceRegistry {
     /// Lookup by (group, version, plural) -> ResourceMetadata
     /// Uses RwLock for interior mutability instead of Arc cloning
     resources: RwLock<HashMap<(String, String, String), ResourceMetadata>>,
+    /// Scale subresource mappings by (group, version, kind)
+    scales: RwLock<HashMap<(String, String, String), ScaleSubresource>>,
+    /// Built-in resources the cluster doesn't serve, by (group, version, kind)
+    removed: RwLock<HashSet<(String, String, String)>>,
 }
 
 impl ResourceRegistry {
@@ -40,47 +78,155 @@ impl ResourceRegistry {
     pub fn new() -> Self {
         Self {
             resources: RwLock::new(HashMap::new()),
+            scales: RwLock::new(HashMap::new()),
+            removed: RwLock::new(HashSet::new()),
         }
     }
 
     /// Register a resource type using its Resource trait implementation
     ///
     /// Extracts metadata from the type's Resource trait and stores it for lookup.
+    /// Built-in resources are known already, so registering one has no effect;
+    /// use [`register_metadata`](Self::register_metadata) to change how one is
+    /// served.
     pub fn register<K: Resource<DynamicType = ()>>(&self) {
-        let kind = K::kind(&()).into_owned();
-        let group = K::group(&()).into_owned();
-        let version = K::version(&()).into_owned();
-        let plural = K::plural(&()).into_owned();
-
+        if is_builtin(&K::group(&()), &K::version(&()), &K::kind(&())) {
+            return;
+        }
         // Determine if namespaced by checking the Scope type
         // For now, we'll use a heuristic: if it has `fn namespaced()` we can call it
         // Otherwise default to true (most CRDs are namespaced)
         let namespaced = is_namespaced_resource();
 
-        let metadata = ResourceMetadata {
-            kind: kind.clone(),
-            group: group.clone(),
-            version: version.clone(),
-            plural: plural.clone(),
+        self.insert(ResourceMetadata {
+            kind: K::kind(&()).into_owned(),
+            group: K::group(&()).into_owned(),
+            version: K::version(&()).into_owned(),
+            plural: K::plural(&()).into_owned(),
             namespaced,
-        };
+            verbs: standard_verbs(),
+            subresources: Vec::new(),
+        });
+    }
 
-        let key = (group, version, plural);
-        self.resources
+    /// Register a resource described by an [`ApiResource`] without a Rust type
+    ///
+    /// Used with `DynamicObject` for custom resources only known at runtime.
+    /// Built-in resources are known already, so registering one has no effect.
+    pub fn register_api_resource(&self, resource: &ApiResource, namespaced: bool) {
+        if is_builtin(&resource.group, &resource.version, &resource.kind) {
+            return;
+        }
+        self.insert(ResourceMetadata {
+            kind: resource.kind.clone(),
+            group: resource.group.clone(),
+            version: resource.version.clone(),
+            plural: resource.plural.clone(),
+            namespaced,
+            verbs: standard_verbs(),
+            subresources: Vec::new(),
+        });
+    }
+
+    /// Register a resource exactly as described
+    ///
+    /// Unlike the other registration methods this also applies to built-in
+    /// resources, replacing their generated discovery data, so their scope, verbs
+    /// and subresources can be changed.
+    pub fn register_metadata(&self, metadata: ResourceMetadata) {
+        self.insert(metadata);
+    }
+
+    /// Stop serving a built-in resource, as on a cluster without it
+    ///
+    /// The resource is left out of discovery and requests for it fail with 404
+    /// Not Found. A resource registered for the same kind is still served.
+    pub fn remove_builtin(&self, group: &str, version: &str, kind: &str) {
+        self.removed
             .write()
-            .expect("ResourceRegistry lock poisoned")
-            .insert(key, metadata);
+            .insert((group.to_string(), version.to_string(), kind.to_string()));
+    }
+
+    /// Whether a built-in resource was removed with [`remove_builtin`](Self::remove_builtin)
+    pub fn is_removed(&self, group: &str, version: &str, kind: &str) -> bool {
+        self.removed
+            .read()
+            .contains(&(group.to_string(), version.to_string(), kind.to_string()))
+    }
+
+    fn insert(&self, metadata: ResourceMetadata) {
+        let key = (
+            metadata.group.clone(),
+            metadata.version.clone(),
+            metadata.plural.clone(),
+        );
+        let mut resources = self.resources.write();
+        // A kind is served under one plural only
+        resources.retain(|_, m| {
+            (&m.group, &m.version, &m.kind) != (&metadata.group, &metadata.version, &metadata.kind)
+        });
+        resources.insert(key, metadata);
+    }
+
+    /// Declare a subresource of a registered resource, such as `approve`
+    ///
+    /// Returns false if no resource of the kind is registered.
+    pub fn add_subresource(&self, group: &str, version: &str, kind: &str, name: &str) -> bool {
+        let mut resources = self.resources.write();
+        let Some(metadata) = resources
+            .values_mut()
+            .find(|m| m.group == group && m.version == version && m.kind == kind)
+        else {
+            return false;
+        };
+        if !metadata.subresources.iter().any(|s| s == name) {
+            metadata.subresources.push(name.to_string());
+        }
+        true
+    }
+
+    /// Map the scale subresource of a registered resource onto its fields
+    ///
+    /// Also declares the `scale` subresource. Returns false if no resource of
+    /// the kind is registered.
+    pub fn set_scale(
+        &self,
+        group: &str,
+        version: &str,
+        kind: &str,
+        scale: ScaleSubresource,
+    ) -> bool {
+        if !self.add_subresource(group, version, kind, "scale") {
+            return false;
+        }
+        self.scales.write().insert(
+            (group.to_string(), version.to_string(), kind.to_string()),
+            scale,
+        );
+        true
+    }
+
+    /// The scale subresource mapping of a resource, if one is registered
+    pub fn scale(&self, group: &str, version: &str, kind: &str) -> Option<ScaleSubresource> {
+        self.scales
+            .read()
+            .get(&(group.to_string(), version.to_string(), kind.to_string()))
+            .cloned()
     }
 
     /// Look up a resource by (group, version, plural)
     pub fn lookup(&self, group: &str, version: &str, plural: &str) -> Option<ResourceMetadata> {
         self.resources
             .read()
-            .expect("ResourceRegistry lock poisoned")
             .get(&(group.to_string(), version.to_string(), plural.to_string()))
             .cloned()
     }
 
+    /// List all registered resources
+    pub fn list(&self) -> Vec<ResourceMetadata> {
+        self.resources.read().values().cloned().collect()
+    }
+
     /// Get the Kind for a given plural name
     pub fn plural_to_kind(&self, group: &str, version: &str, plural: &str) -> Option<String> {
         self.lookup(group, version, plural).map(|m| m.kind)
@@ -95,7 +241,6 @@ impl ResourceRegistry {
     ) -> Option<ResourceMetadata> {
         self.resources
             .read()
-            .expect("ResourceRegistry lock poisoned")
             .values()
             .find(|m| m.group == group && m.version == version && m.kind == kind)
             .cloned()
@@ -113,6 +258,15 @@ impl ResourceRegistry {
     }
 }
 
+/// Whether a kind is in the generated discovery data
+fn is_builtin(group: &str, version: &str, kind: &str) -> bool {
+    Discovery::get_plural(&GVK::new(group, version, kind)).is_some()
+}
+
+fn standard_verbs() -> Vec<String> {
+    STANDARD_VERBS.iter().map(|verb| verb.to_string()).collect()
+}
+
 /// Helper to determine if a Resource type is namespaced
 fn is_namespaced_resource() -> bool {
     // Check if K::Scope implements the namespaced trait
@@ -137,4 +291,34 @@ mod tests {
         let registry = ResourceRegistry::new();
         assert!(registry.lookup("example.com", "v1", "myapps").is_none());
     }
+
+    #[test]
+    fn test_registering_builtin_has_no_effect() {
+        use k8s_openapi::api::core::v1::Node;
+
+        let registry = ResourceRegistry::new();
+        registry.register::<Node>();
+        assert!(registry.lookup_by_kind("", "v1", "Node").is_none());
+    }
+
+    #[test]
+    fn test_register_metadata_replaces_kind() {
+        let registry = ResourceRegistry::new();
+        for plural in ["widgets", "widgetz"] {
+            registry.register_metadata(ResourceMetadata {
+                kind: "Widget".to_string(),
+                group: "example.com".to_string(),
+                version: "v1".to_string(),
+                plural: plural.to_string(),
+                namespaced: true,
+                verbs: vec!["get".to_string()],
+                subresources: Vec::new(),
+            });
+        }
+        assert_eq!(registry.list().len(), 1);
+        assert_eq!(
+            registry.kind_to_plural("example.com", "v1", "Widget"),
+            Some("widgetz".to_string())
+        );
+    }
 }
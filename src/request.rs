@@ -0,0 +1,90 @@
+//! Context of the request being served
+//!
+//! While the mock service handles a request, interceptors can read who made it
+//! and how through [`interceptor::current_request`](crate::interceptor::current_request).
+//! Tests attach tags to the requests made by a piece of code with
+//! [`ClientHandle::with_request_tag`](crate::ClientHandle::with_request_tag), so
+//! requests can be attributed to, say, one reconcile in assertions on the audit
+//! log.
+
+use crate::rbac::Identity;
+use http::HeaderMap;
+use std::collections::BTreeMap;
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: RequestContext;
+    static TAGS: Vec<String>;
+}
+
+/// The request being served
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// Unique ID of the request, the same as its audit entry's
+    pub id: String,
+    /// HTTP method
+    pub method: String,
+    /// Request URI, including the query string
+    pub uri: String,
+    /// Request headers by lowercase name; repeated headers are joined with `, `
+    pub headers: BTreeMap<String, String>,
+    /// User the request was made as, if the client has an identity
+    pub user: Option<String>,
+    /// Groups of the user
+    pub groups: Vec<String>,
+    /// Tags attached with `ClientHandle::with_request_tag`, outermost first
+    pub tags: Vec<String>,
+}
+
+impl RequestContext {
+    pub(crate) fn new(
+        id: String,
+        method: &http::Method,
+        uri: String,
+        headers: &HeaderMap,
+        identity: Option<&Identity>,
+    ) -> Self {
+        let mut by_name: BTreeMap<String, String> = BTreeMap::new();
+        for (name, value) in headers {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            by_name
+                .entry(name.as_str().to_string())
+                .and_modify(|joined| {
+                    joined.push_str(", ");
+                    joined.push_str(&value);
+                })
+                .or_insert_with(|| value.into_owned());
+        }
+        Self {
+            id,
+            method: method.to_string(),
+            uri,
+            headers: by_name,
+            user: identity.map(|i| i.user.clone()),
+            groups: identity.map(|i| i.groups.clone()).unwrap_or_default(),
+            tags: tags(),
+        }
+    }
+}
+
+/// The request being served, if any
+pub(crate) fn current() -> Option<RequestContext> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Run a future as the handling of a request
+pub(crate) async fn serve<F: Future>(context: RequestContext, future: F) -> F::Output {
+    CURRENT.scope(context, future).await
+}
+
+/// Tags attached to the requests made from the current task
+pub(crate) fn tags() -> Vec<String> {
+    TAGS.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Run a future with a tag attached to the requests it makes
+pub(crate) async fn tagged<F: Future>(tag: String, future: F) -> F::Output {
+    let mut tags = tags();
+    tags.push(tag);
+    TAGS.scope(tags, future).await
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::interceptor::{self, RequestContext};
+    use crate::test_fixtures::configmap;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, PostParams};
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_interceptors_see_request_context() {
+        let seen: Arc<Mutex<Vec<RequestContext>>> = Arc::default();
+        let recorded = Arc::clone(&seen);
+        let (client, handle) = ClientBuilder::new()
+            .with_identity("alice", ["developers", "system:masters"])
+            .with_interceptor_funcs(interceptor::Funcs::new().create(move |_| {
+                recorded
+                    .lock()
+                    .push(interceptor::current_request().expect("inside a request"));
+                Ok(None)
+            }))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+
+        configmaps
+            .create(&PostParams::default(), &configmap("settings"))
+            .await
+            .unwrap();
+
+        let seen = seen.lock();
+        let request = &seen[0];
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.uri, "/api/v1/namespaces/default/configmaps?");
+        assert_eq!(
+            request.headers.get("content-type").map(String::as_str),
+            Some("application/json")
+        );
+        assert_eq!(request.user.as_deref(), Some("alice"));
+        assert_eq!(request.groups, ["developers", "system:masters"]);
+        assert!(request.tags.is_empty());
+        assert_eq!(request.id, handle.audit_entries()[0].id);
+        assert!(interceptor::current_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_tags() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+
+        configmaps.get_opt("before").await.unwrap();
+        handle
+            .with_request_tag("reconcile-1", async {
+                configmaps
+                    .create(&PostParams::default(), &configmap("settings"))
+                    .await
+                    .unwrap();
+                handle
+                    .with_request_tag("cleanup", configmaps.get_opt("stale"))
+                    .await
+                    .unwrap();
+            })
+            .await;
+        configmaps.get_opt("after").await.unwrap();
+
+        let tags: Vec<Vec<String>> = handle
+            .audit_entries()
+            .into_iter()
+            .map(|entry| entry.tags)
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                vec![],
+                vec!["reconcile-1".to_string()],
+                vec!["reconcile-1".to_string(), "cleanup".to_string()],
+                vec![],
+            ]
+        );
+        let event = handle.audit_entries()[2].to_event();
+        assert_eq!(
+            event["annotations"]["kube-fake-client/tags"],
+            "reconcile-1,cleanup"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! HTTP response bodies served by the mock service
+//!
+//! Single objects are sent as one buffer. Lists are serialized incrementally:
+//! the items are shared with the tracker and written out a chunk at a time as the
+//! client reads the body, so a list of tens of thousands of objects never exists
+//! as a single JSON string. Watches stream events as they are published.
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use http_body::{Body, Frame, SizeHint};
+use serde_json::Value;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Size at which a chunk of serialized list items is sent
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Body of a mock service response
+pub struct ResponseBody(Kind);
+
+enum Kind {
+    Full(Option<Bytes>),
+    List {
+        head: Option<Bytes>,
+        items: std::vec::IntoIter<Arc<Value>>,
+        first: bool,
+        done: bool,
+    },
+    Stream(BoxStream<'static, Bytes>),
+}
+
+impl ResponseBody {
+    /// A body sent in one piece
+    pub(crate) fn full(data: impl Into<Bytes>) -> Self {
+        let data = data.into();
+        Self(Kind::Full((!data.is_empty()).then_some(data)))
+    }
+
+    /// The whole body, unless it is a streamed list
+    pub(crate) fn as_full(&self) -> Option<&Bytes> {
+        match &self.0 {
+            Kind::Full(data) => data.as_ref(),
+            Kind::List { .. } | Kind::Stream(_) => None,
+        }
+    }
+
+    /// A body sent chunk by chunk as the stream yields them
+    pub(crate) fn stream(chunks: BoxStream<'static, Bytes>) -> Self {
+        Self(Kind::Stream(chunks))
+    }
+
+    /// A list body streamed item by item
+    ///
+    /// `envelope` holds every field of the list except `items` and must be a JSON
+    /// object.
+    pub(crate) fn list(envelope: &Value, items: Vec<Arc<Value>>) -> Self {
+        let mut head = envelope.to_string().into_bytes();
+        debug_assert_eq!(head.last(), Some(&b'}'));
+        head.pop();
+        if head.len() > 1 {
+            head.push(b',');
+        }
+        head.extend_from_slice(b"\"items\":[");
+        Self(Kind::List {
+            head: Some(head.into()),
+            items: items.into_iter(),
+            first: true,
+            done: false,
+        })
+    }
+}
+
+impl Body for ResponseBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        let chunk = match &mut self.get_mut().0 {
+            Kind::Full(data) => data.take(),
+            Kind::Stream(chunks) => {
+                return chunks
+                    .poll_next_unpin(cx)
+                    .map(|chunk| chunk.map(|data| Ok(Frame::data(data))));
+            }
+            Kind::List {
+                head,
+                items,
+                first,
+                done,
+            } => {
+                if let Some(head) = head.take() {
+                    Some(head)
+                } else if *done {
+                    None
+                } else {
+                    let mut buf = Vec::with_capacity(CHUNK_SIZE);
+                    for item in items.by_ref() {
+                        if !std::mem::take(first) {
+                            buf.push(b',');
+                        }
+                        serde_json::to_writer(&mut buf, item.as_ref())
+                            .expect("serializing a JSON value cannot fail");
+                        if buf.len() >= CHUNK_SIZE {
+                            break;
+                        }
+                    }
+                    if items.len() == 0 {
+                        buf.extend_from_slice(b"]}");
+                        *done = true;
+                    }
+                    Some(buf.into())
+                }
+            }
+        };
+        Poll::Ready(chunk.map(|data| Ok(Frame::data(data))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.0 {
+            Kind::Full(data) => data.is_none(),
+            Kind::List { done, .. } => *done,
+            Kind::Stream(_) => false,
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.0 {
+            Kind::Full(data) => SizeHint::with_exact(data.as_ref().map_or(0, |d| d.len() as u64)),
+            Kind::List { .. } | Kind::Stream(_) => SizeHint::default(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::response_body::ResponseBody;
+    use crate::ClientBuilder;
+    use http_body::Body;
+    use http_body_util::BodyExt;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, ListParams};
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    fn envelope() -> Value {
+        json!({ "kind": "ConfigMapList", "apiVersion": "v1", "metadata": { "resourceVersion": "1" } })
+    }
+
+    async fn frames(mut body: ResponseBody) -> Vec<bytes::Bytes> {
+        let mut frames = Vec::new();
+        while let Some(frame) = body.frame().await {
+            frames.push(frame.unwrap().into_data().unwrap());
+        }
+        assert!(body.is_end_stream());
+        frames
+    }
+
+    #[tokio::test]
+    async fn test_full_body() {
+        let body = ResponseBody::full(r#"{"kind":"Status"}"#);
+        assert_eq!(body.size_hint().exact(), Some(17));
+
+        let frames = frames(body).await;
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0], r#"{"kind":"Status"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_empty_list() {
+        let body = ResponseBody::list(&envelope(), Vec::new());
+        let bytes = body.collect().await.unwrap().to_bytes();
+
+        let list: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(list["kind"], "ConfigMapList");
+        assert_eq!(list["metadata"]["resourceVersion"], "1");
+        assert_eq!(list["items"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_large_list_is_streamed_in_chunks() {
+        let items: Vec<Arc<Value>> = (0..2000)
+            .map(|i| Arc::new(json!({ "metadata": { "name": format!("cm-{i}") }, "data": { "key": "x".repeat(100) } })))
+            .collect();
+        let frames = frames(ResponseBody::list(&envelope(), items.clone())).await;
+        assert!(
+            frames.len() > 2,
+            "expected several chunks, got {}",
+            frames.len()
+        );
+
+        let bytes: Vec<u8> = frames.concat();
+        let list: Value = serde_json::from_slice(&bytes).unwrap();
+        let names: Vec<&str> = list["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["metadata"]["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names.len(), 2000);
+        assert_eq!(names[0], "cm-0");
+        assert_eq!(names[1999], "cm-1999");
+    }
+
+    #[tokio::test]
+    async fn test_large_list_through_client() {
+        let objects: Vec<ConfigMap> = (0..5000)
+            .map(|i| {
+                let mut cm = ConfigMap::default();
+                cm.metadata.name = Some(format!("cm-{i}"));
+                cm.metadata.namespace = Some("default".to_string());
+                cm.metadata.labels = Some(BTreeMap::from([(
+                    "even".to_string(),
+                    (i % 2 == 0).to_string(),
+                )]));
+                cm.data = Some(BTreeMap::from([("key".to_string(), "x".repeat(100))]));
+                cm
+            })
+            .collect();
+        let client = ClientBuilder::new()
+            .with_objects(objects)
+            .build()
+            .await
+            .unwrap();
+        let api: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let all = api.list(&ListParams::default()).await.unwrap();
+        assert_eq!(all.items.len(), 5000);
+
+        let even = api
+            .list(&ListParams::default().labels("even=true"))
+            .await
+            .unwrap();
+        assert_eq!(even.items.len(), 2500);
+    }
+}
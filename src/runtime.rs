@@ -0,0 +1,82 @@
+//! Timers the fake waits on, independent of the executor running it
+//!
+//! The fake itself only needs an executor to poll its futures: requests are
+//! served, watched and intercepted the same under tokio, `futures::executor` or
+//! any other. Waiting for time to pass is the exception. On a tokio runtime,
+//! waits follow the tokio clock, so paused time and `ClientHandle::advance`
+//! control them. Elsewhere they run on a timer thread of their own, so targets
+//! that can't spawn threads need a tokio runtime.
+
+use futures::channel::oneshot;
+use futures::future::{self, Either};
+use std::future::Future;
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+
+/// Wait for a duration
+pub(crate) async fn sleep(duration: Duration) {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return tokio::time::sleep(duration).await;
+    }
+    sleep_on_thread(duration).await
+}
+
+/// Run a function once a duration has passed, without waiting for it
+pub(crate) fn run_after(duration: Duration, f: impl FnOnce() + Send + 'static) {
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(async move {
+            tokio::time::sleep(duration).await;
+            f();
+        });
+        return;
+    }
+    on_timer_thread(duration, f);
+}
+
+/// Run a future for at most a duration, `None` if it didn't finish in time
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    match future::select(pin!(future), pin!(sleep(duration))).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(((), _)) => None,
+    }
+}
+
+/// Let other tasks of the executor run before continuing
+pub(crate) async fn yield_now() {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return tokio::task::yield_now().await;
+    }
+    let mut yielded = false;
+    future::poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
+
+async fn sleep_on_thread(duration: Duration) {
+    let (elapsed, wait) = oneshot::channel::<()>();
+    on_timer_thread(duration, move || {
+        let _ = elapsed.send(());
+    });
+    let _ = wait.await;
+}
+
+fn on_timer_thread(duration: Duration, f: impl FnOnce() + Send + 'static) {
+    std::thread::Builder::new()
+        .name("kube-fake-client-timer".to_string())
+        .spawn(move || {
+            std::thread::sleep(duration);
+            f();
+        })
+        .unwrap_or_else(|e| {
+            panic!(
+                "waiting outside a tokio runtime needs a timer thread, which failed to spawn: {e}"
+            )
+        });
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use crate::{runtime, ClientBuilder, Error};
+    use futures::executor::block_on;
+    use http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use k8s_openapi::api::core::v1::Pod;
+    use kube::client::Body;
+    use serde_json::{json, Value};
+    use std::time::{Duration, Instant};
+    use tower::{Service, ServiceExt};
+
+    #[test]
+    fn test_timers_run_without_tokio() {
+        block_on(async {
+            let start = Instant::now();
+            runtime::sleep(Duration::from_millis(20)).await;
+            assert!(start.elapsed() >= Duration::from_millis(20));
+
+            let slow = runtime::sleep(Duration::from_secs(60));
+            assert_eq!(
+                runtime::timeout(Duration::from_millis(10), slow).await,
+                None
+            );
+            let fast = async { 7 };
+            assert_eq!(
+                runtime::timeout(Duration::from_secs(60), fast).await,
+                Some(7)
+            );
+            runtime::yield_now().await;
+        });
+    }
+
+    #[test]
+    fn test_service_serves_requests_without_tokio() {
+        block_on(async {
+            let (mut service, handle) = ClientBuilder::new().build_service().await.unwrap();
+            let configmap = json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": { "name": "settings" },
+                "data": { "mode": "slow" },
+            });
+            let request = Request::post("/api/v1/namespaces/default/configmaps")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&configmap).unwrap()))
+                .unwrap();
+            let response = service.ready().await.unwrap().call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert_eq!(handle.list_namespace("default").unwrap().len(), 1);
+
+            // Watches with a timeout end on the timer thread
+            let request =
+                Request::get("/api/v1/namespaces/default/configmaps?watch=true&timeoutSeconds=1")
+                    .body(Body::empty())
+                    .unwrap();
+            let response = service.ready().await.unwrap().call(request).await.unwrap();
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let events: Vec<Value> = body
+                .split(|b| *b == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| serde_json::from_slice(line).unwrap())
+                .collect();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0]["type"], "ADDED");
+        });
+    }
+
+    #[test]
+    fn test_graceful_pod_deletion_finishes_without_tokio() {
+        block_on(async {
+            let (mut service, handle) = ClientBuilder::new().build_service().await.unwrap();
+            let pod = json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "metadata": { "name": "web" },
+                "spec": { "nodeName": "node-1", "containers": [{ "name": "web", "image": "nginx" }] },
+            });
+            let request = Request::post("/api/v1/namespaces/default/pods")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&pod).unwrap()))
+                .unwrap();
+            let response = service.ready().await.unwrap().call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+
+            let request =
+                Request::delete("/api/v1/namespaces/default/pods/web?gracePeriodSeconds=1")
+                    .body(Body::empty())
+                    .unwrap();
+            let response = service.ready().await.unwrap().call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let terminating: Pod = handle.fake_client().get("default", "web").unwrap();
+            assert!(terminating.metadata.deletion_timestamp.is_some());
+
+            std::thread::sleep(Duration::from_millis(1500));
+            assert!(handle.list_namespace("default").unwrap().is_empty());
+        });
+    }
+
+    #[tokio::test]
+    async fn test_build_service_rejects_layers() {
+        let result = ClientBuilder::new()
+            .with_layer(tower::layer::layer_fn(|service| service))
+            .build_service()
+            .await;
+        assert!(matches!(result, Err(Error::InvalidConfiguration(_))));
+    }
+}
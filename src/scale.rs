@@ -0,0 +1,121 @@
+//! Scale subresource of custom resources
+//!
+//! A custom resource registered with a [`ScaleSubresource`] mapping serves
+//! `autoscaling/v1` `Scale` objects built from the mapped fields, and writes to
+//! its scale set the mapped replica field, as the API server does for a CRD
+//! with `subresources.scale`. Built-in workloads keep their replica counts at
+//! `spec.replicas` and `status.replicas`.
+
+use crate::client::FakeClient;
+use crate::registry::ScaleSubresource;
+use crate::tracker::GVK;
+use crate::{Error, Result};
+use serde_json::{json, Map, Value};
+
+/// The scale mapping registered for a custom resource
+pub(crate) fn mapping(client: &FakeClient, gvk: &GVK) -> Option<ScaleSubresource> {
+    client.registry.scale(&gvk.group, &gvk.version, &gvk.kind)
+}
+
+/// Where the replica counts of a scale target are, registered or built-in
+pub(crate) fn target_mapping(client: &FakeClient, gvk: &GVK) -> ScaleSubresource {
+    mapping(client, gvk).unwrap_or_else(|| ScaleSubresource {
+        spec_replicas_path: "spec.replicas".to_string(),
+        status_replicas_path: "status.replicas".to_string(),
+        label_selector_path: None,
+    })
+}
+
+/// The desired replica count of an object
+pub(crate) fn spec_replicas(object: &Value, mapping: &ScaleSubresource) -> Option<i64> {
+    field(object, &mapping.spec_replicas_path).and_then(Value::as_i64)
+}
+
+/// The observed replica count of an object
+pub(crate) fn status_replicas(object: &Value, mapping: &ScaleSubresource) -> Option<i64> {
+    field(object, &mapping.status_replicas_path).and_then(Value::as_i64)
+}
+
+/// Set the desired replica count of an object
+pub(crate) fn set_spec_replicas(object: &mut Value, mapping: &ScaleSubresource, replicas: i64) {
+    let mut node = object;
+    let mut segments = segments(&mapping.spec_replicas_path).peekable();
+    while let Some(segment) = segments.next() {
+        if !node.is_object() {
+            *node = Value::Object(Map::new());
+        }
+        let fields = node.as_object_mut().expect("just made an object");
+        if segments.peek().is_none() {
+            fields.insert(segment.to_string(), json!(replicas));
+            return;
+        }
+        node = fields
+            .entry(segment)
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+/// The `Scale` of an object
+pub(crate) fn to_scale(object: &Value, mapping: &ScaleSubresource) -> Value {
+    let mut metadata = Map::new();
+    for key in [
+        "name",
+        "namespace",
+        "uid",
+        "resourceVersion",
+        "creationTimestamp",
+    ] {
+        if let Some(value) = object["metadata"].get(key) {
+            metadata.insert(key.to_string(), value.clone());
+        }
+    }
+    let mut scale = json!({
+        "apiVersion": "autoscaling/v1",
+        "kind": "Scale",
+        "metadata": metadata,
+        "spec": { "replicas": spec_replicas(object, mapping).unwrap_or(0) },
+        "status": { "replicas": status_replicas(object, mapping).unwrap_or(0) },
+    });
+    if let Some(selector) = mapping
+        .label_selector_path
+        .as_deref()
+        .and_then(|path| field(object, path))
+        .and_then(Value::as_str)
+    {
+        scale["status"]["selector"] = json!(selector);
+    }
+    scale
+}
+
+/// Write the replica count of a `Scale` to its object
+///
+/// A `resourceVersion` on the `Scale` is kept as a precondition of the write.
+pub(crate) fn apply(object: &mut Value, scale: &Value, mapping: &ScaleSubresource) -> Result<()> {
+    let replicas = match scale.pointer("/spec/replicas") {
+        None | Some(Value::Null) => 0,
+        Some(replicas) => replicas.as_i64().ok_or_else(|| {
+            Error::BadRequest(format!(
+                "Scale.spec.replicas must be an integer, got {replicas}"
+            ))
+        })?,
+    };
+    if replicas < 0 {
+        return Err(Error::ValidationFailed {
+            kind: "Scale".to_string(),
+            errors: vec!["spec.replicas: must be greater than or equal to 0".to_string()],
+        });
+    }
+    set_spec_replicas(object, mapping, replicas);
+    if let Some(resource_version) = scale.pointer("/metadata/resourceVersion") {
+        object["metadata"]["resourceVersion"] = resource_version.clone();
+    }
+    Ok(())
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.trim_start_matches('.').split('.')
+}
+
+fn field<'v>(object: &'v Value, path: &str) -> Option<&'v Value> {
+    segments(path).try_fold(object, |node, segment| node.get(segment))
+}
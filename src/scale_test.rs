@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use crate::{ClientBuilder, ClientHandle};
+    use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use kube::{Client, CustomResource};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+    #[kube(
+        group = "example.com",
+        version = "v1",
+        kind = "Worker",
+        plural = "workers",
+        namespaced,
+        status = "WorkerStatus"
+    )]
+    struct WorkerSpec {
+        size: i64,
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+    struct WorkerStatus {
+        ready: i64,
+        selector: String,
+    }
+
+    async fn setup() -> (Api<Worker>, Client, ClientHandle) {
+        let (client, handle) = ClientBuilder::new()
+            .with_resource_scaled::<Worker>(".spec.size", ".status.ready", ".status.selector")
+            .build_with_handle()
+            .await
+            .unwrap();
+        let api: Api<Worker> = Api::default_namespaced(client.clone());
+        let mut worker = Worker::new("pool", WorkerSpec { size: 2 });
+        worker.status = Some(WorkerStatus {
+            ready: 1,
+            selector: "app=pool".to_string(),
+        });
+        api.create(&PostParams::default(), &worker).await.unwrap();
+        (api, client, handle)
+    }
+
+    fn error_code(err: kube::Error) -> u16 {
+        match err {
+            kube::Error::Api(response) => response.code,
+            other => panic!("expected an API error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_scale() {
+        let (api, _, _) = setup().await;
+
+        let scale = api.get_scale("pool").await.unwrap();
+        assert_eq!(scale.metadata.name.as_deref(), Some("pool"));
+        assert_eq!(scale.spec.unwrap().replicas, Some(2));
+        let status = scale.status.unwrap();
+        assert_eq!(status.replicas, 1);
+        assert_eq!(status.selector.as_deref(), Some("app=pool"));
+    }
+
+    #[tokio::test]
+    async fn test_patch_and_replace_scale() {
+        let (api, _, _) = setup().await;
+
+        let scale = api
+            .patch_scale(
+                "pool",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "spec": { "replicas": 5 } })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(scale.spec.unwrap().replicas, Some(5));
+        assert_eq!(api.get("pool").await.unwrap().spec.size, 5);
+
+        let mut scale = api.get_scale("pool").await.unwrap();
+        scale.spec.as_mut().unwrap().replicas = Some(3);
+        let replaced = api
+            .replace_scale(
+                "pool",
+                &PostParams::default(),
+                serde_json::to_vec(&scale).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(replaced.spec.unwrap().replicas, Some(3));
+        assert_eq!(api.get("pool").await.unwrap().spec.size, 3);
+
+        // The Scale's resourceVersion is a precondition
+        let err = api
+            .replace_scale(
+                "pool",
+                &PostParams::default(),
+                serde_json::to_vec(&scale).unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 409);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_scale() {
+        let (api, _, _) = setup().await;
+
+        let err = api
+            .patch_scale(
+                "pool",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "spec": { "replicas": -1 } })),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 422);
+
+        let err = api.get_scale("missing").await.unwrap_err();
+        assert_eq!(error_code(err), 404);
+    }
+
+    #[tokio::test]
+    async fn test_hpa_scales_custom_resource() {
+        let (api, client, handle) = setup().await;
+        let hpa: HorizontalPodAutoscaler = serde_json::from_value(json!({
+            "apiVersion": "autoscaling/v2",
+            "kind": "HorizontalPodAutoscaler",
+            "metadata": { "name": "pool" },
+            "spec": {
+                "scaleTargetRef": { "apiVersion": "example.com/v1", "kind": "Worker", "name": "pool" },
+                "minReplicas": 1,
+                "maxReplicas": 10,
+                "metrics": [{
+                    "type": "Resource",
+                    "resource": {
+                        "name": "cpu",
+                        "target": { "type": "Utilization", "averageUtilization": 50 },
+                    },
+                }],
+            },
+        }))
+        .unwrap();
+        Api::<HorizontalPodAutoscaler>::default_namespaced(client)
+            .create(&PostParams::default(), &hpa)
+            .await
+            .unwrap();
+
+        // One ready replica at twice the target scales to two
+        let hpa = handle
+            .set_hpa_metrics("default", "pool", &[("cpu", 100.0)])
+            .unwrap();
+        let status = hpa.status.unwrap();
+        assert_eq!(status.current_replicas, Some(1));
+        assert_eq!(status.desired_replicas, 2);
+
+        let hpa = handle
+            .set_hpa_metrics("default", "pool", &[("cpu", 200.0)])
+            .unwrap();
+        assert_eq!(hpa.status.unwrap().desired_replicas, 4);
+        assert_eq!(api.get("pool").await.unwrap().spec.size, 4);
+    }
+}
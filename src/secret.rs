@@ -0,0 +1,93 @@
+//! Secret data handling
+//!
+//! Mirrors the API server's treatment of Secrets on write: `stringData` is a
+//! write-only convenience that is base64-encoded into `data`, `data` values must
+//! be valid base64, keys must be valid config keys, and the total payload is
+//! limited to 1MiB.
+
+use crate::{Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+
+/// Maximum total size of a Secret's data in bytes
+pub(crate) const MAX_SECRET_SIZE: usize = 1024 * 1024;
+
+/// Merge `stringData` into `data` and validate the result
+pub(crate) fn prepare(secret: &mut Value) -> Result<()> {
+    let name = secret
+        .pointer("/metadata/name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let invalid =
+        |detail: String| Error::InvalidRequest(format!("Secret \"{name}\" is invalid: {detail}"));
+
+    let Some(obj) = secret.as_object_mut() else {
+        return Ok(());
+    };
+
+    // stringData entries take precedence over data entries with the same key
+    if let Some(string_data) = obj.remove("stringData") {
+        let string_data = match string_data {
+            Value::Object(map) => map,
+            Value::Null => Default::default(),
+            _ => {
+                return Err(invalid(
+                    "stringData: Invalid value: must be an object".to_string(),
+                ))
+            }
+        };
+        let data = obj.entry("data").or_insert_with(|| json!({}));
+        if data.is_null() {
+            *data = json!({});
+        }
+        for (key, value) in string_data {
+            let Some(value) = value.as_str() else {
+                return Err(invalid(format!(
+                    "stringData[{key}]: Invalid value: must be a string"
+                )));
+            };
+            data[key.as_str()] = json!(STANDARD.encode(value));
+        }
+    }
+
+    let Some(data) = obj.get("data").and_then(|v| v.as_object()) else {
+        return Ok(());
+    };
+
+    let mut total = 0;
+    for (key, value) in data {
+        if !is_config_key(key) {
+            return Err(invalid(format!(
+                "data[{key}]: Invalid value: \"{key}\": a valid config key must consist of \
+                 alphanumeric characters, '-', '_' or '.'"
+            )));
+        }
+        let encoded = value.as_str().unwrap_or_default();
+        let decoded = STANDARD.decode(encoded).map_err(|e| {
+            invalid(format!(
+                "data[{key}]: Invalid value: illegal base64 data: {e}"
+            ))
+        })?;
+        total += decoded.len();
+    }
+
+    if total > MAX_SECRET_SIZE {
+        return Err(invalid(format!(
+            "data: Too long: must have at most {MAX_SECRET_SIZE} bytes"
+        )));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn is_config_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 253
+        && key != "."
+        && key != ".."
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
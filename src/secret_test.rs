@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::secret_from;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::Secret;
+    use k8s_openapi::ByteString;
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    fn assert_invalid(err: kube::Error, needle: &str) {
+        match err {
+            kube::Error::Api(resp) => {
+                assert_eq!(resp.code, 422);
+                assert!(resp.message.contains(needle), "{}", resp.message);
+            }
+            e => panic!("Expected API error, got: {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_string_data_merged_into_data() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+
+        let created = secrets
+            .create(
+                &PostParams::default(),
+                &secret_from(
+                    "creds",
+                    json!({
+                        "data": { "user": "YWRtaW4=", "password": "b2xk" },
+                        "stringData": { "password": "s3cret" }
+                    }),
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert!(created.string_data.is_none());
+        let data = created.data.unwrap();
+        assert_eq!(data["user"], ByteString(b"admin".to_vec()));
+        assert_eq!(data["password"], ByteString(b"s3cret".to_vec()));
+
+        // stringData in a patch is merged the same way
+        let patch = json!({ "stringData": { "token": "abc" } });
+        let patched = secrets
+            .patch("creds", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        let data = patched.data.unwrap();
+        assert_eq!(data["token"], ByteString(b"abc".to_vec()));
+        assert_eq!(data.len(), 3);
+
+        let fetched = secrets.get("creds").await.unwrap();
+        assert!(fetched.string_data.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_base64_rejected() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+
+        secrets
+            .create(&PostParams::default(), &secret_from("bad", json!({})))
+            .await
+            .unwrap();
+
+        // Typed objects always encode correctly, so send raw data in a patch
+        let patch = json!({ "data": { "key": "not base64!" } });
+        let err = secrets
+            .patch("bad", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap_err();
+        assert_invalid(err, "data[key]");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_key_rejected() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+
+        let err = secrets
+            .create(
+                &PostParams::default(),
+                &secret_from("bad", json!({ "stringData": { "bad/key": "value" } })),
+            )
+            .await
+            .unwrap_err();
+        assert_invalid(err, "a valid config key");
+    }
+
+    #[tokio::test]
+    async fn test_size_limit_enforced() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+
+        let at_limit = "a".repeat(1024 * 1024);
+        secrets
+            .create(
+                &PostParams::default(),
+                &secret_from("max", json!({ "stringData": { "blob": at_limit } })),
+            )
+            .await
+            .unwrap();
+
+        let too_big = "a".repeat(1024 * 1024 + 1);
+        let err = secrets
+            .create(
+                &PostParams::default(),
+                &secret_from("huge", json!({ "stringData": { "blob": too_big } })),
+            )
+            .await
+            .unwrap_err();
+        assert_invalid(err, "Too long");
+    }
+}
@@ -0,0 +1,616 @@
+//! Service cluster IP and node port allocation
+//!
+//! Mirrors the API server's Service registry: a Service created without
+//! `spec.clusterIP` gets one from the service CIDR, and NodePort/LoadBalancer
+//! Services get node ports for each port that does not request one. Like the
+//! API server's allocators, the values handed out are kept in a set of their
+//! own rather than found by listing Services, so concurrent creates never get
+//! the same cluster IP or node port.
+
+use crate::tracker::{ObjectTracker, RemovalObserver, GVR};
+use crate::{Error, Result};
+use parking_lot::{Mutex, MutexGuard};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::net::Ipv4Addr;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Default service CIDR used by kubeadm clusters
+pub(crate) const DEFAULT_SERVICE_CIDR: &str = "10.96.0.0/12";
+
+/// Default node port range of the API server
+const NODE_PORT_RANGE: RangeInclusive<u64> = 30000..=32767;
+
+/// An IPv4 CIDR that service cluster IPs are allocated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ServiceCidr {
+    network: u32,
+    prefix: u8,
+}
+
+impl ServiceCidr {
+    /// Parse a CIDR in `a.b.c.d/n` notation
+    pub(crate) fn parse(cidr: &str) -> Result<Self> {
+        let invalid = || Error::InvalidRequest(format!("invalid service CIDR: {cidr}"));
+        let (addr, prefix) = cidr.split_once('/').ok_or_else(invalid)?;
+        let addr: Ipv4Addr = addr.parse().map_err(|_| invalid())?;
+        let prefix: u8 = prefix.parse().map_err(|_| invalid())?;
+        // At least one usable address is required besides network and broadcast
+        if prefix > 30 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            network: u32::from(addr) & Self::mask(prefix),
+            prefix,
+        })
+    }
+
+    fn mask(prefix: u8) -> u32 {
+        u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0)
+    }
+
+    fn contains(&self, ip: Ipv4Addr) -> bool {
+        u32::from(ip) & Self::mask(self.prefix) == self.network
+    }
+
+    /// Number of usable addresses, excluding the network and broadcast addresses
+    fn size(&self) -> u32 {
+        !Self::mask(self.prefix) - 1
+    }
+
+    /// The usable address at an offset from the start of the range
+    fn host(&self, offset: u32) -> String {
+        Ipv4Addr::from(self.network + 1 + offset).to_string()
+    }
+}
+
+impl Default for ServiceCidr {
+    fn default() -> Self {
+        Self::parse(DEFAULT_SERVICE_CIDR).expect("default service CIDR is valid")
+    }
+}
+
+impl std::fmt::Display for ServiceCidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", Ipv4Addr::from(self.network), self.prefix)
+    }
+}
+
+/// Cluster IPs and node ports reserved by Services
+///
+/// Shared by the clones of a client, so concurrent writes never pick the same
+/// value: a value is picked and reserved under one lock. Values are released when
+/// the write that reserved them fails or the Service holding them is deleted, and
+/// the set is read again from the stored Services after the tracker is restored.
+#[derive(Default)]
+pub(crate) struct Allocations {
+    reserved: Mutex<Reserved>,
+    next_reservation: AtomicU64,
+}
+
+#[derive(Default)]
+struct Reserved {
+    ips: HashMap<String, Holder>,
+    node_ports: HashMap<u64, Holder>,
+    /// Where the search for a free cluster IP, and node port, starts next
+    next_ip: u32,
+    next_node_port: u64,
+    /// Restore count of the tracker when the set was last read from it
+    synced: Option<u64>,
+}
+
+/// The Service a value is reserved for, and the write that reserved it
+#[derive(Debug, Clone)]
+struct Holder {
+    namespace: String,
+    name: String,
+    reservation: u64,
+}
+
+impl Holder {
+    fn is_same_service(&self, other: &Holder) -> bool {
+        self.namespace == other.namespace && self.name == other.name
+    }
+}
+
+/// Values reserved by one write, released unless the write is committed
+#[must_use]
+pub(crate) struct Reservation {
+    allocations: Option<Arc<Allocations>>,
+    holder: Option<Holder>,
+    ips: Vec<String>,
+    node_ports: Vec<u64>,
+    /// Values of the stored object the written one no longer holds
+    replaced: (Vec<String>, Vec<u64>),
+}
+
+impl Reservation {
+    /// A reservation of nothing, for writes to other kinds
+    pub(crate) fn none() -> Self {
+        Self {
+            allocations: None,
+            holder: None,
+            ips: Vec::new(),
+            node_ports: Vec::new(),
+            replaced: (Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Keep the values for the stored object, releasing those it replaced
+    pub(crate) fn commit(mut self) {
+        let (Some(allocations), Some(holder)) = (self.allocations.take(), self.holder.take())
+        else {
+            return;
+        };
+        let (ips, node_ports) = std::mem::take(&mut self.replaced);
+        allocations.release_held(&holder, &ips, &node_ports);
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let (Some(allocations), Some(holder)) = (self.allocations.take(), self.holder.take())
+        else {
+            return;
+        };
+        let mut reserved = allocations.reserved.lock();
+        for ip in &self.ips {
+            if reserved
+                .ips
+                .get(ip)
+                .is_some_and(|h| h.reservation == holder.reservation)
+            {
+                reserved.ips.remove(ip);
+            }
+        }
+        for node_port in &self.node_ports {
+            if reserved
+                .node_ports
+                .get(node_port)
+                .is_some_and(|h| h.reservation == holder.reservation)
+            {
+                reserved.node_ports.remove(node_port);
+            }
+        }
+    }
+}
+
+impl Allocations {
+    /// Tracker observer releasing the values of deleted Services
+    pub(crate) fn removal_observer(self: &Arc<Self>) -> RemovalObserver {
+        let allocations = Arc::downgrade(self);
+        Arc::new(move |gvr, namespace, service| {
+            let Some(allocations) = allocations.upgrade() else {
+                return;
+            };
+            if *gvr != services_gvr() {
+                return;
+            }
+            let holder = Holder {
+                namespace: namespace.to_string(),
+                name: object_name(service),
+                reservation: 0,
+            };
+            let (ips, node_ports) = held(service.get("spec"));
+            allocations.release_held(&holder, &ips, &node_ports);
+        })
+    }
+
+    /// Release values if the Service still holds them
+    fn release_held(&self, holder: &Holder, ips: &[String], node_ports: &[u64]) {
+        let mut reserved = self.reserved.lock();
+        for ip in ips {
+            if reserved
+                .ips
+                .get(ip)
+                .is_some_and(|h| h.is_same_service(holder))
+            {
+                reserved.ips.remove(ip);
+            }
+        }
+        for node_port in node_ports {
+            if reserved
+                .node_ports
+                .get(node_port)
+                .is_some_and(|h| h.is_same_service(holder))
+            {
+                reserved.node_ports.remove(node_port);
+            }
+        }
+    }
+
+    /// Lock the set, reading it from the stored Services first if the tracker
+    /// was restored since it was last read
+    fn lock(&self, tracker: &ObjectTracker) -> MutexGuard<'_, Reserved> {
+        let mut reserved = self.reserved.lock();
+        let restores = tracker.restores();
+        if reserved.synced != Some(restores) {
+            reserved.ips.clear();
+            reserved.node_ports.clear();
+            for service in tracker.list_shared(&services_gvr(), None) {
+                let holder = Holder {
+                    namespace: service["metadata"]["namespace"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    name: object_name(&service),
+                    reservation: 0,
+                };
+                let (ips, node_ports) = held(service.get("spec"));
+                for ip in ips {
+                    reserved.ips.insert(ip, holder.clone());
+                }
+                for node_port in node_ports {
+                    reserved.node_ports.insert(node_port, holder.clone());
+                }
+            }
+            reserved.synced = Some(restores);
+        }
+        reserved
+    }
+
+    fn reservation(self: &Arc<Self>, namespace: &str, name: &str) -> Reservation {
+        Reservation {
+            allocations: Some(Arc::clone(self)),
+            holder: Some(Holder {
+                namespace: namespace.to_string(),
+                name: name.to_string(),
+                reservation: self.next_reservation.fetch_add(1, Ordering::Relaxed) + 1,
+            }),
+            ips: Vec::new(),
+            node_ports: Vec::new(),
+            replaced: (Vec::new(), Vec::new()),
+        }
+    }
+}
+
+/// Values a write reserves, while it holds the lock of the set
+struct Reserving<'a> {
+    reserved: MutexGuard<'a, Reserved>,
+    reservation: Reservation,
+}
+
+impl Reserving<'_> {
+    fn holder(&self) -> &Holder {
+        self.reservation
+            .holder
+            .as_ref()
+            .expect("reservations of Services have a holder")
+    }
+
+    /// Whether another Service holds an IP
+    fn ip_taken(&self, ip: &str) -> bool {
+        self.reserved
+            .ips
+            .get(ip)
+            .is_some_and(|h| !h.is_same_service(self.holder()))
+    }
+
+    fn node_port_taken(&self, node_port: u64) -> bool {
+        self.reserved
+            .node_ports
+            .get(&node_port)
+            .is_some_and(|h| !h.is_same_service(self.holder()))
+    }
+
+    fn reserve_ip(&mut self, ip: &str) {
+        if self.reserved.ips.contains_key(ip) {
+            return;
+        }
+        let holder = self.holder().clone();
+        self.reserved.ips.insert(ip.to_string(), holder);
+        self.reservation.ips.push(ip.to_string());
+    }
+
+    fn reserve_node_port(&mut self, node_port: u64) {
+        if self.reserved.node_ports.contains_key(&node_port) {
+            return;
+        }
+        let holder = self.holder().clone();
+        self.reserved.node_ports.insert(node_port, holder);
+        self.reservation.node_ports.push(node_port);
+    }
+
+    /// The next free IP of the range, searching on from the last one handed out
+    fn free_ip(&mut self, cidr: &ServiceCidr) -> Option<String> {
+        let size = cidr.size();
+        let start = self.reserved.next_ip;
+        let offset = (0..size)
+            .map(|offset| (start + offset) % size)
+            .find(|offset| !self.reserved.ips.contains_key(&cidr.host(*offset)))?;
+        self.reserved.next_ip = (offset + 1) % size;
+        Some(cidr.host(offset))
+    }
+
+    /// The next free node port, searching on from the last one handed out
+    fn free_node_port(&mut self) -> Option<u64> {
+        let size = NODE_PORT_RANGE.end() - NODE_PORT_RANGE.start() + 1;
+        let start = self.reserved.next_node_port;
+        let offset = (0..size)
+            .map(|offset| (start + offset) % size)
+            .find(|offset| {
+                !self
+                    .reserved
+                    .node_ports
+                    .contains_key(&(NODE_PORT_RANGE.start() + offset))
+            })?;
+        self.reserved.next_node_port = (offset + 1) % size;
+        Some(NODE_PORT_RANGE.start() + offset)
+    }
+}
+
+/// Assign a cluster IP and node ports to a Service being created
+///
+/// The values stay reserved for the Service if the returned reservation is
+/// committed once it is stored, and are released if it is dropped.
+pub(crate) fn prepare_for_create(
+    cidr: &ServiceCidr,
+    allocations: &Arc<Allocations>,
+    tracker: &ObjectTracker,
+    namespace: &str,
+    service: &mut Value,
+) -> Result<Reservation> {
+    let name = object_name(service);
+    let spec = spec_mut(service)?;
+    let service_type = service_type(spec).to_string();
+    spec.insert("type".to_string(), json!(service_type));
+    if service_type == "ExternalName" {
+        return Ok(Reservation::none());
+    }
+
+    let mut reserving = Reserving {
+        reserved: allocations.lock(tracker),
+        reservation: allocations.reservation(namespace, &name),
+    };
+    assign_cluster_ip(cidr, &mut reserving, spec, &name)?;
+    assign_node_ports(&mut reserving, spec, None, &name)?;
+    Ok(reserving.reservation)
+}
+
+/// Carry over allocations to a Service being updated
+///
+/// Cluster IPs and node ports omitted from the new object are copied from the
+/// stored one, so a full replace does not need to echo back allocated values.
+/// Ports added to a NodePort or LoadBalancer Service are allocated as on create.
+/// Committing the reservation releases the values the update drops.
+pub(crate) fn prepare_for_update(
+    cidr: &ServiceCidr,
+    allocations: &Arc<Allocations>,
+    tracker: &ObjectTracker,
+    namespace: &str,
+    old: &Value,
+    new: &mut Value,
+) -> Result<Reservation> {
+    let name = object_name(new);
+    let old_spec = old.get("spec");
+    let mut reserving = Reserving {
+        reserved: allocations.lock(tracker),
+        reservation: allocations.reservation(namespace, &name),
+    };
+
+    let spec = spec_mut(new)?;
+    let service_type = service_type(spec).to_string();
+    spec.insert("type".to_string(), json!(service_type));
+    if service_type != "ExternalName" {
+        let old_ip = old_spec
+            .and_then(|s| s.get("clusterIP"))
+            .and_then(|v| v.as_str());
+        let new_ip = spec.get("clusterIP").and_then(|v| v.as_str());
+        if let (Some(old_ip), None | Some("")) = (old_ip, new_ip) {
+            if !old_ip.is_empty() {
+                spec.insert("clusterIP".to_string(), json!(old_ip));
+                if let Some(old_ips) = old_spec.and_then(|s| s.get("clusterIPs")) {
+                    spec.insert("clusterIPs".to_string(), old_ips.clone());
+                }
+            }
+        }
+
+        let kept_old_ip = old_ip.is_some_and(|ip| !ip.is_empty())
+            && spec.get("clusterIP").and_then(|v| v.as_str()) == old_ip;
+        if !kept_old_ip {
+            assign_cluster_ip(cidr, &mut reserving, spec, &name)?;
+        }
+        assign_node_ports(&mut reserving, spec, old_spec, &name)?;
+    }
+
+    let (old_ips, old_node_ports) = held(old_spec);
+    let (new_ips, new_node_ports) = held(new.get("spec"));
+    let mut reservation = reserving.reservation;
+    reservation.replaced = (
+        old_ips
+            .into_iter()
+            .filter(|ip| !new_ips.contains(ip))
+            .collect(),
+        old_node_ports
+            .into_iter()
+            .filter(|port| !new_node_ports.contains(port))
+            .collect(),
+    );
+    Ok(reservation)
+}
+
+fn assign_cluster_ip(
+    cidr: &ServiceCidr,
+    reserving: &mut Reserving<'_>,
+    spec: &mut Map<String, Value>,
+    name: &str,
+) -> Result<()> {
+    let requested = spec
+        .get("clusterIP")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let ip = match requested.as_str() {
+        // Headless Service
+        "None" => requested,
+        "" => reserving.free_ip(cidr).ok_or_else(|| {
+            Error::Internal("failed to allocate a serviceIP: range is full".to_string())
+        })?,
+        _ => {
+            let invalid = |reason: String| {
+                Error::InvalidRequest(format!(
+                    "Service \"{name}\" is invalid: spec.clusterIPs: Invalid value: [\"{requested}\"]: \
+                     failed to allocate IP {requested}: {reason}"
+                ))
+            };
+            let ip: Ipv4Addr = requested
+                .parse()
+                .map_err(|_| invalid("must be a valid IP address".to_string()))?;
+            if !cidr.contains(ip) {
+                return Err(invalid(format!(
+                    "the provided IP ({ip}) is not in the valid range. The range of valid IPs is {cidr}"
+                )));
+            }
+            if reserving.ip_taken(&requested) {
+                return Err(invalid("provided IP is already allocated".to_string()));
+            }
+            requested
+        }
+    };
+
+    if ip != "None" {
+        reserving.reserve_ip(&ip);
+    }
+    spec.insert("clusterIP".to_string(), json!(ip));
+    spec.insert("clusterIPs".to_string(), json!([ip]));
+    Ok(())
+}
+
+fn assign_node_ports(
+    reserving: &mut Reserving<'_>,
+    spec: &mut Map<String, Value>,
+    old_spec: Option<&Value>,
+    name: &str,
+) -> Result<()> {
+    if !matches!(service_type(spec), "NodePort" | "LoadBalancer") {
+        return Ok(());
+    }
+    let old_ports: Vec<&Value> = old_spec.map(|s| ports(s).collect()).unwrap_or_default();
+
+    let Some(ports) = spec.get_mut("ports").and_then(|v| v.as_array_mut()) else {
+        return Ok(());
+    };
+
+    // Explicitly requested ports are reserved first so allocation skips them
+    let mut requested = HashSet::new();
+    for (index, port) in ports.iter().enumerate() {
+        let Some(node_port) = port.get("nodePort").and_then(|v| v.as_u64()) else {
+            continue;
+        };
+        let invalid = |reason: &str| {
+            Error::InvalidRequest(format!(
+                "Service \"{name}\" is invalid: spec.ports[{index}].nodePort: \
+                 Invalid value: {node_port}: {reason}"
+            ))
+        };
+        if !NODE_PORT_RANGE.contains(&node_port) {
+            return Err(invalid(&format!(
+                "provided port is not in the valid range. The range of valid ports is {}-{}",
+                NODE_PORT_RANGE.start(),
+                NODE_PORT_RANGE.end()
+            )));
+        }
+        if reserving.node_port_taken(node_port) || !requested.insert(node_port) {
+            return Err(invalid("provided port is already allocated"));
+        }
+        reserving.reserve_node_port(node_port);
+    }
+
+    for port in ports.iter_mut() {
+        if port.get("nodePort").and_then(|v| v.as_u64()).is_some() {
+            continue;
+        }
+        // Keep the node port previously assigned to the same port
+        let previous = old_ports
+            .iter()
+            .find(|old| same_port(old, port))
+            .and_then(|old| old.get("nodePort"))
+            .and_then(|v| v.as_u64())
+            .filter(|p| !reserving.node_port_taken(*p) && !requested.contains(p));
+        let node_port = match previous {
+            Some(p) => p,
+            None => reserving.free_node_port().ok_or_else(|| {
+                Error::Internal("failed to allocate a nodePort: range is full".to_string())
+            })?,
+        };
+        requested.insert(node_port);
+        reserving.reserve_node_port(node_port);
+        if let Some(port) = port.as_object_mut() {
+            port.insert("nodePort".to_string(), json!(node_port));
+        }
+    }
+
+    Ok(())
+}
+
+/// Cluster IPs and node ports a Service spec holds
+fn held(spec: Option<&Value>) -> (Vec<String>, Vec<u64>) {
+    let Some(spec) = spec else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut ips: Vec<String> = spec
+        .get("clusterIPs")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .chain(spec.get("clusterIP"))
+        .filter_map(|ip| ip.as_str())
+        .filter(|ip| !ip.is_empty() && *ip != "None")
+        .map(String::from)
+        .collect();
+    ips.sort();
+    ips.dedup();
+    let node_ports = ports(spec)
+        .filter_map(|port| port.get("nodePort").and_then(|v| v.as_u64()))
+        .collect();
+    (ips, node_ports)
+}
+
+fn services_gvr() -> GVR {
+    GVR::new("", "v1", "services")
+}
+
+/// Ports match when both port number and protocol agree
+fn same_port(a: &Value, b: &Value) -> bool {
+    let protocol = |p: &Value| {
+        p.get("protocol")
+            .and_then(|v| v.as_str())
+            .unwrap_or("TCP")
+            .to_string()
+    };
+    a.get("port") == b.get("port") && protocol(a) == protocol(b)
+}
+
+fn ports(spec: &Value) -> impl Iterator<Item = &Value> {
+    spec.get("ports")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+}
+
+fn service_type(spec: &Map<String, Value>) -> &str {
+    spec.get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or("ClusterIP")
+}
+
+fn spec_mut(service: &mut Value) -> Result<&mut Map<String, Value>> {
+    let obj = service
+        .as_object_mut()
+        .ok_or_else(|| Error::InvalidRequest("Service must be an object".to_string()))?;
+    obj.entry("spec")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or_else(|| Error::InvalidRequest("Service spec must be an object".to_string()))
+}
+
+fn object_name(obj: &Value) -> String {
+    obj.get("metadata")
+        .and_then(|m| m.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
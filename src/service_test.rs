@@ -0,0 +1,343 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::Service;
+    use kube::api::{Api, DeleteParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    fn service(name: &str, spec: serde_json::Value) -> Service {
+        serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "Service",
+            "metadata": { "name": name },
+            "spec": spec
+        }))
+        .unwrap()
+    }
+
+    fn spec(svc: &Service) -> &k8s_openapi::api::core::v1::ServiceSpec {
+        svc.spec.as_ref().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cluster_ip_allocated_on_create() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let services: Api<Service> = Api::namespaced(client, "default");
+
+        let a = services
+            .create(&PostParams::default(), &service("a", json!({})))
+            .await
+            .unwrap();
+        let b = services
+            .create(&PostParams::default(), &service("b", json!({})))
+            .await
+            .unwrap();
+
+        assert_eq!(spec(&a).type_.as_deref(), Some("ClusterIP"));
+        assert_eq!(spec(&a).cluster_ip.as_deref(), Some("10.96.0.1"));
+        assert_eq!(spec(&a).cluster_ips, Some(vec!["10.96.0.1".to_string()]));
+        assert_eq!(spec(&b).cluster_ip.as_deref(), Some("10.96.0.2"));
+
+        // Reading back returns the allocated IP
+        let fetched = services.get("a").await.unwrap();
+        assert_eq!(spec(&fetched).cluster_ip.as_deref(), Some("10.96.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_service_cidr() {
+        let client = ClientBuilder::new()
+            .with_service_cidr("172.20.0.0/16")
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let services: Api<Service> = Api::namespaced(client, "default");
+
+        let svc = services
+            .create(&PostParams::default(), &service("web", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(spec(&svc).cluster_ip.as_deref(), Some("172.20.0.1"));
+
+        // Requested IPs must be inside the CIDR
+        let err = services
+            .create(
+                &PostParams::default(),
+                &service("outside", json!({ "clusterIP": "10.96.0.10" })),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+
+        assert!(ClientBuilder::new()
+            .with_service_cidr("not-a-cidr")
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requested_cluster_ip_conflicts() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let services: Api<Service> = Api::namespaced(client, "default");
+
+        let svc = services
+            .create(
+                &PostParams::default(),
+                &service("dns", json!({ "clusterIP": "10.96.0.10" })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(spec(&svc).cluster_ip.as_deref(), Some("10.96.0.10"));
+
+        let err = services
+            .create(
+                &PostParams::default(),
+                &service("dns-copy", json!({ "clusterIP": "10.96.0.10" })),
+            )
+            .await
+            .unwrap_err();
+        match err {
+            kube::Error::Api(resp) => {
+                assert_eq!(resp.code, 422);
+                assert!(resp.message.contains("provided IP is already allocated"));
+            }
+            e => panic!("Expected API error, got: {:?}", e),
+        }
+
+        // The IP is released once the Service is deleted
+        services
+            .delete("dns", &DeleteParams::default())
+            .await
+            .unwrap();
+        services
+            .create(
+                &PostParams::default(),
+                &service("dns-copy", json!({ "clusterIP": "10.96.0.10" })),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_headless_and_external_name_services() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let services: Api<Service> = Api::namespaced(client, "default");
+
+        let headless = services
+            .create(
+                &PostParams::default(),
+                &service("headless", json!({ "clusterIP": "None" })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(spec(&headless).cluster_ip.as_deref(), Some("None"));
+
+        let external = services
+            .create(
+                &PostParams::default(),
+                &service(
+                    "external",
+                    json!({ "type": "ExternalName", "externalName": "example.com" }),
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(spec(&external).cluster_ip, None);
+    }
+
+    #[tokio::test]
+    async fn test_node_ports_assigned() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let services: Api<Service> = Api::namespaced(client, "default");
+
+        let svc = services
+            .create(
+                &PostParams::default(),
+                &service(
+                    "web",
+                    json!({
+                        "type": "NodePort",
+                        "ports": [
+                            { "name": "http", "port": 80, "nodePort": 30000 },
+                            { "name": "https", "port": 443 }
+                        ]
+                    }),
+                ),
+            )
+            .await
+            .unwrap();
+        let ports = spec(&svc).ports.as_ref().unwrap();
+        assert_eq!(ports[0].node_port, Some(30000));
+        assert_eq!(ports[1].node_port, Some(30001));
+
+        let lb = services
+            .create(
+                &PostParams::default(),
+                &service(
+                    "lb",
+                    json!({ "type": "LoadBalancer", "ports": [{ "port": 80 }] }),
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(spec(&lb).ports.as_ref().unwrap()[0].node_port, Some(30002));
+
+        // ClusterIP Services don't get node ports
+        let internal = services
+            .create(
+                &PostParams::default(),
+                &service("internal", json!({ "ports": [{ "port": 80 }] })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(spec(&internal).ports.as_ref().unwrap()[0].node_port, None);
+
+        // Requested node ports must be free and in range
+        for node_port in [30000, 8080] {
+            let err = services
+                .create(
+                    &PostParams::default(),
+                    &service(
+                        "bad",
+                        json!({
+                            "type": "NodePort",
+                            "ports": [{ "port": 80, "nodePort": node_port }]
+                        }),
+                    ),
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_preserves_allocations() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let services: Api<Service> = Api::namespaced(client, "default");
+
+        let created = services
+            .create(
+                &PostParams::default(),
+                &service(
+                    "web",
+                    json!({ "type": "NodePort", "ports": [{ "port": 80 }] }),
+                ),
+            )
+            .await
+            .unwrap();
+
+        // A replace built from scratch keeps the allocated IP and node port
+        let mut replacement = service(
+            "web",
+            json!({
+                "type": "NodePort",
+                "ports": [{ "port": 80 }, { "port": 8080 }]
+            }),
+        );
+        replacement.metadata.resource_version = created.metadata.resource_version.clone();
+        let replaced = services
+            .replace("web", &PostParams::default(), &replacement)
+            .await
+            .unwrap();
+        assert_eq!(spec(&replaced).cluster_ip, spec(&created).cluster_ip);
+        let ports = spec(&replaced).ports.as_ref().unwrap();
+        assert_eq!(ports[0].node_port, Some(30000));
+        assert_eq!(ports[1].node_port, Some(30001));
+
+        // Changing the cluster IP is rejected
+        let patch = json!({ "spec": { "clusterIP": "10.96.0.99" } });
+        let err = services
+            .patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap_err();
+        match err {
+            kube::Error::Api(resp) => {
+                assert_eq!(resp.code, 422);
+                assert!(resp.message.contains("spec.clusterIP"), "{}", resp.message);
+            }
+            e => panic!("Expected API error, got: {:?}", e),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_creates_get_unique_allocations() {
+        let client = ClientBuilder::new().build().await.unwrap();
+
+        let creates = (0..50).map(|i| {
+            let services: Api<Service> = Api::namespaced(client.clone(), &format!("ns-{}", i % 5));
+            tokio::spawn(async move {
+                services
+                    .create(
+                        &PostParams::default(),
+                        &service(
+                            &format!("svc-{i}"),
+                            json!({ "type": "NodePort", "ports": [{ "port": 80 }, { "port": 443 }] }),
+                        ),
+                    )
+                    .await
+                    .unwrap()
+            })
+        });
+        let created = futures::future::join_all(creates).await;
+
+        let mut ips = std::collections::HashSet::new();
+        let mut node_ports = std::collections::HashSet::new();
+        for svc in created {
+            let svc = svc.unwrap();
+            assert!(ips.insert(spec(&svc).cluster_ip.clone().unwrap()));
+            for port in spec(&svc).ports.as_ref().unwrap() {
+                assert!(node_ports.insert(port.node_port.unwrap()));
+            }
+        }
+        assert_eq!((ips.len(), node_ports.len()), (50, 100));
+    }
+
+    #[tokio::test]
+    async fn test_failed_writes_release_allocations() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let services: Api<Service> = Api::namespaced(client, "default");
+
+        // The cluster IP is reserved before the node port is found invalid
+        let err = services
+            .create(
+                &PostParams::default(),
+                &service(
+                    "web",
+                    json!({
+                        "type": "NodePort",
+                        "clusterIP": "10.96.0.10",
+                        "ports": [{ "port": 80, "nodePort": 80 }]
+                    }),
+                ),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+        let created = services
+            .create(
+                &PostParams::default(),
+                &service("dns", json!({ "clusterIP": "10.96.0.10" })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(spec(&created).cluster_ip.as_deref(), Some("10.96.0.10"));
+
+        // Creating a Service that exists keeps the allocations of the stored one
+        let err = services
+            .create(
+                &PostParams::default(),
+                &service("dns", json!({ "clusterIP": "10.96.0.10" })),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 409));
+        let err = services
+            .create(
+                &PostParams::default(),
+                &service("dns-copy", json!({ "clusterIP": "10.96.0.10" })),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, kube::Error::Api(resp) if resp.code == 422));
+    }
+}
@@ -0,0 +1,181 @@
+//! Tracker storage shards and their secondary indexes
+//!
+//! A shard holds the objects of one resource in one namespace. Alongside the
+//! objects it keeps inverted indexes, from an indexed value (a label value or a
+//! field value) to the names of the objects carrying it, updated on every write so
+//! selector lookups don't have to scan the shard.
+
+use crate::client::IndexerFunc;
+use crate::store::StoredObject;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Insertion counter shared by all shards, so insertion order can be compared
+/// across namespaces
+static INSERTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// What a secondary index is keyed on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IndexKey {
+    /// The value of a label
+    Label(String),
+    /// The values a field selector field extracts
+    Field(String),
+}
+
+impl IndexKey {
+    /// Indexer extracting the value of a label
+    pub(crate) fn label_indexer(key: &str) -> IndexerFunc {
+        let key = key.to_string();
+        Arc::new(move |object| {
+            object
+                .pointer("/metadata/labels")
+                .and_then(|labels| labels.get(&key))
+                .and_then(|value| value.as_str())
+                .map(|value| vec![value.to_string()])
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// An index lookup: objects whose indexed values include any of `values`
+pub(crate) type IndexLookup = (IndexKey, BTreeSet<String>);
+
+struct Index {
+    indexer: IndexerFunc,
+    entries: HashMap<String, BTreeSet<String>>,
+}
+
+impl Index {
+    fn add(&mut self, name: &str, object: &Value) {
+        for value in (self.indexer)(object) {
+            self.entries
+                .entry(value)
+                .or_default()
+                .insert(name.to_string());
+        }
+    }
+
+    fn remove(&mut self, name: &str, object: &Value) {
+        for value in (self.indexer)(object) {
+            if let Some(names) = self.entries.get_mut(&value) {
+                names.remove(name);
+                if names.is_empty() {
+                    self.entries.remove(&value);
+                }
+            }
+        }
+    }
+}
+
+/// Objects of one resource in one namespace, keyed by name
+#[derive(Default)]
+pub(crate) struct Shard {
+    objects: HashMap<String, StoredObject>,
+    indexes: HashMap<IndexKey, Index>,
+    /// When each object was first stored, kept across updates
+    inserted: HashMap<String, u64>,
+}
+
+impl Shard {
+    pub(crate) fn with_indexes(indexers: &HashMap<IndexKey, IndexerFunc>) -> Self {
+        let mut shard = Self::default();
+        for (key, indexer) in indexers {
+            shard.add_index(key.clone(), Arc::clone(indexer));
+        }
+        shard
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&StoredObject> {
+        self.objects.get(name)
+    }
+
+    pub(crate) fn objects(&self) -> impl Iterator<Item = (&String, &StoredObject)> {
+        self.objects.iter()
+    }
+
+    /// Position of an object in insertion order, across all shards
+    pub(crate) fn insertion(&self, name: &str) -> u64 {
+        self.inserted.get(name).copied().unwrap_or(u64::MAX)
+    }
+
+    /// Store an object, replacing any object of the same name
+    pub(crate) fn insert(&mut self, name: &str, stored: StoredObject) {
+        let previous = self.objects.insert(name.to_string(), stored);
+        self.inserted
+            .entry(name.to_string())
+            .or_insert_with(|| INSERTIONS.fetch_add(1, Ordering::Relaxed));
+        let current = &self.objects[name].data;
+        for index in self.indexes.values_mut() {
+            if let Some(previous) = &previous {
+                index.remove(name, &previous.data);
+            }
+            index.add(name, current);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) -> Option<StoredObject> {
+        let removed = self.objects.remove(name)?;
+        self.inserted.remove(name);
+        for index in self.indexes.values_mut() {
+            index.remove(name, &removed.data);
+        }
+        Some(removed)
+    }
+
+    /// Index the shard's objects, unless an index with this key already exists
+    pub(crate) fn add_index(&mut self, key: IndexKey, indexer: IndexerFunc) {
+        if self.indexes.contains_key(&key) {
+            return;
+        }
+        let mut index = Index {
+            indexer,
+            entries: HashMap::new(),
+        };
+        for (name, stored) in &self.objects {
+            index.add(name, &stored.data);
+        }
+        self.indexes.insert(key, index);
+    }
+
+    /// Objects matching every lookup with an index in this shard, ordered by name
+    ///
+    /// Lookups without an index are ignored, so the result is a superset of the
+    /// matching objects and callers still need to apply the full selectors.
+    pub(crate) fn select(&self, lookups: &[IndexLookup]) -> Vec<Arc<Value>> {
+        let mut candidates: Option<BTreeSet<&str>> = None;
+        for (key, values) in lookups {
+            let Some(index) = self.indexes.get(key) else {
+                continue;
+            };
+            let names: BTreeSet<&str> = values
+                .iter()
+                .filter_map(|value| index.entries.get(value))
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            candidates = Some(match candidates {
+                Some(previous) => previous.intersection(&names).copied().collect(),
+                None => names,
+            });
+        }
+
+        match candidates {
+            Some(names) => names
+                .into_iter()
+                .filter_map(|name| self.objects.get(name))
+                .map(|stored| Arc::clone(&stored.data))
+                .collect(),
+            None => {
+                let mut objects: Vec<(&String, &StoredObject)> = self.objects.iter().collect();
+                objects.sort_unstable_by_key(|(name, _)| *name);
+                objects
+                    .into_iter()
+                    .map(|(_, stored)| Arc::clone(&stored.data))
+                    .collect()
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod tests {
+    use crate::shard::{IndexKey, Shard};
+    use crate::store::StoredObject;
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::Pod;
+    use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::{json, Value};
+    use std::collections::{BTreeSet, HashMap};
+    use std::sync::Arc;
+
+    fn stored(name: &str, app: &str) -> StoredObject {
+        let data = json!({ "metadata": { "name": name, "labels": { "app": app } } });
+        StoredObject {
+            metadata: serde_json::from_value(data["metadata"].clone()).unwrap(),
+            data: Arc::new(data),
+        }
+    }
+
+    fn names(objects: Vec<Arc<Value>>) -> Vec<String> {
+        let mut names: Vec<String> = objects
+            .iter()
+            .map(|o| o["metadata"]["name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn app(values: &[&str]) -> Vec<(IndexKey, BTreeSet<String>)> {
+        vec![(
+            IndexKey::Label("app".to_string()),
+            values.iter().map(|v| v.to_string()).collect(),
+        )]
+    }
+
+    fn pod(name: &str, labels: &[(&str, &str)]) -> Pod {
+        let mut pod = Pod::default();
+        pod.metadata.name = Some(name.to_string());
+        pod.metadata.namespace = Some("default".to_string());
+        pod.metadata.labels = Some(
+            labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        pod
+    }
+
+    fn pod_names(pods: kube::core::ObjectList<Pod>) -> Vec<String> {
+        let mut names: Vec<String> = pods
+            .items
+            .into_iter()
+            .filter_map(|p| p.metadata.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_index_follows_writes() {
+        let mut shard = Shard::with_indexes(&HashMap::from([(
+            IndexKey::Label("app".to_string()),
+            IndexKey::label_indexer("app"),
+        )]));
+        shard.insert("a", stored("a", "web"));
+        shard.insert("b", stored("b", "web"));
+        shard.insert("c", stored("c", "db"));
+        assert_eq!(names(shard.select(&app(&["web"]))), ["a", "b"]);
+        assert_eq!(names(shard.select(&app(&["web", "db"]))), ["a", "b", "c"]);
+
+        // Relabeling moves the object between index entries
+        shard.insert("b", stored("b", "db"));
+        assert_eq!(names(shard.select(&app(&["web"]))), ["a"]);
+        assert_eq!(names(shard.select(&app(&["db"]))), ["b", "c"]);
+
+        shard.remove("c");
+        assert_eq!(names(shard.select(&app(&["db"]))), ["b"]);
+        assert!(shard.select(&app(&["cache"])).is_empty());
+    }
+
+    #[test]
+    fn test_index_added_after_objects() {
+        let mut shard = Shard::default();
+        shard.insert("a", stored("a", "web"));
+        shard.insert("b", stored("b", "db"));
+
+        // Without an index, lookups don't narrow the result
+        assert_eq!(names(shard.select(&app(&["web"]))), ["a", "b"]);
+
+        shard.add_index(
+            IndexKey::Label("app".to_string()),
+            IndexKey::label_indexer("app"),
+        );
+        assert_eq!(names(shard.select(&app(&["web"]))), ["a"]);
+    }
+
+    #[test]
+    fn test_lookups_intersect() {
+        let mut shard = Shard::default();
+        shard.add_index(
+            IndexKey::Label("app".to_string()),
+            IndexKey::label_indexer("app"),
+        );
+        shard.add_index(
+            IndexKey::Field("metadata.name".to_string()),
+            Arc::new(|o| vec![o["metadata"]["name"].as_str().unwrap().to_string()]),
+        );
+        shard.insert("a", stored("a", "web"));
+        shard.insert("b", stored("b", "web"));
+
+        let mut lookups = app(&["web"]);
+        lookups.push((
+            IndexKey::Field("metadata.name".to_string()),
+            BTreeSet::from(["b".to_string()]),
+        ));
+        assert_eq!(names(shard.select(&lookups)), ["b"]);
+    }
+
+    #[tokio::test]
+    async fn test_label_index_selection() {
+        let client = ClientBuilder::new()
+            .with_label_index::<Pod>("app")
+            .with_objects(vec![
+                pod("web-1", &[("app", "web"), ("tier", "frontend")]),
+                pod("web-2", &[("app", "web"), ("tier", "backend")]),
+                pod("db-1", &[("app", "db"), ("tier", "backend")]),
+                pod("other", &[]),
+            ])
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+
+        let list = |selector: &str| {
+            let pods = pods.clone();
+            let params = ListParams::default().labels(selector);
+            async move { pod_names(pods.list(&params).await.unwrap()) }
+        };
+        assert_eq!(list("app=web").await, ["web-1", "web-2"]);
+        assert_eq!(list("app==db").await, ["db-1"]);
+        assert_eq!(list("app in (web,db)").await, ["db-1", "web-1", "web-2"]);
+        // Requirements that can't use the index still apply
+        assert_eq!(list("app=web,tier!=frontend").await, ["web-2"]);
+        assert_eq!(list("app!=web").await, ["db-1", "other"]);
+        // Labels without a registered index are indexed on first use
+        assert_eq!(list("tier=backend").await, ["db-1", "web-2"]);
+        assert_eq!(list("tier=backend,app=db").await, ["db-1"]);
+
+        // Writes keep the index current
+        let patch = json!({ "metadata": { "labels": { "app": "db" } } });
+        pods.patch("web-1", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        pods.create(&PostParams::default(), &pod("web-3", &[("app", "web")]))
+            .await
+            .unwrap();
+        pods.delete("web-2", &DeleteParams::default().grace_period(0))
+            .await
+            .unwrap();
+        assert_eq!(list("app=web").await, ["web-3"]);
+        assert_eq!(list("app=db").await, ["db-1", "web-1"]);
+    }
+
+    #[tokio::test]
+    async fn test_field_index_selection() {
+        let mut scheduled = pod("scheduled", &[("app", "web")]);
+        scheduled.spec = Some(k8s_openapi::api::core::v1::PodSpec {
+            node_name: Some("node-1".to_string()),
+            service_account_name: Some("builder".to_string()),
+            ..Default::default()
+        });
+        let client = ClientBuilder::new()
+            .with_index::<Pod>(
+                "spec.serviceAccountName",
+                Arc::new(|obj| {
+                    obj.pointer("/spec/serviceAccountName")
+                        .and_then(|v| v.as_str())
+                        .map(|v| vec![v.to_string()])
+                        .unwrap_or_default()
+                }),
+            )
+            .with_objects(vec![scheduled, pod("pending", &[("app", "web")])])
+            .build()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+
+        let list = |selector: &str| {
+            let pods = pods.clone();
+            let params = ListParams::default().fields(selector);
+            async move { pod_names(pods.list(&params).await.unwrap()) }
+        };
+        assert_eq!(list("spec.nodeName=node-1").await, ["scheduled"]);
+        assert_eq!(list("spec.nodeName==node-1").await, ["scheduled"]);
+        assert_eq!(list("metadata.name=pending").await, ["pending"]);
+        assert_eq!(list("spec.serviceAccountName=builder").await, ["scheduled"]);
+        assert!(list("spec.serviceAccountName=other").await.is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+//! Point-in-time copies of the fake cluster's objects
+//!
+//! Stored objects are shared copy-on-write, so a snapshot only clones pointers and
+//! stays cheap however many objects the cluster holds.
+
+use crate::store::StoredObject;
+use crate::tracker::GVR;
+use std::collections::BTreeMap;
+
+/// Resource group, version, resource, namespace and name of a stored object
+pub(crate) type ObjectKey = (String, String, String, String, String);
+
+/// Every object stored in a fake cluster at one point in time
+///
+/// Taken with [`ClientHandle::snapshot`](crate::ClientHandle::snapshot) and put
+/// back with [`ClientHandle::restore`](crate::ClientHandle::restore).
+#[derive(Clone, Default)]
+pub struct Snapshot {
+    pub(crate) objects: BTreeMap<ObjectKey, StoredObject>,
+    /// Position of each object in insertion order, restored with it
+    pub(crate) insertions: BTreeMap<ObjectKey, u64>,
+}
+
+impl Snapshot {
+    pub(crate) fn key(gvr: &GVR, namespace: &str, name: &str) -> ObjectKey {
+        (
+            gvr.group.clone(),
+            gvr.version.clone(),
+            gvr.resource.clone(),
+            namespace.to_string(),
+            name.to_string(),
+        )
+    }
+
+    /// Number of objects in the snapshot
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Whether the snapshot holds no objects
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Describe how the objects changed between this snapshot and a later one
+    ///
+    /// One line per created, deleted or updated object; updates include the JSON
+    /// Patch from the old object to the new one.
+    pub(crate) fn changes(&self, after: &Snapshot) -> Vec<String> {
+        let describe = |(group, version, resource, namespace, name): &ObjectKey| {
+            let resource = match group.as_str() {
+                "" => format!("{version}/{resource}"),
+                group => format!("{group}/{version}/{resource}"),
+            };
+            match namespace.as_str() {
+                "" => format!("{resource} {name}"),
+                namespace => format!("{resource} {namespace}/{name}"),
+            }
+        };
+
+        let mut changes = Vec::new();
+        for (key, before) in &self.objects {
+            match after.objects.get(key) {
+                None => changes.push(format!("deleted {}", describe(key))),
+                Some(after) if after.data != before.data => {
+                    let patch = json_patch::diff(&before.data, &after.data);
+                    changes.push(format!(
+                        "updated {}: {}",
+                        describe(key),
+                        serde_json::to_string(&patch).unwrap_or_default()
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        for key in after.objects.keys() {
+            if !self.objects.contains_key(key) {
+                changes.push(format!("created {}", describe(key)));
+            }
+        }
+        changes
+    }
+}
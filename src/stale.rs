@@ -0,0 +1,152 @@
+//! Stale reads
+//!
+//! Controllers read through an informer cache that trails the API server. With
+//! stale reads configured for a resource, gets and lists of it are served from a
+//! view that only applies writes once they are a given lag old, so a controller
+//! that reads back what it just wrote gets the old object, as it would from its
+//! cache. Writes still go to the current objects, and watches are not delayed.
+
+use crate::tracker::{ObjectTracker, GVR};
+use crate::watch::{EventType, Expired};
+use crate::Result;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Lagging views of the resources configured for stale reads
+#[derive(Default)]
+pub(crate) struct StaleReads {
+    views: Mutex<HashMap<GVR, View>>,
+}
+
+struct View {
+    lag: Duration,
+    /// Objects by namespace and name
+    objects: BTreeMap<(String, String), Arc<Value>>,
+    /// resourceVersion of the last write applied
+    resource_version: u64,
+}
+
+impl View {
+    fn relist(&mut self, tracker: &ObjectTracker, gvr: &GVR) {
+        self.resource_version = tracker.current_resource_version();
+        self.objects = tracker
+            .list_shared(gvr, None)
+            .into_iter()
+            .map(|object| {
+                let metadata = &object["metadata"];
+                let key = (
+                    metadata["namespace"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    metadata["name"].as_str().unwrap_or_default().to_string(),
+                );
+                (key, object)
+            })
+            .collect();
+    }
+
+    /// Apply the writes that are at least the lag old
+    fn catch_up(&mut self, tracker: &ObjectTracker, gvr: &GVR) {
+        let Some(until) = Instant::now().checked_sub(self.lag) else {
+            return;
+        };
+        match tracker
+            .watch_cache()
+            .delivered(gvr, self.resource_version, until)
+        {
+            Ok(events) => {
+                for event in events {
+                    let name = event.object["metadata"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string();
+                    let key = (event.namespace, name);
+                    match event.event_type {
+                        EventType::Added | EventType::Modified => {
+                            self.objects.insert(key, event.object);
+                        }
+                        EventType::Deleted => {
+                            self.objects.remove(&key);
+                        }
+                    }
+                    self.resource_version = event.resource_version;
+                }
+            }
+            // Fell behind the watch history, so start over like a relisting informer
+            Err(Expired) => self.relist(tracker, gvr),
+        }
+    }
+}
+
+impl StaleReads {
+    /// Serve reads of a resource `lag` behind writes, starting from its current
+    /// objects
+    pub(crate) fn add(&self, tracker: &ObjectTracker, gvr: GVR, lag: Duration) {
+        let mut view = View {
+            lag,
+            objects: BTreeMap::new(),
+            resource_version: 0,
+        };
+        view.relist(tracker, &gvr);
+        self.views.lock().insert(gvr, view);
+    }
+
+    /// Reload every view from the current objects
+    ///
+    /// Needed after the objects change without publishing watch events, as on a
+    /// snapshot restore.
+    pub(crate) fn reset(&self, tracker: &ObjectTracker) {
+        for (gvr, view) in self.views.lock().iter_mut() {
+            view.relist(tracker, gvr);
+        }
+    }
+
+    /// Get an object as the lagging view has it
+    ///
+    /// Returns `None` if reads of the resource are not stale.
+    pub(crate) fn get(
+        &self,
+        tracker: &ObjectTracker,
+        gvr: &GVR,
+        namespace: &str,
+        name: &str,
+    ) -> Option<Result<Arc<Value>>> {
+        let mut views = self.views.lock();
+        let view = views.get_mut(gvr)?;
+        view.catch_up(tracker, gvr);
+        let key = (namespace.to_string(), name.to_string());
+        Some(
+            view.objects
+                .get(&key)
+                .cloned()
+                .ok_or_else(|| gvr.not_found_error(namespace, name)),
+        )
+    }
+
+    /// List objects as the lagging view has them, with the resourceVersion the
+    /// view is at once caught up
+    ///
+    /// Returns `None` if reads of the resource are not stale.
+    pub(crate) fn list(
+        &self,
+        tracker: &ObjectTracker,
+        gvr: &GVR,
+        namespace: Option<&str>,
+    ) -> Option<(Vec<Arc<Value>>, u64)> {
+        let mut views = self.views.lock();
+        let view = views.get_mut(gvr)?;
+        view.catch_up(tracker, gvr);
+        let objects = view
+            .objects
+            .iter()
+            .filter(|((ns, _), _)| namespace.is_none_or(|namespace| ns == namespace))
+            .map(|(_, object)| Arc::clone(object))
+            .collect();
+        Some((objects, view.resource_version))
+    }
+}
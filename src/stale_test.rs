@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{configmap_with, in_namespace};
+    use crate::ClientBuilder;
+    use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+    use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    const LAG: Duration = Duration::from_millis(200);
+
+    fn mode(cm: &ConfigMap) -> &str {
+        &cm.data.as_ref().unwrap()["mode"]
+    }
+
+    #[tokio::test]
+    async fn test_reads_lag_behind_writes() {
+        let client = ClientBuilder::new()
+            .with_object(in_namespace(
+                configmap_with("settings", "mode", "active"),
+                "default",
+            ))
+            .with_stale_reads::<ConfigMap>(LAG)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        // Initial objects are visible right away
+        assert_eq!(mode(&configmaps.get("settings").await.unwrap()), "active");
+
+        let patch = json!({ "data": { "mode": "standby" } });
+        let patched = configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert_eq!(mode(&patched), "standby");
+        configmaps
+            .create(
+                &PostParams::default(),
+                &in_namespace(configmap_with("created", "mode", "active"), "default"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(mode(&configmaps.get("settings").await.unwrap()), "active");
+        assert!(configmaps.get_opt("created").await.unwrap().is_none());
+        let listed = configmaps.list(&ListParams::default()).await.unwrap();
+        assert_eq!(listed.items.len(), 1);
+
+        tokio::time::sleep(LAG + Duration::from_millis(50)).await;
+        assert_eq!(mode(&configmaps.get("settings").await.unwrap()), "standby");
+        let listed = configmaps.list(&ListParams::default()).await.unwrap();
+        assert_eq!(listed.items.len(), 2);
+
+        configmaps
+            .delete("created", &DeleteParams::default())
+            .await
+            .unwrap();
+        assert!(configmaps.get_opt("created").await.unwrap().is_some());
+        tokio::time::sleep(LAG + Duration::from_millis(50)).await;
+        assert!(configmaps.get_opt("created").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_resource_version_matches_items() {
+        let client = ClientBuilder::new()
+            .with_stale_reads::<ConfigMap>(LAG)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let before = configmaps.list(&ListParams::default()).await.unwrap();
+
+        let created = configmaps
+            .create(
+                &PostParams::default(),
+                &in_namespace(configmap_with("settings", "mode", "active"), "default"),
+            )
+            .await
+            .unwrap();
+        let listed = configmaps.list(&ListParams::default()).await.unwrap();
+        assert!(listed.items.is_empty());
+        assert_eq!(
+            listed.metadata.resource_version,
+            before.metadata.resource_version
+        );
+
+        // The list that first shows the write is at its version, not the one before
+        tokio::time::sleep(LAG + Duration::from_millis(50)).await;
+        let listed = configmaps.list(&ListParams::default()).await.unwrap();
+        assert_eq!(listed.items.len(), 1);
+        assert_eq!(
+            listed.metadata.resource_version,
+            created.metadata.resource_version
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_collection_deletes_current_objects() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(in_namespace(
+                configmap_with("settings", "mode", "active"),
+                "default",
+            ))
+            .with_stale_reads::<ConfigMap>(LAG)
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        configmaps
+            .create(
+                &PostParams::default(),
+                &in_namespace(configmap_with("created", "mode", "active"), "default"),
+            )
+            .await
+            .unwrap();
+        configmaps
+            .delete("settings", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        // The lagging view still has "settings" and not "created"
+        configmaps
+            .delete_collection(&DeleteParams::default(), &ListParams::default())
+            .await
+            .unwrap();
+        assert!(handle.list_namespace("default").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_from_stale_read_conflicts() {
+        let client = ClientBuilder::new()
+            .with_object(in_namespace(
+                configmap_with("settings", "mode", "active"),
+                "default",
+            ))
+            .with_stale_reads::<ConfigMap>(LAG)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let patch = json!({ "data": { "mode": "standby" } });
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+
+        let mut stale = configmaps.get("settings").await.unwrap();
+        stale.data = Some(BTreeMap::from([("mode".to_string(), "off".to_string())]));
+        let err = configmaps
+            .replace("settings", &PostParams::default(), &stale)
+            .await
+            .unwrap_err();
+        match err {
+            kube::Error::Api(response) => assert_eq!(response.code, 409),
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_other_resources_read_current_objects() {
+        let client = ClientBuilder::new()
+            .with_stale_reads::<ConfigMap>(LAG)
+            .build()
+            .await
+            .unwrap();
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+
+        let mut secret = Secret::default();
+        secret.metadata.name = Some("token".to_string());
+        secrets
+            .create(&PostParams::default(), &secret)
+            .await
+            .unwrap();
+        assert!(secrets.get_opt("token").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_writes_apply_to_current_objects() {
+        let client = ClientBuilder::new()
+            .with_object(in_namespace(
+                configmap_with("settings", "mode", "active"),
+                "default",
+            ))
+            .with_stale_reads::<ConfigMap>(LAG)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        for mode in ["standby", "off"] {
+            let patch = json!({ "data": { "mode": mode } });
+            let patched = configmaps
+                .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+                .await
+                .unwrap();
+            assert_eq!(self::mode(&patched), mode);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! StatefulSet volume claim simulation and PVC binding helpers
+//!
+//! When enabled, every write to a StatefulSet creates the PersistentVolumeClaims
+//! its `volumeClaimTemplates` call for, one per template and ordinal, named
+//! `<template>-<statefulset>-<ordinal>` like the real StatefulSet controller.
+//! Claims are never deleted on scale-down, matching the default `Retain` policy.
+
+use crate::tracker::{ObjectTracker, WriteObserver, GVK, GVR};
+use crate::{Error, Result};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use tracing::warn;
+
+fn statefulsets_gvr() -> GVR {
+    GVR::new("apps", "v1", "statefulsets")
+}
+
+fn pvcs_gvr() -> GVR {
+    GVR::new("", "v1", "persistentvolumeclaims")
+}
+
+fn pvc_gvk() -> GVK {
+    GVK::new("", "v1", "PersistentVolumeClaim")
+}
+
+/// Tracker observer creating claims whenever a StatefulSet changes
+pub(crate) fn observer() -> WriteObserver {
+    Arc::new(|tracker, gvr, namespace| {
+        if *gvr != statefulsets_gvr() {
+            return;
+        }
+        if let Err(e) = reconcile(tracker, namespace) {
+            warn!(
+                "Failed to reconcile StatefulSet claims in namespace {}: {}",
+                namespace, e
+            );
+        }
+    })
+}
+
+/// Create missing claims for every StatefulSet in a namespace
+pub(crate) fn reconcile(tracker: &ObjectTracker, namespace: &str) -> Result<()> {
+    for statefulset in tracker.list(&statefulsets_gvr(), Some(namespace))? {
+        let name = statefulset
+            .pointer("/metadata/name")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let replicas = statefulset
+            .pointer("/spec/replicas")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1);
+        let templates = statefulset
+            .pointer("/spec/volumeClaimTemplates")
+            .and_then(|v| v.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let selector_labels = statefulset
+            .pointer("/spec/selector/matchLabels")
+            .and_then(|v| v.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        for template in templates {
+            let template_name = template
+                .pointer("/metadata/name")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            for ordinal in 0..replicas {
+                let claim_name = format!("{template_name}-{name}-{ordinal}");
+                if tracker.get(&pvcs_gvr(), namespace, &claim_name).is_ok() {
+                    continue;
+                }
+                let claim = claim_from_template(template, &claim_name, namespace, &selector_labels);
+                tracker.create(&pvcs_gvr(), claim, namespace)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn claim_from_template(
+    template: &Value,
+    name: &str,
+    namespace: &str,
+    selector_labels: &Map<String, Value>,
+) -> Value {
+    let mut labels = template
+        .pointer("/metadata/labels")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+    labels.extend(selector_labels.clone());
+
+    let mut metadata = json!({
+        "name": name,
+        "namespace": namespace,
+        "labels": labels,
+    });
+    if let Some(annotations) = template.pointer("/metadata/annotations") {
+        metadata["annotations"] = annotations.clone();
+    }
+
+    json!({
+        "apiVersion": "v1",
+        "kind": "PersistentVolumeClaim",
+        "metadata": metadata,
+        "spec": template.get("spec").cloned().unwrap_or_else(|| json!({})),
+        "status": { "phase": "Pending" },
+    })
+}
+
+/// Bind a claim to a volume, as the PV controller would
+///
+/// Sets the claim's `spec.volumeName` and moves it to the `Bound` phase with the
+/// requested capacity and access modes. If the PersistentVolume exists, its
+/// `claimRef` is pointed at the claim and it is marked `Bound` too.
+pub(crate) fn bind_pvc(
+    tracker: &ObjectTracker,
+    namespace: &str,
+    name: &str,
+    pv_name: &str,
+) -> Result<Value> {
+    let mut claim = tracker.get(&pvcs_gvr(), namespace, name)?;
+
+    let bound_to = claim.pointer("/spec/volumeName").and_then(|v| v.as_str());
+    if bound_to.is_some_and(|v| !v.is_empty() && v != pv_name) {
+        return Err(Error::ImmutableField {
+            field: "spec.volumeName".to_string(),
+        });
+    }
+
+    claim["spec"]["volumeName"] = json!(pv_name);
+    claim["metadata"]["annotations"]["pv.kubernetes.io/bind-completed"] = json!("yes");
+    claim = tracker.update(&pvcs_gvr(), &pvc_gvk(), claim, namespace, false)?;
+
+    let mut status = json!({ "phase": "Bound" });
+    if let Some(modes) = claim.pointer("/spec/accessModes") {
+        status["accessModes"] = modes.clone();
+    }
+    if let Some(capacity) = claim.pointer("/spec/resources/requests") {
+        status["capacity"] = capacity.clone();
+    }
+    claim["status"] = status;
+    let claim = tracker.update(&pvcs_gvr(), &pvc_gvk(), claim, namespace, true)?;
+
+    let pvs_gvr = GVR::new("", "v1", "persistentvolumes");
+    let pv_gvk = GVK::new("", "v1", "PersistentVolume");
+    if let Ok(mut pv) = tracker.get(&pvs_gvr, "", pv_name) {
+        pv["spec"]["claimRef"] = json!({
+            "apiVersion": "v1",
+            "kind": "PersistentVolumeClaim",
+            "namespace": namespace,
+            "name": name,
+            "uid": claim.pointer("/metadata/uid"),
+            "resourceVersion": claim.pointer("/metadata/resourceVersion"),
+        });
+        let mut pv = tracker.update(&pvs_gvr, &pv_gvk, pv, "", false)?;
+        pv["status"] = json!({ "phase": "Bound" });
+        tracker.update(&pvs_gvr, &pv_gvk, pv, "", true)?;
+    }
+
+    Ok(claim)
+}
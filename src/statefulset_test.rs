@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::apps::v1::StatefulSet;
+    use k8s_openapi::api::core::v1::{PersistentVolume, PersistentVolumeClaim};
+    use kube::api::{Api, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+
+    fn statefulset(replicas: i32) -> StatefulSet {
+        serde_json::from_value(json!({
+            "apiVersion": "apps/v1",
+            "kind": "StatefulSet",
+            "metadata": { "name": "db" },
+            "spec": {
+                "replicas": replicas,
+                "serviceName": "db",
+                "selector": { "matchLabels": { "app": "db" } },
+                "template": { "metadata": { "labels": { "app": "db" } } },
+                "volumeClaimTemplates": [{
+                    "metadata": { "name": "data" },
+                    "spec": {
+                        "accessModes": ["ReadWriteOnce"],
+                        "resources": { "requests": { "storage": "1Gi" } }
+                    }
+                }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_claims_created_for_each_ordinal() {
+        let client = ClientBuilder::new()
+            .with_statefulset_controller()
+            .build()
+            .await
+            .unwrap();
+        let statefulsets: Api<StatefulSet> = Api::namespaced(client.clone(), "default");
+        let claims: Api<PersistentVolumeClaim> = Api::namespaced(client, "default");
+
+        statefulsets
+            .create(&PostParams::default(), &statefulset(2))
+            .await
+            .unwrap();
+
+        let mut names: Vec<_> = claims
+            .list(&ListParams::default().labels("app=db"))
+            .await
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|c| c.metadata.name.unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["data-db-0", "data-db-1"]);
+
+        let claim = claims.get("data-db-0").await.unwrap();
+        assert_eq!(claim.status.unwrap().phase.as_deref(), Some("Pending"));
+
+        // Scaling up adds claims, scaling down keeps them
+        let patch = json!({ "spec": { "replicas": 3 } });
+        statefulsets
+            .patch("db", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert!(claims.get("data-db-2").await.is_ok());
+
+        let patch = json!({ "spec": { "replicas": 1 } });
+        statefulsets
+            .patch("db", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert_eq!(
+            claims
+                .list(&ListParams::default())
+                .await
+                .unwrap()
+                .items
+                .len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bind_pvc() {
+        let mut pv = PersistentVolume::default();
+        pv.metadata.name = Some("pv-0001".to_string());
+
+        let (client, handle) = ClientBuilder::new()
+            .with_statefulset_controller()
+            .with_object(statefulset(1))
+            .with_object(pv)
+            .build_with_handle()
+            .await
+            .unwrap();
+        let claims: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), "default");
+        let volumes: Api<PersistentVolume> = Api::all(client);
+
+        let bound = handle.bind_pvc("default", "data-db-0", "pv-0001").unwrap();
+        assert_eq!(
+            bound.spec.as_ref().unwrap().volume_name.as_deref(),
+            Some("pv-0001")
+        );
+
+        let claim = claims.get("data-db-0").await.unwrap();
+        let status = claim.status.unwrap();
+        assert_eq!(status.phase.as_deref(), Some("Bound"));
+        assert_eq!(status.capacity.unwrap()["storage"].0, "1Gi");
+
+        let pv = volumes.get("pv-0001").await.unwrap();
+        let claim_ref = pv.spec.unwrap().claim_ref.unwrap();
+        assert_eq!(claim_ref.name.as_deref(), Some("data-db-0"));
+        assert_eq!(pv.status.unwrap().phase.as_deref(), Some("Bound"));
+
+        // A bound claim can't move to another volume
+        assert!(handle.bind_pvc("default", "data-db-0", "pv-0002").is_err());
+        assert!(handle.bind_pvc("default", "missing", "pv-0001").is_err());
+    }
+}
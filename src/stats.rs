@@ -0,0 +1,96 @@
+//! Memory held by the tracker
+//!
+//! Long property-based tests write far more than a unit test does, and the
+//! tracker keeps what they write: the stored objects and the watch history
+//! behind resuming watches. Sizes are approximate, the length of the JSON an
+//! object serializes to, which is close enough to see what grows between runs
+//! and to cap it with [`ClientBuilder::with_memory_limit`](crate::ClientBuilder::with_memory_limit).
+
+use crate::tracker::GVR;
+use serde_json::Value;
+
+/// Objects stored for one resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceStats {
+    pub gvr: GVR,
+    /// Number of objects, across namespaces
+    pub objects: usize,
+    /// Approximate size of the objects, in bytes
+    pub bytes: usize,
+}
+
+/// What the tracker holds, from [`ClientHandle::stats`](crate::ClientHandle::stats)
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube_fake_client::{ClientBuilder, GVR};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+///
+/// // ... run the controller under test with `client` ...
+///
+/// let stats = handle.stats();
+/// let configmaps = stats.resource(&GVR::of::<ConfigMap>()).map_or(0, |r| r.objects);
+/// println!("{configmaps} ConfigMaps, {} bytes in total", stats.total_bytes());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackerStats {
+    /// Resources with objects stored, ordered by group, version and resource
+    pub resources: Vec<ResourceStats>,
+    /// Number of events kept for watches resuming from an earlier resourceVersion
+    pub watch_events: usize,
+    /// Approximate size of the objects those events carry, in bytes
+    pub watch_bytes: usize,
+}
+
+impl TrackerStats {
+    /// The objects of one resource, `None` if it has none
+    pub fn resource(&self, gvr: &GVR) -> Option<&ResourceStats> {
+        self.resources.iter().find(|resource| resource.gvr == *gvr)
+    }
+
+    /// Number of stored objects, across resources
+    pub fn objects(&self) -> usize {
+        self.resources.iter().map(|resource| resource.objects).sum()
+    }
+
+    /// Approximate size of the stored objects, in bytes
+    pub fn object_bytes(&self) -> usize {
+        self.resources.iter().map(|resource| resource.bytes).sum()
+    }
+
+    /// Approximate size of the stored objects and the watch history, in bytes
+    ///
+    /// This is what [`ClientBuilder::with_memory_limit`](crate::ClientBuilder::with_memory_limit)
+    /// caps.
+    pub fn total_bytes(&self) -> usize {
+        self.object_bytes() + self.watch_bytes
+    }
+}
+
+/// Length of the JSON a value serializes to, counting numbers as 8 bytes
+pub(crate) fn approximate_size(value: &Value) -> usize {
+    let separators = |len: usize| len.saturating_sub(1);
+    match value {
+        Value::Null | Value::Bool(true) => 4,
+        Value::Bool(false) => 5,
+        Value::Number(_) => 8,
+        Value::String(s) => s.len() + 2,
+        Value::Array(items) => {
+            2 + separators(items.len()) + items.iter().map(approximate_size).sum::<usize>()
+        }
+        Value::Object(fields) => {
+            2 + separators(fields.len())
+                + fields
+                    .iter()
+                    .map(|(key, value)| key.len() + 3 + approximate_size(value))
+                    .sum::<usize>()
+        }
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod tests {
+    use crate::stats::approximate_size;
+    use crate::test_fixtures::configmap_with;
+    use crate::{ClientBuilder, GVR};
+    use futures::StreamExt;
+    use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+    use kube::api::{Api, DeleteParams, PostParams, WatchEvent, WatchParams};
+    use serde_json::json;
+
+    fn status_code(error: &kube::Error) -> Option<u16> {
+        match error {
+            kube::Error::Api(response) => Some(response.code),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_approximate_size_is_the_json_length() {
+        let object = json!({
+            "metadata": { "name": "settings", "labels": {} },
+            "data": ["a", true, false, null],
+        });
+        assert_eq!(approximate_size(&object), object.to_string().len());
+        assert_eq!(approximate_size(&json!(12)), 8);
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_objects_per_resource() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        let secrets: Api<Secret> = Api::namespaced(client, "default");
+
+        for name in ["one", "two"] {
+            configmaps
+                .create(&PostParams::default(), &configmap_with(name, "value", "x"))
+                .await
+                .unwrap();
+        }
+        let mut secret = Secret::default();
+        secret.metadata.name = Some("token".to_string());
+        secrets
+            .create(&PostParams::default(), &secret)
+            .await
+            .unwrap();
+        let small = handle.stats();
+        assert_eq!(small.objects(), 3);
+        assert_eq!(small.watch_events, 3);
+        let stored = small.resource(&GVR::of::<ConfigMap>()).unwrap();
+        assert_eq!(stored.objects, 2);
+        assert!(stored.bytes > 0);
+        assert_eq!(small.resource(&GVR::of::<Secret>()).unwrap().objects, 1);
+
+        let mut large = configmaps.get("one").await.unwrap();
+        large.data = configmap_with("one", "value", &"x".repeat(1000)).data;
+        configmaps
+            .replace("one", &PostParams::default(), &large)
+            .await
+            .unwrap();
+        let grown = handle.stats();
+        let stored = grown.resource(&GVR::of::<ConfigMap>()).unwrap();
+        assert_eq!(stored.objects, 2);
+        assert!(grown.object_bytes() >= small.object_bytes() + 999);
+        assert!(grown.watch_bytes >= small.watch_bytes + 1000);
+        assert_eq!(
+            grown.total_bytes(),
+            grown.object_bytes() + grown.watch_bytes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compact_drops_the_watch_history() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let created = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "value", "x"),
+            )
+            .await
+            .unwrap();
+        let version = created.metadata.resource_version.unwrap();
+        let params = WatchParams::default().timeout(1);
+        let mut open = configmaps.watch(&params, &version).await.unwrap().boxed();
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("removed", "value", "x"),
+            )
+            .await
+            .unwrap();
+        configmaps
+            .delete("removed", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        handle.compact();
+        let stats = handle.stats();
+        assert_eq!((stats.watch_events, stats.watch_bytes), (0, 0));
+        assert_eq!(stats.objects(), 1);
+
+        // Watches resuming from before the compaction have to relist
+        let mut resumed = configmaps.watch(&params, &version).await.unwrap().boxed();
+        match resumed.next().await {
+            Some(Ok(WatchEvent::Error(response))) => assert_eq!(response.code, 410),
+            other => panic!("expected 410 Gone, got {other:?}"),
+        }
+
+        // Open watches keep receiving events
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("later", "value", "x"),
+            )
+            .await
+            .unwrap();
+        let mut names = Vec::new();
+        while let Some(Ok(event)) = open.next().await {
+            if let WatchEvent::Added(cm) = event {
+                names.extend(cm.metadata.name);
+            }
+        }
+        assert_eq!(names, ["removed", "later"]);
+        assert_eq!(handle.stats().watch_events, 1);
+        handle.verify_event_ordering();
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit_fails_writes_over_the_cap() {
+        let (client, handle) = ClientBuilder::new()
+            .with_memory_limit(8 * 1024)
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let value = "x".repeat(1000);
+
+        let mut created = 0;
+        let error = loop {
+            match configmaps
+                .create(
+                    &PostParams::default(),
+                    &configmap_with(&format!("cm-{created}"), "value", &value),
+                )
+                .await
+            {
+                Ok(_) => created += 1,
+                Err(e) => break e,
+            }
+        };
+        assert!((2..8).contains(&created), "created {created}");
+        assert_eq!(status_code(&error), Some(500));
+        assert!(error.to_string().contains("database space exceeded"));
+        assert!(handle.stats().total_bytes() <= 8 * 1024);
+
+        // Deletes go through over the cap, and compaction frees the history
+        for i in 0..created {
+            configmaps
+                .delete(&format!("cm-{i}"), &DeleteParams::default())
+                .await
+                .unwrap();
+        }
+        handle.compact();
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("cm-0", "value", &value),
+            )
+            .await
+            .unwrap();
+    }
+}
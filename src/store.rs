@@ -0,0 +1,315 @@
+//! Storage behind the object tracker
+//!
+//! The tracker gives the fake its API server semantics: resource versions,
+//! optimistic concurrency, generations, graceful deletion and watch events. The
+//! objects themselves live in an [`ObjectStore`]. [`MemoryStore`] is the
+//! default; [`ClientBuilder::with_object_store`](crate::ClientBuilder::with_object_store)
+//! swaps in another, such as one that mirrors every write into a controller's
+//! cache to run the controller against the fake, or one wrapping a
+//! `MemoryStore` to count reads.
+//!
+//! Every write goes through the tracker, which serializes the writes to each
+//! resource in each namespace, so a store only has to keep its individual
+//! operations atomic.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::store::{MemoryStore, ObjectStore, StoredObject};
+//! use kube_fake_client::{ClientBuilder, GVR};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//!
+//! /// Counts the objects read from a store
+//! #[derive(Default)]
+//! struct Counting {
+//!     inner: MemoryStore,
+//!     reads: Arc<AtomicUsize>,
+//! }
+//!
+//! impl ObjectStore for Counting {
+//!     fn get(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+//!         self.reads.fetch_add(1, Ordering::Relaxed);
+//!         self.inner.get(gvr, namespace, name)
+//!     }
+//!
+//!     fn list(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<(String, StoredObject)> {
+//!         let objects = self.inner.list(gvr, namespace);
+//!         self.reads.fetch_add(objects.len(), Ordering::Relaxed);
+//!         objects
+//!     }
+//!
+//!     fn collections(&self) -> Vec<(GVR, String)> {
+//!         self.inner.collections()
+//!     }
+//!
+//!     fn insert(&self, gvr: &GVR, namespace: &str, name: &str, object: StoredObject) {
+//!         self.inner.insert(gvr, namespace, name, object)
+//!     }
+//!
+//!     fn remove(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+//!         self.inner.remove(gvr, namespace, name)
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::new()
+//!     .with_object_store(Counting::default())
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::IndexerFunc;
+use crate::shard::{IndexKey, IndexLookup, Shard};
+use crate::tracker::GVR;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub use crate::watch::EventType;
+
+/// A stored object
+///
+/// The object is shared behind an `Arc`: reads clone the pointer rather than the
+/// JSON tree, and writes replace it (or copy it on write) instead of mutating it
+/// in place. `metadata` is the object's metadata, parsed.
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub data: Arc<Value>,
+    pub metadata: ObjectMeta,
+}
+
+/// Storage of the objects of a fake cluster, keyed by resource, namespace and
+/// name
+///
+/// Cluster-scoped objects are stored under the namespace `""`.
+pub trait ObjectStore: Send + Sync {
+    /// The object stored under a name
+    fn get(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject>;
+
+    /// Objects of a resource in a namespace, or in every namespace, each with
+    /// the namespace it is stored in
+    ///
+    /// Objects come in the order they were first inserted; replacing an object
+    /// keeps its place. The tracker sorts them itself for
+    /// [`ListOrder::Name`](crate::ListOrder::Name).
+    fn list(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<(String, StoredObject)>;
+
+    /// Every resource and namespace with objects stored
+    fn collections(&self) -> Vec<(GVR, String)>;
+
+    /// Store an object under a name, replacing any object of that name
+    fn insert(&self, gvr: &GVR, namespace: &str, name: &str, object: StoredObject);
+
+    /// Remove the object stored under a name, returning it
+    fn remove(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject>;
+
+    /// Called after every committed write, with the event watches receive
+    ///
+    /// Snapshot restores aren't reported.
+    fn on_event(&self, gvr: &GVR, namespace: &str, event_type: EventType, object: &Value) {
+        let _ = (gvr, namespace, event_type, object);
+    }
+}
+
+type ShardRef = Arc<RwLock<Shard>>;
+type ShardsByNamespace = HashMap<String, ShardRef>;
+type ShardMap = HashMap<GVR, ShardsByNamespace>;
+
+/// Indexers registered for each resource, applied to every shard of it
+type Indexers = HashMap<GVR, HashMap<IndexKey, IndexerFunc>>;
+
+/// The default store, keeping objects in memory
+///
+/// Storage is sharded by resource and namespace, each shard behind its own lock.
+/// The outer map is locked just long enough to look a shard up; shards are only
+/// removed by compaction, once empty. Shards keep secondary indexes of label and field
+/// values, which the tracker uses to narrow selector lookups.
+#[derive(Default)]
+pub struct MemoryStore {
+    shards: RwLock<ShardMap>,
+    indexers: RwLock<Indexers>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn shard(&self, gvr: &GVR, namespace: &str) -> Option<ShardRef> {
+        self.shards
+            .read()
+            .get(gvr)
+            .and_then(|by_ns| by_ns.get(namespace))
+            .cloned()
+    }
+
+    fn shard_or_insert(&self, gvr: &GVR, namespace: &str) -> ShardRef {
+        if let Some(shard) = self.shard(gvr, namespace) {
+            return shard;
+        }
+        let mut shards = self.shards.write();
+        shards
+            .entry(gvr.clone())
+            .or_default()
+            .entry(namespace.to_string())
+            .or_insert_with(|| {
+                let indexers = self.indexers.read();
+                let shard = indexers
+                    .get(gvr)
+                    .map(Shard::with_indexes)
+                    .unwrap_or_default();
+                Arc::new(RwLock::new(shard))
+            })
+            .clone()
+    }
+
+    /// The shards of a resource in a namespace, or across namespaces
+    fn shards_of(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<(String, ShardRef)> {
+        if let Some(namespace) = namespace {
+            return self
+                .shard(gvr, namespace)
+                .map(|shard| (namespace.to_string(), shard))
+                .into_iter()
+                .collect();
+        }
+        self.shards
+            .read()
+            .get(gvr)
+            .map(|by_ns| {
+                by_ns
+                    .iter()
+                    .map(|(namespace, shard)| (namespace.clone(), Arc::clone(shard)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Maintain a secondary index for a resource
+    ///
+    /// Existing objects are indexed immediately. Registering a key that is already
+    /// indexed has no effect.
+    pub(crate) fn add_index(&self, gvr: &GVR, key: IndexKey, indexer: IndexerFunc) {
+        // Hold the outer lock so shards created concurrently see the new indexer
+        let shards = self.shards.write();
+        {
+            let mut indexers = self.indexers.write();
+            let by_key = indexers.entry(gvr.clone()).or_default();
+            if by_key.contains_key(&key) {
+                return;
+            }
+            by_key.insert(key.clone(), Arc::clone(&indexer));
+        }
+        for shard in shards.get(gvr).into_iter().flat_map(|by_ns| by_ns.values()) {
+            shard.write().add_index(key.clone(), Arc::clone(&indexer));
+        }
+    }
+
+    /// Whether a resource has a secondary index with this key
+    pub(crate) fn has_index(&self, gvr: &GVR, key: &IndexKey) -> bool {
+        self.indexers
+            .read()
+            .get(gvr)
+            .is_some_and(|by_key| by_key.contains_key(key))
+    }
+
+    /// Drop the shards left without objects
+    ///
+    /// A shard a write is about to insert into is kept: writes hold their own
+    /// reference to the shard, and new ones can't be taken while the outer map
+    /// is locked.
+    pub(crate) fn drop_empty_shards(&self) {
+        self.shards.write().retain(|_, by_ns| {
+            by_ns.retain(|_, shard| {
+                Arc::strong_count(shard) > 1 || shard.read().objects().next().is_some()
+            });
+            !by_ns.is_empty()
+        });
+    }
+
+    /// Objects matching index lookups, with their namespace and insertion
+    /// position
+    ///
+    /// Lookups on keys without an index don't narrow the result.
+    pub(crate) fn list_indexed(
+        &self,
+        gvr: &GVR,
+        namespace: Option<&str>,
+        lookups: &[IndexLookup],
+    ) -> Vec<(String, u64, Arc<Value>)> {
+        self.shards_of(gvr, namespace)
+            .into_iter()
+            .flat_map(|(namespace, shard)| {
+                let shard = shard.read();
+                shard
+                    .select(lookups)
+                    .into_iter()
+                    .map(|object| {
+                        let name = object["metadata"]["name"].as_str().unwrap_or_default();
+                        (namespace.clone(), shard.insertion(name), object)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+impl ObjectStore for MemoryStore {
+    fn get(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+        self.shard(gvr, namespace)?.read().get(name).cloned()
+    }
+
+    fn list(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<(String, StoredObject)> {
+        let mut objects: Vec<(u64, String, StoredObject)> = self
+            .shards_of(gvr, namespace)
+            .into_iter()
+            .flat_map(|(namespace, shard)| {
+                let shard = shard.read();
+                shard
+                    .objects()
+                    .map(|(name, stored)| {
+                        (shard.insertion(name), namespace.clone(), stored.clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        objects.sort_unstable_by_key(|(insertion, _, _)| *insertion);
+        objects
+            .into_iter()
+            .map(|(_, namespace, stored)| (namespace, stored))
+            .collect()
+    }
+
+    fn collections(&self) -> Vec<(GVR, String)> {
+        let shards: Vec<(GVR, String, ShardRef)> = self
+            .shards
+            .read()
+            .iter()
+            .flat_map(|(gvr, by_ns)| {
+                by_ns
+                    .iter()
+                    .map(|(namespace, shard)| (gvr.clone(), namespace.clone(), Arc::clone(shard)))
+            })
+            .collect();
+        shards
+            .into_iter()
+            .filter(|(_, _, shard)| shard.read().objects().next().is_some())
+            .map(|(gvr, namespace, _)| (gvr, namespace))
+            .collect()
+    }
+
+    fn insert(&self, gvr: &GVR, namespace: &str, name: &str, object: StoredObject) {
+        self.shard_or_insert(gvr, namespace)
+            .write()
+            .insert(name, object);
+    }
+
+    fn remove(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+        self.shard(gvr, namespace)?.write().remove(name)
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use crate::store::{EventType, MemoryStore, ObjectStore, StoredObject};
+    use crate::test_fixtures::{configmap, labeled};
+    use crate::{ClientBuilder, GVR};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+    use parking_lot::Mutex;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    /// A store recording the events of every write
+    #[derive(Default)]
+    struct Mirror {
+        inner: MemoryStore,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ObjectStore for Mirror {
+        fn get(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+            self.inner.get(gvr, namespace, name)
+        }
+
+        fn list(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<(String, StoredObject)> {
+            self.inner.list(gvr, namespace)
+        }
+
+        fn collections(&self) -> Vec<(GVR, String)> {
+            self.inner.collections()
+        }
+
+        fn insert(&self, gvr: &GVR, namespace: &str, name: &str, object: StoredObject) {
+            self.inner.insert(gvr, namespace, name, object)
+        }
+
+        fn remove(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<StoredObject> {
+            self.inner.remove(gvr, namespace, name)
+        }
+
+        fn on_event(&self, gvr: &GVR, namespace: &str, event_type: EventType, object: &Value) {
+            self.events.lock().push(format!(
+                "{} {}/{}/{} rv={}",
+                event_type.as_str(),
+                gvr.resource,
+                namespace,
+                object["metadata"]["name"].as_str().unwrap_or_default(),
+                object["metadata"]["resourceVersion"]
+                    .as_str()
+                    .unwrap_or_default()
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_store_sees_every_write() {
+        let store = Mirror::default();
+        let events = Arc::clone(&store.events);
+        let client = ClientBuilder::new()
+            .with_object_store(store)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+
+        for (name, app) in [("web-2", "web"), ("db", "db"), ("web-1", "web")] {
+            configmaps
+                .create(
+                    &PostParams::default(),
+                    &labeled(configmap(name), "app", app),
+                )
+                .await
+                .unwrap();
+        }
+        configmaps
+            .patch(
+                "db",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "data": { "port": "5432" } })),
+            )
+            .await
+            .unwrap();
+        configmaps
+            .delete("web-2", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        // Selectors work without the indexes of the built-in store
+        let names: Vec<String> = configmaps
+            .list(&ListParams::default().labels("app=web"))
+            .await
+            .unwrap()
+            .items
+            .into_iter()
+            .filter_map(|cm| cm.metadata.name)
+            .collect();
+        assert_eq!(names, ["web-1"]);
+        assert_eq!(
+            *events.lock(),
+            [
+                "ADDED configmaps/default/web-2 rv=1",
+                "ADDED configmaps/default/db rv=2",
+                "ADDED configmaps/default/web-1 rv=3",
+                "MODIFIED configmaps/default/db rv=4",
+                "DELETED configmaps/default/web-2 rv=5",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_store_keeps_existing_objects() {
+        let store = MemoryStore::new();
+        let data = json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "existing", "namespace": "default", "resourceVersion": "41" },
+        });
+        store.insert(
+            &GVR::new("", "v1", "configmaps"),
+            "default",
+            "existing",
+            StoredObject {
+                metadata: serde_json::from_value(data["metadata"].clone()).unwrap(),
+                data: Arc::new(data),
+            },
+        );
+        let client = ClientBuilder::new()
+            .with_object_store(store)
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+
+        let existing = configmaps.get("existing").await.unwrap();
+        assert_eq!(existing.metadata.resource_version.as_deref(), Some("41"));
+        let created = configmaps
+            .create(
+                &PostParams::default(),
+                &labeled(configmap("new"), "app", "web"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.metadata.resource_version.as_deref(), Some("42"));
+    }
+}
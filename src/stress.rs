@@ -0,0 +1,314 @@
+//! Concurrency stress testing for the fake client
+//!
+//! [`run`] hammers a few ConfigMaps with concurrent writes through a
+//! `kube::Client` and then checks that the result is consistent with some serial
+//! order of the successful writes:
+//!
+//! - Read-modify-write replaces increment a counter and retry on conflict; the final
+//!   counter must equal the number of successful replaces (no lost updates).
+//! - Merge patches add a unique annotation without pinning a resource version; they
+//!   must never conflict and every annotation must survive.
+//! - Every successful write returns a distinct resource version, greater than the one
+//!   it was based on, and the final object carries the highest of them.
+//! - `metadata.generation` counts every successful write.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::stress::{self, StressConfig};
+//! use kube_fake_client::ClientBuilder;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::new().build().await?;
+//! let report = stress::run(client, &StressConfig::default()).await?;
+//! report.assert_linearizable();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Error, Result};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Api, Patch, PatchParams, PostParams};
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+
+/// Annotation prefix used by stress patches
+const ANNOTATION_PREFIX: &str = "stress.kube-fake-client/";
+
+/// Shape of a stress run
+#[derive(Debug, Clone)]
+pub struct StressConfig {
+    /// Namespace the ConfigMaps are created in
+    pub namespace: String,
+    /// Number of ConfigMaps written to concurrently
+    pub objects: usize,
+    /// Number of concurrent workers
+    pub workers: usize,
+    /// Operations each worker performs, alternating replaces and patches
+    pub operations_per_worker: usize,
+}
+
+impl Default for StressConfig {
+    fn default() -> Self {
+        Self {
+            namespace: "default".to_string(),
+            objects: 2,
+            workers: 8,
+            operations_per_worker: 50,
+        }
+    }
+}
+
+/// Outcome of a stress run
+#[derive(Debug, Clone, Default)]
+pub struct StressReport {
+    /// Successful read-modify-write replaces
+    pub replaces: usize,
+    /// Successful merge patches
+    pub patches: usize,
+    /// Conflicts returned to replaces and retried
+    pub conflicts: usize,
+    /// Invariant violations found; empty if the run was consistent
+    pub violations: Vec<String>,
+}
+
+impl StressReport {
+    /// Whether no invariant was violated
+    pub fn is_linearizable(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Panic listing the violations, if any
+    pub fn assert_linearizable(&self) {
+        assert!(
+            self.is_linearizable(),
+            "stress run found {} violation(s):\n{}",
+            self.violations.len(),
+            self.violations.join("\n")
+        );
+    }
+}
+
+/// A successful write as seen by a worker
+struct Write {
+    object: usize,
+    based_on: u64,
+    resource_version: u64,
+}
+
+#[derive(Default)]
+struct WorkerLog {
+    replaces: BTreeMap<usize, usize>,
+    patches: BTreeMap<usize, usize>,
+    conflicts: usize,
+    writes: Vec<Write>,
+    violations: Vec<String>,
+}
+
+fn object_name(index: usize) -> String {
+    format!("stress-{index}")
+}
+
+fn resource_version(cm: &ConfigMap) -> u64 {
+    cm.metadata
+        .resource_version
+        .as_deref()
+        .and_then(|rv| rv.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Run concurrent writes against the client and verify the outcome
+///
+/// The ConfigMaps named `stress-<n>` must not exist beforehand. Run this on a
+/// multi-threaded runtime to get real parallelism.
+///
+/// # Errors
+///
+/// Returns an error if the ConfigMaps cannot be created or read back. Invariant
+/// violations are reported in [`StressReport::violations`] instead.
+pub async fn run(client: kube::Client, config: &StressConfig) -> Result<StressReport> {
+    let api: Api<ConfigMap> = Api::namespaced(client, &config.namespace);
+    let setup_error = |e: kube::Error| Error::Internal(format!("stress setup failed: {e}"));
+
+    for index in 0..config.objects {
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some(object_name(index));
+        cm.data = Some([("counter".to_string(), "0".to_string())].into());
+        api.create(&PostParams::default(), &cm)
+            .await
+            .map_err(setup_error)?;
+    }
+
+    let tasks: Vec<_> = (0..config.workers)
+        .map(|worker| {
+            let api = api.clone();
+            let config = config.clone();
+            tokio::spawn(async move { run_worker(api, &config, worker).await })
+        })
+        .collect();
+
+    let mut logs = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        logs.push(
+            task.await
+                .map_err(|e| Error::Internal(format!("stress worker panicked: {e}")))?,
+        );
+    }
+
+    let mut report = StressReport::default();
+    let mut replaces = vec![0; config.objects];
+    let mut patches = vec![0; config.objects];
+    let mut max_rv = vec![0; config.objects];
+    let mut seen = HashSet::new();
+    for log in logs {
+        report.conflicts += log.conflicts;
+        report.violations.extend(log.violations);
+        for (object, count) in log.replaces {
+            replaces[object] += count;
+        }
+        for (object, count) in log.patches {
+            patches[object] += count;
+        }
+        for write in log.writes {
+            if write.resource_version <= write.based_on {
+                report.violations.push(format!(
+                    "{}: write returned resourceVersion {} not newer than {}",
+                    object_name(write.object),
+                    write.resource_version,
+                    write.based_on
+                ));
+            }
+            if !seen.insert(write.resource_version) {
+                report.violations.push(format!(
+                    "resourceVersion {} returned by more than one write",
+                    write.resource_version
+                ));
+            }
+            max_rv[write.object] = max_rv[write.object].max(write.resource_version);
+        }
+    }
+    report.replaces = replaces.iter().sum();
+    report.patches = patches.iter().sum();
+
+    for index in 0..config.objects {
+        let name = object_name(index);
+        let cm = api.get(&name).await.map_err(setup_error)?;
+
+        let counter = cm
+            .data
+            .as_ref()
+            .and_then(|d| d.get("counter"))
+            .and_then(|c| c.parse::<usize>().ok())
+            .unwrap_or_default();
+        if counter != replaces[index] {
+            report.violations.push(format!(
+                "{name}: counter is {counter} after {} successful replaces (lost updates)",
+                replaces[index]
+            ));
+        }
+
+        let annotations = cm
+            .metadata
+            .annotations
+            .as_ref()
+            .map(|a| {
+                a.keys()
+                    .filter(|k| k.starts_with(ANNOTATION_PREFIX))
+                    .count()
+            })
+            .unwrap_or_default();
+        if annotations != patches[index] {
+            report.violations.push(format!(
+                "{name}: {annotations} annotations after {} successful patches (lost updates)",
+                patches[index]
+            ));
+        }
+
+        let expected_generation = 1 + replaces[index] + patches[index];
+        let generation = cm.metadata.generation.unwrap_or_default();
+        if generation != expected_generation as i64 {
+            report.violations.push(format!(
+                "{name}: generation is {generation}, expected {expected_generation}"
+            ));
+        }
+
+        let final_rv = resource_version(&cm);
+        if max_rv[index] != 0 && final_rv != max_rv[index] {
+            report.violations.push(format!(
+                "{name}: final resourceVersion {final_rv} is not the latest write {}",
+                max_rv[index]
+            ));
+        }
+    }
+
+    Ok(report)
+}
+
+async fn run_worker(api: Api<ConfigMap>, config: &StressConfig, worker: usize) -> WorkerLog {
+    let mut log = WorkerLog::default();
+    if config.objects == 0 {
+        return log;
+    }
+
+    for op in 0..config.operations_per_worker {
+        let object = (worker + op) % config.objects;
+        let name = object_name(object);
+
+        if op % 2 == 0 {
+            // Read-modify-write, retried until it wins
+            loop {
+                let mut cm = match api.get(&name).await {
+                    Ok(cm) => cm,
+                    Err(e) => {
+                        log.violations.push(format!("{name}: get failed: {e}"));
+                        break;
+                    }
+                };
+                let based_on = resource_version(&cm);
+                let data = cm.data.get_or_insert_with(Default::default);
+                let counter: usize = data
+                    .get("counter")
+                    .and_then(|c| c.parse().ok())
+                    .unwrap_or_default();
+                data.insert("counter".to_string(), (counter + 1).to_string());
+
+                match api.replace(&name, &PostParams::default(), &cm).await {
+                    Ok(updated) => {
+                        *log.replaces.entry(object).or_default() += 1;
+                        log.writes.push(Write {
+                            object,
+                            based_on,
+                            resource_version: resource_version(&updated),
+                        });
+                        break;
+                    }
+                    Err(kube::Error::Api(resp)) if resp.code == 409 => log.conflicts += 1,
+                    Err(e) => {
+                        log.violations.push(format!("{name}: replace failed: {e}"));
+                        break;
+                    }
+                }
+            }
+        } else {
+            // Unconditional patch, which the server must never reject with a conflict
+            let key = format!("{ANNOTATION_PREFIX}w{worker}-{op}");
+            let patch = json!({ "metadata": { "annotations": { key: "1" } } });
+            match api
+                .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+                .await
+            {
+                Ok(updated) => {
+                    *log.patches.entry(object).or_default() += 1;
+                    log.writes.push(Write {
+                        object,
+                        based_on: 0,
+                        resource_version: resource_version(&updated),
+                    });
+                }
+                Err(e) => log.violations.push(format!("{name}: patch failed: {e}")),
+            }
+        }
+    }
+    log
+}
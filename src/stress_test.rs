@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use crate::stress::{self, StressConfig};
+    use crate::ClientBuilder;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_writes_are_linearizable() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let config = StressConfig::default();
+
+        let report = stress::run(client, &config).await.unwrap();
+        report.assert_linearizable();
+
+        let total = config.workers * config.operations_per_worker;
+        assert_eq!(report.replaces + report.patches, total);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_single_object_contention() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let config = StressConfig {
+            namespace: "stress".to_string(),
+            objects: 1,
+            workers: 16,
+            operations_per_worker: 20,
+        };
+
+        let report = stress::run(client, &config).await.unwrap();
+        report.assert_linearizable();
+        assert_eq!(
+            report.patches,
+            config.workers * config.operations_per_worker / 2
+        );
+    }
+}
@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod tests {
+    use crate::{interceptor, ClientBuilder, Error};
+    use kube::api::{Api, Patch, PatchParams, PostParams};
+    use kube::CustomResource;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+
+    #[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+    #[kube(
+        group = "example.com",
+        version = "v1",
+        kind = "Release",
+        plural = "releases",
+        namespaced
+    )]
+    struct ReleaseSpec {
+        version: String,
+        #[serde(default)]
+        approved: bool,
+    }
+
+    fn release() -> Release {
+        Release::new(
+            "web",
+            ReleaseSpec {
+                version: "1.2.0".to_string(),
+                approved: false,
+            },
+        )
+    }
+
+    async fn releases(funcs: Option<interceptor::Funcs>) -> Api<Release> {
+        let mut builder = ClientBuilder::new()
+            .with_resource::<Release>()
+            .with_subresource::<Release>("approve");
+        if let Some(funcs) = funcs {
+            builder = builder.with_interceptor_funcs(funcs);
+        }
+        let api = Api::default_namespaced(builder.build().await.unwrap());
+        api.create(&PostParams::default(), &release())
+            .await
+            .unwrap();
+        api
+    }
+
+    fn error_code(err: kube::Error) -> u16 {
+        match err {
+            kube::Error::Api(response) => response.code,
+            other => panic!("expected an API error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_declared_subresource_without_handler() {
+        let api = releases(None).await;
+
+        let fetched = api.get_subresource("approve", "web").await.unwrap();
+        assert_eq!(fetched.spec.version, "1.2.0");
+
+        let err = api
+            .create_subresource::<Release>("approve", "web", &PostParams::default(), b"{}".to_vec())
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 405);
+    }
+
+    #[tokio::test]
+    async fn test_subresource_handler() {
+        let funcs = interceptor::Funcs::new().subresource("approve", |ctx| {
+            assert_eq!(ctx.resource, "releases");
+            assert_eq!(ctx.namespace, "default");
+            assert_eq!(ctx.name, "web");
+            let mut release = ctx.object.clone();
+            release["spec"]["approved"] = json!(true);
+            release["metadata"]["annotations"] = json!({
+                "verb": ctx.verb,
+                "by": ctx.body.and_then(|body| body["by"].as_str()).unwrap_or_default(),
+            });
+            Ok(Some(release))
+        });
+        let api = releases(Some(funcs)).await;
+
+        let body = serde_json::to_vec(&json!({ "by": "alice" })).unwrap();
+        let approved: Release = api
+            .create_subresource("approve", "web", &PostParams::default(), body.clone())
+            .await
+            .unwrap();
+        assert!(approved.spec.approved);
+        let annotations = approved.metadata.annotations.unwrap();
+        assert_eq!(annotations["verb"], "create");
+        assert_eq!(annotations["by"], "alice");
+
+        let replaced = api
+            .replace_subresource("approve", "web", &PostParams::default(), body)
+            .await
+            .unwrap();
+        assert_eq!(replaced.metadata.annotations.unwrap()["verb"], "update");
+
+        let patched = api
+            .patch_subresource(
+                "approve",
+                "web",
+                &PatchParams::default(),
+                &Patch::Merge(json!({ "by": "bob" })),
+            )
+            .await
+            .unwrap();
+        let annotations = patched.metadata.annotations.unwrap();
+        assert_eq!(annotations["verb"], "patch");
+        assert_eq!(annotations["by"], "bob");
+
+        let fetched = api.get_subresource("approve", "web").await.unwrap();
+        assert_eq!(fetched.metadata.annotations.unwrap()["verb"], "get");
+
+        // The handler's response is not stored
+        assert!(!api.get("web").await.unwrap().spec.approved);
+    }
+
+    #[tokio::test]
+    async fn test_subresource_handler_errors_and_fallthrough() {
+        let funcs = interceptor::Funcs::new().subresource("approve", |ctx| match ctx.verb {
+            "create" => Err(Error::Forbidden("releases are frozen".to_string())),
+            _ => Ok(None),
+        });
+        let api = releases(Some(funcs)).await;
+
+        let err = api
+            .create_subresource::<Value>("approve", "web", &PostParams::default(), b"{}".to_vec())
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 403);
+
+        // Returning None falls through to the default behavior
+        assert_eq!(
+            api.get_subresource("approve", "web")
+                .await
+                .unwrap()
+                .spec
+                .version,
+            "1.2.0"
+        );
+        let err = api
+            .replace_subresource("approve", "web", &PostParams::default(), b"{}".to_vec())
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 405);
+    }
+
+    #[tokio::test]
+    async fn test_subresource_of_missing_object() {
+        let api = releases(None).await;
+        let err = api.get_subresource("approve", "missing").await.unwrap_err();
+        assert_eq!(error_code(err), 404);
+    }
+
+    #[tokio::test]
+    async fn test_declaring_registers_the_resource() {
+        let client = ClientBuilder::new()
+            .with_subresource::<Release>("approve")
+            .with_subresource::<Release>("status")
+            .build()
+            .await
+            .unwrap();
+        let api: Api<Release> = Api::default_namespaced(client);
+        api.create(&PostParams::default(), &release())
+            .await
+            .unwrap();
+        api.get_subresource("approve", "web").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_status_writes_to_kinds_without_status_are_not_found() {
+        use k8s_openapi::api::core::v1::ConfigMap;
+
+        let client = ClientBuilder::new()
+            .with_resource::<Release>()
+            .build()
+            .await
+            .unwrap();
+        let releases: Api<Release> = Api::default_namespaced(client.clone());
+        releases
+            .create(&PostParams::default(), &release())
+            .await
+            .unwrap();
+        let patch = json!({ "status": { "phase": "Released" } });
+        let err = releases
+            .patch_status("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 404);
+        let err = releases
+            .patch_scale("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 404);
+
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        let mut configmap = ConfigMap::default();
+        configmap.metadata.name = Some("settings".to_string());
+        configmaps
+            .create(&PostParams::default(), &configmap)
+            .await
+            .unwrap();
+        let err = configmaps
+            .replace_status(
+                "settings",
+                &PostParams::default(),
+                serde_json::to_vec(&configmap).unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 404);
+
+        // With the subresource declared, the same write goes through
+        let client = ClientBuilder::new()
+            .with_subresource::<Release>("status")
+            .build()
+            .await
+            .unwrap();
+        let releases: Api<Release> = Api::default_namespaced(client);
+        releases
+            .create(&PostParams::default(), &release())
+            .await
+            .unwrap();
+        releases
+            .patch_status("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+//! Defaults shared by every client a test binary builds
+//!
+//! Suites with hundreds of tests often want the same invariants everywhere,
+//! such as auditing every delete or forbidding writes to cluster-scoped
+//! resources. A [`TestEnv`] installed once per test binary adds its
+//! interceptors and warning rules to every [`ClientBuilder`](crate::ClientBuilder)
+//! created afterwards, in front of the ones each test adds itself.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::{interceptor, ClientBuilder, Error, TestEnv};
+//!
+//! fn install_test_env() {
+//!     TestEnv::builder()
+//!         .with_interceptor_funcs(interceptor::Funcs::new().create(|ctx| {
+//!             if ctx.namespace.is_empty() {
+//!                 return Err(Error::Forbidden("tests may not create cluster-scoped objects".into()));
+//!             }
+//!             Ok(None)
+//!         }))
+//!         .install();
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // At the start of each test; only the first call installs the environment
+//! install_test_env();
+//! let client = ClientBuilder::new().build().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::interceptor::{self, RequestContext};
+use crate::warning::WarningRule;
+use once_cell::sync::OnceCell;
+use serde_json::Value;
+use std::sync::Arc;
+
+static INSTALLED: OnceCell<TestEnv> = OnceCell::new();
+
+/// Interceptors and warning rules every client starts with
+#[derive(Clone, Default)]
+pub struct TestEnv {
+    pub(crate) interceptors: Option<interceptor::Funcs>,
+    pub(crate) warning_rules: Vec<WarningRule>,
+}
+
+impl TestEnv {
+    /// Start describing an environment
+    pub fn builder() -> TestEnvBuilder {
+        TestEnvBuilder::default()
+    }
+
+    /// The environment installed for this test binary, if any
+    pub fn installed() -> Option<&'static TestEnv> {
+        INSTALLED.get()
+    }
+
+    /// Interceptors of the environment, followed by a client's own
+    pub(crate) fn interceptors(
+        &self,
+        own: Option<interceptor::Funcs>,
+    ) -> Option<interceptor::Funcs> {
+        match (self.interceptors.clone(), own) {
+            (Some(env), Some(own)) => Some(env.merge(own)),
+            (env, own) => env.or(own),
+        }
+    }
+
+    /// Warning rules of the environment, followed by a client's own
+    pub(crate) fn warning_rules(&self, own: Vec<WarningRule>) -> Vec<WarningRule> {
+        self.warning_rules.iter().cloned().chain(own).collect()
+    }
+}
+
+/// Builder of a [`TestEnv`]
+#[derive(Default)]
+pub struct TestEnvBuilder {
+    env: TestEnv,
+}
+
+impl TestEnvBuilder {
+    /// Add interceptors run before those of each client
+    ///
+    /// Calling this more than once chains the sets in order, as
+    /// [`Funcs::merge`](interceptor::Funcs::merge) does.
+    pub fn with_interceptor_funcs(mut self, interceptors: interceptor::Funcs) -> Self {
+        self.env.interceptors = Some(match self.env.interceptors.take() {
+            Some(existing) => existing.merge(interceptors),
+            None => interceptors,
+        });
+        self
+    }
+
+    /// Add a warning rule run before those of each client
+    ///
+    /// See [`ClientBuilder::with_warning_rule`](crate::ClientBuilder::with_warning_rule).
+    pub fn with_warning_rule(
+        mut self,
+        rule: impl Fn(&RequestContext, Option<&Value>) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.env.warning_rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Finish the environment without installing it, for
+    /// [`ClientBuilder::with_test_env`](crate::ClientBuilder::with_test_env)
+    pub fn build(self) -> TestEnv {
+        self.env
+    }
+
+    /// Install the environment for every client built from now on
+    ///
+    /// Only the first environment installed in a test binary takes effect, so
+    /// every test can call this on startup. Returns whether this call
+    /// installed it.
+    pub fn install(self) -> bool {
+        INSTALLED.set(self.env).is_ok()
+    }
+}
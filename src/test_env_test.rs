@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap;
+    use crate::{interceptor, ClientBuilder, Error, TestEnv};
+    use k8s_openapi::api::core::v1::{ConfigMap, Node};
+    use kube::api::{Api, DeleteParams, PostParams};
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn no_cluster_scoped_creates() -> TestEnv {
+        TestEnv::builder()
+            .with_interceptor_funcs(interceptor::Funcs::new().create(|ctx| {
+                if ctx.namespace.is_empty() {
+                    return Err(Error::Forbidden("cluster-scoped create".to_string()));
+                }
+                Ok(None)
+            }))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_env_interceptors_run_before_the_clients() {
+        let created: Arc<Mutex<Vec<String>>> = Arc::default();
+        let recorded = Arc::clone(&created);
+        let client = ClientBuilder::new()
+            .with_test_env(no_cluster_scoped_creates())
+            .with_interceptor_funcs(interceptor::Funcs::new().create(move |ctx| {
+                recorded
+                    .lock()
+                    .push(ctx.object["metadata"]["name"].to_string());
+                Ok(None)
+            }))
+            .build()
+            .await
+            .unwrap();
+
+        let mut node = Node::default();
+        node.metadata.name = Some("worker".to_string());
+        let err = Api::<Node>::all(client.clone())
+            .create(&PostParams::default(), &node)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, kube::Error::Api(ref e) if e.code == 403),
+            "{err:?}"
+        );
+
+        Api::<ConfigMap>::default_namespaced(client)
+            .create(&PostParams::default(), &configmap("settings"))
+            .await
+            .unwrap();
+        // The environment stopped the node before the client's interceptor saw it
+        assert_eq!(*created.lock(), ["\"settings\""]);
+    }
+
+    #[tokio::test]
+    async fn test_merged_after_hooks_all_run() {
+        let env = TestEnv::builder()
+            .with_interceptor_funcs(interceptor::Funcs::new().after_delete(|ctx| {
+                ctx.response["metadata"]["annotations"] = json!({"audited": "env"});
+                Ok(())
+            }))
+            .build();
+        let client = ClientBuilder::new()
+            .with_object(configmap("settings"))
+            .with_test_env(env)
+            .with_interceptor_funcs(interceptor::Funcs::new().after_delete(|ctx| {
+                ctx.response["metadata"]["annotations"]["seen"] = json!("client");
+                Ok(())
+            }))
+            .build()
+            .await
+            .unwrap();
+
+        let deleted = Api::<ConfigMap>::default_namespaced(client)
+            .delete("settings", &DeleteParams::default())
+            .await
+            .unwrap()
+            .left()
+            .unwrap();
+        let annotations = deleted.metadata.annotations.unwrap();
+        assert_eq!(annotations["audited"], "env");
+        assert_eq!(annotations["seen"], "client");
+    }
+
+    #[tokio::test]
+    async fn test_installed_env_applies_to_new_builders() {
+        // Only fires for tagged requests, so other tests sharing the binary are unaffected
+        TestEnv::builder()
+            .with_warning_rule(|request, _| {
+                request
+                    .tags
+                    .iter()
+                    .any(|tag| tag == "test-env")
+                    .then(|| "from the test environment".to_string())
+            })
+            .install();
+        assert!(TestEnv::installed().is_some());
+
+        for (builder, expected) in [
+            (
+                ClientBuilder::new(),
+                vec!["from the test environment".to_string()],
+            ),
+            (ClientBuilder::new().without_test_env(), vec![]),
+        ] {
+            let (client, handle) = builder.build_with_handle().await.unwrap();
+            let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+            handle
+                .with_request_tag("test-env", configmaps.get_opt("settings"))
+                .await
+                .unwrap();
+            assert_eq!(handle.audit_entries()[0].warnings, expected);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Objects the unit tests write, shared between test modules
+
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::Resource;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// A ConfigMap with only a name
+pub(crate) fn configmap(name: &str) -> ConfigMap {
+    let mut cm = ConfigMap::default();
+    cm.metadata.name = Some(name.to_string());
+    cm
+}
+
+/// A ConfigMap holding one data entry
+pub(crate) fn configmap_with(name: &str, key: &str, value: &str) -> ConfigMap {
+    let mut cm = configmap(name);
+    cm.data = Some(BTreeMap::from([(key.to_string(), value.to_string())]));
+    cm
+}
+
+/// A ConfigMap with the given top-level fields, such as `data` or `binaryData`
+pub(crate) fn configmap_from(name: &str, fields: Value) -> ConfigMap {
+    serde_json::from_value(object("v1", "ConfigMap", name, fields)).unwrap()
+}
+
+/// A Secret with the given top-level fields, such as `data` or `stringData`
+pub(crate) fn secret_from(name: &str, fields: Value) -> Secret {
+    serde_json::from_value(object("v1", "Secret", name, fields)).unwrap()
+}
+
+/// An object placed in a namespace
+pub(crate) fn in_namespace<K: Resource>(mut object: K, namespace: &str) -> K {
+    object.meta_mut().namespace = Some(namespace.to_string());
+    object
+}
+
+/// An object with one more label
+pub(crate) fn labeled<K: Resource>(mut object: K, key: &str, value: &str) -> K {
+    object
+        .meta_mut()
+        .labels
+        .get_or_insert_with(BTreeMap::new)
+        .insert(key.to_string(), value.to_string());
+    object
+}
+
+fn object(api_version: &str, kind: &str, name: &str, fields: Value) -> Value {
+    let mut value = json!({ "apiVersion": api_version, "kind": kind });
+    value
+        .as_object_mut()
+        .unwrap()
+        .extend(fields.as_object().cloned().unwrap_or_default());
+    value["metadata"]["name"] = json!(name);
+    value
+}
@@ -0,0 +1,507 @@
+//! Test harness helpers for controllers
+//!
+//! [`assert_idempotent`] checks the most common operator invariant: once a
+//! reconcile has brought the cluster to the desired state, reconciling again
+//! changes nothing. [`assert_owned_by`] checks that created objects are wired to
+//! their owner for garbage collection, and [`assert_condition`] checks the status
+//! conditions a reconcile reports, and [`assert_observed_generation_current`]
+//! checks that its status reflects the latest spec. [`is_pod_ready`] and
+//! [`is_deployment_ready`] are predicates for
+//! [`ClientHandle::wait_for`](crate::ClientHandle::wait_for).
+//! [`assert_objects_eq`] compares objects without the noise of server-managed
+//! fields. [`ControllerHarness`] runs a reconciler against the fake
+//! client's watch streams, like `kube_runtime::Controller`, so tests don't have to
+//! build that plumbing themselves.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use kube_fake_client::{testing, ClientBuilder};
+//! use k8s_openapi::api::core::v1::ConfigMap;
+//! use kube::api::{Api, PostParams};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+//! let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+//!
+//! let reconcile = || async {
+//!     if configmaps.get_opt("settings").await?.is_none() {
+//!         let mut settings = ConfigMap::default();
+//!         settings.metadata.name = Some("settings".to_string());
+//!         configmaps.create(&PostParams::default(), &settings).await?;
+//!     }
+//!     Ok::<_, kube::Error>(())
+//! };
+//! testing::assert_idempotent(reconcile, &handle).await;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::budget::{self, Verb};
+use crate::generation;
+use crate::tracker::GVK;
+use crate::{normalize_object, owners, ClientHandle};
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::Pod;
+use kube::core::GroupVersionKind;
+use kube::Resource;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::future::Future;
+
+#[cfg(feature = "tokio-runtime")]
+pub use crate::harness::{ControllerHarness, ReconcileResult};
+
+/// Metadata fields set by the API server, ignored by [`assert_objects_eq`]
+pub(crate) const SERVER_MANAGED_FIELDS: [&str; 5] = [
+    "resourceVersion",
+    "uid",
+    "creationTimestamp",
+    "generation",
+    "managedFields",
+];
+
+const WRITE_VERBS: [Verb; 5] = [
+    Verb::Create,
+    Verb::Update,
+    Verb::Patch,
+    Verb::Delete,
+    Verb::DeleteCollection,
+];
+
+/// Assert that a reconcile is idempotent
+///
+/// Runs `reconcile` once to bring the cluster to its desired state, snapshots the
+/// cluster, then runs it again and asserts the second pass made no write requests
+/// at all, whether or not they would have changed anything. Server-side apply
+/// patches count as writes, since they cost a round trip and a resource version
+/// check even when nothing changes.
+///
+/// # Panics
+///
+/// Panics if either pass fails, or if the second pass made write requests. The
+/// message lists the requests and how they changed the cluster.
+pub async fn assert_idempotent<F, Fut, T, E>(mut reconcile: F, handle: &ClientHandle)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Debug,
+{
+    if let Err(e) = reconcile().await {
+        panic!("first reconcile failed: {e:?}");
+    }
+
+    let audit = &handle.client.audit;
+    let settled = handle.snapshot();
+    let start = audit.len();
+    if let Err(e) = reconcile().await {
+        panic!("second reconcile failed: {e:?}");
+    }
+
+    let entries = audit.entries_since(start);
+    let writes: Vec<_> = entries
+        .iter()
+        .filter(|e| WRITE_VERBS.iter().any(|verb| e.verb == verb.as_str()))
+        .collect();
+    if writes.is_empty() {
+        return;
+    }
+
+    let changes = settled.changes(&handle.snapshot());
+    let summary = if changes.is_empty() {
+        format!(
+            "reconcile is not idempotent: the second pass made {} write request(s), \
+             changing nothing",
+            writes.len()
+        )
+    } else {
+        format!(
+            "reconcile is not idempotent: the second pass made {} write request(s), \
+             changing:\n  {}\nrequests",
+            writes.len(),
+            changes.join("\n  ")
+        )
+    };
+    budget::fail(&summary, &writes);
+}
+
+/// Assert that an object has an owner reference to another
+///
+/// Looks up the dependent of type `D` and the owner of type `O` in `namespace`
+/// (ignored for cluster-scoped types) and asserts that one of the dependent's
+/// `metadata.ownerReferences` names the owner's apiVersion, kind, name and uid,
+/// as garbage collection requires.
+///
+/// # Panics
+///
+/// Panics if either object does not exist or if the dependent has no reference
+/// to the owner. The message lists the references it does have.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::{testing, ClientBuilder};
+/// use k8s_openapi::api::apps::v1::Deployment;
+/// use k8s_openapi::api::core::v1::Service;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+///
+/// // ... reconcile the `web` Deployment with `client` ...
+///
+/// testing::assert_owned_by::<Service, Deployment>(&handle, "default", "web", "web");
+/// # Ok(())
+/// # }
+/// ```
+pub fn assert_owned_by<D, O>(handle: &ClientHandle, namespace: &str, dependent: &str, owner: &str)
+where
+    D: Resource<DynamicType = ()>,
+    O: Resource<DynamicType = ()>,
+{
+    let describe = |kind: std::borrow::Cow<'_, str>, name: &str| match namespace {
+        "" => format!("{kind} {name}"),
+        namespace => format!("{kind} {namespace}/{name}"),
+    };
+    let dependent_object = owners::get::<D>(&handle.client, namespace, dependent)
+        .unwrap_or_else(|e| panic!("{} not found: {e}", describe(D::kind(&()), dependent)));
+    let owner_object = owners::get::<O>(&handle.client, namespace, owner)
+        .unwrap_or_else(|e| panic!("{} not found: {e}", describe(O::kind(&()), owner)));
+
+    let references = owners::references(&dependent_object);
+    if references
+        .iter()
+        .any(|reference| owners::points_to(reference, &owner_object))
+    {
+        return;
+    }
+    let found = if references.is_empty() {
+        "it has no owner references".to_string()
+    } else {
+        let references: Vec<String> = references
+            .iter()
+            .map(|reference| {
+                format!(
+                    "{} {} {} (uid {})",
+                    reference["apiVersion"].as_str().unwrap_or_default(),
+                    reference["kind"].as_str().unwrap_or_default(),
+                    reference["name"].as_str().unwrap_or_default(),
+                    reference["uid"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect();
+        format!("its owner references are:\n  {}", references.join("\n  "))
+    };
+    panic!(
+        "{} is not owned by {} (uid {}); {found}",
+        describe(D::kind(&()), dependent),
+        describe(O::kind(&()), owner),
+        owner_object
+            .pointer("/metadata/uid")
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+    );
+}
+
+/// A status condition, read from any of the shapes conditions come in
+struct Condition {
+    condition_type: String,
+    status: String,
+    reason: Option<String>,
+    message: Option<String>,
+}
+
+impl Condition {
+    /// Read a condition, either a `metav1.Condition` or one of the legacy
+    /// per-resource condition types, which share `type` and `status`
+    fn from_value(condition_type: Option<&str>, value: &Value) -> Option<Self> {
+        let text = |field: &str| value.get(field).and_then(Value::as_str).map(str::to_string);
+        let condition_type = condition_type
+            .map(str::to_string)
+            .or_else(|| text("type"))?;
+        // Some custom resources use booleans, or map condition types straight to
+        // their status
+        let status = match value.get("status").unwrap_or(value) {
+            Value::String(status) => status.clone(),
+            Value::Bool(true) => "True".to_string(),
+            Value::Bool(false) => "False".to_string(),
+            _ => "Unknown".to_string(),
+        };
+        Some(Self {
+            condition_type,
+            status,
+            reason: text("reason"),
+            message: text("message"),
+        })
+    }
+}
+
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.condition_type, self.status)?;
+        let details: Vec<String> = [("reason", &self.reason), ("message", &self.message)]
+            .into_iter()
+            .filter_map(|(label, value)| Some(format!("{label}: {}", value.as_ref()?)))
+            .collect();
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Conditions under `status.conditions`, as a list or keyed by type
+fn conditions(object: &Value) -> Vec<Condition> {
+    match object.pointer("/status/conditions") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| Condition::from_value(None, item))
+            .collect(),
+        Some(Value::Object(by_type)) => by_type
+            .iter()
+            .filter_map(|(condition_type, item)| Condition::from_value(Some(condition_type), item))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Assert that an object has a status condition with a given status
+///
+/// Looks up the object of kind `gvk` in `namespace` (ignored for cluster-scoped
+/// kinds) and finds the condition of type `condition_type` in
+/// `status.conditions`. Both `metav1.Condition` and the legacy per-resource
+/// condition types are understood, as are custom resources with boolean
+/// statuses or conditions keyed by type. Statuses are compared ignoring case, so
+/// `"True"` matches `true`.
+///
+/// # Panics
+///
+/// Panics if the object does not exist, has no condition of the type, or the
+/// condition has another status. The message shows the conditions found, with
+/// their reasons and messages.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::{testing, ClientBuilder};
+/// use kube::core::GroupVersionKind;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+///
+/// // ... reconcile the `web` Deployment with `client` ...
+///
+/// let deployment = GroupVersionKind::gvk("apps", "v1", "Deployment");
+/// testing::assert_condition(&handle, &deployment, "default", "web", "Available", "True");
+/// # Ok(())
+/// # }
+/// ```
+pub fn assert_condition(
+    handle: &ClientHandle,
+    gvk: &GroupVersionKind,
+    namespace: &str,
+    name: &str,
+    condition_type: &str,
+    status: &str,
+) {
+    let object_name = match namespace {
+        "" => format!("{} {name}", gvk.kind),
+        namespace => format!("{} {namespace}/{name}", gvk.kind),
+    };
+    let object = handle
+        .client
+        .get_by_kind(&GVK::from(gvk), namespace, name)
+        .unwrap_or_else(|e| panic!("{object_name} not found: {e}"));
+
+    let conditions = conditions(&object);
+    match conditions
+        .iter()
+        .find(|condition| condition.condition_type == condition_type)
+    {
+        Some(condition) if condition.status.eq_ignore_ascii_case(status) => {}
+        Some(condition) => {
+            panic!("{object_name} has condition {condition}, expected {condition_type}={status}")
+        }
+        None if conditions.is_empty() => panic!(
+            "{object_name} has no {condition_type} condition, expected {condition_type}={status}; \
+             it has no conditions"
+        ),
+        None => {
+            let found: Vec<String> = conditions.iter().map(ToString::to_string).collect();
+            panic!(
+                "{object_name} has no {condition_type} condition, expected {condition_type}={status}; \
+                 its conditions are:\n  {}",
+                found.join("\n  ")
+            )
+        }
+    }
+}
+
+/// Whether a Pod is ready, with its `Ready` condition true
+///
+/// The predicate for waiting on Pods with
+/// [`ClientHandle::wait_for`](crate::ClientHandle::wait_for); a simulated
+/// kubelet sets it with
+/// [`ClientHandle::set_pod_ready`](crate::ClientHandle::set_pod_ready).
+pub fn is_pod_ready(pod: &Pod) -> bool {
+    pod.status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .is_some_and(|conditions| {
+            conditions
+                .iter()
+                .any(|c| c.type_ == "Ready" && c.status == "True")
+        })
+}
+
+/// Whether a Deployment has finished rolling out, as `kubectl rollout status`
+/// decides it
+///
+/// The status has to reflect the latest spec, with every desired replica
+/// updated and available and no old replicas left.
+pub fn is_deployment_ready(deployment: &Deployment) -> bool {
+    let Some(status) = &deployment.status else {
+        return false;
+    };
+    let desired = deployment
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.replicas)
+        .unwrap_or(1);
+    status.observed_generation >= deployment.metadata.generation
+        && status.updated_replicas.unwrap_or_default() == desired
+        && status.replicas.unwrap_or_default() == desired
+        && status.available_replicas.unwrap_or_default() == desired
+}
+
+/// Assert that an object's status reflects its latest spec
+///
+/// Compares `status.observedGeneration` of the `K` named `name` in `namespace`
+/// (ignored for cluster-scoped kinds) with its `metadata.generation`, which the
+/// fake bumps on every spec change, the way operators report having reconciled
+/// the latest change.
+///
+/// # Panics
+///
+/// Panics if the object does not exist, has no `status.observedGeneration`, or
+/// its observed generation is not the current one.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::{testing, ClientBuilder};
+/// use k8s_openapi::api::apps::v1::Deployment;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+///
+/// // ... reconcile the `web` Deployment with `client` ...
+///
+/// testing::assert_observed_generation_current::<Deployment>(&handle, "default", "web");
+/// # Ok(())
+/// # }
+/// ```
+pub fn assert_observed_generation_current<K>(handle: &ClientHandle, namespace: &str, name: &str)
+where
+    K: Resource<DynamicType = ()>,
+{
+    let object_name = match namespace {
+        "" => format!("{} {name}", K::kind(&())),
+        namespace => format!("{} {namespace}/{name}", K::kind(&())),
+    };
+    let object = owners::get::<K>(&handle.client, namespace, name)
+        .unwrap_or_else(|e| panic!("{object_name} not found: {e}"));
+
+    let generation = object
+        .pointer("/metadata/generation")
+        .and_then(Value::as_i64)
+        .unwrap_or_else(|| panic!("{object_name} has no metadata.generation"));
+    match generation::observed_generation(&object) {
+        Some(observed) if observed == generation => {}
+        Some(observed) => panic!(
+            "{object_name} has status.observedGeneration {observed}, expected the current \
+             metadata.generation {generation}"
+        ),
+        None => panic!(
+            "{object_name} has no status.observedGeneration, expected the current \
+             metadata.generation {generation}"
+        ),
+    }
+}
+
+/// Assert that two objects are equal, ignoring server-managed fields
+///
+/// Objects are compared as JSON, so a typed object can be compared with a
+/// `serde_json::json!` literal, after removing `metadata.resourceVersion`,
+/// `uid`, `creationTimestamp`, `generation` and `managedFields`, which differ
+/// between an object as built and as stored. Both objects are normalized with
+/// [`normalize_object`], so fields that are `null` or empty maps or lists count as
+/// absent.
+///
+/// # Panics
+///
+/// Panics if the objects differ, with a diff of the two as YAML: lines only in
+/// `expected` are marked `-` and lines only in `actual` are marked `+`. The diff
+/// is colored unless the `NO_COLOR` environment variable is set.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::{testing, ClientBuilder};
+/// use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube::api::Api;
+/// use serde_json::json;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new().build_with_handle().await?;
+/// let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+///
+/// // ... reconcile with `client` ...
+///
+/// testing::assert_objects_eq(
+///     &configmaps.get("settings").await?,
+///     &json!({
+///         "apiVersion": "v1",
+///         "kind": "ConfigMap",
+///         "metadata": { "name": "settings", "namespace": "default" },
+///         "data": { "mode": "fast" }
+///     }),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[track_caller]
+pub fn assert_objects_eq<A, E>(actual: &A, expected: &E)
+where
+    A: Serialize + ?Sized,
+    E: Serialize + ?Sized,
+{
+    let normalize = |object: Value| {
+        let mut object = object;
+        normalize_object(&mut object);
+        if let Some(metadata) = object.get_mut("metadata").and_then(Value::as_object_mut) {
+            for field in SERVER_MANAGED_FIELDS {
+                metadata.remove(field);
+            }
+        }
+        object
+    };
+    let actual = normalize(serde_json::to_value(actual).expect("actual object serializes"));
+    let expected = normalize(serde_json::to_value(expected).expect("expected object serializes"));
+    if actual == expected {
+        return;
+    }
+
+    let yaml =
+        |object: &Value| serde_yaml::to_string(object).unwrap_or_else(|_| object.to_string());
+    let color = std::env::var_os("NO_COLOR").is_none();
+    panic!(
+        "objects differ (- expected, + actual):\n{}",
+        crate::diff::lines(&yaml(&expected), &yaml(&actual), color)
+    );
+}
@@ -0,0 +1,330 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap_with;
+    use crate::testing::{assert_condition, assert_idempotent, assert_objects_eq, assert_owned_by};
+    use crate::ClientBuilder;
+    use futures::FutureExt;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{Api, ListParams, Patch, PatchParams, PostParams};
+    use serde_json::json;
+    use std::panic::AssertUnwindSafe;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_idempotent_reconcile_passes() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let reconcile = || async {
+            if configmaps.get_opt("settings").await?.is_none() {
+                configmaps
+                    .create(
+                        &PostParams::default(),
+                        &configmap_with("settings", "mode", "active"),
+                    )
+                    .await?;
+            }
+            Ok::<_, kube::Error>(())
+        };
+        assert_idempotent(reconcile, &handle).await;
+
+        assert!(configmaps.get_opt("settings").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_second_pass_write_fails_with_changes() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap_with("settings", "mode", "active"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        // Bumps a counter on every pass, so never settles
+        let passes = AtomicUsize::new(0);
+        let reconcile = || async {
+            let pass = passes.fetch_add(1, Ordering::SeqCst);
+            let patch = json!({ "data": { "pass": pass.to_string() } });
+            configmaps
+                .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+                .await
+        };
+
+        let payload = AssertUnwindSafe(assert_idempotent(reconcile, &handle))
+            .catch_unwind()
+            .await
+            .unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.contains("the second pass made 1 write request(s)"));
+        assert!(message.contains("updated v1/configmaps default/settings"));
+        assert!(message.contains("/data/pass"));
+        assert!(message.contains("PATCH /api/v1/namespaces/default/configmaps/settings"));
+    }
+
+    #[tokio::test]
+    async fn test_failing_reconcile_panics() {
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+
+        let reconcile = || async { configmaps.get("missing").await };
+        let payload = AssertUnwindSafe(assert_idempotent(reconcile, &handle))
+            .catch_unwind()
+            .await
+            .unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("first reconcile failed"));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restore() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap_with("settings", "mode", "active"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let initial = handle.snapshot();
+        assert_eq!(initial.len(), 1);
+        let version = configmaps
+            .get("settings")
+            .await
+            .unwrap()
+            .metadata
+            .resource_version
+            .unwrap();
+
+        let patch = json!({ "data": { "mode": "standby" } });
+        configmaps
+            .patch("settings", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("extra", "mode", "active"),
+            )
+            .await
+            .unwrap();
+
+        handle.restore(&initial);
+
+        let restored = configmaps.list(&ListParams::default()).await.unwrap();
+        assert_eq!(restored.items.len(), 1);
+        let settings = &restored.items[0];
+        assert_eq!(settings.data.as_ref().unwrap()["mode"], "active");
+        assert_eq!(settings.metadata.resource_version.as_ref(), Some(&version));
+
+        // Indexed label selection still works on restored objects
+        let selected = configmaps
+            .list(&ListParams::default().labels("app in (web)"))
+            .await
+            .unwrap();
+        assert!(selected.items.is_empty());
+
+        // New writes get fresh resource versions
+        let created = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("extra", "mode", "active"),
+            )
+            .await
+            .unwrap();
+        let created_version: u64 = created.metadata.resource_version.unwrap().parse().unwrap();
+        assert!(created_version > 3);
+    }
+
+    async fn owned_configmap(client: kube::Client) -> ConfigMap {
+        use k8s_openapi::api::apps::v1::Deployment;
+        use kube::Resource;
+
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), "default");
+        let mut deployment = Deployment::default();
+        deployment.metadata.name = Some("web".to_string());
+        let deployment = deployments
+            .create(&PostParams::default(), &deployment)
+            .await
+            .unwrap();
+
+        let mut cm = configmap_with("web-config", "mode", "active");
+        cm.metadata.owner_references = Some(vec![deployment.controller_owner_ref(&()).unwrap()]);
+        Api::namespaced(client, "default")
+            .create(&PostParams::default(), &cm)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_assert_owned_by_passes() {
+        use k8s_openapi::api::apps::v1::Deployment;
+
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        owned_configmap(client).await;
+
+        assert_owned_by::<ConfigMap, Deployment>(&handle, "default", "web-config", "web");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "ConfigMap default/settings is not owned by Deployment default/web")]
+    async fn test_assert_owned_by_fails_without_reference() {
+        use k8s_openapi::api::apps::v1::Deployment;
+
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        owned_configmap(client.clone()).await;
+        Api::namespaced(client, "default")
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "mode", "active"),
+            )
+            .await
+            .unwrap();
+
+        assert_owned_by::<ConfigMap, Deployment>(&handle, "default", "settings", "web");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Deployment default/api not found")]
+    async fn test_assert_owned_by_fails_for_missing_owner() {
+        use k8s_openapi::api::apps::v1::Deployment;
+
+        let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        owned_configmap(client).await;
+
+        assert_owned_by::<ConfigMap, Deployment>(&handle, "default", "web-config", "api");
+    }
+
+    async fn conditions_handle() -> crate::ClientHandle {
+        use kube::api::{ApiResource, GroupVersionKind};
+
+        let widget = GroupVersionKind::gvk("example.com", "v1", "Widget");
+        let (_client, handle) = ClientBuilder::new()
+            .with_dynamic_resource(ApiResource::from_gvk_with_plural(&widget, "widgets"))
+            .with_runtime_objects(vec![
+                json!({
+                    "apiVersion": "apps/v1",
+                    "kind": "Deployment",
+                    "metadata": { "name": "web", "namespace": "default" },
+                    "status": { "conditions": [
+                        { "type": "Available", "status": "True", "reason": "MinimumReplicasAvailable" },
+                        { "type": "Progressing", "status": "False", "reason": "ProgressDeadlineExceeded",
+                          "message": "ReplicaSet web-1 has timed out progressing." }
+                    ] }
+                }),
+                json!({
+                    "apiVersion": "v1",
+                    "kind": "Node",
+                    "metadata": { "name": "node-1" },
+                    "status": { "conditions": [
+                        { "type": "Ready", "status": "True", "lastHeartbeatTime": "2024-01-01T00:00:00Z" }
+                    ] }
+                }),
+                json!({
+                    "apiVersion": "example.com/v1",
+                    "kind": "Widget",
+                    "metadata": { "name": "gear", "namespace": "default" },
+                    "status": { "conditions": { "Ready": true, "Synced": { "status": false } } }
+                }),
+            ])
+            .build_with_handle()
+            .await
+            .unwrap();
+        handle
+    }
+
+    #[tokio::test]
+    async fn test_assert_condition_passes() {
+        use kube::api::GroupVersionKind;
+
+        let handle = conditions_handle().await;
+        let deployment = GroupVersionKind::gvk("apps", "v1", "Deployment");
+        assert_condition(&handle, &deployment, "default", "web", "Available", "True");
+        assert_condition(
+            &handle,
+            &deployment,
+            "default",
+            "web",
+            "Progressing",
+            "False",
+        );
+
+        // Cluster-scoped objects are found whatever the namespace
+        let node = GroupVersionKind::gvk("", "v1", "Node");
+        assert_condition(&handle, &node, "default", "node-1", "Ready", "True");
+
+        // Conditions keyed by type, with boolean statuses
+        let widget = GroupVersionKind::gvk("example.com", "v1", "Widget");
+        assert_condition(&handle, &widget, "default", "gear", "Ready", "True");
+        assert_condition(&handle, &widget, "default", "gear", "Synced", "false");
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Deployment default/web has condition Progressing=False (reason: ProgressDeadlineExceeded, message: ReplicaSet web-1 has timed out progressing.), expected Progressing=True"
+    )]
+    async fn test_assert_condition_fails_on_status() {
+        use kube::api::GroupVersionKind;
+
+        let handle = conditions_handle().await;
+        let deployment = GroupVersionKind::gvk("apps", "v1", "Deployment");
+        assert_condition(
+            &handle,
+            &deployment,
+            "default",
+            "web",
+            "Progressing",
+            "True",
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Deployment default/web has no ReplicaFailure condition")]
+    async fn test_assert_condition_fails_when_missing() {
+        use kube::api::GroupVersionKind;
+
+        let handle = conditions_handle().await;
+        let deployment = GroupVersionKind::gvk("apps", "v1", "Deployment");
+        assert_condition(
+            &handle,
+            &deployment,
+            "default",
+            "web",
+            "ReplicaFailure",
+            "True",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assert_objects_eq_ignores_server_managed_fields() {
+        let (client, _handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let created = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "mode", "active"),
+            )
+            .await
+            .unwrap();
+        assert!(created.metadata.resource_version.is_some());
+
+        assert_objects_eq(
+            &created,
+            &json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": { "name": "settings", "namespace": "default" },
+                "data": { "mode": "active" }
+            }),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "-   mode: passive")]
+    fn test_assert_objects_eq_shows_diff() {
+        assert_objects_eq(
+            &configmap_with("settings", "mode", "active"),
+            &json!({
+                "data": { "mode": "passive" },
+                "metadata": { "name": "settings" }
+            }),
+        );
+    }
+}
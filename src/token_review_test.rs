@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use crate::ClientBuilder;
+    use k8s_openapi::api::authentication::v1::{TokenReview, TokenReviewSpec, UserInfo};
+    use kube::api::{Api, PostParams};
+
+    fn token_review(token: &str) -> TokenReview {
+        TokenReview {
+            spec: TokenReviewSpec {
+                token: Some(token.to_string()),
+                audiences: Some(vec!["api".to_string()]),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_review_handler_authenticates_tokens() {
+        let (client, handle) = ClientBuilder::new()
+            .with_token_review_handler(|spec| {
+                (spec.token.as_deref() == Some("good")).then(|| UserInfo {
+                    username: Some("alice".to_string()),
+                    groups: Some(vec!["developers".to_string()]),
+                    ..Default::default()
+                })
+            })
+            .build_with_handle()
+            .await
+            .unwrap();
+        let reviews: Api<TokenReview> = Api::all(client);
+
+        let status = reviews
+            .create(&PostParams::default(), &token_review("good"))
+            .await
+            .unwrap()
+            .status
+            .unwrap();
+        assert_eq!(status.authenticated, Some(true));
+        assert_eq!(status.user.unwrap().username.as_deref(), Some("alice"));
+        assert_eq!(status.audiences, Some(vec!["api".to_string()]));
+
+        let status = reviews
+            .create(&PostParams::default(), &token_review("bad"))
+            .await
+            .unwrap()
+            .status
+            .unwrap();
+        assert_eq!(status.authenticated, Some(false));
+        assert!(status.error.is_some());
+
+        // Reviews are answered, not stored
+        assert!(handle.list_namespace("").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tokens_rejected_without_handler() {
+        let client = ClientBuilder::new().build().await.unwrap();
+        let reviews: Api<TokenReview> = Api::all(client);
+
+        let status = reviews
+            .create(&PostParams::default(), &token_review("anything"))
+            .await
+            .unwrap()
+            .status
+            .unwrap();
+        assert_eq!(status.authenticated, Some(false));
+    }
+}
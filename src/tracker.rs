@@ -1,24 +1,57 @@
+use crate::client::IndexerFunc;
+use crate::discovery::Discovery;
+use crate::normalize::normalized;
+use crate::shard::{IndexKey, IndexLookup};
+use crate::snapshot::Snapshot;
+use crate::stats::{approximate_size, ResourceStats, TrackerStats};
+use crate::store::{MemoryStore, ObjectStore, StoredObject};
 use crate::utils::{
     deletion_timestamp_equal, ensure_metadata, increment_generation, should_be_deleted,
 };
+use crate::watch::{EventType, WatchCache, WatchEvent};
 use crate::{Error, Result};
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::core::{ApiResource, GroupVersionKind, GroupVersionResource};
+use kube::Resource;
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, RwLock};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
 use tracing::{debug, trace};
 
+/// Group, version and plural resource name, such as `apps/v1 deployments`
+///
+/// Identifies a collection in URLs and in the object store. The core group is
+/// the empty string. Converts to and from kube's
+/// [`GroupVersionResource`](kube::core::GroupVersionResource), and from an
+/// [`ApiResource`].
+///
+/// # Example
+///
+/// ```
+/// use k8s_openapi::api::apps::v1::Deployment;
+/// use kube_fake_client::GVR;
+///
+/// let gvr = GVR::of::<Deployment>();
+/// assert_eq!(gvr, GVR::new("apps", "v1", "deployments"));
+/// assert_eq!(gvr.to_string(), "apps/v1, Resource=deployments");
+/// ```
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GVR {
+    /// API group, empty for the core group
     pub group: String,
+    /// API version, such as `v1`
     pub version: String,
+    /// Plural resource name, such as `deployments`
     pub resource: String,
 }
 
 impl GVR {
+    /// A resource from its group, version and plural name
     pub fn new(
         group: impl Into<String>,
         version: impl Into<String>,
@@ -31,7 +64,17 @@ impl GVR {
         }
     }
 
-    pub fn not_found_error(&self, namespace: &str, name: &str) -> Error {
+    /// The resource of a Rust type
+    pub fn of<K: Resource<DynamicType = ()>>() -> Self {
+        Self::new(K::group(&()), K::version(&()), K::plural(&()))
+    }
+
+    /// The `apiVersion` of objects of the resource, such as `apps/v1` or `v1`
+    pub fn api_version(&self) -> String {
+        api_version(&self.group, &self.version)
+    }
+
+    pub(crate) fn not_found_error(&self, namespace: &str, name: &str) -> Error {
         Error::NotFound {
             kind: self.resource.clone(),
             name: name.to_string(),
@@ -40,15 +83,69 @@ impl GVR {
     }
 }
 
+impl fmt::Display for GVR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, Resource={}", self.api_version(), self.resource)
+    }
+}
+
+impl From<GroupVersionResource> for GVR {
+    fn from(gvr: GroupVersionResource) -> Self {
+        Self::new(gvr.group, gvr.version, gvr.resource)
+    }
+}
+
+impl From<&GroupVersionResource> for GVR {
+    fn from(gvr: &GroupVersionResource) -> Self {
+        Self::new(&gvr.group, &gvr.version, &gvr.resource)
+    }
+}
+
+impl From<GVR> for GroupVersionResource {
+    fn from(gvr: GVR) -> Self {
+        GroupVersionResource::gvr(&gvr.group, &gvr.version, &gvr.resource)
+    }
+}
+
+impl From<&ApiResource> for GVR {
+    fn from(resource: &ApiResource) -> Self {
+        Self::new(&resource.group, &resource.version, &resource.plural)
+    }
+}
+
+/// Group, version and kind, such as `apps/v1 Deployment`
+///
+/// Identifies the type of an object, as its `apiVersion` and `kind` do. The
+/// core group is the empty string. Converts to and from kube's
+/// [`GroupVersionKind`], and from an [`ApiResource`].
+///
+/// # Example
+///
+/// ```
+/// use k8s_openapi::api::apps::v1::Deployment;
+/// use kube::core::GroupVersionKind;
+/// use kube_fake_client::GVK;
+///
+/// let gvk = GVK::of::<Deployment>();
+/// assert_eq!(gvk.api_version(), "apps/v1");
+/// assert_eq!(
+///     GroupVersionKind::from(gvk),
+///     GroupVersionKind::gvk("apps", "v1", "Deployment")
+/// );
+/// ```
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct GVK {
+    /// API group, empty for the core group
     pub group: String,
+    /// API version, such as `v1`
     pub version: String,
+    /// Kind, such as `Deployment`
     pub kind: String,
 }
 
 impl GVK {
+    /// A kind from its group, version and name
     pub fn new(
         group: impl Into<String>,
         version: impl Into<String>,
@@ -60,32 +157,359 @@ impl GVK {
             kind: kind.into(),
         }
     }
+
+    /// The kind of a Rust type
+    pub fn of<K: Resource<DynamicType = ()>>() -> Self {
+        Self::new(K::group(&()), K::version(&()), K::kind(&()))
+    }
+
+    /// The `apiVersion` of objects of the kind, such as `apps/v1` or `v1`
+    pub fn api_version(&self) -> String {
+        api_version(&self.group, &self.version)
+    }
+
+    /// An [`ApiResource`] for the kind, served under a plural resource name
+    pub fn api_resource(&self, plural: &str) -> ApiResource {
+        ApiResource::from_gvk_with_plural(&self.clone().into(), plural)
+    }
+}
+
+impl fmt::Display for GVK {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, Kind={}", self.api_version(), self.kind)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StoredObject {
-    pub data: Value,
-    pub gvk: GVK,
-    pub metadata: ObjectMeta,
+impl From<GroupVersionKind> for GVK {
+    fn from(gvk: GroupVersionKind) -> Self {
+        Self::new(gvk.group, gvk.version, gvk.kind)
+    }
 }
 
-type ObjectsByName = HashMap<String, StoredObject>;
-type ObjectsByNamespace = HashMap<String, ObjectsByName>;
-type ObjectStorage = HashMap<GVR, ObjectsByNamespace>;
+impl From<&GroupVersionKind> for GVK {
+    fn from(gvk: &GroupVersionKind) -> Self {
+        Self::new(&gvk.group, &gvk.version, &gvk.kind)
+    }
+}
 
+impl From<GVK> for GroupVersionKind {
+    fn from(gvk: GVK) -> Self {
+        GroupVersionKind::gvk(&gvk.group, &gvk.version, &gvk.kind)
+    }
+}
+
+impl From<&ApiResource> for GVK {
+    fn from(resource: &ApiResource) -> Self {
+        Self::new(&resource.group, &resource.version, &resource.kind)
+    }
+}
+
+fn api_version(group: &str, version: &str) -> String {
+    match group {
+        "" => version.to_string(),
+        group => format!("{group}/{version}"),
+    }
+}
+
+/// Order of list results
+///
+/// Lists default to the order of a real API server, which lists from etcd
+/// sorted by key: by namespace, then by name. Tests written against an earlier
+/// version of this crate that rely on objects coming back in the order they
+/// were created can opt into [`ListOrder::Insertion`] with
+/// [`ClientBuilder::with_list_order`](crate::ClientBuilder::with_list_order).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListOrder {
+    /// By namespace, then by name, like a real API server
+    #[default]
+    Name,
+    /// In the order objects were first stored; updates don't move an object
+    Insertion,
+}
+
+/// An object listed from the store: its namespace, its position in insertion
+/// order and the object
+type Listed = (String, u64, Arc<Value>);
+
+/// Lock serializing the writes to a resource in a namespace
+type CollectionLock = Arc<RwLock<()>>;
+type LockMap = HashMap<GVR, HashMap<String, CollectionLock>>;
+
+/// Callback invoked after an object of the given resource is written in a namespace
+pub(crate) type WriteObserver = Arc<dyn Fn(&ObjectTracker, &GVR, &str) + Send + Sync>;
+
+/// Called with the resource, namespace and last state of each object removed
+/// from the store by a delete
+pub(crate) type RemovalObserver = Arc<dyn Fn(&GVR, &str, &Value) + Send + Sync>;
+
+/// Objects of a fake cluster, with the API server's write semantics
+///
+/// The objects live in an [`ObjectStore`], a [`MemoryStore`] unless another is
+/// given. Writes to a resource in a namespace are serialized by a lock of their
+/// own, so they only block other writes of the same resource in the same
+/// namespace; reads go straight to the store. Locks nobody holds are dropped by
+/// [`compact`](Self::compact), and created again by the next write.
 pub struct ObjectTracker {
-    objects: Arc<RwLock<ObjectStorage>>,
+    store: Arc<dyn ObjectStore>,
+    /// The store when it is the built-in one, whose indexes narrow selector
+    /// lookups
+    memory: Option<Arc<MemoryStore>>,
+    locks: Arc<RwLock<LockMap>>,
     with_status_subresource: Arc<RwLock<std::collections::HashSet<GVK>>>,
     resource_version: Arc<AtomicU64>,
+    observers: Arc<RwLock<Vec<WriteObserver>>>,
+    removal_observers: Arc<RwLock<Vec<RemovalObserver>>>,
+    /// Number of times the objects were replaced by a snapshot
+    restores: Arc<AtomicU64>,
+    watch_cache: Arc<WatchCache>,
+    list_order: Arc<RwLock<ListOrder>>,
+    /// Approximate size of the stored objects
+    object_bytes: Arc<AtomicUsize>,
+    memory_limit: Arc<RwLock<Option<usize>>>,
 }
 
 impl ObjectTracker {
     pub fn new() -> Self {
+        let memory = Arc::new(MemoryStore::new());
         Self {
-            objects: Arc::new(RwLock::new(HashMap::new())),
+            memory: Some(Arc::clone(&memory)),
+            ..Self::with_store(memory)
+        }
+    }
+
+    /// A tracker keeping its objects in a store of the caller's
+    ///
+    /// Objects already in the store are kept, and later writes get resource
+    /// versions newer than theirs. Selector lookups match every object of the
+    /// resource, since only the built-in store has indexes.
+    pub fn with_store(store: Arc<dyn ObjectStore>) -> Self {
+        let tracker = Self {
+            store,
+            memory: None,
+            locks: Arc::new(RwLock::new(HashMap::new())),
             with_status_subresource: Arc::new(RwLock::new(std::collections::HashSet::new())),
             resource_version: Arc::new(AtomicU64::new(0)),
+            observers: Arc::new(RwLock::new(Vec::new())),
+            removal_observers: Arc::new(RwLock::new(Vec::new())),
+            restores: Arc::new(AtomicU64::new(0)),
+            watch_cache: Arc::new(WatchCache::default()),
+            list_order: Arc::new(RwLock::new(ListOrder::default())),
+            object_bytes: Arc::new(AtomicUsize::new(0)),
+            memory_limit: Arc::new(RwLock::new(None)),
+        };
+        let objects = tracker.list_all_shared();
+        tracker.object_bytes.store(
+            objects
+                .iter()
+                .map(|(_, object)| approximate_size(object))
+                .sum(),
+            Ordering::SeqCst,
+        );
+        let newest = objects
+            .iter()
+            .filter_map(|(_, object)| Self::resource_version_of(object))
+            .max();
+        if let Some(newest) = newest {
+            tracker.advance_resource_version(newest);
         }
+        tracker
+    }
+
+    /// Set the order lists return objects in
+    pub(crate) fn set_list_order(&self, order: ListOrder) {
+        *self.list_order.write() = order;
+    }
+
+    /// Fail writes that would take the stored objects and the watch history over
+    /// an approximate number of bytes
+    pub(crate) fn set_memory_limit(&self, bytes: usize) {
+        *self.memory_limit.write() = Some(bytes);
+    }
+
+    /// Make room for an object replacing another, or none, in the store
+    ///
+    /// Called with the collection lock held, right before the store is written.
+    /// Fails the way the API server does once etcd is out of space, when the
+    /// write would go over the memory limit.
+    fn reserve(&self, gvr: &GVR, replaced: Option<&Value>, object: &Value) -> Result<()> {
+        let replaced = replaced.map_or(0, approximate_size);
+        let size = approximate_size(object);
+        if let Some(limit) = *self.memory_limit.read() {
+            let (_, watch_bytes) = self.watch_cache.history_size();
+            // The write's watch event holds a copy of the object as well
+            let held = (self.object_bytes.load(Ordering::SeqCst) + watch_bytes)
+                .saturating_sub(replaced)
+                + 2 * size;
+            if held > limit {
+                return Err(Error::Internal(format!(
+                    "etcdserver: mvcc: database space exceeded: writing {} would take the \
+                     tracker to about {held} bytes, over its limit of {limit}; call \
+                     ClientHandle::compact() or raise the limit",
+                    gvr.resource
+                )));
+            }
+        }
+        self.release(replaced);
+        self.object_bytes.fetch_add(size, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Account for an object replacing another in the store, whatever the limit
+    ///
+    /// For the writes of a deletion, which must go through to free space.
+    fn account(&self, replaced: &Value, object: &Value) {
+        self.release(approximate_size(replaced));
+        self.object_bytes
+            .fetch_add(approximate_size(object), Ordering::SeqCst);
+    }
+
+    /// Account for stored objects that are gone
+    fn release(&self, bytes: usize) {
+        let _ = self
+            .object_bytes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |held| {
+                Some(held.saturating_sub(bytes))
+            });
+    }
+
+    /// Register a callback run after every create, update, and delete
+    ///
+    /// Observers run after the write is committed and may themselves write to the
+    /// tracker (e.g. to simulate a controller reacting to the change).
+    pub(crate) fn add_observer(&self, observer: WriteObserver) {
+        self.observers.write().push(observer);
+    }
+
+    /// Run a function with the last state of every object deleted from now on
+    pub(crate) fn add_removal_observer(&self, observer: RemovalObserver) {
+        self.removal_observers.write().push(observer);
+    }
+
+    /// Number of times [`restore`](Self::restore) replaced the objects
+    pub(crate) fn restores(&self) -> u64 {
+        self.restores.load(Ordering::SeqCst)
+    }
+
+    fn notify(&self, gvr: &GVR, namespace: &str) {
+        let observers = self.observers.read().clone();
+        for observer in observers {
+            observer(self, gvr, namespace);
+        }
+    }
+
+    /// The write lock of a resource in a namespace, created if needed
+    pub(crate) fn lock(&self, gvr: &GVR, namespace: &str) -> CollectionLock {
+        if let Some(lock) = self
+            .locks
+            .read()
+            .get(gvr)
+            .and_then(|by_ns| by_ns.get(namespace))
+        {
+            return Arc::clone(lock);
+        }
+        Arc::clone(
+            self.locks
+                .write()
+                .entry(gvr.clone())
+                .or_default()
+                .entry(namespace.to_string())
+                .or_default(),
+        )
+    }
+
+    /// Whether a write currently holds the lock of a resource in a namespace, or
+    /// in any namespace; `None` if the lock map itself is held
+    fn is_locked(&self, gvr: &GVR, namespace: Option<&str>) -> Option<bool> {
+        let locks = self.locks.try_read()?;
+        let Some(by_ns) = locks.get(gvr) else {
+            return Some(false);
+        };
+        let locked = match namespace {
+            Some(namespace) => by_ns.get(namespace).is_some_and(|lock| lock.is_locked()),
+            None => by_ns.values().any(|lock| lock.is_locked()),
+        };
+        Some(locked)
+    }
+
+    /// Maintain a secondary index for a resource, used to narrow selector lookups
+    ///
+    /// Existing objects are indexed immediately. Registering a key that is already
+    /// indexed has no effect, as does registering one with a store of the
+    /// caller's.
+    pub fn add_index(&self, gvr: &GVR, key: IndexKey, indexer: IndexerFunc) {
+        if let Some(memory) = &self.memory {
+            debug!("Indexing {:?} by {:?}", gvr, key);
+            memory.add_index(gvr, key, indexer);
+        }
+    }
+
+    /// Whether a resource has a secondary index with this key
+    pub fn has_index(&self, gvr: &GVR, key: &IndexKey) -> bool {
+        self.memory
+            .as_ref()
+            .is_some_and(|memory| memory.has_index(gvr, key))
+    }
+
+    /// Objects of a resource as the store lists them
+    fn listed(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<Listed> {
+        self.store
+            .list(gvr, namespace)
+            .into_iter()
+            .zip(0..)
+            .map(|((namespace, stored), insertion)| (namespace, insertion, stored.data))
+            .collect()
+    }
+
+    /// Put listed objects into list order
+    fn in_list_order(&self, mut objects: Vec<Listed>) -> Vec<Arc<Value>> {
+        match *self.list_order.read() {
+            ListOrder::Name => objects.sort_by(|(a_ns, _, a), (b_ns, _, b)| {
+                let name = |object: &Value| object["metadata"]["name"].as_str().map(str::to_owned);
+                (a_ns, name(a)).cmp(&(b_ns, name(b)))
+            }),
+            ListOrder::Insertion => objects.sort_by_key(|(_, insertion, _)| *insertion),
+        }
+        objects.into_iter().map(|(_, _, object)| object).collect()
+    }
+
+    /// The resourceVersion of the latest write
+    pub fn current_resource_version(&self) -> u64 {
+        self.resource_version.load(Ordering::SeqCst)
+    }
+
+    /// Events published for every write
+    pub(crate) fn watch_cache(&self) -> &WatchCache {
+        &self.watch_cache
+    }
+
+    fn resource_version_of(object: &Value) -> Option<u64> {
+        object
+            .pointer("/metadata/resourceVersion")
+            .and_then(Value::as_str)
+            .and_then(|rv| rv.parse().ok())
+    }
+
+    /// Publish a write to watches and to the store
+    ///
+    /// Called with the collection lock held, so events of an object are
+    /// published in the order its writes were committed.
+    fn publish(&self, gvr: &GVR, namespace: &str, event_type: EventType, object: Arc<Value>) {
+        let resource_version =
+            Self::resource_version_of(&object).unwrap_or_else(|| self.current_resource_version());
+        self.store.on_event(gvr, namespace, event_type, &object);
+        self.watch_cache.publish(WatchEvent {
+            gvr: gvr.clone(),
+            namespace: namespace.to_string(),
+            event_type,
+            object,
+            resource_version,
+        });
+    }
+
+    /// Make sure later writes get resource versions newer than `resource_version`
+    pub(crate) fn advance_resource_version(&self, resource_version: u64) {
+        self.resource_version
+            .fetch_max(resource_version, Ordering::SeqCst);
     }
 
     fn next_resource_version(&self) -> String {
@@ -93,44 +517,82 @@ impl ObjectTracker {
         rv.to_string()
     }
 
+    /// Enable the status subresource of a kind
+    ///
+    /// Built-in kinds have theirs from discovery already. Registering a kind also
+    /// makes creates through the main resource drop the status they carry.
     pub fn add_status_subresource(&self, gvk: GVK) {
-        self.with_status_subresource
-            .write()
-            .expect("lock poisoned")
-            .insert(gvk);
+        self.with_status_subresource.write().insert(gvk);
     }
 
+    /// Whether a kind's status is written only through its status subresource
+    ///
+    /// True for registered kinds and for built-in kinds whose discovery data lists
+    /// a status subresource.
     pub fn has_status_subresource(&self, gvk: &GVK) -> bool {
-        self.with_status_subresource
-            .read()
-            .expect("lock poisoned")
-            .contains(gvk)
+        self.is_status_subresource_registered(gvk) || Discovery::has_subresource(gvk, "status")
     }
 
-    /// Auto-register status subresource if object has a status field
-    fn maybe_register_status_subresource(&self, gvk: &GVK, object: &Value) {
-        if object.get("status").is_some() {
-            self.add_status_subresource(gvk.clone());
-            debug!("Auto-registered status subresource for GVK: {:?}", gvk);
-        }
+    /// Whether a kind's status subresource was registered explicitly
+    pub fn is_status_subresource_registered(&self, gvk: &GVK) -> bool {
+        self.with_status_subresource.read().contains(gvk)
     }
 
     /// Store object in tracker storage
+    ///
+    /// Fails with AlreadyExists if an object of the name is stored, unless
+    /// `replace` is set. An object without a resourceVersion gets the next one
+    /// while the collection lock is held, so its event is published in order
+    /// with the other writes to the object.
     fn store_object(
         &self,
         gvr: &GVR,
         namespace: &str,
         name: &str,
-        stored: StoredObject,
-    ) -> Result<()> {
-        let mut objects = self.objects.write().expect("lock poisoned");
-        objects
-            .entry(gvr.clone())
-            .or_default()
-            .entry(namespace.to_string())
-            .or_default()
-            .insert(name.to_string(), stored);
-        Ok(())
+        mut object: Value,
+        mut meta: ObjectMeta,
+        replace: bool,
+    ) -> Result<Value> {
+        let lock = self.lock(gvr, namespace);
+        let _write = lock.write();
+        let existing = self.store.get(gvr, namespace, name);
+        let event_type = match &existing {
+            Some(_) if !replace => {
+                return Err(Error::AlreadyExists {
+                    kind: gvr.resource.clone(),
+                    name: name.to_string(),
+                    namespace: namespace.to_string(),
+                })
+            }
+            Some(_) => EventType::Modified,
+            None => EventType::Added,
+        };
+
+        // Set resource version if not present or empty; otherwise make sure later
+        // writes, and lists, get newer versions than the one the object brings
+        match meta.resource_version.as_deref().filter(|rv| !rv.is_empty()) {
+            Some(rv) => {
+                if let Ok(rv) = rv.parse() {
+                    self.advance_resource_version(rv);
+                }
+            }
+            None => meta.resource_version = Some(self.next_resource_version()),
+        }
+        object["metadata"] = serde_json::to_value(&meta)?;
+        self.reserve(
+            gvr,
+            existing.as_ref().map(|stored| stored.data.as_ref()),
+            &object,
+        )?;
+
+        let data = Arc::new(object.clone());
+        let stored = StoredObject {
+            data: Arc::clone(&data),
+            metadata: meta,
+        };
+        self.store.insert(gvr, namespace, name, stored);
+        self.publish(gvr, namespace, event_type, data);
+        Ok(object)
     }
 
     /// Extract object name from metadata
@@ -140,7 +602,34 @@ impl ObjectTracker {
             .ok_or_else(|| Error::InvalidRequest("Object name is required".to_string()))
     }
 
-    pub fn add(&self, gvr: &GVR, gvk: &GVK, mut object: Value, namespace: &str) -> Result<Value> {
+    /// Store an object as given, keeping the metadata it carries
+    ///
+    /// Fails with AlreadyExists if an object of the name is stored already.
+    pub fn add(&self, gvr: &GVR, object: Value, namespace: &str) -> Result<Value> {
+        self.add_object(gvr, object, namespace, false)
+    }
+
+    /// Store an object as given, replacing any stored object of the name
+    pub fn add_or_replace(&self, gvr: &GVR, object: Value, namespace: &str) -> Result<Value> {
+        self.add_object(gvr, object, namespace, true)
+    }
+
+    #[cfg_attr(
+        feature = "instrumentation",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(group = %gvr.group, version = %gvr.version, resource = %gvr.resource, namespace = %namespace),
+            err(level = "debug")
+        )
+    )]
+    fn add_object(
+        &self,
+        gvr: &GVR,
+        object: Value,
+        namespace: &str,
+        replace: bool,
+    ) -> Result<Value> {
         trace!("Adding object: {:?} in namespace: {}", gvr, namespace);
 
         let mut meta = self.extract_metadata(&object)?;
@@ -155,39 +644,25 @@ impl ObjectTracker {
             )));
         }
 
-        // Set resource version if not present or empty
-        if meta
-            .resource_version
-            .as_ref()
-            .is_none_or(|rv| rv.is_empty())
-        {
-            meta.resource_version = Some(self.next_resource_version());
-        }
-
         ensure_metadata(&mut meta, namespace);
-        object["metadata"] = serde_json::to_value(&meta)?;
-
-        let stored = StoredObject {
-            data: object.clone(),
-            gvk: gvk.clone(),
-            metadata: meta,
-        };
-
-        self.store_object(gvr, namespace, &name, stored)?;
+        let object = self.store_object(gvr, namespace, &name, object, meta, replace)?;
         debug!("Added object: {}/{}", namespace, name);
 
-        self.maybe_register_status_subresource(gvk, &object);
+        self.notify(gvr, namespace);
 
         Ok(object)
     }
 
-    pub fn create(
-        &self,
-        gvr: &GVR,
-        gvk: &GVK,
-        mut object: Value,
-        namespace: &str,
-    ) -> Result<Value> {
+    #[cfg_attr(
+        feature = "instrumentation",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(group = %gvr.group, version = %gvr.version, resource = %gvr.resource, namespace = %namespace),
+            err(level = "debug")
+        )
+    )]
+    pub fn create(&self, gvr: &GVR, mut object: Value, namespace: &str) -> Result<Value> {
         trace!("Creating object: {:?} in namespace: {}", gvr, namespace);
 
         let mut meta = self.extract_metadata(&object)?;
@@ -204,16 +679,6 @@ impl ObjectTracker {
             ));
         }
 
-        // Check if object already exists
-        if self.get(gvr, namespace, &name).is_ok() {
-            return Err(Error::AlreadyExists {
-                kind: gvr.resource.clone(),
-                name: name.clone(),
-                namespace: namespace.to_string(),
-            });
-        }
-
-        meta.resource_version = Some(self.next_resource_version());
         ensure_metadata(&mut meta, namespace);
 
         // Clear deletion timestamp if present
@@ -221,18 +686,35 @@ impl ObjectTracker {
             meta.deletion_timestamp = None;
         }
 
+        // The existence check and insert happen under one lock so concurrent creates
+        // of the same name can't both succeed, and resource versions follow commit order
+        let lock = self.lock(gvr, namespace);
+        let write = lock.write();
+        if self.store.get(gvr, namespace, &name).is_some() {
+            return Err(Error::AlreadyExists {
+                kind: gvr.resource.clone(),
+                name: name.clone(),
+                namespace: namespace.to_string(),
+            });
+        }
+        meta.resource_version = Some(self.next_resource_version());
         object["metadata"] = serde_json::to_value(&meta)?;
-
-        let stored = StoredObject {
-            data: object.clone(),
-            gvk: gvk.clone(),
-            metadata: meta,
-        };
-
-        self.store_object(gvr, namespace, &name, stored)?;
+        self.reserve(gvr, None, &object)?;
+        let data = Arc::new(object.clone());
+        self.store.insert(
+            gvr,
+            namespace,
+            &name,
+            StoredObject {
+                data: Arc::clone(&data),
+                metadata: meta,
+            },
+        );
+        self.publish(gvr, namespace, EventType::Added, data);
+        drop(write);
         debug!("Created object: {}/{}", namespace, name);
 
-        self.maybe_register_status_subresource(gvk, &object);
+        self.notify(gvr, namespace);
 
         Ok(object)
     }
@@ -240,62 +722,145 @@ impl ObjectTracker {
     pub fn get(&self, gvr: &GVR, namespace: &str, name: &str) -> Result<Value> {
         trace!("Getting object: {:?} {}/{}", gvr, namespace, name);
 
-        let objects = self.objects.read().expect("lock poisoned");
+        self.get_shared(gvr, namespace, name)
+            .map(|object| object.as_ref().clone())
+    }
 
-        objects
-            .get(gvr)
-            .and_then(|gvr_objects| gvr_objects.get(namespace))
-            .and_then(|ns_objects| ns_objects.get(name))
-            .map(|stored| stored.data.clone())
+    /// Get an object without copying it
+    #[cfg_attr(
+        feature = "instrumentation",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(group = %gvr.group, version = %gvr.version, resource = %gvr.resource, namespace = %namespace, name = %name),
+            err(level = "debug")
+        )
+    )]
+    pub fn get_shared(&self, gvr: &GVR, namespace: &str, name: &str) -> Result<Arc<Value>> {
+        self.store
+            .get(gvr, namespace, name)
+            .map(|stored| stored.data)
             .ok_or_else(|| gvr.not_found_error(namespace, name))
     }
 
+    /// Get an object without blocking
+    ///
+    /// Returns `None` if a write currently holds the lock of the object's
+    /// resource in its namespace. Useful in tests that check the tracker stays
+    /// responsive while other operations run.
+    pub fn try_get(&self, gvr: &GVR, namespace: &str, name: &str) -> Option<Result<Value>> {
+        if self.is_locked(gvr, Some(namespace))? {
+            return None;
+        }
+        Some(self.get(gvr, namespace, name))
+    }
+
+    #[cfg_attr(
+        feature = "instrumentation",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(group = %gvr.group, version = %gvr.version, resource = %gvr.resource, namespace = %namespace),
+            err(level = "debug")
+        )
+    )]
     pub fn update(
         &self,
         gvr: &GVR,
         gvk: &GVK,
-        mut object: Value,
+        object: Value,
         namespace: &str,
         is_status: bool,
     ) -> Result<Value> {
         trace!("Updating object: {:?} in namespace: {}", gvr, namespace);
 
+        // The update is computed from a snapshot of the stored object; if another
+        // write lands in between, start over so the resource version is checked
+        // against (and changes are applied on top of) the latest state.
+        loop {
+            if let Some(updated) =
+                self.try_update(gvr, gvk, object.clone(), namespace, is_status)?
+            {
+                return Ok(updated);
+            }
+        }
+    }
+
+    /// Fail with Conflict if an update carries a resourceVersion other than the
+    /// stored object's
+    ///
+    /// The API server checks this precondition before it validates the update,
+    /// so a stale write conflicts even when it would also be invalid.
+    pub(crate) fn check_resource_version(existing: &Value, object: &Value) -> Result<()> {
+        let version = |object: &Value| {
+            object
+                .pointer("/metadata/resourceVersion")
+                .and_then(Value::as_str)
+                .filter(|rv| !rv.is_empty())
+                .map(str::to_string)
+        };
+        match (version(object), version(existing)) {
+            (Some(provided_rv), Some(current_rv)) if provided_rv != current_rv => {
+                Err(Error::Conflict(format!(
+                    "Resource version mismatch: expected {current_rv}, got {provided_rv}"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Attempt an update, returning `None` if the object changed concurrently
+    fn try_update(
+        &self,
+        gvr: &GVR,
+        gvk: &GVK,
+        mut object: Value,
+        namespace: &str,
+        is_status: bool,
+    ) -> Result<Option<Value>> {
         let meta = self.extract_metadata(&object)?;
         let name = Self::extract_name(&meta)?;
 
-        let existing = self.get(gvr, namespace, &name)?;
+        let existing = self.get_shared(gvr, namespace, &name)?;
         let existing_meta = self.extract_metadata(&existing)?;
 
-        // Validate resource version for optimistic locking
-        if let Some(provided_rv) = &meta.resource_version {
-            if let Some(current_rv) = &existing_meta.resource_version {
-                if provided_rv != current_rv && !provided_rv.is_empty() {
-                    return Err(Error::Conflict(format!(
-                        "Resource version mismatch: expected {current_rv}, got {provided_rv}"
-                    )));
-                }
-            }
-        }
+        Self::check_resource_version(&existing, &object)?;
 
         // Handle status subresource logic
-        if self.has_status_subresource(gvk) {
-            if is_status {
-                // Status update: preserve spec
-                if let Some(spec) = existing.get("spec") {
-                    object["spec"] = spec.clone();
+        if is_status {
+            // Status update: only the status field may change, everything else
+            // (spec, metadata, top-level data) is taken from the stored object,
+            // except the field ownership recorded for the write
+            let mut merged = existing.as_ref().clone();
+            match object.get("status") {
+                Some(status) => merged["status"] = status.clone(),
+                None => {
+                    if let Some(obj) = merged.as_object_mut() {
+                        obj.remove("status");
+                    }
                 }
-            } else {
-                // Regular update: preserve status
-                if let Some(status) = existing.get("status") {
-                    object["status"] = status.clone();
+            }
+            if let Some(managed_fields) = object.pointer("/metadata/managedFields") {
+                merged["metadata"]["managedFields"] = managed_fields.clone();
+            }
+            object = merged;
+        } else if self.has_status_subresource(gvk) {
+            // Regular update: preserve status, including its absence
+            match existing.get("status") {
+                Some(status) => object["status"] = status.clone(),
+                None => {
+                    if let Some(obj) = object.as_object_mut() {
+                        obj.remove("status");
+                    }
                 }
             }
         }
 
         let mut new_meta = self.extract_metadata(&object)?;
-        new_meta.resource_version = Some(self.next_resource_version());
+        new_meta.resource_version = None;
         new_meta.uid = existing_meta.uid;
         new_meta.creation_timestamp = existing_meta.creation_timestamp;
+        ensure_metadata(&mut new_meta, namespace);
 
         // Increment generation for spec changes, not for status-only updates
         new_meta.generation = if is_status {
@@ -316,66 +881,403 @@ impl ObjectTracker {
 
         object["metadata"] = serde_json::to_value(&new_meta)?;
 
+        // Like the API server, a write that changes nothing isn't stored: the
+        // resourceVersion and generation stay put and watchers see no event
+        if unchanged(&existing, &object) {
+            return Ok(Some(existing.as_ref().clone()));
+        }
+
         // Delete if conditions are met
         if should_be_deleted(&new_meta) {
-            return self.delete(gvr, namespace, &name);
+            return self.delete(gvr, namespace, &name).map(Some);
         }
 
-        let stored = StoredObject {
-            data: object.clone(),
-            gvk: gvk.clone(),
-            metadata: new_meta,
-        };
-
-        let mut objects = self.objects.write().expect("lock poisoned");
-        objects
-            .get_mut(gvr)
-            .and_then(|gvr_objects| gvr_objects.get_mut(namespace))
-            .and_then(|ns_objects| ns_objects.insert(name.clone(), stored))
+        let lock = self.lock(gvr, namespace);
+        let write = lock.write();
+        let current = self
+            .store
+            .get(gvr, namespace, &name)
             .ok_or_else(|| gvr.not_found_error(namespace, &name))?;
+        if current.metadata.resource_version != existing_meta.resource_version {
+            return Ok(None);
+        }
+        let resource_version = self.next_resource_version();
+        object["metadata"]["resourceVersion"] = json!(resource_version);
+        new_meta.resource_version = Some(resource_version);
+        self.reserve(gvr, Some(&current.data), &object)?;
+        let data = Arc::new(object.clone());
+        self.store.insert(
+            gvr,
+            namespace,
+            &name,
+            StoredObject {
+                data: Arc::clone(&data),
+                metadata: new_meta,
+            },
+        );
+        self.publish(gvr, namespace, EventType::Modified, data);
+        drop(write);
 
         debug!("Updated object: {}/{}", namespace, name);
-        Ok(object)
+        self.notify(gvr, namespace);
+        Ok(Some(object))
     }
 
+    #[cfg_attr(
+        feature = "instrumentation",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(group = %gvr.group, version = %gvr.version, resource = %gvr.resource, namespace = %namespace, name = %name),
+            err(level = "debug")
+        )
+    )]
     pub fn delete(&self, gvr: &GVR, namespace: &str, name: &str) -> Result<Value> {
         trace!("Deleting object: {:?} {}/{}", gvr, namespace, name);
 
-        let mut objects = self.objects.write().expect("lock poisoned");
+        let lock = self.lock(gvr, namespace);
+        let write = lock.write();
+        let stored = self
+            .store
+            .remove(gvr, namespace, name)
+            .ok_or_else(|| gvr.not_found_error(namespace, name))?;
+        self.release(approximate_size(&stored.data));
+        // Like the API server, the delete event carries the version of the deletion
+        let mut last_state = stored.data.as_ref().clone();
+        last_state["metadata"]["resourceVersion"] = json!(self.next_resource_version());
+        self.publish(gvr, namespace, EventType::Deleted, Arc::new(last_state));
+        drop(write);
+        debug!("Deleted object: {}/{}", namespace, name);
+        let deleted = Arc::unwrap_or_clone(stored.data);
 
-        objects
-            .get_mut(gvr)
-            .and_then(|gvr_objects| gvr_objects.get_mut(namespace))
-            .and_then(|ns_objects| ns_objects.remove(name))
-            .map(|stored| {
-                debug!("Deleted object: {}/{}", namespace, name);
-                stored.data
-            })
-            .ok_or_else(|| gvr.not_found_error(namespace, name))
+        let removal_observers = self.removal_observers.read().clone();
+        for observer in removal_observers {
+            observer(gvr, namespace, &deleted);
+        }
+        self.notify(gvr, namespace);
+        Ok(deleted)
+    }
+
+    /// Start (or shorten) the graceful deletion of an object
+    ///
+    /// Sets `deletionTimestamp` to `grace_period_seconds` from now and records the
+    /// grace period, leaving the object stored until it is deleted for good. An object
+    /// already terminating keeps the earlier deadline and the shorter grace period.
+    pub fn begin_graceful_deletion(
+        &self,
+        gvr: &GVR,
+        namespace: &str,
+        name: &str,
+        grace_period_seconds: i64,
+    ) -> Result<Value> {
+        self.begin_deletion(gvr, namespace, name, grace_period_seconds, None)
     }
 
+    /// Start the deletion of an object that waits for a finalizer
+    ///
+    /// Adds the finalizer, if missing, in the same write that sets
+    /// `deletionTimestamp`, as the API server does for the `orphan` and
+    /// `foregroundDeletion` finalizers of propagated deletes.
+    pub(crate) fn begin_finalized_deletion(
+        &self,
+        gvr: &GVR,
+        namespace: &str,
+        name: &str,
+        finalizer: &str,
+    ) -> Result<Value> {
+        self.begin_deletion(gvr, namespace, name, 0, Some(finalizer))
+    }
+
+    fn begin_deletion(
+        &self,
+        gvr: &GVR,
+        namespace: &str,
+        name: &str,
+        grace_period_seconds: i64,
+        finalizer: Option<&str>,
+    ) -> Result<Value> {
+        trace!(
+            "Gracefully deleting object: {:?} {}/{} ({}s)",
+            gvr,
+            namespace,
+            name,
+            grace_period_seconds
+        );
+
+        let lock = self.lock(gvr, namespace);
+        let write = lock.write();
+        let mut stored = self
+            .store
+            .get(gvr, namespace, name)
+            .ok_or_else(|| gvr.not_found_error(namespace, name))?;
+
+        let deadline = crate::clock::now() + chrono::Duration::seconds(grace_period_seconds);
+        let mut meta = stored.metadata.clone();
+        let timestamp = match &meta.deletion_timestamp {
+            Some(existing) if existing.0 <= deadline => existing.0,
+            _ => deadline,
+        };
+        let grace = meta
+            .deletion_grace_period_seconds
+            .map_or(grace_period_seconds, |g| g.min(grace_period_seconds));
+        let finalizers = meta.finalizers.get_or_insert_with(Vec::new);
+        let added = match finalizer {
+            Some(finalizer) if !finalizers.iter().any(|f| f == finalizer) => {
+                finalizers.push(finalizer.to_string());
+                true
+            }
+            _ => false,
+        };
+        if finalizers.is_empty() {
+            meta.finalizers = None;
+        }
+        if !added
+            && meta.deletion_timestamp.as_ref().map(|t| t.0) == Some(timestamp)
+            && meta.deletion_grace_period_seconds == Some(grace)
+        {
+            return Ok(stored.data.as_ref().clone());
+        }
+
+        meta.deletion_timestamp = Some(k8s_openapi::apimachinery::pkg::apis::meta::v1::Time(
+            timestamp,
+        ));
+        meta.deletion_grace_period_seconds = Some(grace);
+        meta.resource_version = Some(self.next_resource_version());
+        meta.generation = Some(increment_generation(meta.generation));
+        let mut updated = stored.data.as_ref().clone();
+        updated["metadata"] = serde_json::to_value(&meta)?;
+        self.account(&stored.data, &updated);
+        stored.data = Arc::new(updated);
+        stored.metadata = meta;
+        let object = stored.data.as_ref().clone();
+        let data = Arc::clone(&stored.data);
+        self.store.insert(gvr, namespace, name, stored);
+        self.publish(gvr, namespace, EventType::Modified, data);
+        drop(write);
+
+        debug!("Marked object for deletion: {}/{}", namespace, name);
+        self.notify(gvr, namespace);
+        Ok(object)
+    }
+
+    /// List objects of a resource, in one namespace or across all of them
+    ///
+    /// Objects come back in the configured [`ListOrder`]: by default ordered by
+    /// namespace and then name, so results are the same between calls and runs.
     pub fn list(&self, gvr: &GVR, namespace: Option<&str>) -> Result<Vec<Value>> {
-        trace!("Listing objects: {:?} in namespace: {:?}", gvr, namespace);
+        Ok(self
+            .list_shared(gvr, namespace)
+            .into_iter()
+            .map(Arc::unwrap_or_clone)
+            .collect())
+    }
 
-        let objects = self.objects.read().expect("lock poisoned");
+    /// List objects without copying them
+    ///
+    /// Only the `Arc`s are cloned, so callers can filter large lists before paying
+    /// for copies.
+    #[cfg_attr(
+        feature = "instrumentation",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(group = %gvr.group, version = %gvr.version, resource = %gvr.resource, namespace = ?namespace)
+        )
+    )]
+    pub fn list_shared(&self, gvr: &GVR, namespace: Option<&str>) -> Vec<Arc<Value>> {
+        trace!("Listing objects: {:?} in namespace: {:?}", gvr, namespace);
 
         // If no objects of this type exist, return empty list (matches Kubernetes API behavior)
-        let Some(gvr_objects) = objects.get(gvr) else {
-            return Ok(Vec::new());
+        self.in_list_order(self.listed(gvr, namespace))
+    }
+
+    /// List objects without blocking
+    ///
+    /// Returns `None` if a write currently holds the lock of the resource in any
+    /// namespace listed.
+    pub fn try_list(&self, gvr: &GVR, namespace: Option<&str>) -> Option<Vec<Value>> {
+        if self.is_locked(gvr, namespace)? {
+            return None;
+        }
+        self.list(gvr, namespace).ok()
+    }
+
+    /// List objects, narrowed by secondary indexes
+    ///
+    /// Returns the objects whose indexed values match every lookup. Lookups on keys
+    /// without an index, and all lookups with a store of the caller's, don't narrow
+    /// the result, so callers must still match their selectors against what is
+    /// returned.
+    pub fn list_indexed(
+        &self,
+        gvr: &GVR,
+        namespace: Option<&str>,
+        lookups: &[IndexLookup],
+    ) -> Vec<Arc<Value>> {
+        trace!(
+            "Listing objects: {:?} in namespace: {:?} with lookups: {:?}",
+            gvr,
+            namespace,
+            lookups
+        );
+
+        let objects = match &self.memory {
+            Some(memory) => memory.list_indexed(gvr, namespace, lookups),
+            None => self.listed(gvr, namespace),
         };
+        self.in_list_order(objects)
+    }
+
+    /// List every stored object in a namespace, across all resource types
+    ///
+    /// Objects are returned grouped by resource type (ordered by group, version,
+    /// resource) and then ordered by name, so results are stable between calls.
+    pub fn list_namespace(&self, namespace: &str) -> Vec<Value> {
+        trace!("Listing all objects in namespace: {}", namespace);
+
+        let mut gvrs: Vec<GVR> = self
+            .store
+            .collections()
+            .into_iter()
+            .filter(|(_, ns)| ns == namespace)
+            .map(|(gvr, _)| gvr)
+            .collect();
+        gvrs.sort_by(|a, b| {
+            (&a.group, &a.version, &a.resource).cmp(&(&b.group, &b.version, &b.resource))
+        });
+
+        gvrs.into_iter()
+            .flat_map(|gvr| {
+                let mut objects = self.store.list(&gvr, Some(namespace));
+                objects.sort_by(|(_, a), (_, b)| a.metadata.name.cmp(&b.metadata.name));
+                objects
+                    .into_iter()
+                    .map(|(_, stored)| stored.data.as_ref().clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Every stored object, with the resource it is stored under
+    pub(crate) fn list_all_shared(&self) -> Vec<(GVR, Arc<Value>)> {
+        self.resources()
+            .into_iter()
+            .flat_map(|gvr| {
+                self.store
+                    .list(&gvr, None)
+                    .into_iter()
+                    .map(move |(_, stored)| (gvr.clone(), stored.data))
+            })
+            .collect()
+    }
 
-        let result = match namespace {
-            Some(ns) => gvr_objects
-                .get(ns)
-                .map(|objs| objs.values().map(|s| s.data.clone()).collect())
-                .unwrap_or_default(),
-            None => gvr_objects
+    /// Every resource with objects stored
+    fn resources(&self) -> Vec<GVR> {
+        let mut gvrs: Vec<GVR> = self
+            .store
+            .collections()
+            .into_iter()
+            .map(|(gvr, _)| gvr)
+            .collect();
+        gvrs.sort_by(|a, b| {
+            (&a.group, &a.version, &a.resource).cmp(&(&b.group, &b.version, &b.resource))
+        });
+        gvrs.dedup();
+        gvrs
+    }
+
+    /// Copy of every stored object
+    ///
+    /// Resources are copied one at a time, so the snapshot is only consistent when no
+    /// writes are in flight.
+    pub fn snapshot(&self) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        for gvr in self.resources() {
+            for ((namespace, stored), insertion) in self.store.list(&gvr, None).into_iter().zip(0..)
+            {
+                let name = stored.metadata.name.clone().unwrap_or_default();
+                let key = Snapshot::key(&gvr, &namespace, &name);
+                snapshot.insertions.insert(key.clone(), insertion);
+                snapshot.objects.insert(key, stored);
+            }
+        }
+        snapshot
+    }
+
+    /// Replace every stored object with the objects of a snapshot
+    ///
+    /// The resource version counter is not rolled back, so objects written after
+    /// the restore still get versions newer than any handed out before it. Write
+    /// observers are not run.
+    pub fn restore(&self, snapshot: &Snapshot) {
+        for (gvr, namespace) in self.store.collections() {
+            for (_, stored) in self.store.list(&gvr, Some(&namespace)) {
+                let name = stored.metadata.name.unwrap_or_default();
+                self.store.remove(&gvr, &namespace, &name);
+            }
+        }
+
+        let mut objects: Vec<_> = snapshot.objects.iter().collect();
+        objects.sort_by_key(|(key, _)| snapshot.insertions.get(*key).copied().unwrap_or(u64::MAX));
+        for (key, stored) in objects {
+            let (group, version, resource, namespace, name) = key;
+            let gvr = GVR::new(group, version, resource);
+            self.store.insert(&gvr, namespace, name, stored.clone());
+        }
+        self.object_bytes.store(
+            snapshot
+                .objects
                 .values()
-                .flat_map(|objs| objs.values().map(|s| s.data.clone()))
-                .collect(),
-        };
+                .map(|stored| approximate_size(&stored.data))
+                .sum(),
+            Ordering::SeqCst,
+        );
+        self.restores.fetch_add(1, Ordering::SeqCst);
+    }
 
-        Ok(result)
+    /// Objects stored for each resource and the size of the watch history
+    ///
+    /// Sizes are measured from the store, not from the tracker's running count.
+    pub fn stats(&self) -> TrackerStats {
+        let resources = self
+            .resources()
+            .into_iter()
+            .map(|gvr| {
+                let objects = self.store.list(&gvr, None);
+                ResourceStats {
+                    objects: objects.len(),
+                    bytes: objects
+                        .iter()
+                        .map(|(_, stored)| approximate_size(&stored.data))
+                        .sum(),
+                    gvr,
+                }
+            })
+            .collect();
+        let (watch_events, watch_bytes) = self.watch_cache.history_size();
+        TrackerStats {
+            resources,
+            watch_events,
+            watch_bytes,
+        }
+    }
+
+    /// Drop what the tracker keeps beyond the stored objects
+    ///
+    /// Empties the watch history, so watches resuming from an earlier
+    /// resourceVersion get 410 Gone and relist, as after etcd compaction; open
+    /// watches keep running. Also forgets the deleted objects event ordering is
+    /// checked for, the write locks nobody holds and, with the built-in store,
+    /// the collections left without objects.
+    pub fn compact(&self) {
+        self.watch_cache.compact();
+        self.locks.write().retain(|_, by_ns| {
+            by_ns.retain(|_, lock| Arc::strong_count(lock) > 1);
+            !by_ns.is_empty()
+        });
+        if let Some(memory) = &self.memory {
+            memory.drop_empty_shards();
+        }
     }
 
     fn extract_metadata(&self, object: &Value) -> Result<ObjectMeta> {
@@ -394,3 +1296,28 @@ impl Default for ObjectTracker {
         Self::new()
     }
 }
+
+/// Whether an update leaves the stored object as it was
+///
+/// The versions the update would bump and the times of managedFields entries,
+/// which every write refreshes, don't count as changes; neither do the order
+/// of the entries or serialization artifacts such as `null` fields.
+fn unchanged(existing: &Value, updated: &Value) -> bool {
+    let content = |object: &Value| {
+        let mut object = normalized(object);
+        if let Some(metadata) = object.get_mut("metadata").and_then(Value::as_object_mut) {
+            metadata.remove("resourceVersion");
+            metadata.remove("generation");
+            if let Some(Value::Array(entries)) = metadata.get_mut("managedFields") {
+                for entry in entries.iter_mut() {
+                    if let Some(entry) = entry.as_object_mut() {
+                        entry.remove("time");
+                    }
+                }
+                entries.sort_by_cached_key(Value::to_string);
+            }
+        }
+        object
+    };
+    content(existing) == content(updated)
+}
@@ -2,6 +2,7 @@
 mod tests {
     use crate::tracker::*;
     use serde_json::json;
+    use std::sync::Arc;
 
     fn create_test_object(name: &str, namespace: &str) -> serde_json::Value {
         json!({
@@ -24,10 +25,9 @@ mod tests {
     fn test_add_sets_globally_increasing_resource_version() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let obj = create_test_object("test-pod", "default");
 
-        let added = tracker.add(&gvr, &gvk, obj, "default").unwrap();
+        let added = tracker.add(&gvr, obj, "default").unwrap();
         assert_eq!(added["metadata"]["name"], "test-pod");
         // Should have a resource version set (globally increasing)
         let rv1 = added["metadata"]["resourceVersion"].as_str().unwrap();
@@ -38,7 +38,7 @@ mod tests {
 
         // Add another object and verify RV increases
         let obj2 = create_test_object("test-pod-2", "default");
-        let added2 = tracker.add(&gvr, &gvk, obj2, "default").unwrap();
+        let added2 = tracker.add(&gvr, obj2, "default").unwrap();
         let rv2 = added2["metadata"]["resourceVersion"].as_str().unwrap();
 
         // Parse and compare to verify RV is globally increasing
@@ -54,27 +54,31 @@ mod tests {
     fn test_add_preserves_existing_resource_version() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let mut obj = create_test_object("test-pod", "default");
         obj["metadata"]["resourceVersion"] = json!("42");
 
-        let added = tracker.add(&gvr, &gvk, obj, "default").unwrap();
+        let added = tracker.add(&gvr, obj, "default").unwrap();
         assert_eq!(added["metadata"]["resourceVersion"], "42");
     }
 
     #[test]
-    fn test_add_replaces_existing_object() {
+    fn test_add_replaces_existing_object_only_when_asked() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
 
         let obj1 = create_test_object("test-pod", "default");
-        tracker.add(&gvr, &gvk, obj1, "default").unwrap();
+        tracker.add(&gvr, obj1, "default").unwrap();
 
         let mut obj2 = create_test_object("test-pod", "default");
         obj2["spec"]["containers"][0]["image"] = json!("nginx:latest");
-        let added = tracker.add(&gvr, &gvk, obj2, "default").unwrap();
-
+        assert!(matches!(
+            tracker.add(&gvr, obj2.clone(), "default"),
+            Err(crate::Error::AlreadyExists { .. })
+        ));
+        let stored = tracker.get(&gvr, "default", "test-pod").unwrap();
+        assert_eq!(stored["spec"]["containers"][0]["image"], "nginx");
+
+        let added = tracker.add_or_replace(&gvr, obj2, "default").unwrap();
         assert_eq!(added["spec"]["containers"][0]["image"], "nginx:latest");
     }
 
@@ -82,10 +86,9 @@ mod tests {
     fn test_create_sets_resource_version_1() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let obj = create_test_object("test-pod", "default");
 
-        let created = tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        let created = tracker.create(&gvr, obj, "default").unwrap();
         assert_eq!(created["metadata"]["name"], "test-pod");
         assert_eq!(created["metadata"]["resourceVersion"], "1");
 
@@ -97,11 +100,10 @@ mod tests {
     fn test_create_errors_if_resource_version_set() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let mut obj = create_test_object("test-pod", "default");
         obj["metadata"]["resourceVersion"] = json!("1");
 
-        let result = tracker.create(&gvr, &gvk, obj, "default");
+        let result = tracker.create(&gvr, obj, "default");
         assert!(result.is_err());
         assert!(matches!(result, Err(crate::Error::InvalidRequest(_))));
 
@@ -114,11 +116,10 @@ mod tests {
     fn test_add_errors_if_deletion_timestamp_without_finalizers() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let mut obj = create_test_object("test-pod", "default");
         obj["metadata"]["deletionTimestamp"] = json!("2024-01-01T00:00:00Z");
 
-        let result = tracker.add(&gvr, &gvk, obj, "default");
+        let result = tracker.add(&gvr, obj, "default");
         assert!(result.is_err());
         assert!(matches!(result, Err(crate::Error::InvalidRequest(_))));
 
@@ -131,12 +132,11 @@ mod tests {
     fn test_add_allows_deletion_timestamp_with_finalizers() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let mut obj = create_test_object("test-pod", "default");
         obj["metadata"]["deletionTimestamp"] = json!("2024-01-01T00:00:00Z");
         obj["metadata"]["finalizers"] = json!(["test-finalizer"]);
 
-        let result = tracker.add(&gvr, &gvk, obj, "default");
+        let result = tracker.add(&gvr, obj, "default");
         assert!(result.is_ok());
     }
 
@@ -147,7 +147,7 @@ mod tests {
         let gvk = GVK::new("", "v1", "Pod");
         let obj = create_test_object("test-pod", "default");
 
-        tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        tracker.create(&gvr, obj, "default").unwrap();
 
         let mut updated_obj = create_test_object("test-pod", "default");
         updated_obj["metadata"]["resourceVersion"] = json!("1");
@@ -164,10 +164,9 @@ mod tests {
     fn test_delete() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let obj = create_test_object("test-pod", "default");
 
-        tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        tracker.create(&gvr, obj, "default").unwrap();
         tracker.delete(&gvr, "default", "test-pod").unwrap();
 
         assert!(tracker.get(&gvr, "default", "test-pod").is_err());
@@ -177,16 +176,15 @@ mod tests {
     fn test_list() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
 
         tracker
-            .create(&gvr, &gvk, create_test_object("pod1", "default"), "default")
+            .create(&gvr, create_test_object("pod1", "default"), "default")
             .unwrap();
         tracker
-            .create(&gvr, &gvk, create_test_object("pod2", "default"), "default")
+            .create(&gvr, create_test_object("pod2", "default"), "default")
             .unwrap();
         tracker
-            .create(&gvr, &gvk, create_test_object("pod3", "other"), "other")
+            .create(&gvr, create_test_object("pod3", "other"), "other")
             .unwrap();
 
         let default_list = tracker.list(&gvr, Some("default")).unwrap();
@@ -196,6 +194,83 @@ mod tests {
         assert_eq!(all_list.len(), 3);
     }
 
+    fn names(objects: &[serde_json::Value]) -> Vec<String> {
+        objects
+            .iter()
+            .map(|o| {
+                format!(
+                    "{}/{}",
+                    o["metadata"]["namespace"].as_str().unwrap(),
+                    o["metadata"]["name"].as_str().unwrap()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_list_orders_by_namespace_and_name() {
+        let tracker = ObjectTracker::new();
+        let gvr = GVR::new("", "v1", "pods");
+
+        for (name, namespace) in [
+            ("web-2", "prod"),
+            ("web-1", "prod"),
+            ("db", "dev"),
+            ("web-10", "prod"),
+        ] {
+            tracker
+                .create(&gvr, create_test_object(name, namespace), namespace)
+                .unwrap();
+        }
+
+        assert_eq!(
+            names(&tracker.list(&gvr, None).unwrap()),
+            ["dev/db", "prod/web-1", "prod/web-10", "prod/web-2"]
+        );
+        assert_eq!(
+            names(&tracker.list(&gvr, Some("prod")).unwrap()),
+            ["prod/web-1", "prod/web-10", "prod/web-2"]
+        );
+        assert_eq!(
+            names(&tracker.try_list(&gvr, None).unwrap()),
+            ["dev/db", "prod/web-1", "prod/web-10", "prod/web-2"]
+        );
+    }
+
+    #[test]
+    fn test_list_insertion_order() {
+        let tracker = ObjectTracker::new();
+        tracker.set_list_order(ListOrder::Insertion);
+        let gvr = GVR::new("", "v1", "pods");
+        let gvk = GVK::new("", "v1", "Pod");
+
+        for (name, namespace) in [("web-2", "prod"), ("db", "dev"), ("web-1", "prod")] {
+            tracker
+                .create(&gvr, create_test_object(name, namespace), namespace)
+                .unwrap();
+        }
+        let snapshot = tracker.snapshot();
+
+        // Updates keep an object's place, recreating it moves it to the end
+        let mut web_2 = tracker.get(&gvr, "prod", "web-2").unwrap();
+        web_2["metadata"]["labels"] = json!({ "updated": "true" });
+        tracker.update(&gvr, &gvk, web_2, "prod", false).unwrap();
+        tracker.delete(&gvr, "dev", "db").unwrap();
+        tracker
+            .create(&gvr, create_test_object("db", "dev"), "dev")
+            .unwrap();
+        assert_eq!(
+            names(&tracker.list(&gvr, None).unwrap()),
+            ["prod/web-2", "prod/web-1", "dev/db"]
+        );
+
+        tracker.restore(&snapshot);
+        assert_eq!(
+            names(&tracker.list(&gvr, None).unwrap()),
+            ["prod/web-2", "dev/db", "prod/web-1"]
+        );
+    }
+
     #[test]
     fn test_list_empty_returns_empty_list() {
         let tracker = ObjectTracker::new();
@@ -219,10 +294,9 @@ mod tests {
     fn test_generation_initialized_on_create() {
         let tracker = ObjectTracker::new();
         let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
         let obj = create_test_object("test-pod", "default");
 
-        let created = tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        let created = tracker.create(&gvr, obj, "default").unwrap();
         assert_eq!(created["metadata"]["generation"], 1);
     }
 
@@ -233,7 +307,7 @@ mod tests {
         let gvk = GVK::new("", "v1", "Pod");
         let obj = create_test_object("test-pod", "default");
 
-        let created = tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        let created = tracker.create(&gvr, obj, "default").unwrap();
         assert_eq!(created["metadata"]["generation"], 1);
 
         let mut updated_obj = create_test_object("test-pod", "default");
@@ -254,7 +328,7 @@ mod tests {
         tracker.add_status_subresource(gvk.clone());
 
         let obj = create_test_object("test-pod", "default");
-        let created = tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        let created = tracker.create(&gvr, obj, "default").unwrap();
         assert_eq!(created["metadata"]["generation"], 1);
 
         let mut status_update = create_test_object("test-pod", "default");
@@ -274,7 +348,7 @@ mod tests {
         let gvk = GVK::new("", "v1", "Pod");
         let obj = create_test_object("test-pod", "default");
 
-        let created = tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        let created = tracker.create(&gvr, obj, "default").unwrap();
         assert_eq!(created["metadata"]["generation"], 1);
 
         // First spec update
@@ -302,9 +376,8 @@ mod tests {
 
         // Create a Pod
         let pod_gvr = GVR::new("", "v1", "pods");
-        let pod_gvk = GVK::new("", "v1", "Pod");
         let pod = create_test_object("test-pod", "default");
-        let created_pod = tracker.create(&pod_gvr, &pod_gvk, pod, "default").unwrap();
+        let created_pod = tracker.create(&pod_gvr, pod, "default").unwrap();
         let rv1: u64 = created_pod["metadata"]["resourceVersion"]
             .as_str()
             .unwrap()
@@ -313,7 +386,6 @@ mod tests {
 
         // Create a ConfigMap
         let cm_gvr = GVR::new("", "v1", "configmaps");
-        let cm_gvk = GVK::new("", "v1", "ConfigMap");
         let cm = json!({
             "apiVersion": "v1",
             "kind": "ConfigMap",
@@ -325,7 +397,7 @@ mod tests {
                 "key": "value"
             }
         });
-        let created_cm = tracker.create(&cm_gvr, &cm_gvk, cm, "default").unwrap();
+        let created_cm = tracker.create(&cm_gvr, cm, "default").unwrap();
         let rv2: u64 = created_cm["metadata"]["resourceVersion"]
             .as_str()
             .unwrap()
@@ -334,7 +406,6 @@ mod tests {
 
         // Create a Service
         let svc_gvr = GVR::new("", "v1", "services");
-        let svc_gvk = GVK::new("", "v1", "Service");
         let svc = json!({
             "apiVersion": "v1",
             "kind": "Service",
@@ -348,7 +419,7 @@ mod tests {
                 }]
             }
         });
-        let created_svc = tracker.create(&svc_gvr, &svc_gvk, svc, "default").unwrap();
+        let created_svc = tracker.create(&svc_gvr, svc, "default").unwrap();
         let rv3: u64 = created_svc["metadata"]["resourceVersion"]
             .as_str()
             .unwrap()
@@ -377,69 +448,45 @@ mod tests {
     }
 
     #[test]
-    fn test_auto_register_status_subresource_on_create() {
-        let tracker = ObjectTracker::new();
-        let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
-
-        // Initially no status subresource registered
-        assert!(!tracker.has_status_subresource(&gvk));
-
-        // Create a Pod with a status field
-        let mut obj = create_test_object("test-pod", "default");
-        obj["status"] = json!({"phase": "Pending"});
-
-        tracker.create(&gvr, &gvk, obj, "default").unwrap();
-
-        // Status subresource should be automatically registered
-        assert!(tracker.has_status_subresource(&gvk));
-    }
-
-    #[test]
-    fn test_auto_register_status_subresource_on_add() {
+    fn test_builtin_status_subresource_from_discovery() {
         let tracker = ObjectTracker::new();
-        let gvr = GVR::new("", "v1", "pods");
-        let gvk = GVK::new("", "v1", "Pod");
-
-        // Initially no status subresource registered
-        assert!(!tracker.has_status_subresource(&gvk));
 
-        // Add a Pod with a status field
-        let mut obj = create_test_object("test-pod", "default");
-        obj["status"] = json!({"phase": "Running"});
-
-        tracker.add(&gvr, &gvk, obj, "default").unwrap();
-
-        // Status subresource should be automatically registered
-        assert!(tracker.has_status_subresource(&gvk));
+        assert!(tracker.has_status_subresource(&GVK::new("", "v1", "Pod")));
+        assert!(tracker.has_status_subresource(&GVK::new("apps", "v1", "Deployment")));
+        assert!(!tracker.has_status_subresource(&GVK::new("", "v1", "ConfigMap")));
+        // Only explicit registration counts as registered
+        assert!(!tracker.is_status_subresource_registered(&GVK::new("", "v1", "Pod")));
     }
 
     #[test]
-    fn test_no_auto_register_without_status_field() {
+    fn test_no_auto_register_for_custom_resources() {
         let tracker = ObjectTracker::new();
-        let gvr = GVR::new("", "v1", "configmaps");
-        let gvk = GVK::new("", "v1", "ConfigMap");
+        let gvr = GVR::new("example.com", "v1", "widgets");
+        let gvk = GVK::new("example.com", "v1", "Widget");
 
-        // Initially no status subresource registered
-        assert!(!tracker.has_status_subresource(&gvk));
-
-        // Create a ConfigMap without a status field
+        // A status field doesn't enable the status subresource
         let obj = json!({
-            "apiVersion": "v1",
-            "kind": "ConfigMap",
-            "metadata": {
-                "name": "test-cm",
-                "namespace": "default",
-            },
-            "data": {
-                "key": "value"
-            }
+            "apiVersion": "example.com/v1",
+            "kind": "Widget",
+            "metadata": { "name": "test-widget", "namespace": "default" },
+            "spec": { "size": 1 },
+            "status": { "ready": false },
         });
+        tracker.add(&gvr, obj.clone(), "default").unwrap();
+        assert!(!tracker.has_status_subresource(&gvk));
 
-        tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        // So a regular update writes the status
+        let mut updated = obj;
+        updated["metadata"]["resourceVersion"] = json!("1");
+        updated["status"]["ready"] = json!(true);
+        let updated = tracker
+            .update(&gvr, &gvk, updated, "default", false)
+            .unwrap();
+        assert_eq!(updated["status"]["ready"], true);
 
-        // Status subresource should NOT be registered
-        assert!(!tracker.has_status_subresource(&gvk));
+        tracker.add_status_subresource(gvk.clone());
+        assert!(tracker.has_status_subresource(&gvk));
+        assert!(tracker.is_status_subresource_registered(&gvk));
     }
 
     #[test]
@@ -448,16 +495,13 @@ mod tests {
         let gvr = GVR::new("", "v1", "pods");
         let gvk = GVK::new("", "v1", "Pod");
 
-        // Create a Pod with status - this auto-registers status subresource
+        // Pods have a status subresource
         let mut obj = create_test_object("test-pod", "default");
         obj["status"] = json!({"phase": "Pending"});
 
-        let created = tracker.create(&gvr, &gvk, obj, "default").unwrap();
+        let created = tracker.create(&gvr, obj, "default").unwrap();
         assert_eq!(created["status"]["phase"], "Pending");
 
-        // Verify status subresource was auto-registered
-        assert!(tracker.has_status_subresource(&gvk));
-
         // Try to update spec AND status in a regular update
         let mut updated_obj = create_test_object("test-pod", "default");
         updated_obj["metadata"]["resourceVersion"] = json!("1");
@@ -473,4 +517,174 @@ mod tests {
         // Status should NOT be updated (preserved from original)
         assert_eq!(updated["status"]["phase"], "Pending");
     }
+
+    #[test]
+    fn test_concurrent_updates_with_same_resource_version_conflict() {
+        use std::sync::{Arc, Barrier};
+
+        let tracker = Arc::new(ObjectTracker::new());
+        let gvr = GVR::new("", "v1", "pods");
+        let gvk = GVK::new("", "v1", "Pod");
+        tracker
+            .create(&gvr, create_test_object("test-pod", "default"), "default")
+            .unwrap();
+
+        for round in 0..50 {
+            let base = tracker.get(&gvr, "default", "test-pod").unwrap();
+            let barrier = Arc::new(Barrier::new(8));
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    let (tracker, barrier) = (Arc::clone(&tracker), Arc::clone(&barrier));
+                    let (gvr, gvk) = (gvr.clone(), gvk.clone());
+                    let mut obj = base.clone();
+                    obj["spec"]["containers"][0]["image"] = json!(format!("nginx:{round}-{i}"));
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        tracker.update(&gvr, &gvk, obj, "default", false).is_ok()
+                    })
+                })
+                .collect();
+
+            let winners = handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .filter(|ok| *ok)
+                .count();
+            assert_eq!(winners, 1, "round {round}");
+        }
+    }
+
+    #[test]
+    fn test_concurrent_creates_of_same_name_have_one_winner() {
+        use std::sync::{Arc, Barrier};
+
+        let tracker = Arc::new(ObjectTracker::new());
+        let gvr = GVR::new("", "v1", "pods");
+
+        for round in 0..50 {
+            let name = format!("pod-{round}");
+            let barrier = Arc::new(Barrier::new(8));
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    let (tracker, barrier) = (Arc::clone(&tracker), Arc::clone(&barrier));
+                    let gvr = gvr.clone();
+                    let obj = create_test_object(&name, "default");
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        tracker.create(&gvr, obj, "default").is_ok()
+                    })
+                })
+                .collect();
+
+            let winners = handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .filter(|ok| *ok)
+                .count();
+            assert_eq!(winners, 1, "round {round}");
+        }
+    }
+
+    #[test]
+    fn test_try_get_and_try_list() {
+        let tracker = ObjectTracker::new();
+        let gvr = GVR::new("", "v1", "pods");
+        tracker
+            .create(&gvr, create_test_object("test-pod", "default"), "default")
+            .unwrap();
+
+        let pod = tracker
+            .try_get(&gvr, "default", "test-pod")
+            .unwrap()
+            .unwrap();
+        assert_eq!(pod["metadata"]["name"], "test-pod");
+        assert!(tracker
+            .try_get(&gvr, "default", "missing")
+            .unwrap()
+            .is_err());
+        assert_eq!(tracker.try_list(&gvr, Some("default")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_shared_objects_are_copy_on_write() {
+        let tracker = ObjectTracker::new();
+        let gvr = GVR::new("", "v1", "pods");
+        let gvk = GVK::new("", "v1", "Pod");
+        tracker
+            .create(&gvr, create_test_object("test-pod", "default"), "default")
+            .unwrap();
+
+        let before = tracker.get_shared(&gvr, "default", "test-pod").unwrap();
+        let listed = tracker.list_shared(&gvr, Some("default"));
+        assert!(Arc::ptr_eq(&before, &listed[0]));
+
+        let mut updated = before.as_ref().clone();
+        updated["metadata"]["labels"] = json!({ "changed": "true" });
+        tracker
+            .update(&gvr, &gvk, updated, "default", false)
+            .unwrap();
+        tracker
+            .begin_graceful_deletion(&gvr, "default", "test-pod", 30)
+            .unwrap();
+
+        // Earlier snapshots are unaffected by later writes
+        assert!(before.pointer("/metadata/labels/changed").is_none());
+        assert!(before.pointer("/metadata/deletionTimestamp").is_none());
+        let after = tracker.get_shared(&gvr, "default", "test-pod").unwrap();
+        assert_eq!(after["metadata"]["labels"]["changed"], "true");
+        assert!(after.pointer("/metadata/deletionTimestamp").is_some());
+    }
+
+    #[test]
+    fn test_shards_lock_independently() {
+        let tracker = ObjectTracker::new();
+        let gvr = GVR::new("", "v1", "pods");
+        for ns in ["ns-a", "ns-b"] {
+            tracker
+                .create(&gvr, create_test_object("test-pod", ns), ns)
+                .unwrap();
+        }
+
+        let lock = tracker.lock(&gvr, "ns-a");
+        let _write = lock.write();
+
+        assert!(tracker.try_get(&gvr, "ns-a", "test-pod").is_none());
+        assert!(tracker.try_list(&gvr, Some("ns-a")).is_none());
+        assert!(tracker.try_list(&gvr, None).is_none());
+        assert!(tracker.try_get(&gvr, "ns-b", "test-pod").unwrap().is_ok());
+        assert_eq!(tracker.try_list(&gvr, Some("ns-b")).unwrap().len(), 1);
+        assert!(tracker.try_get(&gvr, "ns-c", "test-pod").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_gvk_gvr_conversions() {
+        use k8s_openapi::api::core::v1::ConfigMap;
+        use kube::core::{ApiResource, GroupVersionKind, GroupVersionResource};
+
+        let gvk = GVK::of::<ConfigMap>();
+        assert_eq!(gvk, GVK::new("", "v1", "ConfigMap"));
+        assert_eq!(gvk.to_string(), "v1, Kind=ConfigMap");
+        let kube_gvk = GroupVersionKind::from(gvk.clone());
+        assert_eq!(kube_gvk, GroupVersionKind::gvk("", "v1", "ConfigMap"));
+        assert_eq!(GVK::from(&kube_gvk), gvk);
+
+        let resource = GVK::new("example.com", "v1", "Widget").api_resource("widgets");
+        assert_eq!(resource.api_version, "example.com/v1");
+        assert_eq!(
+            GVK::from(&resource),
+            GVK::new("example.com", "v1", "Widget")
+        );
+        let gvr = GVR::from(&resource);
+        assert_eq!(gvr, GVR::new("example.com", "v1", "widgets"));
+        assert_eq!(gvr.to_string(), "example.com/v1, Resource=widgets");
+
+        let kube_gvr = GroupVersionResource::from(gvr.clone());
+        assert_eq!(kube_gvr.resource, "widgets");
+        assert_eq!(GVR::from(kube_gvr), gvr);
+        assert_eq!(GVR::of::<ConfigMap>(), GVR::new("", "v1", "configmaps"));
+        assert_eq!(
+            ApiResource::erase::<ConfigMap>(&()).plural,
+            GVR::of::<ConfigMap>().resource
+        );
+    }
 }
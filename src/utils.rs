@@ -5,7 +5,10 @@ pub fn increment_generation(current: Option<i64>) -> i64 {
 }
 
 pub fn should_be_deleted(meta: &ObjectMeta) -> bool {
-    meta.deletion_timestamp.is_some() && meta.finalizers.as_ref().is_none_or(Vec::is_empty)
+    // Objects inside a deletion grace period stay until the period elapses
+    meta.deletion_timestamp.is_some()
+        && meta.finalizers.as_ref().is_none_or(Vec::is_empty)
+        && meta.deletion_grace_period_seconds.is_none_or(|g| g == 0)
 }
 
 pub fn ensure_metadata(meta: &mut ObjectMeta, namespace: &str) {
@@ -18,7 +21,7 @@ pub fn ensure_metadata(meta: &mut ObjectMeta, namespace: &str) {
     }
     if meta.creation_timestamp.is_none() {
         meta.creation_timestamp = Some(k8s_openapi::apimachinery::pkg::apis::meta::v1::Time(
-            chrono::Utc::now(),
+            crate::clock::now(),
         ));
     }
     if meta.uid.is_none() {
@@ -10,27 +10,103 @@ pub trait SchemaValidator: Send + Sync {
     /// Takes group, version, and kind to uniquely identify the schema.
     /// For core resources, group is an empty string.
     fn validate(&self, group: &str, version: &str, kind: &str, value: &Value) -> Result<()>;
+
+    /// Fill in schema-declared defaults for fields missing from a JSON value
+    ///
+    /// Called before `validate` when schema defaulting is enabled. The default
+    /// implementation leaves the value untouched.
+    fn apply_defaults(
+        &self,
+        _group: &str,
+        _version: &str,
+        _kind: &str,
+        _value: &mut Value,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Remove fields the schema for a given GVK doesn't specify
+    ///
+    /// Called first on writes of custom resources, like the API server's pruning
+    /// of fields unknown to a CRD's structural schema. The default implementation
+    /// leaves the value untouched.
+    fn prune(&self, _group: &str, _version: &str, _kind: &str, _value: &mut Value) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// When [`ClientBuilder::with_precompiled_schemas`](crate::ClientBuilder::with_precompiled_schemas)
+/// compiles schemas
+#[cfg(feature = "validation")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precompile {
+    /// Compile before `build()` returns, failing it if a schema doesn't compile
+    Blocking,
+    /// Compile on a background thread while the test starts; validations
+    /// arriving first compile what they need themselves
+    Background,
 }
 
 #[cfg(feature = "validation")]
 mod runtime_openapi_validator {
     use super::*;
     use jsonschema::JSONSchema;
+    use once_cell::sync::Lazy;
+    use std::collections::hash_map::DefaultHasher;
     use std::collections::HashMap;
     use std::fs;
+    use std::hash::{Hash, Hasher};
     use std::path::Path;
-    use std::sync::RwLock;
+    use std::sync::{Arc, RwLock};
+
+    /// Maximum number of `$ref` indirections followed when resolving a schema
+    const MAX_REF_HOPS: usize = 16;
+
+    /// Maximum nesting depth walked when applying schema defaults or pruning
+    const MAX_SCHEMA_DEPTH: usize = 64;
+
+    /// Top-level fields every object keeps, whatever its schema lists
+    const ROOT_FIELDS: &[&str] = &["apiVersion", "kind", "metadata"];
+
+    type Definitions = Arc<HashMap<String, Value>>;
+
+    /// Compiled schemas by definitions hash and definition name
+    type CompiledSchemas = HashMap<(u64, String), Arc<JSONSchema>>;
+
+    /// Definitions parsed from OpenAPI files, keyed by the hash of the file
+    ///
+    /// Shared by every validator in the process, so test suites building many
+    /// clients from the same file parse it once.
+    static PARSED: Lazy<parking_lot::RwLock<HashMap<u64, Definitions>>> =
+        Lazy::new(Default::default);
+
+    /// Schemas compiled by any validator in the process, keyed by the hash of
+    /// the definitions they were compiled from and the definition name
+    static COMPILED: Lazy<parking_lot::RwLock<CompiledSchemas>> = Lazy::new(Default::default);
+
+    fn hash_of(value: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
 
     /// Runtime OpenAPI validator that loads schemas from OpenAPI spec files
     ///
     /// This validator allows developers to explicitly choose which resources to validate
     /// by loading schemas from OpenAPI spec files at runtime. Supports both Kubernetes
     /// built-in resources and CRDs with generated OpenAPI schemas.
+    ///
+    /// Parsed files and compiled schemas are cached for the whole process, keyed
+    /// by the contents they came from, so building many clients from the same
+    /// files only parses and compiles each schema once.
     pub struct RuntimeOpenAPIValidator {
         /// Parsed OpenAPI definitions
-        definitions: HashMap<String, Value>,
+        definitions: Definitions,
+        /// Hash identifying the definitions: of the file they were read from and
+        /// the CRD schemas added since
+        source: u64,
         /// Compiled schemas for registered GVKs (gvk_key -> schema)
-        schemas: RwLock<HashMap<String, JSONSchema>>,
+        schemas: RwLock<HashMap<String, Arc<JSONSchema>>>,
         /// Set of GVK keys that should be validated
         enabled_gvks: RwLock<Vec<String>>,
     }
@@ -46,26 +122,167 @@ mod runtime_openapi_validator {
                 ))
             })?;
 
-            let spec: Value = serde_json::from_str(&content)
-                .map_err(|e| Error::Internal(format!("Failed to parse OpenAPI JSON: {}", e)))?;
-
-            let definitions = spec
-                .get("definitions")
-                .and_then(|d| d.as_object())
-                .ok_or_else(|| Error::Internal("OpenAPI spec missing 'definitions'".to_string()))?;
-
-            let definitions_map: HashMap<String, Value> = definitions
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect();
+            let source = hash_of(&content);
+            let cached = PARSED.read().get(&source).cloned();
+            let definitions = match cached {
+                Some(definitions) => definitions,
+                None => {
+                    let spec: Value = serde_json::from_str(&content).map_err(|e| {
+                        Error::Internal(format!("Failed to parse OpenAPI JSON: {}", e))
+                    })?;
+
+                    let definitions = spec
+                        .get("definitions")
+                        .and_then(|d| d.as_object())
+                        .ok_or_else(|| {
+                            Error::Internal("OpenAPI spec missing 'definitions'".to_string())
+                        })?;
+
+                    let definitions_map: HashMap<String, Value> = definitions
+                        .iter()
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .collect();
+                    let definitions = Arc::new(definitions_map);
+                    PARSED.write().insert(source, Arc::clone(&definitions));
+                    definitions
+                }
+            };
 
             Ok(Self {
-                definitions: definitions_map,
+                definitions,
+                source,
                 schemas: RwLock::new(HashMap::new()),
                 enabled_gvks: RwLock::new(Vec::new()),
             })
         }
 
+        /// Create a validator without definitions, for schemas added from CRDs
+        pub fn new() -> Self {
+            Self {
+                definitions: Arc::default(),
+                source: 0,
+                schemas: RwLock::new(HashMap::new()),
+                enabled_gvks: RwLock::new(Vec::new()),
+            }
+        }
+
+        /// Validate the instances of a CustomResourceDefinition against its schemas
+        ///
+        /// Every version of the CRD with an `openAPIV3Schema` gets its schema
+        /// added and validation enabled, replacing a definition of the same kind
+        /// from an OpenAPI file. Returns the GVKs validation was enabled for.
+        pub fn add_crd(&mut self, crd: &Value) -> Result<Vec<String>> {
+            let describe = || {
+                crd.pointer("/metadata/name")
+                    .and_then(Value::as_str)
+                    .map(|name| format!("CustomResourceDefinition {name:?}"))
+                    .unwrap_or_else(|| "CustomResourceDefinition".to_string())
+            };
+            if crd.get("kind").and_then(Value::as_str) != Some("CustomResourceDefinition") {
+                return Err(Error::InvalidConfiguration(format!(
+                    "expected a CustomResourceDefinition, got kind {}",
+                    crd.get("kind").unwrap_or(&Value::Null)
+                )));
+            }
+            let (Some(group), Some(kind)) = (
+                crd.pointer("/spec/group").and_then(Value::as_str),
+                crd.pointer("/spec/names/kind").and_then(Value::as_str),
+            ) else {
+                return Err(Error::InvalidConfiguration(format!(
+                    "{} has no spec.group or spec.names.kind",
+                    describe()
+                )));
+            };
+
+            let mut enabled = Vec::new();
+            let versions = crd
+                .pointer("/spec/versions")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            for version in versions {
+                let (Some(name), Some(schema)) = (
+                    version.get("name").and_then(Value::as_str),
+                    version.pointer("/schema/openAPIV3Schema"),
+                ) else {
+                    continue;
+                };
+                let gvk = Self::gvk_key(group, name, kind);
+                let definition_name = self.gvk_to_definition_name(&gvk)?;
+                let schema = Self::json_schema(schema.clone());
+                self.source = hash_of((self.source, &definition_name, schema.to_string()));
+                Arc::make_mut(&mut self.definitions).insert(definition_name, schema);
+                enabled.push(gvk);
+            }
+            if enabled.is_empty() {
+                return Err(Error::InvalidConfiguration(format!(
+                    "{} has no version with a schema.openAPIV3Schema",
+                    describe()
+                )));
+            }
+
+            // Schemas compiled before hold the definitions they were compiled with
+            self.schemas
+                .write()
+                .map_err(|e| Error::Internal(format!("Failed to acquire write lock: {}", e)))?
+                .clear();
+            let mut enabled_gvks = self
+                .enabled_gvks
+                .write()
+                .map_err(|e| Error::Internal(format!("Failed to acquire write lock: {}", e)))?;
+            for gvk in &enabled {
+                if !enabled_gvks.contains(gvk) {
+                    enabled_gvks.push(gvk.clone());
+                }
+            }
+            Ok(enabled)
+        }
+
+        /// Translate an OpenAPI v3 schema of a CRD into JSON Schema
+        ///
+        /// `nullable: true` becomes a `null` alternative of the field's type;
+        /// the `x-kubernetes-*` extensions are ignored by the validator, and
+        /// used as-is by defaulting and pruning.
+        fn json_schema(mut schema: Value) -> Value {
+            if let Value::Object(fields) = &mut schema {
+                if fields.get("nullable").and_then(Value::as_bool) == Some(true) {
+                    if let Some(Value::String(kind)) = fields.get("type").cloned() {
+                        fields.insert("type".to_string(), serde_json::json!([kind, "null"]));
+                    }
+                }
+                for value in fields.values_mut() {
+                    *value = Self::json_schema(value.take());
+                }
+            } else if let Value::Array(items) = &mut schema {
+                for item in items {
+                    *item = Self::json_schema(item.take());
+                }
+            }
+            schema
+        }
+
+        /// Compile the schemas of every GVK validation is enabled for, in parallel
+        ///
+        /// Schemas are otherwise compiled on the first validation of their GVK.
+        pub fn precompile(&self) -> Result<()> {
+            let gvks = self
+                .enabled_gvks
+                .read()
+                .map_err(|e| Error::Internal(format!("Failed to acquire read lock: {}", e)))?
+                .clone();
+            std::thread::scope(|scope| {
+                let compiling: Vec<_> = gvks
+                    .iter()
+                    .map(|gvk| scope.spawn(move || self.get_or_compile_schema(gvk)))
+                    .collect();
+                compiling.into_iter().try_for_each(|compiled| {
+                    compiled.join().unwrap_or_else(|_| {
+                        Err(Error::Internal("Schema compilation panicked".to_string()))
+                    })
+                })
+            })
+        }
+
         pub fn enable_validation_for(&self, gvk: &str) -> Result<()> {
             let definition_name = self.gvk_to_definition_name(gvk)?;
 
@@ -112,6 +329,135 @@ mod runtime_openapi_validator {
             Ok(def_name)
         }
 
+        fn gvk_key(group: &str, version: &str, kind: &str) -> String {
+            if group.is_empty() {
+                format!("/{}/{}", version, kind)
+            } else {
+                format!("{}/{}/{}", group, version, kind)
+            }
+        }
+
+        fn is_enabled(&self, gvk_key: &str) -> Result<bool> {
+            Ok(self
+                .enabled_gvks
+                .read()
+                .map_err(|e| Error::Internal(format!("Failed to acquire read lock: {}", e)))?
+                .iter()
+                .any(|enabled| enabled == gvk_key))
+        }
+
+        /// Resolve a `$ref` to its definition, returning the schema itself otherwise
+        fn resolve<'a>(&'a self, schema: &'a Value) -> &'a Value {
+            let mut current = schema;
+            // Bound the number of hops so a self-referencing definition can't loop forever
+            for _ in 0..MAX_REF_HOPS {
+                let Some(name) = current
+                    .get("$ref")
+                    .and_then(|r| r.as_str())
+                    .and_then(|r| r.strip_prefix("#/definitions/"))
+                else {
+                    break;
+                };
+                match self.definitions.get(name) {
+                    Some(definition) => current = definition,
+                    None => break,
+                }
+            }
+            current
+        }
+
+        /// Recursively apply `default` values from a schema to a JSON value
+        fn default_value(&self, schema: &Value, value: &mut Value, depth: usize) {
+            if depth > MAX_SCHEMA_DEPTH {
+                return;
+            }
+            let schema = self.resolve(schema);
+
+            match value {
+                Value::Object(obj) => {
+                    if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+                        for (field, field_schema) in properties {
+                            let field_schema = self.resolve(field_schema);
+                            match obj.get_mut(field) {
+                                Some(field_value) => {
+                                    self.default_value(field_schema, field_value, depth + 1)
+                                }
+                                None => {
+                                    if let Some(default) = field_schema.get("default") {
+                                        let mut default = default.clone();
+                                        self.default_value(field_schema, &mut default, depth + 1);
+                                        obj.insert(field.clone(), default);
+                                    }
+                                }
+                            }
+                        }
+                    } else if let Some(additional) =
+                        schema.get("additionalProperties").filter(|a| a.is_object())
+                    {
+                        for field_value in obj.values_mut() {
+                            self.default_value(additional, field_value, depth + 1);
+                        }
+                    }
+                }
+                Value::Array(items) => {
+                    if let Some(item_schema) = schema.get("items") {
+                        for item in items {
+                            self.default_value(item_schema, item, depth + 1);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        /// Recursively remove fields a schema doesn't list
+        ///
+        /// Only objects whose schema has `properties` are pruned, and nothing is
+        /// pruned below `x-kubernetes-preserve-unknown-fields: true`.
+        fn prune_value(&self, schema: &Value, value: &mut Value, depth: usize) {
+            if depth > MAX_SCHEMA_DEPTH {
+                return;
+            }
+            let schema = self.resolve(schema);
+            if schema
+                .get("x-kubernetes-preserve-unknown-fields")
+                .and_then(|p| p.as_bool())
+                == Some(true)
+            {
+                return;
+            }
+
+            match value {
+                Value::Object(obj) => {
+                    if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+                        obj.retain(|field, _| {
+                            properties.contains_key(field)
+                                || (depth == 0 && ROOT_FIELDS.contains(&field.as_str()))
+                        });
+                        for (field, field_value) in obj.iter_mut() {
+                            if let Some(field_schema) = properties.get(field) {
+                                self.prune_value(field_schema, field_value, depth + 1);
+                            }
+                        }
+                    } else if let Some(additional) =
+                        schema.get("additionalProperties").filter(|a| a.is_object())
+                    {
+                        for field_value in obj.values_mut() {
+                            self.prune_value(additional, field_value, depth + 1);
+                        }
+                    }
+                }
+                Value::Array(items) => {
+                    if let Some(item_schema) = schema.get("items") {
+                        for item in items {
+                            self.prune_value(item_schema, item, depth + 1);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
         fn get_or_compile_schema(&self, gvk_key: &str) -> Result<()> {
             {
                 let cache = self
@@ -133,15 +479,28 @@ mod runtime_openapi_validator {
                 )));
             }
 
-            let schema = serde_json::json!({
-                "$schema": "http://json-schema.org/draft-04/schema#",
-                "definitions": self.definitions,
-                "$ref": format!("#/definitions/{}", definition_name)
-            });
+            let key = (self.source, definition_name);
+            let cached = COMPILED.read().get(&key).cloned();
+            let compiled = match cached {
+                Some(compiled) => compiled,
+                None => {
+                    let schema = serde_json::json!({
+                        "$schema": "http://json-schema.org/draft-04/schema#",
+                        "definitions": *self.definitions,
+                        "$ref": format!("#/definitions/{}", key.1)
+                    });
 
-            let compiled = JSONSchema::compile(&schema).map_err(|e| {
-                Error::Internal(format!("Failed to compile schema for '{}': {}", gvk_key, e))
-            })?;
+                    let compiled = JSONSchema::compile(&schema).map_err(|e| {
+                        Error::Internal(format!(
+                            "Failed to compile schema for '{}': {}",
+                            gvk_key, e
+                        ))
+                    })?;
+                    let compiled = Arc::new(compiled);
+                    COMPILED.write().insert(key, Arc::clone(&compiled));
+                    compiled
+                }
+            };
 
             self.schemas
                 .write()
@@ -152,21 +511,17 @@ mod runtime_openapi_validator {
         }
     }
 
+    impl Default for RuntimeOpenAPIValidator {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl SchemaValidator for RuntimeOpenAPIValidator {
         fn validate(&self, group: &str, version: &str, kind: &str, value: &Value) -> Result<()> {
-            let gvk_key = if group.is_empty() {
-                format!("/{}/{}", version, kind)
-            } else {
-                format!("{}/{}/{}", group, version, kind)
-            };
-
-            let enabled = self
-                .enabled_gvks
-                .read()
-                .map_err(|e| Error::Internal(format!("Failed to acquire read lock: {}", e)))?
-                .contains(&gvk_key);
+            let gvk_key = Self::gvk_key(group, version, kind);
 
-            if !enabled {
+            if !self.is_enabled(&gvk_key)? {
                 return Ok(());
             }
 
@@ -194,6 +549,42 @@ mod runtime_openapi_validator {
 
             Ok(())
         }
+
+        fn apply_defaults(
+            &self,
+            group: &str,
+            version: &str,
+            kind: &str,
+            value: &mut Value,
+        ) -> Result<()> {
+            let gvk_key = Self::gvk_key(group, version, kind);
+
+            if !self.is_enabled(&gvk_key)? {
+                return Ok(());
+            }
+
+            let definition_name = self.gvk_to_definition_name(&gvk_key)?;
+            if let Some(definition) = self.definitions.get(&definition_name) {
+                self.default_value(definition, value, 0);
+            }
+
+            Ok(())
+        }
+
+        fn prune(&self, group: &str, version: &str, kind: &str, value: &mut Value) -> Result<()> {
+            let gvk_key = Self::gvk_key(group, version, kind);
+
+            if !self.is_enabled(&gvk_key)? {
+                return Ok(());
+            }
+
+            let definition_name = self.gvk_to_definition_name(&gvk_key)?;
+            if let Some(definition) = self.definitions.get(&definition_name) {
+                self.prune_value(definition, value, 0);
+            }
+
+            Ok(())
+        }
     }
 }
 
@@ -205,4 +596,18 @@ impl SchemaValidator for std::sync::Arc<RuntimeOpenAPIValidator> {
     fn validate(&self, group: &str, version: &str, kind: &str, value: &Value) -> Result<()> {
         (**self).validate(group, version, kind, value)
     }
+
+    fn apply_defaults(
+        &self,
+        group: &str,
+        version: &str,
+        kind: &str,
+        value: &mut Value,
+    ) -> Result<()> {
+        (**self).apply_defaults(group, version, kind, value)
+    }
+
+    fn prune(&self, group: &str, version: &str, kind: &str, value: &mut Value) -> Result<()> {
+        (**self).prune(group, version, kind, value)
+    }
 }
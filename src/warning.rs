@@ -0,0 +1,162 @@
+//! Warnings returned with responses
+//!
+//! The API server points out questionable requests, such as uses of deprecated
+//! APIs or labels, in `Warning` response headers instead of failing them. Rules
+//! added with [`ClientBuilder::with_warning_rule`](crate::ClientBuilder::with_warning_rule)
+//! look at every request and may return a warning for it, which is sent as a
+//! `Warning: 299 - "<message>"` header and recorded in the request's
+//! [`AuditEntry::warnings`](crate::AuditEntry::warnings), so tests can enforce
+//! best practices without failing the code under test outright.
+//!
+//! [`label_selectors`] is a ready-made rule for selectors that repeat a key or
+//! select on deprecated node labels.
+
+use crate::interceptor::RequestContext;
+use http::HeaderValue;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A rule deciding whether a request deserves a warning
+///
+/// Called with the request and its JSON body, if it has one.
+pub type WarningRule = Arc<dyn Fn(&RequestContext, Option<&Value>) -> Option<String> + Send + Sync>;
+
+/// Node labels the API server warns about, with their replacements and the
+/// release that deprecated them
+const DEPRECATED_NODE_LABELS: [(&str, &str, &str); 5] = [
+    ("beta.kubernetes.io/arch", "kubernetes.io/arch", "v1.14"),
+    ("beta.kubernetes.io/os", "kubernetes.io/os", "v1.14"),
+    (
+        "failure-domain.beta.kubernetes.io/region",
+        "topology.kubernetes.io/region",
+        "v1.17",
+    ),
+    (
+        "failure-domain.beta.kubernetes.io/zone",
+        "topology.kubernetes.io/zone",
+        "v1.17",
+    ),
+    (
+        "beta.kubernetes.io/instance-type",
+        "node.kubernetes.io/instance-type",
+        "v1.17",
+    ),
+];
+
+/// Where Pod specs keep their node selector, in Pods and in workload templates
+const NODE_SELECTOR_PATHS: [&str; 3] = [
+    "/spec/nodeSelector",
+    "/spec/template/spec/nodeSelector",
+    "/spec/jobTemplate/spec/template/spec/nodeSelector",
+];
+
+/// Warn about label selectors that repeat a key or use deprecated node labels
+///
+/// Checks the `labelSelector` of list, watch and deletecollection requests and
+/// the node selectors of Pods and Pod templates in request bodies. A key that
+/// appears twice in a label selector is usually the mark of a selector
+/// assembled by concatenation, which may match nothing; deprecated node labels
+/// such as `beta.kubernetes.io/arch` get the API server's own warning.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use kube_fake_client::{warning, ClientBuilder};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (client, handle) = ClientBuilder::new()
+///     .with_warning_rule(warning::label_selectors)
+///     .build_with_handle()
+///     .await?;
+///
+/// // ... run the controller under test with `client` ...
+///
+/// let warnings: Vec<String> = handle
+///     .audit_entries()
+///     .into_iter()
+///     .flat_map(|entry| entry.warnings)
+///     .collect();
+/// assert!(warnings.is_empty(), "{warnings:?}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn label_selectors(request: &RequestContext, object: Option<&Value>) -> Option<String> {
+    if let Some(selector) = query_label_selector(&request.uri) {
+        let mut keys = HashSet::new();
+        for key in requirement_keys(&selector) {
+            if let Some(warning) = deprecated_node_label(&format!("labelSelector[{key}]"), key) {
+                return Some(warning);
+            }
+            if !keys.insert(key) {
+                return Some(format!(
+                    "labelSelector {selector:?}: key {key:?} appears more than once"
+                ));
+            }
+        }
+    }
+
+    let object = object?;
+    NODE_SELECTOR_PATHS.iter().find_map(|path| {
+        let selector = object.pointer(path)?.as_object()?;
+        let field = path.trim_start_matches('/').replace('/', ".");
+        selector
+            .keys()
+            .find_map(|key| deprecated_node_label(&format!("{field}[{key}]"), key))
+    })
+}
+
+fn deprecated_node_label(field: &str, key: &str) -> Option<String> {
+    let (_, replacement, since) = DEPRECATED_NODE_LABELS
+        .iter()
+        .find(|(label, _, _)| *label == key)?;
+    Some(format!(
+        "{field}: deprecated since {since}; use {replacement:?} instead"
+    ))
+}
+
+/// The `labelSelector` query parameter of a request URI
+fn query_label_selector(uri: &str) -> Option<String> {
+    let (_, query) = uri.split_once('?')?;
+    query.split('&').find_map(|pair| {
+        let value = pair.strip_prefix("labelSelector=")?.replace('+', " ");
+        urlencoding::decode(&value).ok().map(|v| v.into_owned())
+    })
+}
+
+/// The keys of a label selector's requirements, in order
+fn requirement_keys(selector: &str) -> Vec<&str> {
+    let mut keys = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in selector.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                keys.extend(requirement_key(&selector[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    keys.extend(requirement_key(&selector[start..]));
+    keys
+}
+
+/// The key of one requirement, such as `app` in `app in (web, db)`
+fn requirement_key(requirement: &str) -> Option<&str> {
+    let requirement = requirement.trim().trim_start_matches('!').trim_start();
+    let end = requirement
+        .find(|c: char| c == '=' || c == '!' || c.is_whitespace())
+        .unwrap_or(requirement.len());
+    let key = &requirement[..end];
+    (!key.is_empty()).then_some(key)
+}
+
+/// A `Warning` header carrying a message, in the API server's format
+pub(crate) fn header_value(message: &str) -> Option<HeaderValue> {
+    let escaped = message.replace('\\', "\\\\").replace('"', "\\\"");
+    HeaderValue::from_str(&format!("299 - \"{escaped}\"")).ok()
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use crate::{warning, ClientBuilder};
+    use k8s_openapi::api::apps::v1::Deployment;
+    use k8s_openapi::api::core::v1::{ConfigMap, Pod};
+    use kube::api::{Api, ListParams, PostParams};
+    use kube::client::Body;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_warning_rule_adds_header_and_audit_warning() {
+        let (client, handle) = ClientBuilder::new()
+            .with_warning_rule(|request, object| {
+                let unlabeled = object
+                    .and_then(|o| o.pointer("/metadata/labels/app"))
+                    .is_none();
+                (request.method == "POST" && unlabeled)
+                    .then(|| "metadata.labels: \"app\" is missing".to_string())
+            })
+            .build_with_handle()
+            .await
+            .unwrap();
+
+        let body = json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": {"name": "settings"},
+        });
+        let request = http::Request::post("/api/v1/namespaces/default/configmaps")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let response = client.send(request).await.unwrap();
+        assert_eq!(response.status(), 201);
+        assert_eq!(
+            response.headers().get(http::header::WARNING).unwrap(),
+            r#"299 - "metadata.labels: \"app\" is missing""#
+        );
+
+        // The warning doesn't stop the request, and reads get none
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+        configmaps.get("settings").await.unwrap();
+
+        let warnings: Vec<Vec<String>> = handle
+            .audit_entries()
+            .into_iter()
+            .map(|entry| entry.warnings)
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                vec!["metadata.labels: \"app\" is missing".to_string()],
+                vec![]
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_label_selectors_rule() {
+        let (client, handle) = ClientBuilder::new()
+            .with_warning_rule(warning::label_selectors)
+            .build_with_handle()
+            .await
+            .unwrap();
+        let pods: Api<Pod> = Api::default_namespaced(client.clone());
+
+        pods.list(&ListParams::default().labels("app=web,tier!=db"))
+            .await
+            .unwrap();
+        pods.list(&ListParams::default().labels("app=web,app=db"))
+            .await
+            .unwrap();
+        pods.list(&ListParams::default().labels("beta.kubernetes.io/os=linux"))
+            .await
+            .unwrap();
+        let deployment: Deployment = serde_json::from_value(json!({
+            "metadata": {"name": "web"},
+            "spec": {
+                "selector": {"matchLabels": {"app": "web"}},
+                "template": {
+                    "metadata": {"labels": {"app": "web"}},
+                    "spec": {
+                        "nodeSelector": {"beta.kubernetes.io/arch": "amd64"},
+                        "containers": [{"name": "web", "image": "nginx"}],
+                    },
+                },
+            },
+        }))
+        .unwrap();
+        Api::<Deployment>::default_namespaced(client)
+            .create(&PostParams::default(), &deployment)
+            .await
+            .unwrap();
+
+        let warnings: Vec<Vec<String>> = handle
+            .audit_entries()
+            .into_iter()
+            .map(|entry| entry.warnings)
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                vec![],
+                vec![r#"labelSelector "app=web,app=db": key "app" appears more than once"#.to_string()],
+                vec![r#"labelSelector[beta.kubernetes.io/os]: deprecated since v1.14; use "kubernetes.io/os" instead"#.to_string()],
+                vec![r#"spec.template.spec.nodeSelector[beta.kubernetes.io/arch]: deprecated since v1.14; use "kubernetes.io/arch" instead"#.to_string()],
+            ]
+        );
+    }
+}
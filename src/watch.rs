@@ -0,0 +1,261 @@
+//! Watch events
+//!
+//! Every write to the tracker publishes an event carrying the object as written.
+//! Recent events are kept in a bounded history, like the API server's watch cache,
+//! so a watch started from the resourceVersion of a list sees everything written
+//! since the list. Watches from a version older than the history are rejected with
+//! 410 Gone, which makes clients such as `kube_runtime::watcher` relist.
+//!
+//! Compacting the history drops every event in it, as etcd compaction does:
+//! open watches keep running, while watches resuming from before the compaction
+//! get 410 Gone.
+//!
+//! Delivery can be paused: events published meanwhile are held back from watches,
+//! and from the history, until delivery resumes.
+//!
+//! Events of an object are delivered in resourceVersion order. Every delivery is
+//! checked against the last one of the same object, and any event that is not
+//! newer is recorded as an ordering violation for tests to fail on.
+
+use crate::stats::approximate_size;
+use crate::tracker::GVR;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+/// Number of events kept for watches resuming from an earlier resourceVersion
+const HISTORY_SIZE: usize = 10_000;
+
+/// Number of events buffered for each watch before it falls behind
+const CHANNEL_SIZE: usize = 4096;
+
+/// Type of a watch event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventType {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl EventType {
+    /// The type as it appears in watch responses, such as `ADDED`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventType::Added => "ADDED",
+            EventType::Modified => "MODIFIED",
+            EventType::Deleted => "DELETED",
+        }
+    }
+}
+
+/// A write to a stored object
+#[derive(Debug, Clone)]
+pub(crate) struct WatchEvent {
+    pub(crate) gvr: GVR,
+    pub(crate) namespace: String,
+    pub(crate) event_type: EventType,
+    pub(crate) object: Arc<Value>,
+    pub(crate) resource_version: u64,
+}
+
+impl WatchEvent {
+    /// The event as a line of a watch response
+    pub(crate) fn to_json(&self) -> Value {
+        json!({ "type": self.event_type.as_str(), "object": self.object.as_ref() })
+    }
+}
+
+/// A watch asked to resume from a resourceVersion no longer in the history
+#[derive(Debug)]
+pub(crate) struct Expired;
+
+/// Recent events and the channel new ones are published on
+pub(crate) struct WatchCache {
+    inner: Mutex<History>,
+    sender: broadcast::Sender<WatchEvent>,
+}
+
+struct History {
+    /// Delivered events, when they were delivered and their approximate size,
+    /// oldest first
+    events: VecDeque<(Instant, WatchEvent, usize)>,
+    /// Approximate size of the events in the history
+    bytes: usize,
+    /// Highest resourceVersion evicted from the history
+    evicted: u64,
+    paused: bool,
+    /// Events published while paused, oldest first
+    held: Vec<WatchEvent>,
+    /// resourceVersion and type of the last delivered event of each object
+    last_delivered: HashMap<(GVR, String, String), (u64, EventType)>,
+    /// Delivered events that were not newer than the object's previous event
+    violations: Vec<String>,
+}
+
+impl Default for WatchCache {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(History {
+                events: VecDeque::new(),
+                bytes: 0,
+                evicted: 0,
+                paused: false,
+                held: Vec::new(),
+                last_delivered: HashMap::new(),
+                violations: Vec::new(),
+            }),
+            sender: broadcast::channel(CHANNEL_SIZE).0,
+        }
+    }
+}
+
+impl WatchCache {
+    pub(crate) fn publish(&self, event: WatchEvent) {
+        let mut history = self.inner.lock();
+        if history.paused {
+            history.held.push(event);
+        } else {
+            self.deliver(&mut history, event);
+        }
+    }
+
+    /// Hold back events published from now on
+    pub(crate) fn pause(&self) {
+        self.inner.lock().paused = true;
+    }
+
+    /// Deliver the held events, in order, and every later one as it is published
+    pub(crate) fn resume(&self) {
+        let mut history = self.inner.lock();
+        history.paused = false;
+        for event in std::mem::take(&mut history.held) {
+            self.deliver(&mut history, event);
+        }
+    }
+
+    /// Number of delivered events some open watch has not received yet
+    pub(crate) fn undelivered(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Delivered events that were out of order, or duplicates, for their object
+    pub(crate) fn ordering_violations(&self) -> Vec<String> {
+        self.inner.lock().violations.clone()
+    }
+
+    /// Number of events in the history and their approximate size
+    pub(crate) fn history_size(&self) -> (usize, usize) {
+        let history = self.inner.lock();
+        (history.events.len(), history.bytes)
+    }
+
+    /// Drop the history, and what is remembered of deleted objects
+    ///
+    /// Watches resuming from a resourceVersion up to the newest event dropped get
+    /// [`Expired`]; ordering is still checked for the objects that exist.
+    pub(crate) fn compact(&self) {
+        let mut history = self.inner.lock();
+        if let Some((_, newest, _)) = history.events.back() {
+            history.evicted = history.evicted.max(newest.resource_version);
+        }
+        history.events = VecDeque::new();
+        history.bytes = 0;
+        history
+            .last_delivered
+            .retain(|_, (_, event_type)| *event_type != EventType::Deleted);
+        history.last_delivered.shrink_to_fit();
+    }
+
+    fn deliver(&self, history: &mut History, event: WatchEvent) {
+        let name = event.object["metadata"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let key = (event.gvr.clone(), event.namespace.clone(), name.clone());
+        if let Some((previous, _)) = history
+            .last_delivered
+            .insert(key, (event.resource_version, event.event_type))
+            .filter(|(previous, _)| *previous >= event.resource_version)
+        {
+            let problem = if previous == event.resource_version {
+                "duplicate"
+            } else {
+                "out-of-order"
+            };
+            let object = if event.namespace.is_empty() {
+                name
+            } else {
+                format!("{}/{name}", event.namespace)
+            };
+            history.violations.push(format!(
+                "{problem} {} event for {} {object}: resourceVersion {} after {previous}",
+                event.event_type.as_str(),
+                event.gvr.resource,
+                event.resource_version,
+            ));
+        }
+        if history.events.len() == HISTORY_SIZE {
+            if let Some((_, evicted, bytes)) = history.events.pop_front() {
+                history.evicted = history.evicted.max(evicted.resource_version);
+                history.bytes -= bytes;
+            }
+        }
+        let bytes = approximate_size(&event.object);
+        history.bytes += bytes;
+        history
+            .events
+            .push_back((Instant::now(), event.clone(), bytes));
+        // Sending only fails when nobody is watching
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to events of a resource after a resourceVersion
+    ///
+    /// Returns the events already in the history after `since`, followed by a
+    /// receiver for later ones, with no gap in between. Without `since` only new
+    /// events are delivered.
+    pub(crate) fn subscribe(
+        &self,
+        gvr: &GVR,
+        since: Option<u64>,
+    ) -> Result<(Vec<WatchEvent>, broadcast::Receiver<WatchEvent>), Expired> {
+        let history = self.inner.lock();
+        let replay = match since {
+            Some(since) if since < history.evicted => return Err(Expired),
+            Some(since) => history
+                .events
+                .iter()
+                .map(|(_, event, _)| event)
+                .filter(|e| e.resource_version > since && e.gvr == *gvr)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok((replay, self.sender.subscribe()))
+    }
+
+    /// Events of a resource after a resourceVersion that were delivered no later
+    /// than `until`, oldest first
+    pub(crate) fn delivered(
+        &self,
+        gvr: &GVR,
+        since: u64,
+        until: Instant,
+    ) -> Result<Vec<WatchEvent>, Expired> {
+        let history = self.inner.lock();
+        if since < history.evicted {
+            return Err(Expired);
+        }
+        Ok(history
+            .events
+            .iter()
+            .take_while(|(delivered, _, _)| *delivered <= until)
+            .map(|(_, event, _)| event)
+            .filter(|e| e.resource_version > since && e.gvr == *gvr)
+            .cloned()
+            .collect())
+    }
+}
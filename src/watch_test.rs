@@ -0,0 +1,313 @@
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::configmap_with;
+    use crate::ClientBuilder;
+    use futures::StreamExt;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::{
+        Api, DeleteParams, ListParams, Patch, PatchParams, PostParams, ResourceExt, WatchEvent,
+        WatchParams,
+    };
+    use kube::runtime::reflector::{self, ObjectRef};
+    use kube::runtime::{watcher, WatchStreamExt};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_watcher_sees_changes() {
+        let client = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        let mut events = watcher(configmaps.clone(), watcher::Config::default())
+            .applied_objects()
+            .boxed();
+
+        let first = events.next().await.unwrap().unwrap();
+        assert_eq!(first.name_any(), "existing");
+
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("created", "mode", "active"),
+            )
+            .await
+            .unwrap();
+        let created = events.next().await.unwrap().unwrap();
+        assert_eq!(created.name_any(), "created");
+
+        let patch = json!({ "data": { "mode": "standby" } });
+        configmaps
+            .patch("existing", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        let patched = events.next().await.unwrap().unwrap();
+        assert_eq!(patched.name_any(), "existing");
+        assert_eq!(patched.data.unwrap()["mode"], "standby");
+    }
+
+    #[tokio::test]
+    async fn test_watch_resumes_from_resource_version() {
+        let client = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let list = configmaps.list(&ListParams::default()).await.unwrap();
+        let version = list.metadata.resource_version.unwrap();
+
+        // Written between the list and the watch
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("missed", "mode", "active"),
+            )
+            .await
+            .unwrap();
+        configmaps
+            .delete("missed", &DeleteParams::default())
+            .await
+            .unwrap();
+
+        let params = WatchParams::default().timeout(1);
+        let events: Vec<_> = configmaps
+            .watch(&params, &version)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        let kinds: Vec<_> = events
+            .into_iter()
+            .map(|event| match event.unwrap() {
+                WatchEvent::Added(cm) => format!("added {}", cm.name_any()),
+                WatchEvent::Deleted(cm) => format!("deleted {}", cm.name_any()),
+                other => format!("{other:?}"),
+            })
+            .collect();
+        assert_eq!(kinds, vec!["added missed", "deleted missed"]);
+    }
+
+    /// Lists report the resourceVersion of the latest write to any resource, so
+    /// it only moves forward and informers can watch from it
+    #[tokio::test]
+    async fn test_list_resource_version_follows_writes() {
+        use k8s_openapi::api::core::v1::Pod;
+
+        let client = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client.clone(), "default");
+        let pods: Api<Pod> = Api::namespaced(client, "default");
+        let list_version = || async {
+            let list = configmaps.list(&ListParams::default()).await.unwrap();
+            list.metadata
+                .resource_version
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+        };
+
+        let initial = list_version().await;
+        assert!(initial > 0);
+        assert_eq!(list_version().await, initial);
+
+        // Objects seeded with their own version are never newer than the list
+        let mut seeded = configmap_with("seeded", "mode", "active");
+        seeded.metadata.resource_version = Some("500".to_string());
+        let client = ClientBuilder::new()
+            .with_object(seeded)
+            .build()
+            .await
+            .unwrap();
+        let seeded_list = Api::<ConfigMap>::namespaced(client, "default")
+            .list(&ListParams::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            seeded_list.metadata.resource_version.as_deref(),
+            Some("500")
+        );
+
+        let created = configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("settings", "mode", "active"),
+            )
+            .await
+            .unwrap();
+        let after_create = list_version().await;
+        assert!(after_create > initial);
+        assert_eq!(
+            created.resource_version().unwrap(),
+            after_create.to_string()
+        );
+
+        // Writes to other resources move it too, as with etcd's global revision
+        let mut pod = Pod::default();
+        pod.metadata.name = Some("web".to_string());
+        pods.create(&PostParams::default(), &pod).await.unwrap();
+        let after_pod = list_version().await;
+        assert!(after_pod > after_create);
+
+        configmaps
+            .delete("settings", &DeleteParams::default())
+            .await
+            .unwrap();
+        assert!(list_version().await > after_pod);
+    }
+
+    #[tokio::test]
+    async fn test_label_selector_filters_events() {
+        let client = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let version = configmaps
+            .list(&ListParams::default())
+            .await
+            .unwrap()
+            .metadata
+            .resource_version
+            .unwrap();
+
+        let mut labelled = configmap_with("labelled", "mode", "active");
+        labelled.metadata.labels = Some(BTreeMap::from([("app".to_string(), "web".to_string())]));
+        configmaps
+            .create(&PostParams::default(), &labelled)
+            .await
+            .unwrap();
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("other", "mode", "active"),
+            )
+            .await
+            .unwrap();
+
+        let params = WatchParams::default().labels("app=web").timeout(1);
+        let names: Vec<_> = configmaps
+            .watch(&params, &version)
+            .await
+            .unwrap()
+            .map(|event| match event.unwrap() {
+                WatchEvent::Added(cm) => cm.name_any(),
+                other => format!("{other:?}"),
+            })
+            .collect()
+            .await;
+        assert_eq!(names, vec!["labelled"]);
+    }
+
+    #[tokio::test]
+    async fn test_paused_watches_hold_events_until_resumed() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let (store, writer) = reflector::store();
+        let reflector = reflector::reflector(
+            writer,
+            watcher(configmaps.clone(), watcher::Config::default()),
+        );
+        tokio::spawn(reflector.applied_objects().for_each(|_| async {}));
+        store.wait_until_ready().await.unwrap();
+        let cached = |name: &str| store.get(&ObjectRef::new(name).within("default"));
+
+        handle.pause_watches();
+        let patch = json!({ "data": { "mode": "standby" } });
+        configmaps
+            .patch("existing", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        configmaps
+            .create(
+                &PostParams::default(),
+                &configmap_with("created", "mode", "active"),
+            )
+            .await
+            .unwrap();
+        handle.flush_watches().await;
+
+        // The API server has the writes, the cache doesn't
+        let current = configmaps.get("existing").await.unwrap();
+        assert_eq!(current.data.unwrap()["mode"], "standby");
+        assert_eq!(
+            cached("existing").unwrap().data.as_ref().unwrap()["mode"],
+            "active"
+        );
+        assert!(cached("created").is_none());
+
+        handle.resume_watches();
+        handle.flush_watches().await;
+        assert_eq!(
+            cached("existing").unwrap().data.as_ref().unwrap()["mode"],
+            "standby"
+        );
+        assert!(cached("created").is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_writes_are_delivered_in_order() {
+        let (client, handle) = ClientBuilder::new()
+            .with_object(configmap_with("existing", "mode", "active"))
+            .build_with_handle()
+            .await
+            .unwrap();
+        let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+        let writers = (0..8).map(|i| {
+            let configmaps = configmaps.clone();
+            tokio::spawn(async move {
+                for j in 0..10 {
+                    let patch = json!({ "data": { format!("writer-{i}"): j.to_string() } });
+                    configmaps
+                        .patch("existing", &PatchParams::default(), &Patch::Merge(&patch))
+                        .await
+                        .unwrap();
+                    configmaps
+                        .create(
+                            &PostParams::default(),
+                            &configmap_with(&format!("cm-{i}-{j}"), "mode", "active"),
+                        )
+                        .await
+                        .unwrap();
+                }
+            })
+        });
+        for writer in writers.collect::<Vec<_>>() {
+            writer.await.unwrap();
+        }
+
+        handle.verify_event_ordering();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "duplicate MODIFIED event for configmaps default/existing")]
+    async fn test_verify_event_ordering_reports_duplicates() {
+        let mut cm = configmap_with("existing", "mode", "active");
+        cm.metadata.namespace = Some("default".to_string());
+        cm.metadata.resource_version = Some("42".to_string());
+        let (_client, handle) = ClientBuilder::new()
+            .with_object(cm.clone())
+            .build_with_handle()
+            .await
+            .unwrap();
+        // Replacing an object with the resourceVersion it had repeats its event
+        let gvr = crate::tracker::GVR::new("", "v1", "configmaps");
+        handle
+            .client
+            .tracker
+            .add_or_replace(&gvr, serde_json::to_value(&cm).unwrap(), "default")
+            .unwrap();
+
+        handle.verify_event_ordering();
+    }
+}
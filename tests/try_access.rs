@@ -0,0 +1,71 @@
+//! Non-blocking reads through the public API
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Api, PostParams};
+use kube_fake_client::{ClientBuilder, Error, FakeClient};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+fn configmap(name: &str) -> ConfigMap {
+    let mut cm = ConfigMap::default();
+    cm.metadata.name = Some(name.to_string());
+    cm
+}
+
+#[tokio::test]
+async fn test_try_get_and_try_list_through_handle() {
+    let (client, handle) = ClientBuilder::new().build_with_handle().await.unwrap();
+    let configmaps: Api<ConfigMap> = Api::namespaced(client, "default");
+    configmaps
+        .create(&PostParams::default(), &configmap("settings"))
+        .await
+        .unwrap();
+
+    let fake = handle.fake_client();
+    let settings: ConfigMap = fake.try_get("default", "settings").unwrap().unwrap();
+    assert_eq!(settings.metadata.name.as_deref(), Some("settings"));
+    assert!(settings.metadata.managed_fields.is_none());
+
+    match fake.try_get::<ConfigMap>("default", "missing").unwrap() {
+        Err(Error::NotFound { name, .. }) => assert_eq!(name, "missing"),
+        other => panic!("Expected NotFound, got: {:?}", other),
+    }
+
+    let listed: Vec<ConfigMap> = fake.try_list(Some("default")).unwrap().unwrap();
+    assert_eq!(listed.len(), 1);
+    let listed: Vec<ConfigMap> = fake.try_list(Some("other")).unwrap().unwrap();
+    assert!(listed.is_empty());
+}
+
+#[test]
+fn test_try_get_never_blocks_on_concurrent_writes() {
+    let fake = Arc::new(FakeClient::new());
+    fake.create("default", &configmap("settings"), &PostParams::default())
+        .unwrap();
+
+    let done = Arc::new(AtomicBool::new(false));
+    let writer = {
+        let fake = Arc::clone(&fake);
+        let done = Arc::clone(&done);
+        std::thread::spawn(move || {
+            for i in 0..200 {
+                let mut cm: ConfigMap = fake.get("default", "settings").unwrap();
+                cm.data = Some(BTreeMap::from([("n".to_string(), i.to_string())]));
+                fake.update("default", &cm, &PostParams::default()).unwrap();
+            }
+            done.store(true, Ordering::SeqCst);
+        })
+    };
+
+    // Reads either see a whole object or report the lock as held
+    while !done.load(Ordering::SeqCst) {
+        if let Some(read) = fake.try_get::<ConfigMap>("default", "settings") {
+            assert_eq!(read.unwrap().metadata.name.as_deref(), Some("settings"));
+        }
+        if let Some(listed) = fake.try_list::<ConfigMap>(None) {
+            assert_eq!(listed.unwrap().len(), 1);
+        }
+    }
+    writer.join().unwrap();
+}